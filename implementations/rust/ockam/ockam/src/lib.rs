@@ -95,8 +95,18 @@ pub mod udp {
     pub use ockam_transport_udp::{
         RendezvousClient, RendezvousService, UdpBind, UdpBindArguments, UdpBindOptions,
         UdpPuncture, UdpPunctureNegotiation, UdpPunctureNegotiationListener,
-        UdpPunctureNegotiationListenerOptions, UdpTransport, UdpTransportExtension,
-        MAX_MESSAGE_SIZE, UDP,
+        UdpPunctureNegotiationListenerOptions, UdpPunctureOptions, UdpReliableDeliveryOptions,
+        UdpTransport, UdpTransportExtension, MAX_MESSAGE_SIZE, UDP,
+    };
+}
+#[cfg(feature = "ockam_transport_quic")]
+/// QUIC transport
+pub mod quic {
+    pub use ockam_transport_quic::{
+        QuicConnection, QuicConnectionMode, QuicConnectionOptions, QuicInlet, QuicInletOptions,
+        QuicListener, QuicListenerInfo, QuicListenerOptions, QuicOutlet, QuicOutletOptions,
+        QuicPortalStreamInfo, QuicSenderInfo, QuicTransport, QuicTransportExtension,
+        MAX_MESSAGE_SIZE, QUIC,
     };
 }
 pub use relay_service::{RelayService, RelayServiceOptions};
@@ -1,13 +1,15 @@
 use core::str::from_utf8;
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::fmt;
 use ockam_core::compat::fmt::Debug;
 use ockam_core::compat::fmt::Formatter;
 use ockam_core::compat::str;
 use ockam_core::compat::sync::Arc;
-use ockam_core::compat::vec::vec;
-use ockam_core::{RelayMessage, SecureChannelMetadata};
+use ockam_core::compat::vec::{vec, Vec};
+use ockam_core::{Address, RelayMessage, SecureChannelMetadata};
 use ockam_core::{Result, SecureChannelLocalInfo};
 
+use crate::abac::RequestContext;
 use crate::expr::str;
 use crate::{eval, Env, Expr};
 use ockam_core::compat::format;
@@ -63,16 +65,26 @@ impl Abac {
         ctx: &Context,
         relay_msg: &RelayMessage,
     ) -> Result<Option<Identifier>> {
-        let metadata = if let Some((_address, metadata)) =
+        Ok(Self::get_outgoing_secure_channel(ctx, relay_msg)?
+            .map(|(_address, identifier)| identifier))
+    }
+
+    /// Return the address of the secure channel an outgoing message is sent to,
+    /// and the identifier of the other side of that secure channel
+    pub fn get_outgoing_secure_channel(
+        ctx: &Context,
+        relay_msg: &RelayMessage,
+    ) -> Result<Option<(Address, Identifier)>> {
+        let (address, metadata) = if let Some((address, metadata)) =
             ctx.find_terminal_address(relay_msg.onward_route().iter())?
         {
-            metadata
+            (address, metadata)
         } else {
             return Ok(None);
         };
 
         if let Ok(metadata) = SecureChannelMetadata::from_terminal_address_metadata(&metadata) {
-            Ok(Some(metadata.their_identifier().into()))
+            Ok(Some((address.clone(), metadata.their_identifier().into())))
         } else {
             Ok(None)
         }
@@ -95,9 +107,48 @@ impl Abac {
         identifier: &Identifier,
         expression: &Expr,
     ) -> Result<bool> {
-        Self::is_identity_authorized_static(
+        self.is_identity_authorized_in_context(
+            identifier,
+            expression,
+            RequestContext::new().with_current_time(),
+        )
+        .await
+    }
+
+    /// Returns true if the identity is authorized, the `context.*` and `resource.*`
+    /// attributes of the request being available to the expression
+    pub async fn is_identity_authorized_in_context(
+        &self,
+        identifier: &Identifier,
+        expression: &Expr,
+        context: RequestContext,
+    ) -> Result<bool> {
+        let environment = self
+            .environment_in_context(identifier, expression, context)
+            .await?;
+        Ok(Self::is_authorized_in_environment(
+            identifier,
+            expression,
+            &environment,
+        ))
+    }
+
+    /// Return the environment used to evaluate an expression for a given identity:
+    /// the environment of this access control, the attributes of the request and the
+    /// attributes of the identity
+    pub async fn environment_in_context(
+        &self,
+        identifier: &Identifier,
+        expression: &Expr,
+        context: RequestContext,
+    ) -> Result<Env> {
+        let mut environment = self.environment.clone();
+        // the attributes of the request can't be set in the environment of the access control
+        environment.merge_right(context.into());
+
+        Self::environment_static(
             self.identities_attributes.clone(),
-            &self.environment,
+            &environment,
             self.authority.as_ref(),
             identifier,
             expression,
@@ -113,6 +164,29 @@ impl Abac {
         identifier: &Identifier,
         expression: &Expr,
     ) -> Result<bool> {
+        let environment = Self::environment_static(
+            identities_attributes,
+            environment,
+            authority,
+            identifier,
+            expression,
+        )
+        .await?;
+        Ok(Self::is_authorized_in_environment(
+            identifier,
+            expression,
+            &environment,
+        ))
+    }
+
+    /// Add the attributes of an identity to an environment
+    pub async fn environment_static(
+        identities_attributes: Arc<IdentitiesAttributes>,
+        environment: &Env,
+        authority: Option<&Identifier>,
+        identifier: &Identifier,
+        expression: &Expr,
+    ) -> Result<Env> {
         let mut environment = environment.clone();
 
         // add the identifier itself as a subject parameter
@@ -124,73 +198,93 @@ impl Abac {
 
         // Get identity attributes and populate the environment:
         if let Some(authority) = authority {
-            match identities_attributes
+            let attributes = identities_attributes
                 .get_attributes(identifier, authority)
-                .await?
-            {
-                Some(attrs) => {
-                    environment.put(
-                        subject_has_credential_attribute().to_string(),
-                        Expr::CONST_TRUE,
-                    );
-
-                    for (key, value) in attrs.attrs() {
-                        let key = match from_utf8(key) {
-                            Ok(key) => key,
-                            Err(_) => {
-                                warn! {
-                                    policy = %expression,
-                                    id     = %identifier,
-                                    "attribute key is not utf-8"
-                                }
-                                continue;
-                            }
-                        };
-                        if key.find(|c: char| c.is_whitespace()).is_some() {
-                            warn! {
-                                policy = %expression,
-                                id     = %identifier,
-                                key    = %key,
-                                "attribute key with whitespace ignored"
-                            }
-                        }
-                        match str::from_utf8(value) {
-                            Ok(s) => {
-                                if environment.contains(key) {
-                                    warn! {
-                                        policy = %expression,
-                                        id     = %identifier,
-                                        key    = %key,
-                                        "attribute already present"
-                                    }
-                                } else {
-                                    environment
-                                        .put(format!("{}.{key}", SUBJECT_KEY), str(s.to_string()));
-                                }
-                            }
-                            Err(e) => {
-                                warn! {
-                                    policy = %expression,
-                                    id     = %identifier,
-                                    key    = %key,
-                                    err    = %e,
-                                    "failed to interpret attribute as string"
-                                }
-                            }
+                .await?;
+            Self::put_credential_attributes(
+                &mut environment,
+                identifier,
+                expression,
+                attributes.as_ref().map(|a| a.attrs()),
+            );
+        }
+
+        Ok(environment)
+    }
+
+    /// Add the attributes of the credential of an identity to an environment, as `subject.*`
+    /// attributes. `subject.has_credential` is false if the identity has no credential.
+    pub fn put_credential_attributes(
+        environment: &mut Env,
+        identifier: &Identifier,
+        expression: &Expr,
+        attributes: Option<&BTreeMap<Vec<u8>, Vec<u8>>>,
+    ) {
+        let Some(attrs) = attributes else {
+            environment.put(
+                subject_has_credential_attribute().to_string(),
+                Expr::CONST_FALSE,
+            );
+            return;
+        };
+        environment.put(
+            subject_has_credential_attribute().to_string(),
+            Expr::CONST_TRUE,
+        );
+
+        for (key, value) in attrs {
+            let key = match from_utf8(key) {
+                Ok(key) => key,
+                Err(_) => {
+                    warn! {
+                        policy = %expression,
+                        id     = %identifier,
+                        "attribute key is not utf-8"
+                    }
+                    continue;
+                }
+            };
+            if key.find(|c: char| c.is_whitespace()).is_some() {
+                warn! {
+                    policy = %expression,
+                    id     = %identifier,
+                    key    = %key,
+                    "attribute key with whitespace ignored"
+                }
+            }
+            match str::from_utf8(value) {
+                Ok(s) => {
+                    if environment.contains(key) {
+                        warn! {
+                            policy = %expression,
+                            id     = %identifier,
+                            key    = %key,
+                            "attribute already present"
                         }
+                    } else {
+                        environment.put(format!("{}.{key}", SUBJECT_KEY), str(s.to_string()));
                     }
                 }
-                None => {
-                    environment.put(
-                        subject_has_credential_attribute().to_string(),
-                        Expr::CONST_FALSE,
-                    );
+                Err(e) => {
+                    warn! {
+                        policy = %expression,
+                        id     = %identifier,
+                        key    = %key,
+                        err    = %e,
+                        "failed to interpret attribute as string"
+                    }
                 }
             }
         }
+    }
 
-        // Finally, evaluate the expression and return the result:
-        match eval(expression, &environment) {
+    /// Returns true if the expression evaluates to `true` in the environment of an identity
+    pub fn is_authorized_in_environment(
+        identifier: &Identifier,
+        expression: &Expr,
+        environment: &Env,
+    ) -> bool {
+        match eval(expression, environment) {
             Ok(Expr::Bool(b)) => {
                 debug! {
                     policy        = %expression,
@@ -198,7 +292,7 @@ impl Abac {
                     is_authorized = %b,
                     "policy evaluated"
                 }
-                Ok(b)
+                b
             }
             Ok(x) => {
                 warn! {
@@ -207,7 +301,7 @@ impl Abac {
                     expr   = %x,
                     "evaluation did not yield a boolean result"
                 }
-                Ok(false)
+                false
             }
             Err(e) => {
                 warn! {
@@ -217,7 +311,7 @@ impl Abac {
                     env    = %environment,
                     "policy evaluation failed"
                 }
-                Ok(false)
+                false
             }
         }
     }
@@ -0,0 +1,243 @@
+use core::net::SocketAddr;
+use core::str::FromStr;
+use ockam_core::api::RequestHeader;
+use ockam_core::compat::format;
+use ockam_core::compat::string::ToString;
+use ockam_core::{Address, RelayMessage, SecureChannelLocalInfo, SourceAddressLocalInfo};
+use ockam_identity::Identifier;
+use tracing::debug;
+
+use crate::expr::{int, str};
+use crate::{Env, Expr, Resource, ResourceName, ResourceType};
+
+/// Prefix of the attributes describing the request being authorized
+pub const CONTEXT_KEY: &str = "context";
+
+/// Prefix of the attributes describing the resource being accessed
+pub const RESOURCE_KEY: &str = "resource";
+
+const DAYS_OF_WEEK: [&str; 7] = [
+    "monday",
+    "tuesday",
+    "wednesday",
+    "thursday",
+    "friday",
+    "saturday",
+    "sunday",
+];
+
+/// Attributes of a request, which are added to the environment of a policy evaluation,
+/// next to the `subject.*` attributes of the identity making the request:
+///
+///  - `context.time`: Unix timestamp in seconds.
+///  - `context.hour`, `context.minute`: UTC time of the day.
+///  - `context.day_of_week`: UTC day of the week, from `"monday"` to `"sunday"`.
+///  - `context.secure_channel.identifier`: identifier of the other side of the secure channel.
+///  - `context.secure_channel.address`: local address of the secure channel.
+///  - `context.source.transport`: transport type of the address the message comes from.
+///  - `context.source.address`: that transport address, for example `"192.168.1.10:4000"`.
+///  - `context.source.ip`: the IP of that address, if it is a socket address.
+///  - `context.api.method`, `context.api.path`: method, in lowercase, and path of a request
+///    to the API of a node, for example `"get"` and `"/node/inlet"`.
+///  - `resource.name`, `resource.type`: the resource being accessed.
+///
+/// The source of a message is the peer address recorded by the transport which received it,
+/// for example the peer of a TCP connection or the sender of a UDP datagram, and is kept when the
+/// message is decrypted by a secure channel. For a TCP inlet, it is the address of the TCP client.
+/// The return route of a message is never used, since its content is chosen by the sender.
+#[derive(Clone, Debug, Default)]
+pub struct RequestContext {
+    env: Env,
+}
+
+impl RequestContext {
+    /// Create an empty context
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add the time attributes, for the given Unix timestamp in seconds
+    pub fn with_time(mut self, timestamp: u64) -> Self {
+        let seconds_of_day = timestamp % 86_400;
+        // 1970-01-01 was a thursday
+        let day_of_week = ((timestamp / 86_400 + 3) % 7) as usize;
+
+        self.put("time", int(timestamp as i64));
+        self.put("hour", int((seconds_of_day / 3_600) as i64));
+        self.put("minute", int((seconds_of_day % 3_600 / 60) as i64));
+        self.put("day_of_week", str(DAYS_OF_WEEK[day_of_week]));
+        self
+    }
+
+    /// Add the time attributes for the current time
+    pub fn with_current_time(self) -> Self {
+        match ockam_core::compat::time::now() {
+            Ok(now) => self.with_time(now),
+            Err(e) => {
+                debug!("the time attributes are not available: {e}");
+                self
+            }
+        }
+    }
+
+    /// Add the attributes of the resource being accessed
+    pub fn with_resource(self, resource: &Resource) -> Self {
+        self.with_resource_name(&resource.resource_name)
+            .with_resource_type(&resource.resource_type)
+    }
+
+    /// Add the name of the resource being accessed
+    pub fn with_resource_name(mut self, resource_name: &ResourceName) -> Self {
+        self.env.put(
+            format!("{RESOURCE_KEY}.name"),
+            str(resource_name.to_string()),
+        );
+        self
+    }
+
+    /// Add the type of the resource being accessed
+    pub fn with_resource_type(mut self, resource_type: &ResourceType) -> Self {
+        self.env.put(
+            format!("{RESOURCE_KEY}.type"),
+            str(resource_type.to_string()),
+        );
+        self
+    }
+
+    /// Add the attributes of the secure channel used to exchange a message
+    pub fn with_secure_channel(mut self, address: &Address, identifier: &Identifier) -> Self {
+        self.put("secure_channel.address", str(address.address()));
+        self.put("secure_channel.identifier", str(identifier.to_string()));
+        self
+    }
+
+    /// Add the attributes of an incoming message: its secure channel and its source
+    pub fn with_incoming_message(mut self, relay_msg: &RelayMessage) -> Self {
+        let return_route = relay_msg.return_route();
+
+        if let Ok(info) = SecureChannelLocalInfo::find_info(relay_msg.local_message()) {
+            // replies go through the secure channel, which is the first hop of the return route
+            if let Ok(address) = return_route.next() {
+                self = self.with_secure_channel(address, &info.their_identifier().into());
+            }
+        }
+
+        self.with_message_source(relay_msg)
+    }
+
+    /// Add the attributes of the transport address a message comes from,
+    /// if it was recorded by the transport which received the message
+    pub fn with_message_source(self, relay_msg: &RelayMessage) -> Self {
+        match SourceAddressLocalInfo::find_info(relay_msg.local_message()) {
+            Ok(info) => self.with_source(&info.address()),
+            Err(_) => self,
+        }
+    }
+
+    /// Add the attributes of the transport address a message comes from
+    pub fn with_source(mut self, address: &Address) -> Self {
+        self.put(
+            "source.transport",
+            int(u8::from(address.transport_type()) as i64),
+        );
+        self.put("source.address", str(address.address()));
+        if let Ok(socket_address) = SocketAddr::from_str(address.address()) {
+            self.put("source.ip", str(socket_address.ip().to_string()));
+        }
+        self
+    }
+
+    /// Add the method and the path of a request to the API of a node
+    pub fn with_api_request(mut self, request: &RequestHeader) -> Self {
+        if let Some(method) = request.method() {
+            self.put("api.method", str(method.to_string().to_lowercase()));
+        }
+        self.put(
+            "api.path",
+            str(format!("/{}", request.path().trim_start_matches('/'))),
+        );
+        self
+    }
+
+    /// Return the attributes as an environment
+    pub fn env(&self) -> &Env {
+        &self.env
+    }
+
+    fn put(&mut self, key: &str, value: Expr) {
+        self.env.put(format!("{CONTEXT_KEY}.{key}"), value);
+    }
+}
+
+impl From<RequestContext> for Env {
+    fn from(value: RequestContext) -> Self {
+        value.env
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_core::{route, LocalMessage, TransportType};
+
+    #[test]
+    fn time_attributes() {
+        // 2024-06-05T14:30:00Z, a wednesday
+        let context = RequestContext::new().with_time(1_717_597_800);
+        let env = context.env();
+
+        assert_eq!(env.get("context.time").unwrap(), &int(1_717_597_800));
+        assert_eq!(env.get("context.hour").unwrap(), &int(14));
+        assert_eq!(env.get("context.minute").unwrap(), &int(30));
+        assert_eq!(env.get("context.day_of_week").unwrap(), &str("wednesday"));
+    }
+
+    #[test]
+    fn forged_return_route_has_no_source_attributes() {
+        // the return route of a decrypted message comes from the remote peer,
+        // which can insert any transport address in it
+        let resource = Resource::new("outlet-db", ResourceType::TcpOutlet);
+        let local_message = LocalMessage::new()
+            .with_onward_route(route!["outlet"])
+            .with_return_route(route![
+                "secure_channel_encryptor",
+                (TransportType::new(2), "192.168.1.10:4000"),
+                "remote"
+            ]);
+        let relay_msg = RelayMessage::new("decryptor".into(), "outlet".into(), local_message);
+
+        let env: Env = RequestContext::new()
+            .with_resource(&resource)
+            .with_incoming_message(&relay_msg)
+            .into();
+
+        assert_eq!(env.get("resource.name").unwrap(), &str("outlet-db"));
+        assert_eq!(env.get("resource.type").unwrap(), &str("tcp-outlet"));
+        assert!(!env.contains("context.source.transport"));
+        assert!(!env.contains("context.source.address"));
+        assert!(!env.contains("context.source.ip"));
+        assert!(!env.contains("context.secure_channel.identifier"));
+    }
+
+    #[test]
+    fn source_attributes_from_local_info() -> ockam_core::Result<()> {
+        let source = SourceAddressLocalInfo::new(TransportType::new(1), "10.0.0.7:52000");
+        let local_message = LocalMessage::new()
+            .with_onward_route(route!["outlet"])
+            .with_return_route(route!["secure_channel_encryptor", "remote"])
+            .with_local_info(source.mark(vec![])?);
+        let relay_msg = RelayMessage::new("decryptor".into(), "outlet".into(), local_message);
+
+        let env: Env = RequestContext::new()
+            .with_incoming_message(&relay_msg)
+            .into();
+
+        assert_eq!(env.get("context.source.transport").unwrap(), &int(1));
+        assert_eq!(
+            env.get("context.source.address").unwrap(),
+            &str("10.0.0.7:52000")
+        );
+        assert_eq!(env.get("context.source.ip").unwrap(), &str("10.0.0.7"));
+        Ok(())
+    }
+}
@@ -7,8 +7,8 @@ use ockam_core::compat::vec::vec;
 use ockam_core::Result;
 use ockam_core::{IncomingAccessControl, RelayMessage};
 
-use crate::abac::Abac;
 use crate::abac::SUBJECT_KEY;
+use crate::abac::{Abac, RequestContext};
 use crate::Expr::*;
 use crate::{Env, Expr};
 use ockam_core::compat::format;
@@ -73,8 +73,12 @@ impl IncomingAbac {
             }
         };
 
+        let context = RequestContext::new()
+            .with_current_time()
+            .with_incoming_message(relay_msg);
+
         self.abac
-            .is_identity_authorized(&identifier, &self.expression)
+            .is_identity_authorized_in_context(&identifier, &self.expression, context)
             .await
     }
 
@@ -1,8 +1,10 @@
 #[allow(clippy::module_inception)]
 mod abac;
+mod context;
 mod incoming;
 mod outgoing;
 
 pub use abac::*;
+pub use context::*;
 pub use incoming::*;
 pub use outgoing::*;
@@ -8,8 +8,8 @@ use ockam_core::RelayMessage;
 use ockam_core::{async_trait, OutgoingAccessControl};
 use ockam_core::{Address, DenyAll, Result};
 
-use crate::abac::Abac;
 use crate::abac::SUBJECT_KEY;
+use crate::abac::{Abac, RequestContext};
 use crate::Expr::*;
 use crate::{Env, Expr};
 use ockam_core::compat::format;
@@ -80,8 +80,8 @@ impl OutgoingAbac {
 
     /// Returns true if the sender of the message is validated by the expression stored in AbacAccessControl
     pub async fn is_authorized_impl(&self, relay_msg: &RelayMessage) -> Result<bool> {
-        let identifier = match Abac::get_outgoing_identifier(&self.ctx, relay_msg)? {
-            Some(identifier) => identifier,
+        let (address, identifier) = match Abac::get_outgoing_secure_channel(&self.ctx, relay_msg)? {
+            Some(secure_channel) => secure_channel,
             None => {
                 debug! {
                     policy = %self.expression,
@@ -92,8 +92,13 @@ impl OutgoingAbac {
             }
         };
 
+        let context = RequestContext::new()
+            .with_current_time()
+            .with_secure_channel(&address, &identifier)
+            .with_message_source(relay_msg);
+
         self.abac
-            .is_identity_authorized(&identifier, &self.expression)
+            .is_identity_authorized_in_context(&identifier, &self.expression, context)
             .await
     }
 }
@@ -1,6 +1,7 @@
-use ockam_abac::{eval, parse, Env, Expr};
+use ockam_abac::{eval, parse, Env, Expr, OPERATORS};
 use rustyline::error::ReadlineError;
 use rustyline::highlight::MatchingBracketHighlighter;
+use rustyline::history::DefaultHistory;
 use rustyline::validate::MatchingBracketValidator;
 use rustyline::{Config, EditMode, Editor, Result};
 use rustyline_derive::{Completer, Helper, Highlighter, Hinter, Validator};
@@ -9,6 +10,7 @@ const HELP: &str = r#"Available commands:
   :def <id> <expression>  -- Add an expression to the environment.
   :env                    -- Show all current environment entries.
   :clear                  -- Remove all bindings from the environment.
+  :ops                    -- Show all available operators.
   :help | :h | :?         -- Show this help message."#;
 
 #[derive(Completer, Helper, Highlighter, Hinter, Validator)]
@@ -27,7 +29,7 @@ fn main() -> Result<()> {
         .build();
 
     let mut env = Env::new();
-    let mut repl = Editor::<ReplHelper>::with_config(c)?;
+    let mut repl = Editor::<ReplHelper, DefaultHistory>::with_config(c)?;
     repl.set_helper(Some(ReplHelper {
         highlighter: MatchingBracketHighlighter::new(),
         validator: MatchingBracketValidator::new(),
@@ -86,6 +88,7 @@ fn on_command(line: &str, env: &mut Env) {
             }
         }
         (":clear", _) => env.clear(),
+        (":ops", _) => println!("{}", OPERATORS.join(" ")),
         (":help" | ":h" | ":?", _) => println!("{HELP}"),
         (cmd, _) => eprintln!("unknown command {cmd}"),
     }
@@ -24,6 +24,8 @@ const NAME_FORMAT: &str =
 /// A BooleanExpr models a boolean expression made of:
 ///
 ///  - Names.
+///  - Name-value pairs: `name=value`.
+///  - Names compared to a pattern: `name^=prefix`, `name$=suffix`, `name*=substring`, `name~=glob`.
 ///  - Binary operators: and, or.
 ///  - Unary operator: not.
 ///  - Optional parentheses: 'and' takes precedence over 'or', and 'not' over 'and'.
@@ -50,6 +52,48 @@ pub enum BooleanExpr {
     Not(#[n(0)] Box<BooleanExpr>),
     #[n(6)]
     Empty,
+    #[n(7)]
+    NameMatch(#[n(0)] String, #[n(1)] BooleanOperator, #[n(2)] String),
+}
+
+/// Operators comparing the value of a name to a pattern
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum BooleanOperator {
+    #[n(0)] StartsWith,
+    #[n(1)] EndsWith,
+    #[n(2)] Contains,
+    #[n(3)] Glob,
+}
+
+impl BooleanOperator {
+    /// All the operators
+    pub const ALL: [BooleanOperator; 4] = [
+        BooleanOperator::StartsWith,
+        BooleanOperator::EndsWith,
+        BooleanOperator::Contains,
+        BooleanOperator::Glob,
+    ];
+
+    /// Symbol of the operator in a boolean expression
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            BooleanOperator::StartsWith => "^=",
+            BooleanOperator::EndsWith => "$=",
+            BooleanOperator::Contains => "*=",
+            BooleanOperator::Glob => "~=",
+        }
+    }
+
+    /// Corresponding operator of a policy expression
+    pub fn operator(&self) -> &'static str {
+        match self {
+            BooleanOperator::StartsWith => "starts-with?",
+            BooleanOperator::EndsWith => "ends-with?",
+            BooleanOperator::Contains => "contains?",
+            BooleanOperator::Glob => "glob?",
+        }
+    }
 }
 
 impl PartialEq for BooleanExpr {
@@ -59,6 +103,9 @@ impl PartialEq for BooleanExpr {
             (BooleanExpr::NameValue(n1, v1), BooleanExpr::NameValue(n2, v2)) => {
                 n1 == n2 && v1 == v2
             }
+            (BooleanExpr::NameMatch(n1, o1, v1), BooleanExpr::NameMatch(n2, o2, v2)) => {
+                n1 == n2 && o1 == o2 && v1 == v2
+            }
             (BooleanExpr::Identifier(n1), BooleanExpr::Identifier(n2)) => n1 == n2,
             (BooleanExpr::Or(e1, e2), BooleanExpr::Or(e3, e4)) => e1 == e3 && e2 == e4,
             (BooleanExpr::And(e1, e2), BooleanExpr::And(e3, e4)) => e1 == e3 && e2 == e4,
@@ -73,6 +120,18 @@ impl Eq for BooleanExpr {}
 #[cfg(feature = "std")]
 impl Display for BooleanExpr {
     fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        fn name_match_to_string(s: &str, symbol: &str, v: &str) -> String {
+            // values containing other characters than the ones allowed in names must be quoted
+            let is_simple_value = v
+                .chars()
+                .all(|c| c.is_alphanumeric() || c == '.' || c == '_' || c == '-');
+            if is_simple_value {
+                format!("{s}{symbol}{v}")
+            } else {
+                format!("{s}{symbol}\"{v}\"")
+            }
+        }
+
         fn to_nested_string(b: &BooleanExpr) -> String {
             match b {
                 BooleanExpr::Name(s) => s.clone(),
@@ -83,6 +142,7 @@ impl Display for BooleanExpr {
                         format!("{}={}", s, v)
                     }
                 }
+                BooleanExpr::NameMatch(s, o, v) => name_match_to_string(s, o.symbol(), v),
                 BooleanExpr::Identifier(s) => s.clone(),
                 BooleanExpr::Or(e1, e2) => format!("({e1} or {e2})"),
                 BooleanExpr::And(e1, e2) => format!("({e1} and {e2})"),
@@ -100,6 +160,7 @@ impl Display for BooleanExpr {
                     f.write_str(&format!("{}={}", s, v))
                 }
             }
+            BooleanExpr::NameMatch(s, o, v) => f.write_str(&name_match_to_string(s, o.symbol(), v)),
             BooleanExpr::Identifier(s) => f.write_str(s),
             BooleanExpr::Or(e1, e2) => f.write_str(&format!(
                 "{} or {}",
@@ -163,6 +224,11 @@ impl BooleanExpr {
         BooleanExpr::NameValue(s.to_string(), v.to_string())
     }
 
+    /// Create a name compared to a pattern to be used in a boolean expression.
+    pub fn name_match(s: &str, operator: BooleanOperator, v: &str) -> BooleanExpr {
+        BooleanExpr::NameMatch(s.to_string(), operator, v.to_string())
+    }
+
     /// Create an identity identifier to be used in a boolean expression.
     pub fn identifier(s: &str) -> BooleanExpr {
         BooleanExpr::Identifier(s.to_string())
@@ -206,6 +272,11 @@ impl BooleanExpr {
                 Ident(format!("{}.{}", SUBJECT_KEY, n)),
                 Str(v.to_string()),
             ]),
+            BooleanExpr::NameMatch(n, o, v) => List(vec![
+                Ident(o.operator().to_string()),
+                Ident(format!("{}.{}", SUBJECT_KEY, n)),
+                Str(v.to_string()),
+            ]),
             BooleanExpr::Identifier(i) => List(vec![
                 Ident("=".to_string()),
                 Ident(format!("{}.identifier", SUBJECT_KEY)),
@@ -279,7 +350,7 @@ impl Not for BooleanExpr {
 ///    name : (alphanum | '.' | '_' | '-')+
 #[cfg(feature = "std")]
 mod parsers {
-    use crate::boolean_expr::{BooleanExpr, NAME_FORMAT};
+    use crate::boolean_expr::{BooleanExpr, BooleanOperator, NAME_FORMAT};
     use ockam_core::env::FromString;
     use ockam_identity::Identifier;
     use winnow::ascii::multispace0;
@@ -363,20 +434,30 @@ mod parsers {
             return Ok(name);
         }
 
-        // otherwise, keep processing the input to figure out if it's a name-value pair
-        // peek the next char; continue only if it's an equal sign
-        let peeked: IResult<&str, &str> = take_while(1, |c| c == '=').parse_peek(input.as_ref());
-        let next_char_is_not_equal_sign = peeked.map(|(_, s)| s.is_empty()).unwrap_or(true);
-        if next_char_is_not_equal_sign {
-            return Ok(name);
-        }
+        // otherwise, keep processing the input to figure out if it's a name compared to a pattern
+        let operator = BooleanOperator::ALL
+            .into_iter()
+            .find(|o| input.starts_with(o.symbol()));
+        if let Some(operator) = operator {
+            // skip the operator
+            literal(operator.symbol()).parse_next(input)?;
+        } else {
+            // or a name-value pair
+            // peek the next char; continue only if it's an equal sign
+            let peeked: IResult<&str, &str> =
+                take_while(1, |c| c == '=').parse_peek(input.as_ref());
+            let next_char_is_not_equal_sign = peeked.map(|(_, s)| s.is_empty()).unwrap_or(true);
+            if next_char_is_not_equal_sign {
+                return Ok(name);
+            }
 
-        // skip '=' character
-        take_while(1, |c| c == '=')
-            .context(StrContext::Expected(
-                "not a name-value pair, missing '='".into(),
-            ))
-            .parse_next(input)?;
+            // skip '=' character
+            take_while(1, |c| c == '=')
+                .context(StrContext::Expected(
+                    "not a name-value pair, missing '='".into(),
+                ))
+                .parse_next(input)?;
+        }
         // skip the opening '"' if any
         let is_quoted = {
             let res: PResult<&str> = take_while(1, |c| c == '"').parse_next(input);
@@ -400,7 +481,10 @@ mod parsers {
             .parse_next(input)?
             .to_string()
         };
-        Ok(BooleanExpr::NameValue(name.to_string(), value))
+        match operator {
+            Some(operator) => Ok(BooleanExpr::NameMatch(name.to_string(), operator, value)),
+            None => Ok(BooleanExpr::NameValue(name.to_string(), value)),
+        }
     }
 
     /// Parse the 'and' operator
@@ -527,6 +611,41 @@ mod tests {
         test_fail_parse_name_value("=b", "The first character cannot be");
     }
 
+    #[test]
+    fn parse_name_match() {
+        test_parse_expr(
+            &mut "ockam-role^=ops-",
+            BooleanExpr::name_match("ockam-role", BooleanOperator::StartsWith, "ops-"),
+        );
+        test_parse_expr(
+            &mut "email~=\"*@corp.com\" and team*=data",
+            BooleanExpr::and(
+                BooleanExpr::name_match("email", BooleanOperator::Glob, "*@corp.com"),
+                BooleanExpr::name_match("team", BooleanOperator::Contains, "data"),
+            ),
+        );
+
+        test_fail_parse_name_value("a$=", "the value can't be empty");
+    }
+
+    #[test]
+    fn name_match_to_expr_and_string() {
+        let boolean_expr = BooleanExpr::or(
+            BooleanExpr::name_match("ockam-role", BooleanOperator::StartsWith, "ops-"),
+            BooleanExpr::name_match("email", BooleanOperator::Glob, "*@corp.com"),
+        );
+        let expr = parse(
+            "(or (starts-with? subject.ockam-role \"ops-\") (glob? subject.email \"*@corp.com\"))",
+        )
+        .unwrap()
+        .unwrap();
+        assert_eq!(boolean_expr.to_expression(), expr);
+
+        let string = "ockam-role^=ops- or email~=\"*@corp.com\"";
+        assert_eq!(boolean_expr.to_string(), string);
+        assert_eq!(BooleanExpr::try_from(string).unwrap(), boolean_expr);
+    }
+
     #[test]
     fn parse_boolean_expr() {
         test_parse_expr(
@@ -15,7 +15,7 @@ pub enum ParseError {
     TypeMismatch(Expr, Expr),
 }
 
-#[derive(Debug)]
+#[derive(Debug, Clone)]
 pub enum EvalError {
     Unbound(String),
     Unknown(String),
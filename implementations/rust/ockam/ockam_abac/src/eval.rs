@@ -6,6 +6,18 @@ use crate::expr::{unit, Expr};
 use ockam_core::compat::string::ToString;
 use ockam_core::compat::vec::Vec;
 
+/// Maximum length of the patterns accepted by 'regex?'.
+#[cfg(feature = "std")]
+const MAX_REGEX_LENGTH: usize = 256;
+
+/// Maximum size of a compiled 'regex?' pattern, in bytes.
+#[cfg(feature = "std")]
+const MAX_REGEX_SIZE: usize = 64 * 1024;
+
+/// Maximum number of compiled 'regex?' patterns kept for the next evaluations.
+#[cfg(feature = "std")]
+const REGEX_CACHE_CAPACITY: usize = 64;
+
 #[rustfmt::skip]
 pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
     /// A stack operation.
@@ -23,6 +35,14 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
         Gt(usize),
         Lt(usize),
         Member,
+        StartsWith,
+        EndsWith,
+        Contains,
+        Glob,
+        Regex,
+        Intersects,
+        Subset,
+        Len,
         Seq(usize),
     }
 
@@ -132,6 +152,61 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                             }
                             ctrl.push(Op::Member)
                         }
+                        "starts-with?" => {
+                            if nargs != 2 {
+                                let msg = "'starts-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::StartsWith)
+                        }
+                        "ends-with?" => {
+                            if nargs != 2 {
+                                let msg = "'ends-with?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::EndsWith)
+                        }
+                        "contains?" => {
+                            if nargs != 2 {
+                                let msg = "'contains?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Contains)
+                        }
+                        "glob?" => {
+                            if nargs != 2 {
+                                let msg = "'glob?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Glob)
+                        }
+                        "regex?" => {
+                            if nargs != 2 {
+                                let msg = "'regex?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Regex)
+                        }
+                        "intersects?" => {
+                            if nargs != 2 {
+                                let msg = "'intersects?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Intersects)
+                        }
+                        "subset?" => {
+                            if nargs != 2 {
+                                let msg = "'subset?' requires two arguments";
+                                return Err(EvalError::malformed(msg))
+                            }
+                            ctrl.push(Op::Subset)
+                        }
+                        "len" => {
+                            if nargs != 1 {
+                                return Err(EvalError::malformed("'len' requires one argument"))
+                            }
+                            ctrl.push(Op::Len)
+                        }
                         "exists?" => {
                             let mut b = true;
                             for x in &xs[1 ..] {
@@ -255,6 +330,66 @@ pub fn eval(expr: &Expr, env: &Env) -> Result<Expr, EvalError> {
                     }
                 }
             }
+            Op::StartsWith => {
+                let msg = "'starts-with?' expects string arguments";
+                eval_strings(&mut args, msg, |x, p| Ok(x.starts_with(p)))?
+            }
+            Op::EndsWith => {
+                let msg = "'ends-with?' expects string arguments";
+                eval_strings(&mut args, msg, |x, p| Ok(x.ends_with(p)))?
+            }
+            Op::Contains => {
+                let msg = "'contains?' expects string arguments";
+                eval_strings(&mut args, msg, |x, p| Ok(x.contains(p)))?
+            }
+            Op::Glob => {
+                let msg = "'glob?' expects string arguments";
+                eval_strings(&mut args, msg, |x, p| Ok(glob_match(p, x)))?
+            }
+            Op::Regex => {
+                let msg = "'regex?' expects string arguments";
+                eval_strings(&mut args, msg, regex_match)?
+            }
+            Op::Intersects => {
+                let msg = "'intersects?' expects sequences or comma-separated strings";
+                let ys = pop_set(&mut args, msg)?;
+                let xs = pop_set(&mut args, msg)?;
+                let mut b = false;
+                'outer: for x in &xs {
+                    for y in &ys {
+                        if x.equals(y)? {
+                            b = true;
+                            break 'outer
+                        }
+                    }
+                }
+                args.push(Expr::Bool(b))
+            }
+            Op::Subset => {
+                let msg = "'subset?' expects sequences or comma-separated strings";
+                let ys = pop_set(&mut args, msg)?;
+                let xs = pop_set(&mut args, msg)?;
+                let mut b = true;
+                for x in &xs {
+                    let mut found = false;
+                    for y in &ys {
+                        if x.equals(y)? {
+                            found = true;
+                            break
+                        }
+                    }
+                    if !found {
+                        b = false;
+                        break
+                    }
+                }
+                args.push(Expr::Bool(b))
+            }
+            Op::Len => {
+                let msg = "'len' expects a sequence or a comma-separated string";
+                let xs = pop_set(&mut args, msg)?;
+                args.push(Expr::Int(xs.len() as i64))
+            }
             Op::Seq(n) => {
                 let s = args.split_off(args.len() - n);
                 args.push(Expr::Seq(s))
@@ -293,12 +428,210 @@ where
     Ok(())
 }
 
+/// Evaluate a predicate against the two topmost arguments, which must be strings.
+fn eval_strings<F>(args: &mut Vec<Expr>, msg: &'static str, f: F) -> Result<(), EvalError>
+where
+    F: Fn(&str, &str) -> Result<bool, EvalError>,
+{
+    let y = pop(args);
+    let x = pop(args);
+    match (x, y) {
+        (Expr::Str(x), Expr::Str(y)) => {
+            args.push(Expr::Bool(f(&x, &y)?));
+            Ok(())
+        }
+        (Expr::Str(_), other) | (other, _) => Err(EvalError::InvalidType(other, msg)),
+    }
+}
+
+/// Pop off the topmost argument as a set of values.
+///
+/// Attributes are strings, so a string is interpreted as a comma-separated
+/// list of values.
+fn pop_set(args: &mut Vec<Expr>, msg: &'static str) -> Result<Vec<Expr>, EvalError> {
+    match pop(args) {
+        Expr::Seq(xs) => Ok(xs),
+        Expr::Str(s) => Ok(s
+            .split(',')
+            .map(str::trim)
+            .filter(|x| !x.is_empty())
+            .map(|x| Expr::Str(x.to_string()))
+            .collect()),
+        other => Err(EvalError::InvalidType(other, msg)),
+    }
+}
+
+/// Match a string against a glob pattern where `*` matches any sequence of
+/// characters and `?` matches a single character.
+///
+/// The matching is done in `O(pattern * input)` time by only backtracking to
+/// the most recent `*`.
+fn glob_match(pattern: &str, input: &str) -> bool {
+    let p: Vec<char> = pattern.chars().collect();
+    let s: Vec<char> = input.chars().collect();
+    let (mut i, mut j) = (0, 0);
+    // Position of the last '*' in the pattern and of the input when it was encountered.
+    let mut star: Option<(usize, usize)> = None;
+    while j < s.len() {
+        if i < p.len() && (p[i] == '?' || p[i] == s[j]) {
+            i += 1;
+            j += 1
+        } else if i < p.len() && p[i] == '*' {
+            star = Some((i, j));
+            i += 1
+        } else if let Some((si, sj)) = star {
+            i = si + 1;
+            j = sj + 1;
+            star = Some((si, sj + 1))
+        } else {
+            return false;
+        }
+    }
+    p[i..].iter().all(|c| *c == '*')
+}
+
+/// Match a string against a regular expression.
+///
+/// The `regex` crate guarantees a linear matching time, and the size of the
+/// patterns is bounded to keep their compilation cheap.
+#[cfg(feature = "std")]
+fn regex_match(input: &str, pattern: &str) -> Result<bool, EvalError> {
+    Ok(compiled_regex(pattern)?.is_match(input))
+}
+
+/// Return the compiled regular expression of a pattern.
+///
+/// Policies are evaluated for each message, so the most recently used
+/// patterns are kept in a bounded cache instead of being compiled again.
+#[cfg(feature = "std")]
+fn compiled_regex(pattern: &str) -> Result<regex::Regex, EvalError> {
+    use ockam_core::compat::sync::Mutex;
+    use once_cell::race::OnceBox;
+
+    if pattern.len() > MAX_REGEX_LENGTH {
+        let msg = format!("'regex?' patterns are limited to {MAX_REGEX_LENGTH} characters");
+        return Err(EvalError::malformed(msg));
+    }
+
+    /// Compiled patterns, the most recently used first.
+    static CACHE: OnceBox<Mutex<Vec<(String, regex::Regex)>>> = OnceBox::new();
+    let cache = CACHE.get_or_init(|| Box::new(Mutex::new(Vec::new())));
+
+    {
+        let mut cache = cache.lock().unwrap();
+        if let Some(index) = cache.iter().position(|(p, _)| p == pattern) {
+            let entry = cache.remove(index);
+            let regex = entry.1.clone();
+            cache.insert(0, entry);
+            return Ok(regex);
+        }
+    }
+
+    let regex = regex::RegexBuilder::new(pattern)
+        .size_limit(MAX_REGEX_SIZE)
+        .dfa_size_limit(MAX_REGEX_SIZE)
+        .build()
+        .map_err(|e| EvalError::malformed(format!("invalid 'regex?' pattern: {e}")))?;
+
+    let mut cache = cache.lock().unwrap();
+    if !cache.iter().any(|(p, _)| p == pattern) {
+        cache.truncate(REGEX_CACHE_CAPACITY - 1);
+        cache.insert(0, (pattern.to_string(), regex.clone()));
+    }
+    Ok(regex)
+}
+
+#[cfg(not(feature = "std"))]
+fn regex_match(_input: &str, _pattern: &str) -> Result<bool, EvalError> {
+    Err(EvalError::Unknown("regex?".to_string()))
+}
+
 #[cfg(test)]
 mod tests {
     use crate::{
         eval, subject_has_credential_attribute, subject_has_credential_policy_expression, Env, Expr,
     };
 
+    fn eval_str(s: &str, environment: &Env) -> Result<Expr, crate::EvalError> {
+        eval(&crate::parse(s).unwrap().unwrap(), environment)
+    }
+
+    #[test]
+    fn string_operators() {
+        let mut environment = Env::new();
+        environment.put("subject.ockam-role", Expr::Str("ops-admin".into()));
+        environment.put("subject.email", Expr::Str("alice@corp.com".into()));
+
+        for (expression, expected) in [
+            ("(starts-with? subject.ockam-role \"ops-\")", true),
+            ("(starts-with? subject.ockam-role \"dev-\")", false),
+            ("(ends-with? subject.email \"@corp.com\")", true),
+            ("(contains? subject.ockam-role \"-adm\")", true),
+            ("(glob? subject.email \"*@corp.com\")", true),
+            ("(glob? subject.email \"a?ice@*.com\")", true),
+            ("(glob? subject.email \"*@corp.org\")", false),
+            ("(regex? subject.email \"^[a-z]+@corp\\\\.com$\")", true),
+            ("(regex? subject.email \"^bob@\")", false),
+        ] {
+            let actual = eval_str(expression, &environment).unwrap();
+            assert_eq!(actual, Expr::Bool(expected), "{expression}");
+        }
+
+        assert!(eval_str("(starts-with? subject.ockam-role 1)", &environment).is_err());
+        assert!(eval_str("(glob? subject.email)", &environment).is_err());
+        assert!(eval_str("(regex? subject.email \"(\")", &environment).is_err());
+        let long_pattern = format!("(regex? subject.email \"{}\")", "a".repeat(1000));
+        assert!(eval_str(&long_pattern, &environment).is_err());
+    }
+
+    #[test]
+    fn regex_cache() {
+        // the patterns are still matched correctly once they are evicted from the cache
+        for round in 0..2 {
+            for n in 0..2 * super::REGEX_CACHE_CAPACITY {
+                let pattern = format!("^user-{n}$");
+                let regex = super::compiled_regex(&pattern).unwrap();
+                assert!(regex.is_match(&format!("user-{n}")), "{round} {pattern}");
+                assert!(!regex.is_match(&format!("user-{n}0")), "{round} {pattern}");
+            }
+        }
+        assert!(super::compiled_regex("(").is_err());
+        assert!(super::compiled_regex("(").is_err());
+    }
+
+    #[test]
+    fn set_operators() {
+        let mut environment = Env::new();
+        environment.put("subject.groups", Expr::Str("dev, ops".into()));
+        environment.put(
+            "resource.groups",
+            Expr::Seq(vec![Expr::Str("ops".into()), Expr::Str("sec".into())]),
+        );
+
+        for (expression, expected) in [
+            (
+                "(intersects? subject.groups resource.groups)",
+                Expr::Bool(true),
+            ),
+            ("(intersects? subject.groups [\"sec\"])", Expr::Bool(false)),
+            ("(subset? [\"ops\"] subject.groups)", Expr::Bool(true)),
+            (
+                "(subset? subject.groups resource.groups)",
+                Expr::Bool(false),
+            ),
+            ("(subset? [] resource.groups)", Expr::Bool(true)),
+            ("(len subject.groups)", Expr::Int(2)),
+            ("(= (len resource.groups) 2)", Expr::Bool(true)),
+            ("(len \"\")", Expr::Int(0)),
+        ] {
+            let actual = eval_str(expression, &environment).unwrap();
+            assert_eq!(actual, expected, "{expression}");
+        }
+
+        assert!(eval_str("(len 1)", &environment).is_err());
+        assert!(eval_str("(intersects? [1] [\"a\"])", &environment).is_err());
+    }
+
     #[test]
     fn test() {
         let mut environment = Env::new();
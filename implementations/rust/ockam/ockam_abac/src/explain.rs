@@ -0,0 +1,190 @@
+use core::fmt;
+
+use crate::env::Env;
+use crate::error::EvalError;
+use crate::eval::eval;
+use crate::expr::Expr;
+use ockam_core::compat::vec::Vec;
+
+/// The result of the evaluation of an expression, together with the explanations of the
+/// sub-expressions which were evaluated to get that result.
+///
+/// Only the boolean operators `and`, `or`, `not` and `if` are explained with sub-expressions,
+/// the other expressions are leaves. Like in [`eval`], the arguments of `and` and `or` are
+/// evaluated lazily, so the arguments after the one deciding the result are not explained.
+#[derive(Debug, Clone)]
+pub struct Explanation {
+    expression: Expr,
+    result: Result<Expr, EvalError>,
+    children: Vec<Explanation>,
+}
+
+/// Evaluate an expression in a given environment and explain the result
+pub fn explain(expr: &Expr, env: &Env) -> Explanation {
+    let result = eval(expr, env);
+
+    let mut children = Vec::new();
+    if let Expr::List(xs) = expr {
+        if let [Expr::Ident(id), args @ ..] = &xs[..] {
+            match id.as_str() {
+                "and" | "or" => {
+                    // stop at the first argument which doesn't let the evaluation continue
+                    let continue_with = id == "and";
+                    for arg in args {
+                        let child = explain(arg, env);
+                        let is_decisive =
+                            !matches!(child.result, Ok(Expr::Bool(b)) if b == continue_with);
+                        children.push(child);
+                        if is_decisive {
+                            break;
+                        }
+                    }
+                }
+                "not" => children.extend(args.iter().map(|arg| explain(arg, env))),
+                "if" => {
+                    if let [test, then, orelse] = args {
+                        let test = explain(test, env);
+                        let branch = match test.result {
+                            Ok(Expr::Bool(true)) => Some(then),
+                            Ok(Expr::Bool(false)) => Some(orelse),
+                            _ => None,
+                        };
+                        children.push(test);
+                        if let Some(branch) = branch {
+                            children.push(explain(branch, env));
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+    }
+
+    Explanation {
+        expression: expr.clone(),
+        result,
+        children,
+    }
+}
+
+impl Explanation {
+    /// The explained expression
+    pub fn expression(&self) -> &Expr {
+        &self.expression
+    }
+
+    /// The result of the evaluation of the expression
+    pub fn result(&self) -> &Result<Expr, EvalError> {
+        &self.result
+    }
+
+    /// The explanations of the evaluated sub-expressions
+    pub fn children(&self) -> &[Explanation] {
+        &self.children
+    }
+
+    /// Return true if the expression evaluated to `true`
+    pub fn is_true(&self) -> bool {
+        matches!(self.result, Ok(Expr::Bool(true)))
+    }
+
+    /// Return the smallest sub-expression which decided the result of the evaluation.
+    ///
+    /// For example the decisive sub-expression of `(and (= a 1) (= b 2))`, when `b` is 3,
+    /// is `(= b 2)`. When all the arguments of `and` are true, the whole `and` expression is
+    /// decisive since all its arguments were necessary to get the result.
+    pub fn decisive(&self) -> &Explanation {
+        let Some(last) = self.children.last() else {
+            return self;
+        };
+        let operator = match &self.expression {
+            Expr::List(xs) => match xs.first() {
+                Some(Expr::Ident(id)) => id.as_str(),
+                _ => return self,
+            },
+            _ => return self,
+        };
+        match operator {
+            "and" if !self.is_true() => last.decisive(),
+            "or" if self.is_true() => last.decisive(),
+            "not" | "if" => last.decisive(),
+            _ => self,
+        }
+    }
+
+    fn fmt_indented(&self, f: &mut fmt::Formatter<'_>, indent: usize) -> fmt::Result {
+        match &self.result {
+            Ok(value) => writeln!(f, "{:indent$}{} => {}", "", self.expression, value)?,
+            Err(e) => writeln!(f, "{:indent$}{} => error: {}", "", self.expression, e)?,
+        }
+        for child in &self.children {
+            child.fmt_indented(f, indent + 2)?;
+        }
+        Ok(())
+    }
+}
+
+/// Display the explanation as a tree of the evaluated expressions and their values
+impl fmt::Display for Explanation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.fmt_indented(f, 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{and, eq, ident, int, or, str};
+
+    #[test]
+    fn explain_and_or() {
+        let mut env = Env::new();
+        env.put("subject.role", str("dev"));
+        env.put("subject.level", int(3));
+
+        // the second argument of 'and' is false, the third one is not evaluated
+        let expr = and([
+            eq([ident("subject.level"), int(3)]),
+            eq([ident("subject.role"), str("admin")]),
+            eq([ident("subject.missing"), int(1)]),
+        ]);
+        let explanation = explain(&expr, &env);
+        assert!(!explanation.is_true());
+        assert_eq!(explanation.children().len(), 2);
+        assert_eq!(
+            explanation.decisive().expression(),
+            &eq([ident("subject.role"), str("admin")])
+        );
+
+        // 'or' is decided by its first true argument
+        let expr = or([
+            eq([ident("subject.role"), str("admin")]),
+            eq([ident("subject.role"), str("dev")]),
+        ]);
+        let explanation = explain(&expr, &env);
+        assert!(explanation.is_true());
+        assert_eq!(
+            explanation.decisive().expression(),
+            &eq([ident("subject.role"), str("dev")])
+        );
+
+        // a true 'and' is decided by all its arguments
+        let expr = and([eq([ident("subject.level"), int(3)])]);
+        let explanation = explain(&expr, &env);
+        assert!(explanation.is_true());
+        assert_eq!(explanation.decisive().expression(), &expr);
+    }
+
+    #[test]
+    fn explain_unbound_identifier() {
+        let env = Env::new();
+        let expr = and([eq([ident("subject.role"), str("admin")])]);
+        let explanation = explain(&expr, &env);
+
+        assert!(explanation.result().is_err());
+        assert!(explanation.decisive().result().is_err());
+        assert!(explanation
+            .to_string()
+            .contains("(= subject.role \"admin\") => error"));
+    }
+}
@@ -10,6 +10,7 @@ extern crate core;
 mod env;
 mod error;
 mod eval;
+mod explain;
 mod policy;
 mod types;
 
@@ -29,16 +30,21 @@ pub use boolean_expr::*;
 pub use env::Env;
 pub use error::{EvalError, ParseError};
 pub use eval::eval;
+pub use explain::{explain, Explanation};
 pub use expr::Expr;
+pub use policy::node_api_roles_policy_expression;
 pub use policy::{
-    storage::*, Policies, PolicyAccessControl, ResourcePolicy, ResourceTypePolicy, Resources,
+    check_policy, storage::*, Policies, PolicyAccessControl, PolicyCheckResult, PolicyCheckSubject,
+    PolicyDecision, PolicyDecisionLog, PolicyDecisionsQuery, PolicyDecisionsRetention,
+    ResourcePolicy, ResourceTypePolicy, Resources, NODE_API_ADMIN_ROLE, NODE_API_OPERATOR_ROLE,
+    NODE_API_READER_ROLE, NODE_API_ROLE_ATTRIBUTE,
 };
 pub use policy_expr::*;
 pub use resource::{Resource, ResourceType};
 pub use types::{Action, ResourceName, Subject};
 
 #[cfg(feature = "std")]
-pub use parser::parse;
+pub use parser::{parse, OPERATORS};
 
 #[cfg(not(feature = "std"))]
 pub use ockam_executor::tokio;
@@ -19,8 +19,25 @@ fn ident_pattern() -> &'static Regex {
     })
 }
 
-pub const OPERATORS: [&str; 10] = [
-    "and", "or", "not", "if", "<", ">", "=", "!=", "member?", "exists?",
+pub const OPERATORS: [&str; 18] = [
+    "and",
+    "or",
+    "not",
+    "if",
+    "<",
+    ">",
+    "=",
+    "!=",
+    "member?",
+    "exists?",
+    "starts-with?",
+    "ends-with?",
+    "contains?",
+    "glob?",
+    "regex?",
+    "intersects?",
+    "subset?",
+    "len",
 ];
 
 #[rustfmt::skip]
@@ -1,6 +1,6 @@
-use crate::abac::Abac;
+use crate::abac::{Abac, RequestContext};
 use crate::policy::{IncomingPolicyAccessControl, OutgoingPolicyAccessControl};
-use crate::{Action, Env, Policies, Resource};
+use crate::{explain, Action, Env, Expr, Policies, PolicyDecision, Resource};
 use core::fmt;
 use core::fmt::{Debug, Formatter};
 use ockam_core::compat::sync::Arc;
@@ -76,6 +76,26 @@ impl PolicyAccessControl {
     }
 
     pub async fn is_identity_authorized(&self, identifier: &Identifier) -> Result<bool> {
+        self.is_request_authorized(identifier, self.request_context())
+            .await
+    }
+
+    /// Return true if a policy is set for the resource and action
+    pub async fn has_policy(&self) -> Result<bool> {
+        Ok(self
+            .policies
+            .get_expression_for_resource(&self.resource, &self.action)
+            .await?
+            .is_some())
+    }
+
+    /// Return true if an identity is authorized by the policy of the resource and action,
+    /// for a request having the given attributes
+    pub async fn is_request_authorized(
+        &self,
+        identifier: &Identifier,
+        context: RequestContext,
+    ) -> Result<bool> {
         // Load the policy expression for resource and action:
         let expression = if let Some(expr) = self
             .policies
@@ -84,17 +104,135 @@ impl PolicyAccessControl {
         {
             expr
         } else {
-            // If no expression exists for this resource and action, access is denied:
-            debug! {
-                resource = %self.resource,
-                action   = %self.action,
-                "no policy found; access denied"
-            }
-            return Ok(false);
+            return self.deny_without_policy(Some(identifier)).await;
         };
 
-        self.abac
-            .is_identity_authorized(identifier, &expression)
+        self.is_identity_authorized_in_context(identifier, &expression, context)
             .await
     }
+
+    /// Evaluate the policy expression for an identity and record the decision
+    /// if the policies have a decision log
+    pub(super) async fn is_identity_authorized_in_context(
+        &self,
+        identifier: &Identifier,
+        expression: &Expr,
+        context: RequestContext,
+    ) -> Result<bool> {
+        let Some(decision_log) = self.policies.decision_log() else {
+            return self
+                .abac
+                .is_identity_authorized_in_context(identifier, expression, context)
+                .await;
+        };
+
+        let environment = self
+            .abac
+            .environment_in_context(identifier, expression, context)
+            .await?;
+        let allowed = Abac::is_authorized_in_environment(identifier, expression, &environment);
+
+        if decision_log.records(allowed) {
+            let decision = PolicyDecision::new(
+                ockam_core::compat::time::now().unwrap_or_default(),
+                identifier.clone(),
+                &self.resource,
+                self.action.clone(),
+                &environment,
+                &explain(expression, &environment),
+            );
+            decision_log.record(decision).await;
+        }
+
+        Ok(allowed)
+    }
+
+    /// Deny access when no expression exists for the resource and action,
+    /// and record the denial if the identity making the request is known
+    pub(super) async fn deny_without_policy(
+        &self,
+        identifier: Option<&Identifier>,
+    ) -> Result<bool> {
+        debug! {
+            resource = %self.resource,
+            action   = %self.action,
+            "no policy found; access denied"
+        }
+
+        if let (Some(identifier), Some(decision_log)) = (identifier, self.policies.decision_log()) {
+            let decision = PolicyDecision::denied(
+                ockam_core::compat::time::now().unwrap_or_default(),
+                identifier.clone(),
+                &self.resource,
+                self.action.clone(),
+                "no policy",
+            );
+            decision_log.record(decision).await;
+        }
+
+        Ok(false)
+    }
+
+    /// Return the attributes of a request for the current time and the resource
+    /// protected by this access control
+    pub fn request_context(&self) -> RequestContext {
+        RequestContext::new()
+            .with_current_time()
+            .with_resource(&self.resource)
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{
+        PolicyDecisionLog, PolicyDecisionSqlxDatabase, PolicyDecisionsQuery,
+        ResourcePolicySqlxDatabase, ResourceType, ResourceTypePolicySqlxDatabase,
+    };
+    use core::str::FromStr;
+    use core::time::Duration;
+    use ockam_core::compat::vec::Vec;
+    use ockam_identity::identities;
+
+    #[tokio::test]
+    async fn test_denial_without_policy_is_recorded() -> Result<()> {
+        let repository = Arc::new(PolicyDecisionSqlxDatabase::create().await?);
+        let policies = Policies::new(
+            Arc::new(ResourcePolicySqlxDatabase::create().await?),
+            Arc::new(ResourceTypePolicySqlxDatabase::create().await?),
+        )
+        .with_decision_log(PolicyDecisionLog::new(repository.clone()));
+        let resource = Resource::new("outlet", ResourceType::TcpOutlet);
+        let access_control = PolicyAccessControl::new(
+            policies,
+            identities().await?.identities_attributes(),
+            None,
+            Env::new(),
+            resource.clone(),
+            Action::HandleMessage,
+        );
+        let identifier = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )?;
+
+        assert!(!access_control.is_identity_authorized(&identifier).await?);
+
+        let mut decisions = Vec::new();
+        for _ in 0..100 {
+            decisions = repository
+                .get_decisions(&PolicyDecisionsQuery::default())
+                .await?;
+            if !decisions.is_empty() {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(decisions.len(), 1);
+        let decision = &decisions[0];
+        assert!(!decision.allowed);
+        assert_eq!(decision.identifier, identifier);
+        assert_eq!(decision.resource_name, resource.resource_name);
+        assert_eq!(decision.reason, Some("no policy".into()));
+        Ok(())
+    }
 }
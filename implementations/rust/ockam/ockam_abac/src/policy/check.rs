@@ -0,0 +1,135 @@
+use crate::abac::Abac;
+use crate::{eval, subject_identifier_attribute, Env, Expr};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::vec::Vec;
+use ockam_identity::Identifier;
+use serde::Serialize;
+
+use crate::expr::str;
+
+/// An identity, with the attributes of its credential, used to check a policy expression
+#[derive(Clone, Debug, Encode, Decode, CborLen, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyCheckSubject {
+    #[n(1)] pub identifier: Identifier,
+    /// Attributes of the identity, `None` if the identity has no credential
+    #[n(2)] pub attributes: Option<BTreeMap<Vec<u8>, Vec<u8>>>,
+}
+
+/// Access of an identity to a resource, with the current policy and with a candidate policy
+#[derive(Clone, Debug, Encode, Decode, CborLen, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyCheckResult {
+    #[n(1)] pub identifier: Identifier,
+    #[n(2)] pub current: bool,
+    #[n(3)] pub candidate: bool,
+}
+
+impl PolicyCheckResult {
+    /// Return true if the identity is only authorized by the candidate policy
+    pub fn gains_access(&self) -> bool {
+        !self.current && self.candidate
+    }
+
+    /// Return true if the identity is only authorized by the current policy
+    pub fn loses_access(&self) -> bool {
+        self.current && !self.candidate
+    }
+}
+
+/// Evaluate the current policy expression of a resource, if there is one, and a candidate
+/// expression for each subject, with the same attributes as an access control would use.
+///
+/// The environment contains the attributes which don't depend on the subject, for example
+/// the `resource.*` attributes.
+pub fn check_policy(
+    environment: &Env,
+    current: Option<&Expr>,
+    candidate: &Expr,
+    subjects: &[PolicyCheckSubject],
+) -> Vec<PolicyCheckResult> {
+    subjects
+        .iter()
+        .map(|subject| PolicyCheckResult {
+            identifier: subject.identifier.clone(),
+            current: current
+                .map(|current| is_authorized(environment, current, subject))
+                .unwrap_or(false),
+            candidate: is_authorized(environment, candidate, subject),
+        })
+        .collect()
+}
+
+fn is_authorized(environment: &Env, expression: &Expr, subject: &PolicyCheckSubject) -> bool {
+    let mut environment = environment.clone();
+    environment.put(
+        subject_identifier_attribute().to_string(),
+        str(subject.identifier.to_string()),
+    );
+    Abac::put_credential_attributes(
+        &mut environment,
+        &subject.identifier,
+        expression,
+        subject.attributes.as_ref(),
+    );
+    matches!(eval(expression, &environment), Ok(Expr::Bool(true)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::expr::{and, eq, ident};
+    use crate::subject_has_credential_policy_expression;
+    use core::str::FromStr;
+
+    #[test]
+    fn check_candidate_policy() {
+        let admin = subject(ADMIN, Some("admin"));
+        let developer = subject(
+            "I0000000000000000000000000000000000000000000000000000000000000002",
+            Some("developer"),
+        );
+        let anonymous = subject(
+            "I0000000000000000000000000000000000000000000000000000000000000003",
+            None,
+        );
+
+        let current = subject_has_credential_policy_expression();
+        let candidate = and([current.clone(), eq([ident("subject.role"), str("admin")])]);
+        let results = check_policy(
+            &Env::new(),
+            Some(&current),
+            &candidate,
+            &[admin.clone(), developer, anonymous],
+        );
+
+        assert_eq!(
+            results
+                .iter()
+                .map(|r| (r.current, r.candidate))
+                .collect::<Vec<_>>(),
+            vec![(true, true), (true, false), (false, false)]
+        );
+        assert!(results[1].loses_access());
+        assert!(!results[0].gains_access());
+
+        // without a current policy, no identity is authorized
+        let results = check_policy(&Env::new(), None, &candidate, &[admin]);
+        assert!(results[0].gains_access());
+    }
+
+    /// HELPERS
+    const ADMIN: &str = "I0000000000000000000000000000000000000000000000000000000000000001";
+
+    fn subject(identifier: &str, role: Option<&str>) -> PolicyCheckSubject {
+        PolicyCheckSubject {
+            identifier: Identifier::from_str(identifier).unwrap(),
+            attributes: role
+                .map(|role| BTreeMap::from([(b"role".to_vec(), role.as_bytes().to_vec())])),
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use crate::{Action, Env, Explanation, Expr, Resource, ResourceName, ResourceType};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+use ockam_identity::Identifier;
+use serde::Serialize;
+
+/// The record of an access control decision: an identity was allowed, or denied, to perform an
+/// action on a resource, according to a policy expression.
+#[derive(Clone, Debug, Encode, Decode, CborLen, Serialize, PartialEq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyDecision {
+    /// Time of the decision, in seconds since the Unix epoch
+    #[n(1)] pub decided_at: u64,
+    /// Identifier of the subject trying to access the resource
+    #[n(2)] pub identifier: Identifier,
+    #[n(3)] pub resource_name: ResourceName,
+    #[n(4)] pub resource_type: ResourceType,
+    #[n(5)] pub action: Action,
+    /// Evaluated policy expression
+    #[n(6)] pub expression: Expr,
+    /// Values of the attributes referenced by the expression at the time of the decision
+    #[n(7)] pub attributes: BTreeMap<String, Expr>,
+    #[n(8)] pub allowed: bool,
+    /// Sub-expression of the policy expression which decided the result
+    #[n(9)] pub decisive_expression: Expr,
+    /// Reason of a denial which was not decided by a policy expression,
+    /// for example when no policy is set for the resource and action
+    #[n(10)] pub reason: Option<String>,
+}
+
+impl PolicyDecision {
+    /// Create a decision from the explained evaluation of a policy expression
+    pub fn new(
+        decided_at: u64,
+        identifier: Identifier,
+        resource: &Resource,
+        action: Action,
+        environment: &Env,
+        explanation: &Explanation,
+    ) -> Self {
+        let expression = explanation.expression().clone();
+        let mut identifiers = Vec::new();
+        collect_identifiers(&expression, &mut identifiers);
+        let attributes = identifiers
+            .into_iter()
+            .filter_map(|name| {
+                environment
+                    .get(name)
+                    .ok()
+                    .map(|value| (name.into(), value.clone()))
+            })
+            .collect();
+
+        Self {
+            decided_at,
+            identifier,
+            resource_name: resource.resource_name.clone(),
+            resource_type: resource.resource_type.clone(),
+            action,
+            expression,
+            attributes,
+            allowed: explanation.is_true(),
+            decisive_expression: explanation.decisive().expression().clone(),
+            reason: None,
+        }
+    }
+
+    /// Create a denial which was not decided by a policy expression
+    pub fn denied(
+        decided_at: u64,
+        identifier: Identifier,
+        resource: &Resource,
+        action: Action,
+        reason: &str,
+    ) -> Self {
+        Self {
+            decided_at,
+            identifier,
+            resource_name: resource.resource_name.clone(),
+            resource_type: resource.resource_type.clone(),
+            action,
+            expression: Expr::Bool(false),
+            attributes: BTreeMap::new(),
+            allowed: false,
+            decisive_expression: Expr::Bool(false),
+            reason: Some(reason.into()),
+        }
+    }
+}
+
+/// Collect the names of the identifiers used in an expression
+fn collect_identifiers<'a>(expression: &'a Expr, identifiers: &mut Vec<&'a str>) {
+    match expression {
+        Expr::Ident(name) => identifiers.push(name),
+        Expr::List(xs) | Expr::Seq(xs) => {
+            // the first element of a list is an operator
+            let arguments = if matches!(expression, Expr::List(_)) {
+                xs.get(1..).unwrap_or_default()
+            } else {
+                xs
+            };
+            for x in arguments {
+                collect_identifiers(x, identifiers)
+            }
+        }
+        _ => (),
+    }
+}
+
+/// Limits of the decisions kept in a [`crate::PolicyDecisionsRepository`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PolicyDecisionsRetention {
+    /// Maximum number of decisions kept for a node
+    pub max_decisions: u64,
+    /// Maximum age of the decisions kept, in seconds
+    pub max_age: u64,
+}
+
+impl Default for PolicyDecisionsRetention {
+    fn default() -> Self {
+        Self {
+            max_decisions: 10_000,
+            max_age: 7 * 24 * 3600,
+        }
+    }
+}
+
+/// Criteria used to select the decisions returned by a [`crate::PolicyDecisionsRepository`]
+#[derive(Clone, Debug, Default, Encode, Decode, CborLen, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyDecisionsQuery {
+    #[n(1)] pub resource_name: Option<ResourceName>,
+    #[n(2)] pub action: Option<Action>,
+    /// Only return the decisions which denied access
+    #[n(3)] pub denied_only: bool,
+    /// Maximum number of decisions to return, the most recent ones first
+    #[n(4)] pub limit: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::explain;
+    use crate::expr::{and, eq, ident, int, str};
+    use core::str::FromStr;
+
+    #[test]
+    fn decision_attributes() {
+        let mut env = Env::new();
+        env.put("subject.role", str("dev"));
+        env.put("subject.level", int(3));
+        env.put("subject.unused", str("value"));
+        let expression = and([
+            eq([ident("subject.role"), str("admin")]),
+            eq([ident("subject.level"), int(3)]),
+        ]);
+        let identifier = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+        let resource = Resource::new("outlet", ResourceType::TcpOutlet);
+
+        let decision = PolicyDecision::new(
+            1,
+            identifier,
+            &resource,
+            Action::HandleMessage,
+            &env,
+            &explain(&expression, &env),
+        );
+
+        assert!(!decision.allowed);
+        assert_eq!(
+            decision.attributes,
+            BTreeMap::from([
+                ("subject.level".into(), int(3)),
+                ("subject.role".into(), str("dev"))
+            ])
+        );
+        assert_eq!(
+            decision.decisive_expression,
+            eq([ident("subject.role"), str("admin")])
+        );
+    }
+}
@@ -0,0 +1,216 @@
+use crate::{PolicyDecision, PolicyDecisionsRepository, PolicyDecisionsRetention};
+use ockam_core::compat::sync::Arc;
+use tracing::warn;
+
+#[cfg(feature = "std")]
+use core::time::Duration;
+#[cfg(feature = "std")]
+use std::sync::OnceLock;
+#[cfg(feature = "std")]
+use tokio::sync::mpsc::{channel, Receiver, Sender};
+#[cfg(feature = "std")]
+use tracing::debug;
+
+/// Maximum number of decisions waiting to be written to the repository.
+/// When the repository can't keep up, for example during a flood of denied messages,
+/// the new decisions are dropped instead of slowing down the access controls
+#[cfg(feature = "std")]
+const MAX_PENDING_DECISIONS: usize = 1000;
+
+/// Interval between two deletions of the decisions exceeding the retention limits
+#[cfg(feature = "std")]
+const RETENTION_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Records the decisions of the policy access controls of a node.
+///
+/// By default only the denied accesses are recorded, since recording all the decisions
+/// means writing to the database for every message checked by an access control.
+///
+/// The decisions are written to the repository by a background task, started with the first
+/// recorded decision, which also deletes the decisions exceeding the retention limits at
+/// regular intervals.
+#[derive(Clone)]
+pub struct PolicyDecisionLog {
+    repository: Arc<dyn PolicyDecisionsRepository>,
+    retention: PolicyDecisionsRetention,
+    record_allowed: bool,
+    #[cfg(feature = "std")]
+    writer: Arc<OnceLock<Sender<PolicyDecision>>>,
+}
+
+impl PolicyDecisionLog {
+    /// Create a log recording the denied accesses with the default retention limits
+    pub fn new(repository: Arc<dyn PolicyDecisionsRepository>) -> Self {
+        Self {
+            repository,
+            retention: PolicyDecisionsRetention::default(),
+            record_allowed: false,
+            #[cfg(feature = "std")]
+            writer: Arc::new(OnceLock::new()),
+        }
+    }
+
+    /// Set the limits of the decisions kept in the repository
+    pub fn with_retention(mut self, retention: PolicyDecisionsRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// Record the allowed accesses in addition to the denied ones
+    pub fn with_allowed_decisions(mut self, record_allowed: bool) -> Self {
+        self.record_allowed = record_allowed;
+        self
+    }
+
+    /// Return the repository storing the decisions
+    pub fn repository(&self) -> Arc<dyn PolicyDecisionsRepository> {
+        self.repository.clone()
+    }
+
+    /// Return true if a decision with the given result must be recorded
+    pub fn records(&self, allowed: bool) -> bool {
+        !allowed || self.record_allowed
+    }
+
+    /// Record a decision.
+    /// The decision is queued for the background writer, and dropped if too many decisions
+    /// are already waiting to be written.
+    #[cfg(feature = "std")]
+    pub async fn record(&self, decision: PolicyDecision) {
+        let writer = self.writer.get_or_init(|| self.start_writer());
+        if writer.try_send(decision).is_err() {
+            debug!("too many pending policy decisions, a decision is not recorded");
+        }
+    }
+
+    /// Record a decision and delete the decisions exceeding the retention limits.
+    /// A failure to record a decision does not change the decision, it is only logged.
+    #[cfg(not(feature = "std"))]
+    pub async fn record(&self, decision: PolicyDecision) {
+        store_decision(self.repository.as_ref(), &decision).await;
+        delete_expired_decisions(
+            self.repository.as_ref(),
+            &self.retention,
+            decision.decided_at,
+        )
+        .await;
+    }
+
+    /// Spawn the task writing the queued decisions to the repository
+    #[cfg(feature = "std")]
+    fn start_writer(&self) -> Sender<PolicyDecision> {
+        let (sender, receiver) = channel(MAX_PENDING_DECISIONS);
+        tokio::spawn(write_decisions(
+            self.repository.clone(),
+            self.retention.clone(),
+            receiver,
+        ));
+        sender
+    }
+}
+
+/// Write the decisions received from the access controls and regularly delete the decisions
+/// exceeding the retention limits.
+/// The task stops when all the copies of the decision log have been dropped
+#[cfg(feature = "std")]
+async fn write_decisions(
+    repository: Arc<dyn PolicyDecisionsRepository>,
+    retention: PolicyDecisionsRetention,
+    mut receiver: Receiver<PolicyDecision>,
+) {
+    let mut retention_interval = tokio::time::interval(RETENTION_INTERVAL);
+    loop {
+        tokio::select! {
+            decision = receiver.recv() => match decision {
+                Some(decision) => store_decision(repository.as_ref(), &decision).await,
+                None => break,
+            },
+            _ = retention_interval.tick() => {
+                let now = ockam_core::compat::time::now().unwrap_or_default();
+                delete_expired_decisions(repository.as_ref(), &retention, now).await
+            }
+        }
+    }
+}
+
+/// Store a decision, only logging a failure
+async fn store_decision(repository: &dyn PolicyDecisionsRepository, decision: &PolicyDecision) {
+    if let Err(e) = repository.store_decision(decision).await {
+        warn! {
+            resource = %decision.resource_name,
+            action   = %decision.action,
+            err      = %e,
+            "failed to record a policy decision"
+        }
+    }
+}
+
+/// Delete the decisions exceeding the retention limits, only logging a failure
+async fn delete_expired_decisions(
+    repository: &dyn PolicyDecisionsRepository,
+    retention: &PolicyDecisionsRetention,
+    now: u64,
+) {
+    if let Err(e) = repository.delete_expired_decisions(retention, now).await {
+        warn! {
+            err = %e,
+            "failed to delete the expired policy decisions"
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::expr::{eq, ident, str};
+    use crate::{
+        explain, Action, Env, PolicyDecisionSqlxDatabase, PolicyDecisionsQuery, Resource,
+        ResourceType,
+    };
+    use core::str::FromStr;
+    use ockam_core::Result;
+    use ockam_identity::Identifier;
+
+    #[tokio::test]
+    async fn test_decisions_are_written_in_the_background() -> Result<()> {
+        let repository = Arc::new(PolicyDecisionSqlxDatabase::create().await?);
+        let decision_log = PolicyDecisionLog::new(repository.clone());
+
+        let now = ockam_core::compat::time::now()?;
+        decision_log.record(decision(now - 1)).await;
+        decision_log.record(decision(now)).await;
+
+        let mut decisions = vec![];
+        for _ in 0..100 {
+            decisions = repository
+                .get_decisions(&PolicyDecisionsQuery::default())
+                .await?;
+            if decisions.len() == 2 {
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        assert_eq!(decisions, vec![decision(now), decision(now - 1)]);
+        Ok(())
+    }
+
+    /// HELPERS
+    fn decision(decided_at: u64) -> PolicyDecision {
+        let identifier = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+        let mut env = Env::new();
+        env.put("subject.role", str("guest"));
+        let expression = eq([ident("subject.role"), str("admin")]);
+
+        PolicyDecision::new(
+            decided_at,
+            identifier,
+            &Resource::new("outlet", ResourceType::TcpOutlet),
+            Action::HandleMessage,
+            &env,
+            &explain(&expression, &env),
+        )
+    }
+}
@@ -28,12 +28,10 @@ impl IncomingAccessControl for IncomingPolicyAccessControl {
             expr
         } else {
             // If no expression exists for this resource and action, access is denied:
-            debug! {
-                resource = %self.policy_access_control.resource,
-                action   = %self.policy_access_control.action,
-                "no policy found; access denied"
-            }
-            return Ok(false);
+            return self
+                .policy_access_control
+                .deny_without_policy(Abac::get_incoming_identifier(relay_msg).as_ref())
+                .await;
         };
 
         let identifier = match Abac::get_incoming_identifier(relay_msg) {
@@ -48,9 +46,13 @@ impl IncomingAccessControl for IncomingPolicyAccessControl {
             }
         };
 
+        let context = self
+            .policy_access_control
+            .request_context()
+            .with_incoming_message(relay_msg);
+
         self.policy_access_control
-            .abac
-            .is_identity_authorized(&identifier, &expression)
+            .is_identity_authorized_in_context(&identifier, &expression, context)
             .await
     }
 }
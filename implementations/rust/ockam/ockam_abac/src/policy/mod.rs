@@ -1,15 +1,23 @@
 mod access_control;
+mod check;
+mod decision;
+mod decision_log;
 mod incoming;
 mod outgoing;
 mod policies;
 mod resource_policy;
 mod resource_type_policy;
 mod resources;
+mod roles;
 pub(crate) mod storage;
 
 pub use access_control::*;
+pub use check::*;
+pub use decision::*;
+pub use decision_log::*;
 pub use incoming::*;
 pub use outgoing::*;
+pub use roles::*;
 
 pub use policies::Policies;
 pub use resource_policy::ResourcePolicy;
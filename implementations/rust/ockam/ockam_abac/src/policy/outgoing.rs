@@ -37,16 +37,16 @@ impl OutgoingAccessControl for OutgoingPolicyAccessControl {
             expr
         } else {
             // If no expression exists for this resource and action, access is denied:
-            debug! {
-                resource = %self.policy_access_control.resource,
-                action   = %self.policy_access_control.action,
-                "no policy found; access denied"
-            }
-            return Ok(false);
+            let identifier = Abac::get_outgoing_secure_channel(&self.ctx, relay_msg)?
+                .map(|(_, identifier)| identifier);
+            return self
+                .policy_access_control
+                .deny_without_policy(identifier.as_ref())
+                .await;
         };
 
-        let identifier = match Abac::get_outgoing_identifier(&self.ctx, relay_msg)? {
-            Some(identifier) => identifier,
+        let (address, identifier) = match Abac::get_outgoing_secure_channel(&self.ctx, relay_msg)? {
+            Some(secure_channel) => secure_channel,
             None => {
                 debug! {
                     policy = %expression,
@@ -57,9 +57,14 @@ impl OutgoingAccessControl for OutgoingPolicyAccessControl {
             }
         };
 
+        let context = self
+            .policy_access_control
+            .request_context()
+            .with_secure_channel(&address, &identifier)
+            .with_message_source(relay_msg);
+
         self.policy_access_control
-            .abac
-            .is_identity_authorized(&identifier, &expression)
+            .is_identity_authorized_in_context(&identifier, &expression, context)
             .await
     }
 }
@@ -1,8 +1,8 @@
 use crate::policy::ResourceTypePolicy;
 use crate::{
-    subject_has_credential_policy_expression, Action, Env, Expr, PolicyAccessControl, Resource,
-    ResourceName, ResourcePoliciesRepository, ResourcePolicy, ResourceType,
-    ResourceTypePoliciesRepository,
+    subject_has_credential_policy_expression, Action, Env, Expr, PolicyAccessControl,
+    PolicyDecisionLog, Resource, ResourceName, ResourcePoliciesRepository, ResourcePolicy,
+    ResourceType, ResourceTypePoliciesRepository,
 };
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
@@ -15,6 +15,7 @@ use tracing::{debug, instrument};
 pub struct Policies {
     resources_policies_repository: Arc<dyn ResourcePoliciesRepository>,
     resource_types_policies_repository: Arc<dyn ResourceTypePoliciesRepository>,
+    decision_log: Option<PolicyDecisionLog>,
 }
 
 impl Policies {
@@ -25,9 +26,20 @@ impl Policies {
         Self {
             resources_policies_repository,
             resource_types_policies_repository,
+            decision_log: None,
         }
     }
 
+    /// Record the decisions of the access controls created by these policies
+    pub fn with_decision_log(mut self, decision_log: PolicyDecisionLog) -> Self {
+        self.decision_log = Some(decision_log);
+        self
+    }
+
+    pub fn decision_log(&self) -> Option<&PolicyDecisionLog> {
+        self.decision_log.as_ref()
+    }
+
     #[instrument(skip_all, fields(resource = %resource, action = %action, env = %env, authority = ?authority))]
     pub fn make_policy_access_control(
         &self,
@@ -63,9 +75,23 @@ impl Policies {
 
 // Methods for resource policies
 impl Policies {
+    /// Store the default policies for the `HandleMessage` action, which are used
+    /// for the other actions of a resource type, unless they have their own policy.
+    ///
+    /// The policies which were already set for a resource type are kept.
+    /// The `node-api` resource type has no default policy: the node API requests are only
+    /// authorized by a policy once a `node-api` policy has been explicitly set.
     pub async fn store_default_resource_type_policies(&self) -> Result<()> {
         for resource_type in ResourceType::iter() {
-            for action in Action::iter() {
+            if resource_type == ResourceType::NodeApi {
+                continue;
+            }
+            let action = Action::HandleMessage;
+            if self
+                .get_policy_for_resource_type(&resource_type, &action)
+                .await?
+                .is_none()
+            {
                 self.store_default_policy_for_resource_type(&resource_type, &action)
                     .await?;
             }
@@ -113,26 +139,40 @@ impl Policies {
             .await
     }
 
+    /// Return the policy expression for a resource and an action.
+    ///
+    /// The policies of the resource name take precedence over the policies of the resource type.
+    /// For both of them, if there is no policy for the action, the policy of its fallback
+    /// action, `HandleMessage`, is used.
     pub async fn get_expression_for_resource(
         &self,
         resource: &Resource,
         action: &Action,
     ) -> Result<Option<Expr>> {
+        let actions: Vec<Action> = [Some(action.clone()), action.fallback()]
+            .into_iter()
+            .flatten()
+            .collect();
+
         // Try to get a policy for the resource name.
-        if let Some(policy) = self
-            .get_policy_for_resource_name(&resource.resource_name, action)
-            .await?
-        {
-            return Ok(Some(policy.expression));
+        for action in &actions {
+            if let Some(policy) = self
+                .get_policy_for_resource_name(&resource.resource_name, action)
+                .await?
+            {
+                return Ok(Some(policy.expression));
+            }
         }
 
         // If there is no policy for the resource name, try to get
         // the policy for the resource type associated to the resource name.
-        if let Some(policy) = self
-            .get_policy_for_resource_type(&resource.resource_type, action)
-            .await?
-        {
-            return Ok(Some(policy.expression));
+        for action in &actions {
+            if let Some(policy) = self
+                .get_policy_for_resource_type(&resource.resource_type, action)
+                .await?
+            {
+                return Ok(Some(policy.expression));
+            }
         }
 
         Ok(None)
@@ -192,3 +232,60 @@ impl Policies {
             .await
     }
 }
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::expr::{eq, ident, str};
+    use crate::{ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase};
+
+    #[tokio::test]
+    async fn test_expression_for_action() -> Result<()> {
+        let policies = Policies::new(
+            Arc::new(ResourcePolicySqlxDatabase::create().await?),
+            Arc::new(ResourceTypePolicySqlxDatabase::create().await?),
+        );
+        let resource = Resource::new("outlet", ResourceType::TcpOutlet);
+        let admin = eq([ident("subject.role"), str("admin")]);
+        let developer = eq([ident("subject.role"), str("developer")]);
+
+        // the resource type policies are used by all the actions
+        policies.store_default_resource_type_policies().await?;
+        let expression = policies
+            .get_expression_for_resource(&resource, &Action::Connect)
+            .await?;
+        assert_eq!(expression, Some(subject_has_credential_policy_expression()));
+
+        // a policy for an action takes precedence over the handle message policy
+        policies
+            .store_policy_for_resource_type(&ResourceType::TcpOutlet, &Action::Connect, &admin)
+            .await?;
+        let expression = policies
+            .get_expression_for_resource(&resource, &Action::Connect)
+            .await?;
+        assert_eq!(expression, Some(admin.clone()));
+
+        // a resource name policy takes precedence over the resource type policies
+        policies
+            .store_policy_for_resource_name(
+                &resource.resource_name,
+                &Action::HandleMessage,
+                &developer,
+            )
+            .await?;
+        let expression = policies
+            .get_expression_for_resource(&resource, &Action::Connect)
+            .await?;
+        assert_eq!(expression, Some(developer));
+
+        // the handle message action has no fallback
+        let expression = policies
+            .get_expression_for_resource(
+                &Resource::new("inlet", ResourceType::TcpInlet),
+                &Action::HandleMessage,
+            )
+            .await?;
+        assert_eq!(expression, Some(subject_has_credential_policy_expression()));
+        Ok(())
+    }
+}
@@ -19,6 +19,10 @@ impl Resources {
         Ok(())
     }
 
+    pub async fn get_resource(&self, resource_name: &ResourceName) -> Result<Option<Resource>> {
+        self.resources_repository.get_resource(resource_name).await
+    }
+
     pub async fn delete_resource(&self, resource_name: &ResourceName) -> Result<()> {
         self.resources_repository
             .delete_resource(resource_name)
@@ -0,0 +1,123 @@
+use crate::expr::{and, eq, ident, or, seq, str};
+use crate::{Expr, CONTEXT_KEY, SUBJECT_KEY};
+use ockam_core::compat::format;
+use ockam_core::compat::string::ToString;
+use ockam_core::compat::vec::vec;
+
+/// Name of the credential attribute containing the role of an identity on the API of a node
+pub const NODE_API_ROLE_ATTRIBUTE: &str = "ockam-node-role";
+
+/// Role allowed to call all the endpoints of the node API
+pub const NODE_API_ADMIN_ROLE: &str = "admin";
+
+/// Role allowed to read the node state, and to create and delete inlets, outlets and relays
+pub const NODE_API_OPERATOR_ROLE: &str = "operator";
+
+/// Role only allowed to read the node state
+pub const NODE_API_READER_ROLE: &str = "reader";
+
+/// Paths of the node API which can be modified by an operator
+const NODE_API_OPERATOR_PATHS: [&str; 3] = ["/node/inlet", "/node/outlet", "/node/relay"];
+
+/// Return the policy expression of the built-in node API roles, checking the role
+/// of the subject, given by its `ockam-node-role` attribute, against the method and
+/// path of the request. This expression is not set by default, it must be set as the
+/// `admin` policy of the `node-api` resource type to enforce the roles:
+///
+/// ```text
+/// (or (= subject.ockam-node-role "admin")
+///     (and (member? subject.ockam-node-role ["operator" "reader"])
+///          (= context.api.method "get"))
+///     (and (= subject.ockam-node-role "operator")
+///          (or (starts-with? context.api.path "/node/inlet")
+///              (starts-with? context.api.path "/node/outlet")
+///              (starts-with? context.api.path "/node/relay"))))
+/// ```
+pub fn node_api_roles_policy_expression() -> Expr {
+    let role = || ident(format!("{SUBJECT_KEY}.{NODE_API_ROLE_ATTRIBUTE}"));
+    let path = || ident(format!("{CONTEXT_KEY}.api.path"));
+
+    let is_admin = eq([role(), str(NODE_API_ADMIN_ROLE)]);
+    let can_read = and([
+        Expr::List(vec![
+            ident("member?"),
+            role(),
+            seq([str(NODE_API_OPERATOR_ROLE), str(NODE_API_READER_ROLE)]),
+        ]),
+        eq([ident(format!("{CONTEXT_KEY}.api.method")), str("get")]),
+    ]);
+    let can_operate = and([
+        eq([role(), str(NODE_API_OPERATOR_ROLE)]),
+        or(NODE_API_OPERATOR_PATHS
+            .iter()
+            .map(|p| Expr::List(vec![ident("starts-with?"), path(), str(p.to_string())]))),
+    ]);
+
+    or([is_admin, can_read, can_operate])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{eval, Env, RequestContext};
+    use core::str::FromStr;
+    use ockam_core::api::Request;
+
+    #[test]
+    fn node_api_roles() {
+        let cases = [
+            (
+                NODE_API_ADMIN_ROLE,
+                Request::post("/node/tcp/listener"),
+                true,
+            ),
+            (
+                NODE_API_OPERATOR_ROLE,
+                Request::get("/node/tcp/listener"),
+                true,
+            ),
+            (NODE_API_OPERATOR_ROLE, Request::post("/node/outlet"), true),
+            (
+                NODE_API_OPERATOR_ROLE,
+                Request::delete("/node/relay/r1"),
+                true,
+            ),
+            (
+                NODE_API_OPERATOR_ROLE,
+                Request::post("/policy/handle_message"),
+                false,
+            ),
+            (NODE_API_READER_ROLE, Request::get("/node/inlet"), true),
+            (NODE_API_READER_ROLE, Request::post("/node/inlet"), false),
+            ("developer", Request::get("/node/inlet"), false),
+        ];
+
+        let expression = node_api_roles_policy_expression();
+        for (role, request, expected) in cases {
+            let mut environment: Env = RequestContext::new()
+                .with_api_request(request.header())
+                .into();
+            environment.put(
+                format!("{SUBJECT_KEY}.{NODE_API_ROLE_ATTRIBUTE}"),
+                str(role),
+            );
+            assert_eq!(
+                eval(&expression, &environment).unwrap(),
+                Expr::Bool(expected),
+                "{role} {:?}",
+                request.header()
+            );
+        }
+    }
+
+    /// The roles expression is documented in the help of `ockam policy create`,
+    /// to be set as a policy of the `node-api` resource type
+    #[test]
+    fn node_api_roles_expression_can_be_set_as_a_policy() {
+        let documented = r#"(or (= subject.ockam-node-role "admin") (and (member? subject.ockam-node-role ["operator" "reader"]) (= context.api.method "get")) (and (= subject.ockam-node-role "operator") (or (starts-with? context.api.path "/node/inlet") (starts-with? context.api.path "/node/outlet") (starts-with? context.api.path "/node/relay"))))"#;
+        assert_eq!(
+            Expr::from_str(documented).unwrap(),
+            node_api_roles_policy_expression()
+        );
+    }
+}
@@ -0,0 +1,54 @@
+use crate::{PolicyDecision, PolicyDecisionsQuery, PolicyDecisionsRetention};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+#[cfg(feature = "std")]
+use ockam_node::database::AutoRetry;
+#[cfg(feature = "std")]
+use ockam_node::retry;
+
+/// This repository stores the decisions taken by policy access controls, so that denied
+/// accesses can be investigated after the fact.
+#[async_trait]
+pub trait PolicyDecisionsRepository: Send + Sync + 'static {
+    /// Store a decision
+    async fn store_decision(&self, decision: &PolicyDecision) -> Result<()>;
+
+    /// Delete the decisions exceeding the retention limits at a given time, in seconds
+    async fn delete_expired_decisions(
+        &self,
+        retention: &PolicyDecisionsRetention,
+        now: u64,
+    ) -> Result<()>;
+
+    /// Return the decisions matching a query, the most recent ones first
+    async fn get_decisions(&self, query: &PolicyDecisionsQuery) -> Result<Vec<PolicyDecision>>;
+
+    /// Delete all the stored decisions
+    async fn delete_decisions(&self) -> Result<()>;
+}
+
+#[cfg(feature = "std")]
+#[async_trait]
+impl<T: PolicyDecisionsRepository> PolicyDecisionsRepository for AutoRetry<T> {
+    async fn store_decision(&self, decision: &PolicyDecision) -> Result<()> {
+        retry!(self.wrapped.store_decision(decision))
+    }
+
+    async fn delete_expired_decisions(
+        &self,
+        retention: &PolicyDecisionsRetention,
+        now: u64,
+    ) -> Result<()> {
+        retry!(self.wrapped.delete_expired_decisions(retention, now))
+    }
+
+    async fn get_decisions(&self, query: &PolicyDecisionsQuery) -> Result<Vec<PolicyDecision>> {
+        retry!(self.wrapped.get_decisions(query))
+    }
+
+    async fn delete_decisions(&self) -> Result<()> {
+        retry!(self.wrapped.delete_decisions())
+    }
+}
@@ -0,0 +1,264 @@
+use sqlx::*;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::{
+    PolicyDecision, PolicyDecisionsQuery, PolicyDecisionsRepository, PolicyDecisionsRetention,
+};
+use ockam_core::async_trait;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use ockam_node::database::AutoRetry;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+#[derive(Clone)]
+pub struct PolicyDecisionSqlxDatabase {
+    database: SqlxDatabase,
+    node_name: String,
+}
+
+impl PolicyDecisionSqlxDatabase {
+    /// Create a new database for policy decisions
+    pub fn new(database: SqlxDatabase, node_name: &str) -> Self {
+        debug!("create a repository for policy decisions");
+        Self {
+            database,
+            node_name: node_name.to_string(),
+        }
+    }
+
+    /// Create a repository
+    pub fn make_repository(
+        database: SqlxDatabase,
+        node_name: &str,
+    ) -> Arc<dyn PolicyDecisionsRepository> {
+        if database.needs_retry() {
+            Arc::new(AutoRetry::new(Self::new(database, node_name)))
+        } else {
+            Arc::new(Self::new(database, node_name))
+        }
+    }
+
+    /// Create a new in-memory database for policy decisions
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("policy_decisions").await?,
+            "default",
+        ))
+    }
+}
+
+#[async_trait]
+impl PolicyDecisionsRepository for PolicyDecisionSqlxDatabase {
+    async fn store_decision(&self, decision: &PolicyDecision) -> Result<()> {
+        let query = query(
+            r#"INSERT INTO policy_decision (decided_at, resource_name, action, allowed, decision, node_name)
+            VALUES ($1, $2, $3, $4, $5, $6)"#,
+        )
+        .bind(decision.decided_at as i64)
+        .bind(&decision.resource_name)
+        .bind(&decision.action)
+        .bind(decision.allowed)
+        .bind(ockam_core::cbor_encode_preallocate(decision)?)
+        .bind(&self.node_name);
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn delete_expired_decisions(
+        &self,
+        retention: &PolicyDecisionsRetention,
+        now: u64,
+    ) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+
+        // remove the decisions which are too old
+        let query = sqlx::query(
+            r#"DELETE FROM policy_decision
+            WHERE node_name = $1 AND decided_at < $2"#,
+        )
+        .bind(&self.node_name)
+        .bind(now.saturating_sub(retention.max_age) as i64);
+        query.execute(&mut *transaction).await.void()?;
+
+        // only keep the most recent decisions
+        let query = if retention.max_decisions == 0 {
+            sqlx::query(r#"DELETE FROM policy_decision WHERE node_name = $1"#).bind(&self.node_name)
+        } else {
+            sqlx::query(
+                r#"DELETE FROM policy_decision
+                WHERE node_name = $1 AND decided_at < (
+                    SELECT decided_at FROM policy_decision
+                    WHERE node_name = $1
+                    ORDER BY decided_at DESC
+                    LIMIT 1 OFFSET $2)"#,
+            )
+            .bind(&self.node_name)
+            .bind((retention.max_decisions - 1) as i64)
+        };
+        query.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_decisions(&self, query: &PolicyDecisionsQuery) -> Result<Vec<PolicyDecision>> {
+        let mut sql = String::from(
+            r#"SELECT decision FROM policy_decision
+            WHERE node_name = $1"#,
+        );
+        let mut parameters = 1;
+        if query.resource_name.is_some() {
+            parameters += 1;
+            sql.push_str(&format!(" AND resource_name = ${parameters}"));
+        }
+        if query.action.is_some() {
+            parameters += 1;
+            sql.push_str(&format!(" AND action = ${parameters}"));
+        }
+        if query.denied_only {
+            parameters += 1;
+            sql.push_str(&format!(" AND allowed = ${parameters}"));
+        }
+        sql.push_str(" ORDER BY decided_at DESC");
+        if query.limit > 0 {
+            sql.push_str(&format!(" LIMIT ${}", parameters + 1));
+        }
+
+        let mut sql_query = query_as(&sql).bind(&self.node_name);
+        if let Some(resource_name) = &query.resource_name {
+            sql_query = sql_query.bind(resource_name);
+        }
+        if let Some(action) = &query.action {
+            sql_query = sql_query.bind(action);
+        }
+        if query.denied_only {
+            sql_query = sql_query.bind(false);
+        }
+        if query.limit > 0 {
+            sql_query = sql_query.bind(query.limit as i64);
+        }
+
+        let rows: Vec<PolicyDecisionRow> = sql_query
+            .fetch_all(&*self.database.pool)
+            .await
+            .into_core()?;
+        rows.into_iter().map(|r| r.decision()).collect()
+    }
+
+    async fn delete_decisions(&self) -> Result<()> {
+        let query =
+            query(r#"DELETE FROM policy_decision WHERE node_name = $1"#).bind(&self.node_name);
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+/// Low-level representation of a row in the policy_decision table
+#[derive(FromRow)]
+struct PolicyDecisionRow {
+    decision: Vec<u8>,
+}
+
+impl PolicyDecisionRow {
+    fn decision(&self) -> Result<PolicyDecision> {
+        Ok(minicbor::decode(&self.decision)?)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::expr::*;
+    use crate::{explain, Action, Env, Resource, ResourceName, ResourceType};
+    use core::str::FromStr;
+    use ockam_identity::Identifier;
+
+    #[tokio::test]
+    async fn test_repository() -> Result<()> {
+        let repo = PolicyDecisionSqlxDatabase::create().await?;
+        let retention = PolicyDecisionsRetention {
+            max_decisions: 3,
+            max_age: 100,
+        };
+
+        // decisions can be stored and retrieved, the most recent ones first
+        repo.store_decision(&decision(1000, "outlet1", true))
+            .await?;
+        repo.store_decision(&decision(1001, "outlet1", false))
+            .await?;
+        repo.store_decision(&decision(1002, "outlet2", false))
+            .await?;
+        let decisions = repo.get_decisions(&PolicyDecisionsQuery::default()).await?;
+        assert_eq!(
+            decisions,
+            vec![
+                decision(1002, "outlet2", false),
+                decision(1001, "outlet1", false),
+                decision(1000, "outlet1", true)
+            ]
+        );
+
+        // decisions can be filtered
+        let query = PolicyDecisionsQuery {
+            resource_name: Some(ResourceName::from("outlet1")),
+            action: Some(Action::HandleMessage),
+            denied_only: true,
+            limit: 0,
+        };
+        assert_eq!(
+            repo.get_decisions(&query).await?,
+            vec![decision(1001, "outlet1", false)]
+        );
+        let query = PolicyDecisionsQuery {
+            limit: 1,
+            ..Default::default()
+        };
+        assert_eq!(
+            repo.get_decisions(&query).await?,
+            vec![decision(1002, "outlet2", false)]
+        );
+
+        // only the most recent decisions are kept
+        repo.store_decision(&decision(1003, "outlet2", true))
+            .await?;
+        repo.delete_expired_decisions(&retention, 1003).await?;
+        let decisions = repo.get_decisions(&PolicyDecisionsQuery::default()).await?;
+        assert_eq!(decisions.len(), 3);
+        assert_eq!(decisions.last().unwrap().decided_at, 1001);
+
+        // old decisions are deleted
+        repo.store_decision(&decision(1102, "outlet2", true))
+            .await?;
+        repo.delete_expired_decisions(&retention, 1102).await?;
+        let decisions = repo.get_decisions(&PolicyDecisionsQuery::default()).await?;
+        assert_eq!(decisions.len(), 3);
+        assert_eq!(decisions.last().unwrap().decided_at, 1002);
+
+        // all the decisions can be deleted
+        repo.delete_decisions().await?;
+        assert!(repo
+            .get_decisions(&PolicyDecisionsQuery::default())
+            .await?
+            .is_empty());
+
+        Ok(())
+    }
+
+    /// HELPERS
+    fn decision(decided_at: u64, resource_name: &str, allowed: bool) -> PolicyDecision {
+        let identifier = Identifier::from_str(
+            "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+        )
+        .unwrap();
+        let mut env = Env::new();
+        env.put("subject.allowed", str(allowed.to_string()));
+        let expression = eq([ident("subject.allowed"), str("true")]);
+
+        PolicyDecision::new(
+            decided_at,
+            identifier,
+            &Resource::new(resource_name, ResourceType::TcpOutlet),
+            Action::HandleMessage,
+            &env,
+            &explain(&expression, &env),
+        )
+    }
+}
@@ -59,6 +59,9 @@ pub enum ResourceType {
     #[n(7)]
     #[strum(serialize = "lessor")]
     InfluxDBLessor,
+    #[n(8)]
+    #[strum(serialize = "node-api")]
+    NodeApi,
 }
 
 impl ResourceType {
@@ -3,9 +3,10 @@ use minicbor::decode::{self, Decoder};
 use minicbor::encode::{self, Encoder, Write};
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::compat::string::{String, ToString};
+use ockam_core::compat::vec::Vec;
 use serde::{Serialize, Serializer};
 use str_buf::StrBuf;
-use strum::{AsRefStr, Display, EnumIter, EnumString};
+use strum::{AsRefStr, Display, EnumIter, EnumString, IntoEnumIterator};
 
 macro_rules! define {
     ($t:ident) => {
@@ -109,9 +110,46 @@ define!(ResourceName);
 )]
 #[cbor(index_only)]
 pub enum Action {
+    /// Any interaction with a resource. The policies for this action apply to all the other
+    /// actions which don't have their own policy
     #[n(1)]
     #[strum(serialize = "handle_message")]
     HandleMessage,
+    /// Establish a connection with a resource, for example open a TCP connection
+    /// through an outlet or create a relay
+    #[n(2)]
+    #[strum(serialize = "connect")]
+    Connect,
+    /// Send messages to a resource, for example the data of a TCP connection
+    #[n(3)]
+    #[strum(serialize = "send")]
+    Send,
+    /// Receive messages from a resource
+    #[n(4)]
+    #[strum(serialize = "receive")]
+    Receive,
+    /// Manage a resource, for example call the API of a node
+    #[n(5)]
+    #[strum(serialize = "admin")]
+    Admin,
+}
+
+impl Action {
+    /// Return the action whose policies are used when there is no policy for this action
+    pub fn fallback(&self) -> Option<Action> {
+        match self {
+            Action::HandleMessage => None,
+            _ => Some(Action::HandleMessage),
+        }
+    }
+
+    /// Return a string with all valid values joined by a commas
+    pub fn join_enum_values_as_string() -> String {
+        Self::iter()
+            .map(|v| v.to_string())
+            .collect::<Vec<String>>()
+            .join(", ")
+    }
 }
 
 impl Serialize for Action {
@@ -0,0 +1,404 @@
+use ockam::identity::models::{PurposeKeyAttestation, PurposePublicKey};
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, Identities};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_vault::VerifyingPublicKey;
+use tokio::sync::Mutex;
+
+use crate::authenticator::audit::{
+    AuditAction, AuditEntry, AuditEntryContent, AuditHash, AuditSignature, GENESIS_AUDIT_HASH,
+};
+use crate::authenticator::{AuthorityAuditLogRepository, AuthorityMemberChange};
+use crate::ApiError;
+
+/// Number of attempts to append an entry when another process appended an entry at the same index
+const APPEND_ATTEMPTS: usize = 3;
+
+/// This log records the operations changing the members of an authority.
+///
+/// Entries are chained with their hashes and each hash is signed with a credentials purpose key
+/// of the authority, so that any modification of the log can be detected with an [`AuditLogVerifier`].
+pub struct AuthorityAuditLog {
+    authority: Identifier,
+    repository: Arc<dyn AuthorityAuditLogRepository>,
+    identities: Arc<Identities>,
+    append_lock: Mutex<()>,
+}
+
+impl AuthorityAuditLog {
+    pub fn new(
+        authority: &Identifier,
+        repository: Arc<dyn AuthorityAuditLogRepository>,
+        identities: Arc<Identities>,
+    ) -> Self {
+        Self {
+            authority: authority.clone(),
+            repository,
+            identities,
+            append_lock: Mutex::new(()),
+        }
+    }
+
+    /// Append a signed entry at the end of the log
+    #[instrument(skip_all, fields(actor = %actor, action = %action))]
+    pub async fn record(
+        &self,
+        actor: &Identifier,
+        action: AuditAction,
+        target: Option<&Identifier>,
+        attributes: BTreeMap<String, String>,
+    ) -> Result<AuditEntry> {
+        let entry = self.append(actor, action, target, attributes, None).await?;
+        // an entry without a change of members is always appended
+        entry.ok_or_else(|| ApiError::core("the audit log entry was not appended"))
+    }
+
+    /// Apply a change of members and append the signed entry recording it, in one transaction,
+    /// so that no change can be made without being recorded.
+    /// Return None if the change did not modify any member, in which case nothing is recorded
+    #[instrument(skip_all, fields(actor = %actor, action = %action))]
+    pub async fn record_change(
+        &self,
+        actor: &Identifier,
+        action: AuditAction,
+        target: Option<&Identifier>,
+        attributes: BTreeMap<String, String>,
+        change: AuthorityMemberChange,
+    ) -> Result<Option<AuditEntry>> {
+        self.append(actor, action, target, attributes, Some(change))
+            .await
+    }
+
+    async fn append(
+        &self,
+        actor: &Identifier,
+        action: AuditAction,
+        target: Option<&Identifier>,
+        attributes: BTreeMap<String, String>,
+        change: Option<AuthorityMemberChange>,
+    ) -> Result<Option<AuditEntry>> {
+        let _guard = self.append_lock.lock().await;
+        let mut attempt = 1;
+        loop {
+            let (index, previous_hash) =
+                match self.repository.get_last_entry(&self.authority).await? {
+                    Some(last) => (last.index + 1, last.hash),
+                    None => (0, GENESIS_AUDIT_HASH),
+                };
+            let entry = self
+                .create_entry(
+                    index,
+                    previous_hash,
+                    actor,
+                    action,
+                    target,
+                    attributes.clone(),
+                )
+                .await?;
+            let appended = match &change {
+                Some(change) => {
+                    self.repository
+                        .append_entry_with_change(&self.authority, entry.clone(), change.clone())
+                        .await
+                }
+                None => self
+                    .repository
+                    .append_entry(&self.authority, entry.clone())
+                    .await
+                    .map(|_| true),
+            };
+            match appended {
+                Ok(true) => {
+                    debug!("recorded the audit log entry {}", index);
+                    return Ok(Some(entry));
+                }
+                Ok(false) => return Ok(None),
+                Err(e) if attempt < APPEND_ATTEMPTS => {
+                    warn!("could not append the audit log entry {}: {}", index, e);
+                    attempt += 1;
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
+    /// Return at most `limit` entries, starting at the index `from`
+    pub async fn get_entries(&self, from: u64, limit: u32) -> Result<Vec<AuditEntry>> {
+        self.repository
+            .get_entries(&self.authority, from, limit)
+            .await
+    }
+
+    async fn create_entry(
+        &self,
+        index: u64,
+        previous_hash: AuditHash,
+        actor: &Identifier,
+        action: AuditAction,
+        target: Option<&Identifier>,
+        attributes: BTreeMap<String, String>,
+    ) -> Result<AuditEntry> {
+        let recorded_at = now()?;
+        let content = ockam_core::cbor_encode_preallocate(AuditEntryContent {
+            index,
+            recorded_at,
+            actor,
+            action,
+            target,
+            attributes: &attributes,
+            previous_hash,
+        })?;
+
+        let vault = self.identities.vault();
+        let hash = vault.verifying_vault.sha256(&content).await?.0;
+        let purpose_key = self
+            .identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .get_or_create_credential_purpose_key(&self.authority)
+            .await?;
+        let signature = vault
+            .credential_vault
+            .sign(purpose_key.key(), &hash)
+            .await?;
+
+        Ok(AuditEntry {
+            index,
+            recorded_at,
+            actor: actor.clone(),
+            action,
+            target: target.cloned(),
+            attributes,
+            previous_hash,
+            hash,
+            signature: AuditSignature {
+                signature: signature.into(),
+                purpose_key_attestation: purpose_key.attestation().clone(),
+            },
+        })
+    }
+}
+
+/// Reasons for rejecting an entry of an audit log
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum AuditEntryError {
+    #[error("entry {expected} is missing, the next entry is {actual}")]
+    MissingEntry { expected: u64, actual: u64 },
+    #[error("entry {0} does not refer to the hash of the previous entry")]
+    BrokenChain(u64),
+    #[error("the content of entry {0} does not match its hash")]
+    InvalidHash(u64),
+    #[error("entry {0} is not signed by the authority")]
+    InvalidSignature(u64),
+}
+
+/// Verify the entries of an audit log, in order, starting with the first entry.
+///
+/// The entries removed at the end of a log can't be detected from the log alone. They can be
+/// detected by comparing the last hash of the log with a hash verified previously.
+pub struct AuditLogVerifier {
+    authority: Identifier,
+    identities: Arc<Identities>,
+    next_index: u64,
+    previous_hash: AuditHash,
+    verified_keys: Vec<(PurposeKeyAttestation, VerifyingPublicKey)>,
+}
+
+impl AuditLogVerifier {
+    pub fn new(identities: Arc<Identities>, authority: &Identifier) -> Self {
+        Self {
+            authority: authority.clone(),
+            identities,
+            next_index: 0,
+            previous_hash: GENESIS_AUDIT_HASH,
+            verified_keys: vec![],
+        }
+    }
+
+    /// Return the number of entries verified so far
+    pub fn verified_entries(&self) -> u64 {
+        self.next_index
+    }
+
+    /// Return the hash of the last verified entry
+    pub fn last_hash(&self) -> AuditHash {
+        self.previous_hash
+    }
+
+    /// Verify the next entry of the log.
+    /// An error is only returned if the verification could not be performed
+    pub async fn verify(&mut self, entry: &AuditEntry) -> Result<Result<(), AuditEntryError>> {
+        if entry.index != self.next_index {
+            return Ok(Err(AuditEntryError::MissingEntry {
+                expected: self.next_index,
+                actual: entry.index,
+            }));
+        }
+        if entry.previous_hash != self.previous_hash {
+            return Ok(Err(AuditEntryError::BrokenChain(entry.index)));
+        }
+
+        let verifying_vault = self.identities.vault().verifying_vault;
+        let hash = verifying_vault.sha256(&entry.hashed_content()?).await?.0;
+        if hash != entry.hash {
+            return Ok(Err(AuditEntryError::InvalidHash(entry.index)));
+        }
+
+        let Some(public_key) = self
+            .verifying_key(&entry.signature.purpose_key_attestation)
+            .await
+        else {
+            return Ok(Err(AuditEntryError::InvalidSignature(entry.index)));
+        };
+        if !verifying_vault
+            .verify_signature(
+                &public_key,
+                &entry.hash,
+                &entry.signature.signature.clone().into(),
+            )
+            .await?
+        {
+            return Ok(Err(AuditEntryError::InvalidSignature(entry.index)));
+        }
+
+        self.next_index += 1;
+        self.previous_hash = entry.hash;
+        Ok(Ok(()))
+    }
+
+    /// Return the public key of a purpose key attested by the authority
+    async fn verifying_key(
+        &mut self,
+        attestation: &PurposeKeyAttestation,
+    ) -> Option<VerifyingPublicKey> {
+        if let Some((_, key)) = self.verified_keys.iter().find(|(a, _)| a == attestation) {
+            return Some(key.clone());
+        }
+        let data = self
+            .identities
+            .purpose_keys()
+            .purpose_keys_verification()
+            .verify_purpose_key_attestation(Some(&self.authority), attestation)
+            .await
+            .ok()?;
+        let PurposePublicKey::CredentialSigning(public_key) = data.public_key else {
+            return None;
+        };
+        let public_key: VerifyingPublicKey = public_key.into();
+        self.verified_keys
+            .push((attestation.clone(), public_key.clone()));
+        Some(public_key)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticator::AuthorityAuditLogSqlxDatabase;
+    use ockam::identity::identities;
+
+    #[tokio::test]
+    async fn test_verify_audit_log() -> Result<()> {
+        let identities = identities().await?;
+        let authority = identities.identities_creation().create_identity().await?;
+        let member = identities.identities_creation().create_identity().await?;
+        let repository = Arc::new(AuthorityAuditLogSqlxDatabase::create().await?);
+        let audit_log = AuthorityAuditLog::new(&authority, repository, identities.clone());
+
+        audit_log
+            .record(
+                &authority,
+                AuditAction::AddMember,
+                Some(&member),
+                BTreeMap::from([("key".to_string(), "value".to_string())]),
+            )
+            .await?;
+        audit_log
+            .record(
+                &authority,
+                AuditAction::SuspendMember,
+                Some(&member),
+                BTreeMap::new(),
+            )
+            .await?;
+        audit_log
+            .record(
+                &authority,
+                AuditAction::DeleteMember,
+                Some(&member),
+                BTreeMap::new(),
+            )
+            .await?;
+        let entries = audit_log.get_entries(0, 10).await?;
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].previous_hash, GENESIS_AUDIT_HASH);
+        assert_eq!(entries[1].previous_hash, entries[0].hash);
+
+        // the complete log is valid
+        let mut verifier = AuditLogVerifier::new(identities.clone(), &authority);
+        for entry in entries.iter() {
+            assert_eq!(verifier.verify(entry).await?, Ok(()));
+        }
+        assert_eq!(verifier.verified_entries(), 3);
+        assert_eq!(verifier.last_hash(), entries[2].hash);
+
+        // a removed entry is detected
+        let mut verifier = AuditLogVerifier::new(identities.clone(), &authority);
+        assert_eq!(verifier.verify(&entries[0]).await?, Ok(()));
+        assert_eq!(
+            verifier.verify(&entries[2]).await?,
+            Err(AuditEntryError::MissingEntry {
+                expected: 1,
+                actual: 2
+            })
+        );
+
+        // a modified entry is detected
+        let mut modified = entries[1].clone();
+        modified.action = AuditAction::ResumeMember;
+        let mut verifier = AuditLogVerifier::new(identities.clone(), &authority);
+        assert_eq!(verifier.verify(&entries[0]).await?, Ok(()));
+        assert_eq!(
+            verifier.verify(&modified).await?,
+            Err(AuditEntryError::InvalidHash(1))
+        );
+
+        // a modified entry with a recomputed hash is detected
+        modified.hash = identities
+            .vault()
+            .verifying_vault
+            .sha256(&modified.hashed_content()?)
+            .await?
+            .0;
+        let mut verifier = AuditLogVerifier::new(identities.clone(), &authority);
+        assert_eq!(verifier.verify(&entries[0]).await?, Ok(()));
+        assert_eq!(
+            verifier.verify(&modified).await?,
+            Err(AuditEntryError::InvalidSignature(1))
+        );
+
+        // an entry signed by another identity is detected
+        let other = AuthorityAuditLog::new(
+            &member,
+            Arc::new(AuthorityAuditLogSqlxDatabase::create().await?),
+            identities.clone(),
+        );
+        let forged = other
+            .record(
+                &authority,
+                AuditAction::AddMember,
+                Some(&member),
+                BTreeMap::new(),
+            )
+            .await?;
+        let mut verifier = AuditLogVerifier::new(identities.clone(), &authority);
+        assert_eq!(
+            verifier.verify(&forged).await?,
+            Err(AuditEntryError::InvalidSignature(0))
+        );
+
+        Ok(())
+    }
+}
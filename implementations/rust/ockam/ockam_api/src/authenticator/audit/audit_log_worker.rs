@@ -0,0 +1,123 @@
+use either::Either;
+use minicbor::Decoder;
+use tracing::trace;
+
+use ockam::identity::{Identifier, IdentitiesAttributes};
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Result, Routed, SecureChannelLocalInfo, Worker};
+use ockam_node::Context;
+
+use crate::authenticator::audit::{AuditEntry, AuthorityAuditLog, ListAuditEntries};
+use crate::authenticator::common::EnrollerAccessControlChecks;
+use crate::authenticator::direct::AccountAuthorityInfo;
+use crate::authenticator::AuthorityMembersRepository;
+
+/// Maximum number of entries returned for one request
+pub const MAX_AUDIT_ENTRIES_PER_PAGE: u32 = 1000;
+
+pub struct AuditLogError(pub String);
+
+/// This worker lets the admins of a project page through the audit log of the authority
+pub struct AuditLogWorker {
+    authority: Identifier,
+    audit_log: Arc<AuthorityAuditLog>,
+    members: Arc<dyn AuthorityMembersRepository>,
+    identities_attributes: Arc<IdentitiesAttributes>,
+    account_authority: Option<AccountAuthorityInfo>,
+}
+
+impl AuditLogWorker {
+    pub fn new(
+        authority: &Identifier,
+        audit_log: Arc<AuthorityAuditLog>,
+        members: Arc<dyn AuthorityMembersRepository>,
+        identities_attributes: Arc<IdentitiesAttributes>,
+        account_authority: Option<AccountAuthorityInfo>,
+    ) -> Self {
+        Self {
+            authority: authority.clone(),
+            audit_log,
+            members,
+            identities_attributes,
+            account_authority,
+        }
+    }
+
+    #[instrument(skip_all, fields(from = %from))]
+    async fn list_entries(
+        &self,
+        from: &Identifier,
+        request: ListAuditEntries,
+    ) -> Result<Either<Vec<AuditEntry>, AuditLogError>> {
+        let check = EnrollerAccessControlChecks::check_identifier(
+            &self.authority,
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            from,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check.is_admin {
+            warn!("Not admin {} is trying to read the audit log", from);
+            return Ok(Either::Right(AuditLogError(
+                "Not admin is trying to read the audit log".to_string(),
+            )));
+        }
+
+        let entries = self
+            .audit_log
+            .get_entries(
+                request.from(),
+                request.limit().min(MAX_AUDIT_ENTRIES_PER_PAGE),
+            )
+            .await?;
+        Ok(Either::Left(entries))
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for AuditLogWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match SecureChannelLocalInfo::find_info(m.local_message()) {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route().clone(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = Identifier::from(secure_channel_info.their_identifier());
+        let return_route = m.return_route().clone();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "authority_audit_log",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let res = match (req.method(), req.path()) {
+            (Some(Method::Get), "/") | (Some(Method::Get), "/entries") => {
+                let request: ListAuditEntries = dec.decode()?;
+                match self.list_entries(&from, request).await? {
+                    Either::Left(entries) => {
+                        Response::ok().with_headers(&req).body(entries).to_vec()?
+                    }
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+        c.send(return_route, res).await
+    }
+}
@@ -0,0 +1,86 @@
+use miette::IntoDiagnostic;
+
+use ockam_core::api::Request;
+use ockam_core::async_trait;
+use ockam_node::Context;
+
+use crate::authenticator::audit::{
+    AuditEntry, AuditEntryError, AuditHash, AuditLogVerifier, ListAuditEntries,
+    MAX_AUDIT_ENTRIES_PER_PAGE,
+};
+use crate::nodes::service::default_address::DefaultAddress;
+use crate::orchestrator::{AuthorityNodeClient, HasSecureClient};
+
+/// Result of the verification of a complete audit log
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AuditLogVerification {
+    /// Number of entries which were successfully verified
+    pub verified_entries: u64,
+    /// Hash of the last verified entry
+    pub last_hash: AuditHash,
+    /// First invalid entry, if any
+    pub error: Option<AuditEntryError>,
+}
+
+#[async_trait]
+pub trait AuditLogReader {
+    /// Return at most `limit` entries of the audit log, starting at the index `from`
+    async fn list_audit_entries(
+        &self,
+        ctx: &Context,
+        from: u64,
+        limit: u32,
+    ) -> miette::Result<Vec<AuditEntry>>;
+
+    /// Retrieve all the entries of the audit log and verify that they are
+    /// correctly chained and signed by the authority
+    async fn verify_audit_log(&self, ctx: &Context) -> miette::Result<AuditLogVerification>;
+}
+
+#[async_trait]
+impl AuditLogReader for AuthorityNodeClient {
+    async fn list_audit_entries(
+        &self,
+        ctx: &Context,
+        from: u64,
+        limit: u32,
+    ) -> miette::Result<Vec<AuditEntry>> {
+        let req = Request::get("/").body(ListAuditEntries::new(from, limit));
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::AUTHORITY_AUDIT_LOG, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn verify_audit_log(&self, ctx: &Context) -> miette::Result<AuditLogVerification> {
+        let client = self.get_secure_client();
+        let mut verifier = AuditLogVerifier::new(
+            client.secure_channels().identities(),
+            client.server_identifier(),
+        );
+        loop {
+            let entries = self
+                .list_audit_entries(ctx, verifier.verified_entries(), MAX_AUDIT_ENTRIES_PER_PAGE)
+                .await?;
+            if entries.is_empty() {
+                break;
+            }
+            for entry in entries.iter() {
+                if let Err(error) = verifier.verify(entry).await.into_diagnostic()? {
+                    return Ok(AuditLogVerification {
+                        verified_entries: verifier.verified_entries(),
+                        last_hash: verifier.last_hash(),
+                        error: Some(error),
+                    });
+                }
+            }
+        }
+        Ok(AuditLogVerification {
+            verified_entries: verifier.verified_entries(),
+            last_hash: verifier.last_hash(),
+            error: None,
+        })
+    }
+}
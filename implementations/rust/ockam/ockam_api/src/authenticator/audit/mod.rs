@@ -0,0 +1,9 @@
+mod audit_log;
+mod audit_log_worker;
+mod client;
+mod types;
+
+pub use audit_log::*;
+pub use audit_log_worker::*;
+pub use client::*;
+pub use types::*;
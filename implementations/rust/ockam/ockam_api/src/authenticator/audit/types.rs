@@ -0,0 +1,166 @@
+use core::fmt::{Display, Formatter};
+use core::str::FromStr;
+use minicbor::{CborLen, Decode, Encode};
+use ockam::identity::models::{CredentialSignature, PurposeKeyAttestation};
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::Result;
+
+use crate::error::ApiError;
+
+/// Size of the hashes chaining the entries of an audit log
+pub const AUDIT_HASH_LEN: usize = 32;
+
+/// Hash of an [`AuditEntry`]
+pub type AuditHash = [u8; AUDIT_HASH_LEN];
+
+/// Value of the previous hash for the first entry of an audit log
+pub const GENESIS_AUDIT_HASH: AuditHash = [0; AUDIT_HASH_LEN];
+
+/// Operations changing the members of an authority
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum AuditAction {
+    #[n(0)] AddMember,
+    #[n(1)] DeleteMember,
+    #[n(2)] SetMemberExpiration,
+    #[n(3)] SuspendMember,
+    #[n(4)] ResumeMember,
+    #[n(5)] IssueEnrollmentToken,
+    #[n(6)] AcceptEnrollmentToken,
+    #[n(7)] EnrollWithJwt,
+}
+
+impl AuditAction {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::AddMember => "add_member",
+            AuditAction::DeleteMember => "delete_member",
+            AuditAction::SetMemberExpiration => "set_member_expiration",
+            AuditAction::SuspendMember => "suspend_member",
+            AuditAction::ResumeMember => "resume_member",
+            AuditAction::IssueEnrollmentToken => "issue_enrollment_token",
+            AuditAction::AcceptEnrollmentToken => "accept_enrollment_token",
+            AuditAction::EnrollWithJwt => "enroll_with_jwt",
+        }
+    }
+}
+
+impl Display for AuditAction {
+    fn fmt(&self, f: &mut Formatter<'_>) -> core::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for AuditAction {
+    type Err = ockam_core::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Ok(match s {
+            "add_member" => AuditAction::AddMember,
+            "delete_member" => AuditAction::DeleteMember,
+            "set_member_expiration" => AuditAction::SetMemberExpiration,
+            "suspend_member" => AuditAction::SuspendMember,
+            "resume_member" => AuditAction::ResumeMember,
+            "issue_enrollment_token" => AuditAction::IssueEnrollmentToken,
+            "accept_enrollment_token" => AuditAction::AcceptEnrollmentToken,
+            "enroll_with_jwt" => AuditAction::EnrollWithJwt,
+            other => return Err(ApiError::core(format!("unknown audit action: {other}"))),
+        })
+    }
+}
+
+/// Signature of the hash of an [`AuditEntry`], made with a credentials purpose key of the authority
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct AuditSignature {
+    #[n(0)] pub signature: CredentialSignature,
+    #[n(1)] pub purpose_key_attestation: PurposeKeyAttestation,
+}
+
+/// Entry of the audit log of an authority.
+///
+/// Each entry contains the hash of the previous entry, so that removing, inserting or
+/// modifying an entry breaks the chain of hashes.
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct AuditEntry {
+    #[n(1)] pub index: u64,
+    #[n(2)] pub recorded_at: TimestampInSeconds,
+    #[n(3)] pub actor: Identifier,
+    #[n(4)] pub action: AuditAction,
+    #[n(5)] pub target: Option<Identifier>,
+    #[b(6)] pub attributes: BTreeMap<String, String>,
+    #[n(7)] pub previous_hash: AuditHash,
+    #[n(8)] pub hash: AuditHash,
+    #[n(9)] pub signature: AuditSignature,
+}
+
+impl AuditEntry {
+    /// Return the serialized content of the entry which is hashed:
+    /// all the fields except the hash and the signature
+    pub fn hashed_content(&self) -> Result<Vec<u8>> {
+        ockam_core::cbor_encode_preallocate(AuditEntryContent {
+            index: self.index,
+            recorded_at: self.recorded_at,
+            actor: &self.actor,
+            action: self.action,
+            target: self.target.as_ref(),
+            attributes: &self.attributes,
+            previous_hash: self.previous_hash,
+        })
+    }
+}
+
+#[derive(Encode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub(crate) struct AuditEntryContent<'a> {
+    #[n(1)] pub(crate) index: u64,
+    #[n(2)] pub(crate) recorded_at: TimestampInSeconds,
+    #[n(3)] pub(crate) actor: &'a Identifier,
+    #[n(4)] pub(crate) action: AuditAction,
+    #[n(5)] pub(crate) target: Option<&'a Identifier>,
+    #[n(6)] pub(crate) attributes: &'a BTreeMap<String, String>,
+    #[n(7)] pub(crate) previous_hash: AuditHash,
+}
+
+/// Request for a page of audit log entries
+#[derive(Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct ListAuditEntries {
+    #[n(1)] from: u64,
+    #[n(2)] limit: u32,
+}
+
+impl ListAuditEntries {
+    /// Request at most `limit` entries, starting at the entry with the index `from`
+    pub fn new(from: u64, limit: u32) -> Self {
+        ListAuditEntries { from, limit }
+    }
+
+    pub fn from(&self) -> u64 {
+        self.from
+    }
+
+    pub fn limit(&self) -> u32 {
+        self.limit
+    }
+}
+
+/// Attribute of an audit entry recording the end date of a membership
+pub const AUDIT_EXPIRES_AT_ATTRIBUTE: &str = "ockam-expires-at";
+
+/// Attribute of an audit entry recording the reference of an enrollment token
+pub const AUDIT_TOKEN_REFERENCE_ATTRIBUTE: &str = "ockam-token-reference";
+
+/// Attribute of an audit entry recording the expiration date of an enrollment token
+pub const AUDIT_TOKEN_EXPIRES_AT_ATTRIBUTE: &str = "ockam-token-expires-at";
+
+/// Attribute of an audit entry recording how many times an enrollment token can be used
+pub const AUDIT_TOKEN_USAGE_COUNT_ATTRIBUTE: &str = "ockam-token-usage-count";
+
+/// Attribute of an audit entry recording the subject of a JWT used to enroll
+pub const AUDIT_JWT_SUBJECT_ATTRIBUTE: &str = "ockam-jwt-subject";
@@ -2,9 +2,13 @@ use core::time::Duration;
 
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::AuthorityMembersRepository;
-use ockam::identity::models::{CredentialAndPurposeKey, CredentialSchemaIdentifier};
-use ockam::identity::utils::AttributesBuilder;
-use ockam::identity::{Attributes, Credentials, Identifier, IdentitiesAttributes};
+use ockam::identity::models::{
+    CredentialAndPurposeKey, CredentialSchemaIdentifier, RevocationListAndPurposeKey,
+};
+use ockam::identity::utils::{now, AttributesBuilder};
+use ockam::identity::{
+    Attributes, Credentials, Identifier, IdentitiesAttributes, TimestampInSeconds,
+};
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
@@ -17,6 +21,9 @@ pub const PROJECT_MEMBER_SCHEMA: CredentialSchemaIdentifier = CredentialSchemaId
 /// Maximum duration for a valid credential in seconds (30 days)
 pub const DEFAULT_CREDENTIAL_VALIDITY: Duration = Duration::from_secs(30 * 24 * 3600);
 
+/// Validity of a revocation list. Credential retrievers refresh it more frequently than that
+pub const DEFAULT_REVOCATION_LIST_VALIDITY: Duration = Duration::from_secs(10 * 60);
+
 /// This struct runs as a Worker to issue credentials based on a request/response protocol
 pub struct CredentialIssuer {
     members: Arc<dyn AuthorityMembersRepository>,
@@ -113,6 +120,27 @@ impl CredentialIssuer {
             None => return Ok(None),
         };
 
+        let now = now()?;
+        if member.is_suspended() {
+            warn!("Member {} is suspended, no credential is issued", subject);
+            return Ok(None);
+        }
+        if member.is_expired(now) {
+            warn!(
+                "The membership of {} has expired, no credential is issued",
+                subject
+            );
+            return Ok(None);
+        }
+
+        // A credential must not outlive the membership
+        let credential_ttl = match member.expires_at() {
+            Some(expires_at) => self
+                .credential_ttl
+                .min(Duration::from_secs(expires_at.0 - now.0)),
+            None => self.credential_ttl,
+        };
+
         let mut subject_attributes = self.subject_attributes.clone();
         for (key, value) in member.attributes().iter() {
             subject_attributes
@@ -123,16 +151,35 @@ impl CredentialIssuer {
         let credential = self
             .credentials
             .credentials_creation()
-            .issue_credential(
-                &self.issuer,
-                subject,
-                subject_attributes,
-                self.credential_ttl,
-            )
+            .issue_credential(&self.issuer, subject, subject_attributes, credential_ttl)
             .await?;
 
         info!("Successfully issued a credential for {}", subject);
 
         Ok(Some(credential))
     }
+
+    /// Issue a revocation list for the members which were deleted from this authority.
+    /// Members revoked before the oldest credential that could still be valid are omitted
+    #[instrument(skip_all)]
+    pub async fn issue_revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        let oldest_valid_credential =
+            TimestampInSeconds(now()?.saturating_sub(self.credential_ttl.as_secs()));
+        let revoked_subjects = self
+            .members
+            .get_revoked_members(&self.issuer)
+            .await?
+            .into_iter()
+            .filter(|revoked| revoked.revoked_at >= oldest_valid_credential)
+            .collect();
+
+        self.credentials
+            .credentials_creation()
+            .issue_revocation_list(
+                &self.issuer,
+                revoked_subjects,
+                DEFAULT_REVOCATION_LIST_VALIDITY,
+            )
+            .await
+    }
 }
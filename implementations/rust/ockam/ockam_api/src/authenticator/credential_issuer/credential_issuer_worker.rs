@@ -83,6 +83,15 @@ impl Worker for CredentialIssuerWorker {
                     Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
                 }
             }
+            (Some(Method::Get), "/revocation_list") => {
+                match self.credential_issuer.issue_revocation_list().await {
+                    Ok(revocation_list) => Response::ok()
+                        .with_headers(&req)
+                        .body(revocation_list)
+                        .to_vec()?,
+                    Err(error) => Response::internal_error(&req, &error.to_string()).to_vec()?,
+                }
+            }
             _ => Response::unknown_path(&req).to_vec()?,
         };
 
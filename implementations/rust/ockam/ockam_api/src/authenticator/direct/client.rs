@@ -2,12 +2,12 @@ use miette::IntoDiagnostic;
 use std::collections::{BTreeMap, HashMap};
 
 use ockam::identity::AttributesEntry;
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::api::Request;
 use ockam_core::async_trait;
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::direct::types::{AddMember, SetMemberExpiration};
 use crate::nodes::service::default_address::DefaultAddress;
 use crate::orchestrator::{AuthorityNodeClient, HasSecureClient};
 
@@ -20,6 +20,31 @@ pub trait Members {
         attributes: BTreeMap<String, String>,
     ) -> miette::Result<()>;
 
+    /// Add a member whose membership ends at the given date
+    async fn add_member_with_expiration(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        attributes: BTreeMap<String, String>,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> miette::Result<()>;
+
+    /// Set, or remove, the end date of a membership
+    async fn set_member_expiration(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> miette::Result<()>;
+
+    /// Suspend a member: it can't get new credentials and its existing credentials are revoked
+    async fn suspend_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<()>;
+
+    /// Resume a suspended member
+    async fn resume_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<()>;
+
+    async fn list_suspended_member_ids(&self, ctx: &Context) -> miette::Result<Vec<Identifier>>;
+
     async fn show_member(
         &self,
         ctx: &Context,
@@ -45,7 +70,38 @@ impl Members for AuthorityNodeClient {
         identifier: Identifier,
         attributes: BTreeMap<String, String>,
     ) -> miette::Result<()> {
-        let req = Request::post("/").body(AddMember::new(identifier).with_attributes(attributes));
+        self.add_member_with_expiration(ctx, identifier, attributes, None)
+            .await
+    }
+
+    async fn add_member_with_expiration(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        attributes: BTreeMap<String, String>,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> miette::Result<()> {
+        let req = Request::post("/").body(
+            AddMember::new(identifier)
+                .with_attributes(attributes)
+                .with_expires_at(expires_at),
+        );
+        self.get_secure_client()
+            .tell(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn set_member_expiration(
+        &self,
+        ctx: &Context,
+        identifier: Identifier,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> miette::Result<()> {
+        let req = Request::put(format!("/members/{identifier}/expiration"))
+            .body(SetMemberExpiration::new(expires_at));
         self.get_secure_client()
             .tell(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
             .await
@@ -54,6 +110,36 @@ impl Members for AuthorityNodeClient {
             .into_diagnostic()
     }
 
+    async fn suspend_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<()> {
+        let req = Request::post(format!("/members/{identifier}/suspend"));
+        self.get_secure_client()
+            .tell(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn resume_member(&self, ctx: &Context, identifier: Identifier) -> miette::Result<()> {
+        let req = Request::post(format!("/members/{identifier}/resume"));
+        self.get_secure_client()
+            .tell(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
+    async fn list_suspended_member_ids(&self, ctx: &Context) -> miette::Result<Vec<Identifier>> {
+        let req = Request::get("/suspended_member_ids");
+        self.get_secure_client()
+            .ask(ctx, DefaultAddress::DIRECT_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+            .success()
+            .into_diagnostic()
+    }
+
     async fn show_member(
         &self,
         ctx: &Context,
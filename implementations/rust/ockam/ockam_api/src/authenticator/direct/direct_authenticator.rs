@@ -2,13 +2,14 @@ use either::Either;
 use std::collections::{BTreeMap, HashMap};
 
 use ockam::identity::utils::now;
-use ockam::identity::Identifier;
 use ockam::identity::{AttributesEntry, IdentitiesAttributes};
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
+use crate::authenticator::audit::{AuditAction, AuthorityAuditLog, AUDIT_EXPIRES_AT_ATTRIBUTE};
 use crate::authenticator::common::EnrollerAccessControlChecks;
-use crate::authenticator::{AuthorityMember, AuthorityMembersRepository};
+use crate::authenticator::{AuthorityMember, AuthorityMemberChange, AuthorityMembersRepository};
 
 /// Identity attribute key that indicates the role of the subject
 pub const OCKAM_ROLE_ATTRIBUTE_KEY: &str = "ockam-role";
@@ -27,6 +28,7 @@ pub type DirectAuthenticatorResult<T> = Either<T, DirectAuthenticatorError>;
 pub struct DirectAuthenticator {
     authority: Identifier,
     members: Arc<dyn AuthorityMembersRepository>,
+    audit_log: Arc<AuthorityAuditLog>,
     identities_attributes: Arc<IdentitiesAttributes>,
     account_authority: Option<AccountAuthorityInfo>,
 }
@@ -65,12 +67,14 @@ impl DirectAuthenticator {
     pub fn new(
         authority: &Identifier,
         members: Arc<dyn AuthorityMembersRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
         identities_attributes: Arc<IdentitiesAttributes>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
         Self {
             authority: authority.clone(),
             members,
+            audit_log,
             identities_attributes,
             account_authority,
         }
@@ -82,6 +86,7 @@ impl DirectAuthenticator {
         enroller: &Identifier,
         identifier: &Identifier,
         attributes: &BTreeMap<String, String>,
+        expires_at: Option<TimestampInSeconds>,
     ) -> Result<DirectAuthenticatorResult<()>> {
         let check = EnrollerAccessControlChecks::check_identifier(
             &self.authority,
@@ -123,10 +128,43 @@ impl DirectAuthenticator {
             }
         }
 
-        let member =
-            AuthorityMember::new(identifier.clone(), attrs, enroller.clone(), now()?, false);
+        // Adding a member again updates its attributes, but keeps its suspension and end date.
+        // A suspended member must be resumed explicitly
+        let existing = self.members.get_member(&self.authority, identifier).await?;
+        if existing.as_ref().is_some_and(|m| m.is_suspended()) {
+            warn!(
+                "{} is trying to add the suspended member {}",
+                enroller, identifier
+            );
+            return Ok(Either::Right(DirectAuthenticatorError(format!(
+                "The member {} is suspended, it must be resumed first",
+                identifier
+            ))));
+        }
+        let expires_at = expires_at.or(existing.and_then(|m| m.expires_at()));
 
-        if let Err(err) = self.members.add_member(&self.authority, member).await {
+        let member =
+            AuthorityMember::new(identifier.clone(), attrs, enroller.clone(), now()?, false)
+                .with_expires_at(expires_at);
+
+        let mut audited_attributes = attributes.clone();
+        if let Some(expires_at) = expires_at {
+            audited_attributes.insert(
+                AUDIT_EXPIRES_AT_ATTRIBUTE.to_string(),
+                expires_at.to_string(),
+            );
+        }
+        if let Err(err) = self
+            .audit_log
+            .record_change(
+                enroller,
+                AuditAction::AddMember,
+                Some(identifier),
+                audited_attributes,
+                AuthorityMemberChange::Add(member),
+            )
+            .await
+        {
             warn!("Error adding member {} directly: {}", identifier, err);
             return Ok(Either::Right(DirectAuthenticatorError(
                 "Error adding member".to_string(),
@@ -168,7 +206,7 @@ impl DirectAuthenticator {
                 let entry = AttributesEntry::new(
                     member.attributes().clone(),
                     member.added_at(),
-                    None,
+                    member.expires_at(),
                     Some(member.added_by().clone()),
                 );
                 Ok(Either::Left(entry))
@@ -211,7 +249,7 @@ impl DirectAuthenticator {
             let entry = AttributesEntry::new(
                 member.attributes().clone(),
                 member.added_at(),
-                None,
+                member.expires_at(),
                 Some(member.added_by().clone()),
             );
             res.insert(member.identifier().clone(), entry);
@@ -292,12 +330,195 @@ impl DirectAuthenticator {
             )));
         }
 
-        self.members
-            .delete_member(&self.authority, identifier)
+        self.audit_log
+            .record_change(
+                enroller,
+                AuditAction::DeleteMember,
+                Some(identifier),
+                BTreeMap::new(),
+                AuthorityMemberChange::Delete(identifier.clone()),
+            )
             .await?;
 
         info!("Successfully deleted member {}", identifier);
 
         Ok(Either::Left(()))
     }
+
+    #[instrument(skip_all, fields(enroller = %enroller, identifier = %identifier))]
+    pub async fn set_member_expiration(
+        &self,
+        enroller: &Identifier,
+        identifier: &Identifier,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> Result<DirectAuthenticatorResult<()>> {
+        if let Some(error) = self.check_can_update_member(enroller, identifier).await? {
+            return Ok(Either::Right(error));
+        }
+
+        let audited_attributes = expires_at
+            .map(|expires_at| {
+                BTreeMap::from([(
+                    AUDIT_EXPIRES_AT_ATTRIBUTE.to_string(),
+                    expires_at.to_string(),
+                )])
+            })
+            .unwrap_or_default();
+        if self
+            .audit_log
+            .record_change(
+                enroller,
+                AuditAction::SetMemberExpiration,
+                Some(identifier),
+                audited_attributes,
+                AuthorityMemberChange::SetExpiration(identifier.clone(), expires_at),
+            )
+            .await?
+            .is_none()
+        {
+            return Ok(Either::Right(DirectAuthenticatorError(format!(
+                "Member {} not found",
+                identifier
+            ))));
+        }
+
+        info!(
+            "Successfully set the expiration of member {} to {:?}",
+            identifier, expires_at
+        );
+        Ok(Either::Left(()))
+    }
+
+    #[instrument(skip_all, fields(enroller = %enroller, identifier = %identifier))]
+    pub async fn set_member_suspended(
+        &self,
+        enroller: &Identifier,
+        identifier: &Identifier,
+        is_suspended: bool,
+    ) -> Result<DirectAuthenticatorResult<()>> {
+        if let Some(error) = self.check_can_update_member(enroller, identifier).await? {
+            return Ok(Either::Right(error));
+        }
+
+        let action = if is_suspended {
+            AuditAction::SuspendMember
+        } else {
+            AuditAction::ResumeMember
+        };
+        if self
+            .audit_log
+            .record_change(
+                enroller,
+                action,
+                Some(identifier),
+                BTreeMap::new(),
+                AuthorityMemberChange::SetSuspended(identifier.clone(), is_suspended),
+            )
+            .await?
+            .is_none()
+        {
+            return Ok(Either::Right(DirectAuthenticatorError(format!(
+                "Member {} not found",
+                identifier
+            ))));
+        }
+
+        if is_suspended {
+            info!("Successfully suspended member {}", identifier);
+        } else {
+            info!("Successfully resumed member {}", identifier);
+        }
+        Ok(Either::Left(()))
+    }
+
+    #[instrument(skip_all, fields(enroller = %enroller))]
+    pub async fn list_suspended_members(
+        &self,
+        enroller: &Identifier,
+    ) -> Result<DirectAuthenticatorResult<Vec<Identifier>>> {
+        let check = EnrollerAccessControlChecks::check_identifier(
+            &self.authority,
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check.is_enroller {
+            warn!("Non-enroller {} is trying to list members", enroller);
+            return Ok(Either::Right(DirectAuthenticatorError(
+                "Non-enroller is trying to list members".to_string(),
+            )));
+        }
+
+        let suspended = self
+            .members
+            .get_members(&self.authority)
+            .await?
+            .into_iter()
+            .filter(|m| m.is_suspended())
+            .map(|m| m.identifier().clone())
+            .collect();
+        Ok(Either::Left(suspended))
+    }
+
+    /// Check that an enroller can change the membership of an identity.
+    /// The same rules as for deleting a member apply: pre-trusted identities can't be changed,
+    /// and only admins can change the membership of enrollers
+    async fn check_can_update_member(
+        &self,
+        enroller: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<Option<DirectAuthenticatorError>> {
+        let check_enroller = EnrollerAccessControlChecks::check_identifier(
+            &self.authority,
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            enroller,
+            &self.account_authority,
+        )
+        .await?;
+
+        if !check_enroller.is_enroller {
+            warn!(
+                "Non-enroller {} is trying to update member {}",
+                enroller, identifier
+            );
+            return Ok(Some(DirectAuthenticatorError(
+                "Non-enroller is trying to update a member".to_string(),
+            )));
+        }
+
+        let check_member = EnrollerAccessControlChecks::check_identifier(
+            &self.authority,
+            self.members.clone(),
+            self.identities_attributes.clone(),
+            identifier,
+            &self.account_authority,
+        )
+        .await?;
+
+        if check_member.is_pre_trusted {
+            warn!(
+                "Enroller {} is trying to update a pre trusted identity {}",
+                enroller, identifier
+            );
+            return Ok(Some(DirectAuthenticatorError(
+                "Enroller is trying to update a pre trusted identity".to_string(),
+            )));
+        }
+
+        if check_member.is_enroller && !check_enroller.is_admin {
+            warn!(
+                "Not admin {} is trying to update enroller {}",
+                enroller, identifier
+            );
+            return Ok(Some(DirectAuthenticatorError(
+                "Not admin is trying to update an enroller".to_string(),
+            )));
+        }
+
+        Ok(None)
+    }
 }
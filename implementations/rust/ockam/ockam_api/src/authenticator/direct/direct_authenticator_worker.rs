@@ -8,7 +8,8 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::{Result, Routed, SecureChannelLocalInfo, Worker};
 use ockam_node::Context;
 
-use crate::authenticator::direct::types::AddMember;
+use crate::authenticator::audit::AuthorityAuditLog;
+use crate::authenticator::direct::types::{AddMember, SetMemberExpiration};
 use crate::authenticator::direct::DirectAuthenticator;
 use crate::authenticator::AuthorityMembersRepository;
 
@@ -22,6 +23,7 @@ impl DirectAuthenticatorWorker {
     pub fn new(
         authority: &Identifier,
         members: Arc<dyn AuthorityMembersRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
         identities_attributes: Arc<IdentitiesAttributes>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
@@ -29,6 +31,7 @@ impl DirectAuthenticatorWorker {
             authenticator: DirectAuthenticator::new(
                 authority,
                 members,
+                audit_log,
                 identities_attributes,
                 account_authority,
             ),
@@ -71,7 +74,7 @@ impl Worker for DirectAuthenticatorWorker {
                 let add: AddMember = dec.decode()?;
                 let res = self
                     .authenticator
-                    .add_member(&from, add.member(), add.attributes())
+                    .add_member(&from, add.member(), add.attributes(), add.expires_at())
                     .await?;
                 match res {
                     Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
@@ -88,6 +91,36 @@ impl Worker for DirectAuthenticatorWorker {
                     Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
                 }
             }
+            (Some(Method::Get), ["suspended_member_ids"]) => {
+                let res = self.authenticator.list_suspended_members(&from).await?;
+                match res {
+                    Either::Left(ids) => Response::ok().with_headers(&req).body(ids).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            (Some(Method::Put), ["members", id, "expiration"]) => {
+                let identifier = Identifier::try_from(id.to_string())?;
+                let expiration: SetMemberExpiration = dec.decode()?;
+                let res = self
+                    .authenticator
+                    .set_member_expiration(&from, &identifier, expiration.expires_at())
+                    .await?;
+                match res {
+                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
+            (Some(Method::Post), ["members", id, action @ ("suspend" | "resume")]) => {
+                let identifier = Identifier::try_from(id.to_string())?;
+                let res = self
+                    .authenticator
+                    .set_member_suspended(&from, &identifier, *action == "suspend")
+                    .await?;
+                match res {
+                    Either::Left(_) => Response::ok().with_headers(&req).to_vec()?,
+                    Either::Right(error) => Response::forbidden(&req, &error.0).to_vec()?,
+                }
+            }
             (Some(Method::Get), [""]) | (Some(Method::Get), ["members"]) => {
                 let res = self.authenticator.list_members(&from).await?;
 
@@ -1,5 +1,5 @@
 use minicbor::{CborLen, Decode, Encode};
-use ockam::identity::Identifier;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use std::collections::BTreeMap;
 use std::time::Duration;
 
@@ -9,6 +9,7 @@ use std::time::Duration;
 pub struct AddMember {
     #[n(1)] member: Identifier,
     #[b(2)] attributes: BTreeMap<String, String>,
+    #[n(3)] expires_at: Option<TimestampInSeconds>,
 }
 
 impl AddMember {
@@ -16,6 +17,7 @@ impl AddMember {
         AddMember {
             member,
             attributes: BTreeMap::new(),
+            expires_at: None,
         }
     }
 
@@ -24,6 +26,15 @@ impl AddMember {
         self
     }
 
+    pub fn with_expires_at(mut self, expires_at: Option<TimestampInSeconds>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    pub fn expires_at(&self) -> Option<TimestampInSeconds> {
+        self.expires_at
+    }
+
     pub fn member(&self) -> &Identifier {
         &self.member
     }
@@ -33,6 +44,24 @@ impl AddMember {
     }
 }
 
+#[derive(Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct SetMemberExpiration {
+    #[n(1)] expires_at: Option<TimestampInSeconds>,
+}
+
+impl SetMemberExpiration {
+    /// Create a request to set the end of a membership. No date means that the membership never expires
+    pub fn new(expires_at: Option<TimestampInSeconds>) -> Self {
+        SetMemberExpiration { expires_at }
+    }
+
+    pub fn expires_at(&self) -> Option<TimestampInSeconds> {
+        self.expires_at
+    }
+}
+
 #[derive(Debug, Encode, Decode, CborLen)]
 #[rustfmt::skip]
 #[cbor(map)]
@@ -4,10 +4,14 @@ use ockam::identity::Identifier;
 use ockam_core::compat::sync::Arc;
 use ockam_core::Result;
 
+use crate::authenticator::audit::{
+    AuditAction, AuthorityAuditLog, AUDIT_TOKEN_REFERENCE_ATTRIBUTE,
+};
 use crate::authenticator::common::EnrollerAccessControlChecks;
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{
-    AuthorityEnrollmentTokenRepository, AuthorityMember, AuthorityMembersRepository,
+    AuthorityEnrollmentTokenRepository, AuthorityMember, AuthorityMemberChange,
+    AuthorityMembersRepository,
 };
 
 pub struct EnrollmentTokenAcceptorError(pub String);
@@ -18,6 +22,7 @@ pub struct EnrollmentTokenAcceptor {
     authority: Identifier,
     pub(super) tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
     pub(super) members: Arc<dyn AuthorityMembersRepository>,
+    pub(super) audit_log: Arc<AuthorityAuditLog>,
 }
 
 impl EnrollmentTokenAcceptor {
@@ -25,11 +30,13 @@ impl EnrollmentTokenAcceptor {
         authority: &Identifier,
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
     ) -> Self {
         Self {
             authority: authority.clone(),
             tokens,
             members,
+            audit_log,
         }
     }
 
@@ -82,7 +89,22 @@ impl EnrollmentTokenAcceptor {
 
         let member = AuthorityMember::new(from.clone(), attrs, token.issued_by, now()?, false);
 
-        if let Err(err) = self.members.add_member(&self.authority, member).await {
+        let mut audited_attributes = token.attrs.clone();
+        audited_attributes.insert(
+            AUDIT_TOKEN_REFERENCE_ATTRIBUTE.to_string(),
+            reference.clone(),
+        );
+        if let Err(err) = self
+            .audit_log
+            .record_change(
+                from,
+                AuditAction::AcceptEnrollmentToken,
+                Some(from),
+                audited_attributes,
+                AuthorityMemberChange::Add(member),
+            )
+            .await
+        {
             warn!(
                 "Error adding member {} using enrollment token: {}",
                 from, err
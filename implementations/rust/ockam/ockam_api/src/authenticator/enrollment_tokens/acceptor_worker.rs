@@ -1,3 +1,4 @@
+use crate::authenticator::audit::AuthorityAuditLog;
 use crate::authenticator::enrollment_tokens::EnrollmentTokenAcceptor;
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::authenticator::{AuthorityEnrollmentTokenRepository, AuthorityMembersRepository};
@@ -19,9 +20,10 @@ impl EnrollmentTokenAcceptorWorker {
         authority: &Identifier,
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
     ) -> Self {
         Self {
-            acceptor: EnrollmentTokenAcceptor::new(authority, tokens, members),
+            acceptor: EnrollmentTokenAcceptor::new(authority, tokens, members, audit_log),
         }
     }
 }
@@ -9,6 +9,10 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::compat::time::Duration;
 use ockam_core::Result;
 
+use crate::authenticator::audit::{
+    AuditAction, AuthorityAuditLog, AUDIT_TOKEN_EXPIRES_AT_ATTRIBUTE,
+    AUDIT_TOKEN_REFERENCE_ATTRIBUTE, AUDIT_TOKEN_USAGE_COUNT_ATTRIBUTE,
+};
 use crate::authenticator::common::EnrollerAccessControlChecks;
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::one_time_code::OneTimeCode;
@@ -29,6 +33,7 @@ pub struct EnrollmentTokenIssuer {
     authority: Identifier,
     pub(super) tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
     pub(super) members: Arc<dyn AuthorityMembersRepository>,
+    pub(super) audit_log: Arc<AuthorityAuditLog>,
     pub(super) identities_attributes: Arc<IdentitiesAttributes>,
     pub(super) account_authority: Option<AccountAuthorityInfo>,
 }
@@ -38,6 +43,7 @@ impl EnrollmentTokenIssuer {
         authority: &Identifier,
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
         identities_attributes: Arc<IdentitiesAttributes>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
@@ -45,6 +51,7 @@ impl EnrollmentTokenIssuer {
             authority: authority.clone(),
             tokens,
             members,
+            audit_log,
             identities_attributes,
             account_authority,
         }
@@ -101,6 +108,21 @@ impl EnrollmentTokenIssuer {
         let ttl_count = ttl_count.unwrap_or(DEFAULT_TOKEN_USAGE_COUNT);
         let now = now()?;
         let expires_at = now + max_token_duration.as_secs();
+        // The one-time code is secret, so the token is only referred to by its reference
+        let mut audited_attributes = attrs.clone();
+        audited_attributes.insert(
+            AUDIT_TOKEN_REFERENCE_ATTRIBUTE.to_string(),
+            reference.clone(),
+        );
+        audited_attributes.insert(
+            AUDIT_TOKEN_EXPIRES_AT_ATTRIBUTE.to_string(),
+            expires_at.to_string(),
+        );
+        audited_attributes.insert(
+            AUDIT_TOKEN_USAGE_COUNT_ATTRIBUTE.to_string(),
+            ttl_count.to_string(),
+        );
+
         let tkn = EnrollmentToken {
             one_time_code,
             reference: Some(reference.clone()),
@@ -112,6 +134,15 @@ impl EnrollmentTokenIssuer {
         };
         self.tokens.store_new_token(tkn).await?;
 
+        self.audit_log
+            .record(
+                enroller,
+                AuditAction::IssueEnrollmentToken,
+                None,
+                audited_attributes,
+            )
+            .await?;
+
         info!(
             "Successfully issued an enrollment token. TTL count: {}, expires_at: {}, reference: {}",
             ttl_count, expires_at.0, reference
@@ -9,6 +9,7 @@ use ockam_core::compat::time::Duration;
 use ockam_core::{Result, Routed, SecureChannelLocalInfo, Worker};
 use ockam_node::Context;
 
+use crate::authenticator::audit::AuthorityAuditLog;
 use crate::authenticator::direct::types::CreateToken;
 use crate::authenticator::direct::AccountAuthorityInfo;
 use crate::authenticator::enrollment_tokens::EnrollmentTokenIssuer;
@@ -23,6 +24,7 @@ impl EnrollmentTokenIssuerWorker {
         authority: &Identifier,
         tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
         members: Arc<dyn AuthorityMembersRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
         identities_attributes: Arc<IdentitiesAttributes>,
         account_authority: Option<AccountAuthorityInfo>,
     ) -> Self {
@@ -31,6 +33,7 @@ impl EnrollmentTokenIssuerWorker {
                 authority,
                 tokens,
                 members,
+                audit_log,
                 identities_attributes,
                 account_authority,
             ),
@@ -1,7 +1,10 @@
+pub mod audit;
 pub mod credential_issuer;
 pub mod direct;
 pub mod enrollment_tokens;
+pub mod oidc;
 pub mod one_time_code;
+pub mod scim;
 
 pub(crate) mod common;
 
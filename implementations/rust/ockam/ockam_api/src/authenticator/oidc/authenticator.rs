@@ -0,0 +1,380 @@
+use either::Either;
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use serde_json::Value;
+use std::time::Duration;
+
+use crate::authenticator::audit::{AuditAction, AuthorityAuditLog, AUDIT_JWT_SUBJECT_ATTRIBUTE};
+use crate::authenticator::oidc::{Jwks, Jwt, JwtError};
+use crate::authenticator::{
+    AuthorityMember, AuthorityMemberChange, AuthorityMembersRepository, AuthorityUsedJwtRepository,
+};
+use crate::authority_node::OidcConfiguration;
+use crate::error::ApiError;
+
+/// Minimum number of seconds between two retrievals of a remote key set.
+/// The key set is retrieved again when a token refers to an unknown key, which happens
+/// when the identity provider rotates its signing keys.
+const JWKS_REFRESH_INTERVAL: u64 = 60;
+
+/// Number of seconds a remote key set is used before being retrieved again,
+/// when the identity provider does not specify it with a `Cache-Control` header
+const JWKS_DEFAULT_MAX_AGE: u64 = 3600;
+
+/// Maximum number of seconds a remote key set is used before being retrieved again,
+/// so that revoked keys are eventually removed
+const JWKS_MAX_AGE: u64 = 24 * 3600;
+
+/// Timeout for connecting to the identity provider
+const HTTP_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Timeout for a request to the identity provider, including the connection
+const HTTP_REQUEST_TIMEOUT: Duration = Duration::from_secs(10);
+
+pub struct OidcAuthenticatorError(pub String);
+
+pub type OidcAuthenticatorResult<T> = Either<T, OidcAuthenticatorError>;
+
+/// This authenticator adds the identities presenting a valid JWT as project members.
+/// The attributes of the new member are taken from the claims of the token.
+pub struct OidcAuthenticator {
+    authority: Identifier,
+    members: Arc<dyn AuthorityMembersRepository>,
+    used_jwts: Arc<dyn AuthorityUsedJwtRepository>,
+    audit_log: Arc<AuthorityAuditLog>,
+    configuration: OidcConfiguration,
+    http_client: reqwest::Client,
+    jwks: Option<CachedJwks>,
+}
+
+/// A key set with the time it was retrieved
+struct CachedJwks {
+    jwks: Jwks,
+    retrieved_at: TimestampInSeconds,
+    /// Number of seconds before the key set must be retrieved again.
+    /// A local key set is never retrieved again
+    max_age: Option<u64>,
+}
+
+impl CachedJwks {
+    fn is_expired(&self, now: TimestampInSeconds) -> bool {
+        self.max_age
+            .is_some_and(|max_age| now.0 >= self.retrieved_at.0.saturating_add(max_age))
+    }
+}
+
+impl OidcAuthenticator {
+    pub fn new(
+        authority: &Identifier,
+        members: Arc<dyn AuthorityMembersRepository>,
+        used_jwts: Arc<dyn AuthorityUsedJwtRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
+        configuration: &OidcConfiguration,
+    ) -> Result<Self> {
+        let http_client = reqwest::ClientBuilder::new()
+            .connect_timeout(HTTP_CONNECT_TIMEOUT)
+            .timeout(HTTP_REQUEST_TIMEOUT)
+            .build()
+            .map_err(|e| ApiError::core(format!("Failed to create http client: {e}")))?;
+        Ok(Self {
+            authority: authority.clone(),
+            members,
+            used_jwts,
+            audit_log,
+            configuration: configuration.clone(),
+            http_client,
+            jwks: None,
+        })
+    }
+
+    #[instrument(skip_all, fields(from = %from))]
+    pub async fn authenticate(
+        &mut self,
+        token: &str,
+        from: &Identifier,
+    ) -> Result<OidcAuthenticatorResult<()>> {
+        let jwt = match self.verify_token(token).await? {
+            Ok(jwt) => jwt,
+            Err(err) => {
+                warn!("Invalid token received from {}: {}", from, err);
+                return Ok(Either::Right(OidcAuthenticatorError(format!(
+                    "Invalid token: {err}"
+                ))));
+            }
+        };
+
+        // Only members previously enrolled with a token can have their attributes updated.
+        // Their suspension and expiration still apply
+        let existing = self.members.get_member(&self.authority, from).await?;
+        if let Some(member) = &existing {
+            if member.is_pre_trusted() || member.added_by() != &self.authority {
+                warn!("{} is already a member", from);
+                return Ok(Either::Right(OidcAuthenticatorError(
+                    "Already a member".to_string(),
+                )));
+            }
+            if member.is_suspended() {
+                warn!("{} is a suspended member", from);
+                return Ok(Either::Right(OidcAuthenticatorError(
+                    "Suspended member".to_string(),
+                )));
+            }
+        }
+
+        let attributes: BTreeMap<String, String> = self
+            .configuration
+            .claims
+            .iter()
+            .filter_map(|(claim, attribute)| {
+                jwt.claim_as_string(claim)
+                    .map(|value| (attribute.clone(), value))
+            })
+            .collect();
+        let attrs = attributes
+            .iter()
+            .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
+            .collect();
+
+        let member =
+            AuthorityMember::new(from.clone(), attrs, self.authority.clone(), now()?, false)
+                .with_expires_at(existing.and_then(|m| m.expires_at()));
+        let subject = jwt.claim_as_string("sub").unwrap_or_default();
+        let mut audited_attributes = attributes;
+        audited_attributes.insert(AUDIT_JWT_SUBJECT_ATTRIBUTE.to_string(), subject.clone());
+        if let Err(err) = self
+            .audit_log
+            .record_change(
+                from,
+                AuditAction::EnrollWithJwt,
+                Some(from),
+                audited_attributes,
+                AuthorityMemberChange::Add(member),
+            )
+            .await
+        {
+            warn!("Error adding member {} using a JWT: {}", from, err);
+            return Ok(Either::Right(OidcAuthenticatorError(
+                "Error adding member using a JWT".to_string(),
+            )));
+        }
+
+        info!(
+            "Successfully authenticated {} with a token for the subject {}",
+            from, subject
+        );
+        Ok(Either::Left(()))
+    }
+
+    /// Check the signature and the claims of a token, and mark it as used.
+    /// An error is only returned if the key set could not be retrieved
+    /// or if the used tokens could not be stored.
+    async fn verify_token(&mut self, token: &str) -> Result<Result<Jwt, JwtError>> {
+        let jwt = match Jwt::decode(token) {
+            Ok(jwt) => jwt,
+            Err(err) => return Ok(Err(err)),
+        };
+
+        let mut verified = jwt.verify_signature(self.jwks().await?);
+        if verified == Err(JwtError::UnknownKey) && self.can_refresh_jwks()? {
+            if let Some(cached) = self.jwks.as_mut() {
+                cached.max_age = Some(0);
+            }
+            verified = jwt.verify_signature(self.jwks().await?);
+        }
+        if let Err(err) = verified {
+            return Ok(Err(err));
+        }
+
+        let configuration = &self.configuration;
+        let now = now()?;
+        if let Err(err) = jwt.verify_claims(&configuration.issuer, &configuration.audience, now.0) {
+            return Ok(Err(err));
+        }
+        for (claim, expected) in configuration.required_claims.iter() {
+            if !jwt.claim_matches(claim, expected) {
+                return Ok(Err(JwtError::UnexpectedClaim(claim.clone())));
+            }
+        }
+
+        // A token can only be used once, so that it can't be replayed by another identity
+        let Some(expires_at) = jwt.expires_at() else {
+            return Ok(Err(JwtError::MissingClaim("exp".to_string())));
+        };
+        if !self
+            .used_jwts
+            .use_jwt(
+                &self.authority,
+                &configuration.issuer,
+                &jwt.jwt_id(),
+                TimestampInSeconds(expires_at),
+                now,
+            )
+            .await?
+        {
+            return Ok(Err(JwtError::AlreadyUsed));
+        }
+        Ok(Ok(jwt))
+    }
+
+    /// Return the key set used to verify tokens, retrieving it if it is missing or expired.
+    /// If an expired key set can't be retrieved again, it is still used, and retrieved again
+    /// after the refresh interval
+    async fn jwks(&mut self) -> Result<&Jwks> {
+        let now = now()?;
+        if self
+            .jwks
+            .as_ref()
+            .map_or(true, |cached| cached.is_expired(now))
+        {
+            match retrieve_jwks(&self.http_client, &self.configuration).await {
+                Ok((jwks, max_age)) => {
+                    self.jwks = Some(CachedJwks {
+                        jwks,
+                        retrieved_at: now,
+                        max_age,
+                    })
+                }
+                Err(err) => match self.jwks.as_mut() {
+                    Some(cached) => {
+                        warn!("Cannot retrieve the key set again, the previous one is used: {err}");
+                        cached.retrieved_at = now;
+                        cached.max_age = Some(JWKS_REFRESH_INTERVAL);
+                    }
+                    None => return Err(err),
+                },
+            }
+        }
+        self.jwks
+            .as_ref()
+            .map(|cached| &cached.jwks)
+            .ok_or_else(|| ApiError::core("the key set is not available"))
+    }
+
+    /// A local key set is never retrieved again, and a remote key set is retrieved
+    /// at most once per refresh interval
+    fn can_refresh_jwks(&self) -> Result<bool> {
+        if !self.configuration.has_remote_jwks() {
+            return Ok(false);
+        }
+        Ok(match &self.jwks {
+            Some(cached) => now()?.0 >= cached.retrieved_at.0 + JWKS_REFRESH_INTERVAL,
+            None => true,
+        })
+    }
+}
+
+/// Retrieve the key set used to verify tokens, with the number of seconds it can be used
+/// if it is a remote key set.
+/// When no key set location is configured, it is discovered with the OpenID configuration
+/// of the issuer.
+async fn retrieve_jwks(
+    http_client: &reqwest::Client,
+    configuration: &OidcConfiguration,
+) -> Result<(Jwks, Option<u64>)> {
+    let location = match &configuration.jwks {
+        Some(location) => location.clone(),
+        None => {
+            let url = format!(
+                "{}/.well-known/openid-configuration",
+                configuration.issuer.trim_end_matches('/')
+            );
+            let (discovery, _): (BTreeMap<String, Value>, _) = get_json(http_client, &url).await?;
+            discovery
+                .get("jwks_uri")
+                .and_then(|v| v.as_str())
+                .map(|v| v.to_string())
+                .ok_or_else(|| ApiError::core(format!("no jwks_uri found at {url}")))?
+        }
+    };
+
+    if OidcConfiguration::is_url(&location) {
+        let (jwks, max_age) = get_json(http_client, &location).await?;
+        let max_age = max_age
+            .unwrap_or(JWKS_DEFAULT_MAX_AGE)
+            .clamp(JWKS_REFRESH_INTERVAL, JWKS_MAX_AGE);
+        Ok((jwks, Some(max_age)))
+    } else {
+        let content = tokio::fs::read_to_string(&location)
+            .await
+            .map_err(|e| ApiError::core(format!("cannot read the key set at {location}: {e}")))?;
+        let jwks = serde_json::from_str(&content)
+            .map_err(|e| ApiError::core(format!("invalid key set at {location}: {e}")))?;
+        Ok((jwks, None))
+    }
+}
+
+/// Retrieve a JSON document, with the `max-age` of its `Cache-Control` header if present
+async fn get_json<T: serde::de::DeserializeOwned>(
+    http_client: &reqwest::Client,
+    url: &str,
+) -> Result<(T, Option<u64>)> {
+    let response = http_client
+        .get(url)
+        .send()
+        .await
+        .and_then(|res| res.error_for_status())
+        .map_err(|e| ApiError::core(format!("cannot retrieve {url}: {e}")))?;
+    let max_age = response
+        .headers()
+        .get(reqwest::header::CACHE_CONTROL)
+        .and_then(|value| value.to_str().ok())
+        .and_then(parse_max_age);
+    let document = response
+        .json()
+        .await
+        .map_err(|e| ApiError::core(format!("invalid JSON document at {url}: {e}")))?;
+    Ok((document, max_age))
+}
+
+/// Return the number of seconds a response can be cached, from its `Cache-Control` header
+fn parse_max_age(cache_control: &str) -> Option<u64> {
+    let mut max_age = None;
+    for directive in cache_control.split(',').map(|d| d.trim()) {
+        if directive.eq_ignore_ascii_case("no-cache") || directive.eq_ignore_ascii_case("no-store")
+        {
+            return Some(0);
+        }
+        if let Some((name, value)) = directive.split_once('=') {
+            if name.trim().eq_ignore_ascii_case("max-age") {
+                max_age = value.trim().trim_matches('"').parse().ok();
+            }
+        }
+    }
+    max_age
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_max_age() {
+        assert_eq!(parse_max_age("public, max-age=300"), Some(300));
+        assert_eq!(parse_max_age("max-age=\"600\", must-revalidate"), Some(600));
+        assert_eq!(parse_max_age("Max-Age=20"), Some(20));
+        assert_eq!(parse_max_age("no-cache, max-age=300"), Some(0));
+        assert_eq!(parse_max_age("public"), None);
+        assert_eq!(parse_max_age("max-age=soon"), None);
+    }
+
+    #[test]
+    fn test_cached_jwks_expiration() {
+        let remote = CachedJwks {
+            jwks: Jwks::default(),
+            retrieved_at: TimestampInSeconds(1000),
+            max_age: Some(JWKS_DEFAULT_MAX_AGE),
+        };
+        assert!(!remote.is_expired(TimestampInSeconds(1000 + JWKS_DEFAULT_MAX_AGE - 1)));
+        assert!(remote.is_expired(TimestampInSeconds(1000 + JWKS_DEFAULT_MAX_AGE)));
+
+        let local = CachedJwks {
+            jwks: Jwks::default(),
+            retrieved_at: TimestampInSeconds(1000),
+            max_age: None,
+        };
+        assert!(!local.is_expired(TimestampInSeconds(u64::MAX)));
+    }
+}
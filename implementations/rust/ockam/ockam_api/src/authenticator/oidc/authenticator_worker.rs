@@ -0,0 +1,85 @@
+use crate::authenticator::audit::AuthorityAuditLog;
+use crate::authenticator::oidc::OidcAuthenticator;
+use crate::authenticator::{AuthorityMembersRepository, AuthorityUsedJwtRepository};
+use crate::authority_node::OidcConfiguration;
+use crate::orchestrator::enroll::auth0::AuthenticateOidcToken;
+use either::Either;
+use minicbor::Decoder;
+use ockam::identity::Identifier;
+use ockam_core::api::{Method, RequestHeader, Response};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{Result, Routed, SecureChannelLocalInfo, Worker};
+use ockam_node::Context;
+use tracing::trace;
+
+pub struct OidcAuthenticatorWorker {
+    authenticator: OidcAuthenticator,
+}
+
+impl OidcAuthenticatorWorker {
+    pub fn new(
+        authority: &Identifier,
+        members: Arc<dyn AuthorityMembersRepository>,
+        used_jwts: Arc<dyn AuthorityUsedJwtRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
+        configuration: &OidcConfiguration,
+    ) -> Result<Self> {
+        Ok(Self {
+            authenticator: OidcAuthenticator::new(
+                authority,
+                members,
+                used_jwts,
+                audit_log,
+                configuration,
+            )?,
+        })
+    }
+}
+
+#[ockam_core::worker]
+impl Worker for OidcAuthenticatorWorker {
+    type Context = Context;
+    type Message = Vec<u8>;
+
+    async fn handle_message(&mut self, c: &mut Context, m: Routed<Self::Message>) -> Result<()> {
+        let secure_channel_info = match SecureChannelLocalInfo::find_info(m.local_message()) {
+            Ok(secure_channel_info) => secure_channel_info,
+            Err(_e) => {
+                let resp = Response::bad_request_no_request("secure channel required").to_vec()?;
+                c.send(m.return_route().clone(), resp).await?;
+                return Ok(());
+            }
+        };
+
+        let from = Identifier::from(secure_channel_info.their_identifier());
+        let return_route = m.return_route().clone();
+        let body = m.into_body()?;
+        let mut dec = Decoder::new(&body);
+        let req: RequestHeader = dec.decode()?;
+        trace! {
+            target: "oidc_authenticator",
+            from   = %from,
+            id     = %req.id(),
+            method = ?req.method(),
+            path   = %req.path(),
+            body   = %req.has_body(),
+            "request"
+        }
+        let res = match (req.method(), req.path()) {
+            (Some(Method::Post), "/") => {
+                let token: AuthenticateOidcToken = dec.decode()?;
+                match self
+                    .authenticator
+                    .authenticate(&token.access_token.0, &from)
+                    .await
+                {
+                    Ok(Either::Left(_)) => Response::ok().with_headers(&req).to_vec()?,
+                    Ok(Either::Right(error)) => Response::forbidden(&req, &error.0).to_vec()?,
+                    Err(err) => Response::internal_error(&req, &err.to_string()).to_vec()?,
+                }
+            }
+            _ => Response::unknown_path(&req).to_vec()?,
+        };
+        c.send(return_route, res).await
+    }
+}
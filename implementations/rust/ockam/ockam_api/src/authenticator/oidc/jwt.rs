@@ -0,0 +1,306 @@
+use p256::ecdsa::signature::Verifier;
+use rsa::pkcs1v15;
+use rsa::sha2::{Digest, Sha256, Sha384, Sha512};
+use rsa::{BigUint, RsaPublicKey};
+use serde::Deserialize;
+use serde_json::{Map, Value};
+
+/// Number of seconds of clock skew tolerated when checking the `exp` and `nbf` claims
+const CLOCK_SKEW_LEEWAY: u64 = 60;
+
+/// Signature algorithms which can be used to sign a token
+const SUPPORTED_ALGORITHMS: [&str; 4] = ["RS256", "RS384", "RS512", "ES256"];
+
+/// Reasons for rejecting a JSON Web Token
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum JwtError {
+    #[error("malformed token: {0}")]
+    Malformed(String),
+    #[error("unsupported signature algorithm: {0}")]
+    UnsupportedAlgorithm(String),
+    #[error("no signing key found for the token")]
+    UnknownKey,
+    #[error("invalid signature")]
+    InvalidSignature,
+    #[error("unexpected issuer: {0}")]
+    InvalidIssuer(String),
+    #[error("the token is not intended for this audience")]
+    InvalidAudience,
+    #[error("the token has already been used")]
+    AlreadyUsed,
+    #[error("the token has expired")]
+    Expired,
+    #[error("the token is not valid yet")]
+    NotYetValid,
+    #[error("missing claim: {0}")]
+    MissingClaim(String),
+    #[error("unexpected value for the claim: {0}")]
+    UnexpectedClaim(String),
+}
+
+/// A decoded JSON Web Token.
+///
+/// The signature and the claims of the token must be checked with [`Jwt::verify_signature`]
+/// and [`Jwt::verify_claims`] before the claims can be trusted.
+#[derive(Debug, Clone)]
+pub struct Jwt {
+    header: JwtHeader,
+    claims: Map<String, Value>,
+    signed_content: String,
+    signature: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct JwtHeader {
+    alg: String,
+    kid: Option<String>,
+}
+
+impl Jwt {
+    /// Decode a compact serialized JWT: `header.payload.signature`
+    pub fn decode(token: &str) -> Result<Jwt, JwtError> {
+        let token = token.trim();
+        let parts: Vec<&str> = token.split('.').collect();
+        let [header, payload, signature] = parts.as_slice() else {
+            return Err(JwtError::Malformed(
+                "a token must have 3 dot-separated parts".to_string(),
+            ));
+        };
+        let header: JwtHeader = serde_json::from_slice(&decode_part(header)?)
+            .map_err(|e| JwtError::Malformed(format!("invalid header: {e}")))?;
+        let claims: Map<String, Value> = serde_json::from_slice(&decode_part(payload)?)
+            .map_err(|e| JwtError::Malformed(format!("invalid payload: {e}")))?;
+        Ok(Jwt {
+            header,
+            claims,
+            signed_content: format!("{}.{}", parts[0], parts[1]),
+            signature: decode_part(signature)?,
+        })
+    }
+
+    /// Return the signature algorithm declared in the token header
+    pub fn algorithm(&self) -> &str {
+        &self.header.alg
+    }
+
+    /// Return the identifier of the key used to sign the token, if present
+    pub fn key_id(&self) -> Option<&str> {
+        self.header.kid.as_deref()
+    }
+
+    /// Return all the claims of the token
+    pub fn claims(&self) -> &Map<String, Value> {
+        &self.claims
+    }
+
+    /// Return the identifier of the token, used to reject the tokens which are presented twice.
+    /// This is the value of the `jti` claim or, if the token has no `jti` claim, a hash of
+    /// the token
+    pub fn jwt_id(&self) -> String {
+        match self.claim("jti").and_then(claim_value_as_string) {
+            Some(jti) => jti,
+            None => hex::encode(Sha256::digest(self.signed_content.as_bytes())),
+        }
+    }
+
+    /// Return the time after which the token is rejected, including the tolerated clock skew
+    pub fn expires_at(&self) -> Option<u64> {
+        self.claim("exp")
+            .and_then(|v| v.as_u64())
+            .map(|exp| exp.saturating_add(CLOCK_SKEW_LEEWAY))
+    }
+
+    /// Return the value of a claim.
+    /// Nested claims can be accessed with a dotted path, for example `realm_access.roles`
+    pub fn claim(&self, path: &str) -> Option<&Value> {
+        if let Some(value) = self.claims.get(path) {
+            return Some(value);
+        }
+        let mut segments = path.split('.');
+        let mut value = self.claims.get(segments.next()?)?;
+        for segment in segments {
+            value = value.as_object()?.get(segment)?;
+        }
+        Some(value)
+    }
+
+    /// Return the value of a claim as a string.
+    /// Numbers and booleans are converted to strings, and arrays are joined with commas
+    pub fn claim_as_string(&self, path: &str) -> Option<String> {
+        claim_value_as_string(self.claim(path)?)
+    }
+
+    /// Return true if a claim has the expected value.
+    /// If the claim is an array, one of its elements must have the expected value
+    pub fn claim_matches(&self, path: &str, expected: &str) -> bool {
+        match self.claim(path) {
+            Some(Value::Array(values)) => values
+                .iter()
+                .any(|v| claim_value_as_string(v).as_deref() == Some(expected)),
+            Some(value) => claim_value_as_string(value).as_deref() == Some(expected),
+            None => false,
+        }
+    }
+
+    /// Check that the token was signed by one of the keys of the key set
+    pub fn verify_signature(&self, jwks: &Jwks) -> Result<(), JwtError> {
+        let algorithm = self.algorithm();
+        if !SUPPORTED_ALGORITHMS.contains(&algorithm) {
+            return Err(JwtError::UnsupportedAlgorithm(algorithm.to_string()));
+        }
+        let candidates: Vec<&Jwk> = jwks
+            .keys
+            .iter()
+            .filter(|k| k.can_verify(algorithm))
+            .filter(|k| match (self.key_id(), &k.kid) {
+                (Some(kid), Some(key_kid)) => kid == key_kid,
+                (Some(_), None) => false,
+                (None, _) => true,
+            })
+            .collect();
+        if candidates.is_empty() {
+            return Err(JwtError::UnknownKey);
+        }
+        for key in candidates {
+            if key.verify(algorithm, self.signed_content.as_bytes(), &self.signature)? {
+                return Ok(());
+            }
+        }
+        Err(JwtError::InvalidSignature)
+    }
+
+    /// Check the registered claims of the token: issuer, audience and validity period
+    pub fn verify_claims(&self, issuer: &str, audience: &str, now: u64) -> Result<(), JwtError> {
+        match self.claim("iss").and_then(|v| v.as_str()) {
+            Some(iss) if iss.trim_end_matches('/') == issuer.trim_end_matches('/') => {}
+            Some(iss) => return Err(JwtError::InvalidIssuer(iss.to_string())),
+            None => return Err(JwtError::MissingClaim("iss".to_string())),
+        }
+
+        let matches = match self.claim("aud") {
+            Some(Value::String(aud)) => aud == audience,
+            Some(Value::Array(auds)) => auds.iter().any(|a| a.as_str() == Some(audience)),
+            _ => false,
+        };
+        if !matches {
+            return Err(JwtError::InvalidAudience);
+        }
+
+        match self.expires_at() {
+            Some(expires_at) if expires_at < now => return Err(JwtError::Expired),
+            Some(_) => {}
+            None => return Err(JwtError::MissingClaim("exp".to_string())),
+        }
+
+        if let Some(nbf) = self.claim("nbf").and_then(|v| v.as_u64()) {
+            if nbf > now.saturating_add(CLOCK_SKEW_LEEWAY) {
+                return Err(JwtError::NotYetValid);
+            }
+        }
+        Ok(())
+    }
+}
+
+/// A JSON Web Key Set, as published by an identity provider on its `jwks_uri` endpoint
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct Jwks {
+    pub keys: Vec<Jwk>,
+}
+
+/// A public JSON Web Key. Only RSA keys and P-256 elliptic curve keys are supported
+#[derive(Debug, Clone, Deserialize)]
+pub struct Jwk {
+    pub kty: String,
+    pub kid: Option<String>,
+    pub alg: Option<String>,
+    #[serde(rename = "use")]
+    pub usage: Option<String>,
+    // RSA parameters
+    pub n: Option<String>,
+    pub e: Option<String>,
+    // Elliptic curve parameters
+    pub crv: Option<String>,
+    pub x: Option<String>,
+    pub y: Option<String>,
+}
+
+impl Jwk {
+    /// Return true if this key can be used to verify a signature made with the given algorithm
+    fn can_verify(&self, algorithm: &str) -> bool {
+        if self.usage.as_deref().is_some_and(|u| u != "sig") {
+            return false;
+        }
+        if self.alg.as_deref().is_some_and(|a| a != algorithm) {
+            return false;
+        }
+        match algorithm {
+            "RS256" | "RS384" | "RS512" => self.kty == "RSA",
+            "ES256" => self.kty == "EC" && self.crv.as_deref() == Some("P-256"),
+            _ => false,
+        }
+    }
+
+    /// Verify a signature with this key.
+    /// Return false if the signature does not match the content
+    fn verify(&self, algorithm: &str, content: &[u8], signature: &[u8]) -> Result<bool, JwtError> {
+        match algorithm {
+            "RS256" => Ok(self.verify_rsa::<Sha256>(content, signature)?),
+            "RS384" => Ok(self.verify_rsa::<Sha384>(content, signature)?),
+            "RS512" => Ok(self.verify_rsa::<Sha512>(content, signature)?),
+            "ES256" => {
+                let mut point = vec![0x04];
+                point.extend(decode_part(self.parameter(&self.x, "x")?)?);
+                point.extend(decode_part(self.parameter(&self.y, "y")?)?);
+                let key = p256::ecdsa::VerifyingKey::from_sec1_bytes(&point)
+                    .map_err(|e| JwtError::Malformed(format!("invalid EC key: {e}")))?;
+                let Ok(signature) = p256::ecdsa::Signature::from_slice(signature) else {
+                    return Ok(false);
+                };
+                Ok(key.verify(content, &signature).is_ok())
+            }
+            other => Err(JwtError::UnsupportedAlgorithm(other.to_string())),
+        }
+    }
+
+    fn verify_rsa<D>(&self, content: &[u8], signature: &[u8]) -> Result<bool, JwtError>
+    where
+        D: rsa::sha2::Digest + rsa::pkcs8::AssociatedOid,
+    {
+        let n = BigUint::from_bytes_be(&decode_part(self.parameter(&self.n, "n")?)?);
+        let e = BigUint::from_bytes_be(&decode_part(self.parameter(&self.e, "e")?)?);
+        let key = RsaPublicKey::new(n, e)
+            .map_err(|e| JwtError::Malformed(format!("invalid RSA key: {e}")))?;
+        let Ok(signature) = pkcs1v15::Signature::try_from(signature) else {
+            return Ok(false);
+        };
+        Ok(pkcs1v15::VerifyingKey::<D>::new(key)
+            .verify(content, &signature)
+            .is_ok())
+    }
+
+    fn parameter<'a>(&self, value: &'a Option<String>, name: &str) -> Result<&'a str, JwtError> {
+        value
+            .as_deref()
+            .ok_or_else(|| JwtError::Malformed(format!("missing key parameter '{name}'")))
+    }
+}
+
+fn decode_part(part: &str) -> Result<Vec<u8>, JwtError> {
+    base64_url::decode(part).map_err(|e| JwtError::Malformed(e.to_string()))
+}
+
+fn claim_value_as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Array(values) => Some(
+            values
+                .iter()
+                .filter_map(claim_value_as_string)
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        Value::Null | Value::Object(_) => None,
+    }
+}
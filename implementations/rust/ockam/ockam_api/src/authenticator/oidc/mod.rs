@@ -0,0 +1,7 @@
+mod authenticator;
+mod authenticator_worker;
+mod jwt;
+
+pub use authenticator::*;
+pub use authenticator_worker::*;
+pub use jwt::*;
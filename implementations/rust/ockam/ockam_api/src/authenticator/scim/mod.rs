@@ -0,0 +1,7 @@
+mod provisioner;
+mod server;
+mod types;
+
+pub use provisioner::*;
+pub use server::*;
+pub use types::*;
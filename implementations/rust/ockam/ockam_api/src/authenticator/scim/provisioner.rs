@@ -0,0 +1,961 @@
+use chrono::{DateTime, SecondsFormat};
+use core::future::Future;
+use core::time::Duration;
+use either::Either;
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::collections::{BTreeMap, BTreeSet};
+use ockam_core::compat::str::FromStr;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use serde_json::{json, Map, Value};
+
+use crate::authenticator::audit::{AuditAction, AuthorityAuditLog};
+use crate::authenticator::scim::{
+    attribute_as_bool, attribute_as_string, get_attribute, remove_attribute, set_attribute,
+    ScimError, ScimFilter, ScimPatchOp, ScimPatchRequest, SCIM_BASE_PATH, SCIM_GROUP_SCHEMA,
+    SCIM_LIST_RESPONSE_SCHEMA, SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA, SCIM_USER_SCHEMA,
+};
+use crate::authenticator::{
+    AuthorityMember, AuthorityMemberChange, AuthorityMembersRepository, AuthorityScimRepository,
+    ScimGroupRecord, ScimUserRecord,
+};
+use crate::authority_node::ScimConfiguration;
+
+/// Maximum number of resources returned in a list response
+pub const MAX_SCIM_RESULTS: usize = 1000;
+
+/// Number of seconds after which the lock serializing the SCIM modifications expires,
+/// in case the authority node holding it stopped without releasing it
+const SCIM_LOCK_EXPIRATION: u64 = 60;
+
+/// Time to wait before trying again to acquire the lock serializing the SCIM modifications
+const SCIM_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+pub type ScimResult<T> = Either<T, ScimError>;
+
+/// Return early with a SCIM error
+macro_rules! scim_try {
+    ($e:expr) => {
+        match $e {
+            Ok(value) => value,
+            Err(error) => return Ok(Either::Right(error)),
+        }
+    };
+}
+
+/// This provisioner manages the users and groups pushed by a SCIM client, and keeps the
+/// corresponding project members up to date:
+///
+///  - the user name of a user is the identifier of a member
+///  - the attributes of a member are taken from the attributes of the user, and from the names
+///    of the groups containing the user
+///  - a deactivated user is a suspended member
+///
+/// Only the members created with SCIM are modified or deleted by the provisioner. An identity
+/// which is already a member, for example added by an enroller, can't be provisioned with SCIM.
+///
+/// Modifications are serialized, since changing a group modifies several members, with a lock
+/// stored in the database and shared by all the replicas of the authority node.
+///
+pub struct ScimProvisioner {
+    authority: Identifier,
+    members: Arc<dyn AuthorityMembersRepository>,
+    repository: Arc<dyn AuthorityScimRepository>,
+    audit_log: Arc<AuthorityAuditLog>,
+    configuration: ScimConfiguration,
+}
+
+/// Public functions implementing the SCIM operations
+impl ScimProvisioner {
+    pub fn new(
+        authority: &Identifier,
+        members: Arc<dyn AuthorityMembersRepository>,
+        repository: Arc<dyn AuthorityScimRepository>,
+        audit_log: Arc<AuthorityAuditLog>,
+        configuration: &ScimConfiguration,
+    ) -> Self {
+        Self {
+            authority: authority.clone(),
+            members,
+            repository,
+            audit_log,
+            configuration: configuration.clone(),
+        }
+    }
+
+    /// Return the bearer token that SCIM clients must present
+    pub fn bearer_token(&self) -> &str {
+        &self.configuration.bearer_token
+    }
+
+    /// Return the features supported by this service
+    pub fn service_provider_config(&self) -> Value {
+        json!({
+            "schemas": [SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA],
+            "patch": { "supported": true },
+            "bulk": { "supported": false, "maxOperations": 0, "maxPayloadSize": 0 },
+            "filter": { "supported": true, "maxResults": MAX_SCIM_RESULTS },
+            "changePassword": { "supported": false },
+            "sort": { "supported": false },
+            "etag": { "supported": false },
+            "authenticationSchemes": [{
+                "type": "oauthbearertoken",
+                "name": "OAuth Bearer Token",
+                "description": "Authentication with the bearer token configured on the Authority node"
+            }]
+        })
+    }
+
+    #[instrument(skip_all)]
+    pub async fn create_user(&self, body: Value) -> Result<ScimResult<Value>> {
+        let resource = scim_try!(user_resource(body));
+        let identifier = scim_try!(user_name(&resource));
+
+        self.locked(async {
+            if self
+                .repository
+                .get_user(&self.authority, &identifier)
+                .await?
+                .is_some()
+            {
+                return Ok(Either::Right(ScimError::uniqueness(format!(
+                    "The user {identifier} already exists"
+                ))));
+            }
+            if let Some(member) = self
+                .members
+                .get_member(&self.authority, &identifier)
+                .await?
+            {
+                return Ok(Either::Right(ScimError::uniqueness(format!(
+                    "{identifier} is already a member, added by {}",
+                    member.added_by()
+                ))));
+            }
+
+            let now = now()?;
+            let user = ScimUserRecord {
+                identifier: identifier.clone(),
+                resource,
+                created_at: now,
+                updated_at: now,
+            };
+            self.repository.store_user(&self.authority, user).await?;
+            self.update_member(&identifier).await?;
+
+            info!("Provisioned the user {} with SCIM", identifier);
+            self.user_response(&identifier).await
+        })
+        .await
+    }
+
+    pub async fn get_user(&self, id: &str) -> Result<ScimResult<Value>> {
+        let identifier = scim_try!(user_identifier(id));
+        self.user_response(&identifier).await
+    }
+
+    pub async fn list_users(
+        &self,
+        filter: Option<&str>,
+        start_index: Option<usize>,
+        count: Option<usize>,
+    ) -> Result<ScimResult<Value>> {
+        let filter = scim_try!(filter.map(ScimFilter::parse).transpose());
+        let mut users = vec![];
+        for user in self.repository.get_users(&self.authority).await? {
+            let matches = match &filter {
+                None => true,
+                Some(f) if f.is_on("id") || f.is_on("userName") => {
+                    user.identifier.to_string() == f.value
+                }
+                Some(f) => {
+                    get_attribute(&user.resource, &f.attribute).and_then(attribute_as_string)
+                        == Some(f.value.clone())
+                }
+            };
+            if matches {
+                let groups = self
+                    .repository
+                    .get_member_groups(&self.authority, &user.identifier)
+                    .await?;
+                users.push(user_json(&user, &groups));
+            }
+        }
+        Ok(Either::Left(list_response(users, start_index, count)))
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    pub async fn replace_user(&self, id: &str, body: Value) -> Result<ScimResult<Value>> {
+        let identifier = scim_try!(user_identifier(id));
+        let mut resource = scim_try!(user_resource(body));
+        match get_attribute(&resource, "userName") {
+            Some(_) => scim_try!(check_user_name(&resource, &identifier)),
+            None => scim_try!(set_attribute(
+                &mut resource,
+                "userName",
+                Value::String(identifier.to_string())
+            )),
+        }
+
+        self.locked(async {
+            let Some(existing) = self
+                .repository
+                .get_user(&self.authority, &identifier)
+                .await?
+            else {
+                return Ok(Either::Right(user_not_found(id)));
+            };
+            let user = ScimUserRecord {
+                resource,
+                updated_at: now()?,
+                ..existing
+            };
+            self.repository.store_user(&self.authority, user).await?;
+            self.update_member(&identifier).await?;
+
+            info!("Replaced the user {} with SCIM", identifier);
+            self.user_response(&identifier).await
+        })
+        .await
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    pub async fn patch_user(&self, id: &str, body: Value) -> Result<ScimResult<Value>> {
+        let identifier = scim_try!(user_identifier(id));
+        let patch = scim_try!(patch_request(body));
+
+        self.locked(async {
+            let Some(existing) = self
+                .repository
+                .get_user(&self.authority, &identifier)
+                .await?
+            else {
+                return Ok(Either::Right(user_not_found(id)));
+            };
+            let mut resource = existing.resource.clone();
+            for operation in patch.operations {
+                let kind = scim_try!(operation.kind());
+                match (kind, operation.path.as_deref()) {
+                    (ScimPatchOp::Remove, None) => {
+                        return Ok(Either::Right(ScimError::new(
+                            400,
+                            Some("noTarget"),
+                            "A remove operation requires a path",
+                        )))
+                    }
+                    (ScimPatchOp::Remove, Some(path)) => {
+                        scim_try!(check_simple_path(path));
+                        remove_attribute(&mut resource, path);
+                    }
+                    (_, None) => {
+                        let Some(Value::Object(values)) = operation.value else {
+                            return Ok(Either::Right(ScimError::invalid_value(
+                                "An operation without a path requires an object value",
+                            )));
+                        };
+                        for (path, value) in values {
+                            scim_try!(check_simple_path(&path));
+                            scim_try!(set_attribute(&mut resource, &path, value));
+                        }
+                    }
+                    (_, Some(path)) => {
+                        scim_try!(check_simple_path(path));
+                        let Some(value) = operation.value else {
+                            return Ok(Either::Right(ScimError::invalid_value(format!(
+                                "A value is required to modify {path}"
+                            ))));
+                        };
+                        scim_try!(set_attribute(&mut resource, path, value));
+                    }
+                }
+            }
+            let resource = scim_try!(user_resource(resource));
+            scim_try!(check_user_name(&resource, &identifier));
+
+            let user = ScimUserRecord {
+                resource,
+                updated_at: now()?,
+                ..existing
+            };
+            self.repository.store_user(&self.authority, user).await?;
+            self.update_member(&identifier).await?;
+
+            info!("Patched the user {} with SCIM", identifier);
+            self.user_response(&identifier).await
+        })
+        .await
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    pub async fn delete_user(&self, id: &str) -> Result<ScimResult<()>> {
+        let identifier = scim_try!(user_identifier(id));
+
+        self.locked(async {
+            if !self
+                .repository
+                .delete_user(&self.authority, &identifier)
+                .await?
+            {
+                return Ok(Either::Right(user_not_found(id)));
+            }
+            let member = self
+                .members
+                .get_member(&self.authority, &identifier)
+                .await?;
+            if !member.is_some_and(|m| self.is_managed(&m)) {
+                warn!(
+                    "The member {} was not created with SCIM, it is not deleted",
+                    identifier
+                );
+                return Ok(Either::Left(()));
+            }
+            self.audit_log
+                .record_change(
+                    &self.authority,
+                    AuditAction::DeleteMember,
+                    Some(&identifier),
+                    BTreeMap::new(),
+                    AuthorityMemberChange::Delete(identifier.clone()),
+                )
+                .await?;
+
+            info!("Deprovisioned the user {} with SCIM", identifier);
+            Ok(Either::Left(()))
+        })
+        .await
+    }
+
+    #[instrument(skip_all)]
+    pub async fn create_group(&self, body: Value) -> Result<ScimResult<Value>> {
+        self.locked(async {
+            let now = now()?;
+            let group = ScimGroupRecord {
+                id: hex::encode(rand::random::<[u8; 16]>()),
+                display_name: String::new(),
+                external_id: None,
+                members: vec![],
+                created_at: now,
+                updated_at: now,
+            };
+            let group = scim_try!(self.group_from_json(group, &body).await?);
+
+            self.repository
+                .store_group(&self.authority, group.clone())
+                .await?;
+            self.update_members(&group.members).await?;
+
+            info!("Provisioned the group {} with SCIM", group.display_name);
+            Ok(Either::Left(group_json(&group)))
+        })
+        .await
+    }
+
+    pub async fn get_group(&self, id: &str) -> Result<ScimResult<Value>> {
+        match self.repository.get_group(&self.authority, id).await? {
+            Some(group) => Ok(Either::Left(group_json(&group))),
+            None => Ok(Either::Right(group_not_found(id))),
+        }
+    }
+
+    pub async fn list_groups(
+        &self,
+        filter: Option<&str>,
+        start_index: Option<usize>,
+        count: Option<usize>,
+    ) -> Result<ScimResult<Value>> {
+        let filter = scim_try!(filter.map(ScimFilter::parse).transpose());
+        let groups = self
+            .repository
+            .get_groups(&self.authority)
+            .await?
+            .into_iter()
+            .filter(|group| match &filter {
+                None => true,
+                Some(f) if f.is_on("id") => group.id == f.value,
+                Some(f) if f.is_on("displayName") => group.display_name == f.value,
+                Some(f) if f.is_on("externalId") => group.external_id.as_ref() == Some(&f.value),
+                Some(f) if f.is_on("members.value") || f.is_on("members") => group
+                    .members
+                    .iter()
+                    .any(|member| member.to_string() == f.value),
+                Some(_) => false,
+            })
+            .map(|group| group_json(&group))
+            .collect();
+        Ok(Either::Left(list_response(groups, start_index, count)))
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    pub async fn replace_group(&self, id: &str, body: Value) -> Result<ScimResult<Value>> {
+        self.locked(async {
+            let Some(existing) = self.repository.get_group(&self.authority, id).await? else {
+                return Ok(Either::Right(group_not_found(id)));
+            };
+            let previous_members = existing.members.clone();
+            let group = ScimGroupRecord {
+                external_id: None,
+                members: vec![],
+                updated_at: now()?,
+                ..existing
+            };
+            let group = scim_try!(self.group_from_json(group, &body).await?);
+
+            self.repository
+                .store_group(&self.authority, group.clone())
+                .await?;
+            self.update_members(&[previous_members, group.members.clone()].concat())
+                .await?;
+
+            info!("Replaced the group {} with SCIM", group.display_name);
+            Ok(Either::Left(group_json(&group)))
+        })
+        .await
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    pub async fn patch_group(&self, id: &str, body: Value) -> Result<ScimResult<Value>> {
+        let patch = scim_try!(patch_request(body));
+
+        self.locked(async {
+            let Some(existing) = self.repository.get_group(&self.authority, id).await? else {
+                return Ok(Either::Right(group_not_found(id)));
+            };
+            let previous_members = existing.members.clone();
+            let mut group = ScimGroupRecord {
+                updated_at: now()?,
+                ..existing
+            };
+            for operation in patch.operations {
+                let kind = scim_try!(operation.kind());
+                let value = operation.value.unwrap_or(Value::Null);
+                match operation.path.as_deref() {
+                    None => {
+                        let Value::Object(values) = value else {
+                            return Ok(Either::Right(ScimError::invalid_value(
+                                "An operation without a path requires an object value",
+                            )));
+                        };
+                        for (path, value) in values {
+                            scim_try!(
+                                self.patch_group_attribute(&mut group, kind, &path, value)
+                                    .await?
+                            );
+                        }
+                    }
+                    Some(path) => {
+                        scim_try!(
+                            self.patch_group_attribute(&mut group, kind, path, value)
+                                .await?
+                        )
+                    }
+                }
+            }
+            if group.display_name.is_empty() {
+                return Ok(Either::Right(ScimError::invalid_value(
+                    "A group must have a displayName",
+                )));
+            }
+
+            self.repository
+                .store_group(&self.authority, group.clone())
+                .await?;
+            self.update_members(&[previous_members, group.members.clone()].concat())
+                .await?;
+
+            info!("Patched the group {} with SCIM", group.display_name);
+            Ok(Either::Left(group_json(&group)))
+        })
+        .await
+    }
+
+    #[instrument(skip_all, fields(id = %id))]
+    pub async fn delete_group(&self, id: &str) -> Result<ScimResult<()>> {
+        self.locked(async {
+            let Some(existing) = self.repository.get_group(&self.authority, id).await? else {
+                return Ok(Either::Right(group_not_found(id)));
+            };
+            self.repository.delete_group(&self.authority, id).await?;
+            self.update_members(&existing.members).await?;
+
+            info!(
+                "Deprovisioned the group {} with SCIM",
+                existing.display_name
+            );
+            Ok(Either::Left(()))
+        })
+        .await
+    }
+}
+
+/// Private functions
+impl ScimProvisioner {
+    /// Run a modification while holding the lock shared by all the replicas of the authority node
+    async fn locked<T>(&self, f: impl Future<Output = Result<T>>) -> Result<T> {
+        let holder = hex::encode(rand::random::<[u8; 16]>());
+        loop {
+            let now = now()?;
+            if self
+                .repository
+                .acquire_lock(&self.authority, &holder, now + SCIM_LOCK_EXPIRATION, now)
+                .await?
+            {
+                break;
+            }
+            tokio::time::sleep(SCIM_LOCK_RETRY_INTERVAL).await;
+        }
+        let result = f.await;
+        self.repository
+            .release_lock(&self.authority, &holder)
+            .await?;
+        result
+    }
+
+    /// Return true if a member was created by this provisioner
+    fn is_managed(&self, member: &AuthorityMember) -> bool {
+        !member.is_pre_trusted() && member.added_by() == &self.authority
+    }
+
+    /// Set the attributes and the suspension of the member corresponding to a user
+    async fn update_member(&self, identifier: &Identifier) -> Result<()> {
+        let Some(user) = self
+            .repository
+            .get_user(&self.authority, identifier)
+            .await?
+        else {
+            return Ok(());
+        };
+        let existing = self.members.get_member(&self.authority, identifier).await?;
+        if existing.as_ref().is_some_and(|m| !self.is_managed(m)) {
+            warn!(
+                "The member {} was not created with SCIM, it can't be modified with SCIM",
+                identifier
+            );
+            return Ok(());
+        }
+
+        let groups = self
+            .repository
+            .get_member_groups(&self.authority, identifier)
+            .await?;
+        let attributes = self.member_attributes(&user, &groups);
+        let attrs: BTreeMap<Vec<u8>, Vec<u8>> = attributes
+            .iter()
+            .map(|(k, v)| (k.as_bytes().to_vec(), v.as_bytes().to_vec()))
+            .collect();
+
+        let was_suspended = existing.as_ref().is_some_and(|m| m.is_suspended());
+        if existing.as_ref().map(|m| m.attributes()) != Some(&attrs) {
+            let member = AuthorityMember::new(
+                identifier.clone(),
+                attrs,
+                self.authority.clone(),
+                now()?,
+                false,
+            )
+            .with_expires_at(existing.as_ref().and_then(|m| m.expires_at()))
+            .with_suspended(was_suspended);
+            self.audit_log
+                .record_change(
+                    &self.authority,
+                    AuditAction::AddMember,
+                    Some(identifier),
+                    attributes,
+                    AuthorityMemberChange::Add(member),
+                )
+                .await?;
+        }
+
+        let is_active = get_attribute(&user.resource, "active")
+            .and_then(attribute_as_bool)
+            .unwrap_or(true);
+        if is_active == was_suspended {
+            let action = if is_active {
+                AuditAction::ResumeMember
+            } else {
+                AuditAction::SuspendMember
+            };
+            self.audit_log
+                .record_change(
+                    &self.authority,
+                    action,
+                    Some(identifier),
+                    BTreeMap::new(),
+                    AuthorityMemberChange::SetSuspended(identifier.clone(), !is_active),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// Update the members corresponding to a list of users, for example after a group change
+    async fn update_members(&self, identifiers: &[Identifier]) -> Result<()> {
+        let identifiers: BTreeSet<&Identifier> = identifiers.iter().collect();
+        for identifier in identifiers {
+            self.update_member(identifier).await?;
+        }
+        Ok(())
+    }
+
+    /// Return the attributes of the member corresponding to a user
+    fn member_attributes(
+        &self,
+        user: &ScimUserRecord,
+        groups: &[ScimGroupRecord],
+    ) -> BTreeMap<String, String> {
+        let mut attributes: BTreeMap<String, String> = self
+            .configuration
+            .attributes
+            .iter()
+            .filter_map(|(scim_attribute, attribute)| {
+                get_attribute(&user.resource, scim_attribute)
+                    .and_then(attribute_as_string)
+                    .map(|value| (attribute.clone(), value))
+            })
+            .collect();
+        if let Some(groups_attribute) = &self.configuration.groups_attribute {
+            if !groups.is_empty() {
+                let names: BTreeSet<&str> =
+                    groups.iter().map(|g| g.display_name.as_str()).collect();
+                attributes.insert(
+                    groups_attribute.clone(),
+                    names.into_iter().collect::<Vec<_>>().join(","),
+                );
+            }
+        }
+        attributes
+    }
+
+    /// Return the JSON representation of a user
+    async fn user_response(&self, identifier: &Identifier) -> Result<ScimResult<Value>> {
+        let Some(user) = self
+            .repository
+            .get_user(&self.authority, identifier)
+            .await?
+        else {
+            return Ok(Either::Right(user_not_found(&identifier.to_string())));
+        };
+        let groups = self
+            .repository
+            .get_member_groups(&self.authority, identifier)
+            .await?;
+        Ok(Either::Left(user_json(&user, &groups)))
+    }
+
+    /// Set the attributes of a group from its JSON representation
+    async fn group_from_json(
+        &self,
+        mut group: ScimGroupRecord,
+        body: &Value,
+    ) -> Result<core::result::Result<ScimGroupRecord, ScimError>> {
+        if !body.is_object() {
+            return Ok(Err(ScimError::invalid_syntax(
+                "A group must be a JSON object",
+            )));
+        }
+        group.display_name = match get_attribute(body, "displayName").and_then(|v| v.as_str()) {
+            Some(display_name) if !display_name.is_empty() => display_name.to_string(),
+            _ => {
+                return Ok(Err(ScimError::invalid_value(
+                    "A group must have a displayName",
+                )))
+            }
+        };
+        group.external_id = get_attribute(body, "externalId")
+            .and_then(|v| v.as_str())
+            .map(|v| v.to_string());
+        if let Some(members) = get_attribute(body, "members") {
+            group.members = match self.group_members(members).await? {
+                Ok(members) => members,
+                Err(error) => return Ok(Err(error)),
+            };
+        }
+        Ok(Ok(group))
+    }
+
+    /// Apply a PATCH operation to an attribute of a group
+    async fn patch_group_attribute(
+        &self,
+        group: &mut ScimGroupRecord,
+        kind: ScimPatchOp,
+        path: &str,
+        value: Value,
+    ) -> Result<core::result::Result<(), ScimError>> {
+        if path.eq_ignore_ascii_case("members") {
+            let members = match (kind, &value) {
+                (ScimPatchOp::Remove, Value::Null) => group.members.clone(),
+                _ => match self.group_members(&value).await? {
+                    Ok(members) => members,
+                    Err(error) => return Ok(Err(error)),
+                },
+            };
+            match kind {
+                ScimPatchOp::Add => {
+                    for member in members {
+                        if !group.members.contains(&member) {
+                            group.members.push(member);
+                        }
+                    }
+                }
+                ScimPatchOp::Replace => group.members = members,
+                ScimPatchOp::Remove => group.members.retain(|m| !members.contains(m)),
+            }
+            return Ok(Ok(()));
+        }
+
+        // remove a single member with a path like members[value eq "I123..."]
+        if let Some(filter) = member_filter(path) {
+            if kind != ScimPatchOp::Remove {
+                return Ok(Err(ScimError::invalid_path(format!(
+                    "Unsupported path: {path}"
+                ))));
+            }
+            let filter = match ScimFilter::parse(filter) {
+                Ok(filter) if filter.is_on("value") => filter,
+                _ => {
+                    return Ok(Err(ScimError::invalid_filter(format!(
+                        "Unsupported path: {path}"
+                    ))))
+                }
+            };
+            group.members.retain(|m| m.to_string() != filter.value);
+            return Ok(Ok(()));
+        }
+
+        let string_value = || match &value {
+            Value::String(s) if !s.is_empty() => Ok(s.clone()),
+            _ => Err(ScimError::invalid_value(format!(
+                "A non-empty string is required for {path}"
+            ))),
+        };
+        match kind {
+            _ if path.eq_ignore_ascii_case("id") => {}
+            ScimPatchOp::Remove if path.eq_ignore_ascii_case("externalId") => {
+                group.external_id = None
+            }
+            ScimPatchOp::Add | ScimPatchOp::Replace if path.eq_ignore_ascii_case("externalId") => {
+                match string_value() {
+                    Ok(external_id) => group.external_id = Some(external_id),
+                    Err(error) => return Ok(Err(error)),
+                }
+            }
+            ScimPatchOp::Add | ScimPatchOp::Replace if path.eq_ignore_ascii_case("displayName") => {
+                match string_value() {
+                    Ok(display_name) => group.display_name = display_name,
+                    Err(error) => return Ok(Err(error)),
+                }
+            }
+            _ => {
+                return Ok(Err(ScimError::invalid_path(format!(
+                    "Unsupported path: {path}"
+                ))))
+            }
+        }
+        Ok(Ok(()))
+    }
+
+    /// Return the identifiers of the users referenced by the `members` attribute of a group.
+    /// All the users must exist
+    async fn group_members(
+        &self,
+        members: &Value,
+    ) -> Result<core::result::Result<Vec<Identifier>, ScimError>> {
+        let Value::Array(members) = members else {
+            return Ok(Err(ScimError::invalid_value(
+                "The members of a group must be an array",
+            )));
+        };
+        let mut identifiers = vec![];
+        for member in members {
+            let Some(value) = get_attribute(member, "value").and_then(|v| v.as_str()) else {
+                return Ok(Err(ScimError::invalid_value(
+                    "The members of a group must have a value",
+                )));
+            };
+            let identifier = match Identifier::from_str(value) {
+                Ok(identifier) => identifier,
+                Err(_) => {
+                    return Ok(Err(ScimError::invalid_value(format!(
+                        "Unknown user: {value}"
+                    ))))
+                }
+            };
+            if self
+                .repository
+                .get_user(&self.authority, &identifier)
+                .await?
+                .is_none()
+            {
+                return Ok(Err(ScimError::invalid_value(format!(
+                    "Unknown user: {value}"
+                ))));
+            }
+            if !identifiers.contains(&identifier) {
+                identifiers.push(identifier);
+            }
+        }
+        Ok(Ok(identifiers))
+    }
+}
+
+/// Validate the JSON representation of a user provided by a client, and remove
+/// the attributes which are managed by the Authority
+fn user_resource(body: Value) -> core::result::Result<Value, ScimError> {
+    let Value::Object(mut object) = body else {
+        return Err(ScimError::invalid_syntax("A user must be a JSON object"));
+    };
+    object.retain(|k, _| {
+        !["id", "meta", "groups"]
+            .iter()
+            .any(|read_only| k.eq_ignore_ascii_case(read_only))
+    });
+    Ok(Value::Object(object))
+}
+
+/// Return the identifier used as the user name of a user
+fn user_name(resource: &Value) -> core::result::Result<Identifier, ScimError> {
+    get_attribute(resource, "userName")
+        .and_then(|v| v.as_str())
+        .and_then(|v| Identifier::from_str(v).ok())
+        .ok_or_else(|| {
+            ScimError::invalid_value("The userName must be the identifier of an Ockam identity")
+        })
+}
+
+/// Check that the user name of a user has not been modified
+fn check_user_name(
+    resource: &Value,
+    identifier: &Identifier,
+) -> core::result::Result<(), ScimError> {
+    if get_attribute(resource, "userName").and_then(|v| v.as_str())
+        != Some(identifier.to_string().as_str())
+    {
+        return Err(ScimError::mutability(
+            "The userName of a user can't be modified",
+        ));
+    }
+    Ok(())
+}
+
+/// Return the identifier of a user from the id used in a URL.
+/// An invalid id is reported as a missing user
+fn user_identifier(id: &str) -> core::result::Result<Identifier, ScimError> {
+    Identifier::from_str(id).map_err(|_| user_not_found(id))
+}
+
+/// Paths with value filters, like `emails[type eq "work"].value`, are not supported
+/// for the attributes of a user
+fn check_simple_path(path: &str) -> core::result::Result<(), ScimError> {
+    if path.contains('[') {
+        return Err(ScimError::invalid_path(format!("Unsupported path: {path}")));
+    }
+    Ok(())
+}
+
+/// Return the filter of a path like `members[value eq "I123..."]`
+fn member_filter(path: &str) -> Option<&str> {
+    let (attribute, rest) = path.split_once('[')?;
+    if !attribute.eq_ignore_ascii_case("members") {
+        return None;
+    }
+    rest.strip_suffix(']')
+}
+
+fn patch_request(body: Value) -> core::result::Result<ScimPatchRequest, ScimError> {
+    serde_json::from_value(body)
+        .map_err(|e| ScimError::invalid_syntax(format!("Invalid PATCH request: {e}")))
+}
+
+fn user_not_found(id: &str) -> ScimError {
+    ScimError::not_found(format!("User {id} not found"))
+}
+
+fn group_not_found(id: &str) -> ScimError {
+    ScimError::not_found(format!("Group {id} not found"))
+}
+
+fn user_json(user: &ScimUserRecord, groups: &[ScimGroupRecord]) -> Value {
+    let mut object = user.resource.as_object().cloned().unwrap_or_default();
+    let id = user.identifier.to_string();
+    object
+        .entry("schemas")
+        .or_insert_with(|| json!([SCIM_USER_SCHEMA]));
+    object.entry("active").or_insert_with(|| Value::Bool(true));
+    object.insert("id".to_string(), Value::String(id.clone()));
+    object.insert(
+        "groups".to_string(),
+        groups
+            .iter()
+            .map(|g| json!({ "value": g.id, "display": g.display_name }))
+            .collect(),
+    );
+    object.insert(
+        "meta".to_string(),
+        meta("User", &id, user.created_at, user.updated_at),
+    );
+    Value::Object(object)
+}
+
+fn group_json(group: &ScimGroupRecord) -> Value {
+    let mut object = Map::new();
+    object.insert("schemas".to_string(), json!([SCIM_GROUP_SCHEMA]));
+    object.insert("id".to_string(), Value::String(group.id.clone()));
+    object.insert(
+        "displayName".to_string(),
+        Value::String(group.display_name.clone()),
+    );
+    if let Some(external_id) = &group.external_id {
+        object.insert("externalId".to_string(), Value::String(external_id.clone()));
+    }
+    object.insert(
+        "members".to_string(),
+        group
+            .members
+            .iter()
+            .map(|m| json!({ "value": m.to_string() }))
+            .collect(),
+    );
+    object.insert(
+        "meta".to_string(),
+        meta("Group", &group.id, group.created_at, group.updated_at),
+    );
+    Value::Object(object)
+}
+
+fn meta(
+    resource_type: &str,
+    id: &str,
+    created_at: TimestampInSeconds,
+    updated_at: TimestampInSeconds,
+) -> Value {
+    json!({
+        "resourceType": resource_type,
+        "created": date_time(created_at),
+        "lastModified": date_time(updated_at),
+        "location": format!("{SCIM_BASE_PATH}/{resource_type}s/{id}"),
+    })
+}
+
+fn date_time(timestamp: TimestampInSeconds) -> Option<String> {
+    DateTime::from_timestamp(timestamp.0 as i64, 0)
+        .map(|d| d.to_rfc3339_opts(SecondsFormat::Secs, true))
+}
+
+/// Return a page of resources. The start index is 1-based
+fn list_response(resources: Vec<Value>, start_index: Option<usize>, count: Option<usize>) -> Value {
+    let total_results = resources.len();
+    let start_index = start_index.unwrap_or(1).max(1);
+    let count = count.unwrap_or(MAX_SCIM_RESULTS).min(MAX_SCIM_RESULTS);
+    let page: Vec<Value> = resources
+        .into_iter()
+        .skip(start_index - 1)
+        .take(count)
+        .collect();
+    json!({
+        "schemas": [SCIM_LIST_RESPONSE_SCHEMA],
+        "totalResults": total_results,
+        "startIndex": start_index,
+        "itemsPerPage": page.len(),
+        "Resources": page,
+    })
+}
@@ -0,0 +1,296 @@
+use std::convert::Infallible;
+use std::net::SocketAddr;
+use std::sync::Arc;
+
+use either::Either;
+use http_body_util::combinators::BoxBody;
+use http_body_util::{BodyExt, Full, Limited};
+use hyper::body::Bytes;
+use hyper::header::{AUTHORIZATION, CONTENT_TYPE, LOCATION};
+use hyper::server::conn::http1;
+use hyper::service::service_fn;
+use hyper::{Method, Request, Response, StatusCode};
+use hyper_util::rt::TokioIo;
+use serde_json::Value;
+use tokio::net::TcpListener;
+
+use crate::authenticator::scim::{ScimError, ScimProvisioner, ScimResult, SCIM_BASE_PATH};
+use crate::authority_node::ScimConfiguration;
+use crate::{ApiError, HttpError};
+use ockam_core::{async_trait, Address, Processor, Result};
+use ockam_node::{Context, ProcessorBuilder};
+
+/// Maximum size of the body of a SCIM request
+const MAX_SCIM_REQUEST_SIZE: usize = 1024 * 1024;
+
+const SCIM_CONTENT_TYPE: &str = "application/scim+json";
+
+type ScimResponse = Response<BoxBody<Bytes, Infallible>>;
+
+/// An HTTP server exposing the SCIM 2.0 endpoints of an Authority node,
+/// so that an identity management system can provision project members.
+///
+/// The endpoints are available under `/scim/v2`:
+///
+///  - `GET /ServiceProviderConfig`
+///  - `GET, POST /Users` and `GET, PUT, PATCH, DELETE /Users/{id}`
+///  - `GET, POST /Groups` and `GET, PUT, PATCH, DELETE /Groups/{id}`
+///
+/// All the requests must present the configured bearer token.
+///
+/// The server doesn't support TLS, and only listens on a loopback address.
+/// It must be exposed to the identity management system with a reverse proxy terminating TLS.
+pub struct ScimServer;
+
+impl ScimServer {
+    /// Start a new SCIM server listening on the configured address
+    /// and return the address it is listening on
+    pub async fn start(
+        context: &Context,
+        provisioner: Arc<ScimProvisioner>,
+        configuration: &ScimConfiguration,
+    ) -> Result<SocketAddr> {
+        configuration.validate()?;
+        let address = configuration.listener_address.to_string();
+        debug!("Starting the SCIM server on: {address}");
+        let listener = TcpListener::bind(&address).await.map_err(|e| {
+            ApiError::core(format!("failed to bind the SCIM server to {address}: {e}"))
+        })?;
+        let addr = listener.local_addr().map_err(ApiError::core)?;
+        let processor = ScimServerProcessor {
+            provisioner,
+            tcp_listener: listener,
+        };
+        ProcessorBuilder::new(processor)
+            .with_address(Address::random_tagged("authority_scim_server"))
+            .start(context)?;
+        Ok(addr)
+    }
+}
+
+struct ScimServerProcessor {
+    provisioner: Arc<ScimProvisioner>,
+    tcp_listener: TcpListener,
+}
+
+impl ScimServerProcessor {
+    async fn handle_request(
+        provisioner: Arc<ScimProvisioner>,
+        req: Request<hyper::body::Incoming>,
+    ) -> crate::Result<ScimResponse> {
+        debug!("Processing SCIM request: {} {}", req.method(), req.uri());
+        if !is_authorized(&req, provisioner.bearer_token()) {
+            warn!("Unauthorized SCIM request: {} {}", req.method(), req.uri());
+            return error_response(ScimError::unauthorized());
+        }
+
+        let path = req.uri().path().to_string();
+        let Some(path) = path.strip_prefix(SCIM_BASE_PATH) else {
+            return error_response(ScimError::not_found(format!("Unknown endpoint: {path}")));
+        };
+        let segments = path
+            .split('/')
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string())
+            .collect::<Vec<_>>();
+        let query = Query::parse(req.uri().query());
+        let method = req.method().clone();
+        let body = match read_body(req).await {
+            Ok(body) => body,
+            Err(error) => return error_response(error),
+        };
+        let segments = segments.iter().map(|s| s.as_str()).collect::<Vec<_>>();
+
+        match (&method, segments.as_slice()) {
+            (&Method::GET, ["ServiceProviderConfig"]) => {
+                json_response(StatusCode::OK, provisioner.service_provider_config())
+            }
+            (&Method::GET, ["Users"]) => response(
+                StatusCode::OK,
+                provisioner
+                    .list_users(query.filter.as_deref(), query.start_index, query.count)
+                    .await?,
+            ),
+            (&Method::POST, ["Users"]) => created(provisioner.create_user(body).await?),
+            (&Method::GET, ["Users", id]) => {
+                response(StatusCode::OK, provisioner.get_user(id).await?)
+            }
+            (&Method::PUT, ["Users", id]) => {
+                response(StatusCode::OK, provisioner.replace_user(id, body).await?)
+            }
+            (&Method::PATCH, ["Users", id]) => {
+                response(StatusCode::OK, provisioner.patch_user(id, body).await?)
+            }
+            (&Method::DELETE, ["Users", id]) => no_content(provisioner.delete_user(id).await?),
+            (&Method::GET, ["Groups"]) => response(
+                StatusCode::OK,
+                provisioner
+                    .list_groups(query.filter.as_deref(), query.start_index, query.count)
+                    .await?,
+            ),
+            (&Method::POST, ["Groups"]) => created(provisioner.create_group(body).await?),
+            (&Method::GET, ["Groups", id]) => {
+                response(StatusCode::OK, provisioner.get_group(id).await?)
+            }
+            (&Method::PUT, ["Groups", id]) => {
+                response(StatusCode::OK, provisioner.replace_group(id, body).await?)
+            }
+            (&Method::PATCH, ["Groups", id]) => {
+                response(StatusCode::OK, provisioner.patch_group(id, body).await?)
+            }
+            (&Method::DELETE, ["Groups", id]) => no_content(provisioner.delete_group(id).await?),
+            _ => {
+                warn!("SCIM request received for a non supported endpoint: {method} {path}");
+                error_response(ScimError::not_found(format!(
+                    "Unknown endpoint: {method} {path}"
+                )))
+            }
+        }
+    }
+
+    /// Handle a request, returning an internal server error if the request failed
+    async fn serve(
+        provisioner: Arc<ScimProvisioner>,
+        req: Request<hyper::body::Incoming>,
+    ) -> crate::Result<ScimResponse> {
+        match Self::handle_request(provisioner, req).await {
+            Ok(response) => Ok(response),
+            Err(err) => {
+                error!("Error processing a SCIM request: {err:?}");
+                error_response(ScimError::new(500, None, "Internal server error"))
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for ScimServerProcessor {
+    type Context = Context;
+
+    async fn shutdown(&mut self, _context: &mut Self::Context) -> Result<()> {
+        debug!("Shutting down ScimServerProcessor");
+        Ok(())
+    }
+
+    async fn process(&mut self, _context: &mut Self::Context) -> Result<bool> {
+        if let Ok((stream, _)) = self.tcp_listener.accept().await {
+            // SCIM clients keep their connections open, so each connection is served separately
+            let provisioner = self.provisioner.clone();
+            ockam_node::spawn(async move {
+                let io = TokioIo::new(stream);
+                let service = service_fn(|req| Self::serve(provisioner.clone(), req));
+                if let Err(err) = http1::Builder::new().serve_connection(io, service).await {
+                    error!("Error serving a SCIM connection: {err:?}");
+                }
+            });
+        }
+        Ok(true)
+    }
+}
+
+/// Query parameters of a list request
+struct Query {
+    filter: Option<String>,
+    start_index: Option<usize>,
+    count: Option<usize>,
+}
+
+impl Query {
+    fn parse(query: Option<&str>) -> Self {
+        let mut result = Query {
+            filter: None,
+            start_index: None,
+            count: None,
+        };
+        for (name, value) in url::form_urlencoded::parse(query.unwrap_or_default().as_bytes()) {
+            match name.as_ref() {
+                "filter" => result.filter = Some(value.to_string()),
+                "startIndex" => result.start_index = value.parse().ok(),
+                "count" => result.count = value.parse().ok(),
+                _ => {}
+            }
+        }
+        result
+    }
+}
+
+/// Check the bearer token of a request, in constant time
+fn is_authorized(req: &Request<hyper::body::Incoming>, bearer_token: &str) -> bool {
+    let Some(presented) = req
+        .headers()
+        .get(AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+    else {
+        return false;
+    };
+    let (presented, expected) = (presented.as_bytes(), bearer_token.as_bytes());
+    presented.len() == expected.len()
+        && presented
+            .iter()
+            .zip(expected.iter())
+            .fold(0, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+/// Read the JSON body of a request. An empty body is returned as `null`
+async fn read_body(req: Request<hyper::body::Incoming>) -> Result<Value, ScimError> {
+    let bytes = Limited::new(req.into_body(), MAX_SCIM_REQUEST_SIZE)
+        .collect()
+        .await
+        .map_err(|e| ScimError::invalid_syntax(format!("Invalid request body: {e}")))?
+        .to_bytes();
+    if bytes.is_empty() {
+        return Ok(Value::Null);
+    }
+    serde_json::from_slice(&bytes)
+        .map_err(|e| ScimError::invalid_syntax(format!("Invalid JSON body: {e}")))
+}
+
+fn response(status: StatusCode, result: ScimResult<Value>) -> crate::Result<ScimResponse> {
+    match result {
+        Either::Left(value) => json_response(status, value),
+        Either::Right(error) => error_response(error),
+    }
+}
+
+/// Return a newly created resource, with its location
+fn created(result: ScimResult<Value>) -> crate::Result<ScimResponse> {
+    let location = match &result {
+        Either::Left(value) => value["meta"]["location"].as_str().map(|l| l.to_string()),
+        Either::Right(_) => None,
+    };
+    let mut response = response(StatusCode::CREATED, result)?;
+    if let Some(location) = location {
+        response.headers_mut().insert(
+            LOCATION,
+            location
+                .parse()
+                .map_err(|e| HttpError::from(hyper::http::Error::from(e)))?,
+        );
+    }
+    Ok(response)
+}
+
+fn no_content(result: ScimResult<()>) -> crate::Result<ScimResponse> {
+    match result {
+        Either::Left(()) => Ok(Response::builder()
+            .status(StatusCode::NO_CONTENT)
+            .body(Full::new(Bytes::new()).boxed())
+            .map_err(HttpError::from)?),
+        Either::Right(error) => error_response(error),
+    }
+}
+
+fn error_response(error: ScimError) -> crate::Result<ScimResponse> {
+    let status = StatusCode::from_u16(error.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+    json_response(status, error.to_json())
+}
+
+fn json_response(status: StatusCode, value: Value) -> crate::Result<ScimResponse> {
+    Ok(Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, SCIM_CONTENT_TYPE)
+        .body(Full::new(Bytes::from(value.to_string())).boxed())
+        .map_err(HttpError::from)?)
+}
@@ -0,0 +1,350 @@
+use serde::Deserialize;
+use serde_json::{json, Map, Value};
+
+/// Path of the SCIM endpoints on the HTTP server of the Authority node
+pub const SCIM_BASE_PATH: &str = "/scim/v2";
+
+pub const SCIM_USER_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:User";
+pub const SCIM_GROUP_SCHEMA: &str = "urn:ietf:params:scim:schemas:core:2.0:Group";
+pub const SCIM_LIST_RESPONSE_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:ListResponse";
+pub const SCIM_PATCH_OP_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:PatchOp";
+pub const SCIM_ERROR_SCHEMA: &str = "urn:ietf:params:scim:api:messages:2.0:Error";
+pub const SCIM_SERVICE_PROVIDER_CONFIG_SCHEMA: &str =
+    "urn:ietf:params:scim:schemas:core:2.0:ServiceProviderConfig";
+
+/// Error returned to a SCIM client, as specified in RFC 7644, section 3.12
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScimError {
+    pub status: u16,
+    pub scim_type: Option<&'static str>,
+    pub detail: String,
+}
+
+impl ScimError {
+    pub fn new(status: u16, scim_type: Option<&'static str>, detail: impl Into<String>) -> Self {
+        Self {
+            status,
+            scim_type,
+            detail: detail.into(),
+        }
+    }
+
+    pub fn not_found(detail: impl Into<String>) -> Self {
+        Self::new(404, None, detail)
+    }
+
+    pub fn unauthorized() -> Self {
+        Self::new(401, None, "A valid bearer token is required")
+    }
+
+    pub fn uniqueness(detail: impl Into<String>) -> Self {
+        Self::new(409, Some("uniqueness"), detail)
+    }
+
+    pub fn invalid_value(detail: impl Into<String>) -> Self {
+        Self::new(400, Some("invalidValue"), detail)
+    }
+
+    pub fn invalid_syntax(detail: impl Into<String>) -> Self {
+        Self::new(400, Some("invalidSyntax"), detail)
+    }
+
+    pub fn invalid_filter(detail: impl Into<String>) -> Self {
+        Self::new(400, Some("invalidFilter"), detail)
+    }
+
+    pub fn invalid_path(detail: impl Into<String>) -> Self {
+        Self::new(400, Some("invalidPath"), detail)
+    }
+
+    pub fn mutability(detail: impl Into<String>) -> Self {
+        Self::new(400, Some("mutability"), detail)
+    }
+
+    /// Return the JSON body of the error
+    pub fn to_json(&self) -> Value {
+        let mut error = json!({
+            "schemas": [SCIM_ERROR_SCHEMA],
+            "status": self.status.to_string(),
+            "detail": self.detail,
+        });
+        if let Some(scim_type) = self.scim_type {
+            error["scimType"] = Value::String(scim_type.to_string());
+        }
+        error
+    }
+}
+
+/// Body of a PATCH request
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScimPatchRequest {
+    #[serde(rename = "Operations")]
+    pub operations: Vec<ScimPatchOperation>,
+}
+
+/// Operation of a PATCH request
+#[derive(Debug, Clone, Deserialize)]
+pub struct ScimPatchOperation {
+    pub op: String,
+    pub path: Option<String>,
+    pub value: Option<Value>,
+}
+
+/// Kind of a PATCH operation. The operation names are case-insensitive since some
+/// identity providers send them capitalized
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScimPatchOp {
+    Add,
+    Replace,
+    Remove,
+}
+
+impl ScimPatchOperation {
+    pub fn kind(&self) -> Result<ScimPatchOp, ScimError> {
+        match self.op.to_lowercase().as_str() {
+            "add" => Ok(ScimPatchOp::Add),
+            "replace" => Ok(ScimPatchOp::Replace),
+            "remove" => Ok(ScimPatchOp::Remove),
+            other => Err(ScimError::invalid_syntax(format!(
+                "Unsupported PATCH operation: {other}"
+            ))),
+        }
+    }
+}
+
+/// Equality filter on a single attribute, for example `userName eq "I123..."`.
+/// This is the only kind of filter sent by identity providers to look up resources
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScimFilter {
+    pub attribute: String,
+    pub value: String,
+}
+
+impl ScimFilter {
+    pub fn parse(filter: &str) -> Result<Self, ScimError> {
+        let invalid = || ScimError::invalid_filter(format!("Unsupported filter: {filter}"));
+        let filter = filter.trim();
+        let (attribute, rest) = filter.split_once(char::is_whitespace).ok_or_else(invalid)?;
+        let (operator, value) = rest
+            .trim_start()
+            .split_once(char::is_whitespace)
+            .ok_or_else(invalid)?;
+        if !operator.eq_ignore_ascii_case("eq") {
+            return Err(invalid());
+        }
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .ok_or_else(invalid)?;
+        Ok(Self {
+            attribute: attribute.to_string(),
+            value: value.replace("\\\"", "\""),
+        })
+    }
+
+    /// Return true if the filter applies to the given attribute name
+    pub fn is_on(&self, attribute: &str) -> bool {
+        self.attribute.eq_ignore_ascii_case(attribute)
+    }
+}
+
+/// Return the value of a possibly nested attribute of a SCIM resource.
+///
+/// The path is either a dotted path, like `name.givenName`, or the path of an attribute
+/// defined by an extension schema, like
+/// `urn:ietf:params:scim:schemas:extension:enterprise:2.0:User:department`.
+/// Attribute names are case-insensitive
+pub fn get_attribute<'a>(resource: &'a Value, path: &str) -> Option<&'a Value> {
+    let (object, path) = match path.rsplit_once(':') {
+        Some((schema, attribute)) => (get_field(resource, schema)?, attribute),
+        None => (resource, path),
+    };
+    path.split('.')
+        .try_fold(object, |value, name| get_field(value, name))
+}
+
+/// Set the value of a possibly nested attribute of a SCIM resource,
+/// creating the intermediate objects if necessary
+pub fn set_attribute(resource: &mut Value, path: &str, value: Value) -> Result<(), ScimError> {
+    let (mut object, path) = match path.rsplit_once(':') {
+        Some((schema, attribute)) => (get_or_create_field(resource, schema)?, attribute),
+        None => (resource, path),
+    };
+    let names: Vec<&str> = path.split('.').collect();
+    let (last, parents) = names
+        .split_last()
+        .ok_or_else(|| ScimError::invalid_path("Empty attribute path"))?;
+    for name in parents {
+        object = get_or_create_field(object, name)?;
+    }
+    let object = object
+        .as_object_mut()
+        .ok_or_else(|| ScimError::invalid_path(format!("Invalid attribute path: {path}")))?;
+    let key = field_key(object, last);
+    object.insert(key, value);
+    Ok(())
+}
+
+/// Remove a possibly nested attribute of a SCIM resource
+pub fn remove_attribute(resource: &mut Value, path: &str) {
+    let (object, path) = match path.rsplit_once(':') {
+        Some((schema, attribute)) => match get_field_mut(resource, schema) {
+            Some(object) => (object, attribute),
+            None => return,
+        },
+        None => (resource, path),
+    };
+    let names: Vec<&str> = path.split('.').collect();
+    let Some((last, parents)) = names.split_last() else {
+        return;
+    };
+    let parent = parents
+        .iter()
+        .try_fold(object, |value, name| get_field_mut(value, name));
+    if let Some(Value::Object(object)) = parent {
+        let key = field_key(object, last);
+        object.remove(&key);
+    }
+}
+
+/// Return the value of an attribute as a string.
+/// Numbers and booleans are converted to strings, and multi-valued attributes, like `emails`,
+/// are joined with commas
+pub fn attribute_as_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        Value::Array(values) => {
+            let values: Vec<String> = values
+                .iter()
+                .filter_map(|v| match v {
+                    Value::Object(_) => get_field(v, "value").and_then(attribute_as_string),
+                    _ => attribute_as_string(v),
+                })
+                .collect();
+            Some(values.join(","))
+        }
+        Value::Object(_) | Value::Null => None,
+    }
+}
+
+/// Return the value of an attribute as a boolean.
+/// Some identity providers send booleans as strings, like "False"
+pub fn attribute_as_bool(value: &Value) -> Option<bool> {
+    match value {
+        Value::Bool(b) => Some(*b),
+        Value::String(s) if s.eq_ignore_ascii_case("true") => Some(true),
+        Value::String(s) if s.eq_ignore_ascii_case("false") => Some(false),
+        _ => None,
+    }
+}
+
+fn get_field<'a>(value: &'a Value, name: &str) -> Option<&'a Value> {
+    value
+        .as_object()?
+        .iter()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v)
+}
+
+fn get_field_mut<'a>(value: &'a mut Value, name: &str) -> Option<&'a mut Value> {
+    value
+        .as_object_mut()?
+        .iter_mut()
+        .find(|(k, _)| k.eq_ignore_ascii_case(name))
+        .map(|(_, v)| v)
+}
+
+fn get_or_create_field<'a>(value: &'a mut Value, name: &str) -> Result<&'a mut Value, ScimError> {
+    let object = value
+        .as_object_mut()
+        .ok_or_else(|| ScimError::invalid_path(format!("{name} is not a complex attribute")))?;
+    let key = field_key(object, name);
+    let field = object
+        .entry(key)
+        .or_insert_with(|| Value::Object(Map::new()));
+    if !field.is_object() {
+        return Err(ScimError::invalid_path(format!(
+            "{name} is not a complex attribute"
+        )));
+    }
+    Ok(field)
+}
+
+/// Return the existing key matching a name, ignoring case, or the name itself
+fn field_key(object: &Map<String, Value>, name: &str) -> String {
+    object
+        .keys()
+        .find(|k| k.eq_ignore_ascii_case(name))
+        .cloned()
+        .unwrap_or_else(|| name.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_filter() {
+        assert_eq!(
+            ScimFilter::parse(r#"userName eq "I1234""#),
+            Ok(ScimFilter {
+                attribute: "userName".to_string(),
+                value: "I1234".to_string()
+            })
+        );
+        assert!(ScimFilter::parse(r#"userName EQ "I1234""#)
+            .unwrap()
+            .is_on("username"));
+        assert!(ScimFilter::parse(r#"userName sw "I1""#).is_err());
+        assert!(ScimFilter::parse("userName eq I1234").is_err());
+        assert!(ScimFilter::parse("userName").is_err());
+    }
+
+    #[test]
+    fn test_attributes() {
+        let enterprise = "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User";
+        let mut user = json!({
+            "userName": "I1234",
+            "name": { "givenName": "Jane" },
+            enterprise: { "department": "R&D" }
+        });
+
+        assert_eq!(get_attribute(&user, "username"), Some(&json!("I1234")));
+        assert_eq!(get_attribute(&user, "name.givenName"), Some(&json!("Jane")));
+        assert_eq!(
+            get_attribute(&user, &format!("{enterprise}:department")),
+            Some(&json!("R&D"))
+        );
+        assert_eq!(get_attribute(&user, "name.familyName"), None);
+
+        set_attribute(&mut user, "name.familyName", json!("Doe")).unwrap();
+        set_attribute(&mut user, "NAME.givenName", json!("John")).unwrap();
+        set_attribute(&mut user, &format!("{enterprise}:costCenter"), json!(42)).unwrap();
+        assert_eq!(get_attribute(&user, "name.familyName"), Some(&json!("Doe")));
+        assert_eq!(get_attribute(&user, "name.givenName"), Some(&json!("John")));
+        assert_eq!(
+            get_attribute(&user, &format!("{enterprise}:costCenter")).and_then(attribute_as_string),
+            Some("42".to_string())
+        );
+        assert!(set_attribute(&mut user, "userName.value", json!("x")).is_err());
+
+        remove_attribute(&mut user, "name.givenName");
+        remove_attribute(&mut user, &format!("{enterprise}:department"));
+        assert_eq!(get_attribute(&user, "name.givenName"), None);
+        assert_eq!(
+            get_attribute(&user, &format!("{enterprise}:department")),
+            None
+        );
+
+        assert_eq!(
+            attribute_as_string(&json!([{"value": "a@example.com"}, {"value": "b@example.com"}])),
+            Some("a@example.com,b@example.com".to_string())
+        );
+        assert_eq!(attribute_as_bool(&json!("False")), Some(false));
+        assert_eq!(attribute_as_bool(&json!(true)), Some(true));
+        assert_eq!(attribute_as_bool(&json!("yes")), None);
+    }
+}
@@ -0,0 +1,69 @@
+use ockam::identity::Identifier;
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+use ockam_node::database::AutoRetry;
+use ockam_node::retry;
+
+use crate::authenticator::audit::AuditEntry;
+use crate::authenticator::AuthorityMemberChange;
+
+/// This repository stores the audit log of an Authority node.
+/// Entries can only be appended and read back: they are never updated or deleted.
+#[async_trait]
+pub trait AuthorityAuditLogRepository: Send + Sync + 'static {
+    /// Append an entry to the log.
+    /// This fails if the log already contains an entry with the same index
+    async fn append_entry(&self, authority: &Identifier, entry: AuditEntry) -> Result<()>;
+
+    /// Append an entry to the log and apply the change of members it records, in one transaction.
+    /// Nothing is stored, and false is returned, if the change does not modify any member
+    async fn append_entry_with_change(
+        &self,
+        authority: &Identifier,
+        entry: AuditEntry,
+        change: AuthorityMemberChange,
+    ) -> Result<bool>;
+
+    /// Return the last entry of the log, if any
+    async fn get_last_entry(&self, authority: &Identifier) -> Result<Option<AuditEntry>>;
+
+    /// Return at most `limit` entries, ordered by index, starting at the index `from`
+    async fn get_entries(
+        &self,
+        authority: &Identifier,
+        from: u64,
+        limit: u32,
+    ) -> Result<Vec<AuditEntry>>;
+}
+
+#[async_trait]
+impl<T: AuthorityAuditLogRepository> AuthorityAuditLogRepository for AutoRetry<T> {
+    async fn append_entry(&self, authority: &Identifier, entry: AuditEntry) -> Result<()> {
+        retry!(self.wrapped.append_entry(authority, entry.clone()))
+    }
+
+    async fn append_entry_with_change(
+        &self,
+        authority: &Identifier,
+        entry: AuditEntry,
+        change: AuthorityMemberChange,
+    ) -> Result<bool> {
+        retry!(self
+            .wrapped
+            .append_entry_with_change(authority, entry.clone(), change.clone()))
+    }
+
+    async fn get_last_entry(&self, authority: &Identifier) -> Result<Option<AuditEntry>> {
+        retry!(self.wrapped.get_last_entry(authority))
+    }
+
+    async fn get_entries(
+        &self,
+        authority: &Identifier,
+        from: u64,
+        limit: u32,
+    ) -> Result<Vec<AuditEntry>> {
+        retry!(self.wrapped.get_entries(authority, from, limit))
+    }
+}
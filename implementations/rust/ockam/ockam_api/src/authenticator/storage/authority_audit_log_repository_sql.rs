@@ -0,0 +1,344 @@
+use core::str::FromStr;
+use sqlx::*;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::authenticator::audit::{AuditEntry, AuditHash};
+use crate::authenticator::{
+    AuthorityAuditLogRepository, AuthorityMemberChange, AuthorityMembersSqlxDatabase,
+};
+use crate::error::ApiError;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::{AutoRetry, Nullable};
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+/// Implementation of [`AuthorityAuditLogRepository`] trait based on an underlying database
+/// using sqlx as its API
+#[derive(Clone)]
+pub struct AuthorityAuditLogSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityAuditLogSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for the authority audit log");
+        Self { database }
+    }
+
+    /// Create a repository
+    pub fn make_repository(database: SqlxDatabase) -> Arc<dyn AuthorityAuditLogRepository> {
+        if database.needs_retry() {
+            Arc::new(AutoRetry::new(Self::new(database)))
+        } else {
+            Arc::new(Self::new(database))
+        }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority audit log").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityAuditLogRepository for AuthorityAuditLogSqlxDatabase {
+    async fn append_entry(&self, authority: &Identifier, entry: AuditEntry) -> Result<()> {
+        let mut connection = self.database.pool.acquire().await.into_core()?;
+        Self::insert_entry(&mut connection, authority, &entry).await
+    }
+
+    async fn append_entry_with_change(
+        &self,
+        authority: &Identifier,
+        entry: AuditEntry,
+        change: AuthorityMemberChange,
+    ) -> Result<bool> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        if !AuthorityMembersSqlxDatabase::apply_change(&mut transaction, authority, &change).await?
+        {
+            transaction.rollback().await.void()?;
+            return Ok(false);
+        }
+        Self::insert_entry(&mut transaction, authority, &entry).await?;
+        transaction.commit().await.void()?;
+        Ok(true)
+    }
+
+    async fn get_last_entry(&self, authority: &Identifier) -> Result<Option<AuditEntry>> {
+        let query = query_as("SELECT entry_index, recorded_at, actor, action, target, attributes, previous_hash, hash, signature FROM authority_audit_log WHERE authority_id = $1 ORDER BY entry_index DESC LIMIT 1")
+            .bind(authority);
+        let row: Option<AuditEntryRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.audit_entry()).transpose()
+    }
+
+    async fn get_entries(
+        &self,
+        authority: &Identifier,
+        from: u64,
+        limit: u32,
+    ) -> Result<Vec<AuditEntry>> {
+        let query = query_as("SELECT entry_index, recorded_at, actor, action, target, attributes, previous_hash, hash, signature FROM authority_audit_log WHERE authority_id = $1 AND entry_index >= $2 ORDER BY entry_index ASC LIMIT $3")
+            .bind(authority)
+            .bind(from as i64)
+            .bind(limit as i64);
+        let rows: Vec<AuditEntryRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.audit_entry()).collect()
+    }
+}
+
+impl AuthorityAuditLogSqlxDatabase {
+    /// Insert an entry, which fails if the log already contains an entry with the same index
+    async fn insert_entry(
+        connection: &mut AnyConnection,
+        authority: &Identifier,
+        entry: &AuditEntry,
+    ) -> Result<()> {
+        let query = query(
+            r#"
+            INSERT INTO authority_audit_log (authority_id, entry_index, recorded_at, actor, action, target, attributes, previous_hash, hash, signature)
+            VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10)"#,
+        )
+        .bind(authority)
+        .bind(entry.index as i64)
+        .bind(entry.recorded_at)
+        .bind(&entry.actor)
+        .bind(entry.action.as_str())
+        .bind(entry.target.as_ref().map(|t| t.to_string()))
+        .bind(ockam_core::cbor_encode_preallocate(&entry.attributes)?)
+        .bind(entry.previous_hash.to_vec())
+        .bind(entry.hash.to_vec())
+        .bind(ockam_core::cbor_encode_preallocate(&entry.signature)?);
+        query.execute(connection).await.void()
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct AuditEntryRow {
+    entry_index: i64,
+    recorded_at: i64,
+    actor: String,
+    action: String,
+    target: Nullable<String>,
+    attributes: Vec<u8>,
+    previous_hash: Vec<u8>,
+    hash: Vec<u8>,
+    signature: Vec<u8>,
+}
+
+impl AuditEntryRow {
+    fn audit_entry(&self) -> Result<AuditEntry> {
+        Ok(AuditEntry {
+            index: self.entry_index as u64,
+            recorded_at: TimestampInSeconds(self.recorded_at as u64),
+            actor: Identifier::from_str(&self.actor)?,
+            action: self.action.parse()?,
+            target: self
+                .target
+                .to_option()
+                .map(|t| Identifier::from_str(&t))
+                .transpose()?,
+            attributes: minicbor::decode(&self.attributes)?,
+            previous_hash: Self::audit_hash(&self.previous_hash)?,
+            hash: Self::audit_hash(&self.hash)?,
+            signature: minicbor::decode(&self.signature)?,
+        })
+    }
+
+    fn audit_hash(bytes: &[u8]) -> Result<AuditHash> {
+        bytes
+            .try_into()
+            .map_err(|_| ApiError::core("invalid hash in the authority audit log"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::authenticator::audit::{AuditAction, AuditSignature, GENESIS_AUDIT_HASH};
+    use crate::authenticator::AuthorityMember;
+    use ockam::identity::identities;
+    use ockam::identity::utils::now;
+    use ockam_core::compat::collections::BTreeMap;
+    use ockam_node::database::with_dbs;
+
+    #[tokio::test]
+    async fn test_authority_audit_log_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityAuditLogRepository> =
+                Arc::new(AuthorityAuditLogSqlxDatabase::new(db.clone()));
+
+            let identities = identities().await?;
+            let authority = identities.identities_creation().create_identity().await?;
+            let member = identities.identities_creation().create_identity().await?;
+            let purpose_key = identities
+                .purpose_keys()
+                .purpose_keys_creation()
+                .get_or_create_credential_purpose_key(&authority)
+                .await?;
+            let signature = identities
+                .vault()
+                .credential_vault
+                .sign(purpose_key.key(), &[1; 32])
+                .await?;
+            let signature = AuditSignature {
+                signature: signature.into(),
+                purpose_key_attestation: purpose_key.attestation().clone(),
+            };
+
+            // the log is initially empty
+            assert_eq!(repository.get_last_entry(&authority).await?, None);
+
+            let entry = |index: u64, previous_hash, hash| AuditEntry {
+                index,
+                recorded_at: TimestampInSeconds(now().unwrap().0 + index),
+                actor: authority.clone(),
+                action: AuditAction::AddMember,
+                target: if index == 0 {
+                    None
+                } else {
+                    Some(member.clone())
+                },
+                attributes: BTreeMap::from([("key".to_string(), format!("value{index}"))]),
+                previous_hash,
+                hash,
+                signature: signature.clone(),
+            };
+            let entry0 = entry(0, GENESIS_AUDIT_HASH, [1; 32]);
+            let entry1 = entry(1, [1; 32], [2; 32]);
+            let entry2 = entry(2, [2; 32], [3; 32]);
+            for e in [&entry0, &entry1, &entry2] {
+                repository.append_entry(&authority, e.clone()).await?;
+            }
+
+            // an entry can't be appended twice at the same index
+            let result = repository
+                .append_entry(&authority, entry(2, [2; 32], [4; 32]))
+                .await;
+            assert!(result.is_err());
+
+            assert_eq!(
+                repository.get_last_entry(&authority).await?,
+                Some(entry2.clone())
+            );
+            assert_eq!(
+                repository.get_entries(&authority, 0, 2).await?,
+                vec![entry0.clone(), entry1.clone()]
+            );
+            assert_eq!(
+                repository.get_entries(&authority, 2, 2).await?,
+                vec![entry2.clone()]
+            );
+
+            // the entries can't be modified or deleted
+            let _ = query("UPDATE authority_audit_log SET action = $1")
+                .bind("delete_member")
+                .execute(&*db.pool)
+                .await;
+            let _ = query("DELETE FROM authority_audit_log")
+                .execute(&*db.pool)
+                .await;
+            assert_eq!(
+                repository.get_entries(&authority, 0, 10).await?,
+                vec![entry0, entry1, entry2]
+            );
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_authority_audit_log_repository_with_change() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityAuditLogRepository> =
+                Arc::new(AuthorityAuditLogSqlxDatabase::new(db.clone()));
+            let members = AuthorityMembersSqlxDatabase::make_repository(db.clone());
+
+            let identities = identities().await?;
+            let authority = identities.identities_creation().create_identity().await?;
+            let member = identities.identities_creation().create_identity().await?;
+            let purpose_key = identities
+                .purpose_keys()
+                .purpose_keys_creation()
+                .get_or_create_credential_purpose_key(&authority)
+                .await?;
+            let signature = identities
+                .vault()
+                .credential_vault
+                .sign(purpose_key.key(), &[1; 32])
+                .await?;
+            let signature = AuditSignature {
+                signature: signature.into(),
+                purpose_key_attestation: purpose_key.attestation().clone(),
+            };
+            let recorded_at = now()?;
+            let entry = |index: u64, action| AuditEntry {
+                index,
+                recorded_at,
+                actor: authority.clone(),
+                action,
+                target: Some(member.clone()),
+                attributes: BTreeMap::new(),
+                previous_hash: [index as u8; 32],
+                hash: [index as u8 + 1; 32],
+                signature: signature.clone(),
+            };
+            let authority_member = AuthorityMember::new(
+                member.clone(),
+                BTreeMap::new(),
+                authority.clone(),
+                now()?,
+                false,
+            );
+
+            // a change which does not modify any member is not recorded
+            let changed = repository
+                .append_entry_with_change(
+                    &authority,
+                    entry(0, AuditAction::SuspendMember),
+                    AuthorityMemberChange::SetSuspended(member.clone(), true),
+                )
+                .await?;
+            assert!(!changed);
+            assert_eq!(repository.get_last_entry(&authority).await?, None);
+
+            // the member and the entry are stored together
+            let changed = repository
+                .append_entry_with_change(
+                    &authority,
+                    entry(0, AuditAction::AddMember),
+                    AuthorityMemberChange::Add(authority_member.clone()),
+                )
+                .await?;
+            assert!(changed);
+            assert_eq!(
+                repository.get_last_entry(&authority).await?,
+                Some(entry(0, AuditAction::AddMember))
+            );
+            assert!(members.get_member(&authority, &member).await?.is_some());
+
+            // the member is not deleted if the entry can't be appended
+            let result = repository
+                .append_entry_with_change(
+                    &authority,
+                    entry(0, AuditAction::DeleteMember),
+                    AuthorityMemberChange::Delete(member.clone()),
+                )
+                .await;
+            assert!(result.is_err());
+            assert!(members.get_member(&authority, &member).await?.is_some());
+
+            Ok(())
+        })
+        .await
+    }
+}
@@ -61,43 +61,59 @@ impl AuthorityEnrollmentTokenRepository for AuthorityEnrollmentTokenSqlxDatabase
         let res = query1.execute(&*self.database.pool).await.into_core()?;
         debug!("Deleted {} expired enrollment tokens", res.rows_affected());
 
-        let mut transaction = self.database.pool.begin().await.into_core()?;
+        // Several authority nodes can share the same database, so the usage count of a token
+        // is only decremented if it has not been modified since it was read.
+        // Otherwise the token is read again, until no other node uses it concurrently.
+        loop {
+            let query2 = query_as("SELECT one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes FROM authority_enrollment_token WHERE one_time_code = $1")
+                .bind(one_time_code);
+            let row: Option<EnrollmentTokenRow> = query2
+                .fetch_optional(&*self.database.pool)
+                .await
+                .into_core()?;
+            let Some(token) = row.map(EnrollmentToken::try_from).transpose()? else {
+                return Ok(None);
+            };
 
-        let query2 = query_as("SELECT one_time_code, reference, issued_by, created_at, expires_at, ttl_count, attributes FROM authority_enrollment_token WHERE one_time_code = $1")
-            .bind(one_time_code);
-        let row: Option<EnrollmentTokenRow> =
-            query2.fetch_optional(&mut *transaction).await.into_core()?;
-        let token: Option<EnrollmentToken> = row.map(|r| r.try_into()).transpose()?;
+            let result = if token.ttl_count <= 1 {
+                let query3 = query(
+                    "DELETE FROM authority_enrollment_token WHERE one_time_code = $1 AND ttl_count = $2",
+                )
+                .bind(one_time_code)
+                .bind(token.ttl_count as i64);
+                query3.execute(&*self.database.pool).await.into_core()?
+            } else {
+                let query3 = query(
+                    "UPDATE authority_enrollment_token SET ttl_count = $1 WHERE one_time_code = $2 AND ttl_count = $3",
+                )
+                .bind((token.ttl_count - 1) as i64)
+                .bind(one_time_code)
+                .bind(token.ttl_count as i64);
+                query3.execute(&*self.database.pool).await.into_core()?
+            };
+
+            if result.rows_affected() == 0 {
+                debug!(
+                    "Enrollment token used concurrently, reading it again. Reference: {}",
+                    token.reference()
+                );
+                continue;
+            }
 
-        if let Some(token) = &token {
             if token.ttl_count <= 1 {
-                let query3 =
-                    query("DElETE FROM authority_enrollment_token WHERE one_time_code = $1")
-                        .bind(one_time_code);
-                query3.execute(&mut *transaction).await.void()?;
                 debug!(
                     "Deleted enrollment token because it has been used. Reference: {}",
                     token.reference()
                 );
             } else {
-                let new_ttl_count = token.ttl_count - 1;
-                let query3 = query(
-                    "UPDATE authority_enrollment_token SET ttl_count = $1 WHERE one_time_code = $2",
-                )
-                .bind(new_ttl_count as i64)
-                .bind(one_time_code);
-                query3.execute(&mut *transaction).await.void()?;
                 debug!(
                     "Decreasing enrollment token usage count to {}. Reference: {}",
-                    new_ttl_count,
+                    token.ttl_count - 1,
                     token.reference()
                 );
             }
+            return Ok(Some(token));
         }
-
-        transaction.commit().await.void()?;
-
-        Ok(token)
     }
 
     async fn store_new_token(&self, token: EnrollmentToken) -> Result<()> {
@@ -340,4 +356,51 @@ mod tests {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_authority_enrollment_token_repository_concurrent_use() -> Result<()> {
+        with_dbs(|db| async move {
+            // two repositories for two authority nodes sharing the same database
+            let repositories: Vec<Arc<dyn AuthorityEnrollmentTokenRepository>> = vec![
+                AuthorityEnrollmentTokenSqlxDatabase::make_repository(db.clone()),
+                AuthorityEnrollmentTokenSqlxDatabase::make_repository(db),
+            ];
+
+            let one_time_code = OneTimeCode::new();
+            let created_at = now()?;
+            let token = EnrollmentToken {
+                one_time_code,
+                reference: None,
+                issued_by: Identifier::from_str(
+                    "I0123456789abcdef0123456789abcdef0123456789abcdef0123456789abcdef",
+                )
+                .unwrap(),
+                created_at,
+                expires_at: created_at + 10,
+                ttl_count: 3,
+                attrs: BTreeMap::default(),
+            };
+            repositories[0].store_new_token(token).await?;
+
+            let mut handles = vec![];
+            for i in 0..10 {
+                let repository = repositories[i % 2].clone();
+                handles.push(tokio::spawn(async move {
+                    repository.use_token(one_time_code, now()?).await
+                }));
+            }
+            let mut ttl_counts = vec![];
+            for handle in handles {
+                if let Some(token) = handle.await.unwrap()? {
+                    ttl_counts.push(token.ttl_count);
+                }
+            }
+
+            // the token can only be used as many times as its usage count
+            ttl_counts.sort();
+            assert_eq!(ttl_counts, vec![1, 2, 3]);
+            Ok(())
+        })
+        .await
+    }
 }
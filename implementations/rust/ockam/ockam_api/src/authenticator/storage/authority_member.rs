@@ -2,7 +2,7 @@ use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::str::FromStr;
 use ockam_core::{Error, Result};
-use ockam_node::database::Boolean;
+use ockam_node::database::{Boolean, Nullable};
 
 /// Project member stored on the Authority node
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -14,6 +14,10 @@ pub struct AuthorityMember {
     // Was provided by TrustedIdentities argument during the Authority startup
     // pre-trusted identities can't be deleted using [`MembersStorage::delete_member()`]
     is_pre_trusted: bool,
+    // Date after which the member can't get credentials anymore
+    expires_at: Option<TimestampInSeconds>,
+    // A suspended member can't get credentials until it is resumed
+    is_suspended: bool,
 }
 
 impl AuthorityMember {
@@ -30,8 +34,22 @@ impl AuthorityMember {
             added_by,
             added_at,
             is_pre_trusted,
+            expires_at: None,
+            is_suspended: false,
         }
     }
+
+    /// Set the date after which the membership ends
+    pub fn with_expires_at(mut self, expires_at: Option<TimestampInSeconds>) -> Self {
+        self.expires_at = expires_at;
+        self
+    }
+
+    /// Set the suspended state of the membership
+    pub fn with_suspended(mut self, is_suspended: bool) -> Self {
+        self.is_suspended = is_suspended;
+        self
+    }
     pub fn identifier(&self) -> &Identifier {
         &self.identifier
     }
@@ -47,6 +65,30 @@ impl AuthorityMember {
     pub fn is_pre_trusted(&self) -> bool {
         self.is_pre_trusted
     }
+    pub fn expires_at(&self) -> Option<TimestampInSeconds> {
+        self.expires_at
+    }
+    pub fn is_suspended(&self) -> bool {
+        self.is_suspended
+    }
+
+    /// Return true if the membership has expired at the given date
+    pub fn is_expired(&self, now: TimestampInSeconds) -> bool {
+        self.expires_at.is_some_and(|expires_at| expires_at <= now)
+    }
+}
+
+/// Change of the members of an Authority, stored together with the audit log entry recording it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AuthorityMemberChange {
+    /// Add a member, or replace an existing member
+    Add(AuthorityMember),
+    /// Delete a member which is not pre-trusted, and revoke its credentials
+    Delete(Identifier),
+    /// Set or remove the date after which a member can't get credentials anymore
+    SetExpiration(Identifier, Option<TimestampInSeconds>),
+    /// Suspend a member and revoke its credentials, or resume it
+    SetSuspended(Identifier, bool),
 }
 
 // Low-level representation of a table row
@@ -57,6 +99,8 @@ pub(crate) struct AuthorityMemberRow {
     added_at: i64,
     is_pre_trusted: Boolean,
     attributes: Vec<u8>,
+    expires_at: Nullable<i64>,
+    is_suspended: Boolean,
 }
 
 impl TryFrom<AuthorityMemberRow> for AuthorityMember {
@@ -69,7 +113,14 @@ impl TryFrom<AuthorityMemberRow> for AuthorityMember {
             Identifier::from_str(&value.added_by)?,
             TimestampInSeconds(value.added_at as u64),
             value.is_pre_trusted.to_bool(),
-        );
+        )
+        .with_expires_at(
+            value
+                .expires_at
+                .to_option()
+                .map(|t| TimestampInSeconds(t as u64)),
+        )
+        .with_suspended(value.is_suspended.to_bool());
 
         Ok(member)
     }
@@ -1,5 +1,6 @@
 use crate::authenticator::{AuthorityMember, PreTrustedIdentities};
-use ockam::identity::Identifier;
+use ockam::identity::models::RevokedSubject;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::async_trait;
 use ockam_core::compat::boxed::Box;
 use ockam_core::compat::vec::Vec;
@@ -20,12 +21,36 @@ pub trait AuthorityMembersRepository: Send + Sync + 'static {
     /// Return all members of the Project
     async fn get_members(&self, authority: &Identifier) -> Result<Vec<AuthorityMember>>;
 
-    /// Delete a member from the Project (unless it's pre-trusted)
+    /// Delete a member from the Project (unless it's pre-trusted).
+    /// The member is recorded as revoked so that its credentials can be revoked
     async fn delete_member(&self, authority: &Identifier, identifier: &Identifier) -> Result<()>;
 
+    /// Return the members which were deleted from the Project, with their revocation date
+    async fn get_revoked_members(&self, authority: &Identifier) -> Result<Vec<RevokedSubject>>;
+
     /// Add a member to the Project
     async fn add_member(&self, authority: &Identifier, member: AuthorityMember) -> Result<()>;
 
+    /// Set the date after which a member can't get credentials anymore, or no date
+    /// if the membership never expires.
+    /// Return false if the member does not exist
+    async fn set_member_expiration(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> Result<bool>;
+
+    /// Suspend or resume a member.
+    /// The credentials issued before a suspension are revoked.
+    /// Return false if the member does not exist
+    async fn set_member_suspended(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+        is_suspended: bool,
+    ) -> Result<bool>;
+
     /// Remove the old pre-trusted members and store new pre-trusted members
     async fn bootstrap_pre_trusted_members(
         &self,
@@ -52,10 +77,36 @@ impl<T: AuthorityMembersRepository> AuthorityMembersRepository for AutoRetry<T>
         retry!(self.wrapped.delete_member(authority, identifier))
     }
 
+    async fn get_revoked_members(&self, authority: &Identifier) -> Result<Vec<RevokedSubject>> {
+        retry!(self.wrapped.get_revoked_members(authority))
+    }
+
     async fn add_member(&self, authority: &Identifier, member: AuthorityMember) -> Result<()> {
         retry!(self.wrapped.add_member(authority, member.clone()))
     }
 
+    async fn set_member_expiration(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> Result<bool> {
+        retry!(self
+            .wrapped
+            .set_member_expiration(authority, identifier, expires_at))
+    }
+
+    async fn set_member_suspended(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+        is_suspended: bool,
+    ) -> Result<bool> {
+        retry!(self
+            .wrapped
+            .set_member_suspended(authority, identifier, is_suspended))
+    }
+
     async fn bootstrap_pre_trusted_members(
         &self,
         authority: &Identifier,
@@ -1,12 +1,16 @@
 use core::ops::Deref;
+use core::str::FromStr;
 use sqlx::*;
 use std::sync::Arc;
 use tracing::debug;
 
 use crate::authenticator::{
-    AuthorityMember, AuthorityMemberRow, AuthorityMembersRepository, PreTrustedIdentities,
+    AuthorityMember, AuthorityMemberChange, AuthorityMemberRow, AuthorityMembersRepository,
+    PreTrustedIdentities,
 };
-use ockam::identity::Identifier;
+use ockam::identity::models::RevokedSubject;
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam_core::async_trait;
 use ockam_core::Result;
 use ockam_node::database::AutoRetry;
@@ -39,6 +43,102 @@ impl AuthorityMembersSqlxDatabase {
             SqlxDatabase::in_memory("authority members").await?,
         ))
     }
+
+    /// Apply a change of members in its own transaction
+    async fn apply(&self, authority: &Identifier, change: AuthorityMemberChange) -> Result<bool> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        let changed = Self::apply_change(&mut transaction, authority, &change).await?;
+        transaction.commit().await.void()?;
+        Ok(changed)
+    }
+
+    /// Apply a change of members within a transaction.
+    /// Return false if no member was modified, for example when the member does not exist
+    pub(crate) async fn apply_change(
+        transaction: &mut AnyConnection,
+        authority: &Identifier,
+        change: &AuthorityMemberChange,
+    ) -> Result<bool> {
+        match change {
+            AuthorityMemberChange::Add(member) => {
+                let query = query(r#"
+                     INSERT INTO authority_member (identifier, added_by, added_at, is_pre_trusted, attributes, authority_id, expires_at, is_suspended)
+                     VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
+                     ON CONFLICT (identifier)
+                     DO UPDATE SET added_by = $2, added_at = $3, is_pre_trusted = $4, attributes = $5, authority_id = $6, expires_at = $7, is_suspended = $8"#)
+                    .bind(member.identifier())
+                    .bind(member.added_by())
+                    .bind(member.added_at())
+                    .bind(member.is_pre_trusted())
+                    .bind(ockam_core::cbor_encode_preallocate(member.attributes())?)
+                    .bind(authority)
+                    .bind(member.expires_at())
+                    .bind(member.is_suspended());
+                query.execute(&mut *transaction).await.void()?;
+                Ok(true)
+            }
+            AuthorityMemberChange::Delete(identifier) => {
+                let query =
+                    query("DELETE FROM authority_member WHERE authority_id = $1 AND identifier = $2 AND is_pre_trusted = $3")
+                        .bind(authority)
+                        .bind(identifier)
+                        .bind(false);
+                let result = query.execute(&mut *transaction).await.into_core()?;
+
+                // Only record a revocation when a member was actually deleted
+                let deleted = result.rows_affected() > 0;
+                if deleted {
+                    Self::revoke_member(transaction, authority, identifier).await?;
+                }
+                Ok(deleted)
+            }
+            AuthorityMemberChange::SetExpiration(identifier, expires_at) => {
+                let query = query(
+                    "UPDATE authority_member SET expires_at = $1 WHERE authority_id = $2 AND identifier = $3",
+                )
+                .bind(*expires_at)
+                .bind(authority)
+                .bind(identifier);
+                let result = query.execute(&mut *transaction).await.into_core()?;
+                Ok(result.rows_affected() > 0)
+            }
+            AuthorityMemberChange::SetSuspended(identifier, is_suspended) => {
+                let query = query(
+                    "UPDATE authority_member SET is_suspended = $1 WHERE authority_id = $2 AND identifier = $3",
+                )
+                .bind(*is_suspended)
+                .bind(authority)
+                .bind(identifier);
+                let result = query.execute(&mut *transaction).await.into_core()?;
+
+                // The credentials issued before the suspension must be revoked
+                let updated = result.rows_affected() > 0;
+                if *is_suspended && updated {
+                    Self::revoke_member(transaction, authority, identifier).await?;
+                }
+                Ok(updated)
+            }
+        }
+    }
+
+    /// Record that the credentials issued to a member before now are revoked
+    async fn revoke_member(
+        transaction: &mut AnyConnection,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<()> {
+        let query = query(
+            r#"
+            INSERT INTO authority_revoked_member (identifier, authority_id, revoked_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT (identifier, authority_id)
+            DO UPDATE SET revoked_at = $3"#,
+        )
+        .bind(identifier)
+        .bind(authority)
+        .bind(now()?);
+        query.execute(&mut *transaction).await.void()
+    }
 }
 
 #[async_trait]
@@ -48,7 +148,7 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
         authority: &Identifier,
         identifier: &Identifier,
     ) -> Result<Option<AuthorityMember>> {
-        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes FROM authority_member WHERE authority_id = $1 AND identifier = $2")
+        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes, expires_at, is_suspended FROM authority_member WHERE authority_id = $1 AND identifier = $2")
             .bind(authority)
             .bind(identifier)
             ;
@@ -60,7 +160,7 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     }
 
     async fn get_members(&self, authority: &Identifier) -> Result<Vec<AuthorityMember>> {
-        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes FROM authority_member WHERE authority_id = $1");
+        let query = query_as("SELECT identifier, added_by, added_at, is_pre_trusted, attributes, expires_at, is_suspended FROM authority_member WHERE authority_id = $1");
         let row: Vec<AuthorityMemberRow> = query
             .bind(authority)
             .fetch_all(&*self.database.pool)
@@ -70,28 +170,51 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     }
 
     async fn delete_member(&self, authority: &Identifier, identifier: &Identifier) -> Result<()> {
-        let query =
-            query("DELETE FROM authority_member WHERE authority_id = $1 AND identifier = $2 AND is_pre_trusted = $3")
-                .bind(authority)
-                .bind(identifier)
-                .bind(false);
-        query.execute(&*self.database.pool).await.void()
+        self.apply(authority, AuthorityMemberChange::Delete(identifier.clone()))
+            .await
+            .map(|_| ())
+    }
+
+    async fn get_revoked_members(&self, authority: &Identifier) -> Result<Vec<RevokedSubject>> {
+        let query = query_as(
+            "SELECT identifier, revoked_at FROM authority_revoked_member WHERE authority_id = $1",
+        )
+        .bind(authority);
+        let rows: Vec<RevokedMemberRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.revoked_subject()).collect()
     }
 
     async fn add_member(&self, authority: &Identifier, member: AuthorityMember) -> Result<()> {
-        let query = query(r#"
-             INSERT INTO authority_member (identifier, added_by, added_at, is_pre_trusted, attributes, authority_id)
-             VALUES ($1, $2, $3, $4, $5, $6)
-             ON CONFLICT (identifier)
-             DO UPDATE SET added_by = $2, added_at = $3, is_pre_trusted = $4, attributes = $5, authority_id = $6"#)
-            .bind(member.identifier())
-            .bind(member.added_by())
-            .bind(member.added_at())
-            .bind(member.is_pre_trusted())
-            .bind(ockam_core::cbor_encode_preallocate(member.attributes())?)
-            .bind(authority);
-
-        query.execute(&*self.database.pool).await.void()
+        self.apply(authority, AuthorityMemberChange::Add(member))
+            .await
+            .map(|_| ())
+    }
+
+    async fn set_member_expiration(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+        expires_at: Option<TimestampInSeconds>,
+    ) -> Result<bool> {
+        self.apply(
+            authority,
+            AuthorityMemberChange::SetExpiration(identifier.clone(), expires_at),
+        )
+        .await
+    }
+
+    async fn set_member_suspended(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+        is_suspended: bool,
+    ) -> Result<bool> {
+        self.apply(
+            authority,
+            AuthorityMemberChange::SetSuspended(identifier.clone(), is_suspended),
+        )
+        .await
     }
 
     async fn bootstrap_pre_trusted_members(
@@ -109,16 +232,18 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
         for (identifier, pre_trusted_identity) in pre_trusted_identities.deref() {
             let query2 =
                 query(r#"
-                      INSERT INTO authority_member (identifier, added_by, added_at, is_pre_trusted, attributes, authority_id)
-                      VALUES ($1, $2, $3, $4, $5, $6)
+                      INSERT INTO authority_member (identifier, added_by, added_at, is_pre_trusted, attributes, authority_id, expires_at, is_suspended)
+                      VALUES ($1, $2, $3, $4, $5, $6, $7, $8)
                       ON CONFLICT (identifier)
-                      DO UPDATE SET added_by = $2, added_at = $3, is_pre_trusted = $4, attributes = $5, authority_id = $6"#)
+                      DO UPDATE SET added_by = $2, added_at = $3, is_pre_trusted = $4, attributes = $5, authority_id = $6, expires_at = $7, is_suspended = $8"#)
                     .bind(identifier)
                     .bind(pre_trusted_identity.attested_by())
                     .bind(pre_trusted_identity.added_at())
                     .bind(true)
                     .bind(ockam_core::cbor_encode_preallocate(pre_trusted_identity.attrs())?)
-                    .bind(authority);
+                    .bind(authority)
+                    .bind(pre_trusted_identity.expires_at())
+                    .bind(false);
 
             query2.execute(&mut *transaction).await.void()?;
         }
@@ -127,6 +252,22 @@ impl AuthorityMembersRepository for AuthorityMembersSqlxDatabase {
     }
 }
 
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RevokedMemberRow {
+    identifier: String,
+    revoked_at: i64,
+}
+
+impl RevokedMemberRow {
+    fn revoked_subject(self) -> Result<RevokedSubject> {
+        Ok(RevokedSubject {
+            subject: Identifier::from_str(&self.identifier)?,
+            revoked_at: TimestampInSeconds(self.revoked_at as u64),
+        })
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -206,6 +347,107 @@ mod tests {
             assert_eq!(members.len(), 1);
             assert!(members.contains(&member2));
 
+            // the deleted member is now revoked
+            let revoked = repository.get_revoked_members(&authority).await?;
+            assert_eq!(revoked.len(), 1);
+            assert_eq!(revoked[0].subject, identifier1);
+            assert!(revoked[0].revoked_at >= timestamp1);
+
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_authority_members_expiration_and_suspension() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityMembersRepository> =
+                Arc::new(AuthorityMembersSqlxDatabase::new(db));
+
+            let admin = random_identifier();
+            let authority = random_identifier();
+            let identifier = random_identifier();
+            let timestamp = now()?;
+            let member = AuthorityMember::new(
+                identifier.clone(),
+                BTreeMap::default(),
+                admin.clone(),
+                timestamp,
+                false,
+            )
+            .with_expires_at(Some(timestamp + 10));
+            repository.add_member(&authority, member.clone()).await?;
+
+            let m = repository
+                .get_member(&authority, &identifier)
+                .await?
+                .unwrap();
+            assert_eq!(m, member);
+            assert!(!m.is_expired(timestamp));
+            assert!(m.is_expired(timestamp + 10));
+
+            // the membership can be extended, or made permanent
+            assert!(
+                repository
+                    .set_member_expiration(&authority, &identifier, Some(timestamp + 20))
+                    .await?
+            );
+            let m = repository
+                .get_member(&authority, &identifier)
+                .await?
+                .unwrap();
+            assert_eq!(m.expires_at(), Some(timestamp + 20));
+
+            assert!(
+                repository
+                    .set_member_expiration(&authority, &identifier, None)
+                    .await?
+            );
+            let m = repository
+                .get_member(&authority, &identifier)
+                .await?
+                .unwrap();
+            assert_eq!(m.expires_at(), None);
+
+            // a suspended member is revoked
+            assert!(
+                repository
+                    .set_member_suspended(&authority, &identifier, true)
+                    .await?
+            );
+            let m = repository
+                .get_member(&authority, &identifier)
+                .await?
+                .unwrap();
+            assert!(m.is_suspended());
+            let revoked = repository.get_revoked_members(&authority).await?;
+            assert_eq!(revoked.len(), 1);
+            assert_eq!(revoked[0].subject, identifier);
+
+            assert!(
+                repository
+                    .set_member_suspended(&authority, &identifier, false)
+                    .await?
+            );
+            let m = repository
+                .get_member(&authority, &identifier)
+                .await?
+                .unwrap();
+            assert!(!m.is_suspended());
+
+            // unknown members can't be updated
+            let unknown = random_identifier();
+            assert!(
+                !repository
+                    .set_member_suspended(&authority, &unknown, true)
+                    .await?
+            );
+            assert!(
+                !repository
+                    .set_member_expiration(&authority, &unknown, None)
+                    .await?
+            );
+
             Ok(())
         })
         .await
@@ -272,6 +514,7 @@ mod tests {
             assert_eq!(member2.added_at(), timestamp2);
             assert_eq!(member2.added_by(), &identifier1);
             assert_eq!(member2.attributes(), &attributes2);
+            assert_eq!(member2.expires_at(), Some(timestamp3));
             assert!(member2.is_pre_trusted());
 
             repository.delete_member(&authority, &identifier1).await?;
@@ -281,6 +524,10 @@ mod tests {
             assert!(members.contains(member2));
             assert!(members.contains(member1));
 
+            // pre-trusted members are not revoked
+            let revoked = repository.get_revoked_members(&authority).await?;
+            assert!(revoked.is_empty());
+
             Ok(())
         })
         .await
@@ -0,0 +1,134 @@
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use ockam_node::database::AutoRetry;
+use ockam_node::retry;
+
+use crate::authenticator::{ScimGroupRecord, ScimUserRecord};
+
+/// This repository stores the users and groups provisioned with SCIM on the Authority node
+#[async_trait]
+pub trait AuthorityScimRepository: Send + Sync + 'static {
+    /// Create or replace a user
+    async fn store_user(&self, authority: &Identifier, user: ScimUserRecord) -> Result<()>;
+
+    /// Return a user
+    async fn get_user(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<Option<ScimUserRecord>>;
+
+    /// Return all the users
+    async fn get_users(&self, authority: &Identifier) -> Result<Vec<ScimUserRecord>>;
+
+    /// Delete a user and remove it from all the groups.
+    /// Return false if the user does not exist
+    async fn delete_user(&self, authority: &Identifier, identifier: &Identifier) -> Result<bool>;
+
+    /// Create or replace a group, with its members
+    async fn store_group(&self, authority: &Identifier, group: ScimGroupRecord) -> Result<()>;
+
+    /// Return a group
+    async fn get_group(
+        &self,
+        authority: &Identifier,
+        group_id: &str,
+    ) -> Result<Option<ScimGroupRecord>>;
+
+    /// Return all the groups
+    async fn get_groups(&self, authority: &Identifier) -> Result<Vec<ScimGroupRecord>>;
+
+    /// Return the groups containing a given member
+    async fn get_member_groups(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<Vec<ScimGroupRecord>>;
+
+    /// Delete a group.
+    /// Return false if the group does not exist
+    async fn delete_group(&self, authority: &Identifier, group_id: &str) -> Result<bool>;
+
+    /// Acquire the lock serializing the SCIM modifications, until it is released or expires.
+    /// Return false if the lock is already held by another holder
+    async fn acquire_lock(
+        &self,
+        authority: &Identifier,
+        holder: &str,
+        expires_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> Result<bool>;
+
+    /// Release the lock, if it is still held by the given holder
+    async fn release_lock(&self, authority: &Identifier, holder: &str) -> Result<()>;
+}
+
+#[async_trait]
+impl<T: AuthorityScimRepository> AuthorityScimRepository for AutoRetry<T> {
+    async fn store_user(&self, authority: &Identifier, user: ScimUserRecord) -> Result<()> {
+        retry!(self.wrapped.store_user(authority, user.clone()))
+    }
+
+    async fn get_user(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<Option<ScimUserRecord>> {
+        retry!(self.wrapped.get_user(authority, identifier))
+    }
+
+    async fn get_users(&self, authority: &Identifier) -> Result<Vec<ScimUserRecord>> {
+        retry!(self.wrapped.get_users(authority))
+    }
+
+    async fn delete_user(&self, authority: &Identifier, identifier: &Identifier) -> Result<bool> {
+        retry!(self.wrapped.delete_user(authority, identifier))
+    }
+
+    async fn store_group(&self, authority: &Identifier, group: ScimGroupRecord) -> Result<()> {
+        retry!(self.wrapped.store_group(authority, group.clone()))
+    }
+
+    async fn get_group(
+        &self,
+        authority: &Identifier,
+        group_id: &str,
+    ) -> Result<Option<ScimGroupRecord>> {
+        retry!(self.wrapped.get_group(authority, group_id))
+    }
+
+    async fn get_groups(&self, authority: &Identifier) -> Result<Vec<ScimGroupRecord>> {
+        retry!(self.wrapped.get_groups(authority))
+    }
+
+    async fn get_member_groups(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<Vec<ScimGroupRecord>> {
+        retry!(self.wrapped.get_member_groups(authority, identifier))
+    }
+
+    async fn delete_group(&self, authority: &Identifier, group_id: &str) -> Result<bool> {
+        retry!(self.wrapped.delete_group(authority, group_id))
+    }
+
+    async fn acquire_lock(
+        &self,
+        authority: &Identifier,
+        holder: &str,
+        expires_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> Result<bool> {
+        retry!(self
+            .wrapped
+            .acquire_lock(authority, holder, expires_at, now))
+    }
+
+    async fn release_lock(&self, authority: &Identifier, holder: &str) -> Result<()> {
+        retry!(self.wrapped.release_lock(authority, holder))
+    }
+}
@@ -0,0 +1,452 @@
+use core::str::FromStr;
+use sqlx::*;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::authenticator::{
+    AuthorityScimRepository, ScimGroupRecord, ScimGroupRow, ScimUserRecord, ScimUserRow,
+};
+use crate::ApiError;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::AutoRetry;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+/// Implementation of [`AuthorityScimRepository`] trait based on an underlying database
+/// using sqlx as its API
+#[derive(Clone)]
+pub struct AuthorityScimSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityScimSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for the authority SCIM resources");
+        Self { database }
+    }
+
+    /// Create a repository
+    pub fn make_repository(database: SqlxDatabase) -> Arc<dyn AuthorityScimRepository> {
+        if database.needs_retry() {
+            Arc::new(AutoRetry::new(Self::new(database)))
+        } else {
+            Arc::new(Self::new(database))
+        }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority SCIM resources").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityScimRepository for AuthorityScimSqlxDatabase {
+    async fn store_user(&self, authority: &Identifier, user: ScimUserRecord) -> Result<()> {
+        let resource = serde_json::to_string(&user.resource)
+            .map_err(|e| ApiError::core(format!("invalid SCIM user: {e}")))?;
+        let query = query(
+            r#"
+            INSERT INTO authority_scim_user (authority_id, identifier, resource, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5)
+            ON CONFLICT (authority_id, identifier)
+            DO UPDATE SET resource = $3, created_at = $4, updated_at = $5"#,
+        )
+        .bind(authority)
+        .bind(&user.identifier)
+        .bind(resource)
+        .bind(user.created_at)
+        .bind(user.updated_at);
+        query.execute(&*self.database.pool).await.void()
+    }
+
+    async fn get_user(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<Option<ScimUserRecord>> {
+        let query = query_as("SELECT identifier, resource, created_at, updated_at FROM authority_scim_user WHERE authority_id = $1 AND identifier = $2")
+            .bind(authority)
+            .bind(identifier);
+        let row: Option<ScimUserRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.try_into()).transpose()
+    }
+
+    async fn get_users(&self, authority: &Identifier) -> Result<Vec<ScimUserRecord>> {
+        let query = query_as("SELECT identifier, resource, created_at, updated_at FROM authority_scim_user WHERE authority_id = $1 ORDER BY created_at, identifier")
+            .bind(authority);
+        let rows: Vec<ScimUserRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter().map(|r| r.try_into()).collect()
+    }
+
+    async fn delete_user(&self, authority: &Identifier, identifier: &Identifier) -> Result<bool> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        let query1 =
+            query("DELETE FROM authority_scim_user WHERE authority_id = $1 AND identifier = $2")
+                .bind(authority)
+                .bind(identifier);
+        let result = query1.execute(&mut *transaction).await.into_core()?;
+
+        let query2 = query(
+            "DELETE FROM authority_scim_group_member WHERE authority_id = $1 AND identifier = $2",
+        )
+        .bind(authority)
+        .bind(identifier);
+        query2.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn store_group(&self, authority: &Identifier, group: ScimGroupRecord) -> Result<()> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        let query1 = query(
+            r#"
+            INSERT INTO authority_scim_group (authority_id, group_id, display_name, external_id, created_at, updated_at)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (authority_id, group_id)
+            DO UPDATE SET display_name = $3, external_id = $4, created_at = $5, updated_at = $6"#,
+        )
+        .bind(authority)
+        .bind(&group.id)
+        .bind(&group.display_name)
+        .bind(&group.external_id)
+        .bind(group.created_at)
+        .bind(group.updated_at);
+        query1.execute(&mut *transaction).await.void()?;
+
+        let query2 = query(
+            "DELETE FROM authority_scim_group_member WHERE authority_id = $1 AND group_id = $2",
+        )
+        .bind(authority)
+        .bind(&group.id);
+        query2.execute(&mut *transaction).await.void()?;
+
+        for member in group.members.iter() {
+            let query3 = query(
+                r#"
+                INSERT INTO authority_scim_group_member (authority_id, group_id, identifier)
+                VALUES ($1, $2, $3)
+                ON CONFLICT DO NOTHING"#,
+            )
+            .bind(authority)
+            .bind(&group.id)
+            .bind(member);
+            query3.execute(&mut *transaction).await.void()?;
+        }
+
+        transaction.commit().await.void()
+    }
+
+    async fn get_group(
+        &self,
+        authority: &Identifier,
+        group_id: &str,
+    ) -> Result<Option<ScimGroupRecord>> {
+        let query = query_as("SELECT group_id, display_name, external_id, created_at, updated_at FROM authority_scim_group WHERE authority_id = $1 AND group_id = $2")
+            .bind(authority)
+            .bind(group_id);
+        let row: Option<ScimGroupRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        match row {
+            Some(row) => {
+                let mut members = self.get_groups_members(authority).await?;
+                let group_members = members.remove(&row.group_id).unwrap_or_default();
+                Ok(Some(row.scim_group(group_members)))
+            }
+            None => Ok(None),
+        }
+    }
+
+    async fn get_groups(&self, authority: &Identifier) -> Result<Vec<ScimGroupRecord>> {
+        let query = query_as("SELECT group_id, display_name, external_id, created_at, updated_at FROM authority_scim_group WHERE authority_id = $1 ORDER BY created_at, group_id")
+            .bind(authority);
+        let rows: Vec<ScimGroupRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
+        let mut members = self.get_groups_members(authority).await?;
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let group_members = members.remove(&row.group_id).unwrap_or_default();
+                row.scim_group(group_members)
+            })
+            .collect())
+    }
+
+    async fn get_member_groups(
+        &self,
+        authority: &Identifier,
+        identifier: &Identifier,
+    ) -> Result<Vec<ScimGroupRecord>> {
+        Ok(self
+            .get_groups(authority)
+            .await?
+            .into_iter()
+            .filter(|g| g.members.contains(identifier))
+            .collect())
+    }
+
+    async fn delete_group(&self, authority: &Identifier, group_id: &str) -> Result<bool> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        let query1 =
+            query("DELETE FROM authority_scim_group WHERE authority_id = $1 AND group_id = $2")
+                .bind(authority)
+                .bind(group_id);
+        let result = query1.execute(&mut *transaction).await.into_core()?;
+
+        let query2 = query(
+            "DELETE FROM authority_scim_group_member WHERE authority_id = $1 AND group_id = $2",
+        )
+        .bind(authority)
+        .bind(group_id);
+        query2.execute(&mut *transaction).await.void()?;
+
+        transaction.commit().await.void()?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn acquire_lock(
+        &self,
+        authority: &Identifier,
+        holder: &str,
+        expires_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> Result<bool> {
+        // A lock which was not released, because its holder stopped, can be taken over
+        let query1 =
+            query("DELETE FROM authority_scim_lock WHERE authority_id = $1 AND expires_at <= $2")
+                .bind(authority)
+                .bind(now);
+        query1.execute(&*self.database.pool).await.void()?;
+
+        // The unique index makes sure that the lock has only one holder,
+        // even when several authority nodes share the same database
+        let query2 = query(
+            r#"
+            INSERT INTO authority_scim_lock (authority_id, holder, expires_at)
+            VALUES ($1, $2, $3)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(authority)
+        .bind(holder)
+        .bind(expires_at);
+        let result = query2.execute(&*self.database.pool).await.into_core()?;
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn release_lock(&self, authority: &Identifier, holder: &str) -> Result<()> {
+        let query =
+            query("DELETE FROM authority_scim_lock WHERE authority_id = $1 AND holder = $2")
+                .bind(authority)
+                .bind(holder);
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+impl AuthorityScimSqlxDatabase {
+    /// Return the members of all the groups, indexed by group id
+    async fn get_groups_members(
+        &self,
+        authority: &Identifier,
+    ) -> Result<BTreeMap<String, Vec<Identifier>>> {
+        let query = query_as("SELECT group_id, identifier FROM authority_scim_group_member WHERE authority_id = $1 ORDER BY identifier")
+            .bind(authority);
+        let rows: Vec<ScimGroupMemberRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        let mut members: BTreeMap<String, Vec<Identifier>> = BTreeMap::new();
+        for row in rows {
+            members
+                .entry(row.group_id)
+                .or_default()
+                .push(Identifier::from_str(&row.identifier)?);
+        }
+        Ok(members)
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct ScimGroupMemberRow {
+    group_id: String,
+    identifier: String,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::identities;
+    use ockam_node::database::with_dbs;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn test_authority_scim_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityScimRepository> =
+                Arc::new(AuthorityScimSqlxDatabase::new(db));
+
+            let identities = identities().await?;
+            let authority = identities.identities_creation().create_identity().await?;
+            let member1 = identities.identities_creation().create_identity().await?;
+            let member2 = identities.identities_creation().create_identity().await?;
+
+            // store and update users
+            let user1 = ScimUserRecord {
+                identifier: member1.clone(),
+                resource: json!({"userName": member1.to_string(), "active": true}),
+                created_at: TimestampInSeconds(1),
+                updated_at: TimestampInSeconds(1),
+            };
+            let user2 = ScimUserRecord {
+                identifier: member2.clone(),
+                resource: json!({"userName": member2.to_string(), "title": "engineer"}),
+                created_at: TimestampInSeconds(2),
+                updated_at: TimestampInSeconds(2),
+            };
+            repository.store_user(&authority, user1.clone()).await?;
+            repository.store_user(&authority, user2.clone()).await?;
+
+            let user1 = ScimUserRecord {
+                resource: json!({"userName": member1.to_string(), "active": false}),
+                updated_at: TimestampInSeconds(3),
+                ..user1
+            };
+            repository.store_user(&authority, user1.clone()).await?;
+            assert_eq!(
+                repository.get_user(&authority, &member1).await?,
+                Some(user1.clone())
+            );
+            assert_eq!(
+                repository.get_users(&authority).await?,
+                vec![user1.clone(), user2.clone()]
+            );
+
+            // store and update groups
+            let group1 = ScimGroupRecord {
+                id: "group1".to_string(),
+                display_name: "Engineering".to_string(),
+                external_id: Some("eng".to_string()),
+                members: vec![member1.clone(), member2.clone()],
+                created_at: TimestampInSeconds(1),
+                updated_at: TimestampInSeconds(1),
+            };
+            let group2 = ScimGroupRecord {
+                id: "group2".to_string(),
+                display_name: "Sales".to_string(),
+                external_id: None,
+                members: vec![member1.clone()],
+                created_at: TimestampInSeconds(2),
+                updated_at: TimestampInSeconds(2),
+            };
+            repository.store_group(&authority, group1.clone()).await?;
+            repository.store_group(&authority, group2.clone()).await?;
+
+            let mut expected_members = vec![member1.clone(), member2.clone()];
+            expected_members.sort_by_key(|m| m.to_string());
+            let group1 = ScimGroupRecord {
+                members: expected_members,
+                ..group1
+            };
+            assert_eq!(
+                repository.get_group(&authority, "group1").await?,
+                Some(group1.clone())
+            );
+            assert_eq!(
+                repository.get_member_groups(&authority, &member2).await?,
+                vec![group1.clone()]
+            );
+
+            let group2 = ScimGroupRecord {
+                display_name: "Marketing".to_string(),
+                members: vec![member2.clone()],
+                updated_at: TimestampInSeconds(3),
+                ..group2
+            };
+            repository.store_group(&authority, group2.clone()).await?;
+            assert_eq!(
+                repository.get_member_groups(&authority, &member1).await?,
+                vec![group1.clone()]
+            );
+
+            // deleting a user removes it from the groups
+            assert!(repository.delete_user(&authority, &member2).await?);
+            assert!(!repository.delete_user(&authority, &member2).await?);
+            assert_eq!(repository.get_users(&authority).await?, vec![user1]);
+            assert_eq!(
+                repository.get_group(&authority, "group2").await?,
+                Some(ScimGroupRecord {
+                    members: vec![],
+                    ..group2
+                })
+            );
+
+            // delete a group
+            assert!(repository.delete_group(&authority, "group1").await?);
+            assert!(!repository.delete_group(&authority, "group1").await?);
+            assert_eq!(repository.get_group(&authority, "group1").await?, None);
+            assert!(repository
+                .get_member_groups(&authority, &member1)
+                .await?
+                .is_empty());
+            Ok(())
+        })
+        .await
+    }
+
+    #[tokio::test]
+    async fn test_authority_scim_repository_lock() -> Result<()> {
+        with_dbs(|db| async move {
+            // two repositories for two authority nodes sharing the same database
+            let repository1 = AuthorityScimSqlxDatabase::make_repository(db.clone());
+            let repository2 = AuthorityScimSqlxDatabase::make_repository(db);
+
+            let identities = identities().await?;
+            let authority = identities.identities_creation().create_identity().await?;
+            let now = TimestampInSeconds(100);
+            let expires_at = TimestampInSeconds(130);
+
+            // the lock has only one holder
+            assert!(
+                repository1
+                    .acquire_lock(&authority, "holder1", expires_at, now)
+                    .await?
+            );
+            assert!(
+                !repository2
+                    .acquire_lock(&authority, "holder2", expires_at, now)
+                    .await?
+            );
+
+            // only the holder can release the lock
+            repository2.release_lock(&authority, "holder2").await?;
+            assert!(
+                !repository2
+                    .acquire_lock(&authority, "holder2", expires_at, now)
+                    .await?
+            );
+            repository1.release_lock(&authority, "holder1").await?;
+            assert!(
+                repository2
+                    .acquire_lock(&authority, "holder2", expires_at, now)
+                    .await?
+            );
+
+            // an expired lock can be taken over
+            assert!(
+                repository1
+                    .acquire_lock(&authority, "holder1", TimestampInSeconds(160), expires_at)
+                    .await?
+            );
+            Ok(())
+        })
+        .await
+    }
+}
@@ -0,0 +1,37 @@
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+use ockam_node::database::AutoRetry;
+use ockam_node::retry;
+
+/// This repository stores the JWTs used to enroll members on the Authority node
+#[async_trait]
+pub trait AuthorityUsedJwtRepository: Send + Sync + 'static {
+    /// Mark a token as used, until it expires.
+    /// Return false if the token has already been used
+    async fn use_jwt(
+        &self,
+        authority: &Identifier,
+        issuer: &str,
+        jwt_id: &str,
+        expires_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> Result<bool>;
+}
+
+#[async_trait]
+impl<T: AuthorityUsedJwtRepository> AuthorityUsedJwtRepository for AutoRetry<T> {
+    async fn use_jwt(
+        &self,
+        authority: &Identifier,
+        issuer: &str,
+        jwt_id: &str,
+        expires_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> Result<bool> {
+        retry!(self
+            .wrapped
+            .use_jwt(authority, issuer, jwt_id, expires_at, now))
+    }
+}
@@ -0,0 +1,135 @@
+use sqlx::*;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::authenticator::AuthorityUsedJwtRepository;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::AutoRetry;
+use ockam_node::database::{FromSqlxError, SqlxDatabase};
+
+/// Implementation of [`AuthorityUsedJwtRepository`] trait based on an underlying database
+/// using sqlx as its API
+#[derive(Clone)]
+pub struct AuthorityUsedJwtSqlxDatabase {
+    database: SqlxDatabase,
+}
+
+impl AuthorityUsedJwtSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase) -> Self {
+        debug!("create a repository for the JWTs used on the authority");
+        Self { database }
+    }
+
+    /// Create a repository
+    pub fn make_repository(database: SqlxDatabase) -> Arc<dyn AuthorityUsedJwtRepository> {
+        if database.needs_retry() {
+            Arc::new(AutoRetry::new(Self::new(database)))
+        } else {
+            Arc::new(Self::new(database))
+        }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("authority used JWTs").await?,
+        ))
+    }
+}
+
+#[async_trait]
+impl AuthorityUsedJwtRepository for AuthorityUsedJwtSqlxDatabase {
+    async fn use_jwt(
+        &self,
+        authority: &Identifier,
+        issuer: &str,
+        jwt_id: &str,
+        expires_at: TimestampInSeconds,
+        now: TimestampInSeconds,
+    ) -> Result<bool> {
+        // Expired tokens are rejected anyway, there is no need to keep them
+        let query1 =
+            query("DELETE FROM authority_used_jwt WHERE expires_at <= $1").bind(now.0 as i64);
+        let res = query1.execute(&*self.database.pool).await.into_core()?;
+        debug!("Deleted {} expired JWTs", res.rows_affected());
+
+        // The unique index makes sure that a token is only used once,
+        // even when several authority nodes share the same database
+        let query2 = query(
+            r#"
+            INSERT INTO authority_used_jwt (authority_id, issuer, jwt_id, expires_at)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT DO NOTHING"#,
+        )
+        .bind(authority)
+        .bind(issuer)
+        .bind(jwt_id)
+        .bind(expires_at);
+        let result = query2.execute(&*self.database.pool).await.into_core()?;
+        Ok(result.rows_affected() > 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::identities;
+    use ockam_node::database::with_dbs;
+
+    #[tokio::test]
+    async fn test_authority_used_jwt_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn AuthorityUsedJwtRepository> =
+                Arc::new(AuthorityUsedJwtSqlxDatabase::new(db));
+
+            let identities = identities().await?;
+            let authority = identities.identities_creation().create_identity().await?;
+            let issuer = "https://issuer.example.com";
+
+            // a token can only be used once
+            let now = TimestampInSeconds(100);
+            let expires_at = TimestampInSeconds(200);
+            assert!(
+                repository
+                    .use_jwt(&authority, issuer, "jti-1", expires_at, now)
+                    .await?
+            );
+            assert!(
+                !repository
+                    .use_jwt(&authority, issuer, "jti-1", expires_at, now)
+                    .await?
+            );
+
+            // tokens are distinguished by their issuer and identifier
+            assert!(
+                repository
+                    .use_jwt(&authority, issuer, "jti-2", expires_at, now)
+                    .await?
+            );
+            assert!(
+                repository
+                    .use_jwt(
+                        &authority,
+                        "https://other.example.com",
+                        "jti-1",
+                        expires_at,
+                        now
+                    )
+                    .await?
+            );
+
+            // used tokens are forgotten once they have expired
+            let later = TimestampInSeconds(200);
+            assert!(
+                repository
+                    .use_jwt(&authority, issuer, "jti-1", TimestampInSeconds(300), later)
+                    .await?
+            );
+            Ok(())
+        })
+        .await
+    }
+}
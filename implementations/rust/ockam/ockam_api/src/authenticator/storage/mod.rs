@@ -1,13 +1,27 @@
+mod authority_audit_log_repository;
+mod authority_audit_log_repository_sql;
 mod authority_enrollment_token_repository;
 mod authority_enrollment_token_repository_sql;
 mod authority_member;
 mod authority_members_repository;
 mod authority_members_repository_sql;
+mod authority_scim_repository;
+mod authority_scim_repository_sql;
+mod authority_used_jwt_repository;
+mod authority_used_jwt_repository_sql;
 mod enrollment_token;
+mod scim_resource;
 
+pub use authority_audit_log_repository::*;
+pub use authority_audit_log_repository_sql::*;
 pub use authority_enrollment_token_repository::*;
 pub use authority_enrollment_token_repository_sql::*;
 pub use authority_member::*;
 pub use authority_members_repository::*;
 pub use authority_members_repository_sql::*;
+pub use authority_scim_repository::*;
+pub use authority_scim_repository_sql::*;
+pub use authority_used_jwt_repository::*;
+pub use authority_used_jwt_repository_sql::*;
 pub use enrollment_token::*;
+pub use scim_resource::*;
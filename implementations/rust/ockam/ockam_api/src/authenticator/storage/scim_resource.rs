@@ -0,0 +1,83 @@
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam_core::compat::str::FromStr;
+use ockam_core::{Error, Result};
+use ockam_node::database::Nullable;
+use serde_json::Value;
+
+use crate::ApiError;
+
+/// User provisioned with SCIM on the Authority node
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScimUserRecord {
+    /// Identifier of the project member, used as the SCIM user name
+    pub identifier: Identifier,
+    /// JSON representation of the user, as provided by the SCIM client
+    pub resource: Value,
+    /// Provisioning timestamp
+    pub created_at: TimestampInSeconds,
+    /// Last modification timestamp
+    pub updated_at: TimestampInSeconds,
+}
+
+/// Group provisioned with SCIM on the Authority node
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScimGroupRecord {
+    /// Identifier of the group, generated by the Authority
+    pub id: String,
+    /// Name of the group
+    pub display_name: String,
+    /// Identifier of the group for the SCIM client
+    pub external_id: Option<String>,
+    /// Members of the group
+    pub members: Vec<Identifier>,
+    /// Provisioning timestamp
+    pub created_at: TimestampInSeconds,
+    /// Last modification timestamp
+    pub updated_at: TimestampInSeconds,
+}
+
+// Low-level representation of a table row
+#[derive(sqlx::FromRow)]
+pub(crate) struct ScimUserRow {
+    identifier: String,
+    resource: String,
+    created_at: i64,
+    updated_at: i64,
+}
+
+impl TryFrom<ScimUserRow> for ScimUserRecord {
+    type Error = Error;
+
+    fn try_from(value: ScimUserRow) -> Result<Self, Self::Error> {
+        Ok(ScimUserRecord {
+            identifier: Identifier::from_str(&value.identifier)?,
+            resource: serde_json::from_str(&value.resource)
+                .map_err(|e| ApiError::core(format!("invalid SCIM user: {e}")))?,
+            created_at: TimestampInSeconds(value.created_at as u64),
+            updated_at: TimestampInSeconds(value.updated_at as u64),
+        })
+    }
+}
+
+// Low-level representation of a table row, without the group members
+#[derive(sqlx::FromRow)]
+pub(crate) struct ScimGroupRow {
+    pub(crate) group_id: String,
+    display_name: String,
+    external_id: Nullable<String>,
+    created_at: i64,
+    updated_at: i64,
+}
+
+impl ScimGroupRow {
+    pub(crate) fn scim_group(self, members: Vec<Identifier>) -> ScimGroupRecord {
+        ScimGroupRecord {
+            id: self.group_id,
+            display_name: self.display_name,
+            external_id: self.external_id.to_option(),
+            members,
+            created_at: TimestampInSeconds(self.created_at as u64),
+            updated_at: TimestampInSeconds(self.updated_at as u64),
+        }
+    }
+}
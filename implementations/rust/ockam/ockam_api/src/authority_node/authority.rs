@@ -1,14 +1,19 @@
 use std::collections::BTreeMap;
 use tracing::info;
 
+use crate::authenticator::audit::{AuditLogWorker, AuthorityAuditLog};
 use crate::authenticator::credential_issuer::CredentialIssuerWorker;
 use crate::authenticator::direct::{AccountAuthorityInfo, DirectAuthenticatorWorker};
 use crate::authenticator::enrollment_tokens::{
     EnrollmentTokenAcceptorWorker, EnrollmentTokenIssuerWorker,
 };
+use crate::authenticator::oidc::OidcAuthenticatorWorker;
+use crate::authenticator::scim::{ScimProvisioner, ScimServer};
 use crate::authenticator::{
-    AuthorityEnrollmentTokenRepository, AuthorityEnrollmentTokenSqlxDatabase, AuthorityMember,
-    AuthorityMembersRepository, AuthorityMembersSqlxDatabase,
+    AuthorityAuditLogSqlxDatabase, AuthorityEnrollmentTokenRepository,
+    AuthorityEnrollmentTokenSqlxDatabase, AuthorityMember, AuthorityMembersRepository,
+    AuthorityMembersSqlxDatabase, AuthorityScimRepository, AuthorityScimSqlxDatabase,
+    AuthorityUsedJwtRepository, AuthorityUsedJwtSqlxDatabase,
 };
 use ockam::identity::utils::now;
 use ockam::identity::{
@@ -22,6 +27,7 @@ use ockam_core::flow_control::FlowControlId;
 use ockam_core::Result;
 use ockam_node::database::SqlxDatabase;
 use ockam_node::Context;
+use ockam_vault::storage::{KeyEncryptionKeyProvider, SecretsSqlxDatabase};
 
 use crate::authority_node::Configuration;
 use crate::echoer::Echoer;
@@ -35,12 +41,17 @@ use crate::nodes::service::default_address::DefaultAddress;
 //   - a credential issuer: return the attributes of a member as a time-limited credential.
 //   - an enrollment token issuer: create a token attributed allowing an identity to acquire some specific attributes.
 //   - an enrollment token acceptor: create or update a member, given a token.
+//   - an audit log service: list the signed entries recording the changes of members.
+//   - a SCIM server: provision members from an identity management system, over HTTP.
 #[derive(Clone)]
 pub struct Authority {
     identifier: Identifier,
     secure_channels: Arc<SecureChannels>,
     members: Arc<dyn AuthorityMembersRepository>,
     tokens: Arc<dyn AuthorityEnrollmentTokenRepository>,
+    used_jwts: Arc<dyn AuthorityUsedJwtRepository>,
+    audit_log: Arc<AuthorityAuditLog>,
+    scim: Arc<dyn AuthorityScimRepository>,
     account_authority: Option<AccountAuthorityInfo>,
 }
 
@@ -61,9 +72,32 @@ impl Authority {
     /// Create an identity for an authority from the configured public identity and configured vault
     /// The list of trusted identities in the configuration is used to pre-populate an attributes storage
     /// In practice it contains the list of identities with the ockam-role attribute set as 'enroller'
+    ///
+    /// The secrets of the authority are encrypted with the key-encryption key configured
+    /// with the OCKAM_VAULT_KEK or OCKAM_VAULT_PASSPHRASE environment variables, if any.
     pub async fn create(
         configuration: &Configuration,
         database: Option<SqlxDatabase>,
+    ) -> Result<Self> {
+        let database = if let Some(database) = database {
+            database
+        } else {
+            SqlxDatabase::create(&configuration.database_configuration).await?
+        };
+        let key_encryption_key = SecretsSqlxDatabase::new(database.clone())
+            .key_encryption_key_from_env()
+            .await?
+            .map(|key| Arc::new(key) as Arc<dyn KeyEncryptionKeyProvider>);
+        Self::create_with_key_encryption_key(configuration, Some(database), key_encryption_key)
+            .await
+    }
+
+    /// Create an authority whose secrets are encrypted with a specific key-encryption key.
+    /// The secrets are stored in plain text if no key-encryption key is given.
+    pub async fn create_with_key_encryption_key(
+        configuration: &Configuration,
+        database: Option<SqlxDatabase>,
+        key_encryption_key: Option<Arc<dyn KeyEncryptionKeyProvider>>,
     ) -> Result<Self> {
         debug!(?configuration, "creating the authority");
 
@@ -74,21 +108,38 @@ impl Authority {
         } else {
             SqlxDatabase::create(&configuration.database_configuration).await?
         };
+        let secrets_repository = match key_encryption_key {
+            Some(key_encryption_key) => {
+                SecretsSqlxDatabase::make_encrypted_repository(database.clone(), key_encryption_key)
+                    .await?
+            }
+            None => SecretsSqlxDatabase::make_repository(database.clone()),
+        };
 
         let members = AuthorityMembersSqlxDatabase::make_repository(database.clone());
         let tokens = AuthorityEnrollmentTokenSqlxDatabase::make_repository(database.clone());
+        let used_jwts = AuthorityUsedJwtSqlxDatabase::make_repository(database.clone());
+        let audit_log_repository = AuthorityAuditLogSqlxDatabase::make_repository(database.clone());
+        let scim = AuthorityScimSqlxDatabase::make_repository(database.clone());
         let secure_channel_repository =
             SecureChannelSqlxDatabase::make_repository(database.clone());
 
         Self::bootstrap_repository(members.clone(), configuration).await?;
 
-        let identities = Identities::create_with_node(database, node_name).build();
+        let identities = Identities::create_with_node(database, node_name)
+            .with_secrets_repository(secrets_repository)
+            .build();
 
         let secure_channels =
             SecureChannels::from_identities(identities.clone(), secure_channel_repository);
 
         let identifier = configuration.identifier();
         info!(identifier=%identifier, "retrieved the authority identifier");
+        let audit_log = Arc::new(AuthorityAuditLog::new(
+            &identifier,
+            audit_log_repository,
+            identities.clone(),
+        ));
         let account_authority =
             if let Some(change_history) = configuration.account_authority.clone() {
                 let acc_authority_identifier = identities
@@ -109,6 +160,9 @@ impl Authority {
             secure_channels,
             members,
             tokens,
+            used_jwts,
+            audit_log,
+            scim,
             account_authority,
         })
     }
@@ -173,6 +227,7 @@ impl Authority {
         let direct = DirectAuthenticatorWorker::new(
             &self.identifier,
             self.members.clone(),
+            self.audit_log.clone(),
             self.secure_channels.identities().identities_attributes(),
             self.account_authority.clone(),
         );
@@ -202,6 +257,7 @@ impl Authority {
             &self.identifier,
             self.tokens.clone(),
             self.members.clone(),
+            self.audit_log.clone(),
             self.secure_channels.identities().identities_attributes(),
             self.account_authority.clone(),
         );
@@ -209,6 +265,7 @@ impl Authority {
             &self.identifier,
             self.tokens.clone(),
             self.members.clone(),
+            self.audit_log.clone(),
         );
 
         // start an enrollment token issuer with an abac policy checking that
@@ -294,6 +351,80 @@ impl Authority {
         Ok(())
     }
 
+    /// Start the OIDC authenticator, enrolling the identities presenting a valid JWT
+    pub fn start_oidc_authenticator(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        if let Some(oidc) = &configuration.oidc {
+            let worker = OidcAuthenticatorWorker::new(
+                &self.identifier,
+                self.members.clone(),
+                self.used_jwts.clone(),
+                self.audit_log.clone(),
+                oidc,
+            )?;
+
+            ctx.flow_controls()
+                .add_consumer(&oidc.address.clone().into(), secure_channel_flow_control_id);
+
+            ctx.start_worker(oidc.address.clone(), worker)?;
+
+            info!(
+                "started an OIDC authenticator at '{}' for the issuer {}",
+                oidc.address, oidc.issuer
+            );
+        }
+        Ok(())
+    }
+
+    /// Start the audit log service, allowing admins to read the log of membership changes
+    pub fn start_audit_log_service(
+        &self,
+        ctx: &Context,
+        secure_channel_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        let worker = AuditLogWorker::new(
+            &self.identifier,
+            self.audit_log.clone(),
+            self.members.clone(),
+            self.secure_channels.identities().identities_attributes(),
+            self.account_authority.clone(),
+        );
+
+        let address = DefaultAddress::AUTHORITY_AUDIT_LOG.to_string();
+        ctx.flow_controls()
+            .add_consumer(&address.clone().into(), secure_channel_flow_control_id);
+
+        ctx.start_worker(address.clone(), worker)?;
+
+        info!("started an audit log service at '{address}'");
+        Ok(())
+    }
+
+    /// Start the SCIM server, provisioning members from an identity management system
+    /// (if the optional configuration has been provided)
+    pub async fn start_scim_server(
+        &self,
+        ctx: &Context,
+        configuration: &Configuration,
+    ) -> Result<()> {
+        if let Some(scim) = &configuration.scim {
+            let provisioner = ScimProvisioner::new(
+                &self.identifier,
+                self.members.clone(),
+                self.scim.clone(),
+                self.audit_log.clone(),
+                scim,
+            );
+            let address = ScimServer::start(ctx, Arc::new(provisioner), scim).await?;
+            info!("started a SCIM server at '{address}'");
+        }
+        Ok(())
+    }
+
     /// Start an echo service
     pub fn start_echo_service(
         &self,
@@ -372,6 +503,8 @@ mod tests {
     use ockam_multiaddr::MultiAddr;
     use ockam_node::database::{with_postgres, DatabaseConfiguration};
     use ockam_node::NodeBuilder;
+    use ockam_vault::storage::KeyEncryptionKey;
+    use ockam_vault::{AeadSecret, AEAD_SECRET_LENGTH};
     use std::future::Future;
     use std::net::TcpListener;
     use std::str::FromStr;
@@ -476,6 +609,80 @@ mod tests {
         result.unwrap()
     }
 
+    /// This test starts 2 replicas of the same authority. They share the authority identity
+    /// and its vault in the same postgres database, where the secrets are encrypted with a
+    /// key-encryption key.
+    ///
+    /// We test that both replicas can use the authority identity to issue credentials.
+    #[test]
+    fn test_start_authority_replicas_with_a_key_encryption_key() {
+        let result = execute_test(|db_base, ctx1_base, ctx2_base, ctx_client_base| {
+            let db = db_base.clone();
+            let ctx1 = ctx1_base.try_clone().unwrap();
+            let ctx2 = ctx2_base.try_clone().unwrap();
+            let ctx_client = ctx_client_base.try_clone().unwrap();
+            async move {
+                let key_encryption_key: Arc<dyn KeyEncryptionKeyProvider> =
+                    Arc::new(KeyEncryptionKey::new(AeadSecret([1; AEAD_SECRET_LENGTH])));
+
+                // the authority identity is created with encrypted secrets, as done by 'ockam authority create'
+                let secrets_repository = SecretsSqlxDatabase::make_encrypted_repository(
+                    db.clone(),
+                    key_encryption_key.clone(),
+                )
+                .await?;
+                let authority = Identities::create_with_node(db.clone(), "authority")
+                    .with_secrets_repository(secrets_repository)
+                    .build()
+                    .identities_creation()
+                    .create_identity()
+                    .await?;
+
+                let secure_channels: Arc<SecureChannels> = secure_channels().await?;
+                let identities_creation = secure_channels.identities().identities_creation();
+                let enroller = identities_creation.create_identity().await?;
+
+                let port1 = random_port();
+                let port2 = random_port();
+                start_authority_replica(
+                    db.clone(),
+                    &ctx1,
+                    port1,
+                    &authority,
+                    &[enroller.clone()],
+                    key_encryption_key.clone(),
+                )
+                .await?;
+                start_authority_replica(
+                    db,
+                    &ctx2,
+                    port2,
+                    &authority,
+                    &[enroller.clone()],
+                    key_encryption_key,
+                )
+                .await?;
+
+                // both replicas can sign with the authority identity
+                for port in [port1, port2] {
+                    let client = make_authority_node_client(
+                        &ctx_client,
+                        secure_channels.clone(),
+                        &authority,
+                        &MultiAddr::from_str(&format!("/dnsaddr/127.0.0.1/tcp/{port}/secure/api"))?,
+                        &enroller,
+                    )
+                    .await?;
+                    let member = identities_creation.create_identity().await?;
+                    add_member(&ctx_client, &client, &member, ("key1", "value1")).await?;
+                    issue_credential(&ctx_client, &client, &member).await?;
+                }
+                Ok(())
+            }
+        });
+        result.unwrap()
+    }
+
     /// HELPERS
 
     /// Create an Authority configuration with:
@@ -512,6 +719,8 @@ mod tests {
             no_direct_authentication: false,
             no_token_enrollment: false,
             okta: None,
+            oidc: None,
+            scim: None,
             account_authority: None,
             enforce_admin_checks: false,
             disable_trust_context_id: false,
@@ -560,6 +769,30 @@ mod tests {
         Ok(authority)
     }
 
+    /// Create and start a replica of an authority node, with:
+    ///  - A specific TCP listener port
+    ///  - The identifier of the authority shared by all the replicas
+    ///  - An identifier for an enroller
+    ///  - The key-encryption key of the authority secrets
+    async fn start_authority_replica(
+        db: SqlxDatabase,
+        ctx: &Context,
+        port: u16,
+        authority: &Identifier,
+        trusted: &[Identifier],
+        key_encryption_key: Arc<dyn KeyEncryptionKeyProvider>,
+    ) -> Result<Authority> {
+        let configuration = create_configuration(authority, port, trusted)?;
+        let authority = Authority::create_with_key_encryption_key(
+            &configuration,
+            Some(db),
+            Some(key_encryption_key),
+        )
+        .await?;
+        authority_node::start_node(ctx, &configuration, authority.clone()).await?;
+        Ok(authority)
+    }
+
     /// Add a member
     /// Add a member
     async fn add_member(
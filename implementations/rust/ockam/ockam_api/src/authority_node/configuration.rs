@@ -2,14 +2,18 @@ use ockam::identity::models::ChangeHistory;
 use serde::{Deserialize, Serialize};
 
 use ockam::identity::Identifier;
-use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::collections::{BTreeMap, HashMap};
 use ockam_core::compat::fmt;
 use ockam_core::compat::fmt::{Display, Formatter};
+use ockam_core::Result;
 use ockam_node::database::DatabaseConfiguration;
 
+use crate::authenticator::credential_issuer::TRUST_CONTEXT_ID;
+use crate::authenticator::direct::OCKAM_ROLE_ATTRIBUTE_KEY;
 use crate::authenticator::PreTrustedIdentities;
 use crate::config::lookup::InternetAddress;
 use crate::nodes::service::default_address::DefaultAddress;
+use crate::ApiError;
 
 /// Configuration for the Authority node
 #[derive(Debug, Clone)]
@@ -46,6 +50,12 @@ pub struct Configuration {
     /// optional configuration for the okta service
     pub okta: Option<OktaConfiguration>,
 
+    /// optional configuration for the OIDC authenticator service
+    pub oidc: Option<OidcConfiguration>,
+
+    /// optional configuration for the SCIM provisioning service
+    pub scim: Option<ScimConfiguration>,
+
     /// Account Authority identity
     pub account_authority: Option<ChangeHistory>,
 
@@ -117,6 +127,105 @@ impl OktaConfiguration {
     }
 }
 
+/// Configuration for the OIDC authenticator service, enrolling identities presenting
+/// a JWT issued by an OpenID Connect provider
+#[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct OidcConfiguration {
+    pub address: String,
+
+    /// expected value of the `iss` claim of the tokens
+    pub issuer: String,
+
+    /// expected value of the `aud` claim of the tokens.
+    /// It is mandatory, otherwise any token issued by the issuer, for any application, would be accepted
+    pub audience: String,
+
+    /// URL, or path of a local file, of the key set used to verify the tokens signatures.
+    /// If not set, the key set is discovered with the OpenID configuration of the issuer.
+    /// A remote key set is retrieved again after the `max-age` of its `Cache-Control` header, or every hour
+    pub jwks: Option<String>,
+
+    /// names of the member attributes to set, indexed by the name of the claim providing their value
+    pub claims: BTreeMap<String, String>,
+
+    /// claims which must have a given value for a token to be accepted
+    pub required_claims: BTreeMap<String, String>,
+}
+
+impl OidcConfiguration {
+    /// Return true if the key set is retrieved from the network rather than from a local file
+    pub(crate) fn has_remote_jwks(&self) -> bool {
+        self.jwks.as_deref().map(Self::is_url).unwrap_or(true)
+    }
+
+    /// Return true if a key set location is a URL
+    pub(crate) fn is_url(location: &str) -> bool {
+        location.starts_with("https://") || location.starts_with("http://")
+    }
+}
+
+/// Configuration for the SCIM service, provisioning project members from an
+/// identity management system over HTTP
+#[derive(PartialEq, Eq, Clone, Serialize, Deserialize)]
+pub struct ScimConfiguration {
+    /// listener address for the HTTP server, for example "127.0.0.1:4001"
+    pub listener_address: InternetAddress,
+
+    /// bearer token that the SCIM clients must present
+    pub bearer_token: String,
+
+    /// names of the member attributes to set, indexed by the SCIM attribute providing their value,
+    /// for example `title` or `urn:ietf:params:scim:schemas:extension:enterprise:2.0:User:department`
+    pub attributes: BTreeMap<String, String>,
+
+    /// name of the member attribute listing the names of the groups of a member, separated by commas
+    pub groups_attribute: Option<String>,
+}
+
+impl ScimConfiguration {
+    /// Check that the configuration can be used to start a SCIM server.
+    ///
+    /// The server only serves plain HTTP, so it must listen on a loopback address, and be
+    /// exposed behind a reverse proxy terminating TLS. Otherwise the bearer token and the
+    /// provisioning requests would be sent in clear over the network.
+    ///
+    /// The member attributes set by the server can't be attributes reserved by the authority,
+    /// like the role of a member, since a SCIM client could then grant itself more privileges
+    pub fn validate(&self) -> Result<()> {
+        let is_loopback = match &self.listener_address {
+            InternetAddress::Dns(host, _) => host == "localhost",
+            InternetAddress::V4(address) => address.ip().is_loopback(),
+            InternetAddress::V6(address) => address.ip().is_loopback(),
+        };
+        if !is_loopback {
+            return Err(ApiError::core(format!(
+                "the SCIM server must listen on a loopback address, for example 127.0.0.1:{}, and be exposed with a reverse proxy terminating TLS",
+                self.listener_address.port()
+            )));
+        }
+        let reserved = [OCKAM_ROLE_ATTRIBUTE_KEY.as_bytes(), TRUST_CONTEXT_ID];
+        for attribute in self.attributes.values().chain(&self.groups_attribute) {
+            if reserved.contains(&attribute.as_bytes()) {
+                return Err(ApiError::core(format!(
+                    "the member attribute {attribute} is reserved, it can't be set by the SCIM server"
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ScimConfiguration {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScimConfiguration")
+            .field("listener_address", &self.listener_address)
+            .field("bearer_token", &"<redacted>")
+            .field("attributes", &self.attributes)
+            .field("groups_attribute", &self.groups_attribute)
+            .finish()
+    }
+}
+
 /// This struct represents an identity that the Authority accepts
 /// as having all its attributes fully authenticated
 #[derive(Debug, PartialEq, Eq, Clone, Serialize, Deserialize)]
@@ -32,6 +32,17 @@ pub async fn start_node(
     authority.start_okta(ctx, &secure_channel_flow_control_id, configuration)?;
     debug!("okta service started");
 
+    // start the OIDC authenticator (if the optional configuration has been provided)
+    authority.start_oidc_authenticator(ctx, &secure_channel_flow_control_id, configuration)?;
+    debug!("oidc authenticator started");
+
+    authority.start_audit_log_service(ctx, &secure_channel_flow_control_id)?;
+    debug!("audit log service started");
+
+    // start the SCIM server (if the optional configuration has been provided)
+    authority.start_scim_server(ctx, configuration).await?;
+    debug!("scim server started");
+
     // start an echo service so that the node can be queried as healthy
     authority.start_echo_service(ctx, &secure_channel_flow_control_id)?;
 
@@ -392,7 +392,10 @@ impl CliState {
 
     /// Return the change history of a persisted identity
     #[instrument(skip_all, fields(identifier = %identifier))]
-    async fn get_change_history(&self, identifier: &Identifier) -> Result<ChangeHistory> {
+    pub(crate) async fn get_change_history(
+        &self,
+        identifier: &Identifier,
+    ) -> Result<ChangeHistory> {
         match self
             .change_history_repository()
             .get_change_history(identifier)
@@ -4,6 +4,7 @@ pub use error::*;
 pub use identities::*;
 pub use nodes::*;
 pub use storage::*;
+pub use vault_bundle::*;
 pub use vaults::*;
 
 #[allow(clippy::module_inception)]
@@ -25,4 +26,5 @@ mod tcp_portals;
 pub mod test_support;
 pub mod trust;
 pub mod users;
+pub mod vault_bundle;
 pub mod vaults;
@@ -1,5 +1,20 @@
 use crate::cli_state::CliState;
-use ockam_abac::{Policies, ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase};
+use ockam_abac::{
+    Policies, PolicyDecisionLog, PolicyDecisionSqlxDatabase, PolicyDecisionsRetention,
+    ResourcePolicySqlxDatabase, ResourceTypePolicySqlxDatabase,
+};
+use ockam_core::env::get_env_with_default;
+use std::time::Duration;
+
+/// Set this variable to `true` to record the allowed accesses in the policy decision log of a node,
+/// in addition to the denied accesses
+pub const OCKAM_POLICY_DECISIONS_LOG_ALLOWED: &str = "OCKAM_POLICY_DECISIONS_LOG_ALLOWED";
+
+/// Maximum number of policy decisions kept for a node
+pub const OCKAM_POLICY_DECISIONS_MAX: &str = "OCKAM_POLICY_DECISIONS_MAX";
+
+/// Maximum age of the policy decisions kept for a node, for example `7d`
+pub const OCKAM_POLICY_DECISIONS_MAX_AGE: &str = "OCKAM_POLICY_DECISIONS_MAX_AGE";
 
 impl CliState {
     pub fn policies(&self, node_name: &str) -> Policies {
@@ -7,5 +22,30 @@ impl CliState {
             ResourcePolicySqlxDatabase::make_repository(self.database(), node_name),
             ResourceTypePolicySqlxDatabase::make_repository(self.database(), node_name),
         )
+        .with_decision_log(self.policy_decision_log(node_name))
+    }
+
+    /// Return the log of the access control decisions taken by a node
+    pub fn policy_decision_log(&self, node_name: &str) -> PolicyDecisionLog {
+        let default = PolicyDecisionsRetention::default();
+        let retention = PolicyDecisionsRetention {
+            max_decisions: get_env_with_default(OCKAM_POLICY_DECISIONS_MAX, default.max_decisions)
+                .unwrap_or(default.max_decisions),
+            max_age: get_env_with_default(
+                OCKAM_POLICY_DECISIONS_MAX_AGE,
+                Duration::from_secs(default.max_age),
+            )
+            .map(|max_age| max_age.as_secs())
+            .unwrap_or(default.max_age),
+        };
+        let record_allowed =
+            get_env_with_default(OCKAM_POLICY_DECISIONS_LOG_ALLOWED, false).unwrap_or(false);
+
+        PolicyDecisionLog::new(PolicyDecisionSqlxDatabase::make_repository(
+            self.database(),
+            node_name,
+        ))
+        .with_retention(retention)
+        .with_allowed_decisions(record_allowed)
     }
 }
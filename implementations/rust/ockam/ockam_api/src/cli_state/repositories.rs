@@ -4,10 +4,11 @@ use ockam::identity::{
     CredentialSqlxDatabase,
 };
 use ockam_core::compat::sync::Arc;
-use ockam_vault::storage::{SecretsRepository, SecretsSqlxDatabase};
+use ockam_vault::storage::SecretsRepository;
 
 use crate::cli_state::storage::*;
 use crate::cli_state::CliState;
+use crate::cli_state::Result;
 use crate::cli_state::{EnrollmentsRepository, EnrollmentsSqlxDatabase};
 use crate::cli_state::{ProjectsRepository, ProjectsSqlxDatabase};
 use crate::cli_state::{SpacesRepository, SpacesSqlxDatabase};
@@ -28,8 +29,10 @@ impl CliState {
         PurposeKeysSqlxDatabase::make_repository(self.database())
     }
 
-    pub(super) fn secrets_repository(&self) -> Arc<dyn SecretsRepository> {
-        SecretsSqlxDatabase::make_repository(self.database())
+    /// The secrets are encrypted if a key-encryption key is configured
+    /// with the OCKAM_VAULT_KEK or OCKAM_VAULT_PASSPHRASE environment variables
+    pub(super) async fn secrets_repository(&self) -> Result<Arc<dyn SecretsRepository>> {
+        self.make_secrets_repository(self.database()).await
     }
 
     pub(super) fn vaults_repository(&self) -> Arc<dyn VaultsRepository> {
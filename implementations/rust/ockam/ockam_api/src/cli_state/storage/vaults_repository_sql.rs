@@ -2,7 +2,7 @@ use sqlx::*;
 use std::path::PathBuf;
 use std::sync::Arc;
 
-use crate::cli_state::{NamedVault, UseAwsKms, VaultType, VaultsRepository};
+use crate::cli_state::{NamedVault, UseAwsKms, UsePkcs11, VaultType, VaultsRepository};
 use ockam::{FromSqlxError, SqlxDatabase, ToVoid};
 use ockam_core::async_trait;
 use ockam_core::Result;
@@ -48,15 +48,16 @@ impl VaultsRepository for VaultsSqlxDatabase {
         let query = query(
             r#"
         INSERT INTO
-            vault (name, path, is_default, is_kms)
-            VALUES ($1, $2, $3, $4)
+            vault (name, path, is_default, is_kms, is_pkcs11)
+            VALUES ($1, $2, $3, $4, $5)
             ON CONFLICT (name)
-            DO UPDATE SET path = $2, is_default = $3, is_kms = $4"#,
+            DO UPDATE SET path = $2, is_default = $3, is_kms = $4, is_pkcs11 = $5"#,
         )
         .bind(name)
         .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
         .bind(!default_exists)
-        .bind(vault_type.use_aws_kms());
+        .bind(vault_type.use_aws_kms())
+        .bind(vault_type.use_pkcs11());
         query.execute(&mut *transaction).await.void()?;
 
         transaction.commit().await.void()?;
@@ -64,10 +65,12 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn update_vault(&self, name: &str, vault_type: VaultType) -> Result<()> {
-        let query = query("UPDATE vault SET path = $1, is_kms = $2 WHERE name = $3")
-            .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
-            .bind(vault_type.use_aws_kms())
-            .bind(name);
+        let query =
+            query("UPDATE vault SET path = $1, is_kms = $2, is_pkcs11 = $3 WHERE name = $4")
+                .bind(vault_type.path().map(|p| p.to_string_lossy().to_string()))
+                .bind(vault_type.use_aws_kms())
+                .bind(vault_type.use_pkcs11())
+                .bind(name);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -77,7 +80,9 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_database_vault(&self) -> Result<Option<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault WHERE path is NULL");
+        let query = query_as(
+            "SELECT name, path, is_default, is_kms, is_pkcs11 FROM vault WHERE path is NULL",
+        );
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -87,7 +92,8 @@ impl VaultsRepository for VaultsSqlxDatabase {
 
     async fn get_named_vault(&self, name: &str) -> Result<Option<NamedVault>> {
         let query =
-            query_as("SELECT name, path, is_default, is_kms FROM vault WHERE name = $1").bind(name);
+            query_as("SELECT name, path, is_default, is_kms, is_pkcs11 FROM vault WHERE name = $1")
+                .bind(name);
         let row: Option<VaultRow> = query
             .fetch_optional(&*self.database.pool)
             .await
@@ -96,7 +102,7 @@ impl VaultsRepository for VaultsSqlxDatabase {
     }
 
     async fn get_named_vaults(&self) -> Result<Vec<NamedVault>> {
-        let query = query_as("SELECT name, path, is_default, is_kms FROM vault");
+        let query = query_as("SELECT name, path, is_default, is_kms, is_pkcs11 FROM vault");
         let rows: Vec<VaultRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         rows.iter().map(|r| r.named_vault()).collect()
     }
@@ -110,6 +116,7 @@ pub(crate) struct VaultRow {
     path: Nullable<String>,
     is_default: Boolean,
     is_kms: Boolean,
+    is_pkcs11: Boolean,
 }
 
 impl VaultRow {
@@ -122,13 +129,14 @@ impl VaultRow {
     }
 
     pub(crate) fn vault_type(&self) -> VaultType {
-        match self.path.to_option() {
+        let vault_type = match self.path.to_option() {
             None => VaultType::database(UseAwsKms::from(self.is_kms.to_bool())),
             Some(p) => VaultType::local_file(
                 PathBuf::from(p).as_path(),
                 UseAwsKms::from(self.is_kms.to_bool()),
             ),
-        }
+        };
+        vault_type.with_pkcs11(UsePkcs11::from(self.is_pkcs11.to_bool()))
     }
 
     pub(crate) fn is_default(&self) -> bool {
@@ -196,4 +204,23 @@ mod test {
         })
         .await
     }
+
+    #[tokio::test]
+    async fn test_store_pkcs11_vault() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn VaultsRepository> = Arc::new(VaultsSqlxDatabase::new(db));
+
+            // It is possible to create a vault storing its signing keys in a PKCS#11 token
+            let vault_type =
+                VaultType::local_file("path", UseAwsKms::No).with_pkcs11(UsePkcs11::Yes);
+            let pkcs11 = repository.store_vault("pkcs11", vault_type.clone()).await?;
+            let expected = NamedVault::new("pkcs11", vault_type.clone(), true);
+            assert_eq!(pkcs11, expected);
+
+            let result = repository.get_named_vault("pkcs11").await?;
+            assert_eq!(result, Some(expected));
+            Ok(())
+        })
+        .await
+    }
 }
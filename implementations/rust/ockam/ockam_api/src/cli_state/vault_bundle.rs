@@ -0,0 +1,578 @@
+use minicbor::{CborLen, Decode, Encode};
+use rand::RngCore;
+use zeroize::Zeroize;
+
+use ockam::identity::models::{ChangeHistory, PurposeKeyAttestation};
+use ockam::identity::{Identifier, Identity, Purpose, Vault};
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Error;
+use ockam_vault::storage::{KeyEncryptionKey, SecretsRepository};
+use ockam_vault::{
+    ECDSASHA256CurveP256SecretKey, ECDSASHA384CurveP384SecretKey, EdDSACurve25519SecretKey,
+    HandleToSecret, SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
+};
+
+use crate::cli_state::{CliState, NamedIdentity, NamedVault, Result};
+
+/// Current version of the vault bundle format
+pub const VAULT_BUNDLE_VERSION: u8 = 2;
+
+/// Additional data authenticated with the encrypted bundle, followed by the version and the salt
+const VAULT_BUNDLE_AAD: &[u8] = b"ockam-vault-bundle";
+
+/// Length of the salt used to derive the bundle encryption key from a passphrase
+const VAULT_BUNDLE_SALT_LENGTH: usize = 16;
+
+/// The methods below support the export of a vault to a portable bundle and its import
+/// on another machine.
+///
+/// A bundle contains:
+///
+///  - the signing and X25519 secrets of the vault
+///  - the name, change history and purpose keys of the identities using the vault
+///
+/// The AEAD keys of persistent secure channels are not exported since they are only
+/// meaningful for the node which created them.
+///
+/// The bundle is serialized as CBOR and encrypted with a key derived from a passphrase.
+/// The version and the salt of the bundle are authenticated with its content.
+///
+impl CliState {
+    /// Export a vault to an encrypted bundle
+    #[instrument(skip_all, fields(vault_name = vault_name.clone()))]
+    pub async fn export_vault(
+        &self,
+        vault_name: &Option<String>,
+        passphrase: &str,
+    ) -> Result<Vec<u8>> {
+        let named_vault = self.get_named_vault_or_default(vault_name).await?;
+        Self::check_exportable(&named_vault)?;
+        let secrets_repository = self
+            .make_secrets_repository(self.make_vault_database(&named_vault).await?)
+            .await?;
+
+        let mut secrets = vec![];
+        for handle in secrets_repository.get_signing_secret_handles().await? {
+            if let Some(secret) = secrets_repository.get_signing_secret(&handle).await? {
+                secrets.push(BundledSecret::signing_secret(&handle, &secret));
+            }
+        }
+        for handle in secrets_repository.get_x25519_secret_handles().await? {
+            if let Some(secret) = secrets_repository.get_x25519_secret(&handle).await? {
+                secrets.push(BundledSecret::x25519_secret(&handle, &secret));
+            }
+        }
+
+        let mut identities = vec![];
+        for named_identity in self
+            .identities_repository()
+            .get_named_identities_by_vault_name(&named_vault.name())
+            .await?
+        {
+            identities.push(self.make_bundled_identity(&named_identity).await?);
+        }
+
+        let bundle = VaultBundle {
+            identities,
+            secrets,
+        };
+        bundle.encrypt(passphrase).await
+    }
+
+    /// Import an encrypted bundle into a vault.
+    ///
+    /// If some of the secrets or identities already exist the import fails,
+    /// unless `force` is true, in which case they are replaced.
+    #[instrument(skip_all, fields(vault_name = vault_name.clone(), force = force))]
+    pub async fn import_vault(
+        &self,
+        vault_name: &Option<String>,
+        encrypted_bundle: &[u8],
+        passphrase: &str,
+        force: bool,
+    ) -> Result<Vec<NamedIdentity>> {
+        let bundle = VaultBundle::decrypt(encrypted_bundle, passphrase).await?;
+        let named_vault = self.get_named_vault_or_default(vault_name).await?;
+        Self::check_exportable(&named_vault)?;
+        let secrets_repository = self
+            .make_secrets_repository(self.make_vault_database(&named_vault).await?)
+            .await?;
+
+        // check the identities before storing anything
+        let mut identities = vec![];
+        for bundled_identity in &bundle.identities {
+            let identity = Identity::import_from_change_history(
+                Some(&bundled_identity.identifier),
+                bundled_identity.change_history.clone(),
+                Vault::create_verifying_vault(),
+            )
+            .await?;
+            identities.push(identity);
+        }
+
+        if !force {
+            let conflicts = self
+                .get_import_conflicts(&bundle, secrets_repository.clone())
+                .await?;
+            if !conflicts.is_empty() {
+                return Err(Error::new(
+                    Origin::Api,
+                    Kind::Conflict,
+                    format!(
+                        "The vault {} already contains: {}. Use --force to overwrite them",
+                        named_vault.name(),
+                        conflicts.join(", ")
+                    ),
+                ))?;
+            }
+        }
+
+        for secret in &bundle.secrets {
+            secret.store(secrets_repository.clone()).await?;
+        }
+
+        let mut named_identities = vec![];
+        for (bundled_identity, identity) in bundle.identities.iter().zip(identities) {
+            if let Ok(existing) = self.get_named_identity(&bundled_identity.name).await {
+                if existing.identifier() != bundled_identity.identifier {
+                    self.delete_identity_by_name(&bundled_identity.name).await?;
+                }
+            }
+            self.change_history_repository()
+                .store_change_history(identity.identifier(), identity.change_history().clone())
+                .await?;
+
+            let purpose_keys_repository = self.purpose_keys_repository();
+            for (purpose, attestation) in bundled_identity.purpose_keys() {
+                purpose_keys_repository
+                    .set_purpose_key(identity.identifier(), purpose, attestation)
+                    .await?;
+            }
+
+            named_identities.push(
+                self.store_named_identity(
+                    identity.identifier(),
+                    &bundled_identity.name,
+                    &named_vault.name(),
+                )
+                .await?,
+            );
+        }
+        Ok(named_identities)
+    }
+
+    /// Only the secrets stored in the vault database can be exported
+    fn check_exportable(named_vault: &NamedVault) -> Result<()> {
+        if named_vault.use_aws_kms() || named_vault.use_pkcs11() {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Invalid,
+                format!(
+                    "The vault {} cannot be exported or imported because its signing keys are stored outside of Ockam",
+                    named_vault.name()
+                ),
+            ))?;
+        }
+        Ok(())
+    }
+
+    async fn make_bundled_identity(
+        &self,
+        named_identity: &NamedIdentity,
+    ) -> Result<BundledIdentity> {
+        let identifier = named_identity.identifier();
+        let change_history = self.get_change_history(&identifier).await?;
+        let purpose_keys_repository = self.purpose_keys_repository();
+        Ok(BundledIdentity {
+            name: named_identity.name(),
+            secure_channel_purpose_key: purpose_keys_repository
+                .get_purpose_key(&identifier, Purpose::SecureChannel)
+                .await?,
+            credentials_purpose_key: purpose_keys_repository
+                .get_purpose_key(&identifier, Purpose::Credentials)
+                .await?,
+            identifier,
+            change_history,
+        })
+    }
+
+    /// Return a description of the secrets and identities which already exist
+    async fn get_import_conflicts(
+        &self,
+        bundle: &VaultBundle,
+        secrets_repository: std::sync::Arc<dyn SecretsRepository>,
+    ) -> Result<Vec<String>> {
+        let mut conflicts = vec![];
+        for secret in &bundle.secrets {
+            if secret.exists(secrets_repository.clone()).await? {
+                conflicts.push(format!("the secret {}", hex::encode(&secret.handle)));
+            }
+        }
+        for bundled_identity in &bundle.identities {
+            if self
+                .identities_repository()
+                .get_named_identity(&bundled_identity.name)
+                .await?
+                .is_some()
+            {
+                conflicts.push(format!("the identity named {}", bundled_identity.name));
+            } else if self
+                .identities_repository()
+                .get_named_identity_by_identifier(&bundled_identity.identifier)
+                .await?
+                .is_some()
+            {
+                conflicts.push(format!("the identity {}", bundled_identity.identifier));
+            }
+        }
+        Ok(conflicts)
+    }
+}
+
+/// Exported vault, encrypted with a key derived from a passphrase with Argon2
+#[derive(Debug, Clone, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct EncryptedVaultBundle {
+    #[n(0)] pub version: u8,
+    #[cbor(with = "minicbor::bytes")]
+    #[n(1)] pub salt: Vec<u8>,
+    #[cbor(with = "minicbor::bytes")]
+    #[n(2)] pub encrypted_bundle: Vec<u8>,
+}
+
+/// Content of an exported vault
+#[derive(Debug, Clone, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct VaultBundle {
+    #[n(0)] pub identities: Vec<BundledIdentity>,
+    #[n(1)] pub secrets: Vec<BundledSecret>,
+}
+
+impl VaultBundle {
+    /// Serialize and encrypt the bundle
+    pub async fn encrypt(&self, passphrase: &str) -> Result<Vec<u8>> {
+        let mut salt = vec![0u8; VAULT_BUNDLE_SALT_LENGTH];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = KeyEncryptionKey::from_passphrase(passphrase, &salt)?;
+
+        let mut bundle = ockam_core::cbor_encode_preallocate(self)?;
+        let encrypted_bundle = key.encrypt(&bundle, &Self::aad(VAULT_BUNDLE_VERSION, &salt));
+        bundle.zeroize();
+
+        Ok(ockam_core::cbor_encode_preallocate(EncryptedVaultBundle {
+            version: VAULT_BUNDLE_VERSION,
+            salt,
+            encrypted_bundle: encrypted_bundle?,
+        })?)
+    }
+
+    /// Decrypt and deserialize a bundle
+    pub async fn decrypt(encrypted_bundle: &[u8], passphrase: &str) -> Result<VaultBundle> {
+        let encrypted_bundle: EncryptedVaultBundle =
+            minicbor::decode(encrypted_bundle).map_err(|e| {
+                Error::new(
+                    Origin::Api,
+                    Kind::Serialization,
+                    format!("The file is not a vault bundle: {e}"),
+                )
+            })?;
+        if encrypted_bundle.version != VAULT_BUNDLE_VERSION {
+            return Err(Error::new(
+                Origin::Api,
+                Kind::Unsupported,
+                format!(
+                    "The vault bundle version {} is not supported. The supported version is {VAULT_BUNDLE_VERSION}",
+                    encrypted_bundle.version
+                ),
+            ))?;
+        }
+
+        let key = KeyEncryptionKey::from_passphrase(passphrase, &encrypted_bundle.salt)?;
+        let mut bundle = key
+            .decrypt(
+                &encrypted_bundle.encrypted_bundle,
+                &Self::aad(encrypted_bundle.version, &encrypted_bundle.salt),
+            )
+            .map_err(|_| {
+                Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    "The vault bundle cannot be decrypted. Please check the passphrase",
+                )
+            })?;
+        let result = minicbor::decode(&bundle).map_err(|e| {
+            Error::new(
+                Origin::Api,
+                Kind::Serialization,
+                format!("The vault bundle cannot be decoded: {e}"),
+            )
+        });
+        bundle.zeroize();
+        Ok(result?)
+    }
+
+    /// Return the additional data authenticated with an encrypted bundle
+    fn aad(version: u8, salt: &[u8]) -> Vec<u8> {
+        let mut aad = VAULT_BUNDLE_AAD.to_vec();
+        aad.push(version);
+        aad.extend_from_slice(salt);
+        aad
+    }
+}
+
+/// Identity exported with a vault
+#[derive(Debug, Clone, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct BundledIdentity {
+    #[n(0)] pub name: String,
+    #[n(1)] pub identifier: Identifier,
+    #[n(2)] pub change_history: ChangeHistory,
+    #[n(3)] pub secure_channel_purpose_key: Option<PurposeKeyAttestation>,
+    #[n(4)] pub credentials_purpose_key: Option<PurposeKeyAttestation>,
+}
+
+impl BundledIdentity {
+    fn purpose_keys(&self) -> Vec<(Purpose, &PurposeKeyAttestation)> {
+        let mut purpose_keys = vec![];
+        if let Some(attestation) = &self.secure_channel_purpose_key {
+            purpose_keys.push((Purpose::SecureChannel, attestation));
+        }
+        if let Some(attestation) = &self.credentials_purpose_key {
+            purpose_keys.push((Purpose::Credentials, attestation));
+        }
+        purpose_keys
+    }
+}
+
+/// Type of an exported secret
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(index_only)]
+pub enum BundledSecretType {
+    #[n(0)] EdDSACurve25519,
+    #[n(1)] ECDSASHA256CurveP256,
+    #[n(2)] ECDSASHA384CurveP384,
+    #[n(3)] X25519,
+}
+
+/// Secret exported with a vault
+#[derive(Clone, Encode, Decode, CborLen, Zeroize)]
+#[rustfmt::skip]
+#[zeroize(drop)]
+pub struct BundledSecret {
+    #[zeroize(skip)]
+    #[n(0)] pub secret_type: BundledSecretType,
+    #[cbor(with = "minicbor::bytes")]
+    #[n(1)] pub handle: Vec<u8>,
+    #[cbor(with = "minicbor::bytes")]
+    #[n(2)] pub secret: Vec<u8>,
+}
+
+impl std::fmt::Debug for BundledSecret {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BundledSecret")
+            .field("secret_type", &self.secret_type)
+            .field("handle", &hex::encode(&self.handle))
+            .finish()
+    }
+}
+
+impl BundledSecret {
+    fn signing_secret(handle: &SigningSecretKeyHandle, secret: &SigningSecret) -> Self {
+        let secret_type = match secret {
+            SigningSecret::EdDSACurve25519(_) => BundledSecretType::EdDSACurve25519,
+            SigningSecret::ECDSASHA256CurveP256(_) => BundledSecretType::ECDSASHA256CurveP256,
+            SigningSecret::ECDSASHA384CurveP384(_) => BundledSecretType::ECDSASHA384CurveP384,
+        };
+        Self {
+            secret_type,
+            handle: handle.handle().value().clone(),
+            secret: secret.key().to_vec(),
+        }
+    }
+
+    fn x25519_secret(handle: &X25519SecretKeyHandle, secret: &X25519SecretKey) -> Self {
+        Self {
+            secret_type: BundledSecretType::X25519,
+            handle: handle.0.value().clone(),
+            secret: secret.key().to_vec(),
+        }
+    }
+
+    fn signing_handle(&self) -> Option<SigningSecretKeyHandle> {
+        let handle = HandleToSecret::new(self.handle.clone());
+        match self.secret_type {
+            BundledSecretType::EdDSACurve25519 => {
+                Some(SigningSecretKeyHandle::EdDSACurve25519(handle))
+            }
+            BundledSecretType::ECDSASHA256CurveP256 => {
+                Some(SigningSecretKeyHandle::ECDSASHA256CurveP256(handle))
+            }
+            BundledSecretType::ECDSASHA384CurveP384 => {
+                Some(SigningSecretKeyHandle::ECDSASHA384CurveP384(handle))
+            }
+            BundledSecretType::X25519 => None,
+        }
+    }
+
+    /// Return true if a secret with the same handle exists in the repository
+    async fn exists(&self, repository: std::sync::Arc<dyn SecretsRepository>) -> Result<bool> {
+        Ok(match self.signing_handle() {
+            Some(handle) => repository.get_signing_secret(&handle).await?.is_some(),
+            None => repository
+                .get_x25519_secret(&X25519SecretKeyHandle(HandleToSecret::new(
+                    self.handle.clone(),
+                )))
+                .await?
+                .is_some(),
+        })
+    }
+
+    /// Store the secret in a repository
+    async fn store(&self, repository: std::sync::Arc<dyn SecretsRepository>) -> Result<()> {
+        let handle = HandleToSecret::new(self.handle.clone());
+        match self.secret_type {
+            BundledSecretType::EdDSACurve25519 => {
+                repository
+                    .store_signing_secret(
+                        &SigningSecretKeyHandle::EdDSACurve25519(handle),
+                        SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new(
+                            self.secret_bytes()?,
+                        )),
+                    )
+                    .await?
+            }
+            BundledSecretType::ECDSASHA256CurveP256 => {
+                repository
+                    .store_signing_secret(
+                        &SigningSecretKeyHandle::ECDSASHA256CurveP256(handle),
+                        SigningSecret::ECDSASHA256CurveP256(ECDSASHA256CurveP256SecretKey::new(
+                            self.secret_bytes()?,
+                        )),
+                    )
+                    .await?
+            }
+            BundledSecretType::ECDSASHA384CurveP384 => {
+                repository
+                    .store_signing_secret(
+                        &SigningSecretKeyHandle::ECDSASHA384CurveP384(handle),
+                        SigningSecret::ECDSASHA384CurveP384(ECDSASHA384CurveP384SecretKey::new(
+                            self.secret_bytes()?,
+                        )),
+                    )
+                    .await?
+            }
+            BundledSecretType::X25519 => {
+                repository
+                    .store_x25519_secret(
+                        &X25519SecretKeyHandle(handle),
+                        X25519SecretKey::new(self.secret_bytes()?),
+                    )
+                    .await?
+            }
+        };
+        Ok(())
+    }
+
+    fn secret_bytes<const N: usize>(&self) -> Result<[u8; N]> {
+        Ok(self.secret.as_slice().try_into().map_err(|_| {
+            Error::new(
+                Origin::Api,
+                Kind::Serialization,
+                format!("invalid secret length for a {:?} secret", self.secret_type),
+            )
+        })?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_vault::storage::KeyEncryptionKeyProvider;
+
+    #[tokio::test]
+    async fn test_export_import_vault() -> Result<()> {
+        let cli = CliState::test().await?;
+        let identity = cli.create_identity_with_name("alice").await?;
+        let vault = cli.get_named_vault(&identity.vault_name()).await?;
+        let identities = cli.make_identities(cli.make_vault(vault).await?).await?;
+        identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .create_secure_channel_purpose_key(&identity.identifier())
+            .await?;
+
+        let bundle = cli.export_vault(&None, "passphrase").await?;
+
+        // the bundle can not be decrypted with another passphrase
+        let other = CliState::test().await?;
+        let result = other.import_vault(&None, &bundle, "other", false).await;
+        assert!(result.is_err());
+
+        // the identity can be used after being imported on another machine
+        let imported = other
+            .import_vault(&None, &bundle, "passphrase", false)
+            .await?;
+        assert_eq!(imported.len(), 1);
+        assert_eq!(imported[0].name(), "alice");
+        assert_eq!(imported[0].identifier(), identity.identifier());
+
+        let vault = other.get_named_vault(&imported[0].vault_name()).await?;
+        let identities = other
+            .make_identities(other.make_vault(vault).await?)
+            .await?;
+        let purpose_key = identities
+            .purpose_keys()
+            .purpose_keys_creation()
+            .get_or_create_secure_channel_purpose_key(&identity.identifier())
+            .await?;
+        assert_eq!(
+            purpose_key.attestation(),
+            cli.purpose_keys_repository()
+                .get_purpose_key(&identity.identifier(), Purpose::SecureChannel)
+                .await?
+                .as_ref()
+                .unwrap()
+        );
+
+        // existing keys are not overwritten unless forced
+        let result = other
+            .import_vault(&None, &bundle, "passphrase", false)
+            .await;
+        assert!(result.is_err());
+        let result = other.import_vault(&None, &bundle, "passphrase", true).await;
+        assert!(result.is_ok());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_bundle_is_not_a_data_key() -> Result<()> {
+        let bundle = VaultBundle {
+            identities: vec![],
+            secrets: vec![],
+        };
+        let encrypted = bundle.encrypt("passphrase").await?;
+        let encrypted: EncryptedVaultBundle = minicbor::decode(&encrypted).unwrap();
+
+        // the bundle can't be decrypted as a data key
+        let key = KeyEncryptionKey::from_passphrase("passphrase", &encrypted.salt)?;
+        assert!(key.unwrap_key(&encrypted.encrypted_bundle).await.is_err());
+
+        // the version is authenticated
+        let mut other_version = encrypted.clone();
+        other_version.version = 3;
+        let result = key.decrypt(
+            &other_version.encrypted_bundle,
+            &VaultBundle::aad(other_version.version, &other_version.salt),
+        );
+        assert!(result.is_err());
+
+        // a data key can't be decrypted as a bundle
+        let data_key = key.wrap_key(&[1; 32]).await?;
+        let mut forged = encrypted;
+        forged.encrypted_bundle = data_key;
+        let forged = ockam_core::cbor_encode_preallocate(forged)?;
+        assert!(VaultBundle::decrypt(&forged, "passphrase").await.is_err());
+        Ok(())
+    }
+}
@@ -6,7 +6,9 @@ use colorful::Colorful;
 use ockam::identity::{Identities, Vault};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_node::database::SqlxDatabase;
+use ockam_vault::storage::{SecretsRepository, SecretsSqlxDatabase};
 use ockam_vault_aws::AwsSigningVault;
+use ockam_vault_pkcs11::{Pkcs11SecureChannelVault, Pkcs11SigningVault};
 use std::fmt::Write;
 use std::fmt::{Debug, Display, Formatter};
 use std::fs::OpenOptions;
@@ -15,9 +17,15 @@ use std::sync::Arc;
 
 static DEFAULT_VAULT_NAME: &str = "default";
 
+/// Environment variable containing a new hex-encoded key-encryption key when rotating the key-encryption key of a vault
+pub const OCKAM_VAULT_NEW_KEK: &str = "OCKAM_VAULT_NEW_KEK";
+
+/// Environment variable containing a new passphrase when rotating the key-encryption key of a vault
+pub const OCKAM_VAULT_NEW_PASSPHRASE: &str = "OCKAM_VAULT_NEW_PASSPHRASE";
+
 /// The methods below support the creation and update of local vaults
 ///
-///  - by default private keys are stored locally but they can also be stored in a KMS
+///  - by default private keys are stored locally but they can also be stored in a KMS or in a PKCS#11 token
 ///  - keys stored locally are stored with other application data in the local database if the default vault is used
 ///  - any additional vault stores its keys in a separate file
 ///
@@ -32,6 +40,7 @@ impl CliState {
         vault_name: Option<String>,
         path: Option<PathBuf>,
         use_aws_kms: UseAwsKms,
+        use_pkcs11: UsePkcs11,
     ) -> Result<NamedVault> {
         let vaults_repository = self.vaults_repository();
 
@@ -58,17 +67,20 @@ impl CliState {
         match path {
             None => match self.vaults_repository().get_database_vault().await? {
                 None => Ok(vaults_repository
-                    .store_vault(&vault_name, VaultType::database(use_aws_kms))
+                    .store_vault(
+                        &vault_name,
+                        VaultType::database(use_aws_kms).with_pkcs11(use_pkcs11),
+                    )
                     .await?),
                 Some(_) => {
                     let path = self.make_vault_path(&vault_name)?;
                     Ok(self
-                        .create_local_vault(vault_name, &path, use_aws_kms)
+                        .create_local_vault(vault_name, &path, use_aws_kms, use_pkcs11)
                         .await?)
                 }
             },
             Some(path) => Ok(self
-                .create_local_vault(vault_name, &path, use_aws_kms)
+                .create_local_vault(vault_name, &path, use_aws_kms, use_pkcs11)
                 .await?),
         }
     }
@@ -104,7 +116,7 @@ impl CliState {
             match vault.vault_type {
                 VaultType::DatabaseVault { .. } => {
                     self.purpose_keys_repository().delete_all().await?;
-                    self.secrets_repository().delete_all().await?;
+                    self.secrets_repository().await?.delete_all().await?;
                 }
                 VaultType::LocalFileVault { path, .. } => {
                     let _ = std::fs::remove_file(path);
@@ -195,7 +207,7 @@ impl CliState {
                 "There is no default Vault on this machine, creating one..."
             ));
             let vault = self
-                .create_database_vault(vault_name.to_string(), UseAwsKms::No)
+                .create_database_vault(vault_name.to_string(), UseAwsKms::No, UsePkcs11::No)
                 .await?;
             self.notify_message(fmt_ok!(
                 "Created a new Vault named {}.",
@@ -208,6 +220,7 @@ impl CliState {
                     vault_name.to_string(),
                     &self.make_vault_path(vault_name)?,
                     UseAwsKms::No,
+                    UsePkcs11::No,
                 )
                 .await?;
             self.notify_message(fmt_ok!(
@@ -277,12 +290,16 @@ impl CliState {
             VaultType::LocalFileVault {
                 path: old_path,
                 use_aws_kms,
+                use_pkcs11,
             } => {
                 // copy the file to the new location
                 std::fs::copy(&old_path, path)?;
                 // update the path in the database
                 repository
-                    .update_vault(vault_name, VaultType::local_file(path, use_aws_kms))
+                    .update_vault(
+                        vault_name,
+                        VaultType::local_file(path, use_aws_kms).with_pkcs11(use_pkcs11),
+                    )
                     .await?;
                 // remove the old file
                 std::fs::remove_file(old_path)?;
@@ -291,27 +308,89 @@ impl CliState {
         Ok(())
     }
 
+    /// Encrypt the data keys of a vault with a new key-encryption key.
+    ///
+    /// The current key-encryption key is read from the OCKAM_VAULT_KEK or OCKAM_VAULT_PASSPHRASE
+    /// environment variables, and the new one from OCKAM_VAULT_NEW_KEK or OCKAM_VAULT_NEW_PASSPHRASE.
+    /// Secrets which are still stored in plain text are encrypted with the new key.
+    /// Return the number of updated secrets.
+    #[instrument(skip_all, fields(vault_name = vault_name.clone()))]
+    pub async fn rotate_vault_key_encryption_key(
+        &self,
+        vault_name: &Option<String>,
+    ) -> Result<usize> {
+        let named_vault = self.get_named_vault_or_default(vault_name).await?;
+        let mut repository =
+            SecretsSqlxDatabase::new(self.make_vault_database(&named_vault).await?);
+
+        let new_key_encryption_key = repository
+            .key_encryption_key_from_env_variables(OCKAM_VAULT_NEW_KEK, OCKAM_VAULT_NEW_PASSPHRASE)
+            .await?
+            .ok_or_else(|| {
+                ockam_core::Error::new(
+                    Origin::Api,
+                    Kind::Invalid,
+                    format!("A new key-encryption key must be set with the {OCKAM_VAULT_NEW_KEK} or the {OCKAM_VAULT_NEW_PASSPHRASE} environment variable"),
+                )
+            })?;
+        if let Some(key_encryption_key) = repository.key_encryption_key_from_env().await? {
+            repository = repository.with_key_encryption_key(Arc::new(key_encryption_key));
+        }
+        Ok(repository
+            .rotate_key_encryption_key(Arc::new(new_key_encryption_key))
+            .await?)
+    }
+
     /// Make a concrete vault based on the NamedVault metadata
     #[instrument(skip_all, fields(vault_name = named_vault.name))]
     pub async fn make_vault(&self, named_vault: NamedVault) -> Result<Vault> {
-        let db = match named_vault.vault_type {
+        let db = self.make_vault_database(&named_vault).await?;
+        let mut vault =
+            Vault::create_with_secrets_repository(self.make_secrets_repository(db).await?);
+
+        if named_vault.vault_type.use_aws_kms() {
+            let aws_vault = Arc::new(AwsSigningVault::create().await?);
+            vault.identity_vault = aws_vault.clone();
+            vault.credential_vault = aws_vault;
+        } else if named_vault.vault_type.use_pkcs11() {
+            let pkcs11_vault = Arc::new(Pkcs11SigningVault::create().await?);
+            let client = pkcs11_vault.client();
+            vault.identity_vault = pkcs11_vault.clone();
+            vault.credential_vault = pkcs11_vault;
+            // static secure channel keys are kept in the token when it supports X25519
+            if Pkcs11SecureChannelVault::is_supported(&client) {
+                vault.secure_channel_vault = Arc::new(Pkcs11SecureChannelVault::new(
+                    client,
+                    vault.secure_channel_vault,
+                ));
+            }
+        }
+        Ok(vault)
+    }
+
+    /// Return the database storing the secrets of a vault
+    pub(crate) async fn make_vault_database(
+        &self,
+        named_vault: &NamedVault,
+    ) -> Result<SqlxDatabase> {
+        Ok(match named_vault.vault_type {
             VaultType::DatabaseVault { .. } => self.database(),
             VaultType::LocalFileVault { ref path, .. } =>
             // TODO: Avoid creating multiple dbs with the same file
             {
                 SqlxDatabase::create_sqlite(path.as_path()).await?
             }
-        };
+        })
+    }
 
-        if named_vault.vault_type.use_aws_kms() {
-            let mut vault = Vault::create_with_database(db);
-            let aws_vault = Arc::new(AwsSigningVault::create().await?);
-            vault.identity_vault = aws_vault.clone();
-            vault.credential_vault = aws_vault;
-            Ok(vault)
-        } else {
-            Ok(Vault::create_with_database(db))
-        }
+    /// Return a repository for the secrets of a vault.
+    /// The secrets are encrypted if a key-encryption key is configured
+    /// with the OCKAM_VAULT_KEK or OCKAM_VAULT_PASSPHRASE environment variables
+    pub(crate) async fn make_secrets_repository(
+        &self,
+        database: SqlxDatabase,
+    ) -> Result<Arc<dyn SecretsRepository>> {
+        Ok(SecretsSqlxDatabase::make_repository_from_env(database).await?)
     }
 }
 
@@ -332,11 +411,15 @@ impl CliState {
         &self,
         vault_name: String,
         use_aws_kms: UseAwsKms,
+        use_pkcs11: UsePkcs11,
     ) -> Result<NamedVault> {
         match self.vaults_repository().get_database_vault().await? {
             None => Ok(self
                 .vaults_repository()
-                .store_vault(&vault_name, VaultType::database(use_aws_kms))
+                .store_vault(
+                    &vault_name,
+                    VaultType::database(use_aws_kms).with_pkcs11(use_pkcs11),
+                )
                 .await?),
             Some(vault) => Err(CliStateError::AlreadyExists {
                 resource: "database vault".to_string(),
@@ -351,6 +434,7 @@ impl CliState {
         vault_name: String,
         path: &PathBuf,
         use_aws_kms: UseAwsKms,
+        use_pkcs11: UsePkcs11,
     ) -> Result<NamedVault> {
         // check if the new file can be created
         let path_taken = self
@@ -374,7 +458,10 @@ impl CliState {
         };
         Ok(self
             .vaults_repository()
-            .store_vault(&vault_name, VaultType::local_file(path, use_aws_kms))
+            .store_vault(
+                &vault_name,
+                VaultType::local_file(path, use_aws_kms).with_pkcs11(use_pkcs11),
+            )
             .await?)
     }
 
@@ -411,10 +498,14 @@ pub struct NamedVault {
 pub enum VaultType {
     DatabaseVault {
         use_aws_kms: UseAwsKms,
+        #[serde(default)]
+        use_pkcs11: UsePkcs11,
     },
     LocalFileVault {
         path: PathBuf,
         use_aws_kms: UseAwsKms,
+        #[serde(default)]
+        use_pkcs11: UsePkcs11,
     },
 }
 
@@ -431,6 +522,9 @@ impl Display for VaultType {
         if self.use_aws_kms() {
             writeln!(f, "Uses AWS KMS: true",)?;
         }
+        if self.use_pkcs11() {
+            writeln!(f, "Uses PKCS#11: true",)?;
+        }
         Ok(())
     }
 }
@@ -451,15 +545,53 @@ impl UseAwsKms {
     }
 }
 
+#[derive(Debug, PartialEq, Eq, Clone, Default, serde::Serialize, serde::Deserialize)]
+pub enum UsePkcs11 {
+    Yes,
+    #[default]
+    No,
+}
+
+impl UsePkcs11 {
+    pub fn from(b: bool) -> Self {
+        if b {
+            UsePkcs11::Yes
+        } else {
+            UsePkcs11::No
+        }
+    }
+}
+
 impl VaultType {
     pub fn database(use_aws_kms: UseAwsKms) -> Self {
-        VaultType::DatabaseVault { use_aws_kms }
+        VaultType::DatabaseVault {
+            use_aws_kms,
+            use_pkcs11: UsePkcs11::No,
+        }
     }
 
     pub fn local_file(path: impl Into<PathBuf>, use_aws_kms: UseAwsKms) -> Self {
         VaultType::LocalFileVault {
             path: path.into(),
             use_aws_kms,
+            use_pkcs11: UsePkcs11::No,
+        }
+    }
+
+    /// Store the signing keys of the vault in a PKCS#11 token
+    pub fn with_pkcs11(self, use_pkcs11: UsePkcs11) -> Self {
+        match self {
+            VaultType::DatabaseVault { use_aws_kms, .. } => VaultType::DatabaseVault {
+                use_aws_kms,
+                use_pkcs11,
+            },
+            VaultType::LocalFileVault {
+                path, use_aws_kms, ..
+            } => VaultType::LocalFileVault {
+                path,
+                use_aws_kms,
+                use_pkcs11,
+            },
         }
     }
 
@@ -472,11 +604,15 @@ impl VaultType {
 
     pub fn use_aws_kms(&self) -> bool {
         match self {
-            VaultType::DatabaseVault { use_aws_kms } => use_aws_kms == &UseAwsKms::Yes,
-            VaultType::LocalFileVault {
-                path: _,
-                use_aws_kms,
-            } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::DatabaseVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+            VaultType::LocalFileVault { use_aws_kms, .. } => use_aws_kms == &UseAwsKms::Yes,
+        }
+    }
+
+    pub fn use_pkcs11(&self) -> bool {
+        match self {
+            VaultType::DatabaseVault { use_pkcs11, .. } => use_pkcs11 == &UsePkcs11::Yes,
+            VaultType::LocalFileVault { use_pkcs11, .. } => use_pkcs11 == &UsePkcs11::Yes,
         }
     }
 }
@@ -511,6 +647,11 @@ impl NamedVault {
         self.vault_type.use_aws_kms()
     }
 
+    /// Return true if a PKCS#11 token is used to store signing keys
+    pub fn use_pkcs11(&self) -> bool {
+        self.vault_type.use_pkcs11()
+    }
+
     /// Return the vault path if the vault data is stored in a local file
     pub fn path(&self) -> Option<&Path> {
         self.vault_type.path()
@@ -547,6 +688,9 @@ impl Output for NamedVault {
         if self.vault_type.use_aws_kms() {
             writeln!(output, "Uses AWS KMS: true",)?;
         }
+        if self.vault_type.use_pkcs11() {
+            writeln!(output, "Uses PKCS#11: true",)?;
+        }
         Ok(output)
     }
 }
@@ -557,6 +701,7 @@ mod tests {
     use ockam::identity::models::{PurposeKeyAttestation, PurposeKeyAttestationSignature};
     use ockam::identity::Purpose;
     use ockam_node::database::skip_if_postgres;
+    use ockam_vault::storage::KeyEncryptionKey;
     use ockam_vault::{
         ECDSASHA256CurveP256SecretKey, ECDSASHA256CurveP256Signature, HandleToSecret,
         SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
@@ -575,7 +720,12 @@ mod tests {
 
         // another vault cannot be created with the same name
         let result = cli
-            .create_named_vault(Some("vault1".to_string()), None, UseAwsKms::No)
+            .create_named_vault(
+                Some("vault1".to_string()),
+                None,
+                UseAwsKms::No,
+                UsePkcs11::No,
+            )
             .await
             .ok();
         assert_eq!(result, None);
@@ -604,6 +754,7 @@ mod tests {
                 Some("another name".to_string()),
                 named_vault2.path().map(|p| p.to_path_buf()),
                 UseAwsKms::No,
+                UsePkcs11::No,
             )
             .await
             .ok();
@@ -662,7 +813,12 @@ mod tests {
 
         // if we create a second vault, it can be returned by name
         let vault2 = cli
-            .create_named_vault(Some("vault-2".to_string()), None, UseAwsKms::No)
+            .create_named_vault(
+                Some("vault-2".to_string()),
+                None,
+                UseAwsKms::No,
+                UsePkcs11::No,
+            )
             .await?;
         let result = cli.get_named_vault_or_default(&Some(vault2.name())).await?;
         assert_eq!(result, vault2);
@@ -710,13 +866,17 @@ mod tests {
         let cli = CliState::test().await?;
 
         // the first vault is stored in the main database with the name 'default'
-        let result = cli.create_named_vault(None, None, UseAwsKms::No).await?;
+        let result = cli
+            .create_named_vault(None, None, UseAwsKms::No, UsePkcs11::No)
+            .await?;
         assert_eq!(result.name(), DEFAULT_VAULT_NAME.to_string());
         assert_eq!(result.vault_type(), VaultType::database(UseAwsKms::No));
 
         // the second vault is stored in a separate file, with a random name
         // that name is used to create the file name
-        let result = cli.create_named_vault(None, None, UseAwsKms::No).await?;
+        let result = cli
+            .create_named_vault(None, None, UseAwsKms::No, UsePkcs11::No)
+            .await?;
         assert!(result.path().is_some());
         assert!(result
             .path_as_string()
@@ -725,7 +885,12 @@ mod tests {
 
         // a third vault with a name is also stored in a separate file
         let result = cli
-            .create_named_vault(Some("secrets".to_string()), None, UseAwsKms::No)
+            .create_named_vault(
+                Some("secrets".to_string()),
+                None,
+                UseAwsKms::No,
+                UsePkcs11::No,
+            )
             .await?;
         assert_eq!(result.name(), "secrets".to_string());
         assert!(result.path().is_some());
@@ -741,7 +906,12 @@ mod tests {
                 cli_clone.reset().await?;
                 let cli = CliState::test().await?;
                 let result = cli
-                    .create_named_vault(Some("secrets".to_string()), None, UseAwsKms::No)
+                    .create_named_vault(
+                        Some("secrets".to_string()),
+                        None,
+                        UseAwsKms::No,
+                        UsePkcs11::No,
+                    )
                     .await?;
                 assert_eq!(result.name(), "secrets".to_string());
                 assert_eq!(result.vault_type(), VaultType::database(UseAwsKms::No));
@@ -764,6 +934,7 @@ mod tests {
                 Some("secrets".to_string()),
                 Some(vault_path.clone()),
                 UseAwsKms::No,
+                UsePkcs11::No,
             )
             .await?;
         assert_eq!(result.name(), "secrets".to_string());
@@ -777,7 +948,9 @@ mod tests {
         let cli = CliState::test().await?;
 
         // create a vault and populate the tables used by the vault
-        let vault = cli.create_named_vault(None, None, UseAwsKms::No).await?;
+        let vault = cli
+            .create_named_vault(None, None, UseAwsKms::No, UsePkcs11::No)
+            .await?;
 
         let purpose_keys_repository = cli.purpose_keys_repository();
         let identity = cli.create_identity_with_name("name").await?;
@@ -796,7 +969,7 @@ mod tests {
             )
             .await?;
 
-        let secrets_repository = cli.secrets_repository();
+        let secrets_repository = cli.secrets_repository().await?;
         let handle1 =
             SigningSecretKeyHandle::ECDSASHA256CurveP256(HandleToSecret::new(vec![1, 2, 3]));
         let secret1 =
@@ -834,4 +1007,41 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_rotate_vault_key_encryption_key() -> Result<()> {
+        let cli = CliState::test().await?;
+        let vault = cli.get_or_create_named_vault("vault").await?;
+
+        let handle = X25519SecretKeyHandle(HandleToSecret::new(vec![1, 2, 3]));
+        let secret = X25519SecretKey::new([1; 32]);
+        cli.secrets_repository()
+            .await?
+            .store_x25519_secret(&handle, secret.clone())
+            .await?;
+
+        // the secrets stored in plain text are encrypted with the new key-encryption key
+        let key = hex::encode([2; 32]);
+        std::env::set_var(OCKAM_VAULT_NEW_KEK, &key);
+        let result = cli
+            .rotate_vault_key_encryption_key(&Some(vault.name()))
+            .await;
+        std::env::remove_var(OCKAM_VAULT_NEW_KEK);
+        assert_eq!(result?, 1);
+
+        // they can only be read with that key
+        let result = cli
+            .secrets_repository()
+            .await?
+            .get_x25519_secret(&handle)
+            .await;
+        assert!(result.is_err());
+
+        let key_encryption_key = Arc::new(KeyEncryptionKey::from_hex(&key)?);
+        let repository =
+            SecretsSqlxDatabase::new(cli.database()).with_key_encryption_key(key_encryption_key);
+        let result = repository.get_x25519_secret(&handle).await?;
+        assert!(result == Some(secret));
+        Ok(())
+    }
 }
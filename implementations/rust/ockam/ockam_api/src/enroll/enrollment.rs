@@ -1,6 +1,7 @@
 use crate::authenticator::one_time_code::OneTimeCode;
 use crate::nodes::service::default_address::DefaultAddress;
-use crate::orchestrator::enroll::auth0::{AuthenticateOidcToken, OidcToken};
+use crate::orchestrator::enroll::auth0::{AuthenticateOidcToken, OidcToken, TokenType};
+use crate::orchestrator::enroll::Token;
 use crate::orchestrator::HasSecureClient;
 use miette::IntoDiagnostic;
 use ockam::identity::models::CredentialAndPurposeKey;
@@ -39,6 +40,8 @@ pub trait Enrollment {
         token: &OneTimeCode,
     ) -> miette::Result<EnrollStatus>;
 
+    async fn enroll_with_jwt(&self, ctx: &Context, token: Token) -> miette::Result<EnrollStatus>;
+
     async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey>;
 }
 
@@ -72,6 +75,10 @@ impl<T: HasSecureClient + Send + Sync> Enrollment for T {
         self.get_secure_client().present_token(ctx, token).await
     }
 
+    async fn enroll_with_jwt(&self, ctx: &Context, token: Token) -> miette::Result<EnrollStatus> {
+        self.get_secure_client().enroll_with_jwt(ctx, token).await
+    }
+
     async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey> {
         self.get_secure_client().issue_credential(ctx).await
     }
@@ -148,6 +155,30 @@ impl Enrollment for SecureClient {
         }
     }
 
+    #[instrument(skip_all)]
+    async fn enroll_with_jwt(&self, ctx: &Context, token: Token) -> miette::Result<EnrollStatus> {
+        let req = Request::post("/").body(AuthenticateOidcToken {
+            token_type: TokenType::Bearer,
+            access_token: token,
+        });
+        trace!(target: TARGET, "present a JWT");
+        match self
+            .tell(ctx, DefaultAddress::OIDC_AUTHENTICATOR, req)
+            .await
+            .into_diagnostic()?
+        {
+            Reply::Successful(_) => Ok(EnrollStatus::EnrolledSuccessfully),
+            Reply::Failed(e, s) => match (e.message(), s) {
+                (Some(error), Some(Status::Forbidden))
+                    if error.to_lowercase().contains("already a member") =>
+                {
+                    Ok(EnrollStatus::AlreadyEnrolled)
+                }
+                _ => Err(miette::miette!(e)),
+            },
+        }
+    }
+
     #[instrument(skip_all)]
     async fn issue_credential(&self, ctx: &Context) -> miette::Result<CredentialAndPurposeKey> {
         let req = Request::post("/");
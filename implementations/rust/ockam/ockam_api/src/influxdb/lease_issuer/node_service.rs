@@ -8,7 +8,7 @@ use crate::nodes::{InMemoryNode, NodeManagerWorker};
 use crate::{ApiError, DefaultAddress};
 use miette::IntoDiagnostic;
 use minicbor::{CborLen, Decode, Encode};
-use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_abac::{PolicyExpression, Resource, ResourceType};
 use ockam_core::api::{Error, Request, Response};
 use ockam_core::{async_trait, Address};
 use ockam_multiaddr::MultiAddr;
@@ -81,7 +81,6 @@ impl InMemoryNode {
                 context,
                 self.project_authority(),
                 Resource::new(address.address(), ResourceType::InfluxDBLessor),
-                Action::HandleMessage,
                 req.policy_expression,
             )
             .await?;
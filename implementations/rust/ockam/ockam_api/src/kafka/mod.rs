@@ -15,6 +15,10 @@ use ockam_abac::expr::{eq, or, str};
 use ockam_abac::{subject_has_credential_policy_expression, subject_identifier_attribute, Expr};
 use ockam_core::Address;
 pub(crate) use outlet_controller::KafkaOutletController;
+pub use protocol_aware::record_codec::KafkaRecordFormat;
+pub use protocol_aware::record_metadata::{
+    KafkaHeadersEncryption, KafkaKeyEncryption, DETERMINISTIC_KEY_SECRET_LENGTH,
+};
 
 pub const KAFKA_OUTLET_INTERCEPTOR_ADDRESS: &str = "kafka_interceptor";
 pub const KAFKA_OUTLET_BOOTSTRAP_ADDRESS: &str = "kafka_bootstrap";
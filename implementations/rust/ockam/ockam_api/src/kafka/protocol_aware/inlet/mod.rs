@@ -1,5 +1,7 @@
 use crate::kafka::key_exchange::controller::KafkaKeyExchangeControllerImpl;
 use crate::kafka::key_exchange::KafkaKeyExchangeController;
+use crate::kafka::protocol_aware::record_codec::RecordCodec;
+use crate::kafka::protocol_aware::record_metadata::{KafkaHeadersEncryption, RecordKeyEncryptor};
 use crate::kafka::protocol_aware::{
     CorrelationId, KafkaMessageInterceptor, KafkaMessageInterceptorWrapper, RequestInfo,
     TopicUuidMap, MAX_KAFKA_MESSAGE_SIZE,
@@ -23,7 +25,9 @@ pub(crate) struct InletInterceptorImpl {
     key_exchange_controller: Arc<dyn KafkaKeyExchangeController>,
     inlet_map: KafkaInletController,
     encrypt_content: bool,
-    encrypted_fields: Vec<String>,
+    record_codec: Option<Arc<dyn RecordCodec>>,
+    key_encryption: Option<RecordKeyEncryptor>,
+    headers_encryption: KafkaHeadersEncryption,
 }
 
 #[async_trait]
@@ -35,7 +39,9 @@ impl InletInterceptorImpl {
         uuid_to_name: TopicUuidMap,
         inlet_map: KafkaInletController,
         encrypt_content: bool,
-        encrypted_fields: Vec<String>,
+        record_codec: Option<Arc<dyn RecordCodec>>,
+        key_encryption: Option<RecordKeyEncryptor>,
+        headers_encryption: KafkaHeadersEncryption,
     ) -> InletInterceptorImpl {
         Self {
             request_map: Arc::new(Mutex::new(Default::default())),
@@ -43,7 +49,9 @@ impl InletInterceptorImpl {
             key_exchange_controller,
             inlet_map,
             encrypt_content,
-            encrypted_fields,
+            record_codec,
+            key_encryption,
+            headers_encryption,
         }
     }
 
@@ -69,7 +77,9 @@ pub(crate) struct KafkaInletInterceptorFactory {
     uuid_to_name: TopicUuidMap,
     inlet_map: KafkaInletController,
     encrypt_content: bool,
-    encrypted_fields: Vec<String>,
+    record_codec: Option<Arc<dyn RecordCodec>>,
+    key_encryption: Option<RecordKeyEncryptor>,
+    headers_encryption: KafkaHeadersEncryption,
 }
 
 impl KafkaInletInterceptorFactory {
@@ -77,14 +87,18 @@ impl KafkaInletInterceptorFactory {
         secure_channel_controller: KafkaKeyExchangeControllerImpl,
         inlet_map: KafkaInletController,
         encrypt_content: bool,
-        encrypted_fields: Vec<String>,
+        record_codec: Option<Arc<dyn RecordCodec>>,
+        key_encryption: Option<RecordKeyEncryptor>,
+        headers_encryption: KafkaHeadersEncryption,
     ) -> Self {
         Self {
             secure_channel_controller,
             uuid_to_name: Default::default(),
             inlet_map,
             encrypt_content,
-            encrypted_fields,
+            record_codec,
+            key_encryption,
+            headers_encryption,
         }
     }
 }
@@ -97,7 +111,9 @@ impl PortalInterceptorFactory for KafkaInletInterceptorFactory {
                 self.uuid_to_name.clone(),
                 self.inlet_map.clone(),
                 self.encrypt_content,
-                self.encrypted_fields.clone(),
+                self.record_codec.clone(),
+                self.key_encryption.clone(),
+                self.headers_encryption.clone(),
             )),
             MAX_KAFKA_MESSAGE_SIZE,
         ))
@@ -1,4 +1,6 @@
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
+use crate::kafka::protocol_aware::record_codec::{FieldTransformation, RecordCodec};
+use crate::kafka::protocol_aware::record_metadata::RecordKeyEncryptor;
 use crate::kafka::protocol_aware::utils::{decode_body, encode_request};
 use crate::kafka::protocol_aware::RequestInfo;
 use crate::kafka::protocol_aware::{InterceptError, KafkaMessageRequestInterceptor};
@@ -15,6 +17,7 @@ use kafka_protocol::records::{
 use minicbor::encode::Encoder;
 use ockam_core::async_trait;
 use ockam_node::Context;
+use std::collections::VecDeque;
 use std::convert::TryFrom;
 use tracing::warn;
 
@@ -218,22 +221,60 @@ impl InletInterceptorImpl {
 
                     for record in records.iter_mut() {
                         if let Some(record_value) = record.value.take() {
-                            let buffer = if !self.encrypted_fields.is_empty() {
-                                // if we encrypt only specific fields, we assume the record must be
-                                // valid JSON map
+                            let buffer = if let Some(record_codec) = &self.record_codec {
+                                // if we encrypt only specific fields, the record must be
+                                // valid for the configured record format
                                 self.encrypt_specific_fields(
                                     context,
                                     &topic.name,
                                     data,
+                                    record_codec.as_ref(),
                                     &record_value,
                                 )
                                 .await?
                             } else {
-                                self.encrypt_whole_record(context, &topic.name, data, record_value)
-                                    .await?
+                                self.encrypt_record_content(
+                                    context,
+                                    &topic.name,
+                                    data,
+                                    record_value,
+                                )
+                                .await?
                             };
                             record.value = Some(buffer.into());
                         }
+
+                        if let Some(key_encryption) = &self.key_encryption {
+                            if let Some(key) = record.key.take() {
+                                let buffer = match key_encryption {
+                                    RecordKeyEncryptor::Randomized => {
+                                        self.encrypt_record_content(context, &topic.name, data, key)
+                                            .await?
+                                    }
+                                    RecordKeyEncryptor::Deterministic(encryptor) => {
+                                        encryptor.encrypt(&key).map_err(InterceptError::Ockam)?
+                                    }
+                                };
+                                record.key = Some(buffer.into());
+                            }
+                        }
+
+                        for (name, value) in record.headers.iter_mut() {
+                            if !self.headers_encryption.includes(name) {
+                                continue;
+                            }
+                            if let Some(header_value) = value.take() {
+                                let buffer = self
+                                    .encrypt_record_content(
+                                        context,
+                                        &topic.name,
+                                        data,
+                                        header_value,
+                                    )
+                                    .await?;
+                                *value = Some(buffer.into());
+                            }
+                        }
                     }
 
                     let mut encoded = BytesMut::new();
@@ -261,16 +302,17 @@ impl InletInterceptorImpl {
         )
     }
 
-    async fn encrypt_whole_record(
+    /// Encrypt a record value, key or header value for the consumer of the topic partition
+    async fn encrypt_record_content(
         &self,
         context: &mut Context,
         topic_name: &TopicName,
         data: &mut PartitionProduceData,
-        record_value: Bytes,
+        content: Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
         let encrypted_content = self
             .key_exchange_controller
-            .encrypt_content(context, topic_name, data.index, record_value.to_vec())
+            .encrypt_content(context, topic_name, data.index, content.to_vec())
             .await
             .map_err(InterceptError::Ockam)?;
 
@@ -288,37 +330,38 @@ impl InletInterceptorImpl {
         context: &mut Context,
         topic_name: &TopicName,
         data: &mut PartitionProduceData,
+        record_codec: &dyn RecordCodec,
         record_value: &Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
-        let mut record_value = serde_json::from_slice::<serde_json::Value>(record_value)?;
-
-        if let serde_json::Value::Object(map) = &mut record_value {
-            for field in &self.encrypted_fields {
-                if let Some(value) = map.get_mut(field) {
-                    let encrypted_content = self
-                        .key_exchange_controller
-                        .encrypt_content(
-                            context,
-                            topic_name,
-                            data.index,
-                            serde_json::to_vec(value).map_err(|_| InterceptError::InvalidData)?,
-                        )
-                        .await
-                        .map_err(InterceptError::Ockam)?;
-
-                    let mut write_buffer = Vec::with_capacity(1024);
-                    let mut encoder = Encoder::new(&mut write_buffer);
-                    encoder
-                        .encode(encrypted_content)
-                        .map_err(|_| InterceptError::InvalidData)?;
-                    *value = serde_json::Value::String(hex::encode(&write_buffer));
-                }
-            }
-        } else {
-            warn!("only JSON objects are supported for field encryption");
-            return Err("Only JSON objects are supported".into());
+        // the codec can't encrypt the fields while walking the record since encryption is
+        // asynchronous: the fields content is collected first, and then replaced
+        let mut fields_content = vec![];
+        record_codec.map_fields(record_value, FieldTransformation::Encrypt, &mut |content| {
+            fields_content.push(content);
+            Ok(None)
+        })?;
+
+        let mut encrypted_fields = VecDeque::with_capacity(fields_content.len());
+        for content in fields_content {
+            let encrypted_content = self
+                .key_exchange_controller
+                .encrypt_content(context, topic_name, data.index, content)
+                .await
+                .map_err(InterceptError::Ockam)?;
+
+            let mut write_buffer = Vec::with_capacity(1024);
+            let mut encoder = Encoder::new(&mut write_buffer);
+            encoder
+                .encode(encrypted_content)
+                .map_err(|_| InterceptError::InvalidData)?;
+            encrypted_fields.push_back(write_buffer);
         }
 
-        Ok(record_value.to_string().as_bytes().to_vec())
+        record_codec.map_fields(record_value, FieldTransformation::Encrypt, &mut |_| {
+            encrypted_fields
+                .pop_front()
+                .map(Some)
+                .ok_or(InterceptError::InvalidData)
+        })
     }
 }
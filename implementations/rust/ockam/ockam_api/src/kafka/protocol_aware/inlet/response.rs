@@ -1,4 +1,6 @@
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
+use crate::kafka::protocol_aware::record_codec::{FieldTransformation, RecordCodec};
+use crate::kafka::protocol_aware::record_metadata::RecordKeyEncryptor;
 use crate::kafka::protocol_aware::utils::{decode_body, encode_response};
 use crate::kafka::protocol_aware::{
     InterceptError, KafkaEncryptedContent, KafkaMessageResponseInterceptor, RequestInfo,
@@ -17,6 +19,7 @@ use kafka_protocol::records::{
 use minicbor::Decoder;
 use ockam_core::async_trait;
 use ockam_node::Context;
+use std::collections::VecDeque;
 
 #[async_trait]
 impl KafkaMessageResponseInterceptor for InletInterceptorImpl {
@@ -300,13 +303,44 @@ impl InletInterceptorImpl {
 
                     for record in records.iter_mut() {
                         if let Some(record_value) = record.value.take() {
-                            let decrypted_content = if self.encrypted_fields.is_empty() {
-                                self.decrypt_whole_record(context, record_value).await?
-                            } else {
-                                self.decrypt_specific_fields(context, record_value).await?
+                            let decrypted_content = match &self.record_codec {
+                                Some(record_codec) => {
+                                    self.decrypt_specific_fields(
+                                        context,
+                                        record_codec.as_ref(),
+                                        record_value,
+                                    )
+                                    .await?
+                                }
+                                None => self.decrypt_record_content(context, record_value).await?,
                             };
                             record.value = Some(decrypted_content.into());
                         }
+
+                        if let Some(key_encryption) = &self.key_encryption {
+                            if let Some(key) = record.key.take() {
+                                let decrypted_key = match key_encryption {
+                                    RecordKeyEncryptor::Randomized => {
+                                        self.decrypt_record_content(context, key).await?
+                                    }
+                                    RecordKeyEncryptor::Deterministic(encryptor) => {
+                                        encryptor.decrypt(&key).map_err(InterceptError::Ockam)?
+                                    }
+                                };
+                                record.key = Some(decrypted_key.into());
+                            }
+                        }
+
+                        for (name, value) in record.headers.iter_mut() {
+                            if !self.headers_encryption.includes(name) {
+                                continue;
+                            }
+                            if let Some(header_value) = value.take() {
+                                let decrypted_value =
+                                    self.decrypt_record_content(context, header_value).await?;
+                                *value = Some(decrypted_value.into());
+                            }
+                        }
                     }
 
                     let mut encoded = BytesMut::new();
@@ -333,13 +367,13 @@ impl InletInterceptorImpl {
         )
     }
 
-    async fn decrypt_whole_record(
+    /// Decrypt a record value, key or header value encrypted by a producer
+    async fn decrypt_record_content(
         &self,
         context: &mut Context,
-        record_value: Bytes,
+        content: Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
-        let message_wrapper: KafkaEncryptedContent =
-            Decoder::new(record_value.as_ref()).decode()?;
+        let message_wrapper: KafkaEncryptedContent = Decoder::new(content.as_ref()).decode()?;
 
         self.key_exchange_controller
             .decrypt_content(
@@ -354,47 +388,43 @@ impl InletInterceptorImpl {
     async fn decrypt_specific_fields(
         &self,
         context: &mut Context,
+        record_codec: &dyn RecordCodec,
         record_value: Bytes,
     ) -> Result<Vec<u8>, InterceptError> {
-        let mut record_value = serde_json::from_slice::<serde_json::Value>(&record_value)?;
-
-        if let serde_json::Value::Object(map) = &mut record_value {
-            for field in &self.encrypted_fields {
-                // when the encrypted field is present is expected to be a hex encoded string
-                // wrapped by the KafkaEncryptedContent struct
-                if let Some(value) = map.get_mut(field) {
-                    let encrypted_content = if let serde_json::Value::String(string) = value {
-                        hex::decode(string).map_err(|_| "Encrypted is not a valid hex string")?
-                    } else {
-                        error!("encrypted field is not a hex string");
-                        return Err("The encrypted field is not a hex-encoded string".into());
-                    };
-
-                    let message_wrapper: KafkaEncryptedContent =
-                        Decoder::new(&encrypted_content).decode()?;
-
-                    let decrypted_content = self
-                        .key_exchange_controller
-                        .decrypt_content(
-                            context,
-                            &message_wrapper.consumer_decryptor_address,
-                            message_wrapper.content,
-                        )
-                        .await
-                        .map_err(InterceptError::Ockam)?;
-
-                    *value = serde_json::from_slice(decrypted_content.as_slice())?;
-                }
-            }
-            serde_json::to_vec(&record_value).map_err(|error| {
-                error!("cannot serialize decrypted fields");
-                error.into()
-            })
-        } else {
-            error!(
-                "cannot decrypt specific fields, expected a JSON object but got a different type"
-            );
-            Err("Only JSON objects are supported in the message".into())
+        // as for the encryption, the encrypted fields are collected first,
+        // then decrypted and finally replaced
+        let mut encrypted_fields = vec![];
+        record_codec.map_fields(
+            &record_value,
+            FieldTransformation::Decrypt,
+            &mut |content| {
+                encrypted_fields.push(content);
+                Ok(None)
+            },
+        )?;
+
+        let mut decrypted_fields = VecDeque::with_capacity(encrypted_fields.len());
+        for encrypted_content in encrypted_fields {
+            let message_wrapper: KafkaEncryptedContent =
+                Decoder::new(&encrypted_content).decode()?;
+
+            let decrypted_content = self
+                .key_exchange_controller
+                .decrypt_content(
+                    context,
+                    &message_wrapper.consumer_decryptor_address,
+                    message_wrapper.content,
+                )
+                .await
+                .map_err(InterceptError::Ockam)?;
+            decrypted_fields.push_back(decrypted_content);
         }
+
+        record_codec.map_fields(&record_value, FieldTransformation::Decrypt, &mut |_| {
+            decrypted_fields
+                .pop_front()
+                .map(Some)
+                .ok_or(InterceptError::InvalidData)
+        })
     }
 }
@@ -1,9 +1,11 @@
 use crate::kafka::key_exchange::KafkaKeyExchangeController;
 use crate::kafka::protocol_aware::inlet::InletInterceptorImpl;
+use crate::kafka::protocol_aware::record_codec::JsonRecordCodec;
 use crate::kafka::protocol_aware::{
     utils, KafkaEncryptedContent, KafkaMessageRequestInterceptor, KafkaMessageResponseInterceptor,
 };
 use crate::kafka::KafkaInletController;
+use crate::kafka::{KafkaHeadersEncryption, KafkaKeyEncryption};
 use bytes::{Bytes, BytesMut};
 use kafka_protocol::messages::fetch_response::{FetchableTopicResponse, PartitionData};
 use kafka_protocol::messages::produce_request::{PartitionProduceData, TopicProduceData};
@@ -63,7 +65,34 @@ impl KafkaKeyExchangeController for MockKafkaKeyExchangeController {
 
 const TEST_KAFKA_API_VERSION: i16 = 13;
 
+pub fn create_record(content: &[u8]) -> Record {
+    Record {
+        transactional: false,
+        control: false,
+        partition_leader_epoch: 0,
+        producer_id: 0,
+        producer_epoch: 0,
+        timestamp_type: TimestampType::Creation,
+        offset: 0,
+        sequence: 0,
+        timestamp: 0,
+        key: None,
+        value: Some(BytesMut::from(content).freeze()),
+        headers: Default::default(),
+    }
+}
+
+fn header(record: &Record, name: &'static str) -> Bytes {
+    record.headers[&StrBytes::from_static_str(name)]
+        .clone()
+        .unwrap()
+}
+
 pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
+    create_kafka_produce_request_for_record(create_record(content))
+}
+
+pub fn create_kafka_produce_request_for_record(record: Record) -> BytesMut {
     let header = RequestHeader::default()
         .with_request_api_key(ApiKey::ProduceKey as i16)
         .with_request_api_version(TEST_KAFKA_API_VERSION)
@@ -73,21 +102,7 @@ pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
     let mut encoded = BytesMut::new();
     RecordBatchEncoder::encode(
         &mut encoded,
-        [Record {
-            transactional: false,
-            control: false,
-            partition_leader_epoch: 0,
-            producer_id: 0,
-            producer_epoch: 0,
-            timestamp_type: TimestampType::Creation,
-            offset: 0,
-            sequence: 0,
-            timestamp: 0,
-            key: None,
-            value: Some(BytesMut::from(content).freeze()),
-            headers: Default::default(),
-        }]
-        .iter(),
+        [record].iter(),
         &RecordEncodeOptions {
             version: 2,
             compression: Compression::None,
@@ -113,26 +128,16 @@ pub fn create_kafka_produce_request(content: &[u8]) -> BytesMut {
 }
 
 pub fn create_kafka_fetch_response(content: &[u8]) -> BytesMut {
+    create_kafka_fetch_response_for_record(create_record(content))
+}
+
+pub fn create_kafka_fetch_response_for_record(record: Record) -> BytesMut {
     let header = ResponseHeader::default().with_correlation_id(1);
 
     let mut encoded = BytesMut::new();
     RecordBatchEncoder::encode(
         &mut encoded,
-        [Record {
-            transactional: false,
-            control: false,
-            partition_leader_epoch: 0,
-            producer_id: 0,
-            producer_epoch: 0,
-            timestamp_type: TimestampType::Creation,
-            offset: 0,
-            sequence: 0,
-            timestamp: 0,
-            key: None,
-            value: Some(BytesMut::from(content).freeze()),
-            headers: Default::default(),
-        }]
-        .iter(),
+        [record].iter(),
         &RecordEncodeOptions {
             version: 2,
             compression: Compression::None,
@@ -211,11 +216,13 @@ pub async fn json_encrypt_specific_fields(context: &mut Context) -> ockam::Resul
         Default::default(),
         KafkaInletController::stub(),
         true,
-        vec![
+        Some(Arc::new(JsonRecordCodec::new(vec![
             "field1".to_string(),
             "field2".to_string(),
             "field3".to_string(),
-        ],
+        ]))),
+        None,
+        Default::default(),
     );
 
     let encrypted_response = interceptor
@@ -292,11 +299,13 @@ pub async fn json_decrypt_specific_fields(context: &mut Context) -> ockam::Resul
         Default::default(),
         KafkaInletController::stub(),
         true,
-        vec![
+        Some(Arc::new(JsonRecordCodec::new(vec![
             "field1".to_string(),
             "field2".to_string(),
             "field3".to_string(),
-        ],
+        ]))),
+        None,
+        Default::default(),
     );
 
     interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
@@ -364,3 +373,111 @@ pub async fn json_decrypt_specific_fields(context: &mut Context) -> ockam::Resul
 
     Ok(())
 }
+
+#[ockam::test]
+pub async fn encrypt_and_decrypt_keys_and_headers(context: &mut Context) -> ockam::Result<()> {
+    let interceptor = InletInterceptorImpl::new(
+        Arc::new(MockKafkaKeyExchangeController {}),
+        Default::default(),
+        KafkaInletController::stub(),
+        true,
+        None,
+        KafkaKeyEncryption::Deterministic {
+            secret: vec![1; 32],
+        }
+        .encryptor()?,
+        KafkaHeadersEncryption::Selected(vec!["trace-id".to_string()]),
+    );
+
+    let mut record = create_record(b"value");
+    record.key = Some(Bytes::from_static(b"customer-1"));
+    record.headers.insert(
+        StrBytes::from_static_str("trace-id"),
+        Some(Bytes::from_static(b"abc")),
+    );
+    record.headers.insert(
+        StrBytes::from_static_str("content-type"),
+        Some(Bytes::from_static(b"text/plain")),
+    );
+
+    let encrypted_request = interceptor
+        .intercept_request(context, create_kafka_produce_request_for_record(record))
+        .await
+        .unwrap();
+
+    let request = parse_produce_request(&encrypted_request);
+    let mut batch_content = request.topic_data[0].partition_data[0]
+        .records
+        .clone()
+        .unwrap();
+    let encrypted_record = RecordBatchDecoder::decode(
+        &mut batch_content,
+        None::<fn(&mut Bytes, Compression) -> Result<Bytes, _>>,
+    )
+    .unwrap()
+    .remove(0);
+
+    // the key is encrypted deterministically
+    let encrypted_key = encrypted_record.key.clone().unwrap();
+    assert_ne!(encrypted_key.as_ref(), b"customer-1");
+    let other_request = interceptor
+        .intercept_request(context, {
+            let mut record = create_record(b"other value");
+            record.key = Some(Bytes::from_static(b"customer-1"));
+            create_kafka_produce_request_for_record(record)
+        })
+        .await
+        .unwrap();
+    let request = parse_produce_request(&other_request);
+    let mut batch_content = request.topic_data[0].partition_data[0]
+        .records
+        .clone()
+        .unwrap();
+    let other_record = RecordBatchDecoder::decode(
+        &mut batch_content,
+        None::<fn(&mut Bytes, Compression) -> Result<Bytes, _>>,
+    )
+    .unwrap()
+    .remove(0);
+    assert_eq!(other_record.key.unwrap(), encrypted_key);
+
+    // only the selected headers are encrypted
+    let trace_id = header(&encrypted_record, "trace-id");
+    let encrypted_content: KafkaEncryptedContent = Decoder::new(trace_id.as_ref()).decode()?;
+    assert_eq!(encrypted_content.content, b"encrypted:abc");
+    assert_eq!(
+        header(&encrypted_record, "content-type"),
+        Bytes::from_static(b"text/plain")
+    );
+
+    // the consumer decrypts the key and the headers
+    interceptor.add_request(1, ApiKey::FetchKey, TEST_KAFKA_API_VERSION);
+    let decrypted_response = interceptor
+        .intercept_response(
+            context,
+            create_kafka_fetch_response_for_record(encrypted_record),
+        )
+        .await
+        .unwrap();
+
+    let response = parse_fetch_response(&decrypted_response);
+    let mut records = response.responses[0].partitions[0].records.clone().unwrap();
+    let record = RecordBatchDecoder::decode(
+        &mut records,
+        None::<fn(&mut Bytes, Compression) -> Result<Bytes, _>>,
+    )
+    .unwrap()
+    .remove(0);
+    assert_eq!(
+        record.key.clone().unwrap(),
+        Bytes::from_static(b"customer-1")
+    );
+    assert_eq!(record.value.clone().unwrap(), Bytes::from_static(b"value"));
+    assert_eq!(header(&record, "trace-id"), Bytes::from_static(b"abc"));
+    assert_eq!(
+        header(&record, "content-type"),
+        Bytes::from_static(b"text/plain")
+    );
+
+    Ok(())
+}
@@ -15,6 +15,8 @@ mod tests;
 
 pub(crate) mod inlet;
 mod length_delimited;
+pub(crate) mod record_codec;
+pub(crate) mod record_metadata;
 pub(super) mod utils;
 
 use crate::kafka::protocol_aware::length_delimited::{length_encode, KafkaMessageDecoder};
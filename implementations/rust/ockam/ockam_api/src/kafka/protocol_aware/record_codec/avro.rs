@@ -0,0 +1,444 @@
+use crate::kafka::protocol_aware::record_codec::{
+    map_field_content, unsupported_field_type, write_zigzag, FieldKind, FieldMapper, FieldPaths,
+    FieldTransformation, RecordCodec, RecordReader,
+};
+use crate::kafka::protocol_aware::InterceptError;
+use crate::ApiError;
+use ockam_core::compat::collections::{BTreeMap, HashMap};
+use serde_json::Value;
+
+/// Magic byte starting the records serialized with the Confluent wire format
+const CONFLUENT_MAGIC_BYTE: u8 = 0;
+
+/// Length of the Confluent wire format header: magic byte + 4 bytes schema id
+const CONFLUENT_HEADER_LENGTH: usize = 5;
+
+/// Maximum nesting depth of the values of a record, since recursive schemas allow records
+/// nesting values indefinitely
+const MAX_NESTING_DEPTH: usize = 64;
+
+/// Codec for Avro records serialized with the Confluent wire format.
+/// The schema id found in each record selects the schema used to walk the record.
+/// Only string and bytes fields, possibly in a union with other types, can be encrypted.
+pub(crate) struct AvroRecordCodec {
+    schemas: BTreeMap<u32, AvroSchema>,
+    paths: FieldPaths,
+}
+
+impl AvroRecordCodec {
+    pub(crate) fn new(
+        schemas: &BTreeMap<u32, String>,
+        fields: &[String],
+    ) -> ockam_core::Result<Self> {
+        if schemas.is_empty() {
+            return Err(ApiError::core(
+                "At least one Avro schema is required to encrypt fields",
+            ));
+        }
+        let mut parsed = BTreeMap::new();
+        for (id, schema) in schemas {
+            let schema = AvroSchema::parse(schema).map_err(|e| {
+                ApiError::core(format!("The Avro schema with id {id} is invalid: {e}"))
+            })?;
+            parsed.insert(*id, schema);
+        }
+        Ok(Self {
+            schemas: parsed,
+            paths: FieldPaths::new(fields),
+        })
+    }
+}
+
+impl RecordCodec for AvroRecordCodec {
+    fn map_fields(
+        &self,
+        record: &[u8],
+        transformation: FieldTransformation,
+        map: &mut FieldMapper<'_>,
+    ) -> Result<Vec<u8>, InterceptError> {
+        if record.len() < CONFLUENT_HEADER_LENGTH || record[0] != CONFLUENT_MAGIC_BYTE {
+            warn!("the Avro record doesn't use the Confluent wire format");
+            return Err("Avro records must use the Confluent wire format".into());
+        }
+        let schema_id = u32::from_be_bytes([record[1], record[2], record[3], record[4]]);
+        let schema = self.schemas.get(&schema_id).ok_or_else(|| {
+            warn!("unknown Avro schema id {schema_id}");
+            InterceptError::Generic("Unknown Avro schema id")
+        })?;
+
+        let mut reader = RecordReader::new(&record[CONFLUENT_HEADER_LENGTH..]);
+        let mut output = Vec::with_capacity(record.len());
+        output.extend_from_slice(&record[..CONFLUENT_HEADER_LENGTH]);
+        schema.map_value(
+            schema.root,
+            Some(&self.paths),
+            &mut reader,
+            &mut output,
+            transformation,
+            map,
+            0,
+        )?;
+        if !reader.is_empty() {
+            warn!("the Avro record is longer than its schema");
+            return Err(InterceptError::InvalidData);
+        }
+        Ok(output)
+    }
+}
+
+/// Avro type. Named types referencing each other are stored in an arena and
+/// referenced by their index.
+#[derive(Debug, Clone)]
+enum AvroType {
+    Null,
+    Boolean,
+    Int,
+    Long,
+    Float,
+    Double,
+    Bytes,
+    String,
+    Record(Vec<(String, usize)>),
+    Enum,
+    Array(usize),
+    Map(usize),
+    Union(Vec<usize>),
+    Fixed(usize),
+}
+
+/// Parsed Avro schema
+#[derive(Debug)]
+struct AvroSchema {
+    types: Vec<AvroType>,
+    root: usize,
+}
+
+impl AvroSchema {
+    fn parse(schema: &str) -> Result<Self, String> {
+        let json: Value = serde_json::from_str(schema).map_err(|e| e.to_string())?;
+        let mut parser = AvroSchemaParser::default();
+        let root = parser.parse(&json, None)?;
+        Ok(Self {
+            types: parser.types,
+            root,
+        })
+    }
+
+    /// Copy a value, nested at `depth`, to the output, transforming the fields selected by `paths`
+    #[allow(clippy::too_many_arguments)]
+    fn map_value(
+        &self,
+        index: usize,
+        paths: Option<&FieldPaths>,
+        reader: &mut RecordReader,
+        output: &mut Vec<u8>,
+        transformation: FieldTransformation,
+        map: &mut FieldMapper<'_>,
+        depth: usize,
+    ) -> Result<(), InterceptError> {
+        check_depth(depth)?;
+        let paths = match paths {
+            Some(paths) => paths,
+            None => {
+                let start = reader.position();
+                self.skip_value(index, reader, depth)?;
+                output.extend_from_slice(reader.since(start));
+                return Ok(());
+            }
+        };
+
+        match &self.types[index] {
+            AvroType::Null => Ok(()),
+            AvroType::Union(branches) => {
+                let branch = reader.read_zigzag()?;
+                let branch_index = usize::try_from(branch)
+                    .ok()
+                    .and_then(|b| branches.get(b))
+                    .ok_or(InterceptError::InvalidData)?;
+                write_zigzag(output, branch);
+                self.map_value(
+                    *branch_index,
+                    Some(paths),
+                    reader,
+                    output,
+                    transformation,
+                    map,
+                    depth + 1,
+                )
+            }
+            AvroType::String | AvroType::Bytes if paths.is_target() => {
+                let kind = match &self.types[index] {
+                    AvroType::String => FieldKind::String,
+                    _ => FieldKind::Bytes,
+                };
+                let content = read_length_delimited(reader)?;
+                let content = match map_field_content(kind, transformation, content, map)? {
+                    Some(new_content) => new_content,
+                    None => content.to_vec(),
+                };
+                write_zigzag(output, content.len() as i64);
+                output.extend_from_slice(&content);
+                Ok(())
+            }
+            AvroType::Record(fields) if !paths.is_target() => {
+                for (name, field_index) in fields {
+                    self.map_value(
+                        *field_index,
+                        paths.child(name),
+                        reader,
+                        output,
+                        transformation,
+                        map,
+                        depth + 1,
+                    )?;
+                }
+                Ok(())
+            }
+            _ if paths.is_target() => Err(unsupported_field_type()),
+            _ => {
+                warn!("the encrypted fields don't match the Avro schema");
+                Err("The encrypted fields don't match the Avro schema".into())
+            }
+        }
+    }
+
+    /// Move the reader past a value nested at `depth`
+    fn skip_value(
+        &self,
+        index: usize,
+        reader: &mut RecordReader,
+        depth: usize,
+    ) -> Result<(), InterceptError> {
+        check_depth(depth)?;
+        match &self.types[index] {
+            AvroType::Null => {}
+            AvroType::Boolean => {
+                reader.read_bytes(1)?;
+            }
+            AvroType::Int | AvroType::Long | AvroType::Enum => {
+                reader.read_zigzag()?;
+            }
+            AvroType::Float => {
+                reader.read_bytes(4)?;
+            }
+            AvroType::Double => {
+                reader.read_bytes(8)?;
+            }
+            AvroType::Bytes | AvroType::String => {
+                read_length_delimited(reader)?;
+            }
+            AvroType::Fixed(size) => {
+                reader.read_bytes(*size)?;
+            }
+            AvroType::Record(fields) => {
+                for (_, field_index) in fields {
+                    self.skip_value(*field_index, reader, depth + 1)?;
+                }
+            }
+            AvroType::Union(branches) => {
+                let branch = reader.read_zigzag()?;
+                let branch_index = usize::try_from(branch)
+                    .ok()
+                    .and_then(|b| branches.get(b))
+                    .ok_or(InterceptError::InvalidData)?;
+                self.skip_value(*branch_index, reader, depth + 1)?;
+            }
+            AvroType::Array(items) | AvroType::Map(items) => {
+                let is_map = matches!(&self.types[index], AvroType::Map(_));
+                // arrays and maps are encoded as a series of blocks, terminated by an empty block.
+                // A negative count is followed by the size of the block in bytes
+                loop {
+                    let count = reader.read_zigzag()?;
+                    if count == 0 {
+                        break;
+                    }
+                    if count < 0 {
+                        let size = reader.read_zigzag()?;
+                        let size =
+                            usize::try_from(size).map_err(|_| InterceptError::InvalidData)?;
+                        reader.read_bytes(size)?;
+                        continue;
+                    }
+                    // each item consumes at least one byte, otherwise all the items are encoded
+                    // with no bytes and they are skipped at once. So the number of iterations is
+                    // bounded by the length of the record, whatever the count is
+                    for _ in 0..count {
+                        let start = reader.position();
+                        if is_map {
+                            read_length_delimited(reader)?;
+                        }
+                        self.skip_value(*items, reader, depth + 1)?;
+                        if reader.position() == start {
+                            break;
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct AvroSchemaParser {
+    types: Vec<AvroType>,
+    named_types: HashMap<String, usize>,
+}
+
+impl AvroSchemaParser {
+    /// Parse a schema and return the index of its type
+    fn parse(&mut self, json: &Value, namespace: Option<&str>) -> Result<usize, String> {
+        match json {
+            Value::String(name) => match Self::primitive(name) {
+                Some(primitive) => Ok(self.add(primitive)),
+                None => self.named_reference(name, namespace),
+            },
+            Value::Array(branches) => {
+                let branches = branches
+                    .iter()
+                    .map(|branch| self.parse(branch, namespace))
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok(self.add(AvroType::Union(branches)))
+            }
+            Value::Object(object) => {
+                let type_name = match object.get("type") {
+                    Some(Value::String(type_name)) => type_name.as_str(),
+                    Some(other) => return self.parse(other, namespace),
+                    None => return Err("missing type attribute".to_string()),
+                };
+                match type_name {
+                    "record" | "error" => {
+                        let (full_name, namespace) = Self::full_name(object, namespace)?;
+                        // the record is registered before its fields, since they can reference it
+                        let index = self.add(AvroType::Record(vec![]));
+                        self.register(full_name, index)?;
+                        let fields = object
+                            .get("fields")
+                            .and_then(Value::as_array)
+                            .ok_or("missing record fields")?;
+                        let mut parsed_fields = vec![];
+                        for field in fields {
+                            let name = field
+                                .get("name")
+                                .and_then(Value::as_str)
+                                .ok_or("missing field name")?;
+                            let field_type = field.get("type").ok_or("missing field type")?;
+                            let field_index = self.parse(field_type, namespace.as_deref())?;
+                            parsed_fields.push((name.to_string(), field_index));
+                        }
+                        self.types[index] = AvroType::Record(parsed_fields);
+                        Ok(index)
+                    }
+                    "enum" => {
+                        let (full_name, _) = Self::full_name(object, namespace)?;
+                        let index = self.add(AvroType::Enum);
+                        self.register(full_name, index)?;
+                        Ok(index)
+                    }
+                    "fixed" => {
+                        let (full_name, _) = Self::full_name(object, namespace)?;
+                        let size = object
+                            .get("size")
+                            .and_then(Value::as_u64)
+                            .ok_or("missing fixed size")?;
+                        let index = self.add(AvroType::Fixed(size as usize));
+                        self.register(full_name, index)?;
+                        Ok(index)
+                    }
+                    "array" => {
+                        let items = object.get("items").ok_or("missing array items")?;
+                        let items = self.parse(items, namespace)?;
+                        Ok(self.add(AvroType::Array(items)))
+                    }
+                    "map" => {
+                        let values = object.get("values").ok_or("missing map values")?;
+                        let values = self.parse(values, namespace)?;
+                        Ok(self.add(AvroType::Map(values)))
+                    }
+                    other => match Self::primitive(other) {
+                        // a primitive type with attributes, for example a logical type
+                        Some(primitive) => Ok(self.add(primitive)),
+                        None => self.named_reference(other, namespace),
+                    },
+                }
+            }
+            _ => Err(format!("unexpected schema {json}")),
+        }
+    }
+
+    fn primitive(name: &str) -> Option<AvroType> {
+        Some(match name {
+            "null" => AvroType::Null,
+            "boolean" => AvroType::Boolean,
+            "int" => AvroType::Int,
+            "long" => AvroType::Long,
+            "float" => AvroType::Float,
+            "double" => AvroType::Double,
+            "bytes" => AvroType::Bytes,
+            "string" => AvroType::String,
+            _ => return None,
+        })
+    }
+
+    fn add(&mut self, avro_type: AvroType) -> usize {
+        self.types.push(avro_type);
+        self.types.len() - 1
+    }
+
+    fn register(&mut self, full_name: String, index: usize) -> Result<(), String> {
+        if self.named_types.insert(full_name.clone(), index).is_some() {
+            return Err(format!("the type {full_name} is defined twice"));
+        }
+        Ok(())
+    }
+
+    fn named_reference(&self, name: &str, namespace: Option<&str>) -> Result<usize, String> {
+        let qualified = match namespace {
+            Some(namespace) if !name.contains('.') && !namespace.is_empty() => {
+                self.named_types.get(&format!("{namespace}.{name}"))
+            }
+            _ => None,
+        };
+        qualified
+            .or_else(|| self.named_types.get(name))
+            .copied()
+            .ok_or_else(|| format!("unknown type {name}"))
+    }
+
+    /// Return the full name of a named type and its namespace
+    fn full_name(
+        object: &serde_json::Map<String, Value>,
+        namespace: Option<&str>,
+    ) -> Result<(String, Option<String>), String> {
+        let name = object
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or("missing type name")?;
+        if let Some((type_namespace, _)) = name.rsplit_once('.') {
+            return Ok((name.to_string(), Some(type_namespace.to_string())));
+        }
+        let namespace = object
+            .get("namespace")
+            .and_then(Value::as_str)
+            .or(namespace)
+            .filter(|namespace| !namespace.is_empty());
+        Ok(match namespace {
+            Some(namespace) => (format!("{namespace}.{name}"), Some(namespace.to_string())),
+            None => (name.to_string(), None),
+        })
+    }
+}
+
+/// Reject the values nested too deeply
+fn check_depth(depth: usize) -> Result<(), InterceptError> {
+    if depth > MAX_NESTING_DEPTH {
+        warn!("the Avro record nests values too deeply");
+        return Err(InterceptError::InvalidData);
+    }
+    Ok(())
+}
+
+fn read_length_delimited<'a>(reader: &mut RecordReader<'a>) -> Result<&'a [u8], InterceptError> {
+    let length = usize::try_from(reader.read_zigzag()?).map_err(|_| InterceptError::InvalidData)?;
+    reader.read_bytes(length)
+}
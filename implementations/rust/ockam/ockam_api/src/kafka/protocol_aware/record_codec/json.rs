@@ -0,0 +1,73 @@
+use crate::kafka::protocol_aware::record_codec::{FieldMapper, FieldTransformation, RecordCodec};
+use crate::kafka::protocol_aware::InterceptError;
+
+/// Codec for records which are JSON objects.
+/// The encrypted fields are top-level keys and any JSON value can be encrypted:
+/// it is replaced by the hex encoding of its encrypted content.
+pub(crate) struct JsonRecordCodec {
+    fields: Vec<String>,
+}
+
+impl JsonRecordCodec {
+    pub(crate) fn new(fields: Vec<String>) -> Self {
+        Self { fields }
+    }
+}
+
+impl RecordCodec for JsonRecordCodec {
+    fn map_fields(
+        &self,
+        record: &[u8],
+        transformation: FieldTransformation,
+        map: &mut FieldMapper<'_>,
+    ) -> Result<Vec<u8>, InterceptError> {
+        let mut record_value = serde_json::from_slice::<serde_json::Value>(record)?;
+
+        if let serde_json::Value::Object(fields) = &mut record_value {
+            for field in &self.fields {
+                if let Some(value) = fields.get_mut(field) {
+                    match transformation {
+                        FieldTransformation::Encrypt => {
+                            let content = serde_json::to_vec(value)
+                                .map_err(|_| InterceptError::InvalidData)?;
+                            if let Some(encrypted) = map(content)? {
+                                *value = serde_json::Value::String(hex::encode(&encrypted));
+                            }
+                        }
+                        FieldTransformation::Decrypt => {
+                            // when the encrypted field is present is expected to be a hex encoded string
+                            // wrapped by the KafkaEncryptedContent struct
+                            let encrypted = if let serde_json::Value::String(string) = value {
+                                hex::decode(string)
+                                    .map_err(|_| "Encrypted is not a valid hex string")?
+                            } else {
+                                error!("encrypted field is not a hex string");
+                                return Err(
+                                    "The encrypted field is not a hex-encoded string".into()
+                                );
+                            };
+                            if let Some(decrypted) = map(encrypted)? {
+                                *value = serde_json::from_slice(decrypted.as_slice())?;
+                            }
+                        }
+                    }
+                }
+            }
+            serde_json::to_vec(&record_value).map_err(|error| {
+                error!("cannot serialize the record fields");
+                error.into()
+            })
+        } else {
+            match transformation {
+                FieldTransformation::Encrypt => {
+                    warn!("only JSON objects are supported for field encryption");
+                    Err("Only JSON objects are supported".into())
+                }
+                FieldTransformation::Decrypt => {
+                    error!("cannot decrypt specific fields, expected a JSON object but got a different type");
+                    Err("Only JSON objects are supported in the message".into())
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,231 @@
+//! Codecs locating the fields to encrypt or decrypt inside kafka records.
+//!
+//! A codec walks a record with the help of its schema, hands the content of each selected
+//! field to a mapping function, and writes the result back so that the record stays valid
+//! for its schema.
+
+mod avro;
+mod json;
+mod protobuf;
+#[cfg(test)]
+mod tests;
+
+pub(crate) use avro::AvroRecordCodec;
+pub(crate) use json::JsonRecordCodec;
+pub(crate) use protobuf::ProtobufRecordCodec;
+
+use crate::kafka::protocol_aware::InterceptError;
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Format of the kafka records when only some of their fields are encrypted
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum KafkaRecordFormat {
+    /// Each record is a JSON object and the fields are its top-level keys
+    #[n(0)] #[default] Json,
+    /// Each record uses the Confluent wire format: a magic byte, a 4 bytes schema id and
+    /// the Avro binary encoding of the value
+    #[n(1)] Avro {
+        /// Avro schemas (JSON), indexed by their schema registry id
+        #[n(0)] schemas: BTreeMap<u32, String>,
+    },
+    /// Each record is a Protobuf message, optionally prefixed with the Confluent wire format header
+    #[n(2)] Protobuf {
+        /// Serialized `FileDescriptorSet`, as produced by `protoc --descriptor_set_out`
+        #[cbor(with = "minicbor::bytes")]
+        #[n(0)] descriptor_set: Vec<u8>,
+        /// Fully qualified name of the records message type, e.g. `my.package.Order`
+        #[n(1)] message_type: String,
+    },
+}
+
+impl KafkaRecordFormat {
+    /// Create a codec for the given fields. This fails if the schemas can't be parsed
+    pub(crate) fn codec(&self, fields: Vec<String>) -> ockam_core::Result<Arc<dyn RecordCodec>> {
+        Ok(match self {
+            KafkaRecordFormat::Json => Arc::new(JsonRecordCodec::new(fields)),
+            KafkaRecordFormat::Avro { schemas } => {
+                Arc::new(AvroRecordCodec::new(schemas, &fields)?)
+            }
+            KafkaRecordFormat::Protobuf {
+                descriptor_set,
+                message_type,
+            } => Arc::new(ProtobufRecordCodec::new(
+                descriptor_set,
+                message_type,
+                &fields,
+            )?),
+        })
+    }
+}
+
+/// Whether the fields of a record are being encrypted or decrypted
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FieldTransformation {
+    Encrypt,
+    Decrypt,
+}
+
+/// Function called with the content of each field: the plain content when encrypting, and
+/// the encoded `KafkaEncryptedContent` when decrypting.
+/// It returns the new content of the field, or `None` to leave the field unchanged.
+pub(crate) type FieldMapper<'a> =
+    dyn FnMut(Vec<u8>) -> Result<Option<Vec<u8>>, InterceptError> + 'a;
+
+/// A codec for a specific record format
+pub(crate) trait RecordCodec: Send + Sync + 'static {
+    /// Call `map` on each configured field of the record, in order, and return the new record
+    fn map_fields(
+        &self,
+        record: &[u8],
+        transformation: FieldTransformation,
+        map: &mut FieldMapper<'_>,
+    ) -> Result<Vec<u8>, InterceptError>;
+}
+
+/// Tree of dot-separated field paths, e.g. `customer.address.street`
+#[derive(Debug, Default)]
+pub(crate) struct FieldPaths {
+    children: BTreeMap<String, FieldPaths>,
+    is_target: bool,
+}
+
+impl FieldPaths {
+    pub(crate) fn new(fields: &[String]) -> Self {
+        let mut root = FieldPaths::default();
+        for field in fields {
+            let mut node = &mut root;
+            for segment in field.split('.') {
+                node = node.children.entry(segment.to_string()).or_default();
+            }
+            node.is_target = true;
+        }
+        root
+    }
+
+    /// Return the paths below the given field, if that field contains any target
+    pub(crate) fn child(&self, name: &str) -> Option<&FieldPaths> {
+        self.children.get(name)
+    }
+
+    /// Return true if this field must be transformed
+    pub(crate) fn is_target(&self) -> bool {
+        self.is_target
+    }
+}
+
+/// Type of a field which can be encrypted in a schema-based record
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FieldKind {
+    /// Encrypted content is hex-encoded so that the field remains valid UTF-8
+    String,
+    /// Encrypted content is stored as is
+    Bytes,
+}
+
+/// Apply the mapping function to the content of a string or bytes field.
+/// Return the new content of the field, or `None` if it must be left unchanged
+pub(crate) fn map_field_content(
+    kind: FieldKind,
+    transformation: FieldTransformation,
+    content: &[u8],
+    map: &mut FieldMapper<'_>,
+) -> Result<Option<Vec<u8>>, InterceptError> {
+    match transformation {
+        FieldTransformation::Encrypt => Ok(map(content.to_vec())?.map(|encrypted| match kind {
+            FieldKind::String => hex::encode(encrypted).into_bytes(),
+            FieldKind::Bytes => encrypted,
+        })),
+        FieldTransformation::Decrypt => {
+            let encrypted = match kind {
+                FieldKind::String => hex::decode(content)
+                    .map_err(|_| "The encrypted field is not a hex-encoded string")?,
+                FieldKind::Bytes => content.to_vec(),
+            };
+            let decrypted = map(encrypted)?;
+            if let Some(decrypted) = &decrypted {
+                if kind == FieldKind::String && std::str::from_utf8(decrypted).is_err() {
+                    return Err("The decrypted field is not a valid string".into());
+                }
+            }
+            Ok(decrypted)
+        }
+    }
+}
+
+/// Cursor over the binary encoding of a record
+pub(crate) struct RecordReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> RecordReader<'a> {
+    pub(crate) fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    pub(crate) fn position(&self) -> usize {
+        self.position
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.position >= self.data.len()
+    }
+
+    /// Return the bytes between a previous position and the current one
+    pub(crate) fn since(&self, start: usize) -> &'a [u8] {
+        &self.data[start..self.position]
+    }
+
+    pub(crate) fn read_bytes(&mut self, length: usize) -> Result<&'a [u8], InterceptError> {
+        let end = self
+            .position
+            .checked_add(length)
+            .filter(|end| *end <= self.data.len())
+            .ok_or(InterceptError::InvalidData)?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Read an unsigned LEB128 varint
+    pub(crate) fn read_varint(&mut self) -> Result<u64, InterceptError> {
+        let mut value = 0u64;
+        for shift in (0..64).step_by(7) {
+            let byte = self.read_bytes(1)?[0];
+            value |= ((byte & 0x7f) as u64) << shift;
+            if byte & 0x80 == 0 {
+                return Ok(value);
+            }
+        }
+        Err(InterceptError::InvalidData)
+    }
+
+    /// Read a zigzag-encoded signed varint
+    pub(crate) fn read_zigzag(&mut self) -> Result<i64, InterceptError> {
+        let value = self.read_varint()?;
+        Ok((value >> 1) as i64 ^ -((value & 1) as i64))
+    }
+}
+
+/// Write an unsigned LEB128 varint
+pub(crate) fn write_varint(output: &mut Vec<u8>, mut value: u64) {
+    while value >= 0x80 {
+        output.push((value as u8) | 0x80);
+        value >>= 7;
+    }
+    output.push(value as u8);
+}
+
+/// Write a zigzag-encoded signed varint
+pub(crate) fn write_zigzag(output: &mut Vec<u8>, value: i64) {
+    write_varint(output, ((value << 1) ^ (value >> 63)) as u64)
+}
+
+/// Error returned when a field which is neither a string nor bytes must be encrypted
+pub(crate) fn unsupported_field_type() -> InterceptError {
+    warn!("only string and bytes fields can be encrypted");
+    "Only string and bytes fields can be encrypted".into()
+}
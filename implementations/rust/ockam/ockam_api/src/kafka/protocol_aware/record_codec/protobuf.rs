@@ -0,0 +1,397 @@
+use crate::kafka::protocol_aware::record_codec::{
+    map_field_content, unsupported_field_type, write_varint, FieldKind, FieldMapper, FieldPaths,
+    FieldTransformation, RecordCodec, RecordReader,
+};
+use crate::kafka::protocol_aware::InterceptError;
+use crate::ApiError;
+use ockam_core::compat::collections::{BTreeMap, HashMap};
+
+const WIRE_TYPE_VARINT: u64 = 0;
+const WIRE_TYPE_FIXED64: u64 = 1;
+const WIRE_TYPE_LENGTH_DELIMITED: u64 = 2;
+const WIRE_TYPE_FIXED32: u64 = 5;
+
+// field types, as defined in FieldDescriptorProto.Type
+const TYPE_STRING: u64 = 9;
+const TYPE_MESSAGE: u64 = 11;
+const TYPE_BYTES: u64 = 12;
+
+/// Maximum number of message indexes accepted in a Confluent wire format header
+const MAX_MESSAGE_INDEXES: i64 = 32;
+
+/// Codec for Protobuf records.
+/// The records can be prefixed with the Confluent wire format header, in which case the header
+/// is kept as is. The message indexes of the header must designate the configured message type
+/// in its file, the records of other message types are rejected.
+/// Only string and bytes fields can be encrypted, nested messages are traversed with dotted paths.
+pub(crate) struct ProtobufRecordCodec {
+    messages: ProtobufMessages,
+    message_type: String,
+    /// Indexes of the message type in its file, as written in the Confluent wire format header
+    message_indexes: Vec<i64>,
+    paths: FieldPaths,
+}
+
+impl ProtobufRecordCodec {
+    pub(crate) fn new(
+        descriptor_set: &[u8],
+        message_type: &str,
+        fields: &[String],
+    ) -> ockam_core::Result<Self> {
+        let (messages, files) = parse_file_descriptor_set(descriptor_set).map_err(|_| {
+            ApiError::core("The Protobuf descriptor set is not a valid FileDescriptorSet")
+        })?;
+        let message_type = if message_type.starts_with('.') {
+            message_type.to_string()
+        } else {
+            format!(".{message_type}")
+        };
+        let Some(message_indexes) = find_message_indexes(&messages, &files, &message_type) else {
+            return Err(ApiError::core(format!(
+                "The message type {} can't be found in the Protobuf descriptor set",
+                &message_type[1..]
+            )));
+        };
+        Ok(Self {
+            messages,
+            message_type,
+            message_indexes,
+            paths: FieldPaths::new(fields),
+        })
+    }
+
+    /// Copy a message to the output, transforming the fields selected by `paths`
+    fn map_message(
+        &self,
+        message_type: &str,
+        paths: &FieldPaths,
+        reader: &mut RecordReader,
+        output: &mut Vec<u8>,
+        transformation: FieldTransformation,
+        map: &mut FieldMapper<'_>,
+    ) -> Result<(), InterceptError> {
+        let message = self
+            .messages
+            .get(message_type)
+            .ok_or(InterceptError::InvalidData)?;
+
+        while !reader.is_empty() {
+            let tag = reader.read_varint()?;
+            write_varint(output, tag);
+            let wire_type = tag & 0x07;
+
+            let field = message.fields.get(&(tag >> 3));
+            let field_paths = field.and_then(|field| paths.child(&field.name));
+            let (field, field_paths) = match (field, field_paths) {
+                (Some(field), Some(field_paths)) => (field, field_paths),
+                _ => {
+                    let start = reader.position();
+                    skip_field(wire_type, reader)?;
+                    output.extend_from_slice(reader.since(start));
+                    continue;
+                }
+            };
+
+            match &field.field_type {
+                ProtobufFieldType::String | ProtobufFieldType::Bytes
+                    if field_paths.is_target() && wire_type == WIRE_TYPE_LENGTH_DELIMITED =>
+                {
+                    let kind = match field.field_type {
+                        ProtobufFieldType::String => FieldKind::String,
+                        _ => FieldKind::Bytes,
+                    };
+                    let content = read_length_delimited(reader)?;
+                    let content = match map_field_content(kind, transformation, content, map)? {
+                        Some(new_content) => new_content,
+                        None => content.to_vec(),
+                    };
+                    write_varint(output, content.len() as u64);
+                    output.extend_from_slice(&content);
+                }
+                ProtobufFieldType::Message(type_name)
+                    if !field_paths.is_target() && wire_type == WIRE_TYPE_LENGTH_DELIMITED =>
+                {
+                    let content = read_length_delimited(reader)?;
+                    let mut nested = Vec::with_capacity(content.len());
+                    self.map_message(
+                        type_name,
+                        field_paths,
+                        &mut RecordReader::new(content),
+                        &mut nested,
+                        transformation,
+                        map,
+                    )?;
+                    write_varint(output, nested.len() as u64);
+                    output.extend_from_slice(&nested);
+                }
+                _ if field_paths.is_target() => return Err(unsupported_field_type()),
+                _ => {
+                    warn!("the encrypted fields don't match the Protobuf message type");
+                    return Err("The encrypted fields don't match the Protobuf message type".into());
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl RecordCodec for ProtobufRecordCodec {
+    fn map_fields(
+        &self,
+        record: &[u8],
+        transformation: FieldTransformation,
+        map: &mut FieldMapper<'_>,
+    ) -> Result<Vec<u8>, InterceptError> {
+        let mut reader = RecordReader::new(record);
+        let mut output = Vec::with_capacity(record.len());
+
+        // a field tag can't be 0, so a leading 0 is the Confluent magic byte. It is followed
+        // by the schema id, and the indexes of the message type in the schema
+        if record.first() == Some(&0) {
+            reader.read_bytes(5)?;
+            let message_indexes = read_message_indexes(&mut reader)?;
+            if message_indexes != self.message_indexes {
+                warn!(
+                    "the Protobuf message indexes {message_indexes:?} don't match the message type"
+                );
+                return Err("The Protobuf message indexes don't match the message type".into());
+            }
+            output.extend_from_slice(reader.since(0));
+        }
+
+        self.map_message(
+            &self.message_type,
+            &self.paths,
+            &mut reader,
+            &mut output,
+            transformation,
+            map,
+        )?;
+        Ok(output)
+    }
+}
+
+/// Read the message indexes of a Confluent wire format header.
+/// A count of 0 is a shortcut for the first message type of the schema
+fn read_message_indexes(reader: &mut RecordReader) -> Result<Vec<i64>, InterceptError> {
+    let count = reader.read_zigzag()?;
+    if count == 0 {
+        return Ok(vec![0]);
+    }
+    if !(1..=MAX_MESSAGE_INDEXES).contains(&count) {
+        return Err(InterceptError::InvalidData);
+    }
+    (0..count).map(|_| reader.read_zigzag()).collect()
+}
+
+/// Return the indexes of a message type in its file: the index of the top-level message type,
+/// followed by the index of each nested type leading to the message type
+fn find_message_indexes(
+    messages: &ProtobufMessages,
+    files: &[Vec<String>],
+    message_type: &str,
+) -> Option<Vec<i64>> {
+    fn find(
+        messages: &ProtobufMessages,
+        candidates: &[String],
+        message_type: &str,
+    ) -> Option<Vec<i64>> {
+        candidates
+            .iter()
+            .enumerate()
+            .find_map(|(index, candidate)| {
+                let mut indexes = if candidate == message_type {
+                    vec![]
+                } else {
+                    let nested_types = &messages.get(candidate)?.nested_types;
+                    find(messages, nested_types, message_type)?
+                };
+                indexes.insert(0, index as i64);
+                Some(indexes)
+            })
+    }
+
+    files
+        .iter()
+        .find_map(|message_types| find(messages, message_types, message_type))
+}
+
+/// Message types indexed by their fully qualified name
+type ProtobufMessages = HashMap<String, ProtobufMessage>;
+
+#[derive(Debug)]
+struct ProtobufMessage {
+    fields: BTreeMap<u64, ProtobufField>,
+    /// Fully qualified names of the nested message types, in their declaration order
+    nested_types: Vec<String>,
+}
+
+#[derive(Debug)]
+struct ProtobufField {
+    name: String,
+    field_type: ProtobufFieldType,
+}
+
+#[derive(Debug)]
+enum ProtobufFieldType {
+    String,
+    Bytes,
+    /// Fully qualified name of the message type
+    Message(String),
+    Other,
+}
+
+/// Return all the message types of a FileDescriptorSet, indexed by their fully qualified name,
+/// and the names of the top-level message types of each file, in their declaration order
+fn parse_file_descriptor_set(
+    descriptor_set: &[u8],
+) -> Result<(ProtobufMessages, Vec<Vec<String>>), InterceptError> {
+    let mut messages = HashMap::new();
+    let mut files = vec![];
+    // FileDescriptorSet: repeated FileDescriptorProto file = 1
+    for (number, value) in read_fields(descriptor_set)? {
+        if let (1, FieldValue::LengthDelimited(file)) = (number, value) {
+            let mut package = String::new();
+            let mut message_types = vec![];
+            // FileDescriptorProto: string package = 2, repeated DescriptorProto message_type = 4
+            for (number, value) in read_fields(file)? {
+                match (number, value) {
+                    (2, FieldValue::LengthDelimited(value)) => package = read_string(value)?,
+                    (4, FieldValue::LengthDelimited(value)) => message_types.push(value),
+                    _ => {}
+                }
+            }
+            let scope = if package.is_empty() {
+                String::new()
+            } else {
+                format!(".{package}")
+            };
+            let mut file_message_types = vec![];
+            for message_type in message_types {
+                file_message_types.push(parse_descriptor(message_type, &scope, &mut messages)?);
+            }
+            files.push(file_message_types);
+        }
+    }
+    Ok((messages, files))
+}
+
+/// Parse a DescriptorProto and its nested types, and return its fully qualified name
+fn parse_descriptor(
+    descriptor: &[u8],
+    scope: &str,
+    messages: &mut ProtobufMessages,
+) -> Result<String, InterceptError> {
+    let mut name = String::new();
+    let mut fields = BTreeMap::new();
+    let mut nested_types = vec![];
+    // DescriptorProto: string name = 1, repeated FieldDescriptorProto field = 2,
+    // repeated DescriptorProto nested_type = 3
+    for (number, value) in read_fields(descriptor)? {
+        match (number, value) {
+            (1, FieldValue::LengthDelimited(value)) => name = read_string(value)?,
+            (2, FieldValue::LengthDelimited(value)) => {
+                let (number, field) = parse_field_descriptor(value)?;
+                fields.insert(number, field);
+            }
+            (3, FieldValue::LengthDelimited(value)) => nested_types.push(value),
+            _ => {}
+        }
+    }
+    let full_name = format!("{scope}.{name}");
+    let nested_types = nested_types
+        .into_iter()
+        .map(|nested_type| parse_descriptor(nested_type, &full_name, messages))
+        .collect::<Result<Vec<_>, _>>()?;
+    messages.insert(
+        full_name.clone(),
+        ProtobufMessage {
+            fields,
+            nested_types,
+        },
+    );
+    Ok(full_name)
+}
+
+/// Parse a FieldDescriptorProto: string name = 1, int32 number = 3, Type type = 5,
+/// string type_name = 6
+fn parse_field_descriptor(descriptor: &[u8]) -> Result<(u64, ProtobufField), InterceptError> {
+    let mut name = String::new();
+    let mut number = 0;
+    let mut field_type = 0;
+    let mut type_name = String::new();
+    for (field_number, value) in read_fields(descriptor)? {
+        match (field_number, value) {
+            (1, FieldValue::LengthDelimited(value)) => name = read_string(value)?,
+            (3, FieldValue::Varint(value)) => number = value,
+            (5, FieldValue::Varint(value)) => field_type = value,
+            (6, FieldValue::LengthDelimited(value)) => type_name = read_string(value)?,
+            _ => {}
+        }
+    }
+    let field_type = match field_type {
+        TYPE_STRING => ProtobufFieldType::String,
+        TYPE_BYTES => ProtobufFieldType::Bytes,
+        TYPE_MESSAGE => ProtobufFieldType::Message(type_name),
+        _ => ProtobufFieldType::Other,
+    };
+    Ok((number, ProtobufField { name, field_type }))
+}
+
+enum FieldValue<'a> {
+    Varint(u64),
+    LengthDelimited(&'a [u8]),
+    Fixed,
+}
+
+/// Read all the fields of a message
+fn read_fields(message: &[u8]) -> Result<Vec<(u64, FieldValue<'_>)>, InterceptError> {
+    let mut reader = RecordReader::new(message);
+    let mut fields = vec![];
+    while !reader.is_empty() {
+        let tag = reader.read_varint()?;
+        let value = match tag & 0x07 {
+            WIRE_TYPE_VARINT => FieldValue::Varint(reader.read_varint()?),
+            WIRE_TYPE_LENGTH_DELIMITED => {
+                FieldValue::LengthDelimited(read_length_delimited(&mut reader)?)
+            }
+            wire_type => {
+                skip_field(wire_type, &mut reader)?;
+                FieldValue::Fixed
+            }
+        };
+        fields.push((tag >> 3, value));
+    }
+    Ok(fields)
+}
+
+fn skip_field(wire_type: u64, reader: &mut RecordReader) -> Result<(), InterceptError> {
+    match wire_type {
+        WIRE_TYPE_VARINT => {
+            reader.read_varint()?;
+        }
+        WIRE_TYPE_FIXED64 => {
+            reader.read_bytes(8)?;
+        }
+        WIRE_TYPE_LENGTH_DELIMITED => {
+            read_length_delimited(reader)?;
+        }
+        WIRE_TYPE_FIXED32 => {
+            reader.read_bytes(4)?;
+        }
+        _ => {
+            warn!("unsupported Protobuf wire type {wire_type}");
+            return Err(InterceptError::InvalidData);
+        }
+    }
+    Ok(())
+}
+
+fn read_length_delimited<'a>(reader: &mut RecordReader<'a>) -> Result<&'a [u8], InterceptError> {
+    let length = usize::try_from(reader.read_varint()?).map_err(|_| InterceptError::InvalidData)?;
+    reader.read_bytes(length)
+}
+
+fn read_string(value: &[u8]) -> Result<String, InterceptError> {
+    String::from_utf8(value.to_vec()).map_err(|_| InterceptError::InvalidData)
+}
@@ -0,0 +1,414 @@
+use crate::kafka::protocol_aware::record_codec::{
+    write_varint, write_zigzag, FieldTransformation, KafkaRecordFormat, RecordCodec,
+};
+use crate::kafka::protocol_aware::InterceptError;
+use ockam_core::compat::collections::BTreeMap;
+use std::sync::Arc;
+
+const ENCRYPTED_PREFIX: &[u8] = b"encrypted:";
+
+const AVRO_SCHEMA: &str = r#"{
+  "type": "record",
+  "name": "User",
+  "namespace": "shop",
+  "fields": [
+    { "name": "id", "type": "long" },
+    { "name": "name", "type": "string" },
+    { "name": "email", "type": ["null", "string"] },
+    { "name": "tags", "type": { "type": "array", "items": "string" } },
+    {
+      "name": "address",
+      "type": {
+        "type": "record",
+        "name": "Address",
+        "fields": [
+          { "name": "street", "type": "string" },
+          { "name": "zip", "type": "int" }
+        ]
+      }
+    },
+    { "name": "previous_address", "type": ["null", "Address"] },
+    { "name": "avatar", "type": "bytes" }
+  ]
+}"#;
+
+/// Encrypt the fields of a record with a fake encryption, check the content passed
+/// to the encryption, and decrypt the record back
+fn encrypt_and_decrypt(
+    codec: &dyn RecordCodec,
+    record: &[u8],
+    expected_fields: Vec<&[u8]>,
+) -> Result<Vec<u8>, InterceptError> {
+    let mut fields = vec![];
+    let encrypted = codec.map_fields(record, FieldTransformation::Encrypt, &mut |content| {
+        fields.push(content.clone());
+        Ok(Some([ENCRYPTED_PREFIX, &content].concat()))
+    })?;
+    assert_eq!(fields, expected_fields);
+    assert_ne!(encrypted, record);
+
+    let decrypted = codec.map_fields(&encrypted, FieldTransformation::Decrypt, &mut |content| {
+        assert!(content.starts_with(ENCRYPTED_PREFIX));
+        Ok(Some(content[ENCRYPTED_PREFIX.len()..].to_vec()))
+    })?;
+    assert_eq!(decrypted, record);
+
+    // the record is left unchanged when the fields are only collected
+    let collected = codec.map_fields(record, FieldTransformation::Encrypt, &mut |_| Ok(None))?;
+    assert_eq!(collected, record);
+    Ok(encrypted)
+}
+
+fn avro_codec(fields: &[&str]) -> ockam_core::Result<Arc<dyn RecordCodec>> {
+    KafkaRecordFormat::Avro {
+        schemas: BTreeMap::from([(7, AVRO_SCHEMA.to_string())]),
+    }
+    .codec(fields.iter().map(|f| f.to_string()).collect())
+}
+
+fn avro_string(output: &mut Vec<u8>, value: &[u8]) {
+    write_zigzag(output, value.len() as i64);
+    output.extend_from_slice(value);
+}
+
+fn avro_user_record(schema_id: u32) -> Vec<u8> {
+    let mut record = vec![0];
+    record.extend_from_slice(&schema_id.to_be_bytes());
+    // id
+    write_zigzag(&mut record, -42);
+    // name
+    avro_string(&mut record, b"alice");
+    // email: union branch 1 (string)
+    write_zigzag(&mut record, 1);
+    avro_string(&mut record, b"alice@example.com");
+    // tags: one block of 2 items, then the end of the array
+    write_zigzag(&mut record, 2);
+    avro_string(&mut record, b"a");
+    avro_string(&mut record, b"b");
+    write_zigzag(&mut record, 0);
+    // address
+    avro_string(&mut record, b"main street");
+    write_zigzag(&mut record, 75001);
+    // previous_address: union branch 0 (null)
+    write_zigzag(&mut record, 0);
+    // avatar
+    avro_string(&mut record, &[0xff, 0x00, 0x01]);
+    record
+}
+
+#[test]
+fn avro_encrypt_decrypt_fields() -> Result<(), InterceptError> {
+    let codec = avro_codec(&["name", "email", "address.street", "avatar"])?;
+    let record = avro_user_record(7);
+    let encrypted = encrypt_and_decrypt(
+        codec.as_ref(),
+        &record,
+        vec![
+            b"alice",
+            b"alice@example.com",
+            b"main street",
+            &[0xff, 0x00, 0x01],
+        ],
+    )?;
+
+    // the schema id is kept and the encrypted strings are hex-encoded
+    assert_eq!(&encrypted[..5], &record[..5]);
+    let mut expected_name = vec![];
+    avro_string(
+        &mut expected_name,
+        hex::encode([ENCRYPTED_PREFIX, b"alice"].concat()).as_bytes(),
+    );
+    assert_eq!(&encrypted[6..6 + expected_name.len()], expected_name);
+
+    // a null value in a union is left as is
+    let codec = avro_codec(&["previous_address.street"])?;
+    let encrypted = codec.map_fields(&record, FieldTransformation::Encrypt, &mut |_| {
+        panic!("there is no field to encrypt")
+    })?;
+    assert_eq!(encrypted, record);
+    Ok(())
+}
+
+#[test]
+fn avro_invalid_records() -> Result<(), InterceptError> {
+    let codec = avro_codec(&["name"])?;
+
+    // unknown schema id
+    let result = codec.map_fields(
+        &avro_user_record(8),
+        FieldTransformation::Encrypt,
+        &mut |c| Ok(Some(c)),
+    );
+    assert!(result.is_err());
+
+    // missing Confluent wire format header
+    let result = codec.map_fields(
+        &avro_user_record(7)[5..],
+        FieldTransformation::Encrypt,
+        &mut |c| Ok(Some(c)),
+    );
+    assert!(result.is_err());
+
+    // truncated record
+    let record = avro_user_record(7);
+    let result = codec.map_fields(
+        &record[..record.len() - 1],
+        FieldTransformation::Encrypt,
+        &mut |c| Ok(Some(c)),
+    );
+    assert!(result.is_err());
+
+    // only string and bytes fields can be encrypted
+    let codec = avro_codec(&["id"])?;
+    let result = codec.map_fields(&record, FieldTransformation::Encrypt, &mut |c| Ok(Some(c)));
+    assert!(result.is_err());
+
+    // the schema must be valid
+    assert!(KafkaRecordFormat::Avro {
+        schemas: BTreeMap::from([(1, r#"{"type": "record", "name": "Missing"}"#.to_string())]),
+    }
+    .codec(vec!["name".to_string()])
+    .is_err());
+    Ok(())
+}
+
+#[test]
+fn avro_deeply_nested_records() -> Result<(), InterceptError> {
+    let schema = r#"{
+      "type": "record",
+      "name": "Node",
+      "fields": [
+        { "name": "name", "type": "string" },
+        { "name": "next", "type": ["null", "Node"] }
+      ]
+    }"#;
+    let codec = KafkaRecordFormat::Avro {
+        schemas: BTreeMap::from([(1, schema.to_string())]),
+    }
+    .codec(vec!["name".to_string()])?;
+
+    let linked_nodes = |count: usize| {
+        let mut record = vec![0, 0, 0, 0, 1];
+        for n in 0..count {
+            avro_string(&mut record, b"node");
+            // the last node has no next node
+            write_zigzag(&mut record, if n + 1 < count { 1 } else { 0 });
+        }
+        record
+    };
+
+    let record = linked_nodes(10);
+    codec.map_fields(&record, FieldTransformation::Encrypt, &mut |c| Ok(Some(c)))?;
+
+    // the recursion on the nested records is bounded
+    let record = linked_nodes(1000);
+    let result = codec.map_fields(&record, FieldTransformation::Encrypt, &mut |c| Ok(Some(c)));
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test]
+fn avro_array_of_items_encoded_with_no_bytes() -> Result<(), InterceptError> {
+    let schema = r#"{
+      "type": "record",
+      "name": "Nulls",
+      "fields": [
+        { "name": "nulls", "type": { "type": "array", "items": "null" } },
+        { "name": "name", "type": "string" }
+      ]
+    }"#;
+    let codec = KafkaRecordFormat::Avro {
+        schemas: BTreeMap::from([(1, schema.to_string())]),
+    }
+    .codec(vec!["name".to_string()])?;
+
+    // a huge number of null items doesn't take a huge time to skip
+    let mut record = vec![0, 0, 0, 0, 1];
+    write_zigzag(&mut record, i64::MAX);
+    write_zigzag(&mut record, 0);
+    avro_string(&mut record, b"alice");
+    encrypt_and_decrypt(codec.as_ref(), &record, vec![b"alice"])?;
+    Ok(())
+}
+
+fn protobuf_bytes(output: &mut Vec<u8>, number: u64, value: &[u8]) {
+    write_varint(output, number << 3 | 2);
+    write_varint(output, value.len() as u64);
+    output.extend_from_slice(value);
+}
+
+fn protobuf_varint(output: &mut Vec<u8>, number: u64, value: u64) {
+    write_varint(output, number << 3);
+    write_varint(output, value);
+}
+
+fn protobuf_field_descriptor(name: &str, number: u64, field_type: u64, type_name: &str) -> Vec<u8> {
+    let mut field = vec![];
+    protobuf_bytes(&mut field, 1, name.as_bytes());
+    protobuf_varint(&mut field, 3, number);
+    protobuf_varint(&mut field, 5, field_type);
+    if !type_name.is_empty() {
+        protobuf_bytes(&mut field, 6, type_name.as_bytes());
+    }
+    field
+}
+
+/// Descriptor set for:
+///
+/// package shop;
+/// message Order {
+///   message Customer {
+///     string email = 1;
+///     string name = 2;
+///   }
+///   string id = 1;
+///   Customer customer = 2;
+///   bytes payload = 3;
+///   int64 amount = 4;
+/// }
+fn protobuf_descriptor_set() -> Vec<u8> {
+    let mut customer = vec![];
+    protobuf_bytes(&mut customer, 1, b"Customer");
+    protobuf_bytes(
+        &mut customer,
+        2,
+        &protobuf_field_descriptor("email", 1, 9, ""),
+    );
+    protobuf_bytes(
+        &mut customer,
+        2,
+        &protobuf_field_descriptor("name", 2, 9, ""),
+    );
+
+    let mut order = vec![];
+    protobuf_bytes(&mut order, 1, b"Order");
+    protobuf_bytes(&mut order, 2, &protobuf_field_descriptor("id", 1, 9, ""));
+    protobuf_bytes(
+        &mut order,
+        2,
+        &protobuf_field_descriptor("customer", 2, 11, ".shop.Order.Customer"),
+    );
+    protobuf_bytes(
+        &mut order,
+        2,
+        &protobuf_field_descriptor("payload", 3, 12, ""),
+    );
+    protobuf_bytes(
+        &mut order,
+        2,
+        &protobuf_field_descriptor("amount", 4, 3, ""),
+    );
+    protobuf_bytes(&mut order, 3, &customer);
+
+    let mut file = vec![];
+    protobuf_bytes(&mut file, 1, b"shop.proto");
+    protobuf_bytes(&mut file, 2, b"shop");
+    protobuf_bytes(&mut file, 4, &order);
+
+    let mut descriptor_set = vec![];
+    protobuf_bytes(&mut descriptor_set, 1, &file);
+    descriptor_set
+}
+
+fn protobuf_codec(fields: &[&str]) -> ockam_core::Result<Arc<dyn RecordCodec>> {
+    KafkaRecordFormat::Protobuf {
+        descriptor_set: protobuf_descriptor_set(),
+        message_type: "shop.Order".to_string(),
+    }
+    .codec(fields.iter().map(|f| f.to_string()).collect())
+}
+
+fn protobuf_order_record() -> Vec<u8> {
+    let mut customer = vec![];
+    protobuf_bytes(&mut customer, 1, b"bob@example.com");
+    protobuf_bytes(&mut customer, 2, b"bob");
+
+    let mut record = vec![];
+    protobuf_bytes(&mut record, 1, b"order-1");
+    protobuf_bytes(&mut record, 2, &customer);
+    protobuf_bytes(&mut record, 3, &[0xde, 0xad]);
+    protobuf_varint(&mut record, 4, 300);
+    // unknown fields are kept
+    protobuf_varint(&mut record, 15, 1);
+    record
+}
+
+#[test]
+fn protobuf_encrypt_decrypt_fields() -> Result<(), InterceptError> {
+    let codec = protobuf_codec(&["customer.email", "payload"])?;
+    let record = protobuf_order_record();
+    encrypt_and_decrypt(
+        codec.as_ref(),
+        &record,
+        vec![b"bob@example.com", &[0xde, 0xad]],
+    )?;
+
+    // with a Confluent wire format header: schema id 3, message indexes [0]
+    let mut confluent_record = vec![0, 0, 0, 0, 3, 0];
+    confluent_record.extend_from_slice(&record);
+    let encrypted = encrypt_and_decrypt(
+        codec.as_ref(),
+        &confluent_record,
+        vec![b"bob@example.com", &[0xde, 0xad]],
+    )?;
+    assert_eq!(&encrypted[..6], &confluent_record[..6]);
+
+    // the same header with the explicit message indexes [0]
+    let mut confluent_record = vec![0, 0, 0, 0, 3, 2, 0];
+    confluent_record.extend_from_slice(&record);
+    encrypt_and_decrypt(
+        codec.as_ref(),
+        &confluent_record,
+        vec![b"bob@example.com", &[0xde, 0xad]],
+    )?;
+    Ok(())
+}
+
+#[test]
+fn protobuf_message_indexes() -> Result<(), InterceptError> {
+    let codec = protobuf_codec(&["customer.email"])?;
+    let record = protobuf_order_record();
+
+    // records of another message type of the schema are rejected: [1], and [0, 0] for
+    // the Order.Customer nested type
+    for header in [vec![0, 0, 0, 0, 3, 2, 2], vec![0, 0, 0, 0, 3, 4, 0, 0]] {
+        let mut confluent_record = header;
+        confluent_record.extend_from_slice(&record);
+        let result = codec.map_fields(&confluent_record, FieldTransformation::Encrypt, &mut |c| {
+            Ok(Some(c))
+        });
+        assert!(result.is_err());
+    }
+
+    // the indexes of a nested message type
+    let codec = KafkaRecordFormat::Protobuf {
+        descriptor_set: protobuf_descriptor_set(),
+        message_type: "shop.Order.Customer".to_string(),
+    }
+    .codec(vec!["email".to_string()])?;
+    let mut customer = vec![0, 0, 0, 0, 3, 4, 0, 0];
+    protobuf_bytes(&mut customer, 1, b"bob@example.com");
+    encrypt_and_decrypt(codec.as_ref(), &customer, vec![b"bob@example.com"])?;
+    Ok(())
+}
+
+#[test]
+fn protobuf_invalid_configuration() -> Result<(), InterceptError> {
+    // only string and bytes fields can be encrypted
+    let codec = protobuf_codec(&["amount"])?;
+    let result = codec.map_fields(
+        &protobuf_order_record(),
+        FieldTransformation::Encrypt,
+        &mut |c| Ok(Some(c)),
+    );
+    assert!(result.is_err());
+
+    // the message type must be in the descriptor set
+    assert!(KafkaRecordFormat::Protobuf {
+        descriptor_set: protobuf_descriptor_set(),
+        message_type: "shop.Unknown".to_string(),
+    }
+    .codec(vec!["id".to_string()])
+    .is_err());
+    Ok(())
+}
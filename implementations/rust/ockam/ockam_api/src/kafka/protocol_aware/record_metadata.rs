@@ -0,0 +1,209 @@
+//! Encryption of the kafka records keys and headers.
+
+use crate::ApiError;
+use aes_gcm_siv::aead::{Aead, KeyInit, Payload};
+use aes_gcm_siv::{Aes256GcmSiv, Nonce};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::fmt::{Debug, Formatter};
+use std::sync::Arc;
+
+/// Length of the secret used to encrypt keys deterministically
+pub const DETERMINISTIC_KEY_SECRET_LENGTH: usize = 32;
+
+/// Additional data authenticated with the encrypted keys
+const DETERMINISTIC_KEY_AAD: &[u8] = b"ockam-kafka-key";
+
+/// How the records keys are encrypted
+#[derive(Clone, Default, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum KafkaKeyEncryption {
+    /// Keys are sent in clear text
+    #[n(0)] #[default] None,
+    /// Keys are encrypted like the records values: the same key is encrypted differently for
+    /// each record. Partitions are still selected by the producer, but topics compaction
+    /// can't work anymore
+    #[n(1)] Randomized,
+    /// The same key is always encrypted to the same value, with a secret shared by all the
+    /// producers and consumers of the topics. This keeps partitioning and compaction working
+    /// but reveals which records have the same key
+    #[n(2)] Deterministic {
+        #[cbor(with = "minicbor::bytes")]
+        #[n(0)] secret: Vec<u8>,
+    },
+}
+
+impl Debug for KafkaKeyEncryption {
+    fn fmt(&self, f: &mut Formatter<'_>) -> ockam_core::compat::fmt::Result {
+        match self {
+            KafkaKeyEncryption::None => f.write_str("None"),
+            KafkaKeyEncryption::Randomized => f.write_str("Randomized"),
+            KafkaKeyEncryption::Deterministic { .. } => f.write_str("Deterministic"),
+        }
+    }
+}
+
+impl KafkaKeyEncryption {
+    /// Create the encryptor used by the kafka interceptor, if keys must be encrypted
+    pub(crate) fn encryptor(&self) -> ockam_core::Result<Option<RecordKeyEncryptor>> {
+        Ok(match self {
+            KafkaKeyEncryption::None => None,
+            KafkaKeyEncryption::Randomized => Some(RecordKeyEncryptor::Randomized),
+            KafkaKeyEncryption::Deterministic { secret } => {
+                Some(RecordKeyEncryptor::Deterministic(Arc::new(
+                    DeterministicKeyEncryptor::create(secret)?,
+                )))
+            }
+        })
+    }
+}
+
+/// Which records headers are encrypted. Only the headers values are encrypted, not their names
+#[derive(Debug, Clone, Default, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum KafkaHeadersEncryption {
+    /// Headers are sent in clear text
+    #[n(0)] #[default] None,
+    /// Only the headers with these names are encrypted
+    #[n(1)] Selected(#[n(0)] Vec<String>),
+    /// All the headers are encrypted
+    #[n(2)] All,
+}
+
+impl KafkaHeadersEncryption {
+    /// Return true if the value of the header with this name must be encrypted
+    pub(crate) fn includes(&self, header_name: &str) -> bool {
+        match self {
+            KafkaHeadersEncryption::None => false,
+            KafkaHeadersEncryption::Selected(names) => names.iter().any(|n| n == header_name),
+            KafkaHeadersEncryption::All => true,
+        }
+    }
+}
+
+/// Encryption used by the kafka interceptor for the records keys
+#[derive(Clone)]
+pub(crate) enum RecordKeyEncryptor {
+    /// Keys are encrypted with the key exchange controller, like the records values
+    Randomized,
+    /// Keys are encrypted with a shared secret
+    Deterministic(Arc<DeterministicKeyEncryptor>),
+}
+
+/// Deterministic authenticated encryption with AES-256-GCM-SIV (RFC 8452).
+///
+/// AES-GCM-SIV is resistant to nonce reuse: when the same nonce is used for all the keys, the only
+/// information revealed is whether two encrypted keys have the same plain text, which is what
+/// deterministic encryption requires. The encrypted key is: ciphertext || tag
+pub(crate) struct DeterministicKeyEncryptor {
+    cipher: Aes256GcmSiv,
+}
+
+impl DeterministicKeyEncryptor {
+    /// Create an encryptor from a shared secret, used as an AES-256-GCM-SIV key
+    pub(crate) fn create(secret: &[u8]) -> ockam_core::Result<Self> {
+        let cipher = Aes256GcmSiv::new_from_slice(secret).map_err(|_| {
+            ApiError::core(format!(
+                "The secret used to encrypt the kafka keys must be {DETERMINISTIC_KEY_SECRET_LENGTH} bytes long"
+            ))
+        })?;
+        Ok(Self { cipher })
+    }
+
+    pub(crate) fn encrypt(&self, plain_text: &[u8]) -> ockam_core::Result<Vec<u8>> {
+        self.cipher
+            .encrypt(
+                &Self::nonce(),
+                Payload {
+                    msg: plain_text,
+                    aad: DETERMINISTIC_KEY_AAD,
+                },
+            )
+            .map_err(|_| ApiError::core("The kafka key can't be encrypted"))
+    }
+
+    pub(crate) fn decrypt(&self, encrypted: &[u8]) -> ockam_core::Result<Vec<u8>> {
+        self.cipher
+            .decrypt(
+                &Self::nonce(),
+                Payload {
+                    msg: encrypted,
+                    aad: DETERMINISTIC_KEY_AAD,
+                },
+            )
+            .map_err(|_| ApiError::core("The encrypted kafka key is invalid"))
+    }
+
+    /// The same nonce is used for all the keys so that the encryption is deterministic
+    fn nonce() -> Nonce {
+        Nonce::default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deterministic_key_encryption() -> ockam_core::Result<()> {
+        let encryptor = DeterministicKeyEncryptor::create(&[1; 32])?;
+        let encrypted = encryptor.encrypt(b"customer-1")?;
+        assert_ne!(encrypted, b"customer-1");
+        assert_eq!(encryptor.decrypt(&encrypted)?, b"customer-1");
+
+        // the same key is always encrypted to the same value, even by another encryptor
+        let other_encryptor = DeterministicKeyEncryptor::create(&[1; 32])?;
+        assert_eq!(other_encryptor.encrypt(b"customer-1")?, encrypted);
+        assert_ne!(encryptor.encrypt(b"customer-2")?, encrypted);
+
+        // a different secret can't decrypt the key
+        let other_encryptor = DeterministicKeyEncryptor::create(&[2; 32])?;
+        assert!(other_encryptor.decrypt(&encrypted).is_err());
+
+        // tampered keys are rejected
+        let mut tampered = encrypted.clone();
+        tampered[0] ^= 1;
+        assert!(encryptor.decrypt(&tampered).is_err());
+
+        assert!(DeterministicKeyEncryptor::create(&[1; 16]).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_deterministic_key_encryption_known_answers() -> ockam_core::Result<()> {
+        // AEAD_AES_256_GCM_SIV test vectors from RFC 8452, appendix C.2
+        let mut key = [0u8; 32];
+        key[0] = 1;
+        let mut nonce = Nonce::default();
+        nonce[0] = 3;
+        let cipher = Aes256GcmSiv::new_from_slice(&key).unwrap();
+        assert_eq!(
+            hex::encode(cipher.encrypt(&nonce, [].as_slice()).unwrap()),
+            "07f5f4169bbf55a8400cd47ea6fd400f"
+        );
+        assert_eq!(
+            hex::encode(
+                cipher
+                    .encrypt(&nonce, [1, 0, 0, 0, 0, 0, 0, 0].as_slice())
+                    .unwrap()
+            ),
+            "c2ef328e5c71c83b843122130f7364b761e0b97427e3df28"
+        );
+
+        // encrypted kafka keys
+        let encryptor = DeterministicKeyEncryptor::create(&[1; 32])?;
+        assert_eq!(
+            hex::encode(encryptor.encrypt(b"customer-1")?),
+            "7e0df5808543f88740ba8b79443807367df5980c35316ef013b8"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_headers_encryption() {
+        let selected = KafkaHeadersEncryption::Selected(vec!["trace-id".to_string()]);
+        assert!(selected.includes("trace-id"));
+        assert!(!selected.includes("content-type"));
+        assert!(KafkaHeadersEncryption::All.includes("content-type"));
+        assert!(!KafkaHeadersEncryption::None.includes("trace-id"));
+    }
+}
@@ -72,7 +72,9 @@ mod test {
             Default::default(),
             inlet_map,
             true,
-            vec![],
+            None,
+            None,
+            Default::default(),
         );
 
         let mut correlation_id = 0;
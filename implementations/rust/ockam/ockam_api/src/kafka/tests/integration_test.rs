@@ -115,7 +115,9 @@ async fn create_kafka_service(
             secure_channel_controller,
             inlet_controller,
             true,
-            vec![],
+            None,
+            None,
+            Default::default(),
         )),
         Arc::new(AllowAll),
         Arc::new(AllowAll),
@@ -333,7 +333,9 @@ async fn setup_only_worker(context: &mut Context, handle: &NodeManagerHandle) ->
                 Default::default(),
                 inlet_map,
                 true,
-                vec![],
+                None,
+                None,
+                Default::default(),
             )),
             TEST_MAX_KAFKA_MESSAGE_SIZE,
         )),
@@ -431,7 +433,9 @@ async fn kafka_portal_worker__metadata_exchange__response_changed(
                 Default::default(),
                 inlet_map.clone(),
                 true,
-                vec![],
+                None,
+                None,
+                Default::default(),
             )),
             MAX_KAFKA_MESSAGE_SIZE,
         )),
@@ -1,6 +1,7 @@
 use minicbor::{CborLen, Decode, Encode};
 use ockam_abac::{
-    Action, Expr, PolicyExpression, ResourceName, ResourcePolicy, ResourceType, ResourceTypePolicy,
+    Action, Expr, PolicyCheckResult, PolicyCheckSubject, PolicyExpression, ResourceName,
+    ResourcePolicy, ResourceType, ResourceTypePolicy,
 };
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Error;
@@ -25,6 +26,56 @@ impl SetPolicyRequest {
     }
 }
 
+#[derive(Clone, Debug, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct CheckPolicyRequest {
+    #[n(1)] pub resource: ResourceTypeOrName,
+    #[n(2)] pub action: Action,
+    #[n(3)] pub expression: PolicyExpression,
+    /// Identities to check the expression for. If not set, the identities having attributes
+    /// stored on the node are used
+    #[n(4)] pub subjects: Option<Vec<PolicyCheckSubject>>,
+}
+
+impl CheckPolicyRequest {
+    pub fn new(
+        resource: ResourceTypeOrName,
+        action: Action,
+        expression: PolicyExpression,
+        subjects: Option<Vec<PolicyCheckSubject>>,
+    ) -> Self {
+        Self {
+            resource,
+            action,
+            expression,
+            subjects,
+        }
+    }
+}
+
+/// Result of the evaluation of a candidate policy expression, compared to the current policy
+#[derive(Debug, Encode, Decode, CborLen, Serialize, PartialEq, Eq)]
+#[rustfmt::skip]
+#[cbor(map)]
+pub struct PolicyCheck {
+    #[n(1)] pub current_expression: Option<Expr>,
+    #[n(2)] pub candidate_expression: Expr,
+    #[n(3)] pub results: Vec<PolicyCheckResult>,
+}
+
+impl PolicyCheck {
+    /// Return the identities which are only authorized by the candidate expression
+    pub fn gained_access(&self) -> Vec<&PolicyCheckResult> {
+        self.results.iter().filter(|r| r.gains_access()).collect()
+    }
+
+    /// Return the identities which are only authorized by the current expression
+    pub fn lost_access(&self) -> Vec<&PolicyCheckResult> {
+        self.results.iter().filter(|r| r.loses_access()).collect()
+    }
+}
+
 #[derive(Debug, Encode, Decode, CborLen, PartialEq, Eq)]
 #[rustfmt::skip]
 #[cbor(map)]
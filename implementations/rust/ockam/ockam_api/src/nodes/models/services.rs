@@ -1,5 +1,8 @@
 use crate::colors::{color_primary, color_warn};
-use crate::kafka::{ConsumerPublishing, ConsumerResolution};
+use crate::kafka::{
+    ConsumerPublishing, ConsumerResolution, KafkaHeadersEncryption, KafkaKeyEncryption,
+    KafkaRecordFormat,
+};
 use crate::output::Output;
 use minicbor::{CborLen, Decode, Encode};
 use ockam_abac::PolicyExpression;
@@ -100,6 +103,9 @@ pub struct StartKafkaInletRequest {
     #[n(8)] consumer_policy_expression: Option<PolicyExpression>,
     #[n(9)] producer_policy_expression: Option<PolicyExpression>,
     #[n(10)] encrypted_fields: Vec<String>,
+    #[n(11)] record_format: Option<KafkaRecordFormat>,
+    #[n(12)] key_encryption: Option<KafkaKeyEncryption>,
+    #[n(13)] headers_encryption: Option<KafkaHeadersEncryption>,
 }
 
 impl StartKafkaInletRequest {
@@ -110,6 +116,9 @@ impl StartKafkaInletRequest {
         kafka_outlet_route: MultiAddr,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        record_format: KafkaRecordFormat,
+        key_encryption: KafkaKeyEncryption,
+        headers_encryption: KafkaHeadersEncryption,
         consumer_resolution: ConsumerResolution,
         consumer_publishing: ConsumerPublishing,
         inlet_policy_expression: Option<PolicyExpression>,
@@ -127,6 +136,9 @@ impl StartKafkaInletRequest {
             consumer_policy_expression,
             producer_policy_expression,
             encrypted_fields,
+            record_format: Some(record_format),
+            key_encryption: Some(key_encryption),
+            headers_encryption: Some(headers_encryption),
         }
     }
 
@@ -148,6 +160,18 @@ impl StartKafkaInletRequest {
         self.encrypted_fields.clone()
     }
 
+    pub fn record_format(&self) -> KafkaRecordFormat {
+        self.record_format.clone().unwrap_or_default()
+    }
+
+    pub fn key_encryption(&self) -> KafkaKeyEncryption {
+        self.key_encryption.clone().unwrap_or_default()
+    }
+
+    pub fn headers_encryption(&self) -> KafkaHeadersEncryption {
+        self.headers_encryption.clone().unwrap_or_default()
+    }
+
     pub fn consumer_resolution(&self) -> ConsumerResolution {
         self.consumer_resolution.clone()
     }
@@ -1,5 +1,5 @@
 use ockam::udp::UDP;
-use ockam_core::Address;
+use ockam_core::{route, Address, Route};
 
 pub struct DefaultAddress;
 
@@ -19,14 +19,39 @@ impl DefaultAddress {
     pub const ENROLLMENT_TOKEN_ISSUER: &'static str = "enrollment_token_issuer";
     pub const ENROLLMENT_TOKEN_ACCEPTOR: &'static str = "enrollment_token_acceptor";
     pub const OKTA_IDENTITY_PROVIDER: &'static str = "okta";
+    pub const OIDC_AUTHENTICATOR: &'static str = "oidc_authenticator";
+    pub const AUTHORITY_AUDIT_LOG: &'static str = "authority_audit_log";
     pub const KAFKA_OUTLET: &'static str = "kafka_outlet";
     pub const KAFKA_INLET: &'static str = "kafka_inlet";
     pub const LEASE_MANAGER: &'static str = "lease_manager";
 
     pub fn get_rendezvous_server_address() -> Address {
-        let server_address = std::env::var("OCKAM_RENDEZVOUS_SERVER")
-            .unwrap_or("rendezvous.orchestrator.ockam.io:443".to_string());
-        (UDP, server_address).into()
+        Self::get_rendezvous_server_addresses().remove(0)
+    }
+
+    /// `OCKAM_RENDEZVOUS_SERVER` can contain a comma-separated list of servers, which are all
+    /// used to discover the UDP candidates and the NAT behaviour of the node
+    pub fn get_rendezvous_server_addresses() -> Vec<Address> {
+        let server_addresses = std::env::var("OCKAM_RENDEZVOUS_SERVER").unwrap_or_default();
+        let mut server_addresses: Vec<&str> = server_addresses
+            .split(',')
+            .map(str::trim)
+            .filter(|address| !address.is_empty())
+            .collect();
+        if server_addresses.is_empty() {
+            server_addresses.push("rendezvous.orchestrator.ockam.io:443");
+        }
+        server_addresses
+            .into_iter()
+            .map(|address| (UDP, address).into())
+            .collect()
+    }
+
+    pub fn get_rendezvous_routes() -> Vec<Route> {
+        Self::get_rendezvous_server_addresses()
+            .into_iter()
+            .map(|address| route![address, Self::RENDEZVOUS_SERVICE])
+            .collect()
     }
 
     pub fn is_valid(name: &str) -> bool {
@@ -42,6 +67,8 @@ impl DefaultAddress {
             | Self::ENROLLMENT_TOKEN_ISSUER
             | Self::ENROLLMENT_TOKEN_ACCEPTOR
             | Self::OKTA_IDENTITY_PROVIDER
+            | Self::OIDC_AUTHENTICATOR
+            | Self::AUTHORITY_AUDIT_LOG
             | Self::KAFKA_INLET
             | Self::KAFKA_OUTLET
             | Self::LEASE_MANAGER)
@@ -62,6 +89,8 @@ impl DefaultAddress {
             Self::ENROLLMENT_TOKEN_ISSUER,
             Self::ENROLLMENT_TOKEN_ACCEPTOR,
             Self::OKTA_IDENTITY_PROVIDER,
+            Self::OIDC_AUTHENTICATOR,
+            Self::AUTHORITY_AUDIT_LOG,
             Self::KAFKA_INLET,
             Self::KAFKA_OUTLET,
             Self::LEASE_MANAGER,
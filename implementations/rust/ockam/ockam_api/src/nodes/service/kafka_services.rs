@@ -5,8 +5,9 @@ use crate::kafka::protocol_aware::inlet::KafkaInletInterceptorFactory;
 use crate::kafka::protocol_aware::outlet::KafkaOutletInterceptorFactory;
 use crate::kafka::KafkaOutletController;
 use crate::kafka::{
-    kafka_policy_expression, ConsumerPublishing, ConsumerResolution, KafkaInletController,
-    KAFKA_OUTLET_BOOTSTRAP_ADDRESS, KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
+    kafka_policy_expression, ConsumerPublishing, ConsumerResolution, KafkaHeadersEncryption,
+    KafkaInletController, KafkaKeyEncryption, KafkaRecordFormat, KAFKA_OUTLET_BOOTSTRAP_ADDRESS,
+    KAFKA_OUTLET_INTERCEPTOR_ADDRESS,
 };
 use crate::nodes::models::portal::OutletAccessControl;
 use crate::nodes::models::services::{
@@ -48,6 +49,9 @@ impl NodeManagerWorker {
                 request.project_route(),
                 request.encrypt_content(),
                 request.encrypted_fields(),
+                request.record_format(),
+                request.key_encryption(),
+                request.headers_encryption(),
                 request.consumer_resolution(),
                 request.consumer_publishing(),
                 request.inlet_policy_expression(),
@@ -121,12 +125,23 @@ impl InMemoryNode {
         outlet_node_multiaddr: MultiAddr,
         encrypt_content: bool,
         encrypted_fields: Vec<String>,
+        record_format: KafkaRecordFormat,
+        key_encryption: KafkaKeyEncryption,
+        headers_encryption: KafkaHeadersEncryption,
         consumer_resolution: ConsumerResolution,
         consumer_publishing: ConsumerPublishing,
         inlet_policy_expression: Option<PolicyExpression>,
         consumer_policy_expression: Option<PolicyExpression>,
         producer_policy_expression: Option<PolicyExpression>,
     ) -> Result<()> {
+        // the whole record is encrypted when no fields are specified
+        let record_codec = if encrypted_fields.is_empty() {
+            None
+        } else {
+            Some(record_format.codec(encrypted_fields)?)
+        };
+        let key_encryption = key_encryption.encryptor()?;
+
         let consumer_policy_access_control = self
             .policy_access_control(
                 self.project_authority().clone(),
@@ -239,7 +254,9 @@ impl InMemoryNode {
                 secure_channel_controller,
                 inlet_controller,
                 encrypt_content,
-                encrypted_fields,
+                record_codec,
+                key_encryption,
+                headers_encryption,
             )),
             Arc::new(policy_access_control.create_incoming()),
             Arc::new(policy_access_control.create_outgoing(context)?),
@@ -35,8 +35,8 @@ use ockam_abac::{
 };
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
-    route, AllowAll, CachedIncomingAccessControl, CachedOutgoingAccessControl,
-    IncomingAccessControl, OutgoingAccessControl, TryClone,
+    AllowAll, CachedIncomingAccessControl, CachedOutgoingAccessControl, IncomingAccessControl,
+    OutgoingAccessControl, TryClone,
 };
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
@@ -177,11 +177,6 @@ impl NodeManager {
         }
 
         if let Some(udp) = &s.udp_transport {
-            let rendezvous_route = route![
-                DefaultAddress::get_rendezvous_server_address(),
-                DefaultAddress::RENDEZVOUS_SERVICE
-            ];
-
             let options = UdpPunctureNegotiationListenerOptions::new();
             let flow_control_id = options.flow_control_id();
 
@@ -189,7 +184,7 @@ impl NodeManager {
                 ctx,
                 DefaultAddress::UDP_PUNCTURE_NEGOTIATION_LISTENER,
                 udp,
-                rendezvous_route,
+                DefaultAddress::get_rendezvous_routes(),
                 options,
             )?;
 
@@ -248,7 +243,7 @@ impl NodeManager {
                 .policy_access_control(
                     self.project_authority.clone(),
                     Resource::new(DefaultAddress::RELAY_SERVICE, ResourceType::Relay),
-                    Action::HandleMessage,
+                    Action::Connect,
                     None,
                 )
                 .await?;
@@ -533,12 +528,18 @@ impl NodeManager {
         .into_diagnostic()
     }
 
+    /// Return the access controls of a resource:
+    ///
+    ///  - the incoming access control checks the policies of the `Send` action
+    ///  - the outgoing access control checks the policies of the `Receive` action
+    ///
+    /// The expression, if given, is stored as the `HandleMessage` policy of the resource,
+    /// which applies to both actions unless they have their own policy.
     pub(crate) async fn access_control(
         &self,
         ctx: &Context,
         authority: Option<Identifier>,
         resource: Resource,
-        action: Action,
         expression: Option<PolicyExpression>,
     ) -> ockam_core::Result<(
         Arc<dyn IncomingAccessControl>,
@@ -546,14 +547,25 @@ impl NodeManager {
     )> {
         let resource_name_str = resource.resource_name.as_str();
         let resource_type_str = resource.resource_type.to_string();
-        let action_str = action.as_ref();
         if authority.is_some() || expression.is_some() {
-            let policy_access_control = self
-                .policy_access_control(authority, resource, action, expression)
-                .await?;
+            if let Some(expression) = expression {
+                self.policies()
+                    .store_policy_for_resource_name(
+                        &resource.resource_name,
+                        &Action::HandleMessage,
+                        &expression.into(),
+                    )
+                    .await?;
+            }
 
-            let incoming_ac = policy_access_control.create_incoming();
-            let outgoing_ac = policy_access_control.create_outgoing(ctx)?;
+            let incoming_ac = self
+                .policy_access_control(authority.clone(), resource.clone(), Action::Send, None)
+                .await?
+                .create_incoming();
+            let outgoing_ac = self
+                .policy_access_control(authority, resource, Action::Receive, None)
+                .await?
+                .create_outgoing(ctx)?;
 
             cfg_if::cfg_if! {
                 if #[cfg(feature = "std")] {
@@ -573,13 +585,41 @@ impl NodeManager {
             warn! {
                 resource_name = resource_name_str,
                 resource_type = resource_type_str,
-                action = action_str,
                 "no policy access control set"
             }
             Ok((Arc::new(AllowAll), Arc::new(AllowAll)))
         }
     }
 
+    /// Return the access control checking the requests to open a connection to a resource,
+    /// with the policies of the `Connect` action.
+    ///
+    /// This must be called after [`NodeManager::access_control`], which stores the policy
+    /// expression of the resource.
+    pub(crate) async fn connection_access_control(
+        &self,
+        authority: Option<Identifier>,
+        resource: Resource,
+        has_expression: bool,
+    ) -> ockam_core::Result<Arc<dyn IncomingAccessControl>> {
+        if authority.is_none() && !has_expression {
+            return Ok(Arc::new(AllowAll));
+        }
+
+        let incoming_ac = self
+            .policy_access_control(authority, resource, Action::Connect, None)
+            .await?
+            .create_incoming();
+
+        cfg_if::cfg_if! {
+            if #[cfg(feature = "std")] {
+                Ok(Arc::new(CachedIncomingAccessControl::new(Box::new(incoming_ac))))
+            } else {
+                Ok(Arc::new(incoming_ac))
+            }
+        }
+    }
+
     pub fn policies(&self) -> Policies {
         self.cli_state.policies(&self.node_name)
     }
@@ -1,7 +1,7 @@
 use either::Either;
 
 use ockam::{Address, Context, Result};
-use ockam_abac::{Action, Resource, ResourceType};
+use ockam_abac::{Resource, ResourceType};
 use ockam_core::api::{Error, Response};
 use ockam_node::WorkerBuilder;
 
@@ -192,7 +192,6 @@ impl NodeManager {
                 ctx,
                 self.project_authority(),
                 Resource::new(addr.address(), ResourceType::Echoer),
-                Action::HandleMessage,
                 None,
             )
             .await?;
@@ -1,11 +1,18 @@
-use ockam_abac::{Action, PolicyExpression};
+use ockam::identity::{AttributesEntry, Identifier};
+use ockam_abac::{
+    check_policy, Action, Env, Expr, PolicyAccessControl, PolicyCheckSubject, PolicyDecision,
+    PolicyDecisionsQuery, PolicyExpression, RequestContext, Resource, ResourceType,
+};
 use ockam_core::api::{Error, Request, Response};
+use ockam_core::compat::collections::BTreeMap;
 use ockam_core::{async_trait, Result};
 use ockam_node::Context;
 use std::str::FromStr;
 
-use crate::nodes::models::policies::{PoliciesList, Policy, ResourceTypeOrName, SetPolicyRequest};
-use crate::nodes::{BackgroundNodeClient, NodeManagerWorker};
+use crate::nodes::models::policies::{
+    CheckPolicyRequest, PoliciesList, Policy, PolicyCheck, ResourceTypeOrName, SetPolicyRequest,
+};
+use crate::nodes::{BackgroundNodeClient, NodeManagerWorker, NODEMANAGER_ADDR};
 
 use super::NodeManager;
 
@@ -57,6 +64,42 @@ impl NodeManagerWorker {
             Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
         }
     }
+
+    pub(super) async fn check_policy(
+        &self,
+        request: CheckPolicyRequest,
+    ) -> Result<Response<PolicyCheck>, Response<Error>> {
+        match self
+            .node_manager
+            .check_policy(
+                request.resource,
+                &request.action,
+                request.expression.into(),
+                request.subjects,
+            )
+            .await
+        {
+            Ok(check) => Ok(Response::ok().body(check)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn list_policy_decisions(
+        &self,
+        query: PolicyDecisionsQuery,
+    ) -> Result<Response<Vec<PolicyDecision>>, Response<Error>> {
+        match self.node_manager.get_policy_decisions(&query).await {
+            Ok(decisions) => Ok(Response::ok().body(decisions)),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
+
+    pub(super) async fn delete_policy_decisions(&self) -> Result<Response<()>, Response<Error>> {
+        match self.node_manager.delete_policy_decisions().await {
+            Ok(_) => Ok(Response::ok()),
+            Err(e) => Err(Response::internal_error_no_request(&e.to_string())),
+        }
+    }
 }
 
 impl NodeManager {
@@ -146,6 +189,125 @@ impl NodeManager {
     }
 }
 
+impl NodeManager {
+    /// Return the access control of the node API, checking the policies of the `admin` action
+    /// for the `node-api` resource type
+    pub(crate) fn api_access_control(&self) -> PolicyAccessControl {
+        self.policies().make_policy_access_control(
+            self.cli_state.identities_attributes(&self.node_name),
+            Resource::new(NODEMANAGER_ADDR, ResourceType::NodeApi),
+            Action::Admin,
+            Env::new(),
+            self.project_authority(),
+        )
+    }
+
+    /// Evaluate a candidate expression for a resource and an action, and compare the result
+    /// to the current policy, for a list of identities or for all the identities having
+    /// attributes stored on the node
+    pub async fn check_policy(
+        &self,
+        resource: ResourceTypeOrName,
+        action: &Action,
+        candidate: Expr,
+        subjects: Option<Vec<PolicyCheckSubject>>,
+    ) -> Result<PolicyCheck> {
+        let context = RequestContext::new().with_current_time();
+        let (current_expression, context) = match &resource {
+            ResourceTypeOrName::Type(resource_type) => (
+                self.policies()
+                    .get_policy_for_resource_type(resource_type, action)
+                    .await?
+                    .map(|p| p.expression),
+                context.with_resource_type(resource_type),
+            ),
+            ResourceTypeOrName::Name(resource_name) => {
+                match self.resources().get_resource(resource_name).await? {
+                    Some(resource) => (
+                        self.policies()
+                            .get_expression_for_resource(&resource, action)
+                            .await?,
+                        context.with_resource(&resource),
+                    ),
+                    None => (
+                        self.policies()
+                            .get_policy_for_resource_name(resource_name, action)
+                            .await?
+                            .map(|p| p.expression),
+                        context.with_resource_name(resource_name),
+                    ),
+                }
+            }
+        };
+
+        let subjects = match subjects {
+            Some(subjects) => subjects,
+            None => stored_subjects(
+                self.secure_channels
+                    .identities()
+                    .identities_attributes()
+                    .get_all_attributes()
+                    .await?,
+                self.project_authority().as_ref(),
+            ),
+        };
+
+        let results = check_policy(
+            context.env(),
+            current_expression.as_ref(),
+            &candidate,
+            &subjects,
+        );
+        Ok(PolicyCheck {
+            current_expression,
+            candidate_expression: candidate,
+            results,
+        })
+    }
+
+    /// Return the access control decisions recorded by the node, the most recent ones first
+    pub async fn get_policy_decisions(
+        &self,
+        query: &PolicyDecisionsQuery,
+    ) -> Result<Vec<PolicyDecision>> {
+        match self.policies().decision_log() {
+            Some(decision_log) => decision_log.repository().get_decisions(query).await,
+            None => Ok(vec![]),
+        }
+    }
+
+    /// Delete the access control decisions recorded by the node
+    pub async fn delete_policy_decisions(&self) -> Result<()> {
+        match self.policies().decision_log() {
+            Some(decision_log) => decision_log.repository().delete_decisions().await,
+            None => Ok(()),
+        }
+    }
+}
+
+/// Return one subject per identity having stored attributes.
+/// Like the access controls, only the attributes attested by the authority are used, and an
+/// identity with attributes from several attesters is only checked once
+fn stored_subjects(
+    entries: Vec<(Identifier, AttributesEntry)>,
+    authority: Option<&Identifier>,
+) -> Vec<PolicyCheckSubject> {
+    let mut subjects: BTreeMap<Identifier, Option<BTreeMap<Vec<u8>, Vec<u8>>>> = BTreeMap::new();
+    for (identifier, entry) in entries {
+        let attributes = subjects.entry(identifier).or_default();
+        if authority.is_some() && entry.attested_by().as_ref() == authority {
+            *attributes = Some(entry.attrs().clone());
+        }
+    }
+    subjects
+        .into_iter()
+        .map(|(identifier, attributes)| PolicyCheckSubject {
+            identifier,
+            attributes,
+        })
+        .collect()
+}
+
 pub fn policy_path(a: &Action) -> String {
     format!("/policy/{a}")
 }
@@ -179,6 +341,20 @@ pub trait Policies {
         resource: &ResourceTypeOrName,
         action: &Action,
     ) -> miette::Result<()>;
+
+    async fn check_policy(
+        &self,
+        ctx: &Context,
+        request: &CheckPolicyRequest,
+    ) -> miette::Result<PolicyCheck>;
+
+    async fn list_policy_decisions(
+        &self,
+        ctx: &Context,
+        query: &PolicyDecisionsQuery,
+    ) -> miette::Result<Vec<PolicyDecision>>;
+
+    async fn delete_policy_decisions(&self, ctx: &Context) -> miette::Result<()>;
 }
 
 #[async_trait]
@@ -225,4 +401,79 @@ impl Policies for BackgroundNodeClient {
         self.tell(ctx, request).await?;
         Ok(())
     }
+
+    async fn check_policy(
+        &self,
+        ctx: &Context,
+        request: &CheckPolicyRequest,
+    ) -> miette::Result<PolicyCheck> {
+        let request = Request::post("/policy_check").body(request);
+        self.ask(ctx, request).await
+    }
+
+    async fn list_policy_decisions(
+        &self,
+        ctx: &Context,
+        query: &PolicyDecisionsQuery,
+    ) -> miette::Result<Vec<PolicyDecision>> {
+        let request = Request::get("/policy_decisions").body(query);
+        self.ask(ctx, request).await
+    }
+
+    async fn delete_policy_decisions(&self, ctx: &Context) -> miette::Result<()> {
+        let request = Request::delete("/policy_decisions");
+        self.tell(ctx, request).await?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam::identity::TimestampInSeconds;
+
+    #[test]
+    fn test_stored_subjects_are_deduplicated() {
+        let authority = identifier(1);
+        let other_attester = identifier(2);
+        let member = identifier(3);
+        let attributes =
+            |role: &str| BTreeMap::from([(b"role".to_vec(), role.as_bytes().to_vec())]);
+        let entry = |role: &str, attested_by: &Identifier| {
+            AttributesEntry::new(
+                attributes(role),
+                TimestampInSeconds(0),
+                None,
+                Some(attested_by.clone()),
+            )
+        };
+
+        let subjects = stored_subjects(
+            vec![
+                (member.clone(), entry("admin", &other_attester)),
+                (member.clone(), entry("dev", &authority)),
+                (member.clone(), entry("guest", &other_attester)),
+                (other_attester.clone(), entry("admin", &other_attester)),
+            ],
+            Some(&authority),
+        );
+
+        assert_eq!(
+            subjects,
+            vec![
+                PolicyCheckSubject {
+                    identifier: other_attester,
+                    attributes: None,
+                },
+                PolicyCheckSubject {
+                    identifier: member,
+                    attributes: Some(attributes("dev")),
+                },
+            ]
+        );
+    }
+
+    fn identifier(byte: u8) -> Identifier {
+        Identifier([byte; 32])
+    }
 }
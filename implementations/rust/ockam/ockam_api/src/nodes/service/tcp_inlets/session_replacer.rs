@@ -9,7 +9,7 @@ use ockam::identity::{Identifier, SecureChannel};
 use ockam::tcp::TcpInletOptions;
 use ockam::udp::{UdpPuncture, UdpPunctureNegotiation, UdpTransport};
 use ockam::Result;
-use ockam_abac::{Action, PolicyExpression, Resource};
+use ockam_abac::{PolicyExpression, Resource};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, route, Error, IncomingAccessControl, OutgoingAccessControl, Route};
 use ockam_multiaddr::proto::Project as ProjectProto;
@@ -99,7 +99,6 @@ impl InletSessionReplacer {
                 &self.context,
                 authority,
                 self.resource.clone(),
-                Action::HandleMessage,
                 self.policy_expression.clone(),
             )
             .await
@@ -382,16 +381,11 @@ impl AdditionalSessionReplacer for InletSessionReplacer {
             .await?;
         let additional_sc = self.additional_secure_channel.insert(additional_sc);
 
-        let rendezvous_route = route![
-            DefaultAddress::get_rendezvous_server_address(),
-            DefaultAddress::RENDEZVOUS_SERVICE
-        ];
-
         let puncture = UdpPunctureNegotiation::start_negotiation(
             &self.context,
             main_route + DefaultAddress::UDP_PUNCTURE_NEGOTIATION_LISTENER,
             &udp_transport,
-            rendezvous_route,
+            DefaultAddress::get_rendezvous_routes(),
             // TODO: Have a dedicated timeout
             Duration::from_secs(10),
         )
@@ -1,7 +1,7 @@
 use ockam::tcp::TcpOutletOptions;
 use ockam::transport::HostnamePort;
 use ockam::{Address, Result};
-use ockam_abac::{Action, PolicyExpression, Resource, ResourceType};
+use ockam_abac::{PolicyExpression, Resource, ResourceType};
 use ockam_core::api::{Error, Request, RequestHeader, Response};
 use ockam_core::async_trait;
 use ockam_core::errcode::{Kind, Origin};
@@ -114,24 +114,29 @@ impl NodeManager {
             ));
         }
 
-        let (incoming_ac, outgoing_ac) = match access_control {
+        let (connection_ac, incoming_ac, outgoing_ac) = match access_control {
             OutletAccessControl::AccessControl((incoming_ac, outgoing_ac)) => {
-                (incoming_ac, outgoing_ac)
+                (None, incoming_ac, outgoing_ac)
             }
             OutletAccessControl::WithPolicyExpression(expression) => {
-                self.access_control(
-                    ctx,
-                    self.project_authority(),
-                    Resource::new(worker_addr.address(), ResourceType::TcpOutlet),
-                    Action::HandleMessage,
-                    expression,
-                )
-                .await?
+                let resource = Resource::new(worker_addr.address(), ResourceType::TcpOutlet);
+                let has_expression = expression.is_some();
+                let (incoming_ac, outgoing_ac) = self
+                    .access_control(ctx, self.project_authority(), resource.clone(), expression)
+                    .await?;
+                let connection_ac = self
+                    .connection_access_control(self.project_authority(), resource, has_expression)
+                    .await?;
+                (Some(connection_ac), incoming_ac, outgoing_ac)
             }
         };
 
         let options = {
-            let mut options = TcpOutletOptions::new()
+            let mut options = TcpOutletOptions::new();
+            if let Some(connection_ac) = connection_ac {
+                options = options.with_connection_access_control(connection_ac);
+            }
+            let mut options = options
                 .with_incoming_access_control(incoming_ac)
                 .with_outgoing_access_control(outgoing_ac)
                 .with_tls(tls);
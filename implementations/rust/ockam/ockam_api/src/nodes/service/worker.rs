@@ -4,8 +4,10 @@ use crate::nodes::service::{encode_response, TARGET};
 use crate::nodes::{InMemoryNode, NODEMANAGER_ADDR};
 use crate::DefaultAddress;
 use minicbor::Decoder;
+use ockam::identity::Identifier;
+use ockam_abac::PolicyAccessControl;
 use ockam_core::api::{RequestHeader, Response};
-use ockam_core::{Address, Result, Routed, Worker};
+use ockam_core::{Address, Result, Routed, SecureChannelLocalInfo, Worker};
 use ockam_node::Context;
 use std::error::Error;
 use std::sync::Arc;
@@ -13,11 +15,16 @@ use std::sync::Arc;
 #[derive(Clone)]
 pub struct NodeManagerWorker {
     pub node_manager: Arc<InMemoryNode>,
+    api_access_control: PolicyAccessControl,
 }
 
 impl NodeManagerWorker {
     pub fn new(node_manager: Arc<InMemoryNode>) -> Self {
-        NodeManagerWorker { node_manager }
+        let api_access_control = node_manager.api_access_control();
+        NodeManagerWorker {
+            node_manager,
+            api_access_control,
+        }
     }
 
     // TODO: This is never called.
@@ -29,6 +36,58 @@ impl NodeManagerWorker {
 }
 
 impl NodeManagerWorker {
+    //////// Request authorization ////////
+
+    /// Return true if the caller is allowed to send this request.
+    ///
+    /// The requests which are not received through a secure channel come from the transports
+    /// used by the local `ockam` commands, and are always authorized. So are the requests sent by
+    /// the node identity.
+    ///
+    /// The authorization of the other requests is opt-in: when an `admin` policy is set for the
+    /// `node-api` resource, the requests are authorized by that policy, evaluated with the method
+    /// and the path of the request. Otherwise, the requests are authorized by the secure channel,
+    /// like the other requests.
+    ///
+    /// The policy is evaluated with the attributes attested by the project authority, so the
+    /// requests are denied when the node has a `node-api` policy but no project authority.
+    async fn is_authorized(&self, caller: Option<&Identifier>, req: &RequestHeader) -> bool {
+        let Some(caller) = caller else {
+            return true;
+        };
+        if caller == &self.node_manager.identifier() {
+            return true;
+        }
+        match self.api_access_control.has_policy().await {
+            Ok(true) => (),
+            Ok(false) => return true,
+            Err(err) => {
+                warn!(target: TARGET, %caller, path = %req.path(), "failed to get the node API policy: {err}");
+                return false;
+            }
+        }
+        if self.node_manager.project_authority().is_none() {
+            warn!(target: TARGET, %caller, path = %req.path(), "the node API policy can't be evaluated without a project authority");
+            return false;
+        }
+
+        let context = self
+            .api_access_control
+            .request_context()
+            .with_api_request(req);
+        match self
+            .api_access_control
+            .is_request_authorized(caller, context)
+            .await
+        {
+            Ok(authorized) => authorized,
+            Err(err) => {
+                warn!(target: TARGET, %caller, path = %req.path(), "failed to authorize the request: {err}");
+                false
+            }
+        }
+    }
+
     //////// Request matching and response handling ////////
 
     #[instrument(skip_all, fields(method = ?req.method(), path = req.path()))]
@@ -221,6 +280,15 @@ impl NodeManagerWorker {
             (Delete, ["policy", action]) => {
                 encode_response(req, self.delete_policy(action, dec.decode()?).await)?
             }
+            (Post, ["policy_check"]) => {
+                encode_response(req, self.check_policy(dec.decode()?).await)?
+            }
+            (Get, ["policy_decisions"]) => {
+                encode_response(req, self.list_policy_decisions(dec.decode()?).await)?
+            }
+            (Delete, ["policy_decisions"]) => {
+                encode_response(req, self.delete_policy_decisions().await)?
+            }
 
             // ==*== Messages ==*==
             (Post, ["v0", "message"]) => {
@@ -250,6 +318,9 @@ impl Worker for NodeManagerWorker {
 
     async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Vec<u8>>) -> Result<()> {
         let return_route = msg.return_route().clone();
+        let caller = SecureChannelLocalInfo::find_info(msg.local_message())
+            .ok()
+            .map(|info| Identifier::from(info.their_identifier()));
         let body = msg.into_body()?;
         let mut dec = Decoder::new(&body);
         let req: RequestHeader = match dec.decode() {
@@ -260,6 +331,20 @@ impl Worker for NodeManagerWorker {
             }
         };
 
+        if !self.is_authorized(caller.as_ref(), &req).await {
+            warn! {
+                target: TARGET,
+                re     = %req.id(),
+                method = ?req.method(),
+                path   = %req.path(),
+                caller = ?caller,
+                "request denied"
+            }
+            let r = Response::forbidden(&req, "the caller is not authorized to send this request")
+                .to_vec()?;
+            return ctx.send(return_route, r).await;
+        }
+
         let r = match self.handle_request(ctx, &req, &mut dec).await {
             Ok(r) => r,
             Err(err) => {
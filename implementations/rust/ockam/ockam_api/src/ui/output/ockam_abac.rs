@@ -1,6 +1,7 @@
-use crate::colors::color_primary;
-use crate::output::Output;
-use ockam_abac::{ResourcePolicy, ResourceTypePolicy};
+use crate::colors::{color_error, color_ok, color_primary};
+use crate::output::{comma_separated, human_readable_time, Output};
+use ockam::identity::TimestampInSeconds;
+use ockam_abac::{PolicyDecision, ResourcePolicy, ResourceTypePolicy};
 
 use std::fmt::Write;
 
@@ -37,3 +38,57 @@ impl Output for ResourcePolicy {
         Ok(output)
     }
 }
+
+impl Output for PolicyDecision {
+    fn item(&self) -> crate::Result<String> {
+        let mut output = String::new();
+        let result = if self.allowed {
+            color_ok("allowed")
+        } else {
+            color_error("denied")
+        };
+        writeln!(
+            output,
+            "{} at {}",
+            result,
+            color_primary(human_readable_time(TimestampInSeconds(self.decided_at)))
+        )?;
+        writeln!(
+            output,
+            "Identifier: {}",
+            color_primary(self.identifier.to_string())
+        )?;
+        writeln!(
+            output,
+            "Resource: {} ({})",
+            color_primary(self.resource_name.to_string()),
+            color_primary(self.resource_type.to_string())
+        )?;
+        writeln!(output, "Action: {}", color_primary(self.action.to_string()))?;
+        if let Some(reason) = &self.reason {
+            write!(output, "Reason: {}", color_primary(reason))?;
+            return Ok(output);
+        }
+        writeln!(
+            output,
+            "Expression: {}",
+            color_primary(self.expression.to_string())
+        )?;
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<_>>();
+        writeln!(
+            output,
+            "Attributes: {}",
+            color_primary(comma_separated(&attributes))
+        )?;
+        write!(
+            output,
+            "Decided by: {}",
+            color_primary(self.decisive_expression.to_string())
+        )?;
+        Ok(output)
+    }
+}
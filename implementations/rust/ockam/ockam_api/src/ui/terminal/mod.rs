@@ -186,6 +186,20 @@ impl<W: TerminalWriter + Debug> Terminal<W> {
         }
     }
 
+    /// Prompt the user for a password, without echoing it.
+    /// If `confirm` is true the password must be entered twice.
+    /// Return None if the terminal is not interactive.
+    pub fn read_password(&self, msg: impl AsRef<str>, confirm: bool) -> Result<Option<String>> {
+        if !self.can_ask_for_user_input() {
+            return Ok(None);
+        }
+        let mut prompt = dialoguer::Password::new().with_prompt(msg.as_ref());
+        if confirm {
+            prompt = prompt.with_confirmation("Confirm", "The values don't match");
+        }
+        Ok(Some(prompt.interact().map_err(UiError::Dialoguer)?))
+    }
+
     pub fn confirm_interactively(&self, header: String) -> bool {
         let user_input = select_from_list(
             header,
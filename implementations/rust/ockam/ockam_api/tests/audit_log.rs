@@ -0,0 +1,114 @@
+use crate::common::common::{change_client_identifier, start_authority, AuthorityInfo};
+use ockam::identity::secure_channels;
+use ockam_api::authenticator::audit::{
+    AuditAction, AuditLogReader, AUDIT_EXPIRES_AT_ATTRIBUTE, AUDIT_TOKEN_REFERENCE_ATTRIBUTE,
+};
+use ockam_api::authenticator::direct::Members;
+use ockam_api::authenticator::enrollment_tokens::{TokenAcceptor, TokenIssuer};
+use ockam_core::Result;
+use ockam_node::Context;
+use std::collections::BTreeMap;
+
+mod common;
+
+#[ockam_macros::test]
+async fn membership_changes_are_recorded_in_the_audit_log(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let attributes = BTreeMap::from([("key".to_string(), "value".to_string())]);
+    admin
+        .client
+        .add_member(ctx, member.clone(), attributes.clone())
+        .await
+        .unwrap();
+    admin
+        .client
+        .suspend_member(ctx, member.clone())
+        .await
+        .unwrap();
+    admin
+        .client
+        .delete_member(ctx, member.clone())
+        .await
+        .unwrap();
+
+    let otc = admin
+        .client
+        .create_token(ctx, attributes.clone(), None, None)
+        .await
+        .unwrap();
+    let member_client = change_client_identifier(&admin.client, &member, None);
+    member_client.present_token(ctx, otc).await.unwrap();
+
+    let entries = admin.client.list_audit_entries(ctx, 0, 10).await.unwrap();
+    let actions: Vec<AuditAction> = entries.iter().map(|e| e.action).collect();
+    assert_eq!(
+        actions,
+        vec![
+            AuditAction::AddMember,
+            AuditAction::SuspendMember,
+            AuditAction::DeleteMember,
+            AuditAction::IssueEnrollmentToken,
+            AuditAction::AcceptEnrollmentToken,
+        ]
+    );
+    assert_eq!(entries[0].actor, admin.identifier);
+    assert_eq!(entries[0].target, Some(member.clone()));
+    assert_eq!(entries[0].attributes, attributes);
+    assert!(!entries[0]
+        .attributes
+        .contains_key(AUDIT_EXPIRES_AT_ATTRIBUTE));
+
+    // the token is identified by its reference in both entries
+    assert_eq!(entries[3].target, None);
+    let reference = entries[3].attributes.get(AUDIT_TOKEN_REFERENCE_ATTRIBUTE);
+    assert!(reference.is_some());
+    assert_eq!(entries[4].actor, member);
+    assert_eq!(
+        entries[4].attributes.get(AUDIT_TOKEN_REFERENCE_ATTRIBUTE),
+        reference
+    );
+
+    // the log can be read page by page
+    let page = admin.client.list_audit_entries(ctx, 3, 10).await.unwrap();
+    assert_eq!(page, entries[3..].to_vec());
+
+    let verification = admin.client.verify_audit_log(ctx).await.unwrap();
+    assert_eq!(verification.verified_entries, 5);
+    assert_eq!(verification.last_hash, entries[4].hash);
+    assert_eq!(verification.error, None);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn member_cant_read_the_audit_log(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    admin
+        .client
+        .add_member(ctx, member.clone(), Default::default())
+        .await
+        .unwrap();
+
+    let member_client = change_client_identifier(&admin.client, &member, None);
+    assert!(member_client.list_audit_entries(ctx, 0, 10).await.is_err());
+
+    Ok(())
+}
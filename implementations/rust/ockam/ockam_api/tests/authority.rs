@@ -1,7 +1,20 @@
-use crate::common::common::{default_configuration, start_authority_node};
+use crate::common::common::{create_configuration, default_configuration, start_authority_node};
+use ockam::identity::models::CredentialSchemaIdentifier;
+use ockam::identity::utils::AttributesBuilder;
+use ockam::identity::{Identifier, Identities};
+use ockam_api::authority_node;
+use ockam_api::authority_node::Authority;
 use ockam_api::nodes::service::default_address::DefaultAddress;
 use ockam_core::{Address, Result};
+use ockam_node::database::{DatabaseConfiguration, SqlxDatabase};
 use ockam_node::Context;
+use ockam_vault::storage::{KeyEncryptionKey, KeyEncryptionKeyProvider, SecretsSqlxDatabase};
+use ockam_vault::{AeadSecret, AEAD_SECRET_LENGTH};
+use rand::{thread_rng, Rng};
+use sqlx::query_scalar;
+use std::sync::Arc;
+use std::time::Duration;
+use tempfile::NamedTempFile;
 
 mod common;
 
@@ -57,3 +70,77 @@ async fn authority_starts_enrollment_token(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn authority_starts_with_encrypted_secrets(ctx: &mut Context) -> Result<()> {
+    let database_path = NamedTempFile::new().unwrap().keep().unwrap().1;
+    let database_configuration = DatabaseConfiguration::sqlite(database_path.as_path());
+    let database = SqlxDatabase::create(&database_configuration).await?;
+    let key_encryption_key: Arc<dyn KeyEncryptionKeyProvider> =
+        Arc::new(KeyEncryptionKey::new(AeadSecret([1; AEAD_SECRET_LENGTH])));
+
+    // the authority identity is created with encrypted secrets, as done by 'ockam authority create'
+    let secrets_repository = SecretsSqlxDatabase::make_encrypted_repository(
+        database.clone(),
+        key_encryption_key.clone(),
+    )
+    .await?;
+    let identities = Identities::create_with_node(database.clone(), "authority")
+        .with_secrets_repository(secrets_repository)
+        .build();
+    let authority_identifier = identities.identities_creation().create_identity().await?;
+    let member = identities.identities_creation().create_identity().await?;
+
+    let port = thread_rng().gen_range(10000..65535);
+    let configuration = create_configuration(
+        &authority_identifier.to_string(),
+        port,
+        &database_configuration,
+    )?;
+
+    // without the key-encryption key, the authority can not sign
+    let authority =
+        Authority::create_with_key_encryption_key(&configuration, Some(database.clone()), None)
+            .await?;
+    assert!(issue_credential(&authority, &member).await.is_err());
+
+    // with the key-encryption key, the authority can start and issue credentials
+    let authority = Authority::create_with_key_encryption_key(
+        &configuration,
+        Some(database.clone()),
+        Some(key_encryption_key),
+    )
+    .await?;
+    authority_node::start_node(ctx, &configuration, authority.clone()).await?;
+    issue_credential(&authority, &member).await?;
+
+    // the secrets created by the authority are encrypted as well
+    for table in ["signing_secret", "x25519_secret", "aead_secret"] {
+        let count: i64 = query_scalar(&format!(
+            "SELECT COUNT(*) FROM {table} WHERE wrapped_key IS NULL"
+        ))
+        .fetch_one(&*database.pool)
+        .await
+        .unwrap();
+        assert_eq!(count, 0, "plain text secrets found in {table}");
+    }
+    Ok(())
+}
+
+async fn issue_credential(authority: &Authority, member: &Identifier) -> Result<()> {
+    authority
+        .secure_channels()
+        .identities()
+        .credentials()
+        .credentials_creation()
+        .issue_credential(
+            &authority.identifier(),
+            member,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(1))
+                .with_attribute("key", "value")
+                .build(),
+            Duration::from_secs(60),
+        )
+        .await
+        .map(|_| ())
+}
@@ -66,6 +66,8 @@ pub fn create_configuration(
         no_direct_authentication: true,
         no_token_enrollment: true,
         okta: None,
+        oidc: None,
+        scim: None,
         account_authority: None,
         enforce_admin_checks: false,
         disable_trust_context_id: false,
@@ -5,6 +5,7 @@ use ockam_api::authenticator::direct::Members;
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
+use ockam_api::enroll::enrollment::Enrollment;
 use ockam_core::Result;
 use ockam_node::Context;
 use std::collections::BTreeMap;
@@ -570,3 +571,117 @@ async fn member_cant_do_anything(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+#[ockam_macros::test]
+async fn suspended_member_cant_get_credential(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    admin
+        .client
+        .add_member(ctx, member.clone(), Default::default())
+        .await
+        .unwrap();
+    let member_client = change_client_identifier(&admin.client, &member, None);
+    assert!(member_client.issue_credential(ctx).await.is_ok());
+
+    admin
+        .client
+        .suspend_member(ctx, member.clone())
+        .await
+        .unwrap();
+    let suspended = admin.client.list_suspended_member_ids(ctx).await.unwrap();
+    assert_eq!(suspended, vec![member.clone()]);
+    assert!(member_client.issue_credential(ctx).await.is_err());
+
+    // a suspended member keeps its membership
+    let members = admin.client.list_member_ids(ctx).await.unwrap();
+    assert!(members.contains(&member));
+
+    // adding a suspended member again doesn't resume it
+    let res = admin
+        .client
+        .add_member(ctx, member.clone(), Default::default())
+        .await;
+    assert!(res.is_err());
+    let suspended = admin.client.list_suspended_member_ids(ctx).await.unwrap();
+    assert_eq!(suspended, vec![member.clone()]);
+    assert!(member_client.issue_credential(ctx).await.is_err());
+
+    admin
+        .client
+        .resume_member(ctx, member.clone())
+        .await
+        .unwrap();
+    let suspended = admin.client.list_suspended_member_ids(ctx).await.unwrap();
+    assert!(suspended.is_empty());
+    assert!(member_client.issue_credential(ctx).await.is_ok());
+
+    // members can't suspend other members
+    let res = member_client.suspend_member(ctx, member.clone()).await;
+    assert!(res.is_err());
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn expired_member_cant_get_credential(ctx: &mut Context) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+
+    let AuthorityInfo { admins, .. } = start_authority(ctx, secure_channels.clone(), 1).await?;
+    let admin = &admins[0];
+
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let expires_at = now()? + 3600;
+    admin
+        .client
+        .add_member_with_expiration(ctx, member.clone(), Default::default(), Some(expires_at))
+        .await
+        .unwrap();
+    let member_client = change_client_identifier(&admin.client, &member, None);
+
+    // the credential doesn't outlive the membership
+    let credential = member_client.issue_credential(ctx).await.unwrap();
+    assert!(credential.get_credential_data()?.expires_at <= expires_at);
+    let attributes = admin.client.show_member(ctx, member.clone()).await.unwrap();
+    assert_eq!(attributes.expires_at(), Some(expires_at));
+
+    // adding the member again, without an end date, keeps its end date
+    admin
+        .client
+        .add_member(ctx, member.clone(), Default::default())
+        .await
+        .unwrap();
+    let attributes = admin.client.show_member(ctx, member.clone()).await.unwrap();
+    assert_eq!(attributes.expires_at(), Some(expires_at));
+
+    admin
+        .client
+        .set_member_expiration(ctx, member.clone(), Some(now()? - 10.into()))
+        .await
+        .unwrap();
+    assert!(member_client.issue_credential(ctx).await.is_err());
+
+    // removing the end date restores the membership
+    admin
+        .client
+        .set_member_expiration(ctx, member.clone(), None)
+        .await
+        .unwrap();
+    assert!(member_client.issue_credential(ctx).await.is_ok());
+    let attributes = admin.client.show_member(ctx, member.clone()).await.unwrap();
+    assert_eq!(attributes.expires_at(), None);
+
+    Ok(())
+}
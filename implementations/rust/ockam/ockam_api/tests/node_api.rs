@@ -0,0 +1,171 @@
+use ockam::identity::utils::now;
+use ockam::identity::{
+    AttributesEntry, Identifier, SecureChannelListenerOptions, SecureChannelOptions,
+};
+use ockam::route;
+use ockam_abac::{
+    node_api_roles_policy_expression, Action, ResourceType, NODE_API_READER_ROLE,
+    NODE_API_ROLE_ATTRIBUTE,
+};
+use ockam_api::nodes::service::{NodeManagerCredentialRetrieverOptions, NodeManagerTrustOptions};
+use ockam_api::nodes::NODEMANAGER_ADDR;
+use ockam_api::test_utils::{start_manager_for_tests, NodeManagerHandle, TestNode};
+use ockam_core::api::{Reply, Request, Status};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::{Address, Result};
+use ockam_node::api::Client;
+use ockam_node::Context;
+
+#[ockam_macros::test]
+async fn node_api_requests_are_authorized_by_role(context: &mut Context) -> Result<()> {
+    TestNode::clean().await?;
+    let handle = start_manager_for_tests(context, None, None).await?;
+    let listener_address = expose_node_api(context, &handle)?;
+
+    // without a node-api policy, the node API is not restricted by roles
+    let anonymous = create_caller(&handle, None).await?;
+    let status = send_request(context, &handle, &anonymous, &listener_address, get()).await?;
+    assert_eq!(status, None);
+
+    // enforce the built-in roles
+    handle
+        .node_manager
+        .policies()
+        .store_policy_for_resource_type(
+            &ResourceType::NodeApi,
+            &Action::Admin,
+            &node_api_roles_policy_expression(),
+        )
+        .await?;
+
+    // a reader can only read the state of the node
+    let reader = create_caller(&handle, Some(NODE_API_READER_ROLE)).await?;
+    let status = send_request(context, &handle, &reader, &listener_address, get()).await?;
+    assert_eq!(status, None);
+    let status = send_request(context, &handle, &reader, &listener_address, delete()).await?;
+    assert_eq!(status, Some(Status::Forbidden));
+
+    // an identity without a role can't use the node API anymore
+    let status = send_request(context, &handle, &anonymous, &listener_address, get()).await?;
+    assert_eq!(status, Some(Status::Forbidden));
+
+    // the node identity is always authorized
+    let node = handle.node_manager.identifier();
+    let status = send_request(context, &handle, &node, &listener_address, get()).await?;
+    assert_eq!(status, None);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn node_api_requests_are_denied_by_a_policy_without_project_authority(
+    context: &mut Context,
+) -> Result<()> {
+    TestNode::clean().await?;
+    let trust_options = NodeManagerTrustOptions::new(
+        NodeManagerCredentialRetrieverOptions::None,
+        NodeManagerCredentialRetrieverOptions::None,
+        None,
+        NodeManagerCredentialRetrieverOptions::None,
+    );
+    let handle = start_manager_for_tests(context, None, Some(trust_options)).await?;
+    let listener_address = expose_node_api(context, &handle)?;
+
+    handle
+        .node_manager
+        .policies()
+        .store_policy_for_resource_type(
+            &ResourceType::NodeApi,
+            &Action::Admin,
+            &node_api_roles_policy_expression(),
+        )
+        .await?;
+
+    // the roles can't be verified without a project authority
+    let reader = create_caller(&handle, Some(NODE_API_READER_ROLE)).await?;
+    let status = send_request(context, &handle, &reader, &listener_address, get()).await?;
+    assert_eq!(status, Some(Status::Forbidden));
+
+    // the node identity is still authorized
+    let node = handle.node_manager.identifier();
+    let status = send_request(context, &handle, &node, &listener_address, get()).await?;
+    assert_eq!(status, None);
+
+    Ok(())
+}
+
+/// HELPERS
+
+/// Expose the node API through a secure channel listener and return the listener address
+fn expose_node_api(context: &Context, handle: &NodeManagerHandle) -> Result<Address> {
+    let listener_address = Address::random_local();
+    let options = SecureChannelListenerOptions::new();
+    context
+        .flow_controls()
+        .add_consumer(&NODEMANAGER_ADDR.into(), &options.spawner_flow_control_id());
+    handle.secure_channels.create_secure_channel_listener(
+        context,
+        &handle.node_manager.identifier(),
+        listener_address.clone(),
+        options,
+    )?;
+    Ok(listener_address)
+}
+
+/// Create an identity having a role attested by the authority of the node
+async fn create_caller(handle: &NodeManagerHandle, role: Option<&str>) -> Result<Identifier> {
+    let identities = handle.secure_channels.identities();
+    let caller = identities.identities_creation().create_identity().await?;
+    if let Some(role) = role {
+        let attributes = BTreeMap::from([(
+            NODE_API_ROLE_ATTRIBUTE.as_bytes().to_vec(),
+            role.as_bytes().to_vec(),
+        )]);
+        identities
+            .identities_attributes()
+            .put_attributes(
+                &caller,
+                AttributesEntry::new(
+                    attributes,
+                    now()?,
+                    None,
+                    handle.node_manager.project_authority(),
+                ),
+            )
+            .await?;
+    }
+    Ok(caller)
+}
+
+fn get() -> Request {
+    Request::get("/node/tcp/listener")
+}
+
+fn delete() -> Request {
+    Request::delete("/node/tcp/listener")
+}
+
+/// Send a request to the node API through a secure channel and return the error status
+/// of the response, if any
+async fn send_request(
+    context: &Context,
+    handle: &NodeManagerHandle,
+    caller: &Identifier,
+    listener_address: &Address,
+    request: Request,
+) -> Result<Option<Status>> {
+    let channel = handle
+        .secure_channels
+        .create_secure_channel(
+            context,
+            caller,
+            listener_address.clone(),
+            SecureChannelOptions::new(),
+        )
+        .await?;
+    let client = Client::new(&route![channel, NODEMANAGER_ADDR], None);
+    match client.tell(context, request).await? {
+        Reply::Successful(_) => Ok(None),
+        Reply::Failed(_, status) => Ok(status),
+    }
+}
@@ -0,0 +1,201 @@
+use crate::common::common::{default_configuration, start_authority_node};
+use ockam::identity::secure_channels;
+use ockam::identity::utils::now;
+use ockam_api::authority_node::OidcConfiguration;
+use ockam_api::enroll::enrollment::{EnrollStatus, Enrollment};
+use ockam_api::nodes::service::default_address::DefaultAddress;
+use ockam_api::nodes::NodeManager;
+use ockam_api::orchestrator::enroll::Token;
+use ockam_api::orchestrator::AuthorityNodeClient;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::Result;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::Context;
+use ockam_transport_tcp::TcpTransport;
+use p256::ecdsa::signature::Signer;
+use p256::ecdsa::{Signature, SigningKey};
+use serde_json::{json, Value};
+use std::io::Write;
+use tempfile::NamedTempFile;
+
+mod common;
+
+const ISSUER: &str = "https://token.example.com";
+
+#[ockam_macros::test]
+async fn identity_can_enroll_with_a_jwt(ctx: &mut Context) -> Result<()> {
+    let signing_key = signing_key();
+    let client = start_authority_with_oidc(ctx, &signing_key).await?;
+
+    let claims = json!({
+        "iss": ISSUER,
+        "aud": "ockam",
+        "sub": "repo:my-org/my-repo:ref:refs/heads/main",
+        "jti": "token-1",
+        "exp": now()?.0 + 300,
+        "repository": "my-org/my-repo",
+        "repository_owner": "my-org",
+        "realm_access": { "roles": ["deploy", "build"] }
+    });
+    let status = client
+        .enroll_with_jwt(ctx, sign(&signing_key, claims.clone()))
+        .await
+        .unwrap();
+    assert_eq!(status, EnrollStatus::EnrolledSuccessfully);
+
+    // the same token can't be used twice
+    let result = client
+        .enroll_with_jwt(ctx, sign(&signing_key, claims))
+        .await;
+    assert!(result.is_err());
+
+    // the credential contains the attributes mapped from the claims
+    let credential = client.issue_credential(ctx).await.unwrap();
+    let attributes: BTreeMap<String, String> = credential
+        .get_credential_data()?
+        .subject_attributes
+        .map
+        .into_iter()
+        .map(|(k, v)| {
+            (
+                String::from_utf8(k.to_vec()).unwrap(),
+                String::from_utf8(v.to_vec()).unwrap(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        attributes.get("repository"),
+        Some(&"my-org/my-repo".to_string())
+    );
+    assert_eq!(attributes.get("roles"), Some(&"deploy,build".to_string()));
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn invalid_jwts_are_rejected(ctx: &mut Context) -> Result<()> {
+    let signing_key = signing_key();
+    let client = start_authority_with_oidc(ctx, &signing_key).await?;
+    let valid_claims = json!({
+        "iss": ISSUER,
+        "aud": "ockam",
+        "exp": now()?.0 + 300,
+        "repository_owner": "my-org",
+    });
+
+    // wrong issuer
+    let mut claims = valid_claims.clone();
+    claims["iss"] = json!("https://other.example.com");
+    let result = client
+        .enroll_with_jwt(ctx, sign(&signing_key, claims))
+        .await;
+    assert!(result.is_err());
+
+    // wrong audience
+    let mut claims = valid_claims.clone();
+    claims["aud"] = json!("other-application");
+    let result = client
+        .enroll_with_jwt(ctx, sign(&signing_key, claims))
+        .await;
+    assert!(result.is_err());
+
+    // expired token
+    let mut claims = valid_claims.clone();
+    claims["exp"] = json!(now()?.0 - 3600);
+    let result = client
+        .enroll_with_jwt(ctx, sign(&signing_key, claims))
+        .await;
+    assert!(result.is_err());
+
+    // missing required claim
+    let mut claims = valid_claims.clone();
+    claims["repository_owner"] = json!("other-org");
+    let result = client
+        .enroll_with_jwt(ctx, sign(&signing_key, claims))
+        .await;
+    assert!(result.is_err());
+
+    // token signed by another key
+    let other_key = SigningKey::from_slice(&[2; 32]).unwrap();
+    let result = client
+        .enroll_with_jwt(ctx, sign(&other_key, valid_claims.clone()))
+        .await;
+    assert!(result.is_err());
+
+    // the identity is not a member, so it can't get a credential
+    assert!(client.issue_credential(ctx).await.is_err());
+
+    Ok(())
+}
+
+/// HELPERS
+
+fn signing_key() -> SigningKey {
+    SigningKey::from_slice(&[1; 32]).unwrap()
+}
+
+/// Start an authority accepting JWTs signed with the given key and return a client for a new identity
+async fn start_authority_with_oidc(
+    ctx: &Context,
+    signing_key: &SigningKey,
+) -> Result<AuthorityNodeClient> {
+    let point = signing_key.verifying_key().to_encoded_point(false);
+    let jwks = json!({
+        "keys": [{
+            "kty": "EC",
+            "crv": "P-256",
+            "kid": "test-key",
+            "use": "sig",
+            "x": base64_url::encode(point.x().unwrap()),
+            "y": base64_url::encode(point.y().unwrap()),
+        }]
+    });
+    let mut jwks_file = NamedTempFile::new().unwrap();
+    jwks_file.write_all(jwks.to_string().as_bytes()).unwrap();
+    let jwks_path = jwks_file.into_temp_path().keep().unwrap();
+
+    let mut configuration = default_configuration().await?;
+    configuration.oidc = Some(OidcConfiguration {
+        address: DefaultAddress::OIDC_AUTHENTICATOR.to_string(),
+        issuer: ISSUER.to_string(),
+        audience: "ockam".to_string(),
+        jwks: Some(jwks_path.to_string_lossy().to_string()),
+        claims: BTreeMap::from([
+            ("repository".to_string(), "repository".to_string()),
+            ("realm_access.roles".to_string(), "roles".to_string()),
+        ]),
+        required_claims: BTreeMap::from([("repository_owner".to_string(), "my-org".to_string())]),
+    });
+    start_authority_node(ctx, &configuration).await?;
+
+    let secure_channels = secure_channels().await?;
+    let member = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    NodeManager::authority_node_client(
+        &TcpTransport::create(ctx)?,
+        secure_channels,
+        &configuration.identifier,
+        &MultiAddr::try_from("/secure/api")?,
+        &member,
+        None,
+    )
+    .await
+}
+
+/// Create a JWT signed with ES256
+fn sign(signing_key: &SigningKey, claims: Value) -> Token {
+    let header = json!({ "alg": "ES256", "typ": "JWT", "kid": "test-key" });
+    let content = format!(
+        "{}.{}",
+        base64_url::encode(&header.to_string()),
+        base64_url::encode(&claims.to_string())
+    );
+    let signature: Signature = signing_key.sign(content.as_bytes());
+    Token(format!(
+        "{content}.{}",
+        base64_url::encode(&signature.to_bytes())
+    ))
+}
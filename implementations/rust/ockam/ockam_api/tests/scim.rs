@@ -0,0 +1,423 @@
+use crate::common::common::{default_configuration, start_authority_node};
+use ockam::identity::utils::now;
+use ockam::identity::{secure_channels, Identifier};
+use ockam_api::authenticator::{AuthorityMember, AuthorityMembersSqlxDatabase};
+use ockam_api::authority_node::ScimConfiguration;
+use ockam_api::config::lookup::InternetAddress;
+use ockam_api::enroll::enrollment::Enrollment;
+use ockam_api::nodes::NodeManager;
+use ockam_api::orchestrator::AuthorityNodeClient;
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::Result;
+use ockam_multiaddr::MultiAddr;
+use ockam_node::database::{DatabaseConfiguration, SqlxDatabase};
+use ockam_node::Context;
+use ockam_transport_tcp::TcpTransport;
+use reqwest::{Method, StatusCode};
+use serde_json::{json, Value};
+use std::net::TcpListener;
+
+mod common;
+
+const BEARER_TOKEN: &str = "scim-secret";
+const ENTERPRISE_SCHEMA: &str = "urn:ietf:params:scim:schemas:extension:enterprise:2.0:User";
+
+#[ockam_macros::test]
+async fn users_and_groups_are_provisioned_as_members(ctx: &mut Context) -> Result<()> {
+    let scim = start_authority_with_scim(ctx).await?;
+    let (identifier, member) = scim.member_client(ctx).await?;
+
+    // create a user and a group containing that user
+    let (status, user) = scim
+        .request(
+            Method::POST,
+            "/Users",
+            Some(json!({
+                "schemas": ["urn:ietf:params:scim:schemas:core:2.0:User", ENTERPRISE_SCHEMA],
+                "userName": identifier,
+                "externalId": "jane",
+                "title": "engineer",
+                ENTERPRISE_SCHEMA: { "department": "R&D" }
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(user["id"], json!(identifier));
+    assert_eq!(user["active"], json!(true));
+
+    let (status, group) = scim
+        .request(
+            Method::POST,
+            "/Groups",
+            Some(json!({
+                "displayName": "Admins",
+                "members": [{ "value": identifier }]
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let group_id = group["id"].as_str().unwrap().to_string();
+
+    // the member gets the mapped attributes and the names of its groups
+    assert_eq!(
+        credential_attributes(ctx, &member).await,
+        Some(BTreeMap::from([
+            ("department".to_string(), "R&D".to_string()),
+            ("groups".to_string(), "Admins".to_string()),
+            ("title".to_string(), "engineer".to_string()),
+        ]))
+    );
+
+    // the user can be found with a filter
+    let (status, users) = scim
+        .request(
+            Method::GET,
+            &format!("/Users?filter=userName%20eq%20%22{identifier}%22"),
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(users["totalResults"], json!(1));
+    assert_eq!(
+        users["Resources"][0]["groups"][0]["display"],
+        json!("Admins")
+    );
+
+    // a deactivated user can't get credentials anymore
+    let (status, _) = scim
+        .request(
+            Method::PATCH,
+            &format!("/Users/{identifier}"),
+            Some(patch(
+                json!([{ "op": "Replace", "path": "active", "value": "False" }]),
+            )),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(credential_attributes(ctx, &member).await, None);
+
+    let (status, _) = scim
+        .request(
+            Method::PATCH,
+            &format!("/Users/{identifier}"),
+            Some(patch(json!([
+                { "op": "replace", "value": { "active": true, "title": "manager" } }
+            ]))),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        credential_attributes(ctx, &member)
+            .await
+            .and_then(|a| a.get("title").cloned()),
+        Some("manager".to_string())
+    );
+
+    // removing the user from the group removes the groups attribute
+    let (status, group) = scim
+        .request(
+            Method::PATCH,
+            &format!("/Groups/{group_id}"),
+            Some(patch(json!([
+                { "op": "remove", "path": format!("members[value eq \"{identifier}\"]") }
+            ]))),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(group["members"], json!([]));
+    assert_eq!(
+        credential_attributes(ctx, &member)
+            .await
+            .and_then(|a| a.get("groups").cloned()),
+        None
+    );
+
+    // a deleted user is not a member anymore
+    let (status, _) = scim
+        .request(Method::DELETE, &format!("/Users/{identifier}"), None)
+        .await;
+    assert_eq!(status, StatusCode::NO_CONTENT);
+    let (status, _) = scim
+        .request(Method::GET, &format!("/Users/{identifier}"), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(credential_attributes(ctx, &member).await, None);
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn invalid_scim_requests_are_rejected(ctx: &mut Context) -> Result<()> {
+    let scim = start_authority_with_scim(ctx).await?;
+    let (identifier, _) = scim.member_client(ctx).await?;
+    let user = json!({ "userName": identifier });
+
+    // a bearer token is required
+    let response = reqwest::Client::new()
+        .get(scim.url("/Users"))
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = reqwest::Client::new()
+        .get(scim.url("/Users"))
+        .bearer_auth("wrong-token")
+        .send()
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // the user name must be an identifier
+    let (status, error) = scim
+        .request(Method::POST, "/Users", Some(json!({ "userName": "jane" })))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["scimType"], json!("invalidValue"));
+
+    // a user can't be created twice
+    let (status, _) = scim
+        .request(Method::POST, "/Users", Some(user.clone()))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, error) = scim.request(Method::POST, "/Users", Some(user)).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(error["scimType"], json!("uniqueness"));
+
+    // the user name can't be modified
+    let (status, error) = scim
+        .request(
+            Method::PATCH,
+            &format!("/Users/{identifier}"),
+            Some(patch(json!([
+                { "op": "replace", "path": "userName", "value": "jane" }
+            ]))),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["scimType"], json!("mutability"));
+
+    // the members of a group must be existing users
+    let (status, _) = scim
+        .request(
+            Method::POST,
+            "/Groups",
+            Some(json!({
+                "displayName": "Admins",
+                "members": [{ "value": "I0000000000000000000000000000000000000000000000000000000000000000" }]
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // only equality filters are supported
+    let (status, error) = scim
+        .request(Method::GET, "/Users?filter=userName%20sw%20%22I%22", None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(error["scimType"], json!("invalidFilter"));
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn existing_members_are_not_taken_over(ctx: &mut Context) -> Result<()> {
+    let scim = start_authority_with_scim(ctx).await?;
+    let (identifier, member) = scim.member_client(ctx).await?;
+
+    // the identity is already a member, added by an enroller
+    let enroller =
+        Identifier::try_from("I0000000000000000000000000000000000000000000000000000000000000001")?;
+    let members = AuthorityMembersSqlxDatabase::make_repository(
+        SqlxDatabase::create(&scim.database_configuration).await?,
+    );
+    members
+        .add_member(
+            &scim.authority,
+            AuthorityMember::new(
+                Identifier::try_from(identifier.as_str())?,
+                BTreeMap::from([(b"role".to_vec(), b"admin".to_vec())]),
+                enroller,
+                now()?,
+                false,
+            ),
+        )
+        .await?;
+
+    // it can't be provisioned, then deprovisioned with SCIM
+    let (status, error) = scim
+        .request(
+            Method::POST,
+            "/Users",
+            Some(json!({ "userName": identifier, "title": "engineer" })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(error["scimType"], json!("uniqueness"));
+
+    let (status, _) = scim
+        .request(Method::DELETE, &format!("/Users/{identifier}"), None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // the member keeps its attributes
+    assert_eq!(
+        credential_attributes(ctx, &member).await,
+        Some(BTreeMap::from([("role".to_string(), "admin".to_string())]))
+    );
+
+    Ok(())
+}
+
+#[test]
+fn scim_server_must_listen_on_a_loopback_address() {
+    let configuration = |address: &str| ScimConfiguration {
+        listener_address: InternetAddress::new(address).unwrap(),
+        bearer_token: BEARER_TOKEN.to_string(),
+        attributes: BTreeMap::new(),
+        groups_attribute: None,
+    };
+
+    assert!(configuration("127.0.0.1:4300").validate().is_ok());
+    assert!(configuration("[::1]:4300").validate().is_ok());
+    assert!(configuration("localhost:4300").validate().is_ok());
+    assert!(configuration("0.0.0.0:4300").validate().is_err());
+    assert!(configuration("10.0.0.1:4300").validate().is_err());
+    assert!(configuration("scim.example.com:4300").validate().is_err());
+}
+
+#[test]
+fn scim_server_must_not_set_reserved_attributes() {
+    let configuration = |attribute: &str, groups_attribute: Option<&str>| ScimConfiguration {
+        listener_address: InternetAddress::new("127.0.0.1:4300").unwrap(),
+        bearer_token: BEARER_TOKEN.to_string(),
+        attributes: BTreeMap::from([("title".to_string(), attribute.to_string())]),
+        groups_attribute: groups_attribute.map(|a| a.to_string()),
+    };
+
+    assert!(configuration("title", Some("groups")).validate().is_ok());
+    assert!(configuration("ockam-role", None).validate().is_err());
+    assert!(configuration("trust_context_id", None).validate().is_err());
+    assert!(configuration("title", Some("ockam-role"))
+        .validate()
+        .is_err());
+}
+
+/// HELPERS
+
+struct ScimTestServer {
+    base_url: String,
+    authority: Identifier,
+    database_configuration: DatabaseConfiguration,
+}
+
+impl ScimTestServer {
+    fn url(&self, path: &str) -> String {
+        format!("{}{}", self.base_url, path)
+    }
+
+    /// Send a request to the SCIM server and return the status and JSON body of the response
+    async fn request(
+        &self,
+        method: Method,
+        path: &str,
+        body: Option<Value>,
+    ) -> (StatusCode, Value) {
+        let mut request = reqwest::Client::new()
+            .request(method, self.url(path))
+            .bearer_auth(BEARER_TOKEN);
+        if let Some(body) = body {
+            request = request
+                .header("Content-Type", "application/scim+json")
+                .body(body.to_string());
+        }
+        let response = request.send().await.unwrap();
+        let status = response.status();
+        let body = response.text().await.unwrap();
+        let body = if body.is_empty() {
+            Value::Null
+        } else {
+            serde_json::from_str(&body).unwrap()
+        };
+        (status, body)
+    }
+
+    /// Create a new identity and a client to access the authority with that identity
+    async fn member_client(&self, ctx: &Context) -> Result<(String, AuthorityNodeClient)> {
+        let secure_channels = secure_channels().await?;
+        let member = secure_channels
+            .identities()
+            .identities_creation()
+            .create_identity()
+            .await?;
+        let client = NodeManager::authority_node_client(
+            &TcpTransport::create(ctx)?,
+            secure_channels,
+            &self.authority,
+            &MultiAddr::try_from("/secure/api")?,
+            &member,
+            None,
+        )
+        .await?;
+        Ok((member.to_string(), client))
+    }
+}
+
+async fn start_authority_with_scim(ctx: &Context) -> Result<ScimTestServer> {
+    let port = random_port();
+    let mut configuration = default_configuration().await?;
+    configuration.scim = Some(ScimConfiguration {
+        listener_address: InternetAddress::new(&format!("127.0.0.1:{port}")).unwrap(),
+        bearer_token: BEARER_TOKEN.to_string(),
+        attributes: BTreeMap::from([
+            ("title".to_string(), "title".to_string()),
+            (
+                format!("{ENTERPRISE_SCHEMA}:department"),
+                "department".to_string(),
+            ),
+        ]),
+        groups_attribute: Some("groups".to_string()),
+    });
+    start_authority_node(ctx, &configuration).await?;
+    Ok(ScimTestServer {
+        base_url: format!("http://127.0.0.1:{port}/scim/v2"),
+        authority: configuration.identifier.clone(),
+        database_configuration: configuration.database_configuration.clone(),
+    })
+}
+
+/// Return the attributes of a credential issued to a member, or None if no credential
+/// can be issued
+async fn credential_attributes(
+    ctx: &Context,
+    client: &AuthorityNodeClient,
+) -> Option<BTreeMap<String, String>> {
+    let credential = client.issue_credential(ctx).await.ok()?;
+    Some(
+        credential
+            .get_credential_data()
+            .unwrap()
+            .subject_attributes
+            .map
+            .into_iter()
+            .map(|(k, v)| {
+                (
+                    String::from_utf8(k.to_vec()).unwrap(),
+                    String::from_utf8(v.to_vec()).unwrap(),
+                )
+            })
+            .filter(|(k, _)| k != "trust_context_id" && k != "project")
+            .collect(),
+    )
+}
+
+fn patch(operations: Value) -> Value {
+    json!({
+        "schemas": ["urn:ietf:params:scim:api:messages:2.0:PatchOp"],
+        "Operations": operations
+    })
+}
+
+fn random_port() -> u16 {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    listener.local_addr().unwrap().port()
+}
@@ -11,24 +11,31 @@ use ockam::identity::{Identifier, Identity, TimestampInSeconds, Vault};
 use ockam::Context;
 use ockam_api::authenticator::{PreTrustedIdentities, PreTrustedIdentity};
 use ockam_api::authority_node;
-use ockam_api::authority_node::{Authority, OktaConfiguration};
+use ockam_api::authority_node::{
+    Authority, OidcConfiguration, OktaConfiguration, ScimConfiguration,
+};
 use ockam_api::colors::color_primary;
 use ockam_api::config::lookup::InternetAddress;
 use ockam_api::nodes::service::default_address::DefaultAddress;
 use ockam_core::compat::collections::BTreeMap;
 use ockam_core::compat::fmt;
+use ockam_core::env::get_env;
 
 use crate::node::util::run_ockam;
 use crate::util::embedded_node_that_is_not_stopped;
 use crate::util::foreground_args::{wait_for_exit_signal, ForegroundArgs};
 use crate::util::parsers::internet_address_parser;
 use crate::util::{async_cmd, local_cmd};
+use crate::value_parsers::parse_key_val;
 use crate::{docs, CommandGlobalOpts, Result};
 
 const LONG_ABOUT: &str = include_str!("./static/create/long_about.txt");
 const PREVIEW_TAG: &str = include_str!("../static/preview_tag.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/create/after_long_help.txt");
 
+/// Environment variable containing the bearer token of the SCIM server
+const SCIM_TOKEN_ENV: &str = "OCKAM_AUTHORITY_SCIM_TOKEN";
+
 /// Create an Authority node
 #[derive(Clone, Debug, Args)]
 #[command(
@@ -61,6 +68,11 @@ pub struct CreateCommand {
     )]
     tcp_listener_address: InternetAddress,
 
+    /// Name of this replica of the authority, when several authority nodes share the same
+    /// database and identity to be highly available. Each replica must have a different name
+    #[arg(long, value_name = "REPLICA_NAME")]
+    replica: Option<String>,
+
     /// Name of the Identity that the authority will use
     #[arg(long = "identity", value_name = "IDENTITY_NAME")]
     identity: Option<String>,
@@ -106,6 +118,43 @@ pub struct CreateCommand {
     #[arg(long, value_name = "ATTRIBUTE_NAMES", default_value = None)]
     attributes: Option<Vec<String>>,
 
+    /// OIDC: issuer of the JWTs accepted to enroll identities, for example https://token.actions.githubusercontent.com
+    #[arg(long, value_name = "URL", default_value = None, requires = "oidc_audience")]
+    oidc_issuer: Option<String>,
+
+    /// OIDC: expected audience of the JWTs. It is required with --oidc-issuer, so that the tokens
+    /// issued for other applications are rejected
+    #[arg(long, value_name = "AUDIENCE", default_value = None, requires = "oidc_issuer")]
+    oidc_audience: Option<String>,
+
+    /// OIDC: URL, or path of a local file, of the key set used to verify the JWTs signatures.
+    /// If not set, the key set is discovered with the OpenID configuration of the issuer.
+    /// A remote key set is retrieved again after the `max-age` of its `Cache-Control` header, or every hour
+    #[arg(long, value_name = "URL_OR_PATH", default_value = None, requires = "oidc_issuer")]
+    oidc_jwks: Option<String>,
+
+    /// OIDC: claim of the JWTs to copy into a member attribute, for example `repository=repository`
+    #[arg(long, value_name = "CLAIM=ATTRIBUTE", requires = "oidc_issuer", value_parser = parse_key_val::<String, String>)]
+    oidc_claim: Vec<(String, String)>,
+
+    /// OIDC: value that a claim must have for a JWT to be accepted, for example `repository_owner=my-org`
+    #[arg(long, value_name = "CLAIM=VALUE", requires = "oidc_issuer", value_parser = parse_key_val::<String, String>)]
+    oidc_required_claim: Vec<(String, String)>,
+
+    /// SCIM: address of the SCIM 2.0 server used to provision project members, for example 127.0.0.1:4300.
+    /// The server serves plain HTTP, so the address must be a loopback address, exposed with a reverse proxy terminating TLS.
+    /// The bearer token expected by the server is read from the OCKAM_AUTHORITY_SCIM_TOKEN environment variable
+    #[arg(long, value_name = "SOCKET_ADDRESS", default_value = None, value_parser = internet_address_parser)]
+    scim_listener_address: Option<InternetAddress>,
+
+    /// SCIM: user attribute to copy into a member attribute, for example `title=title`
+    #[arg(long, value_name = "SCIM_ATTRIBUTE=ATTRIBUTE", requires = "scim_listener_address", value_parser = parse_key_val::<String, String>)]
+    scim_attribute: Vec<(String, String)>,
+
+    /// SCIM: member attribute containing the names of the groups of a user
+    #[arg(long, value_name = "ATTRIBUTE", default_value = None, requires = "scim_listener_address")]
+    scim_groups_attribute: Option<String>,
+
     /// Full, hex-encoded Identity (change history) of the account authority to trust
     /// for account and project administrator credentials.
     #[arg(long, value_name = "ACCOUNT_AUTHORITY_CHANGE_HISTORY", default_value = None, value_parser = ChangeHistory::import_from_string
@@ -169,6 +218,11 @@ impl CreateCommand {
             args.push("--skip-is-running-check".to_string());
         }
 
+        if let Some(replica) = &self.replica {
+            args.push("--replica".to_string());
+            args.push(replica.clone());
+        }
+
         if self.logging_to_file() || !opts.terminal.is_tty() {
             args.push("--no-color".to_string());
         }
@@ -198,6 +252,46 @@ impl CreateCommand {
             });
         }
 
+        if let Some(oidc_issuer) = &self.oidc_issuer {
+            args.push("--oidc-issuer".to_string());
+            args.push(oidc_issuer.clone());
+        }
+
+        if let Some(oidc_audience) = &self.oidc_audience {
+            args.push("--oidc-audience".to_string());
+            args.push(oidc_audience.clone());
+        }
+
+        if let Some(oidc_jwks) = &self.oidc_jwks {
+            args.push("--oidc-jwks".to_string());
+            args.push(oidc_jwks.clone());
+        }
+
+        for (claim, attribute) in &self.oidc_claim {
+            args.push("--oidc-claim".to_string());
+            args.push(format!("{claim}={attribute}"));
+        }
+
+        for (claim, value) in &self.oidc_required_claim {
+            args.push("--oidc-required-claim".to_string());
+            args.push(format!("{claim}={value}"));
+        }
+
+        if let Some(scim_listener_address) = &self.scim_listener_address {
+            args.push("--scim-listener-address".to_string());
+            args.push(scim_listener_address.to_string());
+        }
+
+        for (scim_attribute, attribute) in &self.scim_attribute {
+            args.push("--scim-attribute".to_string());
+            args.push(format!("{scim_attribute}={attribute}"));
+        }
+
+        if let Some(scim_groups_attribute) = &self.scim_groups_attribute {
+            args.push("--scim-groups-attribute".to_string());
+            args.push(scim_groups_attribute.clone());
+        }
+
         if let Some(identity) = &self.identity {
             args.push("--identity".to_string());
             args.push(identity.clone());
@@ -259,11 +353,38 @@ impl CreateCommand {
 
     /// Given a Context start a node in a new OS process
     async fn create_background_node(&self, opts: CommandGlobalOpts) -> miette::Result<()> {
+        // Fail early if the SCIM token is missing, the child process inherits the environment
+        self.scim_configuration()?;
         // Spawn node in another, new process
         self.spawn_background_node(&opts).await?;
         Ok(())
     }
 
+    /// Return the configuration of the SCIM server if a SCIM listener address is specified.
+    /// The bearer token is taken from the environment so that it doesn't appear in the
+    /// arguments of the node process
+    fn scim_configuration(&self) -> miette::Result<Option<ScimConfiguration>> {
+        let Some(listener_address) = &self.scim_listener_address else {
+            return Ok(None);
+        };
+        let bearer_token = get_env::<String>(SCIM_TOKEN_ENV)
+            .into_diagnostic()?
+            .filter(|t| !t.is_empty())
+            .ok_or_else(|| {
+                miette!(
+                    "The {SCIM_TOKEN_ENV} environment variable must be set to start a SCIM server"
+                )
+            })?;
+        let configuration = ScimConfiguration {
+            listener_address: listener_address.clone(),
+            bearer_token,
+            attributes: self.scim_attribute.iter().cloned().collect(),
+            groups_attribute: self.scim_groups_attribute.clone(),
+        };
+        configuration.validate().into_diagnostic()?;
+        Ok(Some(configuration))
+    }
+
     /// Start an authority node:
     ///   - retrieve the node identity if the authority identity has been created before
     ///   - persist the node state
@@ -292,7 +413,12 @@ impl CreateCommand {
                         .await?;
                 }
                 Err(_) => {
-                    opts.state.create_identity_with_name(&identity_name).await?;
+                    // Another replica of the authority might create the identity at the same time
+                    if let Err(e) = opts.state.create_identity_with_name(&identity_name).await {
+                        if opts.state.get_named_identity(&identity_name).await.is_err() {
+                            return Err(e.into());
+                        }
+                    }
                 }
             }
         };
@@ -318,6 +444,20 @@ impl CreateCommand {
             _ => None,
         };
 
+        let oidc_configuration = match (&self.oidc_issuer, &self.oidc_audience) {
+            (Some(issuer), Some(audience)) => Some(OidcConfiguration {
+                address: DefaultAddress::OIDC_AUTHENTICATOR.to_string(),
+                issuer: issuer.clone(),
+                audience: audience.clone(),
+                jwks: self.oidc_jwks.clone(),
+                claims: self.oidc_claim.iter().cloned().collect(),
+                required_claims: self.oidc_required_claim.iter().cloned().collect(),
+            }),
+            _ => None,
+        };
+
+        let scim_configuration = self.scim_configuration()?;
+
         let now = now().into_diagnostic()?;
         let trusted_identities = self.trusted_identities(now, &node.clone().identifier());
 
@@ -348,6 +488,8 @@ impl CreateCommand {
             no_direct_authentication: self.no_direct_authentication,
             no_token_enrollment: self.no_token_enrollment,
             okta: okta_configuration,
+            oidc: oidc_configuration,
+            scim: scim_configuration,
             account_authority,
             enforce_admin_checks: self.enforce_admin_checks,
             disable_trust_context_id: self.disable_trust_context_id,
@@ -414,8 +556,12 @@ impl CreateCommand {
 
     // The name of the authority node is the name of the authority to make sure that there are
     // no 2 authority nodes with the same name.
+    // Replicas of the same authority are distinguished by their replica name.
     pub fn node_name(&self) -> String {
-        self.authority_name()
+        match &self.replica {
+            Some(replica) => format!("{}-{replica}", self.authority_name()),
+            None => self.authority_name(),
+        }
     }
 }
 
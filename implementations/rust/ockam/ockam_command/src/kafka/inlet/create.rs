@@ -11,14 +11,17 @@ use crate::{
     Command, CommandGlobalOpts,
 };
 use async_trait::async_trait;
-use clap::{command, Args};
+use clap::{command, Args, ValueEnum};
 use colorful::Colorful;
 use miette::miette;
 use ockam::transport::SchemeHostnamePort;
 use ockam_abac::PolicyExpression;
 use ockam_api::colors::{color_primary, color_warn};
 use ockam_api::config::lookup::InternetAddress;
-use ockam_api::kafka::{ConsumerPublishing, ConsumerResolution};
+use ockam_api::kafka::{
+    ConsumerPublishing, ConsumerResolution, KafkaHeadersEncryption, KafkaKeyEncryption,
+    KafkaRecordFormat, DETERMINISTIC_KEY_SECRET_LENGTH,
+};
 use ockam_api::nodes::models::services::{StartKafkaInletRequest, StartServiceRequest};
 use ockam_api::nodes::BackgroundNodeClient;
 use ockam_api::output::Output;
@@ -28,7 +31,9 @@ use ockam_core::api::Request;
 use ockam_multiaddr::MultiAddr;
 use ockam_node::Context;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::fmt::Write;
+use std::path::PathBuf;
 
 /// Create a new Kafka Inlet.
 /// Kafka clients v3.7.0 and earlier are supported.
@@ -100,7 +105,9 @@ pub struct CreateCommand {
     )]
     pub no_content_encryption: bool,
 
-    /// The fields to encrypt in the kafka messages, assuming the record is a valid JSON map.
+    /// The fields to encrypt in the kafka messages. The records must be valid for the
+    /// `--record-format`. Nested Avro and Protobuf fields can be selected with a dotted path,
+    /// e.g. `customer.email`.
     /// By default, the whole record is encrypted.
     #[arg(
         long,
@@ -110,6 +117,55 @@ pub struct CreateCommand {
     )]
     pub encrypted_fields: Vec<String>,
 
+    /// The format of the kafka records when encrypting specific fields
+    #[arg(long, value_enum, default_value_t = RecordFormatArg::Json)]
+    pub record_format: RecordFormatArg,
+
+    /// An Avro schema used by the records, with its schema registry id, in the format
+    /// `<ID>=<PATH>`. Required when the record format is `avro`
+    #[arg(long = "avro-schema", value_name = "ID=PATH", value_parser = avro_schema_parser)]
+    pub avro_schemas: Vec<(u32, PathBuf)>,
+
+    /// The Protobuf descriptor set describing the records, as produced by
+    /// `protoc --include_imports --descriptor_set_out`. Required when the record format is `protobuf`
+    #[arg(long, value_name = "PATH")]
+    pub protobuf_descriptor_set: Option<PathBuf>,
+
+    /// The fully qualified name of the Protobuf message type of the records, e.g. `shop.Order`.
+    /// Required when the record format is `protobuf`
+    #[arg(long, value_name = "NAME")]
+    pub protobuf_message_type: Option<String>,
+
+    /// How to encrypt the records keys. With `randomized`, keys are encrypted like the records
+    /// values and topics compaction doesn't work anymore. With `deterministic`, a key is always
+    /// encrypted to the same value using `--key-secret`, so that partitioning and compaction keep working.
+    /// By default, the keys are not encrypted
+    #[arg(long, value_enum, default_value_t = KeyEncryptionArg::None)]
+    pub key_encryption: KeyEncryptionArg,
+
+    /// The hex-encoded 32 bytes secret used to encrypt the keys deterministically.
+    /// It must be the same for all the producers and consumers of the topics
+    #[arg(
+        long,
+        env = "OCKAM_KAFKA_KEY_SECRET",
+        hide_env_values = true,
+        value_name = "HEX"
+    )]
+    pub key_secret: Option<String>,
+
+    /// The names of the records headers to encrypt. Only the headers values are encrypted
+    #[arg(
+        long = "encrypted-header",
+        alias = "encrypted-headers",
+        value_name = "NAME",
+        conflicts_with = "encrypt_all_headers"
+    )]
+    pub encrypted_headers: Vec<String>,
+
+    /// Encrypt the values of all the records headers
+    #[arg(long)]
+    pub encrypt_all_headers: bool,
+
     #[arg(help = docs::about("\
     Policy expression that will be used for access control to the Kafka Inlet. \
     If you don't provide it, the policy set for the \"tcp-inlet\" resource type will be used. \
@@ -181,6 +237,9 @@ impl Command for CreateCommand {
                 cmd.to.clone(),
                 !cmd.no_content_encryption,
                 cmd.encrypted_fields.clone(),
+                cmd.kafka_record_format()?,
+                cmd.kafka_key_encryption()?,
+                cmd.kafka_headers_encryption(),
                 consumer_resolution,
                 consumer_publishing,
                 cmd.inlet_policy_expression.clone(),
@@ -244,6 +303,36 @@ impl CreateCommand {
             ));
         }
 
+        if self.record_format != RecordFormatArg::Json && self.encrypted_fields.is_empty() {
+            return Err(miette!(
+                "The record format {} requires at least one encrypted field",
+                color_primary(self.record_format.to_string())
+            ));
+        }
+        if self.record_format == RecordFormatArg::Avro && self.avro_schemas.is_empty() {
+            return Err(miette!(
+                "At least one Avro schema must be provided with {}",
+                color_primary("--avro-schema")
+            ));
+        }
+        if self.record_format == RecordFormatArg::Protobuf
+            && (self.protobuf_descriptor_set.is_none() || self.protobuf_message_type.is_none())
+        {
+            return Err(miette!(
+                "The Protobuf record format requires {} and {}",
+                color_primary("--protobuf-descriptor-set"),
+                color_primary("--protobuf-message-type")
+            ));
+        }
+
+        if self.key_encryption == KeyEncryptionArg::Deterministic && self.key_secret.is_none() {
+            return Err(miette!(
+                "The deterministic key encryption requires a secret, set with {} or the {} environment variable",
+                color_primary("--key-secret"),
+                color_primary("OCKAM_KAFKA_KEY_SECRET")
+            ));
+        }
+
         self.to = process_nodes_multiaddr(&self.to, &opts.state).await?;
         Ok(self)
     }
@@ -251,6 +340,108 @@ impl CreateCommand {
     fn brokers_port_range(&self) -> PortRange {
         self.brokers_port_range.unwrap()
     }
+
+    /// Read the schemas referenced by the command arguments
+    fn kafka_record_format(&self) -> miette::Result<KafkaRecordFormat> {
+        Ok(match self.record_format {
+            RecordFormatArg::Json => KafkaRecordFormat::Json,
+            RecordFormatArg::Avro => {
+                let mut schemas = BTreeMap::new();
+                for (id, path) in &self.avro_schemas {
+                    let schema = std::fs::read_to_string(path).map_err(|e| {
+                        miette!("Cannot read the Avro schema {}: {e}", path.display())
+                    })?;
+                    schemas.insert(*id, schema);
+                }
+                KafkaRecordFormat::Avro { schemas }
+            }
+            RecordFormatArg::Protobuf => {
+                let path = self
+                    .protobuf_descriptor_set
+                    .as_ref()
+                    .ok_or(miette!("Missing Protobuf descriptor set"))?;
+                let descriptor_set = std::fs::read(path).map_err(|e| {
+                    miette!(
+                        "Cannot read the Protobuf descriptor set {}: {e}",
+                        path.display()
+                    )
+                })?;
+                KafkaRecordFormat::Protobuf {
+                    descriptor_set,
+                    message_type: self
+                        .protobuf_message_type
+                        .clone()
+                        .ok_or(miette!("Missing Protobuf message type"))?,
+                }
+            }
+        })
+    }
+
+    fn kafka_key_encryption(&self) -> miette::Result<KafkaKeyEncryption> {
+        Ok(match self.key_encryption {
+            KeyEncryptionArg::None => KafkaKeyEncryption::None,
+            KeyEncryptionArg::Randomized => KafkaKeyEncryption::Randomized,
+            KeyEncryptionArg::Deterministic => {
+                let secret = self
+                    .key_secret
+                    .as_ref()
+                    .and_then(|secret| hex::decode(secret.trim()).ok())
+                    .filter(|secret| secret.len() == DETERMINISTIC_KEY_SECRET_LENGTH)
+                    .ok_or(miette!(
+                        "The key secret must be a hex-encoded {DETERMINISTIC_KEY_SECRET_LENGTH} bytes value"
+                    ))?;
+                KafkaKeyEncryption::Deterministic { secret }
+            }
+        })
+    }
+
+    fn kafka_headers_encryption(&self) -> KafkaHeadersEncryption {
+        if self.encrypt_all_headers {
+            KafkaHeadersEncryption::All
+        } else if !self.encrypted_headers.is_empty() {
+            KafkaHeadersEncryption::Selected(self.encrypted_headers.clone())
+        } else {
+            KafkaHeadersEncryption::None
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum KeyEncryptionArg {
+    #[default]
+    None,
+    Randomized,
+    Deterministic,
+}
+
+#[derive(Clone, Debug, Default, ValueEnum, PartialEq, Eq)]
+pub enum RecordFormatArg {
+    #[default]
+    Json,
+    Avro,
+    Protobuf,
+}
+
+impl std::fmt::Display for RecordFormatArg {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RecordFormatArg::Json => write!(f, "json"),
+            RecordFormatArg::Avro => write!(f, "avro"),
+            RecordFormatArg::Protobuf => write!(f, "protobuf"),
+        }
+    }
+}
+
+/// Parse an Avro schema argument: `<ID>=<PATH>`
+fn avro_schema_parser(input: &str) -> miette::Result<(u32, PathBuf)> {
+    let (id, path) = input
+        .split_once('=')
+        .ok_or(miette!("Expected <ID>=<PATH>, got {input}"))?;
+    let id = id
+        .trim()
+        .parse::<u32>()
+        .map_err(|_| miette!("The Avro schema id {id} must be a positive integer"))?;
+    Ok((id, PathBuf::from(path.trim())))
 }
 
 #[derive(Serialize)]
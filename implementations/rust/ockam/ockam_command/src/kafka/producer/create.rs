@@ -53,6 +53,14 @@ impl CreateCommand {
             no_publishing: false,
             no_content_encryption: false,
             encrypted_fields: vec![],
+            record_format: Default::default(),
+            avro_schemas: vec![],
+            protobuf_descriptor_set: None,
+            protobuf_message_type: None,
+            key_encryption: Default::default(),
+            key_secret: None,
+            encrypted_headers: vec![],
+            encrypt_all_headers: false,
             inlet_policy_expression: None,
             consumer_policy_expression: None,
             producer_policy_expression: None,
@@ -12,7 +12,8 @@ use ockam::identity::{Credential, Identifier, Identity};
 use ockam_api::output::{human_readable_time, Output};
 
 use ockam_vault::{
-    ECDSASHA256CurveP256PublicKey, EdDSACurve25519PublicKey, VerifyingPublicKey, X25519PublicKey,
+    ECDSASHA256CurveP256PublicKey, ECDSASHA384CurveP384PublicKey, EdDSACurve25519PublicKey,
+    VerifyingPublicKey, X25519PublicKey,
 };
 
 pub struct X25519PublicKeyDisplay(pub X25519PublicKey);
@@ -39,6 +40,14 @@ impl fmt::Display for P256PublicKeyDisplay {
     }
 }
 
+pub struct P384PublicKeyDisplay(pub ECDSASHA384CurveP384PublicKey);
+
+impl fmt::Display for P384PublicKeyDisplay {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "P384: {}", hex::encode(self.0 .0))
+    }
+}
+
 pub struct PurposePublicKeyDisplay(pub PurposePublicKey);
 
 impl fmt::Display for PurposePublicKeyDisplay {
@@ -66,6 +75,13 @@ impl fmt::Display for PurposePublicKeyDisplay {
                         P256PublicKeyDisplay(key.clone())
                     )?;
                 }
+                CredentialVerifyingKey::ECDSASHA384CurveP384(key) => {
+                    writeln!(
+                        f,
+                        "Credentials Key -> {}",
+                        P384PublicKeyDisplay(key.clone())
+                    )?;
+                }
             },
         }
 
@@ -293,6 +309,9 @@ impl fmt::Display for VerifyingPublicKeyDisplay {
             VerifyingPublicKey::ECDSASHA256CurveP256(value) => {
                 write!(f, "ECDSASHA256CurveP256: {}", hex::encode(value.0))
             }
+            VerifyingPublicKey::ECDSASHA384CurveP384(value) => {
+                write!(f, "ECDSASHA384CurveP384: {}", hex::encode(value.0))
+            }
         }
     }
 }
@@ -309,6 +328,9 @@ impl Serialize for VerifyingPublicKeyDisplay {
             VerifyingPublicKey::ECDSASHA256CurveP256(value) => {
                 format!("ECDSASHA256CurveP256: {}", hex::encode(value.0))
             }
+            VerifyingPublicKey::ECDSASHA384CurveP384(value) => {
+                format!("ECDSASHA384CurveP384: {}", hex::encode(value.0))
+            }
         })
     }
 }
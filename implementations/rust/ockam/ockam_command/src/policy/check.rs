@@ -0,0 +1,161 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::{Context as _, IntoDiagnostic};
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_abac::{Action, PolicyCheckResult, PolicyCheckSubject, PolicyExpression};
+use ockam_abac::{ResourceName, ResourceType};
+use ockam_api::colors::{color_primary, color_warn};
+use ockam_api::nodes::models::policies::{CheckPolicyRequest, ResourceTypeOrName};
+use ockam_api::nodes::{BackgroundNodeClient, Policies};
+use ockam_api::{fmt_info, fmt_log};
+
+use crate::docs;
+use crate::{Command, CommandGlobalOpts};
+
+use super::{action_parser, resource_type_parser};
+
+const LONG_ABOUT: &str = include_str!("./static/check/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/check/after_long_help.txt");
+
+/// Check which identities would gain or lose access with a new policy
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct CheckCommand {
+    #[arg(long, display_order = 900, id = "NODE_NAME")]
+    pub at: Option<String>,
+
+    #[arg(
+        long,
+        conflicts_with = "resource",
+        value_parser = resource_type_parser
+    )]
+    pub resource_type: Option<ResourceType>,
+
+    #[arg(long)]
+    pub resource: Option<ResourceName>,
+
+    /// The candidate policy expression
+    #[arg(long, visible_alias = "expression", id = "POLICY_EXPRESSION")]
+    pub allow: PolicyExpression,
+
+    /// The action to check the policy for
+    #[arg(long, default_value_t = Action::HandleMessage, value_parser = action_parser)]
+    pub action: Action,
+
+    /// Path to a JSON file with the identities to check and their attributes, for example
+    /// '{"I124...": {"role": "admin"}}'. By default, the identities having attributes stored
+    /// on the node are checked
+    #[arg(long, value_name = "PATH")]
+    pub attributes_file: Option<PathBuf>,
+}
+
+#[async_trait]
+impl Command for CheckCommand {
+    const NAME: &'static str = "policy check";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let resource = ResourceTypeOrName::new(self.resource_type.as_ref(), self.resource.as_ref())
+            .into_diagnostic()?;
+        let subjects = match &self.attributes_file {
+            Some(path) => Some(read_subjects(path)?),
+            None => None,
+        };
+
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+        let request = CheckPolicyRequest::new(resource, self.action, self.allow, subjects);
+        let check = node.check_policy(ctx, &request).await?;
+
+        let mut plain = String::new();
+        match &check.current_expression {
+            Some(current) => writeln!(
+                plain,
+                "{}",
+                fmt_info!("Current policy: {}", color_primary(current.to_string()))
+            ),
+            None => writeln!(plain, "{}", fmt_info!("There is no current policy")),
+        }
+        .into_diagnostic()?;
+        writeln!(
+            plain,
+            "{}",
+            fmt_info!(
+                "Candidate policy: {}",
+                color_primary(check.candidate_expression.to_string())
+            )
+        )
+        .into_diagnostic()?;
+
+        write_identities(&mut plain, "would gain access", &check.gained_access())?;
+        write_identities(&mut plain, "would lose access", &check.lost_access())?;
+        let unchanged =
+            check.results.len() - check.gained_access().len() - check.lost_access().len();
+        write!(
+            plain,
+            "{}",
+            fmt_info!("{unchanged} identities would keep the same access")
+        )
+        .into_diagnostic()?;
+
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&check).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+fn write_identities(
+    output: &mut String,
+    change: &str,
+    results: &[&PolicyCheckResult],
+) -> miette::Result<()> {
+    writeln!(
+        output,
+        "{}",
+        fmt_info!("{} identities {change}", results.len())
+    )
+    .into_diagnostic()?;
+    for result in results {
+        writeln!(
+            output,
+            "{}",
+            fmt_log!("  {}", color_warn(result.identifier.to_string()))
+        )
+        .into_diagnostic()?;
+    }
+    Ok(())
+}
+
+/// Read the identities to check, with the attributes of their credential
+fn read_subjects(path: &PathBuf) -> miette::Result<Vec<PolicyCheckSubject>> {
+    let contents = std::fs::read_to_string(path)
+        .into_diagnostic()
+        .wrap_err(format!("Cannot read the file {}", path.display()))?;
+    let identities: BTreeMap<Identifier, BTreeMap<String, String>> =
+        serde_json::from_str(&contents)
+            .into_diagnostic()
+            .wrap_err("Cannot parse the identities attributes")?;
+    Ok(identities
+        .into_iter()
+        .map(|(identifier, attributes)| PolicyCheckSubject {
+            identifier,
+            attributes: Some(
+                attributes
+                    .into_iter()
+                    .map(|(k, v)| (k.into_bytes(), v.into_bytes()))
+                    .collect(),
+            ),
+        })
+        .collect())
+}
@@ -0,0 +1,126 @@
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+
+use ockam::Context;
+use ockam_abac::{explain, Action, Env, PolicyDecision, PolicyDecisionsQuery, ResourceName};
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+use ockam_api::nodes::{BackgroundNodeClient, Policies};
+use ockam_api::output::Output;
+
+use crate::docs;
+use crate::{Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/decisions/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/decisions/after_long_help.txt");
+
+/// Show the access control decisions recorded by a node
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct DecisionsCommand {
+    #[arg(long, display_order = 900, id = "NODE_NAME")]
+    pub at: Option<String>,
+
+    /// Only show the decisions for this resource
+    #[arg(long)]
+    pub resource: Option<ResourceName>,
+
+    /// Only show the decisions for this action
+    #[arg(long)]
+    pub action: Option<Action>,
+
+    /// Only show the denied accesses
+    #[arg(long)]
+    pub denied: bool,
+
+    /// Maximum number of decisions to show, the most recent ones first
+    #[arg(long, default_value_t = 20)]
+    pub limit: u64,
+
+    /// Show the value of each evaluated sub-expression of the policy
+    #[arg(long)]
+    pub explain: bool,
+
+    /// Delete the recorded decisions
+    #[arg(long, conflicts_with_all = ["resource", "action", "denied", "explain"])]
+    pub delete: bool,
+}
+
+#[async_trait]
+impl Command for DecisionsCommand {
+    const NAME: &'static str = "policy decisions";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let node = BackgroundNodeClient::create(ctx, &opts.state, &self.at).await?;
+
+        if self.delete {
+            node.delete_policy_decisions(ctx).await?;
+            opts.terminal
+                .stdout()
+                .plain(fmt_ok!(
+                    "The policy decisions of node {} have been deleted",
+                    color_primary(node.node_name())
+                ))
+                .write_line()?;
+            return Ok(());
+        }
+
+        let query = PolicyDecisionsQuery {
+            resource_name: self.resource.clone(),
+            action: self.action.clone(),
+            denied_only: self.denied,
+            limit: self.limit,
+        };
+        let decisions = node.list_policy_decisions(ctx, &query).await?;
+
+        let plain = if self.explain {
+            let mut plain = String::new();
+            for decision in &decisions {
+                writeln!(plain, "{}", explained(decision)?).into_diagnostic()?;
+            }
+            if decisions.is_empty() {
+                plain = format!("No policy decisions on Node {}", node.node_name());
+            }
+            plain
+        } else {
+            opts.terminal.build_list(
+                &decisions,
+                &format!("No policy decisions on Node {}", node.node_name()),
+            )?
+        };
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json(serde_json::to_string(&decisions).into_diagnostic()?)
+            .write_line()?;
+        Ok(())
+    }
+}
+
+/// Return the description of a decision, followed by the evaluation of its policy expression
+/// with the recorded attributes
+fn explained(decision: &PolicyDecision) -> miette::Result<String> {
+    let mut environment = Env::new();
+    for (name, value) in &decision.attributes {
+        environment.put(name.as_str(), value.clone());
+    }
+    let explanation = explain(&decision.expression, &environment);
+
+    let mut output = decision.item()?;
+    if decision.reason.is_some() {
+        // no policy expression was evaluated
+        return Ok(output);
+    }
+    writeln!(output, "\nEvaluation:").into_diagnostic()?;
+    for line in explanation.to_string().lines() {
+        writeln!(output, "  {line}").into_diagnostic()?;
+    }
+    Ok(output)
+}
@@ -19,6 +19,8 @@ use crate::terminal::tui::DeleteCommandTui;
 use crate::tui::PluralTerm;
 use crate::util::async_cmd;
 
+use super::action_parser;
+
 #[derive(Clone, Debug, Args)]
 pub struct DeleteCommand {
     resource: Option<ResourceTypeOrName>,
@@ -26,6 +28,10 @@ pub struct DeleteCommand {
     #[arg(long, display_order = 900, id = "NODE_NAME")]
     at: Option<String>,
 
+    /// The action the policy applies to
+    #[arg(long, default_value_t = Action::HandleMessage, value_parser = action_parser)]
+    action: Action,
+
     /// Confirm the deletion without prompting
     #[arg(display_order = 901, long, short)]
     yes: bool,
@@ -119,7 +125,7 @@ impl DeleteCommandTui for DeleteTui {
             ResourceTypeOrName::Name(resource.into())
         };
         self.node
-            .delete_policy(&self.ctx, &resource, &Action::HandleMessage)
+            .delete_policy(&self.ctx, &resource, &self.cmd.action)
             .await?;
         let resource_kind = match resource {
             ResourceTypeOrName::Type(_) => "resource type",
@@ -3,15 +3,19 @@ use std::str::FromStr;
 use clap::{Args, Subcommand};
 use miette::miette;
 
-use ockam_abac::ResourceType;
+use ockam_abac::{Action, ResourceType};
 
+use crate::policy::check::CheckCommand;
 pub use crate::policy::create::CreateCommand;
+use crate::policy::decisions::DecisionsCommand;
 use crate::policy::delete::DeleteCommand;
 use crate::policy::list::ListCommand;
 use crate::policy::show::ShowCommand;
 use crate::{Command, CommandGlobalOpts};
 
+mod check;
 mod create;
+mod decisions;
 mod delete;
 mod list;
 mod show;
@@ -29,6 +33,8 @@ pub enum PolicySubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    Decisions(DecisionsCommand),
+    Check(CheckCommand),
 }
 
 impl PolicySubcommand {
@@ -38,6 +44,8 @@ impl PolicySubcommand {
             PolicySubcommand::Show(c) => c.name(),
             PolicySubcommand::Delete(c) => c.name(),
             PolicySubcommand::List(c) => c.name(),
+            PolicySubcommand::Decisions(c) => c.name(),
+            PolicySubcommand::Check(c) => c.name(),
         }
     }
 }
@@ -49,6 +57,8 @@ impl PolicyCommand {
             PolicySubcommand::Show(c) => c.run(opts),
             PolicySubcommand::Delete(c) => c.run(opts),
             PolicySubcommand::List(c) => c.run(opts),
+            PolicySubcommand::Decisions(c) => c.run(opts),
+            PolicySubcommand::Check(c) => c.run(opts),
         }
     }
 
@@ -63,3 +73,10 @@ pub(crate) fn resource_type_parser(input: &str) -> miette::Result<ResourceType>
         miette!(format!("Valid values are: {valid_values}"))
     })
 }
+
+pub(crate) fn action_parser(input: &str) -> miette::Result<Action> {
+    Action::from_str(input).map_err(|_| {
+        let valid_values = Action::join_enum_values_as_string();
+        miette!(format!("Valid values are: {valid_values}"))
+    })
+}
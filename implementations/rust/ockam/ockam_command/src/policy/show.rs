@@ -19,12 +19,18 @@ use crate::terminal::tui::ShowCommandTui;
 use crate::tui::PluralTerm;
 use crate::util::async_cmd;
 
+use super::action_parser;
+
 #[derive(Clone, Debug, Args)]
 pub struct ShowCommand {
     resource: Option<ResourceTypeOrName>,
 
     #[arg(long, display_order = 900, id = "NODE_NAME")]
     at: Option<String>,
+
+    /// The action the policy applies to
+    #[arg(long, default_value_t = Action::HandleMessage, value_parser = action_parser)]
+    action: Action,
 }
 
 impl ShowCommand {
@@ -48,6 +54,7 @@ pub struct ShowTui {
     opts: CommandGlobalOpts,
     node: BackgroundNodeClient,
     resource: Option<ResourceTypeOrName>,
+    action: Action,
 }
 
 impl ShowTui {
@@ -62,6 +69,7 @@ impl ShowTui {
             opts,
             node,
             resource: cmd.resource,
+            action: cmd.action,
         };
         tui.show().await
     }
@@ -115,7 +123,7 @@ impl ShowCommandTui for ShowTui {
         };
         let policy = self
             .node
-            .show_policy(&self.ctx, &resource, &Action::HandleMessage)
+            .show_policy(&self.ctx, &resource, &self.action)
             .await?;
         let resource_kind = match resource {
             ResourceTypeOrName::Type(_) => "resource type",
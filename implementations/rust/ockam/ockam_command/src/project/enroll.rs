@@ -22,6 +22,7 @@ use ockam_api::enroll::enrollment::{EnrollStatus, Enrollment};
 use ockam_api::enroll::oidc_service::OidcService;
 use ockam_api::enroll::okta_oidc_provider::OktaOidcProvider;
 use ockam_api::nodes::InMemoryNode;
+use ockam_api::orchestrator::enroll::Token;
 use ockam_api::orchestrator::project::models::OktaAuth0;
 use ockam_api::orchestrator::AuthorityNodeClient;
 use ockam_api::output::{human_readable_time, Output};
@@ -31,7 +32,7 @@ use ockam_api::{fmt_log, fmt_ok};
 const LONG_ABOUT: &str = include_str!("./static/enroll/long_about.txt");
 const AFTER_LONG_HELP: &str = include_str!("./static/enroll/after_long_help.txt");
 
-/// Use an enrollment ticket, or Okta, to enroll an identity with a project
+/// Use an enrollment ticket, Okta, or a JWT to enroll an identity with a project
 #[derive(Clone, Args)]
 #[command(
 long_about = docs::about(LONG_ABOUT),
@@ -57,6 +58,16 @@ pub struct EnrollCommand {
     #[arg(display_order = 900, long = "okta", group = "authentication_method")]
     pub okta: bool,
 
+    /// Use a JWT issued by an OIDC provider trusted by the project authority, instead of an enrollment ticket.
+    /// The value is either the token itself or the path of a file containing it
+    #[arg(
+        display_order = 901,
+        long = "jwt",
+        value_name = "JWT_OR_PATH",
+        group = "authentication_method"
+    )]
+    pub jwt: Option<String>,
+
     #[command(flatten)]
     pub retry_opts: RetryOpts,
 
@@ -128,6 +139,9 @@ impl Command for EnrollCommand {
         // Enroll if applicable
         if self.okta {
             self.use_okta(ctx, &opts, &authority_node_client).await?;
+        } else if let Some(jwt) = &self.jwt {
+            self.use_jwt(ctx, &opts, &authority_node_client, jwt)
+                .await?;
         } else if let Some(enrollment_ticket) = enrollment_ticket {
             self.use_enrollment_ticket(ctx, &opts, &authority_node_client, enrollment_ticket)
                 .await?;
@@ -215,6 +229,34 @@ impl EnrollCommand {
         Ok(())
     }
 
+    async fn use_jwt(
+        &self,
+        ctx: &Context,
+        opts: &CommandGlobalOpts,
+        authority_node_client: &AuthorityNodeClient,
+        jwt: &str,
+    ) -> Result<()> {
+        let token = match std::fs::read_to_string(jwt) {
+            Ok(content) => content.trim().to_string(),
+            Err(_) => jwt.trim().to_string(),
+        };
+        let enroll_status = {
+            let pb = opts.terminal.spinner();
+            if let Some(pb) = pb.as_ref() {
+                pb.set_message("Using a JWT to enroll identity...");
+            }
+            authority_node_client
+                .enroll_with_jwt(ctx, Token(token))
+                .await
+                .map_err(Error::Retry)?
+        };
+        if enroll_status == EnrollStatus::AlreadyEnrolled {
+            opts.terminal
+                .write_line(fmt_ok!("Identity is already enrolled with the project"))?;
+        }
+        Ok(())
+    }
+
     async fn use_okta(
         &self,
         ctx: &Context,
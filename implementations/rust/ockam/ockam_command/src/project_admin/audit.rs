@@ -0,0 +1,217 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use serde::Serialize;
+
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::Context;
+use ockam_api::authenticator::audit::{AuditEntry, AuditLogReader, AuditLogVerification};
+use ockam_api::colors::{color_primary, color_warn};
+use ockam_api::output::Output;
+use ockam_api::terminal::fmt;
+use ockam_api::{fmt_err, fmt_ok};
+
+use crate::project_member::authority_client;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/audit/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/audit/after_long_help.txt");
+
+/// Show or verify the audit log of a Project
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct AuditCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// The Project to read the audit log from
+    #[arg(long, short, value_name = "PROJECT_NAME")]
+    project_name: Option<String>,
+
+    /// Index of the first entry to return
+    #[arg(long, value_name = "INDEX", default_value_t = 0)]
+    from: u64,
+
+    /// Maximum number of entries to return
+    #[arg(long, value_name = "COUNT", default_value_t = 50)]
+    limit: u32,
+
+    /// Verify the hashes and signatures of the whole audit log instead of listing entries
+    #[arg(long, conflicts_with_all = ["from", "limit"])]
+    verify: bool,
+}
+
+#[async_trait]
+impl Command for AuditCommand {
+    const NAME: &'static str = "project-admin audit";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let (authority_node_client, project_name) =
+            authority_client(ctx, &opts, &self.identity_opts, &self.project_name).await?;
+
+        if self.verify {
+            let verification = authority_node_client.verify_audit_log(ctx).await?;
+            let output = AuditVerificationOutput::new(project_name, verification);
+            opts.terminal
+                .stdout()
+                .plain(output.item()?)
+                .json_obj(&output)?
+                .write_line()?;
+            return match output.error {
+                Some(error) => Err(miette!("The audit log is invalid: {error}"))?,
+                None => Ok(()),
+            };
+        }
+
+        let entries = authority_node_client
+            .list_audit_entries(ctx, self.from, self.limit)
+            .await?
+            .into_iter()
+            .map(AuditEntryOutput::from)
+            .collect::<Vec<_>>();
+
+        let plain = opts
+            .terminal
+            .build_list(&entries, "No entries found in the audit log")?;
+        opts.terminal
+            .stdout()
+            .plain(plain)
+            .json_obj(&entries)?
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct AuditEntryOutput {
+    index: u64,
+    recorded_at: TimestampInSeconds,
+    actor: Identifier,
+    action: String,
+    target: Option<Identifier>,
+    attributes: BTreeMap<String, String>,
+    previous_hash: String,
+    hash: String,
+}
+
+impl From<AuditEntry> for AuditEntryOutput {
+    fn from(entry: AuditEntry) -> Self {
+        Self {
+            index: entry.index,
+            recorded_at: entry.recorded_at,
+            actor: entry.actor,
+            action: entry.action.to_string(),
+            target: entry.target,
+            attributes: entry.attributes,
+            previous_hash: hex::encode(entry.previous_hash),
+            hash: hex::encode(entry.hash),
+        }
+    }
+}
+
+impl Output for AuditEntryOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        writeln!(
+            f,
+            "{}#{} {} at {}",
+            fmt::PADDING,
+            self.index,
+            color_primary(&self.action),
+            color_warn(self.recorded_at.to_string())
+        )?;
+        writeln!(
+            f,
+            "{}{}Actor: {}",
+            fmt::PADDING,
+            fmt::INDENTATION,
+            color_primary(self.actor.to_string())
+        )?;
+        if let Some(target) = &self.target {
+            writeln!(
+                f,
+                "{}{}Target: {}",
+                fmt::PADDING,
+                fmt::INDENTATION,
+                color_primary(target.to_string())
+            )?;
+        }
+        if !self.attributes.is_empty() {
+            let attributes = self
+                .attributes
+                .iter()
+                .map(|(k, v)| format!("{k}={v}"))
+                .collect::<Vec<_>>();
+            writeln!(
+                f,
+                "{}{}Attributes: {}",
+                fmt::PADDING,
+                fmt::INDENTATION,
+                color_primary(attributes.join(", "))
+            )?;
+        }
+        writeln!(f, "{}{}Hash: {}", fmt::PADDING, fmt::INDENTATION, self.hash)?;
+        Ok(f)
+    }
+}
+
+#[derive(Serialize)]
+struct AuditVerificationOutput {
+    project: String,
+    verified_entries: u64,
+    last_hash: String,
+    error: Option<String>,
+}
+
+impl AuditVerificationOutput {
+    fn new(project: String, verification: AuditLogVerification) -> Self {
+        Self {
+            project,
+            verified_entries: verification.verified_entries,
+            last_hash: hex::encode(verification.last_hash),
+            error: verification.error.map(|e| e.to_string()),
+        }
+    }
+}
+
+impl Output for AuditVerificationOutput {
+    fn item(&self) -> ockam_api::Result<String> {
+        let mut f = String::new();
+        match &self.error {
+            None => writeln!(
+                f,
+                "{}",
+                fmt_ok!(
+                    "The audit log of the Project {} is valid: {} entries verified",
+                    color_primary(&self.project),
+                    color_primary(self.verified_entries.to_string())
+                )
+            )?,
+            Some(error) => writeln!(
+                f,
+                "{}",
+                fmt_err!(
+                    "The audit log of the Project {} is invalid after {} verified entries: {}",
+                    color_primary(&self.project),
+                    color_primary(self.verified_entries.to_string()),
+                    error
+                )
+            )?,
+        }
+        writeln!(
+            f,
+            "{}Last hash: {}",
+            fmt::PADDING,
+            color_primary(&self.last_hash)
+        )?;
+        Ok(f)
+    }
+}
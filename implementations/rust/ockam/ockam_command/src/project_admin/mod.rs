@@ -1,10 +1,12 @@
 mod add;
+mod audit;
 mod delete;
 mod list;
 
 use clap::{Args, Subcommand};
 
 use crate::project_admin::add::AddCommand;
+use crate::project_admin::audit::AuditCommand;
 use crate::project_admin::delete::DeleteCommand;
 use crate::project_admin::list::ListCommand;
 use crate::{docs, Command, CommandGlobalOpts};
@@ -27,6 +29,9 @@ enum ProjectAdminSubcommand {
 
     #[command(display_order = 800)]
     Delete(DeleteCommand),
+
+    #[command(display_order = 800)]
+    Audit(AuditCommand),
 }
 
 impl ProjectAdminCommand {
@@ -35,6 +40,7 @@ impl ProjectAdminCommand {
             ProjectAdminSubcommand::List(c) => c.run(opts),
             ProjectAdminSubcommand::Add(c) => c.run(opts),
             ProjectAdminSubcommand::Delete(c) => c.run(opts),
+            ProjectAdminSubcommand::Audit(c) => c.run(opts),
         }
     }
 
@@ -43,6 +49,7 @@ impl ProjectAdminCommand {
             ProjectAdminSubcommand::List(c) => c.name(),
             ProjectAdminSubcommand::Add(c) => c.name(),
             ProjectAdminSubcommand::Delete(c) => c.name(),
+            ProjectAdminSubcommand::Audit(c) => c.name(),
         }
     }
 }
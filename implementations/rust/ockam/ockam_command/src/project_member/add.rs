@@ -4,8 +4,10 @@ use colorful::Colorful;
 use serde::Serialize;
 use std::collections::BTreeMap;
 use std::fmt::Display;
+use std::time::Duration;
 
-use ockam::identity::Identifier;
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, TimestampInSeconds};
 use ockam::Context;
 use ockam_api::authenticator::direct::Members;
 use ockam_api::colors::color_primary;
@@ -13,6 +15,7 @@ use ockam_api::{fmt_log, fmt_ok};
 
 use crate::project_member::{authority_client, create_member_attributes};
 use crate::shared_args::{IdentityOpts, RetryOpts};
+use crate::util::parsers::duration_parser;
 use crate::{docs, Command, CommandGlobalOpts, Error};
 
 const LONG_ABOUT: &str = include_str!("./static/add/long_about.txt");
@@ -59,6 +62,12 @@ pub struct AddCommand {
     #[arg(long = "enroller")]
     enroller: bool,
 
+    /// Duration after which the membership ends, for example `30d` or `12h`.
+    /// Once expired, the member can't get new credentials from the Authority node.
+    /// When an existing member is added again without this option, its end date is kept
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser)]
+    expires_in: Option<Duration>,
+
     #[command(flatten)]
     retry_opts: RetryOpts,
 }
@@ -78,8 +87,13 @@ impl Command for AddCommand {
         let attributes =
             create_member_attributes(&self.attributes, &self.allowed_relay_name, self.enroller)?;
 
+        let expires_at = match self.expires_in {
+            Some(duration) => Some(now()? + duration),
+            None => None,
+        };
+
         authority_node_client
-            .add_member(ctx, self.member.clone(), attributes.clone())
+            .add_member_with_expiration(ctx, self.member.clone(), attributes.clone(), expires_at)
             .await
             .map_err(Error::Retry)?;
 
@@ -87,6 +101,7 @@ impl Command for AddCommand {
             project: project_name,
             identifier: self.member.clone(),
             attributes,
+            expires_at,
         };
 
         opts.terminal
@@ -104,6 +119,7 @@ struct AddMemberOutput {
     project: String,
     identifier: Identifier,
     attributes: BTreeMap<String, String>,
+    expires_at: Option<TimestampInSeconds>,
 }
 
 impl Display for AddMemberOutput {
@@ -130,6 +146,13 @@ impl Display for AddMemberOutput {
                 fmt_log!("With attributes: {}", color_primary(attributes))
             )?;
         }
+        if let Some(expires_at) = self.expires_at {
+            writeln!(
+                f,
+                "{}",
+                fmt_log!("Until: {}", color_primary(expires_at.to_string()))
+            )?;
+        }
         writeln!(
             f,
             "{}",
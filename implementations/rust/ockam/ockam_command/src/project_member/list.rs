@@ -1,6 +1,7 @@
 use async_trait::async_trait;
 use clap::Args;
 
+use ockam::identity::utils::now;
 use ockam::Context;
 use ockam_api::authenticator::direct::{
     Members, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
@@ -29,6 +30,14 @@ pub struct ListCommand {
     /// Return only the enroller members
     #[arg(long, visible_alias = "enroller")]
     enrollers: bool,
+
+    /// Return only the suspended members
+    #[arg(long)]
+    suspended: bool,
+
+    /// Return only the members whose membership has expired
+    #[arg(long)]
+    expired: bool,
 }
 
 #[async_trait]
@@ -39,6 +48,9 @@ impl Command for ListCommand {
         let (authority_node_client, _) =
             authority_client(ctx, &opts, &self.identity_opts, &self.project_name).await?;
 
+        let suspended = authority_node_client.list_suspended_member_ids(ctx).await?;
+        let now = now()?;
+
         let members = authority_node_client
             .list_members(ctx)
             .await?
@@ -50,7 +62,12 @@ impl Command for ListCommand {
                         OCKAM_ROLE_ATTRIBUTE_KEY, OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE
                     ))
             })
-            .map(|(i, a)| MemberOutput::new(i, a))
+            .map(|(i, a)| {
+                let is_suspended = suspended.contains(&i);
+                MemberOutput::new(i, a, is_suspended)
+            })
+            .filter(|m| !self.suspended || m.is_suspended)
+            .filter(|m| !self.expired || m.is_expired(now))
             .collect::<Vec<_>>();
 
         let plain = opts
@@ -10,7 +10,7 @@ use add::{AddCommand, OCKAM_RELAY_ATTRIBUTE};
 use delete::DeleteCommand;
 use list::ListCommand;
 use list_ids::ListIdsCommand;
-use ockam::identity::{AttributesEntry, Identifier};
+use ockam::identity::{AttributesEntry, Identifier, TimestampInSeconds};
 use ockam_api::authenticator::direct::{
     OCKAM_ROLE_ATTRIBUTE_ENROLLER_VALUE, OCKAM_ROLE_ATTRIBUTE_KEY,
 };
@@ -22,6 +22,9 @@ use ockam_api::output::Output;
 use ockam_api::terminal::fmt;
 use ockam_api::CliState;
 use ockam_node::Context;
+use resume::ResumeCommand;
+use set_expiration::SetExpirationCommand;
+use suspend::SuspendCommand;
 
 use crate::project_member::show::ShowCommand;
 use crate::shared_args::IdentityOpts;
@@ -31,7 +34,10 @@ mod add;
 pub(crate) mod delete;
 mod list;
 mod list_ids;
+mod resume;
+mod set_expiration;
 mod show;
+mod suspend;
 
 const LONG_ABOUT: &str = include_str!("./static/long_about.txt");
 
@@ -56,6 +62,9 @@ impl ProjectMemberCommand {
             ProjectMemberSubcommand::Add(c) => c.run(opts),
             ProjectMemberSubcommand::Show(c) => c.run(opts),
             ProjectMemberSubcommand::Delete(c) => c.run(opts),
+            ProjectMemberSubcommand::SetExpiration(c) => c.run(opts),
+            ProjectMemberSubcommand::Suspend(c) => c.run(opts),
+            ProjectMemberSubcommand::Resume(c) => c.run(opts),
         }
     }
 
@@ -66,6 +75,9 @@ impl ProjectMemberCommand {
             ProjectMemberSubcommand::Add(c) => c.name(),
             ProjectMemberSubcommand::Show(c) => c.name(),
             ProjectMemberSubcommand::Delete(c) => c.name(),
+            ProjectMemberSubcommand::SetExpiration(c) => c.name(),
+            ProjectMemberSubcommand::Suspend(c) => c.name(),
+            ProjectMemberSubcommand::Resume(c) => c.name(),
         }
     }
 }
@@ -82,9 +94,15 @@ pub enum ProjectMemberSubcommand {
     Show(ShowCommand),
     #[command(display_order = 800)]
     Delete(DeleteCommand),
+    #[command(display_order = 800)]
+    SetExpiration(SetExpirationCommand),
+    #[command(display_order = 800)]
+    Suspend(SuspendCommand),
+    #[command(display_order = 800)]
+    Resume(ResumeCommand),
 }
 
-pub(super) async fn authority_client(
+pub(crate) async fn authority_client(
     ctx: &Context,
     opts: &CommandGlobalOpts,
     identity_opts: &IdentityOpts,
@@ -150,15 +168,23 @@ pub(crate) fn create_member_attributes(
 struct MemberOutput {
     identifier: Identifier,
     attributes: AttributesEntry,
+    is_suspended: bool,
 }
 
 impl MemberOutput {
-    fn new(identifier: Identifier, attributes: AttributesEntry) -> Self {
+    fn new(identifier: Identifier, attributes: AttributesEntry, is_suspended: bool) -> Self {
         Self {
             identifier,
             attributes,
+            is_suspended,
         }
     }
+
+    fn is_expired(&self, now: TimestampInSeconds) -> bool {
+        self.attributes
+            .expires_at()
+            .is_some_and(|expires_at| expires_at <= now)
+    }
 }
 
 impl Output for MemberOutput {
@@ -170,6 +196,9 @@ impl Output for MemberOutput {
             fmt::PADDING,
             color_primary(self.identifier.to_string())
         )?;
+        if self.is_suspended {
+            writeln!(f, "{}{}", fmt::PADDING, color_warn("Suspended"))?;
+        }
 
         if self.attributes.attrs().is_empty() {
             writeln!(f, "{}Has no attributes", fmt::PADDING)?;
@@ -188,15 +217,6 @@ impl Output for MemberOutput {
                 fmt::INDENTATION,
                 color_warn(self.attributes.added_at().to_string())
             )?;
-            if let Some(expires_at) = self.attributes.expires_at() {
-                writeln!(
-                    f,
-                    "{}{}Expires at: {}",
-                    fmt::PADDING,
-                    fmt::INDENTATION,
-                    color_warn(expires_at.to_string())
-                )?;
-            }
             if let Some(attested_by) = &self.attributes.attested_by() {
                 writeln!(
                     f,
@@ -207,6 +227,15 @@ impl Output for MemberOutput {
                 )?;
             }
         }
+        if let Some(expires_at) = self.attributes.expires_at() {
+            writeln!(
+                f,
+                "{}{}Expires at: {}",
+                fmt::PADDING,
+                fmt::INDENTATION,
+                color_warn(expires_at.to_string())
+            )?;
+        }
         Ok(f)
     }
 }
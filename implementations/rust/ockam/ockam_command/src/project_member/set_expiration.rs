@@ -0,0 +1,135 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::miette;
+use serde::Serialize;
+use std::fmt::Display;
+use std::time::Duration;
+
+use ockam::identity::utils::now;
+use ockam::identity::{Identifier, TimestampInSeconds};
+use ockam::Context;
+use ockam_api::authenticator::direct::Members;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+
+use crate::project_member::authority_client;
+use crate::shared_args::IdentityOpts;
+use crate::util::parsers::duration_parser;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/set_expiration/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/set_expiration/after_long_help.txt");
+
+/// Set, extend or remove the end date of a Project membership
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct SetExpirationCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// The Project that the member belongs to
+    #[arg(long, short, value_name = "PROJECT_NAME")]
+    project_name: Option<String>,
+
+    /// The Identifier of the member
+    #[arg(value_name = "IDENTIFIER")]
+    member: Identifier,
+
+    /// End the membership after the given duration, starting from now
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser, group = "expiration")]
+    expires_in: Option<Duration>,
+
+    /// Push back the current end date of the membership by the given duration.
+    /// If the membership has already expired, the duration starts from now
+    #[arg(long, value_name = "DURATION", value_parser = duration_parser, group = "expiration")]
+    extend_by: Option<Duration>,
+
+    /// Remove the end date of the membership
+    #[arg(long, group = "expiration")]
+    never: bool,
+}
+
+#[async_trait]
+impl Command for SetExpirationCommand {
+    const NAME: &'static str = "project-member set-expiration";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let (authority_node_client, project_name) =
+            authority_client(ctx, &opts, &self.identity_opts, &self.project_name).await?;
+
+        let now = now()?;
+        let expires_at = if let Some(duration) = self.expires_in {
+            Some(now + duration)
+        } else if let Some(duration) = self.extend_by {
+            let member = authority_node_client
+                .show_member(ctx, self.member.clone())
+                .await?;
+            let Some(current) = member.expires_at() else {
+                return Err(miette!(
+                    "The membership of {} has no end date. Use --expires-in to set one",
+                    self.member
+                ))?;
+            };
+            Some(current.max(now) + duration)
+        } else if self.never {
+            None
+        } else {
+            return Err(miette!(
+                "You need to specify one of --expires-in, --extend-by or --never"
+            ))?;
+        };
+
+        authority_node_client
+            .set_member_expiration(ctx, self.member.clone(), expires_at)
+            .await?;
+
+        let output = SetExpirationOutput {
+            project: project_name,
+            identifier: self.member,
+            expires_at,
+        };
+        opts.terminal
+            .stdout()
+            .plain(output.to_string())
+            .json_obj(&output)?
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SetExpirationOutput {
+    project: String,
+    identifier: Identifier,
+    expires_at: Option<TimestampInSeconds>,
+}
+
+impl Display for SetExpirationOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self.expires_at {
+            Some(expires_at) => writeln!(
+                f,
+                "{}",
+                fmt_ok!(
+                    "The membership of {} in the Project {} now ends at {}",
+                    color_primary(self.identifier.to_string()),
+                    color_primary(&self.project),
+                    color_primary(expires_at.to_string())
+                )
+            ),
+            None => writeln!(
+                f,
+                "{}",
+                fmt_ok!(
+                    "The membership of {} in the Project {} no longer has an end date",
+                    color_primary(self.identifier.to_string()),
+                    color_primary(&self.project)
+                )
+            ),
+        }
+    }
+}
@@ -105,7 +105,12 @@ impl ShowCommandTui for ShowTui {
             .client
             .show_member(&self.ctx, identifier.clone())
             .await?;
-        let member = MemberOutput::new(identifier, attributes);
+        let is_suspended = self
+            .client
+            .list_suspended_member_ids(&self.ctx)
+            .await?
+            .contains(&identifier);
+        let member = MemberOutput::new(identifier, attributes, is_suspended);
         self.terminal()
             .stdout()
             .plain(member.item()?)
@@ -0,0 +1,82 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use serde::Serialize;
+use std::fmt::Display;
+
+use ockam::identity::Identifier;
+use ockam::Context;
+use ockam_api::authenticator::direct::Members;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+
+use crate::project_member::authority_client;
+use crate::shared_args::IdentityOpts;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/suspend/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/suspend/after_long_help.txt");
+
+/// Suspend a member of a Project
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP),
+)]
+pub struct SuspendCommand {
+    #[command(flatten)]
+    identity_opts: IdentityOpts,
+
+    /// The Project that the member belongs to
+    #[arg(long, short, value_name = "PROJECT_NAME")]
+    project_name: Option<String>,
+
+    /// The Identifier of the member to suspend
+    #[arg(value_name = "IDENTIFIER")]
+    member: Identifier,
+}
+
+#[async_trait]
+impl Command for SuspendCommand {
+    const NAME: &'static str = "project-member suspend";
+
+    async fn async_run(self, ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let (authority_node_client, project_name) =
+            authority_client(ctx, &opts, &self.identity_opts, &self.project_name).await?;
+
+        authority_node_client
+            .suspend_member(ctx, self.member.clone())
+            .await?;
+
+        let output = SuspendMemberOutput {
+            project: project_name,
+            identifier: self.member,
+        };
+        opts.terminal
+            .stdout()
+            .plain(output.to_string())
+            .json_obj(&output)?
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[derive(Serialize)]
+struct SuspendMemberOutput {
+    project: String,
+    identifier: Identifier,
+}
+
+impl Display for SuspendMemberOutput {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        writeln!(
+            f,
+            "{}",
+            fmt_ok!(
+                "Identifier {} is now suspended from the Project {}",
+                color_primary(self.identifier.to_string()),
+                color_primary(&self.project)
+            )
+        )
+    }
+}
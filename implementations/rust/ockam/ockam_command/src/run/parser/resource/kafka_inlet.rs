@@ -51,6 +51,7 @@ impl KafkaInlet {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::kafka::inlet::create::{KeyEncryptionArg, RecordFormatArg};
     use ockam::transport::SchemeHostnamePort;
 
     use ockam_core::env::FromString;
@@ -68,6 +69,12 @@ mod tests {
               encrypted-fields:
                 - one
                 - two
+              record-format: avro
+              avro-schema:
+                - 1=user.avsc
+              key-encryption: randomized
+              encrypted-headers:
+                - trace-id
         "#;
         let parsed: KafkaInlet = serde_yaml::from_str(unnamed).unwrap();
         let default_node_name = "n1".to_string();
@@ -98,6 +105,10 @@ mod tests {
             cmds[0].encrypted_fields,
             vec!["one".to_string(), "two".to_string()]
         );
+        assert_eq!(cmds[0].record_format, RecordFormatArg::Avro);
+        assert_eq!(cmds[0].avro_schemas, vec![(1, "user.avsc".into())]);
+        assert_eq!(cmds[0].key_encryption, KeyEncryptionArg::Randomized);
+        assert_eq!(cmds[0].encrypted_headers, vec!["trace-id".to_string()]);
 
         let named = r#"
             kafka-inlet:
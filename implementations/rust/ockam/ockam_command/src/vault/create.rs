@@ -3,7 +3,7 @@ use std::path::PathBuf;
 use async_trait::async_trait;
 use clap::Args;
 use colorful::Colorful;
-use ockam_api::cli_state::UseAwsKms;
+use ockam_api::cli_state::{UseAwsKms, UsePkcs11};
 use ockam_api::{fmt_info, fmt_ok};
 
 use ockam_node::Context;
@@ -28,6 +28,11 @@ pub struct CreateCommand {
 
     #[arg(long, default_value = "false")]
     pub aws_kms: bool,
+
+    /// Store the signing keys in a PKCS#11 token.
+    /// The token is configured with the OCKAM_PKCS11_MODULE, OCKAM_PKCS11_TOKEN_LABEL and OCKAM_PKCS11_PIN environment variables
+    #[arg(long, default_value = "false", conflicts_with = "aws_kms")]
+    pub pkcs11: bool,
 }
 
 #[async_trait]
@@ -43,7 +48,12 @@ impl Command for CreateCommand {
 
         let vault = opts
             .state
-            .create_named_vault(self.name, self.path, UseAwsKms::from(self.aws_kms))
+            .create_named_vault(
+                self.name,
+                self.path,
+                UseAwsKms::from(self.aws_kms),
+                UsePkcs11::from(self.pkcs11),
+            )
             .await?;
 
         opts.terminal
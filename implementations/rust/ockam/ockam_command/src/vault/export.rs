@@ -0,0 +1,70 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+
+use ockam_node::Context;
+
+use crate::vault::util::get_bundle_passphrase;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/export/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/export/after_long_help.txt");
+
+/// Export a vault to an encrypted file
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ExportCommand {
+    /// Name of the vault. The default vault is used if not specified
+    #[arg()]
+    pub name: Option<String>,
+
+    /// Path of the file to create
+    #[arg(long, value_name = "FILE")]
+    pub file: PathBuf,
+}
+
+#[async_trait]
+impl Command for ExportCommand {
+    const NAME: &'static str = "vault export";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let passphrase = get_bundle_passphrase(&opts, true)?;
+        let bundle = opts.state.export_vault(&self.name, &passphrase).await?;
+        std::fs::write(&self.file, bundle).into_diagnostic()?;
+
+        let path = self.file.display().to_string();
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The vault has been exported to {}",
+                color_primary(&path)
+            ))
+            .json(serde_json::json!({ "path": path }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            ExportCommand::NAME,
+            &["--file".to_string(), "vault.bundle".to_string()],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -0,0 +1,77 @@
+use std::path::PathBuf;
+
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use miette::IntoDiagnostic;
+use ockam_api::colors::color_primary;
+use ockam_api::fmt_ok;
+
+use ockam_node::Context;
+
+use crate::vault::util::get_bundle_passphrase;
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/import/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/import/after_long_help.txt");
+
+/// Import a vault from a file created with `ockam vault export`
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct ImportCommand {
+    /// Name of the vault receiving the keys. The default vault is used if not specified
+    #[arg()]
+    pub name: Option<String>,
+
+    /// Path of the exported vault
+    #[arg(long, value_name = "FILE")]
+    pub file: PathBuf,
+
+    /// Replace the keys and identities which already exist
+    #[arg(long)]
+    pub force: bool,
+}
+
+#[async_trait]
+impl Command for ImportCommand {
+    const NAME: &'static str = "vault import";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let bundle = std::fs::read(&self.file).into_diagnostic()?;
+        let passphrase = get_bundle_passphrase(&opts, false)?;
+        let identities = opts
+            .state
+            .import_vault(&self.name, &bundle, &passphrase, self.force)
+            .await?;
+
+        let names: Vec<String> = identities.iter().map(|i| i.name()).collect();
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The vault has been imported with the identities: {}",
+                color_primary(names.join(", "))
+            ))
+            .json(serde_json::json!({ "identities": names }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(
+            ImportCommand::NAME,
+            &["--file".to_string(), "vault.bundle".to_string()],
+        );
+        assert!(cmd.is_ok());
+    }
+}
@@ -2,15 +2,21 @@ use clap::{Args, Subcommand};
 
 pub use crate::vault::create::CreateCommand;
 use crate::vault::delete::DeleteCommand;
+use crate::vault::export::ExportCommand;
+use crate::vault::import::ImportCommand;
 use crate::vault::list::ListCommand;
 use crate::vault::move_vault::MoveCommand;
+use crate::vault::rotate_kek::RotateKekCommand;
 use crate::vault::show::ShowCommand;
 use crate::{docs, Command, CommandGlobalOpts};
 
 mod create;
 mod delete;
+mod export;
+mod import;
 mod list;
 mod move_vault;
+mod rotate_kek;
 mod show;
 mod util;
 
@@ -35,6 +41,9 @@ pub enum VaultSubcommand {
     Show(ShowCommand),
     Delete(DeleteCommand),
     List(ListCommand),
+    RotateKek(RotateKekCommand),
+    Export(ExportCommand),
+    Import(ImportCommand),
 }
 
 impl VaultCommand {
@@ -45,6 +54,9 @@ impl VaultCommand {
             VaultSubcommand::Show(cmd) => cmd.run(opts),
             VaultSubcommand::List(cmd) => cmd.run(opts),
             VaultSubcommand::Delete(cmd) => cmd.run(opts),
+            VaultSubcommand::RotateKek(cmd) => cmd.run(opts),
+            VaultSubcommand::Export(cmd) => cmd.run(opts),
+            VaultSubcommand::Import(cmd) => cmd.run(opts),
         }
     }
 
@@ -55,6 +67,9 @@ impl VaultCommand {
             VaultSubcommand::Show(c) => c.name(),
             VaultSubcommand::Delete(c) => c.name(),
             VaultSubcommand::List(c) => c.name(),
+            VaultSubcommand::RotateKek(c) => c.name(),
+            VaultSubcommand::Export(c) => c.name(),
+            VaultSubcommand::Import(c) => c.name(),
         }
     }
 }
@@ -0,0 +1,57 @@
+use async_trait::async_trait;
+use clap::Args;
+use colorful::Colorful;
+use ockam_api::fmt_ok;
+
+use ockam_node::Context;
+
+use crate::{docs, Command, CommandGlobalOpts};
+
+const LONG_ABOUT: &str = include_str!("./static/rotate_kek/long_about.txt");
+const AFTER_LONG_HELP: &str = include_str!("./static/rotate_kek/after_long_help.txt");
+
+/// Rotate the key-encryption key of a vault
+#[derive(Clone, Debug, Args)]
+#[command(
+long_about = docs::about(LONG_ABOUT),
+after_long_help = docs::after_help(AFTER_LONG_HELP)
+)]
+pub struct RotateKekCommand {
+    /// Name of the vault. The default vault is used if not specified
+    #[arg()]
+    pub name: Option<String>,
+}
+
+#[async_trait]
+impl Command for RotateKekCommand {
+    const NAME: &'static str = "vault rotate-kek";
+
+    async fn async_run(self, _ctx: &Context, opts: CommandGlobalOpts) -> crate::Result<()> {
+        let count = opts
+            .state
+            .rotate_vault_key_encryption_key(&self.name)
+            .await?;
+
+        opts.terminal
+            .stdout()
+            .plain(fmt_ok!(
+                "The key-encryption key has been rotated, {count} secrets have been updated"
+            ))
+            .json(serde_json::json!({ "updated_secrets": count }))
+            .write_line()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::run::parser::resource::utils::parse_cmd_from_args;
+
+    use super::*;
+
+    #[test]
+    fn command_can_be_parsed_from_name() {
+        let cmd = parse_cmd_from_args(RotateKekCommand::NAME, &[]);
+        assert!(cmd.is_ok());
+    }
+}
@@ -1,10 +1,14 @@
 use colorful::Colorful;
 use indoc::formatdoc;
+use miette::{miette, IntoDiagnostic};
 
 use ockam_api::cli_state::vaults::NamedVault;
-use ockam_api::cli_state::{UseAwsKms, VaultType};
+use ockam_api::cli_state::VaultType;
 use ockam_api::colors::OckamColor;
 use ockam_api::output::{indent, Output};
+use ockam_core::env::get_env;
+
+use crate::CommandGlobalOpts;
 
 #[derive(serde::Serialize)]
 pub struct VaultOutput {
@@ -49,61 +53,48 @@ impl Output for VaultOutput {
         .to_string()
         .color(OckamColor::PrimaryResource.color());
 
-        let uses_aws_kms = if self.vault.use_aws_kms() {
-            "true"
-        } else {
-            "false"
-        }
-        .to_string()
-        .color(OckamColor::PrimaryResource.color());
-
-        Ok(match self.vault.vault_type() {
-            VaultType::DatabaseVault {
-                use_aws_kms: UseAwsKms::No,
-            } => formatdoc!(
-                r#"Name: {name}
-                   Type: {vault_type}"#,
-                name = name,
-                vault_type = vault_type
-            ),
-            VaultType::DatabaseVault {
-                use_aws_kms: UseAwsKms::Yes,
-            } => formatdoc!(
-                r#"Name: {name}
-            Type: {vault_type}
-            Uses AWS KMS: {uses_aws_kms}"#,
-                name = name,
-                uses_aws_kms = uses_aws_kms
-            ),
-            VaultType::LocalFileVault {
-                path,
-                use_aws_kms: UseAwsKms::No,
-            } => formatdoc!(
-                r#"Name: {name}
-            Type: {vault_type}
-            Path: {vault_path}"#,
-                name = name,
-                vault_type = vault_type,
-                vault_path = path
-                    .to_string_lossy()
+        let mut output = formatdoc!(
+            r#"Name: {name}
+            Type: {vault_type}"#,
+            name = name,
+            vault_type = vault_type
+        );
+        if let VaultType::LocalFileVault { path, .. } = self.vault.vault_type() {
+            output.push_str(&format!(
+                "\nPath: {}",
+                path.to_string_lossy()
                     .to_string()
                     .color(OckamColor::PrimaryResource.color())
-            ),
-            VaultType::LocalFileVault {
-                path,
-                use_aws_kms: UseAwsKms::Yes,
-            } => formatdoc!(
-                r#"Name: {name}
-            Type: External
-            Path: {vault_path}
-            Uses AWS KMS: {uses_aws_kms}"#,
-                name = name,
-                vault_path = path
-                    .to_string_lossy()
-                    .to_string()
-                    .color(OckamColor::PrimaryResource.color()),
-                uses_aws_kms = uses_aws_kms,
-            ),
-        })
+            ));
+        }
+        if self.vault.use_aws_kms() {
+            output.push_str(&format!(
+                "\nUses AWS KMS: {}",
+                "true".color(OckamColor::PrimaryResource.color())
+            ));
+        }
+        if self.vault.use_pkcs11() {
+            output.push_str(&format!(
+                "\nUses PKCS#11: {}",
+                "true".color(OckamColor::PrimaryResource.color())
+            ));
+        }
+        Ok(output)
+    }
+}
+
+/// Environment variable containing the passphrase used to encrypt an exported vault
+pub const OCKAM_VAULT_BUNDLE_PASSPHRASE: &str = "OCKAM_VAULT_BUNDLE_PASSPHRASE";
+
+/// Return the passphrase of a vault bundle, either from an environment variable
+/// or by prompting the user
+pub fn get_bundle_passphrase(opts: &CommandGlobalOpts, confirm: bool) -> miette::Result<String> {
+    if let Some(passphrase) = get_env::<String>(OCKAM_VAULT_BUNDLE_PASSPHRASE).into_diagnostic()? {
+        return Ok(passphrase);
     }
+    opts.terminal
+        .read_password("Enter the passphrase of the vault bundle", confirm)?
+        .ok_or_else(|| {
+            miette!("The passphrase must be set with the {OCKAM_VAULT_BUNDLE_PASSPHRASE} environment variable")
+        })
 }
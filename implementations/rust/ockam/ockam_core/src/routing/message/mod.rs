@@ -3,6 +3,7 @@ mod local_message;
 #[cfg(feature = "std")]
 mod opentelemetry;
 mod relay_message;
+mod source_address_local_info;
 mod transport_message;
 
 pub use local_info::*;
@@ -10,4 +11,5 @@ pub use local_message::*;
 #[cfg(feature = "std")]
 pub use opentelemetry::*;
 pub use relay_message::*;
+pub use source_address_local_info::*;
 pub use transport_message::*;
@@ -0,0 +1,113 @@
+use crate::compat::string::{String, ToString};
+use crate::compat::vec::Vec;
+use crate::errcode::{Kind, Origin};
+use crate::{Address, Error, LocalInfo, LocalMessage, Result, TransportType};
+use minicbor::{CborLen, Decode, Encode};
+
+/// Identifier for the [`SourceAddressLocalInfo`] of a [`LocalMessage`]
+pub const SOURCE_ADDRESS_IDENTIFIER: &str = "SOURCE_ADDRESS_IDENTIFIER";
+
+/// LocalInfo holding the transport address of the peer which delivered a message to this node,
+/// for example the socket address of the other side of a TCP connection.
+///
+/// It is kept when a message is decrypted by a secure channel, so that it can be used to
+/// authorize the messages received through a secure channel.
+#[derive(Encode, Decode, CborLen, Debug, Clone, PartialEq, Eq)]
+#[rustfmt::skip]
+pub struct SourceAddressLocalInfo {
+    #[n(0)] transport_type: TransportType,
+    #[n(1)] address: String,
+}
+
+impl SourceAddressLocalInfo {
+    /// Create a new `SourceAddressLocalInfo` for a transport address
+    pub fn new(transport_type: TransportType, address: impl ToString) -> Self {
+        Self {
+            transport_type,
+            address: address.to_string(),
+        }
+    }
+
+    /// Transport address of the peer
+    pub fn address(&self) -> Address {
+        Address::new_with_string(self.transport_type, self.address.clone())
+    }
+}
+
+impl SourceAddressLocalInfo {
+    #[track_caller]
+    fn error_type_id() -> Error {
+        Error::new(
+            Origin::Core,
+            Kind::Invalid,
+            "invalid local info identifier for source address",
+        )
+    }
+
+    #[track_caller]
+    fn error_format() -> Error {
+        Error::new(
+            Origin::Core,
+            Kind::Invalid,
+            "invalid format for local info identifier for source address",
+        )
+    }
+
+    /// Try to decode `SourceAddressLocalInfo` from general `LocalInfo`
+    pub fn from_local_info(value: &LocalInfo) -> Result<Self> {
+        if value.type_identifier() != SOURCE_ADDRESS_IDENTIFIER {
+            return Err(Self::error_type_id());
+        }
+
+        minicbor::decode::<Self>(value.data()).map_err(|_| Self::error_format())
+    }
+
+    /// Encode `SourceAddressLocalInfo` to general `LocalInfo`
+    pub fn to_local_info(&self) -> Result<LocalInfo> {
+        Ok(LocalInfo::new(
+            SOURCE_ADDRESS_IDENTIFIER.into(),
+            crate::cbor_encode_preallocate(self)?,
+        ))
+    }
+
+    /// Find `SourceAddressLocalInfo` in a list of general `LocalInfo` of that `LocalMessage`
+    pub fn find_info(local_msg: &LocalMessage) -> Result<Self> {
+        Self::find_info_from_list(local_msg.local_info())
+    }
+
+    /// Find `SourceAddressLocalInfo` in a list of general `LocalInfo`
+    pub fn find_info_from_list(local_info: &[LocalInfo]) -> Result<Self> {
+        match local_info
+            .iter()
+            .find(|x| x.type_identifier() == SOURCE_ADDRESS_IDENTIFIER)
+        {
+            Some(local_info) => Self::from_local_info(local_info),
+            None => Err(Self::error_type_id()),
+        }
+    }
+
+    /// Mark a `LocalInfo` vector with this `SourceAddressLocalInfo`
+    /// replacing any pre-existing entries
+    pub fn mark(&self, mut local_info: Vec<LocalInfo>) -> Result<Vec<LocalInfo>> {
+        local_info.retain(|x| x.type_identifier() != SOURCE_ADDRESS_IDENTIFIER);
+        local_info.push(self.to_local_info()?);
+        Ok(local_info)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mark_and_find_source_address() -> Result<()> {
+        let info = SourceAddressLocalInfo::new(TransportType::new(1), "192.168.1.10:4000");
+        let local_message = LocalMessage::new().with_local_info(info.mark(vec![])?);
+
+        let found = SourceAddressLocalInfo::find_info(&local_message)?;
+        assert_eq!(found, info);
+        assert_eq!(found.address().address(), "192.168.1.10:4000");
+        assert_eq!(found.address().transport_type(), TransportType::new(1));
+        Ok(())
+    }
+}
@@ -1,10 +1,10 @@
 use ockam_core::compat::sync::Arc;
 use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
 
-use crate::models::{CredentialData, PurposeKeyAttestationData};
+use crate::models::{CredentialData, PurposeKeyAttestationData, RevocationListData};
 use crate::{
     CredentialsCreation, CredentialsVerification, IdentitiesCreation, IdentityAttributesRepository,
-    PurposeKeys,
+    PurposeKeys, RevocationListRepository,
 };
 
 /// Structure with both [`CredentialData`] and [`PurposeKeyAttestationData`] that we get
@@ -17,6 +17,16 @@ pub struct CredentialAndPurposeKeyData {
     pub purpose_key_data: PurposeKeyAttestationData,
 }
 
+/// Structure with both [`RevocationListData`] and [`PurposeKeyAttestationData`] that we get
+/// after parsing and verifying corresponding [`crate::models::RevocationList`] and [`super::super::models::PurposeKeyAttestation`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RevocationListAndPurposeKeyData {
+    /// [`RevocationListData`]
+    pub revocation_list_data: RevocationListData,
+    /// [`PurposeKeyAttestationData`]
+    pub purpose_key_data: PurposeKeyAttestationData,
+}
+
 /// Service for managing [`Credential`]s
 pub struct Credentials {
     credential_vault: Arc<dyn VaultForSigning>,
@@ -24,6 +34,7 @@ pub struct Credentials {
     purpose_keys: Arc<PurposeKeys>,
     identities_creation: Arc<IdentitiesCreation>,
     identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
 }
 
 impl Credentials {
@@ -34,6 +45,7 @@ impl Credentials {
         purpose_keys: Arc<PurposeKeys>,
         identities_creation: Arc<IdentitiesCreation>,
         identity_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
     ) -> Self {
         Self {
             credential_vault,
@@ -41,6 +53,7 @@ impl Credentials {
             purpose_keys,
             identities_creation,
             identity_attributes_repository,
+            revocation_list_repository,
         }
     }
 
@@ -65,6 +78,7 @@ impl Credentials {
             self.purpose_keys.purpose_keys_verification(),
             self.verifying_vault.clone(),
             self.identity_attributes_repository.clone(),
+            self.revocation_list_repository.clone(),
         ))
     }
 }
@@ -79,7 +93,8 @@ mod tests {
     use ockam_core::Result;
 
     use crate::identities::identities;
-    use crate::models::CredentialSchemaIdentifier;
+    use crate::models::{CredentialSchemaIdentifier, RevokedSubject};
+    use crate::utils::now;
     use crate::Attributes;
 
     #[tokio::test]
@@ -120,4 +135,100 @@ mod tests {
 
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_revoke_credential() -> Result<()> {
+        let identities = identities().await?;
+        let creation = identities.identities_creation();
+
+        let issuer = creation.create_identity().await?;
+        let subject = creation.create_identity().await?;
+        let credentials = identities.credentials();
+        let verification = credentials.credentials_verification();
+
+        let mut map: BTreeMap<ByteVec, ByteVec> = Default::default();
+        map.insert(b"key".to_vec().into(), b"value".to_vec().into());
+        let subject_attributes = Attributes {
+            schema: CredentialSchemaIdentifier(1),
+            map,
+        };
+        let credential = credentials
+            .credentials_creation()
+            .issue_credential(
+                &issuer,
+                &subject,
+                subject_attributes,
+                Duration::from_secs(60 * 60),
+            )
+            .await?;
+        verification
+            .receive_presented_credential(&subject, &[issuer.clone()], &credential)
+            .await?;
+        let attributes = identities
+            .identities_attributes()
+            .get_attributes(&subject, &issuer)
+            .await?;
+        assert!(attributes.is_some());
+
+        // an empty revocation list doesn't revoke anything
+        let revocation_list = credentials
+            .credentials_creation()
+            .issue_revocation_list(&issuer, vec![], Duration::from_secs(60))
+            .await?;
+        assert!(
+            verification
+                .receive_revocation_list(&[issuer.clone()], &revocation_list)
+                .await?
+        );
+        verification
+            .verify_credential(Some(&subject), &[issuer.clone()], &credential)
+            .await?;
+
+        // the revocation list must be issued by a known authority
+        let other = creation.create_identity().await?;
+        let revoked_subjects = vec![RevokedSubject {
+            subject: subject.clone(),
+            revoked_at: now()?,
+        }];
+        let other_revocation_list = credentials
+            .credentials_creation()
+            .issue_revocation_list(&other, revoked_subjects.clone(), Duration::from_secs(60))
+            .await?;
+        assert!(verification
+            .receive_revocation_list(&[issuer.clone()], &other_revocation_list)
+            .await
+            .is_err());
+
+        // make sure that the new revocation list is more recent than the previous one
+        tokio::time::sleep(Duration::from_millis(1100)).await;
+        let revocation_list = credentials
+            .credentials_creation()
+            .issue_revocation_list(&issuer, revoked_subjects, Duration::from_secs(60))
+            .await?;
+        assert!(
+            verification
+                .receive_revocation_list(&[issuer.clone()], &revocation_list)
+                .await?
+        );
+
+        // the credential is now rejected and the attributes of the subject have been removed
+        assert!(verification
+            .verify_credential(Some(&subject), &[issuer.clone()], &credential)
+            .await
+            .is_err());
+        let attributes = identities
+            .identities_attributes()
+            .get_attributes(&subject, &issuer)
+            .await?;
+        assert!(attributes.is_none());
+
+        // the same revocation list is not stored twice
+        assert!(
+            !verification
+                .receive_revocation_list(&[issuer.clone()], &revocation_list)
+                .await?
+        );
+
+        Ok(())
+    }
 }
@@ -1,10 +1,14 @@
 use core::time::Duration;
 
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 use ockam_vault::{VaultForSigning, VaultForVerifyingSignatures};
 
-use crate::models::{Attributes, Credential, CredentialAndPurposeKey, CredentialData, Identifier};
+use crate::models::{
+    Attributes, Credential, CredentialAndPurposeKey, CredentialData, Identifier, RevocationList,
+    RevocationListAndPurposeKey, RevocationListData, RevokedSubject,
+};
 use crate::utils::now;
 use crate::{IdentitiesVerification, PurposeKeyCreation, TimestampInSeconds};
 
@@ -85,4 +89,45 @@ impl CredentialsCreation {
 
         Ok(res)
     }
+
+    /// Issue a [`RevocationList`] listing the subjects whose credentials are revoked
+    pub async fn issue_revocation_list(
+        &self,
+        issuer: &Identifier,
+        revoked_subjects: Vec<RevokedSubject>,
+        ttl: Duration,
+    ) -> Result<RevocationListAndPurposeKey> {
+        let issuer_purpose_key = self
+            .purpose_keys_creation
+            .get_or_create_credential_purpose_key(issuer)
+            .await?;
+
+        let created_at = now()?;
+        let expires_at = created_at + TimestampInSeconds(ttl.as_secs());
+
+        let revocation_list_data = RevocationListData {
+            revoked_subjects,
+            created_at,
+            expires_at,
+        };
+        let revocation_list_data = ockam_core::cbor_encode_preallocate(revocation_list_data)?;
+
+        let versioned_data = RevocationList::create_versioned_data(revocation_list_data);
+        let versioned_data = ockam_core::cbor_encode_preallocate(&versioned_data)?;
+
+        let versioned_data_hash = self.verifying_vault.sha256(&versioned_data).await?;
+
+        let signature = self
+            .credential_vault
+            .sign(issuer_purpose_key.key(), &versioned_data_hash.0)
+            .await?;
+
+        Ok(RevocationListAndPurposeKey {
+            revocation_list: RevocationList {
+                data: versioned_data,
+                signature: signature.into(),
+            },
+            purpose_key_attestation: issuer_purpose_key.attestation().clone(),
+        })
+    }
 }
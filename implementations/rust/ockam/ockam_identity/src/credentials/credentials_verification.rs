@@ -8,12 +8,14 @@ use ockam_vault::VaultForVerifyingSignatures;
 
 use crate::identities::AttributesEntry;
 use crate::models::{
-    CredentialAndPurposeKey, CredentialData, Identifier, PurposePublicKey, VersionedData,
+    CredentialAndPurposeKey, CredentialData, Identifier, PurposePublicKey,
+    RevocationListAndPurposeKey, RevocationListData, VersionedData,
 };
 use crate::utils::now;
 use crate::{
     CredentialAndPurposeKeyData, IdentityAttributesRepository, IdentityError,
-    PurposeKeyVerification, TimestampInSeconds,
+    PurposeKeyVerification, RevocationListAndPurposeKeyData, RevocationListRepository,
+    TimestampInSeconds,
 };
 
 /// We allow Credentials to be created in the future related to this machine's time due to
@@ -25,6 +27,7 @@ pub struct CredentialsVerification {
     purpose_keys_verification: Arc<PurposeKeyVerification>,
     verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
     identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+    revocation_list_repository: Arc<dyn RevocationListRepository>,
 }
 
 impl CredentialsVerification {
@@ -33,34 +36,56 @@ impl CredentialsVerification {
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
         identities_attributes_repository: Arc<dyn IdentityAttributesRepository>,
+        revocation_list_repository: Arc<dyn RevocationListRepository>,
     ) -> Self {
         Self {
             purpose_keys_verification,
             verifying_vault,
             identities_attributes_repository,
+            revocation_list_repository,
         }
     }
 }
 
 impl CredentialsVerification {
-    /// Verify a [`Credential`]
+    /// Verify a [`Credential`] and check that it was not revoked by its authority
     pub async fn verify_credential(
         &self,
         expected_subject: Option<&Identifier>,
         authorities: &[Identifier],
         credential_and_purpose_key: &CredentialAndPurposeKey,
     ) -> Result<CredentialAndPurposeKeyData> {
-        Self::verify_credential_static(
+        let data = Self::verify_credential_static(
             self.purpose_keys_verification.clone(),
             self.verifying_vault.clone(),
             expected_subject,
             authorities,
             credential_and_purpose_key,
         )
-        .await
+        .await?;
+
+        debug!("verify revocation");
+        if let Some(revocation_list) = self
+            .revocation_list_repository
+            .get_revocation_list(&data.purpose_key_data.subject)
+            .await?
+        {
+            let revocation_list_data =
+                revocation_list.revocation_list.get_revocation_list_data()?;
+            // The subject is always present after a successful verification
+            if let Some(subject) = &data.credential_data.subject {
+                if revocation_list_data.is_revoked(subject, data.credential_data.created_at) {
+                    warn!(%subject, authority = %data.purpose_key_data.subject, "revoked credential");
+                    return Err(IdentityError::CredentialRevoked)?;
+                }
+            }
+        }
+
+        Ok(data)
     }
 
-    /// Verify a [`Credential`]
+    /// Verify a [`Credential`]. This only checks the credential itself and not the revocation
+    /// lists which may have been received from its authority
     pub async fn verify_credential_static(
         purpose_keys_verification: Arc<PurposeKeyVerification>,
         verifying_vault: Arc<dyn VaultForVerifyingSignatures>,
@@ -239,4 +264,138 @@ impl CredentialsVerification {
 
         Ok(())
     }
+
+    /// Verify a [`crate::models::RevocationList`]
+    pub async fn verify_revocation_list(
+        &self,
+        authorities: &[Identifier],
+        revocation_list_and_purpose_key: &RevocationListAndPurposeKey,
+    ) -> Result<RevocationListAndPurposeKeyData> {
+        debug!("verify purpose key attestation");
+        let purpose_key_data = self
+            .purpose_keys_verification
+            .verify_purpose_key_attestation(
+                None,
+                &revocation_list_and_purpose_key.purpose_key_attestation,
+            )
+            .await?;
+
+        debug!("verify issuer");
+        if !authorities.contains(&purpose_key_data.subject) {
+            warn!(
+                "unknown authority on a revocation list: {}. Accepted authorities: {:?}",
+                purpose_key_data.subject, authorities
+            );
+            return Err(IdentityError::UnknownAuthority)?;
+        }
+
+        debug!("verify purpose key type");
+        let public_key = match purpose_key_data.public_key.clone() {
+            PurposePublicKey::SecureChannelStatic(_) => {
+                return Err(IdentityError::InvalidKeyType)?;
+            }
+
+            PurposePublicKey::CredentialSigning(public_key) => public_key,
+        };
+
+        debug!("verify signature");
+        let revocation_list = &revocation_list_and_purpose_key.revocation_list;
+        let public_key = public_key.into();
+        let versioned_data_hash = self.verifying_vault.sha256(&revocation_list.data).await?;
+        let signature = revocation_list.signature.clone().into();
+
+        if !self
+            .verifying_vault
+            .verify_signature(&public_key, &versioned_data_hash.0, &signature)
+            .await?
+        {
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        let versioned_data: VersionedData = minicbor::decode(&revocation_list.data)?;
+        let revocation_list_data = RevocationListData::get_data(&versioned_data)?;
+
+        debug!("verify dates");
+        if revocation_list_data.created_at < purpose_key_data.created_at
+            || revocation_list_data.expires_at > purpose_key_data.expires_at
+        {
+            // The revocation list validity time range should be inside the purpose key validity time range
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        let now = now()?;
+
+        if revocation_list_data.created_at > now
+            && revocation_list_data.created_at - now > MAX_ALLOWED_TIME_DRIFT
+        {
+            // The revocation list can't be created in the future
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        if revocation_list_data.expires_at < now {
+            // Revocation list expired
+            return Err(IdentityError::RevocationListVerificationFailed)?;
+        }
+
+        Ok(RevocationListAndPurposeKeyData {
+            revocation_list_data,
+            purpose_key_data,
+        })
+    }
+
+    /// Receive a [`crate::models::RevocationList`]: verify it, store it if it is more recent
+    /// than the one currently known for its authority and remove the attributes of the
+    /// newly revoked subjects, so that their existing secure channels are denied access.
+    ///
+    /// Return true if the revocation list was stored.
+    pub async fn receive_revocation_list(
+        &self,
+        authorities: &[Identifier],
+        revocation_list_and_purpose_key: &RevocationListAndPurposeKey,
+    ) -> Result<bool> {
+        let data = self
+            .verify_revocation_list(authorities, revocation_list_and_purpose_key)
+            .await?;
+        let authority = data.purpose_key_data.subject;
+        let revocation_list_data = data.revocation_list_data;
+
+        let previous = match self
+            .revocation_list_repository
+            .get_revocation_list(&authority)
+            .await?
+        {
+            Some(previous) => Some(previous.revocation_list.get_revocation_list_data()?),
+            None => None,
+        };
+
+        if let Some(previous) = &previous {
+            if previous.created_at >= revocation_list_data.created_at {
+                debug!(%authority, "the revocation list is not more recent than the current one");
+                return Ok(false);
+            }
+        }
+
+        self.revocation_list_repository
+            .put_revocation_list(
+                &authority,
+                revocation_list_data.created_at,
+                revocation_list_and_purpose_key.clone(),
+            )
+            .await?;
+
+        for revoked in &revocation_list_data.revoked_subjects {
+            let is_new = match &previous {
+                Some(previous) => !previous.revoked_subjects.contains(revoked),
+                None => true,
+            };
+            if is_new {
+                info!(subject = %revoked.subject, %authority, revoked_at = %revoked.revoked_at, "revoked subject");
+                self.identities_attributes_repository
+                    .delete_attributes(&revoked.subject, &authority)
+                    .await?;
+            }
+        }
+
+        Ok(true)
+    }
 }
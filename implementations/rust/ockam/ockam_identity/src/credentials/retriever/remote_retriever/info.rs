@@ -37,6 +37,9 @@ impl Display for CredentialIssuerApiServiceAddress {
     }
 }
 
+/// Request path used to get the revocation list of an Authority node
+const AUTHORITY_NODE_REVOCATION_LIST_PATH: &str = "/revocation_list";
+
 /// Information necessary to connect to a remote credential retriever
 #[derive(Debug, Clone)]
 pub struct RemoteCredentialRetrieverInfo {
@@ -50,6 +53,8 @@ pub struct RemoteCredentialRetrieverInfo {
     pub api_service_address: String,
     /// Request method, e.g. Post or Get
     pub request_method: Method,
+    /// Request path used to get the issuer's revocation list with a Get request, if supported
+    pub revocation_list_api_service_address: Option<String>,
 }
 
 impl RemoteCredentialRetrieverInfo {
@@ -62,6 +67,7 @@ impl RemoteCredentialRetrieverInfo {
             CredentialIssuerApiServiceAddress::AuthorityNode.to_string(),
             Method::Post,
         )
+        .with_revocation_list_api_service_address(AUTHORITY_NODE_REVOCATION_LIST_PATH)
     }
 
     /// Create info for a project admin credential that we get from the Orchestrator
@@ -100,6 +106,13 @@ impl RemoteCredentialRetrieverInfo {
             service_address,
             api_service_address,
             request_method,
+            revocation_list_api_service_address: None,
         }
     }
+
+    /// Set the request path used to get the issuer's revocation list
+    pub fn with_revocation_list_api_service_address(mut self, path: &str) -> Self {
+        self.revocation_list_api_service_address = Some(path.to_string());
+        self
+    }
 }
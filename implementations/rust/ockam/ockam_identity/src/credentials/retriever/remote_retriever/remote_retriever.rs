@@ -1,7 +1,7 @@
 use core::cmp::max;
 use tracing::{debug, error, info, trace, warn};
 
-use ockam_core::api::Request;
+use ockam_core::api::{Reply, Request, Status};
 use ockam_core::compat::string::String;
 use ockam_core::compat::sync::{Arc, RwLock};
 use ockam_core::compat::time::Duration;
@@ -11,7 +11,7 @@ use ockam_node::compat::asynchronous::Mutex;
 use ockam_node::Context;
 use ockam_transport_core::Transport;
 
-use crate::models::CredentialAndPurposeKey;
+use crate::models::{CredentialAndPurposeKey, RevocationListAndPurposeKey};
 use crate::utils::now;
 use crate::{
     get_default_timeout, CachedCredentialRetriever, Identifier, RemoteCredentialRetrieverInfo,
@@ -32,6 +32,9 @@ pub const DEFAULT_CREDENTIAL_REQUEST_TIMEOUT: Duration = Duration::from_secs(15)
 /// Start refresh in the background before it expires
 pub const DEFAULT_CREDENTIAL_PROACTIVE_REFRESH_GAP: TimestampInSeconds = TimestampInSeconds(60);
 
+/// Default interval between 2 requests for the revocation list of the Authority
+pub const DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
 /// Timing options for retrieving remote credentials
 #[derive(Clone, Copy)]
 pub struct RemoteCredentialRetrieverTimingOptions {
@@ -46,6 +49,8 @@ pub struct RemoteCredentialRetrieverTimingOptions {
     /// Time gap used to consider credential expired before its actual expiration
     /// to account for time errors on different machines
     pub clock_skew_gap: TimestampInSeconds,
+    /// Interval between 2 requests for the revocation list of the Authority node
+    pub revocation_list_refresh_interval: Duration,
 }
 
 impl Default for RemoteCredentialRetrieverTimingOptions {
@@ -56,6 +61,7 @@ impl Default for RemoteCredentialRetrieverTimingOptions {
             min_refresh_interval: DEFAULT_MIN_REFRESH_CREDENTIAL_INTERVAL,
             proactive_refresh_gap: DEFAULT_PROACTIVE_REFRESH_CREDENTIAL_TIME_GAP,
             clock_skew_gap: DEFAULT_CREDENTIAL_CLOCK_SKEW_GAP,
+            revocation_list_refresh_interval: DEFAULT_REVOCATION_LIST_REFRESH_INTERVAL,
         }
     }
 }
@@ -165,6 +171,11 @@ impl RemoteCredentialRetriever {
             self.schedule_credentials_refresh_impl(refresh_in.duration, false);
         }
 
+        // Keep the revocation list of the authority up to date, when it publishes one
+        if let Some(path) = &self.issuer_info.revocation_list_api_service_address {
+            self.schedule_revocation_list_refresh(path.clone(), Duration::ZERO)?;
+        }
+
         *is_initialized = true;
 
         Ok(())
@@ -356,6 +367,83 @@ impl RemoteCredentialRetriever {
         Ok(())
     }
 
+    /// Retrieve the revocation list of the authority.
+    /// Return false if the authority doesn't publish a revocation list at that path
+    async fn get_revocation_list(&self, path: &str) -> Result<bool> {
+        debug!(issuer=%self.issuer_info.issuer, "retrieving the revocation list");
+        let client = SecureClient::new(
+            self.secure_channels.clone(),
+            None,
+            self.transport.clone(),
+            self.issuer_info.route.clone(),
+            &self.issuer_info.issuer,
+            &self.subject,
+            self.timing_options.secure_channel_creation_timeout,
+            self.timing_options.request_timeout,
+        );
+
+        let reply: Reply<RevocationListAndPurposeKey> = client
+            .ask(
+                &self.ctx,
+                &self.issuer_info.service_address,
+                Request::get(path),
+            )
+            .await?;
+        let revocation_list = match reply {
+            // authorities which don't publish revocation lists answer with an unknown path
+            Reply::Failed(_, Some(Status::BadRequest | Status::NotFound)) => return Ok(false),
+            reply => reply.success()?,
+        };
+
+        let is_updated = self
+            .secure_channels
+            .identities()
+            .credentials()
+            .credentials_verification()
+            .receive_revocation_list(&[self.issuer_info.issuer.clone()], &revocation_list)
+            .await?;
+
+        if is_updated {
+            info!(issuer=%self.issuer_info.issuer, "updated the revocation list");
+        }
+
+        Ok(true)
+    }
+
+    /// Retrieve the revocation list of the authority in the background after `wait`
+    fn schedule_revocation_list_refresh(&self, path: String, wait: Duration) -> Result<()> {
+        let refresh_at = *now()? + wait.as_secs();
+        let s = self.clone();
+        ockam_node::spawn(async move {
+            s.ctx.sleep_long_until(refresh_at).await;
+            s.refresh_revocation_list(path).await;
+        });
+        Ok(())
+    }
+
+    /// Retrieve the revocation list of the authority, then schedule the next request unless
+    /// the authority doesn't publish a revocation list
+    async fn refresh_revocation_list(&self, path: String) {
+        match self.get_revocation_list(&path).await {
+            Ok(true) => {}
+            Ok(false) => {
+                info!(issuer=%self.issuer_info.issuer,
+                    "the authority doesn't publish a revocation list, it won't be requested again");
+                return;
+            }
+            Err(err) => {
+                warn!(issuer=%self.issuer_info.issuer, %err,
+                    "error refreshing the revocation list in the background");
+            }
+        }
+
+        let wait = self.timing_options.revocation_list_refresh_interval;
+        if let Err(err) = self.schedule_revocation_list_refresh(path, wait) {
+            error!(issuer=%self.issuer_info.issuer, %err,
+                "error scheduling the refresh of the revocation list");
+        }
+    }
+
     fn request_new_credential_in_background(&self, wait: Duration, is_retry: bool) {
         let s = self.clone();
         ockam_node::spawn(async move {
@@ -70,6 +70,20 @@ pub enum IdentityError {
     UnknownRole,
     /// Handshake ended up in an internal invalid state
     HandshakeInternalError,
+    /// The other side of the secure channel used an unknown key for a key ratchet step
+    UnknownRatchetKey,
+    /// The rekey interval of a secure channel must be at least one second
+    InvalidRekeyInterval,
+    /// The threshold keys are invalid or not enough of them were used to sign an identity change
+    InvalidThresholdKeys,
+    /// Unknown version of the RevocationList
+    UnknownRevocationListVersion,
+    /// Invalid data_type value for RevocationList
+    InvalidRevocationListDataType,
+    /// RevocationList Verification Failed
+    RevocationListVerificationFailed,
+    /// The Credential was revoked by its Authority
+    CredentialRevoked,
 }
 
 impl ockam_core::compat::error::Error for IdentityError {}
@@ -1,6 +1,7 @@
 use crate::utils::now;
 use crate::{AttributesEntry, Identifier, IdentityAttributesRepository};
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 use tracing_attributes::instrument;
 
@@ -36,6 +37,13 @@ impl IdentitiesAttributes {
         self.repository.get_attributes(subject, attested_by).await
     }
 
+    /// Return the attributes of all the identities, after deleting the expired attributes
+    #[instrument(skip_all)]
+    pub async fn get_all_attributes(&self) -> Result<Vec<(Identifier, AttributesEntry)>> {
+        self.repository.delete_expired_attributes(now()?).await?;
+        self.repository.get_all_attributes().await
+    }
+
     /// Set the attributes associated with the given identity identifier.
     /// Previous values gets overridden.
     #[instrument(skip_all, fields(subject = %subject, entry = %entry))]
@@ -1,11 +1,12 @@
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
-use ockam_vault::{SigningKeyType, SigningSecretKeyHandle};
+use ockam_vault::{SigningKeyType, SigningSecretKeyHandle, VerifyingPublicKey};
 
-use crate::models::TimestampInSeconds;
+use crate::models::{ThresholdKeys, TimestampInSeconds};
 use crate::utils::now;
-use crate::IdentityOptions;
 use crate::{Identifier, IdentitiesCreation};
+use crate::{IdentityError, IdentityOptions};
 
 /// Default TTL for an Identity key
 pub const DEFAULT_IDENTITY_TTL: TimestampInSeconds = TimestampInSeconds(10 * 365 * 24 * 60 * 60); // Ten years
@@ -30,6 +31,8 @@ pub struct IdentityBuilder {
     revoke_all_purpose_keys: bool,
     key: Key,
     ttl: Ttl,
+    threshold_keys: Option<ThresholdKeys>,
+    threshold_signing_keys: Vec<SigningSecretKeyHandle>,
 }
 
 impl IdentityBuilder {
@@ -40,6 +43,8 @@ impl IdentityBuilder {
             revoke_all_purpose_keys: false,
             key: Key::Generate(SigningKeyType::EdDSACurve25519),
             ttl: Ttl::CreatedNowWithTtl(DEFAULT_IDENTITY_TTL),
+            threshold_keys: None,
+            threshold_signing_keys: vec![],
         }
     }
 
@@ -80,8 +85,34 @@ impl IdentityBuilder {
         self
     }
 
+    /// Require the next key rotation to be signed by at least `threshold` of the given keys,
+    /// instead of the Identity key only
+    pub fn with_threshold_keys(
+        mut self,
+        threshold: u8,
+        public_keys: Vec<VerifyingPublicKey>,
+    ) -> Self {
+        self.threshold_keys = Some(ThresholdKeys::new(threshold, public_keys));
+        self
+    }
+
+    /// Sign a key rotation with threshold keys (should be present in the corresponding [`SigningVault`])
+    pub fn with_threshold_signing_keys(
+        mut self,
+        signing_secret_key_handles: Vec<SigningSecretKeyHandle>,
+    ) -> Self {
+        self.threshold_signing_keys = signing_secret_key_handles;
+        self
+    }
+
     /// Create the corresponding [`IdentityOptions`] object
     pub async fn build_options(self) -> Result<IdentityOptions> {
+        if let Some(threshold_keys) = &self.threshold_keys {
+            if !threshold_keys.is_valid() {
+                return Err(IdentityError::InvalidThresholdKeys)?;
+            }
+        }
+
         let key = match self.key {
             Key::Generate(stype) => {
                 self.identities_creation
@@ -105,12 +136,16 @@ impl IdentityBuilder {
             } => (attestations_valid_from, attestations_valid_until),
         };
 
-        let options = IdentityOptions::new(
+        let mut options = IdentityOptions::new(
             key,
             self.revoke_all_purpose_keys,
             attestations_valid_from,
             attestations_valid_until,
-        );
+        )
+        .with_threshold_signing_keys(self.threshold_signing_keys);
+        if let Some(threshold_keys) = self.threshold_keys {
+            options = options.with_threshold_keys(threshold_keys);
+        }
 
         Ok(options)
     }
@@ -1,8 +1,11 @@
 use crate::identity::Identity;
-use crate::models::{Change, ChangeData, ChangeHash, ChangeHistory};
+use crate::models::{
+    Change, ChangeData, ChangeHash, ChangeHistory, ThresholdKeys, ThresholdSignature,
+};
 use crate::{IdentityError, IdentityOptions};
 
 use ockam_core::compat::sync::Arc;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 
 use ockam_vault::{SigningSecretKeyHandle, VaultForSigning, VaultForVerifyingSignatures};
@@ -44,6 +47,10 @@ impl IdentitiesKeys {
     }
 
     /// Rotate the Identity Key
+    ///
+    /// If the current key designated threshold keys, the rotation must be signed with
+    /// enough of them, see [`IdentityOptions::with_threshold_signing_keys`]. In that case
+    /// the current key is not required to sign the rotation
     pub async fn rotate_key_with_options(
         &self,
         identity: Identity,
@@ -53,13 +60,29 @@ impl IdentitiesKeys {
             Some(last_change) => last_change,
             None => return Err(IdentityError::EmptyIdentity)?,
         };
+        let last_version = match identity.change_history().0.last() {
+            Some(change) => change.get_version()?,
+            None => return Err(IdentityError::EmptyIdentity)?,
+        };
+        let threshold_keys = last_change.data().threshold_keys.clone();
 
-        let last_secret_key = self.get_secret_key(&identity).await?;
+        // The current key is optional when the rotation is authorized by threshold keys,
+        // for example if it was lost
+        let last_secret_key = if threshold_keys.is_some() {
+            self.find_secret_key(&identity).await
+        } else {
+            Some(self.get_secret_key(&identity).await?)
+        };
 
         let change = self
             .make_change(
                 options,
-                Some((last_change.change_hash().clone(), last_secret_key.clone())),
+                Some(PreviousChange {
+                    change_hash: last_change.change_hash().clone(),
+                    version: last_version,
+                    secret_key: last_secret_key.clone(),
+                    threshold_keys,
+                }),
             )
             .await?;
 
@@ -67,16 +90,18 @@ impl IdentitiesKeys {
             .add_change(change, self.verifying_vault.clone())
             .await?;
 
-        if self
-            .identity_vault
-            .delete_signing_secret_key(last_secret_key)
-            .await
-            .is_err()
-        {
-            error!(
-                "Error deleting old Identity Key for {}",
-                identity.identifier()
-            );
+        if let Some(last_secret_key) = last_secret_key {
+            if self
+                .identity_vault
+                .delete_signing_secret_key(last_secret_key)
+                .await
+                .is_err()
+            {
+                error!(
+                    "Error deleting old Identity Key for {}",
+                    identity.identifier()
+                );
+            }
         }
 
         Ok(identity)
@@ -94,33 +119,50 @@ impl IdentitiesKeys {
     }
 }
 
+/// Previous change of an identity, when rotating its key
+struct PreviousChange {
+    change_hash: ChangeHash,
+    version: u8,
+    secret_key: Option<SigningSecretKeyHandle>,
+    threshold_keys: Option<ThresholdKeys>,
+}
+
 /// Private  functions
 impl IdentitiesKeys {
+    /// Return the secret key of an identity if it is present in the vault
+    async fn find_secret_key(&self, identity: &Identity) -> Option<SigningSecretKeyHandle> {
+        let secret_key = self.get_secret_key(identity).await.ok()?;
+        self.identity_vault
+            .get_verifying_public_key(&secret_key)
+            .await
+            .ok()
+            .map(|_| secret_key)
+    }
+
     /// Create a new key
     async fn make_change(
         &self,
         identity_options: IdentityOptions,
-        previous: Option<(ChangeHash, SigningSecretKeyHandle)>,
+        previous: Option<PreviousChange>,
     ) -> Result<Change> {
         let secret_key = identity_options.signing_secret_key_handle;
         let public_key = self
             .identity_vault
             .get_verifying_public_key(&secret_key)
             .await?;
-        let (previous_change, previous_key) = previous
-            .map(|(x, y)| (Some(x), Some(y)))
-            .unwrap_or((None, None));
         let change_data = ChangeData {
-            previous_change,
+            previous_change: previous.as_ref().map(|p| p.change_hash.clone()),
             primary_public_key: public_key.into(),
             revoke_all_purpose_keys: identity_options.revoke_all_purpose_keys,
             attestations_valid_from: identity_options.attestations_valid_from,
             attestations_valid_until: identity_options.attestations_valid_until,
+            threshold_keys: identity_options.threshold_keys,
         };
+        let version = change_data.required_version(previous.as_ref().map(|p| p.version));
 
         let change_data = ockam_core::cbor_encode_preallocate(&change_data)?;
 
-        let versioned_data = Change::create_versioned_data(change_data);
+        let versioned_data = Change::create_versioned_data(version, change_data);
         let versioned_data = ockam_core::cbor_encode_preallocate(&versioned_data)?;
 
         let hash = self.verifying_vault.sha256(&versioned_data).await?;
@@ -128,8 +170,13 @@ impl IdentitiesKeys {
         let self_signature = self.identity_vault.sign(&secret_key, &hash.0).await?;
         let self_signature = self_signature.into();
 
+        let (previous_key, threshold_keys) = previous
+            .map(|p| (p.secret_key, p.threshold_keys))
+            .unwrap_or((None, None));
+
         // If we have previous_key passed we should sign using it
-        // If there is no previous_key - we're creating new identity, so we just generated the key
+        // If there is no previous_key - we're creating new identity, so we just generated the key,
+        // or the change is authorized by threshold keys
         let previous_signature = match previous_key {
             Some(previous_key) => {
                 let previous_signature = self.identity_vault.sign(&previous_key, &hash.0).await?;
@@ -139,14 +186,66 @@ impl IdentitiesKeys {
             None => None,
         };
 
+        let threshold_signatures = match threshold_keys {
+            Some(threshold_keys) => Some(
+                self.make_threshold_signatures(
+                    &threshold_keys,
+                    &identity_options.threshold_signing_keys,
+                    &hash.0,
+                )
+                .await?,
+            ),
+            None if identity_options.threshold_signing_keys.is_empty() => None,
+            // There are no threshold keys to sign this change
+            None => return Err(IdentityError::InvalidThresholdKeys)?,
+        };
+
         let change = Change {
             data: versioned_data,
             signature: self_signature,
             previous_signature,
+            threshold_signatures,
         };
 
         Ok(change)
     }
+
+    /// Sign a change with keys designated by the previous change
+    async fn make_threshold_signatures(
+        &self,
+        threshold_keys: &ThresholdKeys,
+        signing_keys: &[SigningSecretKeyHandle],
+        hash: &[u8],
+    ) -> Result<Vec<ThresholdSignature>> {
+        let mut threshold_signatures = Vec::with_capacity(signing_keys.len());
+        for signing_key in signing_keys {
+            let public_key = self
+                .identity_vault
+                .get_verifying_public_key(signing_key)
+                .await?;
+            let key_index = match threshold_keys.key_index(&public_key) {
+                Some(key_index) => key_index,
+                None => return Err(IdentityError::InvalidThresholdKeys)?,
+            };
+            // a key can only be counted once
+            if threshold_signatures
+                .iter()
+                .any(|s: &ThresholdSignature| s.key_index == key_index)
+            {
+                continue;
+            }
+            let signature = self.identity_vault.sign(signing_key, hash).await?;
+            threshold_signatures.push(ThresholdSignature {
+                key_index,
+                signature: signature.into(),
+            });
+        }
+
+        if threshold_signatures.len() < threshold_keys.threshold as usize {
+            return Err(IdentityError::InvalidThresholdKeys)?;
+        }
+        Ok(threshold_signatures)
+    }
 }
 
 #[cfg(test)]
@@ -279,4 +378,107 @@ mod test {
             .is_ok());
         Ok(())
     }
+
+    #[tokio::test]
+    async fn test_threshold_key_rotation() -> Result<()> {
+        let identities = identities().await?;
+        let identities_keys = identities.identities_keys();
+        let vault = identities.vault().identity_vault;
+
+        let mut threshold_secret_keys = vec![];
+        let mut threshold_public_keys = vec![];
+        for _ in 0..3 {
+            let key = vault
+                .generate_signing_secret_key(SigningKeyType::EdDSACurve25519)
+                .await?;
+            threshold_public_keys.push(vault.get_verifying_public_key(&key).await?);
+            threshold_secret_keys.push(key);
+        }
+
+        // the threshold must be reachable
+        let options = identities
+            .identities_creation()
+            .identity_builder()
+            .with_threshold_keys(4, threshold_public_keys.clone())
+            .build_options()
+            .await;
+        assert!(options.is_err());
+
+        let options = identities
+            .identities_creation()
+            .identity_builder()
+            .with_threshold_keys(2, threshold_public_keys.clone())
+            .build_options()
+            .await?;
+        let identity = identities_keys.create_initial_key(options).await?;
+        assert_eq!(
+            identity.change_history().0[0].get_version()?,
+            crate::models::THRESHOLD_CHANGE_DATA_VERSION
+        );
+
+        // a single threshold key is not enough to rotate the identity key
+        let options = identities
+            .identities_creation()
+            .identity_builder()
+            .with_threshold_signing_keys(vec![
+                threshold_secret_keys[0].clone(),
+                threshold_secret_keys[0].clone(),
+            ])
+            .build_options()
+            .await?;
+        let result = identities_keys
+            .rotate_key_with_options(identity.clone(), options)
+            .await;
+        assert!(result.is_err());
+
+        // the current key is not needed when enough threshold keys sign the rotation
+        let current_key = identities_keys.get_secret_key(&identity).await?;
+        vault.delete_signing_secret_key(current_key).await?;
+        let options = identities
+            .identities_creation()
+            .identity_builder()
+            .with_threshold_signing_keys(vec![
+                threshold_secret_keys[0].clone(),
+                threshold_secret_keys[2].clone(),
+            ])
+            .build_options()
+            .await?;
+        let rotated = identities_keys
+            .rotate_key_with_options(identity.clone(), options)
+            .await?;
+        assert!(rotated.change_history().0[1].previous_signature.is_none());
+
+        let imported = Identity::import_from_change_history(
+            Some(identity.identifier()),
+            rotated.change_history().clone(),
+            identities.vault().verifying_vault,
+        )
+        .await?;
+        assert_eq!(imported, rotated);
+
+        // the rotation is rejected if the threshold signatures are removed
+        let mut change_history = rotated.change_history().clone();
+        change_history.0[1].threshold_signatures = Some(vec![change_history.0[1]
+            .threshold_signatures
+            .as_ref()
+            .unwrap()[0]
+            .clone()]);
+        let result = Identity::import_from_change_history(
+            Some(identity.identifier()),
+            change_history.clone(),
+            identities.vault().verifying_vault,
+        )
+        .await;
+        assert!(result.is_err());
+
+        change_history.0[1].threshold_signatures = None;
+        let result = Identity::import_from_change_history(
+            Some(identity.identifier()),
+            change_history,
+            identities.vault().verifying_vault,
+        )
+        .await;
+        assert!(result.is_err());
+        Ok(())
+    }
 }
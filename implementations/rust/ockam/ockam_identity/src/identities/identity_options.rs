@@ -1,4 +1,6 @@
+use crate::models::ThresholdKeys;
 use crate::TimestampInSeconds;
+use ockam_core::compat::vec::Vec;
 use ockam_vault::SigningSecretKeyHandle;
 
 /// Options to create an Identity key
@@ -7,6 +9,8 @@ pub struct IdentityOptions {
     pub(super) revoke_all_purpose_keys: bool,
     pub(super) attestations_valid_from: TimestampInSeconds,
     pub(super) attestations_valid_until: TimestampInSeconds,
+    pub(super) threshold_keys: Option<ThresholdKeys>,
+    pub(super) threshold_signing_keys: Vec<SigningSecretKeyHandle>,
 }
 
 impl IdentityOptions {
@@ -22,9 +26,26 @@ impl IdentityOptions {
             revoke_all_purpose_keys,
             attestations_valid_from,
             attestations_valid_until,
+            threshold_keys: None,
+            threshold_signing_keys: vec![],
         }
     }
 
+    /// Require the next key rotation to be signed by M of N designated keys
+    pub fn with_threshold_keys(mut self, threshold_keys: ThresholdKeys) -> Self {
+        self.threshold_keys = Some(threshold_keys);
+        self
+    }
+
+    /// Sign the key rotation with some of the threshold keys designated by the previous key
+    pub fn with_threshold_signing_keys(
+        mut self,
+        threshold_signing_keys: Vec<SigningSecretKeyHandle>,
+    ) -> Self {
+        self.threshold_signing_keys = threshold_signing_keys;
+        self
+    }
+
     /// New key
     pub fn signing_secret_key_handle(&self) -> &SigningSecretKeyHandle {
         &self.signing_secret_key_handle
@@ -44,4 +65,14 @@ impl IdentityOptions {
     pub fn attestations_valid_until(&self) -> TimestampInSeconds {
         self.attestations_valid_until
     }
+
+    /// Keys which must sign the next key rotation
+    pub fn threshold_keys(&self) -> Option<&ThresholdKeys> {
+        self.threshold_keys.as_ref()
+    }
+
+    /// Threshold keys used to sign the key rotation
+    pub fn threshold_signing_keys(&self) -> &[SigningSecretKeyHandle] {
+        &self.threshold_signing_keys
+    }
 }
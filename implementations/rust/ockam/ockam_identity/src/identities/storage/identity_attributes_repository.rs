@@ -1,6 +1,7 @@
 use crate::{AttributesEntry, Identifier, TimestampInSeconds};
 use async_trait::async_trait;
 use ockam_core::compat::boxed::Box;
+use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
 #[cfg(feature = "std")]
 use ockam_node::database::AutoRetry;
@@ -17,12 +18,19 @@ pub trait IdentityAttributesRepository: Send + Sync + 'static {
         attested_by: &Identifier,
     ) -> Result<Option<AttributesEntry>>;
 
+    /// Get the attributes of all the identities
+    async fn get_all_attributes(&self) -> Result<Vec<(Identifier, AttributesEntry)>>;
+
     /// Set the attributes associated with the given identity identifier.
     /// Previous values gets overridden.
     async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()>;
 
     /// Remove all expired attributes
     async fn delete_expired_attributes(&self, now: TimestampInSeconds) -> Result<()>;
+
+    /// Remove the attributes attested by the given authority for the given identity identifier
+    async fn delete_attributes(&self, subject: &Identifier, attested_by: &Identifier)
+        -> Result<()>;
 }
 
 #[cfg(feature = "std")]
@@ -36,6 +44,10 @@ impl<T: IdentityAttributesRepository> IdentityAttributesRepository for AutoRetry
         retry!(self.wrapped.get_attributes(subject, attested_by))
     }
 
+    async fn get_all_attributes(&self) -> Result<Vec<(Identifier, AttributesEntry)>> {
+        retry!(self.wrapped.get_all_attributes())
+    }
+
     async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()> {
         retry!(self.wrapped.put_attributes(subject, entry.clone()))
     }
@@ -43,4 +55,12 @@ impl<T: IdentityAttributesRepository> IdentityAttributesRepository for AutoRetry
     async fn delete_expired_attributes(&self, now: TimestampInSeconds) -> Result<()> {
         retry!(self.wrapped.delete_expired_attributes(now))
     }
+
+    async fn delete_attributes(
+        &self,
+        subject: &Identifier,
+        attested_by: &Identifier,
+    ) -> Result<()> {
+        retry!(self.wrapped.delete_attributes(subject, attested_by))
+    }
 }
@@ -72,6 +72,18 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
         Ok(identity_attributes.map(|r| r.attributes()).transpose()?)
     }
 
+    async fn get_all_attributes(&self) -> Result<Vec<(Identifier, AttributesEntry)>> {
+        let query = query_as(
+            "SELECT identifier, attributes, added, expires, attested_by FROM identity_attributes WHERE node_name = $1"
+            )
+            .bind(&self.node_name);
+        let rows: Vec<IdentityAttributesRow> =
+            query.fetch_all(&*self.database.pool).await.into_core()?;
+        rows.into_iter()
+            .map(|r| Ok((r.identifier()?, r.attributes()?)))
+            .collect()
+    }
+
     async fn put_attributes(&self, subject: &Identifier, entry: AttributesEntry) -> Result<()> {
         let query = query(
             r#"
@@ -95,6 +107,20 @@ impl IdentityAttributesRepository for IdentityAttributesSqlxDatabase {
             .bind(&self.node_name);
         query.execute(&*self.database.pool).await.void()
     }
+
+    async fn delete_attributes(
+        &self,
+        subject: &Identifier,
+        attested_by: &Identifier,
+    ) -> Result<()> {
+        let query = query(
+            "DELETE FROM identity_attributes WHERE identifier = $1 AND attested_by = $2 AND node_name = $3",
+        )
+        .bind(subject)
+        .bind(attested_by)
+        .bind(&self.node_name);
+        query.execute(&*self.database.pool).await.void()
+    }
 }
 
 // Database serialization / deserialization
@@ -125,7 +151,6 @@ struct IdentityAttributesRow {
 }
 
 impl IdentityAttributesRow {
-    #[allow(dead_code)]
     fn identifier(&self) -> Result<Identifier> {
         Identifier::from_str(&self.identifier)
     }
@@ -195,6 +220,33 @@ mod tests {
                 .await?;
             assert_eq!(result, Some(attributes2.clone()));
 
+            // retrieve the attributes of all the identities
+            let mut result = repository.get_all_attributes().await?;
+            result.sort_by(|a, b| a.0.cmp(&b.0));
+            let mut expected = vec![
+                (identifier1.clone(), attributes1.clone()),
+                (identifier2.clone(), attributes2.clone()),
+            ];
+            expected.sort_by(|a, b| a.0.cmp(&b.0));
+            assert_eq!(result, expected);
+
+            // only the attributes attested by the given authority are deleted
+            repository
+                .delete_attributes(&identifier1, &identifier2)
+                .await?;
+            let result = repository
+                .get_attributes(&identifier1, &identifier1)
+                .await?;
+            assert_eq!(result, Some(attributes1.clone()));
+
+            repository
+                .delete_attributes(&identifier1, &identifier1)
+                .await?;
+            let result = repository
+                .get_attributes(&identifier1, &identifier1)
+                .await?;
+            assert_eq!(result, None);
+
             Ok(())
         })
         .await
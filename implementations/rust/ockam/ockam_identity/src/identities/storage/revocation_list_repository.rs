@@ -0,0 +1,49 @@
+use crate::models::RevocationListAndPurposeKey;
+use crate::{Identifier, TimestampInSeconds};
+use async_trait::async_trait;
+use ockam_core::compat::boxed::Box;
+use ockam_core::Result;
+#[cfg(feature = "std")]
+use ockam_node::database::AutoRetry;
+#[cfg(feature = "std")]
+use ockam_node::retry;
+
+/// This trait supports the persistence of the revocation lists received from authorities
+#[async_trait]
+pub trait RevocationListRepository: Send + Sync + 'static {
+    /// Get the latest revocation list issued by the given authority
+    async fn get_revocation_list(
+        &self,
+        authority: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>>;
+
+    /// Put the revocation list issued by an authority (overwriting)
+    async fn put_revocation_list(
+        &self,
+        authority: &Identifier,
+        created_at: TimestampInSeconds,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<()>;
+}
+
+#[cfg(feature = "std")]
+#[async_trait]
+impl<T: RevocationListRepository> RevocationListRepository for AutoRetry<T> {
+    async fn get_revocation_list(
+        &self,
+        authority: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>> {
+        retry!(self.wrapped.get_revocation_list(authority))
+    }
+
+    async fn put_revocation_list(
+        &self,
+        authority: &Identifier,
+        created_at: TimestampInSeconds,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<()> {
+        retry!(self
+            .wrapped
+            .put_revocation_list(authority, created_at, revocation_list.clone()))
+    }
+}
@@ -0,0 +1,177 @@
+use sqlx::encode::IsNull;
+use sqlx::error::BoxDynError;
+use sqlx::*;
+use sqlx_core::any::AnyArgumentBuffer;
+use std::sync::Arc;
+use tracing::debug;
+
+use crate::models::{Identifier, RevocationListAndPurposeKey};
+use crate::{RevocationListRepository, TimestampInSeconds};
+use ockam_core::async_trait;
+use ockam_core::Result;
+use ockam_node::database::AutoRetry;
+use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+
+/// Implementation of [`RevocationListRepository`] trait based on an underlying database
+/// using sqlx as its API, and Sqlite as its driver
+#[derive(Clone)]
+pub struct RevocationListSqlxDatabase {
+    database: SqlxDatabase,
+    node_name: String,
+}
+
+impl RevocationListSqlxDatabase {
+    /// Create a new database
+    pub fn new(database: SqlxDatabase, node_name: &str) -> Self {
+        debug!("create a repository for revocation lists");
+        Self {
+            database,
+            node_name: node_name.to_string(),
+        }
+    }
+
+    /// Create a repository
+    pub fn make_repository(
+        database: SqlxDatabase,
+        node_name: &str,
+    ) -> Arc<dyn RevocationListRepository> {
+        if database.needs_retry() {
+            Arc::new(AutoRetry::new(Self::new(database, node_name)))
+        } else {
+            Arc::new(Self::new(database, node_name))
+        }
+    }
+
+    /// Create a new in-memory database
+    pub async fn create() -> Result<Self> {
+        Ok(Self::new(
+            SqlxDatabase::in_memory("revocation list").await?,
+            "default",
+        ))
+    }
+}
+
+#[async_trait]
+impl RevocationListRepository for RevocationListSqlxDatabase {
+    async fn get_revocation_list(
+        &self,
+        authority: &Identifier,
+    ) -> Result<Option<RevocationListAndPurposeKey>> {
+        let query = query_as(
+            "SELECT revocation_list FROM credential_revocation_list WHERE authority_identifier = $1 AND node_name = $2",
+        )
+        .bind(authority)
+        .bind(&self.node_name);
+        let row: Option<RevocationListRow> = query
+            .fetch_optional(&*self.database.pool)
+            .await
+            .into_core()?;
+        row.map(|r| r.revocation_list()).transpose()
+    }
+
+    async fn put_revocation_list(
+        &self,
+        authority: &Identifier,
+        created_at: TimestampInSeconds,
+        revocation_list: RevocationListAndPurposeKey,
+    ) -> Result<()> {
+        let query = query(
+            r#"INSERT INTO credential_revocation_list (authority_identifier, created_at, revocation_list, node_name)
+            VALUES ($1, $2, $3, $4)
+            ON CONFLICT (authority_identifier, node_name)
+            DO UPDATE SET created_at = $2, revocation_list = $3"#,
+        )
+        .bind(authority)
+        .bind(created_at)
+        .bind(revocation_list)
+        .bind(&self.node_name);
+        query.execute(&*self.database.pool).await.void()
+    }
+}
+
+// Database serialization / deserialization
+
+impl Type<Any> for RevocationListAndPurposeKey {
+    fn type_info() -> <Any as Database>::TypeInfo {
+        <Vec<u8> as Type<Any>>::type_info()
+    }
+}
+
+impl Encode<'_, Any> for RevocationListAndPurposeKey {
+    fn encode_by_ref(&self, buf: &mut AnyArgumentBuffer) -> Result<IsNull, BoxDynError> {
+        <Vec<u8> as Encode<'_, Any>>::encode_by_ref(&self.encode_as_cbor_bytes().unwrap(), buf)
+    }
+}
+
+// Low-level representation of a table row
+#[derive(FromRow)]
+struct RevocationListRow {
+    revocation_list: Vec<u8>,
+}
+
+impl RevocationListRow {
+    fn revocation_list(&self) -> Result<RevocationListAndPurposeKey> {
+        RevocationListAndPurposeKey::decode_from_cbor_bytes(&self.revocation_list)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ockam_core::compat::sync::Arc;
+    use ockam_node::database::with_dbs;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::identities;
+    use crate::models::RevokedSubject;
+    use crate::utils::now;
+
+    #[tokio::test]
+    async fn test_revocation_list_repository() -> Result<()> {
+        with_dbs(|db| async move {
+            let repository: Arc<dyn RevocationListRepository> =
+                Arc::new(RevocationListSqlxDatabase::new(db, "node"));
+
+            let identities = identities().await?;
+            let authority = identities.identities_creation().create_identity().await?;
+            let subject = identities.identities_creation().create_identity().await?;
+
+            let result = repository.get_revocation_list(&authority).await?;
+            assert_eq!(result, None);
+
+            let revoked_subjects = vec![RevokedSubject {
+                subject: subject.clone(),
+                revoked_at: now()?,
+            }];
+            let revocation_list1 = identities
+                .credentials()
+                .credentials_creation()
+                .issue_revocation_list(&authority, revoked_subjects, Duration::from_secs(60))
+                .await?;
+            let created_at = revocation_list1
+                .revocation_list
+                .get_revocation_list_data()?
+                .created_at;
+            repository
+                .put_revocation_list(&authority, created_at, revocation_list1.clone())
+                .await?;
+            let result = repository.get_revocation_list(&authority).await?;
+            assert_eq!(result, Some(revocation_list1));
+
+            // a new revocation list replaces the previous one
+            let revocation_list2 = identities
+                .credentials()
+                .credentials_creation()
+                .issue_revocation_list(&authority, vec![], Duration::from_secs(60))
+                .await?;
+            repository
+                .put_revocation_list(&authority, created_at, revocation_list2.clone())
+                .await?;
+            let result = repository.get_revocation_list(&authority).await?;
+            assert_eq!(result, Some(revocation_list2));
+
+            Ok(())
+        })
+        .await
+    }
+}
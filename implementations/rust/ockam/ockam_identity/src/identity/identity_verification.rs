@@ -1,7 +1,11 @@
-use crate::models::{Change, ChangeData, ChangeHash, ChangeSignature, VersionedData};
+use crate::models::{
+    Change, ChangeData, ChangeHash, ChangeSignature, ThresholdKeys, ThresholdSignature,
+    VersionedData,
+};
 use crate::verified_change::VerifiedChange;
 use crate::{Identity, IdentityError};
 
+use ockam_core::compat::collections::BTreeSet;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::Result;
@@ -66,6 +70,12 @@ impl Identity {
         for change in new_changes.iter() {
             let change_details = Self::get_change_details(change, vault.clone()).await?;
 
+            if let Some(threshold_keys) = &change_details.change_data.threshold_keys {
+                if !threshold_keys.is_valid() {
+                    return Err(IdentityError::IdentityVerificationFailed)?;
+                }
+            }
+
             if let Some(previous_change_details) = previous_change_details {
                 if previous_change_details.version > change_details.version {
                     // Version downgrade
@@ -150,6 +160,7 @@ impl Identity {
         let new_change_details = Self::get_change_details(new_change, vault.clone()).await?;
 
         if let Some(last_verified_change) = last_verified_change {
+            let threshold_keys = &last_verified_change.data().threshold_keys;
             if let Some(previous_signature) = &new_change.previous_signature {
                 if !Self::verify_change_signature(
                     last_verified_change.primary_public_key(),
@@ -161,10 +172,29 @@ impl Identity {
                 {
                     return Err(IdentityError::IdentityVerificationFailed)?;
                 }
-            } else {
-                // Previous signature should be present if it's not the first change
+            } else if threshold_keys.is_none() {
+                // Previous signature should be present if it's not the first change,
+                // unless the change is authorized by threshold keys
                 return Err(IdentityError::IdentityVerificationFailed)?;
             }
+
+            match (threshold_keys, &new_change.threshold_signatures) {
+                (Some(threshold_keys), Some(threshold_signatures)) => {
+                    Self::verify_threshold_signatures(
+                        threshold_keys,
+                        threshold_signatures,
+                        new_change_details.change_full_hash,
+                        vault.clone(),
+                    )
+                    .await?
+                }
+                (None, None) => (),
+                // The previous change requires threshold signatures, or they are unexpected
+                _ => return Err(IdentityError::IdentityVerificationFailed)?,
+            }
+        } else if new_change.threshold_signatures.is_some() {
+            // The first change can't be signed by threshold keys
+            return Err(IdentityError::IdentityVerificationFailed)?;
         }
 
         if !Self::verify_change_signature(
@@ -184,4 +214,40 @@ impl Identity {
 
         Ok(())
     }
+
+    /// Check that at least `threshold` distinct keys of the threshold keys signed a change
+    async fn verify_threshold_signatures(
+        threshold_keys: &ThresholdKeys,
+        threshold_signatures: &[ThresholdSignature],
+        hash: [u8; 32],
+        vault: Arc<dyn VaultForVerifyingSignatures>,
+    ) -> Result<()> {
+        let mut signers = BTreeSet::new();
+        for threshold_signature in threshold_signatures {
+            let public_key = match threshold_keys
+                .public_keys
+                .get(threshold_signature.key_index as usize)
+            {
+                Some(public_key) => public_key,
+                None => return Err(IdentityError::IdentityVerificationFailed)?,
+            };
+
+            if !Self::verify_change_signature(
+                &public_key.clone().into(),
+                hash,
+                &threshold_signature.signature,
+                vault.clone(),
+            )
+            .await?
+            {
+                return Err(IdentityError::IdentityVerificationFailed)?;
+            }
+            signers.insert(threshold_signature.key_index);
+        }
+
+        if signers.len() < threshold_keys.threshold as usize {
+            return Err(IdentityError::IdentityVerificationFailed)?;
+        }
+        Ok(())
+    }
 }
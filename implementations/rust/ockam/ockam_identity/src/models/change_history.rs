@@ -2,8 +2,8 @@ use crate::models::{ChangeHash, TimestampInSeconds};
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::compat::vec::Vec;
 use ockam_vault::{
-    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, EdDSACurve25519PublicKey,
-    EdDSACurve25519Signature,
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, ECDSASHA384CurveP384PublicKey,
+    ECDSASHA384CurveP384Signature, EdDSACurve25519PublicKey, EdDSACurve25519Signature,
 };
 
 /// Identity Change History
@@ -15,6 +15,13 @@ pub struct ChangeHistory(#[n(0)] pub Vec<Change>);
 /// `data_type` value in [`VersionedData`] struct when used with [`Change`]
 pub const CHANGE_DATA_TYPE: u8 = 1;
 
+/// `version` value in [`VersionedData`] struct when used with [`Change`]
+pub const CHANGE_DATA_VERSION: u8 = 1;
+
+/// `version` value in [`VersionedData`] struct when used with a [`Change`]
+/// whose [`ChangeData`] contains [`ThresholdKeys`]
+pub const THRESHOLD_CHANGE_DATA_VERSION: u8 = 2;
+
 /// Individual Identity change which implies replacing the old key
 #[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
 #[rustfmt::skip]
@@ -29,6 +36,9 @@ pub struct Change {
     /// Self-signature over the data using the key
     /// from the previous [`Change`] in the [`ChangeHistory`]
     #[n(2)] pub previous_signature: Option<ChangeSignature>,
+    /// Signatures over the data using the [`ThresholdKeys`]
+    /// from the previous [`Change`] in the [`ChangeHistory`]
+    #[n(3)] pub threshold_signatures: Option<Vec<ThresholdSignature>>,
 }
 
 /// [`Change`] signature
@@ -39,6 +49,8 @@ pub enum ChangeSignature {
     #[n(0)] EdDSACurve25519(#[n(0)] EdDSACurve25519Signature),
     /// Signature using ECDSA P256
     #[n(1)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256Signature),
+    /// Signature using ECDSA P384
+    #[n(2)] ECDSASHA384CurveP384(#[n(0)] ECDSASHA384CurveP384Signature),
 }
 
 /// Data inside a [`Change`]
@@ -62,6 +74,29 @@ pub struct ChangeData {
     ///  1. Sign a [`super::PurposeKeyAttestation`] that is tied to this Identifier
     ///  2. Sign [`ChangeData`] that belongs to the same [`ChangeHistory`] and goes straight after this one
     #[n(4)] pub attestations_valid_until: TimestampInSeconds,
+    /// Keys which must sign the [`ChangeData`] going straight after this one.
+    /// Only present if the version is [`THRESHOLD_CHANGE_DATA_VERSION`]
+    #[n(5)] pub threshold_keys: Option<ThresholdKeys>,
+}
+
+/// M-of-N set of keys authorizing the next [`Change`] in a [`ChangeHistory`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct ThresholdKeys {
+    /// Minimum number of keys which must sign the next [`Change`]
+    #[n(0)] pub threshold: u8,
+    /// Designated keys
+    #[n(1)] pub public_keys: Vec<PrimaryPublicKey>,
+}
+
+/// Signature of a [`Change`] by one of the [`ThresholdKeys`] of the previous [`Change`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct ThresholdSignature {
+    /// Index of the signing key in [`ThresholdKeys::public_keys`]
+    #[n(0)] pub key_index: u8,
+    /// Signature over the data
+    #[n(1)] pub signature: ChangeSignature,
 }
 
 /// [`Change`]'s public key
@@ -72,4 +107,6 @@ pub enum PrimaryPublicKey {
     #[n(0)] EdDSACurve25519(#[n(0)] EdDSACurve25519PublicKey),
     /// ECDSA P256 Public Key
     #[n(1)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256PublicKey),
+    /// ECDSA P384 Public Key
+    #[n(2)] ECDSASHA384CurveP384(#[n(0)] ECDSASHA384CurveP384PublicKey),
 }
@@ -4,7 +4,9 @@ use minicbor::bytes::ByteVec;
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::compat::string::String;
 use ockam_core::compat::{collections::BTreeMap, vec::Vec};
-use ockam_vault::{ECDSASHA256CurveP256Signature, EdDSACurve25519Signature};
+use ockam_vault::{
+    ECDSASHA256CurveP256Signature, ECDSASHA384CurveP384Signature, EdDSACurve25519Signature,
+};
 
 /// `data_type` value in [`VersionedData`] struct when used with [`Credential`]
 pub const CREDENTIAL_DATA_TYPE: u8 = 3;
@@ -30,6 +32,8 @@ pub enum CredentialSignature {
     #[n(0)] EdDSACurve25519(#[n(0)] EdDSACurve25519Signature),
     /// An ECDSA signature using SHA-256 and Curve P-256.
     #[n(1)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256Signature),
+    /// An ECDSA signature using SHA-384 and Curve P-384.
+    #[n(2)] ECDSASHA384CurveP384(#[n(0)] ECDSASHA384CurveP384Signature),
 }
 
 /// Data inside a [`Credential`]
@@ -4,8 +4,9 @@ use crate::models::{ChangeHash, Identifier, TimestampInSeconds};
 
 use minicbor::{CborLen, Decode, Encode};
 use ockam_vault::{
-    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, EdDSACurve25519PublicKey,
-    EdDSACurve25519Signature, X25519PublicKey,
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, ECDSASHA384CurveP384PublicKey,
+    ECDSASHA384CurveP384Signature, EdDSACurve25519PublicKey, EdDSACurve25519Signature,
+    X25519PublicKey,
 };
 
 /// `data_type` value in [`VersionedData`] struct when used with [`PurposeKeyAttestation`]
@@ -33,6 +34,8 @@ pub enum PurposeKeyAttestationSignature {
     #[n(0)] EdDSACurve25519(#[n(0)] EdDSACurve25519Signature),
     /// Signature using ECDSA P256 key from the corresponding [`super::super::identity::Identity`]
     #[n(1)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256Signature),
+    /// Signature using ECDSA P384 key from the corresponding [`super::super::identity::Identity`]
+    #[n(2)] ECDSASHA384CurveP384(#[n(0)] ECDSASHA384CurveP384Signature),
 }
 
 /// Data inside a [`PurposeKeyAttestation`]
@@ -71,4 +74,6 @@ pub enum CredentialVerifyingKey {
     #[n(0)] EdDSACurve25519(#[n(0)] EdDSACurve25519PublicKey),
     /// Curve P-256 Public Key for verifying ECDSA SHA256 signatures.
     #[n(1)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256PublicKey),
+    /// Curve P-384 Public Key for verifying ECDSA SHA384 signatures.
+    #[n(2)] ECDSASHA384CurveP384(#[n(0)] ECDSASHA384CurveP384PublicKey),
 }
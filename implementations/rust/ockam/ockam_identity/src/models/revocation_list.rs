@@ -0,0 +1,51 @@
+use crate::models::{CredentialSignature, Identifier, PurposeKeyAttestation, TimestampInSeconds};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::vec::Vec;
+
+/// `data_type` value in [`super::VersionedData`] struct when used with [`RevocationList`]
+pub const REVOCATION_LIST_DATA_TYPE: u8 = 4;
+
+/// List of subjects whose [`super::Credential`]s were revoked by an Authority
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationList {
+    /// CBOR serialized [`super::VersionedData`]
+    /// where VersionedData::data is CBOR serialized [`RevocationListData`]
+    /// and VersionedData::data_type is [`REVOCATION_LIST_DATA_TYPE`]
+    #[cbor(with = "minicbor::bytes")]
+    #[n(0)] pub data: Vec<u8>,
+    /// Signature over data field using corresponding Credentials [`super::PurposeKeyAttestation`]
+    #[n(1)] pub signature: CredentialSignature,
+}
+
+/// Data inside a [`RevocationList`]
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationListData {
+    /// Subjects whose credentials were revoked
+    #[n(0)] pub revoked_subjects: Vec<RevokedSubject>,
+    /// Creation [`TimestampInSeconds`] (UTC)
+    #[n(1)] pub created_at: TimestampInSeconds,
+    /// Expiration [`TimestampInSeconds`] (UTC)
+    #[n(2)] pub expires_at: TimestampInSeconds,
+}
+
+/// Subject whose credentials were revoked
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevokedSubject {
+    /// Identifier of the subject
+    #[n(0)] pub subject: Identifier,
+    /// Credentials issued to that subject at or before that [`TimestampInSeconds`] (UTC) are revoked
+    #[n(1)] pub revoked_at: TimestampInSeconds,
+}
+
+/// [`RevocationList`] and the corresponding [`PurposeKeyAttestation`] that was used to sign it
+#[derive(Clone, Debug, PartialEq, Eq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct RevocationListAndPurposeKey {
+    /// [`RevocationList`]
+    #[n(0)] pub revocation_list: RevocationList,
+    /// Corresponding [`PurposeKeyAttestation`] that was used to sign that [`RevocationList`]
+    #[n(1)] pub purpose_key_attestation: PurposeKeyAttestation,
+}
@@ -6,26 +6,34 @@ use ockam_vault::{Signature, VerifyingPublicKey};
 
 use crate::alloc::string::ToString;
 use crate::models::{
-    Change, ChangeData, ChangeHistory, ChangeSignature, PrimaryPublicKey, VersionedData,
-    CHANGE_DATA_TYPE,
+    Change, ChangeData, ChangeHistory, ChangeSignature, PrimaryPublicKey, ThresholdKeys,
+    VersionedData, CHANGE_DATA_TYPE, CHANGE_DATA_VERSION, THRESHOLD_CHANGE_DATA_VERSION,
 };
 use crate::IdentityError;
 
 impl Change {
     /// Create [`VersionedData`] with corresponding version and data_type
-    pub fn create_versioned_data(data: Vec<u8>) -> VersionedData {
+    pub fn create_versioned_data(version: u8, data: Vec<u8>) -> VersionedData {
         VersionedData {
-            version: 1,
+            version,
             data_type: CHANGE_DATA_TYPE,
             data,
         }
     }
+
+    /// Return the version of the [`VersionedData`] of this [`Change`]
+    pub fn get_version(&self) -> Result<u8> {
+        let versioned_data: VersionedData = minicbor::decode(&self.data)?;
+        Ok(versioned_data.version)
+    }
 }
 
 impl ChangeData {
     /// Extract [`ChangeData`] from [`VersionedData`]
     pub fn get_data(versioned_data: &VersionedData) -> Result<Self> {
-        if versioned_data.version != 1 {
+        if versioned_data.version != CHANGE_DATA_VERSION
+            && versioned_data.version != THRESHOLD_CHANGE_DATA_VERSION
+        {
             return Err(IdentityError::UnknownIdentityVersion)?;
         }
 
@@ -33,7 +41,59 @@ impl ChangeData {
             return Err(IdentityError::InvalidIdentityDataType)?;
         }
 
-        Ok(minicbor::decode(&versioned_data.data)?)
+        let change_data: ChangeData = minicbor::decode(&versioned_data.data)?;
+
+        // Threshold keys were introduced with the second version
+        if versioned_data.version == CHANGE_DATA_VERSION && change_data.threshold_keys.is_some() {
+            return Err(IdentityError::UnknownIdentityVersion)?;
+        }
+
+        Ok(change_data)
+    }
+
+    /// Return the version to use for this [`ChangeData`], given the version of the previous one
+    pub fn required_version(&self, previous_version: Option<u8>) -> u8 {
+        let version = if self.threshold_keys.is_some() {
+            THRESHOLD_CHANGE_DATA_VERSION
+        } else {
+            CHANGE_DATA_VERSION
+        };
+        // versions can not be downgraded
+        version.max(previous_version.unwrap_or(CHANGE_DATA_VERSION))
+    }
+}
+
+impl ThresholdKeys {
+    /// Create a new set of threshold keys
+    pub fn new(threshold: u8, public_keys: Vec<VerifyingPublicKey>) -> Self {
+        Self {
+            threshold,
+            public_keys: public_keys.into_iter().map(|k| k.into()).collect(),
+        }
+    }
+
+    /// Return true if the threshold can be reached and if all the keys are distinct,
+    /// so that a single key can not be counted more than once
+    pub fn is_valid(&self) -> bool {
+        if self.threshold == 0
+            || self.public_keys.len() > u8::MAX as usize
+            || self.threshold as usize > self.public_keys.len()
+        {
+            return false;
+        }
+        self.public_keys
+            .iter()
+            .enumerate()
+            .all(|(i, k)| !self.public_keys[..i].contains(k))
+    }
+
+    /// Return the index of a public key in this set of keys
+    pub fn key_index(&self, public_key: &VerifyingPublicKey) -> Option<u8> {
+        let public_key: PrimaryPublicKey = public_key.clone().into();
+        self.public_keys
+            .iter()
+            .position(|k| k == &public_key)
+            .map(|i| i as u8)
     }
 }
 
@@ -67,6 +127,7 @@ impl From<PrimaryPublicKey> for VerifyingPublicKey {
         match value {
             PrimaryPublicKey::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
             PrimaryPublicKey::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+            PrimaryPublicKey::ECDSASHA384CurveP384(value) => Self::ECDSASHA384CurveP384(value),
         }
     }
 }
@@ -78,6 +139,9 @@ impl From<VerifyingPublicKey> for PrimaryPublicKey {
             VerifyingPublicKey::ECDSASHA256CurveP256(value) => {
                 PrimaryPublicKey::ECDSASHA256CurveP256(value)
             }
+            VerifyingPublicKey::ECDSASHA384CurveP384(value) => {
+                PrimaryPublicKey::ECDSASHA384CurveP384(value)
+            }
         }
     }
 }
@@ -87,6 +151,7 @@ impl From<ChangeSignature> for Signature {
         match value {
             ChangeSignature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
             ChangeSignature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+            ChangeSignature::ECDSASHA384CurveP384(value) => Self::ECDSASHA384CurveP384(value),
         }
     }
 }
@@ -96,6 +161,7 @@ impl From<Signature> for ChangeSignature {
         match value {
             Signature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
             Signature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+            Signature::ECDSASHA384CurveP384(value) => Self::ECDSASHA384CurveP384(value),
         }
     }
 }
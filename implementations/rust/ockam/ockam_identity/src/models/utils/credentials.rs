@@ -59,6 +59,7 @@ impl From<CredentialSignature> for Signature {
         match value {
             CredentialSignature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
             CredentialSignature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+            CredentialSignature::ECDSASHA384CurveP384(value) => Self::ECDSASHA384CurveP384(value),
         }
     }
 }
@@ -68,6 +69,7 @@ impl From<Signature> for CredentialSignature {
         match value {
             Signature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
             Signature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+            Signature::ECDSASHA384CurveP384(value) => Self::ECDSASHA384CurveP384(value),
         }
     }
 }
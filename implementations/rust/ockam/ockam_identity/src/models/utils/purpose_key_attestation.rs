@@ -46,6 +46,9 @@ impl From<PurposeKeyAttestationSignature> for Signature {
             PurposeKeyAttestationSignature::ECDSASHA256CurveP256(value) => {
                 Self::ECDSASHA256CurveP256(value)
             }
+            PurposeKeyAttestationSignature::ECDSASHA384CurveP384(value) => {
+                Self::ECDSASHA384CurveP384(value)
+            }
         }
     }
 }
@@ -55,6 +58,7 @@ impl From<Signature> for PurposeKeyAttestationSignature {
         match value {
             Signature::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
             Signature::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+            Signature::ECDSASHA384CurveP384(value) => Self::ECDSASHA384CurveP384(value),
         }
     }
 }
@@ -66,6 +70,9 @@ impl From<CredentialVerifyingKey> for VerifyingPublicKey {
             CredentialVerifyingKey::ECDSASHA256CurveP256(value) => {
                 Self::ECDSASHA256CurveP256(value)
             }
+            CredentialVerifyingKey::ECDSASHA384CurveP384(value) => {
+                Self::ECDSASHA384CurveP384(value)
+            }
         }
     }
 }
@@ -75,6 +82,7 @@ impl From<VerifyingPublicKey> for CredentialVerifyingKey {
         match value {
             VerifyingPublicKey::EdDSACurve25519(value) => Self::EdDSACurve25519(value),
             VerifyingPublicKey::ECDSASHA256CurveP256(value) => Self::ECDSASHA256CurveP256(value),
+            VerifyingPublicKey::ECDSASHA384CurveP384(value) => Self::ECDSASHA384CurveP384(value),
         }
     }
 }
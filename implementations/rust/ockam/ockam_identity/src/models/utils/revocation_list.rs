@@ -0,0 +1,58 @@
+use crate::models::{
+    Identifier, RevocationList, RevocationListAndPurposeKey, RevocationListData,
+    TimestampInSeconds, VersionedData, REVOCATION_LIST_DATA_TYPE,
+};
+use crate::IdentityError;
+
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+
+impl RevocationList {
+    /// Create [`VersionedData`] with corresponding version and data_type
+    pub fn create_versioned_data(data: Vec<u8>) -> VersionedData {
+        VersionedData {
+            version: 1,
+            data_type: REVOCATION_LIST_DATA_TYPE,
+            data,
+        }
+    }
+
+    /// Extract [`RevocationListData`]
+    pub fn get_revocation_list_data(&self) -> Result<RevocationListData> {
+        RevocationListData::get_data(&minicbor::decode(&self.data)?)
+    }
+}
+
+impl RevocationListData {
+    /// Extract [`RevocationListData`] from [`VersionedData`]
+    pub fn get_data(versioned_data: &VersionedData) -> Result<Self> {
+        if versioned_data.version != 1 {
+            return Err(IdentityError::UnknownRevocationListVersion)?;
+        }
+
+        if versioned_data.data_type != REVOCATION_LIST_DATA_TYPE {
+            return Err(IdentityError::InvalidRevocationListDataType)?;
+        }
+
+        Ok(minicbor::decode(&versioned_data.data)?)
+    }
+
+    /// Return true if a credential issued to that subject at the given time is revoked
+    pub fn is_revoked(&self, subject: &Identifier, created_at: TimestampInSeconds) -> bool {
+        self.revoked_subjects
+            .iter()
+            .any(|s| &s.subject == subject && created_at <= s.revoked_at)
+    }
+}
+
+impl RevocationListAndPurposeKey {
+    /// Encode the revocation list as CBOR bytes
+    pub fn encode_as_cbor_bytes(&self) -> Result<Vec<u8>> {
+        ockam_core::cbor_encode_preallocate(self)
+    }
+
+    /// Decode the revocation list from bytes
+    pub fn decode_from_cbor_bytes(bytes: &[u8]) -> Result<RevocationListAndPurposeKey> {
+        Ok(minicbor::decode(bytes)?)
+    }
+}
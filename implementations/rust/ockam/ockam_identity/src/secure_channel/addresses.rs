@@ -22,6 +22,10 @@ pub(crate) struct Addresses {
     pub(crate) encryptor_api: Address,
     // Used by the encryptor itself for timer notifications (to force credentials refresh)
     pub(crate) encryptor_internal: Address,
+    // Used by the encryptor itself for timer notifications (to renew the key of an idle channel)
+    pub(crate) encryptor_rekey: Address,
+    // Used by the decryptor to notify the encryptor of a key ratchet step or of its acknowledgement
+    pub(crate) encryptor_ratchet: Address,
 }
 
 impl Addresses {
@@ -39,6 +43,10 @@ impl Addresses {
             Address::random_tagged(&format!("SecureChannel.{}.encryptor.api", role_str));
         let encryptor_internal =
             Address::random_tagged(&format!("SecureChannel.{}.encryptor.internal", role_str));
+        let encryptor_rekey =
+            Address::random_tagged(&format!("SecureChannel.{}.encryptor.rekey", role_str));
+        let encryptor_ratchet =
+            Address::random_tagged(&format!("SecureChannel.{}.encryptor.ratchet", role_str));
 
         Self {
             decryptor_internal,
@@ -47,6 +55,8 @@ impl Addresses {
             encryptor,
             encryptor_api,
             encryptor_internal,
+            encryptor_rekey,
+            encryptor_ratchet,
         }
     }
 }
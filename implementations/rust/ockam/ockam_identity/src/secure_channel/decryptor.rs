@@ -1,6 +1,8 @@
 use core::sync::atomic::Ordering;
 use ockam_core::compat::sync::Arc;
-use ockam_core::{Any, Result, Route, Routed, SecureChannelLocalInfo};
+use ockam_core::{
+    route, Any, LocalInfo, Result, Route, Routed, SecureChannelLocalInfo, SourceAddressLocalInfo,
+};
 use ockam_core::{Decodable, LocalMessage};
 use ockam_node::Context;
 
@@ -9,15 +11,16 @@ use crate::secure_channel::encryptor::{Encryptor, KEY_RENEWAL_INTERVAL};
 use crate::secure_channel::handshake::handshake_state_machine::CommonStateMachine;
 use crate::secure_channel::key_tracker::KeyTracker;
 use crate::secure_channel::nonce_tracker::NonceTracker;
+use crate::secure_channel::ratchet::derive_ratchet_key;
 use crate::secure_channel::{Addresses, Role};
 use crate::{
     DecryptionRequest, DecryptionResponse, Identities, IdentityError, Nonce,
-    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage,
-    SecureChannelPaddedMessage, NOISE_NONCE_LEN,
+    PlaintextPayloadMessage, RatchetAckMessage, RatchetMessage, RefreshCredentialsMessage,
+    SecureChannelMessage, SecureChannelPaddedMessage, NOISE_NONCE_LEN,
 };
 
 use crate::secure_channel::encryptor_worker::SecureChannelSharedState;
-use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
+use ockam_vault::{AeadSecretKeyHandle, SecretBufferHandle, VaultForSecureChannels};
 use tracing::{debug, info, trace, warn};
 use tracing_attributes::instrument;
 
@@ -102,6 +105,7 @@ impl DecryptorHandler {
         msg: PlaintextPayloadMessage<'_>,
         nonce: Nonce,
         encrypted_msg_return_route: Route,
+        encrypted_msg_local_info: &[LocalInfo],
     ) -> Result<()> {
         if !self.role.is_initiator() {
             let mut remote_route = self.shared_state.remote_route.write().unwrap();
@@ -118,9 +122,14 @@ impl DecryptorHandler {
 
         // Mark message LocalInfo with IdentitySecureChannelLocalInfo,
         // replacing any pre-existing entries
-        let local_info =
+        let mut local_info =
             SecureChannelLocalInfo::mark(vec![], self.their_identity_id.clone().into())?;
 
+        // Keep the transport address the encrypted message was received from
+        if let Ok(source) = SourceAddressLocalInfo::find_info_from_list(encrypted_msg_local_info) {
+            local_info = source.mark(local_info)?;
+        }
+
         let msg = LocalMessage::new()
             .with_onward_route(msg.onward_route)
             .with_return_route(return_route)
@@ -182,6 +191,67 @@ impl DecryptorHandler {
         Ok(())
     }
 
+    /// Handle a ratchet step of the other side: compute the secret that the other side mixes
+    /// into its next key once it receives our acknowledgement, then ask our encryptor to send
+    /// that acknowledgement. The fresh key of the other side is used for our next ratchet step
+    async fn handle_ratchet(&mut self, ctx: &Context, msg: RatchetMessage) -> Result<()> {
+        let (secret_key, obsolete_keys) = self
+            .shared_state
+            .ratchet_state
+            .write()
+            .unwrap()
+            .use_my_key(&msg.peer_public_key)?;
+
+        let vault = self.decryptor.vault().clone();
+        for obsolete_key in obsolete_keys {
+            vault
+                .delete_ephemeral_x25519_secret_key(obsolete_key)
+                .await?;
+        }
+
+        let dh = vault.x25519_ecdh(&secret_key, &msg.public_key).await?;
+        self.decryptor.set_ratchet_secret(dh).await?;
+
+        {
+            let mut ratchet_state = self.shared_state.ratchet_state.write().unwrap();
+            ratchet_state.set_their_public_key(msg.public_key.clone());
+            ratchet_state.set_ack_to_send(msg.public_key);
+        }
+
+        debug!(
+            "Received a ratchet step for {}",
+            self.addresses.decryptor_remote
+        );
+
+        self.notify_encryptor(ctx).await
+    }
+
+    /// The other side acknowledged one of our ratchet steps, our encryptor can use it
+    async fn handle_ratchet_ack(&mut self, ctx: &Context, msg: RatchetAckMessage) -> Result<()> {
+        self.shared_state
+            .ratchet_state
+            .write()
+            .unwrap()
+            .set_received_ack(msg.public_key);
+
+        debug!(
+            "Received a ratchet step acknowledgement for {}",
+            self.addresses.decryptor_remote
+        );
+
+        self.notify_encryptor(ctx).await
+    }
+
+    /// Ask our encryptor to process the changes of the ratchet state
+    async fn notify_encryptor(&self, ctx: &Context) -> Result<()> {
+        ctx.send_from_address(
+            route![self.addresses.encryptor_ratchet.clone()],
+            (),
+            self.addresses.decryptor_api.clone(),
+        )
+        .await
+    }
+
     #[instrument(skip_all)]
     pub(crate) async fn handle_decrypt(
         &mut self,
@@ -196,6 +266,7 @@ impl DecryptorHandler {
 
         let msg = msg.into_local_message();
         let encrypted_msg_return_route = msg.return_route;
+        let encrypted_msg_local_info = msg.local_info;
 
         // Decode raw payload binary
         let mut payload = msg.payload;
@@ -206,13 +277,25 @@ impl DecryptorHandler {
 
         match decrypted_msg.message {
             SecureChannelMessage::Payload(decrypted_msg) => {
-                self.handle_payload(ctx, decrypted_msg, nonce, encrypted_msg_return_route)
-                    .await?
+                self.handle_payload(
+                    ctx,
+                    decrypted_msg,
+                    nonce,
+                    encrypted_msg_return_route,
+                    &encrypted_msg_local_info,
+                )
+                .await?
             }
             SecureChannelMessage::RefreshCredentials(decrypted_msg) => {
                 self.handle_refresh_credentials(ctx, decrypted_msg).await?
             }
             SecureChannelMessage::Close => self.handle_close(ctx)?,
+            SecureChannelMessage::Ratchet(decrypted_msg) => {
+                self.handle_ratchet(ctx, decrypted_msg).await?
+            }
+            SecureChannelMessage::RatchetAck(decrypted_msg) => {
+                self.handle_ratchet_ack(ctx, decrypted_msg).await?
+            }
         };
 
         Ok(())
@@ -228,6 +311,9 @@ pub(crate) struct Decryptor {
     vault: Arc<dyn VaultForSecureChannels>,
     key_tracker: KeyTracker,
     nonce_tracker: Option<NonceTracker>,
+    // Output of a ratchet step of the other side. The other side mixes it into the key of a
+    // new interval once it receives our acknowledgement
+    ratchet_secret: Option<SecretBufferHandle>,
 }
 
 impl Decryptor {
@@ -236,6 +322,7 @@ impl Decryptor {
             vault,
             key_tracker: KeyTracker::new(key, KEY_RENEWAL_INTERVAL),
             nonce_tracker: Some(NonceTracker::new()),
+            ratchet_secret: None,
         }
     }
 
@@ -245,9 +332,14 @@ impl Decryptor {
             vault,
             key_tracker: KeyTracker::new(key, KEY_RENEWAL_INTERVAL),
             nonce_tracker: None,
+            ratchet_secret: None,
         }
     }
 
+    pub(crate) fn vault(&self) -> &Arc<dyn VaultForSecureChannels> {
+        &self.vault
+    }
+
     #[instrument(skip_all)]
     pub async fn decrypt<'a>(&mut self, payload: &'a mut [u8]) -> Result<(&'a [u8], Nonce)> {
         if payload.len() < NOISE_NONCE_LEN {
@@ -261,20 +353,22 @@ impl Decryptor {
             None
         };
 
-        let rekey_key;
-
         let rekeying = self.nonce_tracker.is_some();
-        let key = if rekeying {
+        let (key, new_key) = if rekeying {
             // get the key corresponding to the current nonce and
             // rekey if necessary
-            if let Some(key) = self.key_tracker.get_key(nonce)? {
-                key
-            } else {
-                rekey_key = Encryptor::rekey(&self.vault, &self.key_tracker.current_key).await?;
-                &rekey_key
+            match self.key_tracker.get_key(nonce)? {
+                Some(key) => (key.clone(), false),
+                None => match self.try_ratchet_key(nonce, payload).await? {
+                    Some(ratchet_key) => (ratchet_key, true),
+                    None => (
+                        Encryptor::rekey(&self.vault, &self.key_tracker.current_key).await?,
+                        true,
+                    ),
+                },
             }
         } else {
-            &self.key_tracker.current_key
+            (self.key_tracker.current_key.clone(), false)
         };
 
         // to improve protection against connection disruption attacks, we want to validate the
@@ -282,7 +376,7 @@ impl Decryptor {
         let result = self
             .vault
             .aead_decrypt(
-                key,
+                &key,
                 &mut payload[NOISE_NONCE_LEN..],
                 &nonce.to_aes_gcm_nonce(),
                 &[],
@@ -292,19 +386,76 @@ impl Decryptor {
         match result {
             Ok(result) => {
                 self.nonce_tracker = nonce_tracker;
-                if let Some(key_to_delete) = self.key_tracker.update_key(&key.clone())? {
+                if let Some(key_to_delete) = self.key_tracker.update_key(&key)? {
                     self.vault.delete_aead_secret_key(key_to_delete).await?;
                 }
 
                 Ok((result, nonce))
             }
-            Err(err) => Err(err),
+            Err(err) => {
+                if new_key {
+                    self.vault.delete_aead_secret_key(key).await?;
+                }
+                Err(err)
+            }
         }
     }
 
+    /// When a message starts a new interval and the other side performed a ratchet step, the
+    /// other side may or may not have mixed it into its new key, depending on whether it
+    /// received our acknowledgement. Return the ratcheted key if it decrypts the message
+    async fn try_ratchet_key(
+        &mut self,
+        nonce: Nonce,
+        payload: &[u8],
+    ) -> Result<Option<AeadSecretKeyHandle>> {
+        let Some(ratchet_secret) = &self.ratchet_secret else {
+            return Ok(None);
+        };
+        let ratchet_key =
+            derive_ratchet_key(&self.vault, &self.key_tracker.current_key, ratchet_secret).await?;
+
+        // decrypt a copy of the message since a failed decryption can alter the payload
+        let mut ciphertext = payload[NOISE_NONCE_LEN..].to_vec();
+        let result = self
+            .vault
+            .aead_decrypt(
+                &ratchet_key,
+                ciphertext.as_mut_slice(),
+                &nonce.to_aes_gcm_nonce(),
+                &[],
+            )
+            .await;
+
+        if result.is_ok() {
+            if let Some(ratchet_secret) = self.ratchet_secret.take() {
+                self.vault.delete_secret_buffer(ratchet_secret).await?;
+            }
+            Ok(Some(ratchet_key))
+        } else {
+            self.vault.delete_aead_secret_key(ratchet_key).await?;
+            Ok(None)
+        }
+    }
+
+    /// Use the output of a ratchet step of the other side for the next intervals, until the
+    /// other side mixes it into a new key
+    pub(crate) async fn set_ratchet_secret(
+        &mut self,
+        ratchet_secret: SecretBufferHandle,
+    ) -> Result<()> {
+        if let Some(previous) = self.ratchet_secret.replace(ratchet_secret) {
+            self.vault.delete_secret_buffer(previous).await?;
+        }
+        Ok(())
+    }
+
     /// Remove the channel keys on shutdown
     #[instrument(skip_all)]
     pub(crate) async fn shutdown(&self) -> Result<()> {
+        if let Some(ratchet_secret) = self.ratchet_secret.clone() {
+            self.vault.delete_secret_buffer(ratchet_secret).await?;
+        }
         self.vault
             .delete_aead_secret_key(self.key_tracker.current_key.clone())
             .await?;
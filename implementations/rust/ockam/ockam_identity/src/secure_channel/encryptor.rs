@@ -1,17 +1,30 @@
+use core::time::Duration;
 use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
-use ockam_vault::{AeadSecretKeyHandle, VaultForSecureChannels};
+use ockam_vault::{AeadSecretKeyHandle, SecretBufferHandle, VaultForSecureChannels};
 use tracing_attributes::instrument;
 
 use crate::secure_channel::handshake::handshake::AES_GCM_TAGSIZE;
-use crate::{Nonce, MAX_NONCE, NOISE_NONCE_LEN};
+use crate::secure_channel::ratchet::derive_ratchet_key;
+use crate::{IdentityError, Nonce, RekeyPolicy, MAX_NONCE, NOISE_NONCE_LEN};
 
 pub(crate) struct Encryptor {
     key: AeadSecretKeyHandle,
     nonce: Nonce,
     vault: Arc<dyn VaultForSecureChannels>,
     rekeying: bool,
+    rekey_policy: RekeyPolicy,
+    // Output of an acknowledged ratchet step, mixed into the key of the next interval
+    ratchet_secret: Option<SecretBufferHandle>,
+    // Time when the current key started to be used, in seconds since the UNIX epoch
+    key_created_at: u64,
+    // Number of bytes encrypted with the current key
+    encrypted_bytes: u64,
+    // The current key was used to encrypt a message, or is the initial key of the channel.
+    // The other side only derives the key of the next interval, so a key must be used before
+    // it can be renewed without a message
+    key_used: bool,
 }
 
 // To simplify the implementation, we use the same constant for the size of the message
@@ -48,13 +61,13 @@ impl Encryptor {
 
         self.nonce.increment()?;
 
+        // The key of this interval was already renewed if the channel was idle
         if self.rekeying
             && current_nonce.value() > 0
             && current_nonce.value() % KEY_RENEWAL_INTERVAL == 0
+            && self.key_used
         {
-            let new_key = Self::rekey(&self.vault, &self.key).await?;
-            let old_key = core::mem::replace(&mut self.key, new_key);
-            self.vault.delete_aead_secret_key(old_key).await?;
+            self.renew_key().await?;
         }
 
         payload[..NOISE_NONCE_LEN].copy_from_slice(&current_nonce.to_noise_nonce());
@@ -68,25 +81,134 @@ impl Encryptor {
             )
             .await?;
 
+        self.encrypted_bytes = self
+            .encrypted_bytes
+            .saturating_add((payload.len() - NOISE_NONCE_LEN) as u64);
+        self.key_used = true;
+
+        Ok(())
+    }
+
+    /// Replace the current key with the key of the next interval and delete it
+    async fn renew_key(&mut self) -> Result<()> {
+        let new_key = match self.ratchet_secret.take() {
+            Some(ratchet_secret) => {
+                let new_key = derive_ratchet_key(&self.vault, &self.key, &ratchet_secret).await;
+                self.vault.delete_secret_buffer(ratchet_secret).await?;
+                new_key?
+            }
+            None => Self::rekey(&self.vault, &self.key).await?,
+        };
+        let old_key = core::mem::replace(&mut self.key, new_key);
+        self.vault.delete_aead_secret_key(old_key).await?;
+        self.key_created_at = ockam_core::compat::time::now()?;
+        self.encrypted_bytes = 0;
+        self.key_used = false;
+        Ok(())
+    }
+
+    /// Return true if the [`RekeyPolicy`] requires the current key to be renewed
+    pub(crate) fn is_rekey_due(&self) -> Result<bool> {
+        if !self.rekeying || self.is_rekey_scheduled() {
+            return Ok(false);
+        }
+        self.rekey_policy
+            .is_rekey_due(self.key_created_at, self.encrypted_bytes)
+    }
+
+    /// Mix the output of an acknowledged ratchet step into the key of the next interval.
+    /// The other side tries both the ratcheted key and the regular key for each new interval
+    #[instrument(skip_all)]
+    pub(crate) async fn set_ratchet_secret(
+        &mut self,
+        ratchet_secret: SecretBufferHandle,
+    ) -> Result<()> {
+        if let Some(previous) = self.ratchet_secret.replace(ratchet_secret) {
+            self.vault.delete_secret_buffer(previous).await?;
+        }
+        Ok(())
+    }
+
+    /// Renew the key with the next message by skipping the remaining nonces of the current
+    /// interval
+    #[instrument(skip_all)]
+    pub(crate) fn schedule_rekey(&mut self) -> Result<()> {
+        if !self.is_rekey_scheduled() {
+            let next_interval = self.nonce.value() / KEY_RENEWAL_INTERVAL + 1;
+            let next_nonce = next_interval
+                .checked_mul(KEY_RENEWAL_INTERVAL)
+                .ok_or(IdentityError::NonceOverflow)?;
+            self.nonce = next_nonce.into();
+        }
+
         Ok(())
     }
 
+    /// Renew the key right away instead of with the next message, so that an idle channel
+    /// doesn't keep using the same key. The next message starts the new interval
+    #[instrument(skip_all)]
+    pub(crate) async fn renew_key_now(&mut self) -> Result<()> {
+        self.schedule_rekey()?;
+        self.renew_key().await
+    }
+
+    /// Return true if the key can be renewed without sending a message: the other side can only
+    /// derive the key following the last key it received a message with
+    pub(crate) fn can_renew_key_now(&self) -> bool {
+        self.key_used
+    }
+
+    /// Time left before the current key must be renewed because of the rekey interval
+    pub(crate) fn time_to_rekey(&self) -> Result<Option<Duration>> {
+        if !self.rekeying {
+            return Ok(None);
+        }
+        let Some(interval) = self.rekey_policy.interval() else {
+            return Ok(None);
+        };
+        let key_age = ockam_core::compat::time::now()?.saturating_sub(self.key_created_at);
+        Ok(Some(interval.saturating_sub(Duration::from_secs(key_age))))
+    }
+
+    /// The next message starts a new interval and is encrypted with a new key
+    fn is_rekey_scheduled(&self) -> bool {
+        self.nonce.value() > 0 && self.nonce.value() % KEY_RENEWAL_INTERVAL == 0
+    }
+
+    /// Return true if renewed keys must be derived with a key ratchet
+    pub(crate) fn is_ratchet_enabled(&self) -> bool {
+        self.rekeying && self.rekey_policy.ratchet()
+    }
+
+    pub(crate) fn vault(&self) -> &Arc<dyn VaultForSecureChannels> {
+        &self.vault
+    }
+
     pub fn new(
         key: AeadSecretKeyHandle,
         nonce: Nonce,
         vault: Arc<dyn VaultForSecureChannels>,
         rekeying: bool,
-    ) -> Self {
-        Self {
+        rekey_policy: RekeyPolicy,
+    ) -> Result<Self> {
+        Ok(Self {
             key,
             nonce,
             vault,
             rekeying,
-        }
+            rekey_policy,
+            ratchet_secret: None,
+            key_created_at: ockam_core::compat::time::now()?,
+            encrypted_bytes: 0,
+            key_used: true,
+        })
     }
 
     #[instrument(skip_all)]
     pub(crate) async fn shutdown(&self) -> Result<()> {
+        if let Some(ratchet_secret) = self.ratchet_secret.clone() {
+            self.vault.delete_secret_buffer(ratchet_secret).await?;
+        }
         if !self.vault.delete_aead_secret_key(self.key.clone()).await? {
             Err(Error::new(
                 Origin::Ockam,
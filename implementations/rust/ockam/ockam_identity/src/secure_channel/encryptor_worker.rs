@@ -1,4 +1,5 @@
 use core::sync::atomic::{AtomicBool, Ordering};
+use core::time::Duration;
 
 use tracing::{debug, error, info, trace, warn};
 use tracing_attributes::instrument;
@@ -11,18 +12,20 @@ use ockam_core::{
     async_trait, route, CowBytes, Decodable, Error, LocalMessage, NeutralMessage, Route,
 };
 use ockam_core::{Any, Result, Routed, Worker};
-use ockam_node::Context;
+use ockam_node::{Context, DelayedEvent};
 
 use crate::models::CredentialAndPurposeKey;
 use crate::secure_channel::addresses::Addresses;
 use crate::secure_channel::api::{EncryptionRequest, EncryptionResponse};
 use crate::secure_channel::encryptor::Encryptor;
 use crate::secure_channel::handshake::handshake::AES_GCM_TAGSIZE;
+use crate::secure_channel::ratchet::RatchetState;
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, Identifier, IdentityError, Nonce,
-    PlaintextPayloadMessage, RefreshCredentialsMessage, SecureChannelMessage,
-    SecureChannelPaddedMessage, NOISE_NONCE_LEN,
+    PlaintextPayloadMessage, RatchetAckMessage, RatchetMessage, RefreshCredentialsMessage,
+    SecureChannelMessage, SecureChannelPaddedMessage, NOISE_NONCE_LEN,
 };
+use ockam_vault::{SecretBufferHandle, X25519PublicKey};
 
 /// Wrap last received (during successful decryption) nonce and current route to the remote in a
 /// struct to allow shared access to it. That allows updating it either by calling
@@ -44,6 +47,10 @@ impl RemoteRoute {
     }
 }
 
+/// Delay before checking again if the key of an idle channel can be renewed, when it couldn't
+/// be renewed at the end of the rekey interval
+const REKEY_RETRY_DELAY: Duration = Duration::from_secs(10);
+
 #[derive(Debug, Clone)]
 pub(crate) struct SecureChannelSharedState {
     /// Route to the decryptor on the other side. Can be updated from the initiator side by calling
@@ -53,6 +60,17 @@ pub(crate) struct SecureChannelSharedState {
     /// Allows Decryptor to flag that we're closing the channel because we received a Close message from the other side,
     /// therefore, we don't need to send that message again to the other side
     pub(crate) should_send_close: Arc<AtomicBool>,
+    /// Ephemeral keys used for key ratchet steps. Our keys are created by the Encryptor while
+    /// the keys of the other side are received by the Decryptor
+    pub(crate) ratchet_state: Arc<RwLock<RatchetState>>,
+}
+
+/// Ratchet step which was sent to the other side and is not acknowledged yet
+struct PendingRatchetStep {
+    /// Our fresh ephemeral key for this step
+    public_key: X25519PublicKey,
+    /// Output of the ECDH of that key with the latest key of the other side
+    dh: SecretBufferHandle,
 }
 
 pub(crate) struct EncryptorWorker {
@@ -65,6 +83,10 @@ pub(crate) struct EncryptorWorker {
     credential_retriever: Option<Arc<dyn CredentialRetriever>>,
     last_presented_credential: Option<CredentialAndPurposeKey>,
     shared_state: SecureChannelSharedState,
+    /// Timer renewing the key of an idle channel when the rekey policy has an interval
+    rekey_timer: Option<DelayedEvent<()>>,
+    /// Ratchet step waiting for the acknowledgement of the other side
+    pending_ratchet_step: Option<PendingRatchetStep>,
 }
 
 impl EncryptorWorker {
@@ -90,6 +112,8 @@ impl EncryptorWorker {
             credential_retriever,
             last_presented_credential,
             shared_state,
+            rekey_timer: None,
+            pending_ratchet_step: None,
         }
     }
 
@@ -212,6 +236,7 @@ impl EncryptorWorker {
         let msg = SecureChannelMessage::Payload(msg);
         let msg = Self::add_padding(msg);
 
+        self.rekey_if_due(ctx).await?;
         let payload = self.encrypt(ctx, msg).await?;
 
         let remote_route = self.shared_state.remote_route.read().unwrap().route.clone();
@@ -288,6 +313,7 @@ impl EncryptorWorker {
         let msg = SecureChannelMessage::RefreshCredentials(msg);
         let msg = Self::add_padding(msg);
 
+        self.rekey_if_due(ctx).await?;
         let msg = self.encrypt(ctx, msg).await?;
 
         info!(
@@ -333,6 +359,190 @@ impl EncryptorWorker {
         Ok(())
     }
 
+    /// Renew the encryption key if the rekey policy requires it, then start a ratchet step
+    #[instrument(skip_all)]
+    async fn rekey_if_due(&mut self, ctx: &Context) -> Result<()> {
+        if !self.encryptor.is_rekey_due()? {
+            return Ok(());
+        }
+
+        debug!(
+            role=%self.role,
+            encryptor=%self.addresses.encryptor,
+            "renewing the encryption key");
+
+        self.encryptor.schedule_rekey()?;
+        self.start_ratchet_step(ctx).await
+    }
+
+    /// Renew the key of an idle channel at the end of the rekey interval, then schedule the
+    /// next check
+    #[instrument(skip_all)]
+    async fn handle_rekey_timer(&mut self, ctx: &Context) -> Result<()> {
+        let result = self.renew_idle_key(ctx).await;
+
+        // Schedule the next check here in case something errors
+        if let Some(time_to_rekey) = self.encryptor.time_to_rekey()? {
+            let delay = if time_to_rekey.is_zero() {
+                REKEY_RETRY_DELAY
+            } else {
+                time_to_rekey
+            };
+            if let Some(rekey_timer) = self.rekey_timer.as_mut() {
+                rekey_timer.schedule(delay)?;
+            }
+        }
+
+        result
+    }
+
+    /// Renew the key right away if the rekey policy requires it, since no message may be sent
+    /// for a while.
+    /// The key is only renewed when the other side can derive the new key: the current key
+    /// must have been used, and the ratchet message must be sent on a live route
+    async fn renew_idle_key(&mut self, ctx: &Context) -> Result<()> {
+        if !self.encryptor.is_rekey_due()? || !self.encryptor.can_renew_key_now() {
+            return Ok(());
+        }
+        if !self.is_remote_route_live(ctx)? {
+            debug!(
+                role=%self.role,
+                encryptor=%self.addresses.encryptor,
+                "the route to the other side is not available, the encryption key is renewed with the next message");
+            return Ok(());
+        }
+
+        debug!(
+            role=%self.role,
+            encryptor=%self.addresses.encryptor,
+            "renewing the encryption key of an idle channel");
+
+        self.encryptor.renew_key_now().await?;
+        self.start_ratchet_step(ctx).await
+    }
+
+    /// Return true if the messages sent to the other side can currently be routed: the route
+    /// is set and its first local address, usually a transport connection, is still running
+    fn is_remote_route_live(&self, ctx: &Context) -> Result<bool> {
+        let remote_route = self.shared_state.remote_route.read().unwrap().route.clone();
+        let Ok(next) = remote_route.next() else {
+            return Ok(false);
+        };
+        if !next.is_local() {
+            return Ok(true);
+        }
+        ctx.is_worker_registered_at(next)
+    }
+
+    /// Start a ratchet step, if the other side announced an ephemeral key: send a fresh
+    /// ephemeral key to the other side along with the latest key of the other side.
+    ///
+    /// The output of the ECDH of those keys is only mixed into our next key once the other side
+    /// acknowledges the step. If the ratchet message or its acknowledgement is lost, keys are
+    /// renewed by hashing the current key, and the step is replaced by the step started with
+    /// the next key renewal
+    async fn start_ratchet_step(&mut self, ctx: &Context) -> Result<()> {
+        if !self.encryptor.is_ratchet_enabled() {
+            return Ok(());
+        }
+        let their_public_key = self
+            .shared_state
+            .ratchet_state
+            .read()
+            .unwrap()
+            .their_public_key();
+        let Some(their_public_key) = their_public_key else {
+            return Ok(());
+        };
+
+        let vault = self.encryptor.vault().clone();
+        let secret_key = vault.generate_ephemeral_x25519_secret_key().await?;
+        let public_key = vault.get_x25519_public_key(&secret_key).await?;
+        let dh = vault.x25519_ecdh(&secret_key, &their_public_key).await?;
+
+        let obsolete_keys = self
+            .shared_state
+            .ratchet_state
+            .write()
+            .unwrap()
+            .add_my_key(secret_key, public_key.clone());
+        for obsolete_key in obsolete_keys {
+            vault
+                .delete_ephemeral_x25519_secret_key(obsolete_key)
+                .await?;
+        }
+
+        let step = PendingRatchetStep {
+            public_key: public_key.clone(),
+            dh,
+        };
+        if let Some(previous) = self.pending_ratchet_step.replace(step) {
+            vault.delete_secret_buffer(previous.dh).await?;
+        }
+
+        let msg = SecureChannelMessage::Ratchet(RatchetMessage {
+            public_key,
+            peer_public_key: their_public_key,
+        });
+        self.send_ratchet_message(ctx, msg).await
+    }
+
+    /// Process the changes made by the decryptor to the ratchet state:
+    ///  - acknowledge a ratchet step of the other side
+    ///  - mix our pending ratchet step into the next key once the other side acknowledged it
+    #[instrument(skip_all)]
+    async fn handle_ratchet(&mut self, ctx: &Context) -> Result<()> {
+        let (ack_to_send, received_ack) = {
+            let mut ratchet_state = self.shared_state.ratchet_state.write().unwrap();
+            (
+                ratchet_state.take_ack_to_send(),
+                ratchet_state.take_received_ack(),
+            )
+        };
+
+        if let Some(acknowledged_key) = received_ack {
+            // an acknowledgement of a step which was since replaced is ignored
+            let is_pending = self
+                .pending_ratchet_step
+                .as_ref()
+                .is_some_and(|step| step.public_key == acknowledged_key);
+            if is_pending {
+                if let Some(step) = self.pending_ratchet_step.take() {
+                    debug!(
+                        role=%self.role,
+                        encryptor=%self.addresses.encryptor,
+                        "the ratchet step was acknowledged, it is used for the next key");
+                    self.encryptor.set_ratchet_secret(step.dh).await?;
+                }
+            }
+        }
+
+        if let Some(public_key) = ack_to_send {
+            let msg = SecureChannelMessage::RatchetAck(RatchetAckMessage { public_key });
+            self.send_ratchet_message(ctx, msg).await?;
+        }
+
+        Ok(())
+    }
+
+    /// Send a ratchet message or a ratchet acknowledgement to the other side
+    async fn send_ratchet_message(
+        &mut self,
+        ctx: &Context,
+        msg: SecureChannelMessage<'static>,
+    ) -> Result<()> {
+        let msg = Self::add_padding(msg);
+        let msg = self.encrypt(ctx, msg).await?;
+
+        let remote_route = self.shared_state.remote_route.read().unwrap().route.clone();
+        ctx.send_from_address(
+            remote_route,
+            NeutralMessage::from(msg),
+            self.addresses.encryptor.clone(),
+        )
+        .await
+    }
+
     fn add_padding(msg: SecureChannelMessage) -> SecureChannelPaddedMessage {
         // Naїve padding of 0 to 255 zeros
         // let padding_length: u8 = ockam_core::compat::rand::random();
@@ -352,11 +562,18 @@ impl Worker for EncryptorWorker {
     type Message = Any;
     type Context = Context;
 
-    async fn initialize(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
         if let Some(credential_retriever) = &self.credential_retriever {
             credential_retriever.subscribe(&self.addresses.encryptor_internal)?;
         }
 
+        if let Some(time_to_rekey) = self.encryptor.time_to_rekey()? {
+            let mut rekey_timer =
+                DelayedEvent::create(ctx, self.addresses.encryptor_rekey.clone(), ())?;
+            rekey_timer.schedule(time_to_rekey)?;
+            self.rekey_timer = Some(rekey_timer);
+        }
+
         Ok(())
     }
 
@@ -380,6 +597,10 @@ impl Worker for EncryptorWorker {
             self.handle_encrypt_api(ctx, msg).await?;
         } else if msg_addr == self.addresses.encryptor_internal {
             self.handle_refresh_credentials(ctx).await?;
+        } else if msg_addr == self.addresses.encryptor_rekey {
+            self.handle_rekey_timer(ctx).await?;
+        } else if msg_addr == self.addresses.encryptor_ratchet {
+            self.handle_ratchet(ctx).await?;
         } else {
             return Err(IdentityError::UnknownChannelMsgDestination)?;
         }
@@ -393,10 +614,31 @@ impl Worker for EncryptorWorker {
             credential_retriever.unsubscribe(&self.addresses.encryptor_internal)?;
         }
 
+        if let Some(rekey_timer) = self.rekey_timer.as_mut() {
+            rekey_timer.cancel();
+        }
+
         let _ = context.stop_address(&self.addresses.decryptor_internal);
         if self.shared_state.should_send_close.load(Ordering::Relaxed) {
             let _ = self.send_close_channel(context).await;
         }
+
+        let ratchet_keys = self
+            .shared_state
+            .ratchet_state
+            .write()
+            .unwrap()
+            .take_my_keys();
+        for ratchet_key in ratchet_keys {
+            self.encryptor
+                .vault()
+                .delete_ephemeral_x25519_secret_key(ratchet_key)
+                .await?;
+        }
+        if let Some(step) = self.pending_ratchet_step.take() {
+            self.encryptor.vault().delete_secret_buffer(step.dh).await?;
+        }
+
         self.encryptor.shutdown().await
     }
 }
@@ -9,8 +9,9 @@ use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
 use ockam_vault::{
-    AeadSecretKeyHandle, HKDFNumberOfOutputs, SecretBufferHandle, VaultForSecureChannels,
-    X25519PublicKey, X25519SecretKeyHandle, X25519_PUBLIC_KEY_LENGTH,
+    AeadSecretKeyHandle, HKDFNumberOfOutputs, MlKemCiphertext, MlKemPublicKey,
+    MlKemSecretKeyHandle, SecretBufferHandle, VaultForSecureChannels, X25519PublicKey,
+    X25519SecretKeyHandle, X25519_PUBLIC_KEY_LENGTH,
 };
 use sha2::{Digest, Sha256};
 #[cfg(feature = "debugger")]
@@ -236,6 +237,45 @@ impl Handshake {
         Ok(payload)
     }
 
+    /// Generate the ephemeral ML-KEM key of the initiator for a hybrid key exchange
+    /// and return its public key, which is sent in the message 1 payload
+    pub(super) async fn generate_kem_key(&mut self) -> Result<MlKemPublicKey> {
+        let kem = self.vault.generate_ephemeral_ml_kem_secret_key().await?;
+        let public_key = self.vault.get_ml_kem_public_key(&kem).await?;
+        self.state.kem = Some(kem);
+        Ok(public_key)
+    }
+
+    /// Encapsulate a shared secret for the ML-KEM public key of the initiator
+    pub(super) async fn encapsulate(
+        &self,
+        public_key: &MlKemPublicKey,
+    ) -> Result<(MlKemCiphertext, SecretBufferHandle)> {
+        self.vault.ml_kem_encapsulate(public_key).await
+    }
+
+    /// Decapsulate the shared secret sent by the responder with our ephemeral ML-KEM key
+    /// which is not useful anymore after that
+    pub(super) async fn decapsulate(
+        &mut self,
+        ciphertext: &MlKemCiphertext,
+    ) -> Result<SecretBufferHandle> {
+        let kem = self.state.take_kem()?;
+        let shared_secret = self.vault.ml_kem_decapsulate(&kem, ciphertext).await;
+        _ = self.vault.delete_ephemeral_ml_kem_secret_key(kem).await?;
+        shared_secret
+    }
+
+    /// Mix a secret which is not the result of a Diffie-Hellman key exchange, like a KEM
+    /// shared secret, into the handshake keys
+    pub(super) async fn mix_key(&mut self, secret: SecretBufferHandle) -> Result<()> {
+        let mut state = self.state.clone();
+        // ck, k = HKDF(ck, secret, 2)
+        self.hkdf(&mut state, secret).await?;
+        self.state = state;
+        Ok(())
+    }
+
     /// Set the final state of the state machine by creating the encryption / decryption keys
     /// and return the other party identity
     pub(super) async fn set_final_state(&mut self, role: Role) -> Result<()> {
@@ -282,7 +322,7 @@ impl Handshake {
         let ephemeral_key = Self::generate_ephemeral_key(vault.clone()).await?;
 
         // 2. initialize the handshake
-        // We currently don't use any payload for message 1
+        // The message 1 payload is only used to propose a hybrid key exchange
         Ok(Handshake {
             vault,
             protocol_name: *PROTOCOL_NAME,
@@ -310,6 +350,7 @@ impl Handshake {
     }
 
     /// Compute two derived ck, and k keys based on existing ck and k keys + a Diffie-Hellman key
+    /// (or another shared secret)
     async fn hkdf(&self, state: &mut HandshakeState, dh: SecretBufferHandle) -> Result<()> {
         let hkdf_output = self
             .vault
@@ -407,6 +448,11 @@ impl Handshake {
             .delete_ephemeral_x25519_secret_key(self.state.take_e()?)
             .await?;
 
+        // the ML-KEM key is still there if the responder didn't use the hybrid key exchange
+        if let Some(kem) = self.state.kem.take() {
+            _ = self.vault.delete_ephemeral_ml_kem_secret_key(kem).await?;
+        }
+
         Ok(())
     }
 }
@@ -502,6 +548,7 @@ impl Handshake {
 pub(super) struct HandshakeState {
     pub(super) s: Option<X25519SecretKeyHandle>,
     e: Option<X25519SecretKeyHandle>,
+    kem: Option<MlKemSecretKeyHandle>,
     k: Option<AeadSecretKeyHandle>,
     re: Option<X25519PublicKey>,
     pub(super) rs: Option<X25519PublicKey>,
@@ -520,6 +567,7 @@ impl HandshakeState {
         HandshakeState {
             s: Some(s),
             e: Some(e),
+            kem: None,
             k: None,
             re: None,
             rs: None,
@@ -553,6 +601,16 @@ impl HandshakeState {
         })
     }
 
+    pub(super) fn take_kem(&mut self) -> Result<MlKemSecretKeyHandle> {
+        self.kem.take().ok_or_else(|| {
+            Error::new(
+                Origin::KeyExchange,
+                Kind::Invalid,
+                "key id kem should have been set",
+            )
+        })
+    }
+
     pub(super) fn take_k(&mut self) -> Result<AeadSecretKeyHandle> {
         self.k.take().ok_or_else(|| {
             Error::new(
@@ -709,6 +767,53 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_hybrid_handshake() -> Result<()> {
+        let vault = SoftwareVaultForSecureChannels::create().await?;
+
+        let initiator_static_key = vault.generate_static_x25519_secret_key().await?;
+        let mut initiator = Handshake::new(vault.clone(), initiator_static_key).await?;
+        let responder_static_key = vault.generate_static_x25519_secret_key().await?;
+        let mut responder = Handshake::new(vault.clone(), responder_static_key).await?;
+        initiator.initialize().await?;
+        responder.initialize().await?;
+
+        let public_key = initiator.generate_kem_key().await?;
+        let message1 = initiator.encode_message1(&public_key.0).await?;
+        let payload = responder.decode_message1(&message1).await?;
+        let public_key = MlKemPublicKey(payload.try_into().unwrap());
+
+        let (ciphertext, shared_secret) = responder.encapsulate(&public_key).await?;
+        let message2 = responder.encode_message2(&ciphertext.0).await?;
+        responder.mix_key(shared_secret).await?;
+        let payload = initiator.decode_message2(&message2).await?;
+        let ciphertext = MlKemCiphertext(payload.try_into().unwrap());
+        let shared_secret = initiator.decapsulate(&ciphertext).await?;
+        initiator.mix_key(shared_secret).await?;
+
+        let message3 = initiator.encode_message3(&[]).await?;
+        responder.decode_message3(&message3).await?;
+
+        initiator.set_final_state(Role::Initiator).await?;
+        responder.set_final_state(Role::Responder).await?;
+        assert_eq!(vault.number_of_ephemeral_ml_kem_secrets(), 0);
+
+        let initiator_keys = initiator.get_handshake_keys().unwrap();
+        let responder_keys = responder.get_handshake_keys().unwrap();
+        let nonce = Nonce::new(0).to_aes_gcm_nonce();
+        let mut message = b"hello".to_vec();
+        message.extend_from_slice(&[0u8; AES_GCM_TAGSIZE]);
+        vault
+            .aead_encrypt(&initiator_keys.encryption_key, &mut message, &nonce, &[])
+            .await?;
+        let decrypted = vault
+            .aead_decrypt(&responder_keys.decryption_key, &mut message, &nonce, &[])
+            .await?;
+        assert_eq!(decrypted, b"hello");
+
+        Ok(())
+    }
+
     // --------------------
     // TESTS IMPLEMENTATION
     // --------------------
@@ -6,7 +6,7 @@ use ockam_core::compat::string::ToString;
 use ockam_core::compat::sync::Arc;
 use ockam_core::compat::vec::Vec;
 use ockam_core::{async_trait, Result};
-use ockam_vault::{AeadSecretKeyHandle, X25519PublicKey};
+use ockam_vault::{AeadSecretKeyHandle, MlKemCiphertext, MlKemPublicKey, X25519PublicKey};
 
 use crate::models::{
     ChangeHistory, CredentialAndPurposeKey, PurposeKeyAttestation, PurposePublicKey,
@@ -61,6 +61,8 @@ pub(crate) struct HandshakeResults {
     pub(super) handshake_keys: HandshakeKeys,
     pub(super) their_identifier: Identifier,
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    /// First ephemeral key of the other party for key ratchet steps, if it supports them
+    pub(super) their_ratchet_public_key: Option<X25519PublicKey>,
 }
 
 /// This struct implements functions common to both initiator and the responder state machines
@@ -72,7 +74,10 @@ pub(crate) struct CommonStateMachine {
     pub(super) trust_policy: Arc<dyn TrustPolicy>,
     pub(super) authority: Option<Identifier>, // TODO: Replace with ABAC
     pub(super) presented_credential: Option<CredentialAndPurposeKey>,
+    /// Our first ephemeral key for key ratchet steps, sent with our identity
+    ratchet_public_key: Option<X25519PublicKey>,
     their_identifier: Option<Identifier>,
+    their_ratchet_public_key: Option<X25519PublicKey>,
 }
 
 impl CommonStateMachine {
@@ -83,6 +88,7 @@ impl CommonStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        ratchet_public_key: Option<X25519PublicKey>,
     ) -> Self {
         Self {
             identities,
@@ -92,7 +98,9 @@ impl CommonStateMachine {
             trust_policy,
             authority,
             presented_credential: None,
+            ratchet_public_key,
             their_identifier: None,
+            their_ratchet_public_key: None,
         }
     }
 
@@ -102,8 +110,13 @@ impl CommonStateMachine {
     ///  - the current Identity Change History
     ///  - the current Secure Channel Purpose Key Attestation
     ///  - the Identity Credentials and corresponding Credentials Purpose Key Attestations
+    ///  - the ML-KEM ciphertext for the initiator, if the responder accepts a hybrid key exchange
+    ///  - the first ephemeral key for key ratchet steps, if they are enabled
     ///
-    pub(super) async fn make_identity_payload(&mut self) -> Result<Vec<u8>> {
+    pub(super) async fn make_identity_payload(
+        &mut self,
+        ml_kem_ciphertext: Option<MlKemCiphertext>,
+    ) -> Result<Vec<u8>> {
         // prepare the payload that will be sent either in message 2 or message 3
         let change_history = self.identities.get_change_history(&self.identifier).await?;
         let credential = match &self.credential_retriever {
@@ -118,6 +131,8 @@ impl CommonStateMachine {
             change_history,
             purpose_key_attestation: self.purpose_key_attestation.clone(),
             credentials,
+            ml_kem_ciphertext,
+            ratchet_public_key: self.ratchet_public_key.clone(),
         };
         ockam_core::cbor_encode_preallocate(payload)
     }
@@ -142,6 +157,7 @@ impl CommonStateMachine {
         .await?;
 
         self.their_identifier = Some(identifier);
+        self.their_ratchet_public_key = peer.ratchet_public_key;
 
         Ok(())
     }
@@ -158,6 +174,7 @@ impl CommonStateMachine {
                 their_identifier,
                 handshake_keys,
                 presented_credential: self.presented_credential.clone(),
+                their_ratchet_public_key: self.their_ratchet_public_key.clone(),
             }),
             _ => None,
         }
@@ -305,4 +322,19 @@ pub(super) struct IdentityAndCredentials {
     /// Credentials associated to the identity along with corresponding Credentials Purpose Keys
     /// to verify those Credentials
     #[n(2)] pub(super) credentials: Vec<CredentialAndPurposeKey>,
+    /// ML-KEM ciphertext encapsulating a shared secret for the initiator. It is only sent by a
+    /// responder in message 2, when the initiator proposed a hybrid key exchange
+    #[n(3)] pub(super) ml_kem_ciphertext: Option<MlKemCiphertext>,
+    /// First ephemeral key of the sender for key ratchet steps. It is only sent when key
+    /// ratchet steps are enabled, so that they are only performed with peers supporting them
+    #[n(4)] pub(super) ratchet_public_key: Option<X25519PublicKey>,
+}
+
+/// This internal structure is used as the message 1 payload in the XX protocol when the
+/// initiator proposes a hybrid key exchange. Otherwise the message 1 payload is empty
+#[derive(Debug, Clone, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub(super) struct KeyExchangePayload {
+    /// Ephemeral ML-KEM public key of the initiator
+    #[n(0)] pub(super) ml_kem_public_key: Option<MlKemPublicKey>,
 }
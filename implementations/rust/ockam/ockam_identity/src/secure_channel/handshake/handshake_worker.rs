@@ -29,11 +29,11 @@ use crate::secure_channel::handshake::handshake_state_machine::{
 };
 use crate::secure_channel::handshake::initiator_state_machine::InitiatorStateMachine;
 use crate::secure_channel::handshake::responder_state_machine::ResponderStateMachine;
-use crate::secure_channel::{Addresses, Role};
+use crate::secure_channel::{Addresses, RatchetState, Role};
 use crate::{
     ChangeHistoryRepository, CredentialRetriever, IdentityError, PersistedSecureChannel,
-    SecureChannelPurposeKey, SecureChannelRegistryEntry, SecureChannelRepository, SecureChannels,
-    TrustPolicy,
+    RekeyPolicy, SecureChannelPurposeKey, SecureChannelRegistryEntry, SecureChannelRepository,
+    SecureChannels, TrustPolicy,
 };
 
 /// This struct implements a Worker receiving and sending messages
@@ -57,6 +57,7 @@ pub(crate) struct HandshakeWorker {
     secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
 
     shared_state: SecureChannelSharedState,
+    rekey_policy: RekeyPolicy,
 }
 
 #[ockam_core::worker]
@@ -123,6 +124,20 @@ impl Worker for HandshakeWorker {
 
         if let Some(handler) = &self.decryptor_handler {
             handler.shutdown().await?
+        } else {
+            // the encryptor was not started, delete the ratchet key announced in the handshake
+            let vault = self.secure_channels.identities.vault().secure_channel_vault;
+            let ratchet_keys = self
+                .shared_state
+                .ratchet_state
+                .write()
+                .unwrap()
+                .take_my_keys();
+            for ratchet_key in ratchet_keys {
+                vault
+                    .delete_ephemeral_x25519_secret_key(ratchet_key)
+                    .await?;
+            }
         }
 
         Ok(())
@@ -148,10 +163,27 @@ impl HandshakeWorker {
         key_exchange_only: bool,
         secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
         encryptor_remote_route: Arc<RwLock<RemoteRoute>>,
+        rekey_policy: RekeyPolicy,
+        hybrid_key_exchange: bool,
     ) -> Result<Option<Identifier>> {
         let vault = secure_channels.identities.vault().secure_channel_vault;
         let identities = secure_channels.identities();
 
+        // Announce our first ephemeral key with our identity so that the other side can
+        // perform key ratchet steps. Peers which don't send such a key don't support them
+        let ratchet_state = RatchetState::create();
+        let ratchet_public_key = if !key_exchange_only && rekey_policy.ratchet() {
+            let secret_key = vault.generate_ephemeral_x25519_secret_key().await?;
+            let public_key = vault.get_x25519_public_key(&secret_key).await?;
+            ratchet_state
+                .write()
+                .unwrap()
+                .add_my_key(secret_key, public_key.clone());
+            Some(public_key)
+        } else {
+            None
+        };
+
         let state_machine: Box<dyn StateMachine> = if role.is_initiator() {
             Box::new(
                 InitiatorStateMachine::new(
@@ -162,6 +194,8 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    hybrid_key_exchange,
+                    ratchet_public_key.clone(),
                 )
                 .await?,
             )
@@ -175,6 +209,8 @@ impl HandshakeWorker {
                     credential_retriever.clone(),
                     trust_policy,
                     authority.clone(),
+                    hybrid_key_exchange,
+                    ratchet_public_key.clone(),
                 )
                 .await?,
             )
@@ -190,6 +226,7 @@ impl HandshakeWorker {
         let shared_state = SecureChannelSharedState {
             should_send_close: Arc::new(AtomicBool::new(true)),
             remote_route: encryptor_remote_route,
+            ratchet_state,
         };
         let worker = Self {
             secure_channels,
@@ -206,6 +243,7 @@ impl HandshakeWorker {
             change_history_repository: identities.change_history_repository(),
             secure_channel_repository,
             shared_state,
+            rekey_policy,
         };
 
         WorkerBuilder::new(worker)
@@ -398,6 +436,14 @@ impl HandshakeWorker {
     ) -> Result<DecryptorHandler> {
         let their_identifier = handshake_results.their_identifier.clone();
 
+        if let Some(their_ratchet_public_key) = handshake_results.their_ratchet_public_key {
+            self.shared_state
+                .ratchet_state
+                .write()
+                .unwrap()
+                .set_their_public_key(their_ratchet_public_key);
+        }
+
         // create a decryptor to delegate the processing of all messages after the handshake
         let decryptor = DecryptorHandler::new(
             self.secure_channels.identities.clone(),
@@ -430,7 +476,8 @@ impl HandshakeWorker {
                     0.into(),
                     self.secure_channels.identities.vault().secure_channel_vault,
                     rekeying,
-                ),
+                    self.rekey_policy.clone(),
+                )?,
                 self.my_identifier.clone(),
                 self.change_history_repository.clone(),
                 credential_retriever,
@@ -461,11 +508,29 @@ impl HandshakeWorker {
                 Arc::new(AllowAll),
                 Arc::new(DenyAll),
             );
+            let rekey_mailbox = Mailbox::new(
+                self.addresses.encryptor_rekey.clone(),
+                None,
+                Arc::new(AllowAll),
+                Arc::new(DenyAll),
+            );
+
+            let ratchet_mailbox = Mailbox::new(
+                self.addresses.encryptor_ratchet.clone(),
+                None,
+                Arc::new(AllowAll),
+                Arc::new(DenyAll),
+            );
 
             WorkerBuilder::new(encryptor)
                 .with_mailboxes(Mailboxes::new(
                     main_mailbox,
-                    vec![api_mailbox, internal_mailbox],
+                    vec![
+                        api_mailbox,
+                        internal_mailbox,
+                        rekey_mailbox,
+                        ratchet_mailbox,
+                    ],
                 ))
                 .start(context)?;
         }
@@ -563,6 +628,7 @@ impl HandshakeWorker {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         secure_channel_repository: Option<Arc<dyn SecureChannelRepository>>,
         shared_state: SecureChannelSharedState,
+        rekey_policy: RekeyPolicy,
     ) -> Self {
         Self {
             secure_channels,
@@ -579,6 +645,7 @@ impl HandshakeWorker {
             credential_retriever,
             secure_channel_repository,
             shared_state,
+            rekey_policy,
         }
     }
 }
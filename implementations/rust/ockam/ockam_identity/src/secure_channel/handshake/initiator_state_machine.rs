@@ -15,7 +15,7 @@ use crate::secure_channel::handshake::error::XXError;
 use crate::secure_channel::handshake::handshake::Handshake;
 use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
-    StateMachine, Status,
+    KeyExchangePayload, StateMachine, Status,
 };
 use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
 
@@ -28,7 +28,8 @@ impl StateMachine for InitiatorStateMachine {
             // Initialize the handshake and send message 1
             (Initial, Initialize) => {
                 self.initialize_handshake().await?;
-                let message1 = self.encode_message1(&[]).await?;
+                let message1_payload = self.make_key_exchange_payload().await?;
+                let message1 = self.encode_message1(&message1_payload).await?;
 
                 // Send message 1 and wait for message 2
                 self.handshake.state.status = WaitingForMessage2;
@@ -40,13 +41,18 @@ impl StateMachine for InitiatorStateMachine {
                 let their_identity_payload: IdentityAndCredentials =
                     minicbor::decode(&message2_payload)?;
                 self.process_identity_payload(
-                    their_identity_payload,
+                    their_identity_payload.clone(),
                     self.handshake.state.rs()?.clone(),
                 )
                 .await?;
+                // mix the ML-KEM shared secret if the responder accepted the hybrid key exchange
+                if let Some(ciphertext) = &their_identity_payload.ml_kem_ciphertext {
+                    let shared_secret = self.handshake.decapsulate(ciphertext).await?;
+                    self.handshake.mix_key(shared_secret).await?;
+                }
                 let identity_payload = self
                     .common
-                    .make_identity_payload()
+                    .make_identity_payload(None)
                     .await
                     .map_err(|_e| XXError::InvalidInternalState)?;
                 let message3 = self.encode_message3(&identity_payload).await?;
@@ -74,6 +80,7 @@ impl StateMachine for InitiatorStateMachine {
 pub(super) struct InitiatorStateMachine {
     pub(super) common: CommonStateMachine,
     pub(super) handshake: Handshake,
+    hybrid_key_exchange: bool,
 }
 
 impl InitiatorStateMachine {
@@ -97,6 +104,22 @@ impl InitiatorStateMachine {
 }
 
 impl InitiatorStateMachine {
+    /// Propose a hybrid key exchange by sending an ephemeral ML-KEM public key in message 1.
+    /// The payload stays empty otherwise, as expected by responders which don't support it
+    async fn make_key_exchange_payload(&mut self) -> Result<Vec<u8>> {
+        if !self.hybrid_key_exchange {
+            return Ok(vec![]);
+        }
+
+        let payload = KeyExchangePayload {
+            ml_kem_public_key: Some(self.handshake.generate_kem_key().await?),
+        };
+        ockam_core::cbor_encode_preallocate(payload)
+    }
+}
+
+impl InitiatorStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -105,6 +128,8 @@ impl InitiatorStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        hybrid_key_exchange: bool,
+        ratchet_public_key: Option<X25519PublicKey>,
     ) -> Result<InitiatorStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -113,11 +138,13 @@ impl InitiatorStateMachine {
             credential_retriever,
             trust_policy,
             authority,
+            ratchet_public_key,
         );
 
         Ok(InitiatorStateMachine {
             common,
             handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            hybrid_key_exchange,
         })
     }
 }
@@ -4,7 +4,7 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::compat::{boxed::Box, vec::Vec};
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{Error, Result};
-use ockam_vault::{VaultForSecureChannels, X25519PublicKey};
+use ockam_vault::{MlKemCiphertext, SecretBufferHandle, VaultForSecureChannels, X25519PublicKey};
 use Action::*;
 use Event::*;
 use Role::*;
@@ -15,7 +15,7 @@ use crate::secure_channel::handshake::error::XXError;
 use crate::secure_channel::handshake::handshake::Handshake;
 use crate::secure_channel::handshake::handshake_state_machine::{
     Action, CommonStateMachine, Event, HandshakeKeys, HandshakeResults, IdentityAndCredentials,
-    StateMachine, Status,
+    KeyExchangePayload, StateMachine, Status,
 };
 use crate::{CredentialRetriever, Identities, Role, SecureChannelPurposeKey, TrustPolicy};
 
@@ -33,13 +33,24 @@ impl StateMachine for ResponderStateMachine {
             }
             // Process message 1 and send message 2
             (WaitingForMessage1, ReceivedMessage(message)) => {
-                self.decode_message1(&message).await?;
+                let message1_payload = self.decode_message1(&message).await?;
+                let (ml_kem_ciphertext, shared_secret) = match self
+                    .encapsulate(&message1_payload)
+                    .await?
+                {
+                    Some((ciphertext, shared_secret)) => (Some(ciphertext), Some(shared_secret)),
+                    None => (None, None),
+                };
                 let identity_payload = self
                     .common
-                    .make_identity_payload()
+                    .make_identity_payload(ml_kem_ciphertext)
                     .await
                     .map_err(|_e| XXError::InvalidInternalState)?;
                 let message2 = self.encode_message2(&identity_payload).await?;
+                // the initiator mixes the same shared secret after decoding message 2
+                if let Some(shared_secret) = shared_secret {
+                    self.handshake.mix_key(shared_secret).await?;
+                }
 
                 self.handshake.state.status = WaitingForMessage3;
                 Ok(SendMessage(message2))
@@ -77,6 +88,7 @@ impl StateMachine for ResponderStateMachine {
 pub struct ResponderStateMachine {
     common: CommonStateMachine,
     handshake: Handshake,
+    hybrid_key_exchange: bool,
 }
 
 impl ResponderStateMachine {
@@ -100,6 +112,27 @@ impl ResponderStateMachine {
 }
 
 impl ResponderStateMachine {
+    /// Accept the hybrid key exchange if the initiator proposed it in message 1 and if it is
+    /// enabled for this responder. In that case, return a ciphertext to send in message 2 and
+    /// the encapsulated shared secret
+    async fn encapsulate(
+        &self,
+        message1_payload: &[u8],
+    ) -> Result<Option<(MlKemCiphertext, SecretBufferHandle)>> {
+        if !self.hybrid_key_exchange || message1_payload.is_empty() {
+            return Ok(None);
+        }
+
+        let payload: KeyExchangePayload = minicbor::decode(message1_payload)?;
+        match payload.ml_kem_public_key {
+            Some(public_key) => Ok(Some(self.handshake.encapsulate(&public_key).await?)),
+            None => Ok(None),
+        }
+    }
+}
+
+impl ResponderStateMachine {
+    #[allow(clippy::too_many_arguments)]
     pub async fn new(
         vault: Arc<dyn VaultForSecureChannels>,
         identities: Arc<Identities>,
@@ -108,6 +141,8 @@ impl ResponderStateMachine {
         credential_retriever: Option<Arc<dyn CredentialRetriever>>,
         trust_policy: Arc<dyn TrustPolicy>,
         authority: Option<Identifier>,
+        hybrid_key_exchange: bool,
+        ratchet_public_key: Option<X25519PublicKey>,
     ) -> Result<ResponderStateMachine> {
         let common = CommonStateMachine::new(
             identities,
@@ -116,11 +151,13 @@ impl ResponderStateMachine {
             credential_retriever,
             trust_policy,
             authority,
+            ratchet_public_key,
         );
 
         Ok(ResponderStateMachine {
             common,
             handshake: Handshake::new(vault, purpose_key.key().clone()).await?,
+            hybrid_key_exchange,
         })
     }
 }
@@ -113,6 +113,8 @@ impl Worker for SecureChannelListenerWorker {
             self.options.key_exchange_only,
             self.secure_channel_repository.clone(),
             RemoteRoute::create(),
+            self.options.rekey_policy.clone(),
+            self.options.hybrid_key_exchange,
         )
         .await?;
 
@@ -2,6 +2,7 @@ use crate::models::{ChangeHistory, CredentialAndPurposeKey};
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{CowBytes, Route};
+use ockam_vault::X25519PublicKey;
 
 /// Secure Channel Message format.
 #[derive(Debug, Encode, Decode, CborLen, Clone)]
@@ -23,6 +24,10 @@ pub enum SecureChannelMessage<'a> {
     #[n(1)] RefreshCredentials(#[n(0)] RefreshCredentialsMessage),
     /// Close the channel.
     #[n(2)] Close,
+    /// Perform a key ratchet step.
+    #[n(3)] Ratchet(#[n(0)] RatchetMessage),
+    /// Acknowledge a key ratchet step.
+    #[n(4)] RatchetAck(#[n(0)] RatchetAckMessage),
 }
 
 /// Secure Channel Message format.
@@ -47,3 +52,22 @@ pub struct RefreshCredentialsMessage {
     /// to verify those Credentials
     #[n(1)] pub credentials: Vec<CredentialAndPurposeKey>,
 }
+
+/// Secure Channel Message format.
+#[derive(Debug, Encode, Decode, CborLen, Clone)]
+#[rustfmt::skip]
+pub struct RatchetMessage {
+    /// Fresh ephemeral key of the sender, to be used for the next ratchet step of the receiver
+    #[n(0)] pub public_key: X25519PublicKey,
+    /// Ephemeral key of the receiver that was used for the ECDH of this ratchet step
+    #[n(1)] pub peer_public_key: X25519PublicKey,
+}
+
+/// Secure Channel Message format.
+#[derive(Debug, Encode, Decode, CborLen, Clone)]
+#[rustfmt::skip]
+pub struct RatchetAckMessage {
+    /// Fresh ephemeral key of the ratchet step which is acknowledged. The sender of that
+    /// ratchet step can then use it to derive its next key
+    #[n(0)] pub public_key: X25519PublicKey,
+}
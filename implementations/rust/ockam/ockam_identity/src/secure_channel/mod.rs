@@ -12,7 +12,9 @@ mod message;
 mod nonce;
 mod nonce_tracker;
 mod options;
+mod ratchet;
 mod registry;
+mod rekey_policy;
 mod role;
 
 /// List of trust policies to setup ABAC controls
@@ -28,13 +30,17 @@ pub(crate) use listener::*;
 pub use message::*;
 pub use nonce::*;
 pub use options::*;
+pub(crate) use ratchet::*;
 pub use registry::*;
+pub use rekey_policy::*;
 pub(crate) use role::*;
 pub use trust_policy::*;
 
 #[cfg(test)]
 mod tests {
     use crate::secure_channel::{decryptor::Decryptor, encryptor::Encryptor};
+    use crate::RekeyPolicy;
+    use core::time::Duration;
     use ockam_core::compat::rand::RngCore;
     use ockam_core::Result;
     use ockam_vault::{SoftwareVaultForSecureChannels, VaultForSecureChannels};
@@ -179,7 +185,121 @@ mod tests {
         }
     }
 
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_scheduled_rekey() {
+        let (mut encryptor, mut decryptor) =
+            create_encryptor_decryptor_with_policy(RekeyPolicy::new().with_data_limit(50))
+                .await
+                .unwrap();
+
+        let mut rekeys = 0;
+        for n in 0..100 {
+            if encryptor.is_rekey_due().unwrap() {
+                encryptor.schedule_rekey().unwrap();
+                rekeys += 1;
+            }
+            let msg = vec![n];
+            let mut ciphertext = vec![0u8; 1 + 24];
+            ciphertext[8..9].copy_from_slice(msg.as_slice());
+            encryptor.encrypt(&mut ciphertext).await.unwrap();
+            assert_eq!(
+                msg,
+                decryptor
+                    .decrypt(ciphertext.as_mut_slice())
+                    .await
+                    .unwrap()
+                    .0
+            );
+        }
+        assert!(rekeys > 10);
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_key_renewed_now() {
+        let (mut encryptor, mut decryptor) = create_encryptor_decryptor_with_policy(
+            RekeyPolicy::new()
+                .with_interval(Duration::from_secs(60))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+        assert_eq!(
+            encryptor.time_to_rekey().unwrap(),
+            Some(Duration::from_secs(60))
+        );
+
+        for n in 0..10 {
+            // the key can only be renewed once before it is used by a message
+            assert!(encryptor.can_renew_key_now());
+            encryptor.renew_key_now().await.unwrap();
+            assert!(!encryptor.can_renew_key_now());
+
+            let msg = vec![n];
+            let mut ciphertext = vec![0u8; 1 + 24];
+            ciphertext[8..9].copy_from_slice(msg.as_slice());
+            encryptor.encrypt(&mut ciphertext).await.unwrap();
+            assert_eq!(
+                msg,
+                decryptor
+                    .decrypt(ciphertext.as_mut_slice())
+                    .await
+                    .unwrap()
+                    .0
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_with_ratchet_secret() {
+        let (mut encryptor, mut decryptor) = create_encryptor_decryptor().await.unwrap();
+
+        for n in 0..100 {
+            if n % 10 == 5 {
+                let mut ratchet_secret = [0u8; 32];
+                thread_rng().fill_bytes(&mut ratchet_secret);
+
+                // the decryptor always knows the output of the ratchet step, but the encryptor
+                // only uses it when the step was acknowledged
+                let vault = decryptor.vault().clone();
+                let secret = vault
+                    .import_secret_buffer(ratchet_secret.to_vec())
+                    .await
+                    .unwrap();
+                decryptor.set_ratchet_secret(secret).await.unwrap();
+
+                if n % 20 == 5 {
+                    let vault = encryptor.vault().clone();
+                    let secret = vault
+                        .import_secret_buffer(ratchet_secret.to_vec())
+                        .await
+                        .unwrap();
+                    encryptor.set_ratchet_secret(secret).await.unwrap();
+                }
+                encryptor.schedule_rekey().unwrap();
+            }
+
+            let msg = vec![n];
+            let mut ciphertext = vec![0u8; 1 + 24];
+            ciphertext[8..9].copy_from_slice(msg.as_slice());
+            encryptor.encrypt(&mut ciphertext).await.unwrap();
+            assert_eq!(
+                msg,
+                decryptor
+                    .decrypt(ciphertext.as_mut_slice())
+                    .await
+                    .unwrap()
+                    .0
+            );
+        }
+    }
+
     async fn create_encryptor_decryptor() -> Result<(Encryptor, Decryptor)> {
+        create_encryptor_decryptor_with_policy(RekeyPolicy::new()).await
+    }
+
+    async fn create_encryptor_decryptor_with_policy(
+        rekey_policy: RekeyPolicy,
+    ) -> Result<(Encryptor, Decryptor)> {
         let vault1 = SoftwareVaultForSecureChannels::create().await?;
         let vault2 = SoftwareVaultForSecureChannels::create().await?;
 
@@ -194,7 +314,7 @@ mod tests {
         let key_on_v2 = vault2.convert_secret_buffer_to_aead_key(key_on_v2).await?;
 
         Ok((
-            Encryptor::new(key_on_v1, 0.into(), vault1, true),
+            Encryptor::new(key_on_v1, 0.into(), vault1, true, rekey_policy)?,
             Decryptor::new(key_on_v2, vault2),
         ))
     }
@@ -37,7 +37,7 @@ impl From<Nonce> for u64 {
 
 impl Nonce {
     /// Constructor
-    pub fn new(value: u64) -> Self {
+    pub const fn new(value: u64) -> Self {
         Self { value }
     }
 
@@ -9,7 +9,7 @@ use crate::models::CredentialAndPurposeKey;
 use crate::secure_channel::Addresses;
 use crate::{
     CredentialRetrieverCreator, Identifier, IdentityError, MemoryCredentialRetrieverCreator,
-    TrustEveryonePolicy, TrustPolicy,
+    RekeyPolicy, TrustEveryonePolicy, TrustPolicy,
 };
 
 use core::fmt;
@@ -37,6 +37,8 @@ pub struct SecureChannelOptions {
     pub(crate) key_exchange_only: bool,
     // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
     pub(crate) is_persistent: bool,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) hybrid_key_exchange: bool,
 }
 
 impl fmt::Debug for SecureChannelOptions {
@@ -57,6 +59,8 @@ impl SecureChannelOptions {
             timeout: DEFAULT_TIMEOUT,
             key_exchange_only: false,
             is_persistent: false,
+            rekey_policy: RekeyPolicy::new(),
+            hybrid_key_exchange: false,
         }
     }
 
@@ -119,6 +123,21 @@ impl SecureChannelOptions {
         self.is_persistent = true;
         Ok(self)
     }
+
+    /// Set the [`RekeyPolicy`] deciding when the channel keys are renewed.
+    /// The policy is ignored for key exchange only channels
+    pub fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
+    /// Mix an ML-KEM-768 key encapsulation into the handshake keys, in addition to the X25519
+    /// key exchanges, so that recorded traffic can't be decrypted with a quantum computer.
+    /// The handshake falls back to a plain XX handshake if the listener doesn't support it
+    pub fn with_hybrid_key_exchange(mut self) -> Self {
+        self.hybrid_key_exchange = true;
+        self
+    }
 }
 
 impl SecureChannelOptions {
@@ -185,6 +204,8 @@ pub struct SecureChannelListenerOptions {
     pub(crate) key_exchange_only: bool,
     // Secure Channel will be persisted (currently only supported for key_exchange_only = true)
     pub(crate) is_persistent: bool,
+    pub(crate) rekey_policy: RekeyPolicy,
+    pub(crate) hybrid_key_exchange: bool,
 }
 
 impl fmt::Debug for SecureChannelListenerOptions {
@@ -207,6 +228,8 @@ impl SecureChannelListenerOptions {
             credential_retriever_creator: None,
             key_exchange_only: false,
             is_persistent: false,
+            rekey_policy: RekeyPolicy::new(),
+            hybrid_key_exchange: false,
         }
     }
 
@@ -272,6 +295,20 @@ impl SecureChannelListenerOptions {
         self.is_persistent = true;
         Ok(self)
     }
+
+    /// Set the [`RekeyPolicy`] deciding when the channel keys are renewed.
+    /// The policy is ignored for key exchange only channels
+    pub fn with_rekey_policy(mut self, rekey_policy: RekeyPolicy) -> Self {
+        self.rekey_policy = rekey_policy;
+        self
+    }
+
+    /// Accept the hybrid ML-KEM-768 key exchange from initiators which propose it.
+    /// Initiators which don't propose it still get a plain XX handshake
+    pub fn with_hybrid_key_exchange(mut self) -> Self {
+        self.hybrid_key_exchange = true;
+        self
+    }
 }
 
 impl SecureChannelListenerOptions {
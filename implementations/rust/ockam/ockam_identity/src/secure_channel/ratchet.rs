@@ -0,0 +1,213 @@
+use ockam_core::compat::sync::{Arc, RwLock};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use ockam_vault::{
+    AeadSecretKeyHandle, HKDFNumberOfOutputs, SecretBufferHandle, VaultForSecureChannels,
+    X25519PublicKey, X25519SecretKeyHandle,
+};
+use tracing_attributes::instrument;
+
+use crate::secure_channel::handshake::handshake::AES_GCM_TAGSIZE;
+use crate::{IdentityError, Nonce};
+
+/// Maximum number of our ephemeral keys that the other side can still use for a ratchet step.
+/// Older keys are deleted as soon as they are superseded.
+const MAX_RATCHET_KEYS: usize = 3;
+
+/// Nonce used to derive the chaining value of a ratchet step from the current key.
+/// [`MAX_NONCE`](crate::MAX_NONCE) is already used to derive keys during regular rekeying.
+const RATCHET_NONCE: Nonce = Nonce::new(u64::MAX - 1);
+
+/// State of the key ratchet, shared between the encryptor and the decryptor of a channel.
+///
+/// Each side of the channel announces ephemeral X25519 public keys: a first key is sent in the
+/// handshake, then a fresh key is sent with each ratchet step. A ratchet step for one direction
+/// of the channel performs an ECDH between a fresh ephemeral key of the sender and the latest
+/// ephemeral key announced by the receiver.
+///
+/// The sender only uses the output of the ECDH to derive its keys once the receiver
+/// acknowledged the ratchet step, so that a lost message doesn't desynchronize the keys.
+#[derive(Debug, Default)]
+pub(crate) struct RatchetState {
+    /// Our ephemeral keys which were announced to the other side, oldest first
+    my_keys: Vec<(X25519SecretKeyHandle, X25519PublicKey)>,
+    /// Latest ephemeral key announced by the other side
+    their_public_key: Option<X25519PublicKey>,
+    /// Key of a ratchet step of the other side, which must be acknowledged by our encryptor
+    ack_to_send: Option<X25519PublicKey>,
+    /// Key of one of our ratchet steps, which was acknowledged by the other side
+    received_ack: Option<X25519PublicKey>,
+}
+
+impl RatchetState {
+    pub(crate) fn create() -> Arc<RwLock<Self>> {
+        Arc::new(RwLock::new(Self::default()))
+    }
+
+    /// Latest ephemeral key announced by the other side. None if the other side doesn't
+    /// support the ratchet
+    pub(crate) fn their_public_key(&self) -> Option<X25519PublicKey> {
+        self.their_public_key.clone()
+    }
+
+    pub(crate) fn set_their_public_key(&mut self, public_key: X25519PublicKey) {
+        self.their_public_key = Some(public_key);
+    }
+
+    /// Ask our encryptor to acknowledge the ratchet step performed with `public_key`
+    pub(crate) fn set_ack_to_send(&mut self, public_key: X25519PublicKey) {
+        self.ack_to_send = Some(public_key);
+    }
+
+    pub(crate) fn take_ack_to_send(&mut self) -> Option<X25519PublicKey> {
+        self.ack_to_send.take()
+    }
+
+    /// Record that the other side acknowledged our ratchet step performed with `public_key`
+    pub(crate) fn set_received_ack(&mut self, public_key: X25519PublicKey) {
+        self.received_ack = Some(public_key);
+    }
+
+    pub(crate) fn take_received_ack(&mut self) -> Option<X25519PublicKey> {
+        self.received_ack.take()
+    }
+
+    /// Add a freshly announced key and return the keys that can't be used anymore
+    pub(crate) fn add_my_key(
+        &mut self,
+        secret_key: X25519SecretKeyHandle,
+        public_key: X25519PublicKey,
+    ) -> Vec<X25519SecretKeyHandle> {
+        self.my_keys.push((secret_key, public_key));
+        let excess = self.my_keys.len().saturating_sub(MAX_RATCHET_KEYS);
+        self.my_keys.drain(..excess).map(|(s, _)| s).collect()
+    }
+
+    /// Return the secret key corresponding to one of our announced keys, and remove all the
+    /// keys announced before it since the other side won't use them anymore
+    pub(crate) fn use_my_key(
+        &mut self,
+        public_key: &X25519PublicKey,
+    ) -> Result<(X25519SecretKeyHandle, Vec<X25519SecretKeyHandle>)> {
+        let position = self
+            .my_keys
+            .iter()
+            .position(|(_, p)| p == public_key)
+            .ok_or(IdentityError::UnknownRatchetKey)?;
+        let secret_key = self.my_keys[position].0.clone();
+        let obsolete = self.my_keys.drain(..position).map(|(s, _)| s).collect();
+        Ok((secret_key, obsolete))
+    }
+
+    /// Remove all our keys, in order to delete them
+    pub(crate) fn take_my_keys(&mut self) -> Vec<X25519SecretKeyHandle> {
+        self.my_keys.drain(..).map(|(s, _)| s).collect()
+    }
+}
+
+/// Derive the key for the next key renewal interval from the key which is currently used and
+/// the result of an ECDH
+#[instrument(skip_all)]
+pub(crate) async fn derive_ratchet_key(
+    vault: &Arc<dyn VaultForSecureChannels>,
+    current_key: &AeadSecretKeyHandle,
+    dh: &SecretBufferHandle,
+) -> Result<AeadSecretKeyHandle> {
+    let mut chaining_key = vec![0u8; 32 + AES_GCM_TAGSIZE];
+    vault
+        .aead_encrypt(
+            current_key,
+            chaining_key.as_mut_slice(),
+            &RATCHET_NONCE.to_aes_gcm_nonce(),
+            &[],
+        )
+        .await?;
+    let chaining_key = vault
+        .import_secret_buffer(chaining_key[0..32].to_vec())
+        .await?;
+
+    let hkdf_output = vault
+        .hkdf(&chaining_key, Some(dh), HKDFNumberOfOutputs::Two)
+        .await?;
+    vault.delete_secret_buffer(chaining_key).await?;
+
+    let [k1, k2]: [SecretBufferHandle; 2] = hkdf_output
+        .0
+         .0
+        .try_into()
+        .map_err(|_| IdentityError::HandshakeInternalError)?;
+    vault.delete_secret_buffer(k2).await?;
+
+    vault.convert_secret_buffer_to_aead_key(k1).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ockam_vault::SoftwareVaultForSecureChannels;
+
+    #[tokio::test]
+    async fn test_both_sides_derive_the_same_key() -> Result<()> {
+        let vault1: Arc<dyn VaultForSecureChannels> =
+            SoftwareVaultForSecureChannels::create().await?;
+        let vault2: Arc<dyn VaultForSecureChannels> =
+            SoftwareVaultForSecureChannels::create().await?;
+
+        let key = [7u8; 32].to_vec();
+        let key1 = vault1.import_secret_buffer(key.clone()).await?;
+        let key1 = vault1.convert_secret_buffer_to_aead_key(key1).await?;
+        let key2 = vault2.import_secret_buffer(key).await?;
+        let key2 = vault2.convert_secret_buffer_to_aead_key(key2).await?;
+
+        let secret1 = vault1.generate_ephemeral_x25519_secret_key().await?;
+        let public1 = vault1.get_x25519_public_key(&secret1).await?;
+        let secret2 = vault2.generate_ephemeral_x25519_secret_key().await?;
+        let public2 = vault2.get_x25519_public_key(&secret2).await?;
+
+        let dh1 = vault1.x25519_ecdh(&secret1, &public2).await?;
+        let new_key1 = derive_ratchet_key(&vault1, &key1, &dh1).await?;
+        let dh2 = vault2.x25519_ecdh(&secret2, &public1).await?;
+        let new_key2 = derive_ratchet_key(&vault2, &key2, &dh2).await?;
+
+        let mut message = b"hello".to_vec();
+        message.extend_from_slice(&[0u8; AES_GCM_TAGSIZE]);
+        let nonce = Nonce::new(1).to_aes_gcm_nonce();
+        vault1
+            .aead_encrypt(&new_key1, message.as_mut_slice(), &nonce, &[])
+            .await?;
+        let decrypted = vault2
+            .aead_decrypt(&new_key2, message.as_mut_slice(), &nonce, &[])
+            .await?;
+        assert_eq!(decrypted, b"hello");
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_keep_a_bounded_number_of_keys() {
+        let mut state = RatchetState::default();
+        let keys: Vec<(X25519SecretKeyHandle, X25519PublicKey)> = (0..5u8)
+            .map(|i| {
+                (
+                    X25519SecretKeyHandle(ockam_vault::HandleToSecret::new(vec![i])),
+                    X25519PublicKey([i; 32]),
+                )
+            })
+            .collect();
+
+        for (i, (s, p)) in keys.iter().enumerate() {
+            let removed = state.add_my_key(s.clone(), p.clone());
+            if i < MAX_RATCHET_KEYS {
+                assert!(removed.is_empty());
+            } else {
+                assert_eq!(removed, vec![keys[i - MAX_RATCHET_KEYS].0.clone()]);
+            }
+        }
+
+        assert!(state.use_my_key(&keys[0].1).is_err());
+        let (secret, obsolete) = state.use_my_key(&keys[3].1).unwrap();
+        assert_eq!(secret, keys[3].0);
+        assert_eq!(obsolete, vec![keys[2].0.clone()]);
+        assert_eq!(state.take_my_keys().len(), 2);
+    }
+}
@@ -0,0 +1,137 @@
+use core::time::Duration;
+use ockam_core::Result;
+
+use crate::IdentityError;
+
+/// Key creation times are measured in seconds, so shorter rekey intervals can't be enforced
+const MIN_REKEY_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Policy deciding when the keys of a secure channel are renewed.
+///
+/// Keys are always renewed after a fixed number of messages. This policy additionally
+/// renews them after some time or after some amount of encrypted data, whichever comes
+/// first, even if the channel carries very little traffic.
+///
+/// When the key ratchet is enabled, each renewal mixes the output of a fresh ephemeral
+/// X25519 key exchange into the new key, so that the compromise of a key doesn't
+/// compromise any key used after the next renewal. The ratchet is only used when both sides
+/// of the channel enable it, otherwise keys are renewed by hashing the current key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RekeyPolicy {
+    interval: Option<Duration>,
+    data_limit: Option<u64>,
+    ratchet: bool,
+}
+
+impl RekeyPolicy {
+    /// Create a policy which only renews keys based on the number of exchanged messages
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Renew the keys when they have been in use for longer than `interval`.
+    /// The interval must be at least one second
+    pub fn with_interval(mut self, interval: Duration) -> Result<Self> {
+        if interval < MIN_REKEY_INTERVAL {
+            return Err(IdentityError::InvalidRekeyInterval)?;
+        }
+        self.interval = Some(interval);
+        Ok(self)
+    }
+
+    /// Renew the keys when more than `data_limit` bytes have been encrypted with them
+    pub fn with_data_limit(mut self, data_limit: u64) -> Self {
+        self.data_limit = Some(data_limit);
+        self
+    }
+
+    /// Derive renewed keys with an ephemeral key exchange performed over the channel
+    pub fn with_ratchet(mut self) -> Self {
+        self.ratchet = true;
+        self
+    }
+
+    /// Maximum time during which a key can be used
+    pub fn interval(&self) -> Option<Duration> {
+        self.interval
+    }
+
+    /// Maximum number of bytes encrypted with a key
+    pub fn data_limit(&self) -> Option<u64> {
+        self.data_limit
+    }
+
+    /// Return true if renewed keys are derived with an ephemeral key exchange
+    pub fn ratchet(&self) -> bool {
+        self.ratchet
+    }
+
+    /// Return true if a key which was created at `key_created_at` (in seconds since the
+    /// UNIX epoch) and used to encrypt `encrypted_bytes` must be renewed
+    pub(crate) fn is_rekey_due(&self, key_created_at: u64, encrypted_bytes: u64) -> Result<bool> {
+        if let Some(data_limit) = self.data_limit {
+            if encrypted_bytes >= data_limit {
+                return Ok(true);
+            }
+        }
+
+        if let Some(interval) = self.interval {
+            let key_age = ockam_core::compat::time::now()?.saturating_sub(key_created_at);
+            if Duration::from_secs(key_age) >= interval {
+                return Ok(true);
+            }
+        }
+
+        Ok(false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_policy_never_rekeys() {
+        let policy = RekeyPolicy::new();
+        assert!(!policy.is_rekey_due(0, u64::MAX).unwrap());
+    }
+
+    #[test]
+    fn test_rekey_on_data_limit() {
+        let now = ockam_core::compat::time::now().unwrap();
+        let policy = RekeyPolicy::new().with_data_limit(1024);
+        assert!(!policy.is_rekey_due(now, 1023).unwrap());
+        assert!(policy.is_rekey_due(now, 1024).unwrap());
+    }
+
+    #[test]
+    fn test_rekey_on_interval() {
+        let now = ockam_core::compat::time::now().unwrap();
+        let policy = RekeyPolicy::new()
+            .with_interval(Duration::from_secs(60))
+            .unwrap();
+        assert!(!policy.is_rekey_due(now, 0).unwrap());
+        assert!(policy.is_rekey_due(now - 60, 0).unwrap());
+    }
+
+    #[test]
+    fn test_rekey_on_interval_with_fractional_seconds() {
+        let now = ockam_core::compat::time::now().unwrap();
+        let policy = RekeyPolicy::new()
+            .with_interval(Duration::from_millis(1500))
+            .unwrap();
+        assert!(!policy.is_rekey_due(now, 0).unwrap());
+        assert!(!policy.is_rekey_due(now - 1, 0).unwrap());
+        assert!(policy.is_rekey_due(now - 2, 0).unwrap());
+    }
+
+    #[test]
+    fn test_reject_sub_second_interval() {
+        assert!(RekeyPolicy::new()
+            .with_interval(Duration::from_millis(999))
+            .is_err());
+        assert!(RekeyPolicy::new()
+            .with_interval(Duration::from_millis(1000))
+            .is_ok());
+    }
+}
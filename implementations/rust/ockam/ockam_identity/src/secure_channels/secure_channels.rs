@@ -10,14 +10,14 @@ use crate::identities::Identities;
 use crate::models::Identifier;
 use crate::secure_channel::handshake_worker::HandshakeWorker;
 use crate::secure_channel::{
-    Addresses, DecryptorHandler, RemoteRoute, Role, SecureChannelListenerOptions,
+    Addresses, DecryptorHandler, RatchetState, RemoteRoute, Role, SecureChannelListenerOptions,
     SecureChannelListenerWorker, SecureChannelOptions, SecureChannelRegistry,
     SecureChannelSharedState,
 };
 #[cfg(feature = "storage")]
 use crate::SecureChannelsBuilder;
 use crate::{
-    IdentityError, SecureChannel, SecureChannelListener, SecureChannelRegistryEntry,
+    IdentityError, RekeyPolicy, SecureChannel, SecureChannelListener, SecureChannelRegistryEntry,
     SecureChannelRepository, Vault,
 };
 
@@ -173,6 +173,8 @@ impl SecureChannels {
             options.key_exchange_only,
             secure_channel_repository,
             encryptor_remote_route.clone(),
+            options.rekey_policy,
+            options.hybrid_key_exchange,
         )
         .await?
         else {
@@ -222,6 +224,7 @@ impl SecureChannels {
         let shared_state = SecureChannelSharedState {
             remote_route: RemoteRoute::create(),                 // Unused
             should_send_close: Arc::new(AtomicBool::new(false)), // Don't need to send anything
+            ratchet_state: RatchetState::create(),               // Unused
         };
 
         let mut addresses = Addresses::generate(role);
@@ -258,6 +261,7 @@ impl SecureChannels {
             // Key exchange only secure channel's state is unchanged after the initial creation, so no need to update it
             None,
             shared_state.clone(),
+            RekeyPolicy::new(), // Key exchange only secure channels are not rekeyed
         );
 
         WorkerBuilder::new(decryptor_worker)
@@ -1,5 +1,6 @@
+use core::ops::Range;
 use core::time::Duration;
-use std::sync::atomic::{AtomicU8, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicU8, Ordering};
 
 use ockam_core::compat::sync::Arc;
 use ockam_core::{
@@ -11,8 +12,8 @@ use ockam_identity::secure_channels::secure_channels;
 use ockam_identity::utils::AttributesBuilder;
 use ockam_identity::{
     DecryptionResponse, EncryptionRequest, EncryptionResponse, IdentityAccessControlBuilder,
-    SecureChannelListenerOptions, SecureChannelOptions, SecureChannels, TrustEveryonePolicy,
-    TrustIdentifierPolicy, Vault,
+    RekeyPolicy, SecureChannelListenerOptions, SecureChannelOptions, SecureChannels,
+    TrustEveryonePolicy, TrustIdentifierPolicy, Vault,
 };
 use ockam_node::{Context, MessageReceiveOptions, WorkerBuilder};
 use ockam_vault::{
@@ -317,6 +318,258 @@ async fn test_channel_send_multiple_messages_both_directions(ctx: &mut Context)
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn test_channel_with_key_ratchet(ctx: &mut Context) -> Result<()> {
+    let rekey_policy = RekeyPolicy::new().with_data_limit(100).with_ratchet();
+    exchange_messages_in_both_directions(
+        ctx,
+        SecureChannelOptions::new().with_rekey_policy(rekey_policy.clone()),
+        SecureChannelListenerOptions::new().with_rekey_policy(rekey_policy),
+    )
+    .await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_key_ratchet_on_one_side_only(ctx: &mut Context) -> Result<()> {
+    exchange_messages_in_both_directions(
+        ctx,
+        SecureChannelOptions::new()
+            .with_rekey_policy(RekeyPolicy::new().with_data_limit(100).with_ratchet()),
+        SecureChannelListenerOptions::new()
+            .with_rekey_policy(RekeyPolicy::new().with_data_limit(200)),
+    )
+    .await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_key_ratchet_when_the_ratchet_message_is_lost(
+    ctx: &mut Context,
+) -> Result<()> {
+    // a Ratchet message is 97 bytes long: nonce, 2 public keys and the AEAD tag
+    exchange_messages_through_lossy_hop(ctx, 90..110).await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_key_ratchet_when_the_ratchet_ack_is_lost(
+    ctx: &mut Context,
+) -> Result<()> {
+    // a RatchetAck message is 63 bytes long: nonce, 1 public key and the AEAD tag
+    exchange_messages_through_lossy_hop(ctx, 55..75).await
+}
+
+/// Exchange messages over a channel using a key ratchet, through a hop dropping the first
+/// message with a length in `dropped_lengths`.
+/// The keys must stay synchronized and the messages must still be decrypted
+async fn exchange_messages_through_lossy_hop(
+    ctx: &mut Context,
+    dropped_lengths: Range<usize>,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let dropped = Arc::new(AtomicBool::new(false));
+    WorkerBuilder::new(LossyHop {
+        dropped_lengths,
+        dropped: dropped.clone(),
+    })
+    .with_address("lossy_hop")
+    .with_incoming_access_control(AllowAll)
+    .with_outgoing_access_control(AllowAll)
+    .start(ctx)?;
+
+    let rekey_policy = RekeyPolicy::new().with_data_limit(100).with_ratchet();
+    let bob_options = SecureChannelListenerOptions::new().with_rekey_policy(rekey_policy.clone());
+    let sc_listener_flow_control_id = bob_options.spawner_flow_control_id();
+    secure_channels.create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)?;
+
+    let alice_options = SecureChannelOptions::new().with_rekey_policy(rekey_policy);
+    let sc_flow_control_id = alice_options.producer_flow_control_id();
+    let alice_channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            &alice,
+            route!["lossy_hop", "bob_listener"],
+            alice_options,
+        )
+        .await?;
+
+    let mut child_ctx = ctx.new_detached_with_mailboxes(Mailboxes::primary(
+        "child",
+        Arc::new(AllowAll),
+        Arc::new(AllowAll),
+    ))?;
+
+    // the payloads are long enough to never be dropped by the hop
+    let padding = "x".repeat(200);
+    for n in 0..50 {
+        child_ctx
+            .flow_controls()
+            .add_consumer(child_ctx.primary_address(), &sc_listener_flow_control_id);
+        let payload = format!("Hello, Bob! {n} {padding}");
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.primary_address().clone()],
+                payload.clone(),
+            )
+            .await?;
+
+        let message = child_ctx.receive::<String>().await?;
+        let return_route = message.return_route().clone();
+        assert_eq!(payload, message.into_body()?);
+
+        child_ctx
+            .flow_controls()
+            .add_consumer(child_ctx.primary_address(), &sc_flow_control_id);
+        let payload = format!("Hello, Alice! {n} {padding}");
+        child_ctx.send(return_route, payload.clone()).await?;
+
+        let message = child_ctx.receive::<String>().await?;
+        assert_eq!(payload, message.into_body()?);
+    }
+
+    assert!(dropped.load(Ordering::Relaxed));
+    Ok(())
+}
+
+/// Hop forwarding all messages, except the first one with a length in `dropped_lengths`
+struct LossyHop {
+    dropped_lengths: Range<usize>,
+    dropped: Arc<AtomicBool>,
+}
+
+#[ockam_core::async_trait]
+impl Worker for LossyHop {
+    type Message = Any;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
+        let msg = msg.into_local_message();
+        if self.dropped_lengths.contains(&msg.payload().len())
+            && !self.dropped.swap(true, Ordering::Relaxed)
+        {
+            return Ok(());
+        }
+        ctx.forward(msg.step_forward(ctx.primary_address().clone())?)
+            .await
+    }
+}
+
+#[ockam_macros::test]
+async fn test_channel_renews_keys_when_idle(ctx: &mut Context) -> Result<()> {
+    // the keys are renewed by a timer while no message is sent
+    let rekey_policy = RekeyPolicy::new()
+        .with_interval(Duration::from_secs(1))?
+        .with_ratchet();
+    exchange_messages_with_pauses(
+        ctx,
+        SecureChannelOptions::new().with_rekey_policy(rekey_policy.clone()),
+        SecureChannelListenerOptions::new().with_rekey_policy(rekey_policy),
+        4,
+        Some(Duration::from_millis(2500)),
+    )
+    .await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_hybrid_key_exchange(ctx: &mut Context) -> Result<()> {
+    exchange_messages_in_both_directions(
+        ctx,
+        SecureChannelOptions::new().with_hybrid_key_exchange(),
+        SecureChannelListenerOptions::new().with_hybrid_key_exchange(),
+    )
+    .await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_hybrid_key_exchange_on_initiator_only(ctx: &mut Context) -> Result<()> {
+    exchange_messages_in_both_directions(
+        ctx,
+        SecureChannelOptions::new().with_hybrid_key_exchange(),
+        SecureChannelListenerOptions::new(),
+    )
+    .await
+}
+
+#[ockam_macros::test]
+async fn test_channel_with_hybrid_key_exchange_on_responder_only(ctx: &mut Context) -> Result<()> {
+    exchange_messages_in_both_directions(
+        ctx,
+        SecureChannelOptions::new(),
+        SecureChannelListenerOptions::new().with_hybrid_key_exchange(),
+    )
+    .await
+}
+
+async fn exchange_messages_in_both_directions(
+    ctx: &mut Context,
+    alice_options: SecureChannelOptions,
+    bob_options: SecureChannelListenerOptions,
+) -> Result<()> {
+    exchange_messages_with_pauses(ctx, alice_options, bob_options, 100, None).await
+}
+
+/// Exchange messages in both directions, pausing before each exchange if `pause` is set
+async fn exchange_messages_with_pauses(
+    ctx: &mut Context,
+    alice_options: SecureChannelOptions,
+    bob_options: SecureChannelListenerOptions,
+    rounds: usize,
+    pause: Option<Duration>,
+) -> Result<()> {
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+
+    let alice = identities_creation.create_identity().await?;
+    let bob = identities_creation.create_identity().await?;
+
+    let sc_listener_flow_control_id = bob_options.spawner_flow_control_id();
+    secure_channels.create_secure_channel_listener(ctx, &bob, "bob_listener", bob_options)?;
+
+    let sc_flow_control_id = alice_options.producer_flow_control_id();
+    let alice_channel = secure_channels
+        .create_secure_channel(ctx, &alice, route!["bob_listener"], alice_options)
+        .await?;
+
+    let mut child_ctx = ctx.new_detached_with_mailboxes(Mailboxes::primary(
+        "child",
+        Arc::new(AllowAll),
+        Arc::new(AllowAll),
+    ))?;
+
+    for n in 0..rounds {
+        if let Some(pause) = pause {
+            ctx.sleep(pause).await;
+        }
+        child_ctx
+            .flow_controls()
+            .add_consumer(child_ctx.primary_address(), &sc_listener_flow_control_id);
+        let payload = format!("Hello, Bob! {}", n);
+        child_ctx
+            .send(
+                route![alice_channel.clone(), child_ctx.primary_address().clone()],
+                payload.clone(),
+            )
+            .await?;
+
+        let message = child_ctx.receive::<String>().await?;
+        let return_route = message.return_route().clone();
+        assert_eq!(payload, message.into_body()?);
+
+        child_ctx
+            .flow_controls()
+            .add_consumer(child_ctx.primary_address(), &sc_flow_control_id);
+        let payload = format!("Hello, Alice! {}", n);
+        child_ctx.send(return_route, payload.clone()).await?;
+
+        let message = child_ctx.receive::<String>().await?;
+        assert_eq!(payload, message.into_body()?);
+    }
+    Ok(())
+}
+
 #[ockam_macros::test]
 async fn test_channel_registry(ctx: &mut Context) -> Result<()> {
     let secure_channels = secure_channels().await?;
@@ -109,11 +109,11 @@ impl VaultForVerifyingSignatures for CrazyVerifyingVault {
     ) -> Result<bool> {
         match &signature {
             Signature::EdDSACurve25519(value) => {
-                if value.0.as_ref().iter().all(|&x| x == 0) {
+                if value.0.iter().all(|&x| x == 0) {
                     return Ok(true);
                 }
             }
-            Signature::ECDSASHA256CurveP256(_) => {
+            Signature::ECDSASHA256CurveP256(_) | Signature::ECDSASHA384CurveP384(_) => {
                 panic!()
             }
         }
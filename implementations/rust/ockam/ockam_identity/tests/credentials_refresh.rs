@@ -1,9 +1,9 @@
 use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::time::Duration;
 
-use ockam_core::api::Response;
+use ockam_core::api::{RequestHeader, Response};
 use ockam_core::compat::sync::Arc;
-use ockam_core::{async_trait, Any, Routed, SecureChannelLocalInfo, TryClone, Worker};
+use ockam_core::{async_trait, Any, Decodable, Routed, SecureChannelLocalInfo, TryClone, Worker};
 use ockam_core::{route, Result};
 use ockam_identity::models::CredentialSchemaIdentifier;
 use ockam_identity::secure_channels::secure_channels;
@@ -19,6 +19,7 @@ use ockam_transport_tcp::TcpTransport;
 struct CredentialIssuer {
     delay: Duration,
     call_counter: Arc<AtomicU64>,
+    revocation_list_call_counter: Arc<AtomicU64>,
     pause: Arc<AtomicBool>,
     credentials: Arc<Credentials>,
     authority: Identifier,
@@ -40,6 +41,18 @@ impl Worker for CredentialIssuer {
         }
 
         let msg = msg.into_local_message();
+
+        // Only answer credential requests, not revocation list requests
+        let body: Vec<u8> = Decodable::decode(msg.payload())?;
+        let request: RequestHeader = minicbor::decode(&body)?;
+        if request.path() != "/" {
+            self.revocation_list_call_counter
+                .fetch_add(1, Ordering::Relaxed);
+            let response = Response::unknown_path(&request).to_vec()?;
+            ctx.send(msg.return_route, response).await?;
+            return Ok(());
+        }
+
         let subject = SecureChannelLocalInfo::find_info(&msg)?
             .their_identifier()
             .into();
@@ -188,9 +201,43 @@ async fn init_fail(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+#[ockam_macros::test]
+async fn revocation_list_not_published(ctx: &mut Context) -> Result<()> {
+    let timing_options = RemoteCredentialRetrieverTimingOptions {
+        revocation_list_refresh_interval: Duration::from_secs(1),
+        ..Default::default()
+    };
+    let res = init(
+        ctx,
+        Duration::from_secs(0),
+        Duration::from_secs(60),
+        timing_options,
+    )
+    .await?;
+
+    let _channel = res
+        .client_secure_channels
+        .create_secure_channel(
+            ctx,
+            &res.client,
+            route!["server_api"],
+            SecureChannelOptions::new()
+                .with_credential_retriever_creator(res.retriever)?
+                .with_authority(res.authority.clone()),
+        )
+        .await?;
+
+    // The authority answers with an unknown path, so the revocation list is only requested once
+    ctx.sleep(Duration::from_secs(4)).await;
+    assert_eq!(res.revocation_list_call_counter.load(Ordering::Relaxed), 1);
+
+    Ok(())
+}
+
 #[allow(dead_code)]
 struct InitResult {
     call_counter: Arc<AtomicU64>,
+    revocation_list_call_counter: Arc<AtomicU64>,
     pause: Arc<AtomicBool>,
 
     client: Identifier,
@@ -242,10 +289,12 @@ async fn init(
         .await?;
 
     let call_counter = Arc::new(AtomicU64::new(0));
+    let revocation_list_call_counter = Arc::new(AtomicU64::new(0));
     let pause = Arc::new(AtomicBool::new(false));
     let issuer = CredentialIssuer {
         delay,
         call_counter: call_counter.clone(),
+        revocation_list_call_counter: revocation_list_call_counter.clone(),
         pause: pause.clone(),
         credentials: authority_identities.credentials(),
         authority: authority.clone(),
@@ -285,6 +334,7 @@ async fn init(
 
     Ok(InitResult {
         call_counter,
+        revocation_list_call_counter,
         pause,
         client,
         server,
@@ -1,9 +1,9 @@
 use core::str::FromStr;
 
 use ockam_core::Result;
-use ockam_identity::identities;
 use ockam_identity::models::Identifier;
-use ockam_vault::SigningKeyType;
+use ockam_identity::{identities, Identity, Vault};
+use ockam_vault::{SigningKeyType, VerifyingPublicKey};
 
 #[tokio::test]
 async fn create_and_retrieve() -> Result<()> {
@@ -66,3 +66,78 @@ async fn create_p256() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn create_p384() -> Result<()> {
+    let identities = identities().await?;
+    let identities_creation = identities.identities_creation();
+    let identities_verification = identities.identities_verification();
+    let identities_keys = identities.identities_keys();
+
+    let identifier = identities_creation
+        .identity_builder()
+        .with_random_key(SigningKeyType::ECDSASHA384CurveP384)
+        .build()
+        .await?;
+    let actual = identities_verification.get_identity(&identifier).await?;
+
+    assert!(matches!(
+        actual.get_latest_public_key()?,
+        VerifyingPublicKey::ECDSASHA384CurveP384(_)
+    ));
+
+    let root_key = identities_keys.get_secret_key(&actual).await;
+    assert!(root_key.is_ok(), "there is a key for the created identity");
+
+    // the change history can be verified by another node
+    let imported = Identity::import(
+        Some(&identifier),
+        &actual.export()?,
+        Vault::create_verifying_vault(),
+    )
+    .await?;
+    assert_eq!(imported.identifier(), &identifier);
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn rotate_between_key_types() -> Result<()> {
+    let identities = identities().await?;
+    let identities_creation = identities.identities_creation();
+    let identities_verification = identities.identities_verification();
+
+    let identifier = identities_creation
+        .identity_builder()
+        .with_random_key(SigningKeyType::ECDSASHA384CurveP384)
+        .build()
+        .await?;
+
+    for key_type in [
+        SigningKeyType::EdDSACurve25519,
+        SigningKeyType::ECDSASHA256CurveP256,
+        SigningKeyType::ECDSASHA384CurveP384,
+    ] {
+        let options = identities_creation
+            .identity_builder()
+            .with_random_key(key_type)
+            .build_options()
+            .await?;
+        identities_creation
+            .rotate_identity_with_options(&identifier, options)
+            .await?;
+    }
+
+    let identity = identities_verification.get_identity(&identifier).await?;
+    assert_eq!(identity.changes().len(), 4);
+
+    let imported = Identity::import(
+        Some(&identifier),
+        &identity.export()?,
+        Vault::create_verifying_vault(),
+    )
+    .await?;
+    assert_eq!(imported.identifier(), &identifier);
+
+    Ok(())
+}
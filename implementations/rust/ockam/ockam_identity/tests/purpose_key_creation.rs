@@ -1,5 +1,8 @@
+use core::time::Duration;
 use ockam_core::Result;
 use ockam_identity::identities;
+use ockam_identity::models::CredentialSchemaIdentifier;
+use ockam_identity::utils::AttributesBuilder;
 use ockam_vault::{SigningKeyType, VerifyingPublicKey};
 
 #[tokio::test]
@@ -179,3 +182,62 @@ async fn create_with_rotated_identity() -> Result<()> {
 
     Ok(())
 }
+
+#[tokio::test]
+async fn create_with_p384_identity() -> Result<()> {
+    let identities = identities().await?;
+    let identities_creation = identities.identities_creation();
+    let purpose_keys = identities.purpose_keys();
+    let credentials = identities.credentials();
+
+    let authority = identities_creation
+        .identity_builder()
+        .with_random_key(SigningKeyType::ECDSASHA384CurveP384)
+        .build()
+        .await?;
+    let subject = identities_creation.create_identity().await?;
+
+    let purpose_key = purpose_keys
+        .purpose_keys_creation()
+        .credential_purpose_key_builder(&authority)
+        .with_random_key(SigningKeyType::ECDSASHA384CurveP384)
+        .build()
+        .await?;
+
+    // the attestation is signed with the P-384 identity key
+    let data = purpose_keys
+        .purpose_keys_verification()
+        .verify_purpose_key_attestation(Some(&authority), purpose_key.attestation())
+        .await?;
+    assert_eq!(data.subject, authority);
+
+    let public_key = identities
+        .vault()
+        .credential_vault
+        .get_verifying_public_key(purpose_key.key())
+        .await?;
+    assert!(matches!(
+        public_key,
+        VerifyingPublicKey::ECDSASHA384CurveP384(_)
+    ));
+
+    // the credential is signed with the P-384 purpose key
+    let credential = credentials
+        .credentials_creation()
+        .issue_credential(
+            &authority,
+            &subject,
+            AttributesBuilder::with_schema(CredentialSchemaIdentifier(0))
+                .with_attribute("key", "value")
+                .build(),
+            Duration::from_secs(60),
+        )
+        .await?;
+
+    credentials
+        .credentials_verification()
+        .verify_credential(Some(&subject), &[authority], &credential)
+        .await?;
+
+    Ok(())
+}
@@ -1,5 +1,5 @@
 use super::{Buffer, Checked, Code, Codec, Protocol};
-use crate::proto::{DnsAddr, Node, Project, Secure, Service, Space, Tcp, Udp, Worker};
+use crate::proto::{DnsAddr, Node, Project, Quic, Secure, Service, Space, Tcp, Udp, Worker};
 use crate::{Error, ProtoValue};
 use core::fmt;
 use unsigned_varint::decode;
@@ -9,9 +9,13 @@ pub struct StdCodec;
 impl Codec for StdCodec {
     fn split_str<'a>(
         &self,
-        _prefix: &str,
+        prefix: &str,
         input: &'a str,
     ) -> Result<(Checked<&'a str>, &'a str), Error> {
+        // The quic protocol has no value, the input starts with the next protocol
+        if prefix == Quic::PREFIX {
+            return Ok((Checked(""), input));
+        }
         if let Some(p) = input.find('/') {
             let (x, y) = input.split_at(p);
             Ok((Checked(x), y))
@@ -56,6 +60,7 @@ impl Codec for StdCodec {
                 let (x, y) = input.split_at(2);
                 Ok((Checked(x), y))
             }
+            Quic::CODE => Ok((Checked(&[]), input)),
             c @ Worker::CODE
             | c @ DnsAddr::CODE
             | c @ Service::CODE
@@ -83,6 +88,7 @@ impl Codec for StdCodec {
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(input).is_ok(),
             Tcp::CODE => Tcp::read_bytes(input).is_ok(),
             Udp::CODE => Udp::read_bytes(input).is_ok(),
+            Quic::CODE => Quic::read_bytes(input).is_ok(),
             DnsAddr::CODE => DnsAddr::read_bytes(input).is_ok(),
             Service::CODE => Service::read_bytes(input).is_ok(),
             Node::CODE => Node::read_bytes(input).is_ok(),
@@ -102,6 +108,7 @@ impl Codec for StdCodec {
             crate::proto::Ip6::CODE => crate::proto::Ip6::read_bytes(val.data())?.write_bytes(buf),
             Tcp::CODE => Tcp::read_bytes(val.data())?.write_bytes(buf),
             Udp::CODE => Udp::read_bytes(val.data())?.write_bytes(buf),
+            Quic::CODE => Quic::read_bytes(val.data())?.write_bytes(buf),
             DnsAddr::CODE => DnsAddr::read_bytes(val.data())?.write_bytes(buf),
             Service::CODE => Service::read_bytes(val.data())?.write_bytes(buf),
             Node::CODE => Node::read_bytes(val.data())?.write_bytes(buf),
@@ -142,6 +149,10 @@ impl Codec for StdCodec {
                 Udp::read_str(value)?.write_bytes(buf);
                 Ok(())
             }
+            Quic::PREFIX => {
+                Quic::read_str(value)?.write_bytes(buf);
+                Ok(())
+            }
             DnsAddr::PREFIX => {
                 DnsAddr::read_str(value)?.write_bytes(buf);
                 Ok(())
@@ -199,6 +210,10 @@ impl Codec for StdCodec {
                 Udp::read_bytes(value)?.write_str(f)?;
                 Ok(())
             }
+            Quic::CODE => {
+                Quic::read_bytes(value)?.write_str(f)?;
+                Ok(())
+            }
             DnsAddr::CODE => {
                 DnsAddr::read_bytes(value)?.write_str(f)?;
                 Ok(())
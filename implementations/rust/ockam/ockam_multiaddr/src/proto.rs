@@ -198,6 +198,44 @@ impl Protocol<'_> for Udp {
     }
 }
 
+/// The QUIC protocol, running over the preceding `udp` port.
+///
+/// This protocol has no value, e.g. `/ip4/127.0.0.1/udp/4000/quic`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Quic;
+
+impl Protocol<'_> for Quic {
+    const CODE: Code = Code::new(460);
+    const PREFIX: &'static str = "quic";
+
+    fn read_str(input: Checked<&str>) -> Result<Self, Error> {
+        if input.is_empty() {
+            Ok(Quic)
+        } else {
+            Err(Error::message("the quic protocol has no value"))
+        }
+    }
+
+    fn read_bytes(input: Checked<&[u8]>) -> Result<Self, Error> {
+        if input.is_empty() {
+            Ok(Quic)
+        } else {
+            Err(Error::message("the quic protocol has no value"))
+        }
+    }
+
+    fn write_str(&self, f: &mut fmt::Formatter) -> Result<(), Error> {
+        write!(f, "/{}", Self::PREFIX)?;
+        Ok(())
+    }
+
+    fn write_bytes(&self, buf: &mut dyn Buffer) {
+        let mut b = encode::u32_buffer();
+        let uvi = encode::u32(Self::CODE.into(), &mut b);
+        buf.extend_with(uvi);
+    }
+}
+
 macro_rules! gen_str_proto {
     ($t:ident, $c:literal, $p:literal) => {
         #[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
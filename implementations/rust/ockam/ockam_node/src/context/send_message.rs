@@ -221,6 +221,23 @@ impl Context {
             .await
     }
 
+    /// Send a message to an address or via a fully-qualified route
+    /// from the given sending address, after attaching the given [`LocalInfo`] to the message.
+    pub async fn send_from_address_with_local_info<R, M>(
+        &self,
+        route: R,
+        msg: M,
+        sending_address: Address,
+        local_info: Vec<LocalInfo>,
+    ) -> Result<()>
+    where
+        R: Into<Route>,
+        M: Message + Send + 'static,
+    {
+        self.send_from_address_impl(route.into(), msg, sending_address, local_info)
+            .await
+    }
+
     async fn send_from_address_impl<M>(
         &self,
         route: Route,
@@ -230,42 +230,56 @@ impl Migrator {
     pub(crate) async fn migrate_up_to(&self, pool: &Pool<Any>, up_to: Version) -> Result<()> {
         let mut connection = pool.acquire().await.into_core()?;
 
+        if connection.backend_name() != "SQLite" {
+            // Several nodes can share the same Postgres database and start at the same time.
+            // The lock must be taken before creating the migrations table, otherwise concurrent
+            // nodes can fail while creating that table
+            connection.lock().await.into_core()?;
+            let res = self.migrate_with_connection(&mut connection, up_to).await;
+            connection.unlock().await.into_core()?;
+            return res;
+        }
+
         if !self.needs_migration(&mut connection, up_to).await? {
             debug!("No database migrations was required");
             return Ok(());
         }
 
-        let is_sqlite = connection.backend_name() == "SQLite";
-        if is_sqlite {
-            debug!("Migrating SQLite database with exclusive locking");
-            connection
-                .execute("PRAGMA locking_mode = EXCLUSIVE;")
-                .await
-                .into_core()?;
-        } else {
-            // This lock is only effective for Postgres
-            connection.lock().await.into_core()?;
-        };
+        debug!("Migrating SQLite database with exclusive locking");
+        connection
+            .execute("PRAGMA locking_mode = EXCLUSIVE;")
+            .await
+            .into_core()?;
 
         let res = self.run_migrations(&mut connection, up_to).await;
-        if is_sqlite {
-            debug!("Migration completed, unlocking database");
-            // This is not enough to unlock the database, according to the documentation,
-            // we also need an arbitrary read or write operation to release the
-            // exclusive lock.
-            // In practice, we are closing the connection to release the lock.
-            connection
-                .execute("PRAGMA locking_mode = NORMAL;")
-                .await
-                .into_core()?;
-        } else {
-            connection.unlock().await.into_core()?;
-        }
+        debug!("Migration completed, unlocking database");
+        // This is not enough to unlock the database, according to the documentation,
+        // we also need an arbitrary read or write operation to release the
+        // exclusive lock.
+        // In practice, we are closing the connection to release the lock.
+        connection
+            .execute("PRAGMA locking_mode = NORMAL;")
+            .await
+            .into_core()?;
 
         res?;
         Ok(())
     }
 
+    /// Run the migrations which have not been applied yet, with a connection holding the migration lock
+    async fn migrate_with_connection(
+        &self,
+        connection: &mut AnyConnection,
+        up_to: Version,
+    ) -> Result<()> {
+        if !self.needs_migration(connection, up_to).await? {
+            debug!("No database migrations was required");
+            return Ok(());
+        }
+        self.run_migrations(connection, up_to).await?;
+        Ok(())
+    }
+
     /// Run all migrations
     pub async fn migrate(&self, pool: &Pool<Any>) -> Result<()> {
         self.migrate_up_to(pool, i64::MAX).await
@@ -69,6 +69,17 @@ CREATE TABLE credential
 CREATE UNIQUE INDEX credential_issuer_subject_scope_index ON credential (issuer_identifier, subject_identifier, scope);
 CREATE UNIQUE INDEX credential_issuer_subject_index ON credential (issuer_identifier, subject_identifier);
 
+-- This table stores the latest revocation list received from each authority
+CREATE TABLE credential_revocation_list
+(
+    authority_identifier TEXT    NOT NULL, -- Identifier of the authority which signed the revocation list
+    created_at           INTEGER NOT NULL, -- Creation date of the revocation list
+    revocation_list      BYTEA   NOT NULL, -- Encoded revocation list data
+    node_name            TEXT    NOT NULL  -- Node name to isolate the revocation lists that each node has
+);
+
+CREATE UNIQUE INDEX credential_revocation_list_index ON credential_revocation_list (authority_identifier, node_name);
+
 -- This table stores purpose keys that have been created by a given identity
 CREATE TABLE purpose_key
 (
@@ -89,7 +100,8 @@ CREATE TABLE vault
     name       TEXT PRIMARY KEY, -- User-specified name for a vault
     path       TEXT NULL,        -- If the path is specified, then the secrets are stored in a SQLite file. Otherwise secrets are stored in the *-secrets tables below.
     is_default BOOLEAN,          -- boolean indicating if this vault is the default one (0 means true)
-    is_kms     BOOLEAN           -- boolean indicating if this vault is a KMS one (0 means true). In that case only key handles are stored in the database
+    is_kms     BOOLEAN,          -- boolean indicating if this vault is a KMS one (0 means true). In that case only key handles are stored in the database
+    is_pkcs11  BOOLEAN DEFAULT FALSE -- boolean indicating if the signing keys are stored in a PKCS#11 token. In that case only key handles are stored in the database
 );
 
 -- This table stores secrets for signing data
@@ -97,14 +109,24 @@ CREATE TABLE signing_secret
 (
     handle      BYTEA PRIMARY KEY, -- Secret handle
     secret_type TEXT  NOT NULL,    -- Secret type (EdDSACurve25519 or ECDSASHA256CurveP256)
-    secret      BYTEA NOT NULL     -- Secret binary
+    secret      BYTEA NOT NULL,    -- Secret binary, encrypted with a data key if wrapped_key is not NULL
+    wrapped_key BYTEA,             -- Data key, encrypted with a key-encryption key
+    kek_id      TEXT               -- Identifier of the key-encryption key
 );
 
 -- This table stores secrets for encrypting / decrypting data
 CREATE TABLE x25519_secret
 (
-    handle BYTEA PRIMARY KEY, -- Secret handle
-    secret BYTEA NOT NULL     -- Secret binary
+    handle      BYTEA PRIMARY KEY, -- Secret handle
+    secret      BYTEA NOT NULL,    -- Secret binary, encrypted with a data key if wrapped_key is not NULL
+    wrapped_key BYTEA,             -- Data key, encrypted with a key-encryption key
+    kek_id      TEXT               -- Identifier of the key-encryption key
+);
+
+-- This table stores the salt used to derive a key-encryption key from a passphrase
+CREATE TABLE secrets_encryption_salt
+(
+    salt BYTEA NOT NULL -- Random salt
 );
 
 -------------
@@ -118,12 +140,24 @@ CREATE TABLE authority_member
     added_at       INTEGER NOT NULL,
     is_pre_trusted BOOLEAN NOT NULL,
     attributes     BYTEA,
-    authority_id   TEXT    NOT NULL
+    authority_id   TEXT    NOT NULL,
+    expires_at     INTEGER,
+    is_suspended   BOOLEAN NOT NULL DEFAULT FALSE
 );
 
 CREATE UNIQUE INDEX authority_member_identifier_index ON authority_member (identifier);
 CREATE INDEX authority_member_is_pre_trusted_index ON authority_member (is_pre_trusted);
 
+-- This table stores the members which were removed from an authority, so that their credentials can be revoked
+CREATE TABLE authority_revoked_member
+(
+    identifier   TEXT    NOT NULL,
+    authority_id TEXT    NOT NULL,
+    revoked_at   INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_revoked_member_index ON authority_revoked_member (identifier, authority_id);
+
 -- Reference is a random string that uniquely identifies an enrollment token. However, unlike the one_time_code,
 -- it's not sensitive so can be logged and used to track a lifecycle of a specific enrollment token.
 CREATE TABLE authority_enrollment_token
@@ -140,6 +174,86 @@ CREATE TABLE authority_enrollment_token
 CREATE UNIQUE INDEX authority_enrollment_token_one_time_code_index ON authority_enrollment_token (one_time_code);
 CREATE INDEX authority_enrollment_token_expires_at_index ON authority_enrollment_token (expires_at);
 
+-- This table stores an append-only log of the operations changing the members of an authority.
+-- Each entry contains the hash of the previous entry and is signed by the authority
+CREATE TABLE authority_audit_log
+(
+    authority_id  TEXT    NOT NULL,
+    entry_index   INTEGER NOT NULL,
+    recorded_at   INTEGER NOT NULL,
+    actor         TEXT    NOT NULL,
+    action        TEXT    NOT NULL,
+    target        TEXT,
+    attributes    BYTEA,
+    previous_hash BYTEA   NOT NULL,
+    hash          BYTEA   NOT NULL,
+    signature     BYTEA   NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_audit_log_index ON authority_audit_log (authority_id, entry_index);
+CREATE RULE authority_audit_log_no_update AS ON UPDATE TO authority_audit_log DO INSTEAD NOTHING;
+CREATE RULE authority_audit_log_no_delete AS ON DELETE TO authority_audit_log DO INSTEAD NOTHING;
+
+-- This table stores the users provisioned on an authority with SCIM.
+-- The user name of a SCIM user is the identifier of a project member
+CREATE TABLE authority_scim_user
+(
+    authority_id TEXT    NOT NULL,
+    identifier   TEXT    NOT NULL,
+    resource     TEXT    NOT NULL,
+    created_at   INTEGER NOT NULL,
+    updated_at   INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_scim_user_index ON authority_scim_user (authority_id, identifier);
+
+-- This table stores the groups provisioned on an authority with SCIM
+CREATE TABLE authority_scim_group
+(
+    authority_id TEXT    NOT NULL,
+    group_id     TEXT    NOT NULL,
+    display_name TEXT    NOT NULL,
+    external_id  TEXT,
+    created_at   INTEGER NOT NULL,
+    updated_at   INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_scim_group_index ON authority_scim_group (authority_id, group_id);
+
+-- This table stores the members of the SCIM groups
+CREATE TABLE authority_scim_group_member
+(
+    authority_id TEXT NOT NULL,
+    group_id     TEXT NOT NULL,
+    identifier   TEXT NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_scim_group_member_index ON authority_scim_group_member (authority_id, group_id, identifier);
+
+-- This table stores the lock serializing the SCIM modifications of an authority.
+-- Several authority nodes can share the same database, so the lock can't be held in memory
+CREATE TABLE authority_scim_lock
+(
+    authority_id TEXT    NOT NULL,
+    holder       TEXT    NOT NULL,
+    expires_at   INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_scim_lock_index ON authority_scim_lock (authority_id);
+
+-- This table stores the identifiers of the JWTs already used to enroll members with the
+-- OIDC authenticator, so that a token can't be replayed before it expires
+CREATE TABLE authority_used_jwt
+(
+    authority_id TEXT    NOT NULL,
+    issuer       TEXT    NOT NULL,
+    jwt_id       TEXT    NOT NULL,
+    expires_at   INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_used_jwt_index ON authority_used_jwt (authority_id, issuer, jwt_id);
+CREATE INDEX authority_used_jwt_expires_at_index ON authority_used_jwt (expires_at);
+
 ------------
 -- SERVICES
 ------------
@@ -176,6 +290,18 @@ CREATE TABLE resource
 );
 CREATE UNIQUE INDEX resource_index ON resource (node_name, resource_name, resource_type);
 
+-- This table stores the decisions taken by the policy access controls of a node
+CREATE TABLE policy_decision
+(
+    decided_at    INTEGER NOT NULL, -- Time of the decision
+    resource_name TEXT    NOT NULL, -- Name of the accessed resource
+    action        TEXT    NOT NULL, -- Action performed on the resource
+    allowed       BOOLEAN NOT NULL, -- True if the access was allowed
+    decision      BYTEA   NOT NULL, -- Encoded decision: subject, expression, attributes and decisive expression
+    node_name     TEXT    NOT NULL  -- Node name to isolate the decisions of each node
+);
+CREATE INDEX policy_decision_index ON policy_decision (node_name, decided_at);
+
 -- This table stores the current state of a TCP outlet
 CREATE TABLE tcp_outlet_status
 (
@@ -234,7 +360,9 @@ CREATE UNIQUE INDEX secure_channel_decryptor_api_address_index ON secure_channel
 -- This table stores aead secrets
 CREATE TABLE aead_secret
 (
-    handle BYTEA PRIMARY KEY, -- Secret handle
-    type   TEXT  NOT NULL,    -- Secret type
-    secret BYTEA NOT NULL     -- Secret binary
+    handle      BYTEA PRIMARY KEY, -- Secret handle
+    type        TEXT  NOT NULL,    -- Secret type
+    secret      BYTEA NOT NULL,    -- Secret binary, encrypted with a data key if wrapped_key is not NULL
+    wrapped_key BYTEA,             -- Data key, encrypted with a key-encryption key
+    kek_id      TEXT               -- Identifier of the key-encryption key
 );
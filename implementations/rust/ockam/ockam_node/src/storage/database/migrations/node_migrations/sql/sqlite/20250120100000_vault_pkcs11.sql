@@ -0,0 +1,3 @@
+-- Add a column to indicate if the signing keys of a vault are stored in a PKCS#11 token
+ALTER TABLE vault
+        ADD is_pkcs11 INTEGER DEFAULT 0; -- boolean indicating if the signing keys are stored in a PKCS#11 token (1 means true)
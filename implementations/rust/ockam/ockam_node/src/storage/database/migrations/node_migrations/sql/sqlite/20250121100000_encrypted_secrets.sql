@@ -0,0 +1,17 @@
+-- Secrets can be encrypted at rest with a data key, itself encrypted with a key-encryption key (KEK).
+-- When wrapped_key is NULL the secret is stored in plain text.
+-- Existing secrets are encrypted when the vault is opened with a configured key-encryption key.
+ALTER TABLE signing_secret ADD wrapped_key BLOB;
+ALTER TABLE signing_secret ADD kek_id TEXT;
+
+ALTER TABLE x25519_secret ADD wrapped_key BLOB;
+ALTER TABLE x25519_secret ADD kek_id TEXT;
+
+ALTER TABLE aead_secret ADD wrapped_key BLOB;
+ALTER TABLE aead_secret ADD kek_id TEXT;
+
+-- This table stores the salt used to derive a key-encryption key from a passphrase
+CREATE TABLE secrets_encryption_salt
+(
+    salt BLOB NOT NULL -- Random salt
+);
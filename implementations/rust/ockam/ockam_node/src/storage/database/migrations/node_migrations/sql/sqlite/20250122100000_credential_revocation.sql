@@ -0,0 +1,20 @@
+-- This table stores the latest revocation list received from each authority
+CREATE TABLE credential_revocation_list
+(
+    authority_identifier TEXT    NOT NULL, -- Identifier of the authority which signed the revocation list
+    created_at           INTEGER NOT NULL, -- Creation date of the revocation list
+    revocation_list      BLOB    NOT NULL, -- Encoded revocation list data
+    node_name            TEXT    NOT NULL  -- Node name to isolate the revocation lists that each node has
+);
+
+CREATE UNIQUE INDEX credential_revocation_list_index ON credential_revocation_list (authority_identifier, node_name);
+
+-- This table stores the members which were removed from an authority, so that their credentials can be revoked
+CREATE TABLE authority_revoked_member
+(
+    identifier   TEXT    NOT NULL,
+    authority_id TEXT    NOT NULL,
+    revoked_at   INTEGER NOT NULL
+);
+
+CREATE UNIQUE INDEX authority_revoked_member_index ON authority_revoked_member (identifier, authority_id);
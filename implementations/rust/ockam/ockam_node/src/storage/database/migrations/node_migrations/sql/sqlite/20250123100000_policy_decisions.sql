@@ -0,0 +1,12 @@
+-- This table stores the decisions taken by the policy access controls of a node
+CREATE TABLE policy_decision
+(
+    decided_at    INTEGER NOT NULL, -- Time of the decision
+    resource_name TEXT    NOT NULL, -- Name of the accessed resource
+    action        TEXT    NOT NULL, -- Action performed on the resource
+    allowed       BOOLEAN NOT NULL, -- True if the access was allowed
+    decision      BLOB    NOT NULL, -- Encoded decision: subject, expression, attributes and decisive expression
+    node_name     TEXT    NOT NULL  -- Node name to isolate the decisions of each node
+);
+
+CREATE INDEX policy_decision_index ON policy_decision (node_name, decided_at);
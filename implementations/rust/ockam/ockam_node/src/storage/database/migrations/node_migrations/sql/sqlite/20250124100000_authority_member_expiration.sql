@@ -0,0 +1,5 @@
+-- Add columns to make project memberships time-bounded and to suspend members
+ALTER TABLE authority_member
+    ADD COLUMN expires_at INTEGER;
+ALTER TABLE authority_member
+    ADD COLUMN is_suspended INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,29 @@
+-- This table stores an append-only log of the operations changing the members of an authority.
+-- Each entry contains the hash of the previous entry and is signed by the authority
+CREATE TABLE authority_audit_log
+(
+    authority_id  TEXT    NOT NULL, -- Identifier of the authority
+    entry_index   INTEGER NOT NULL, -- Position of the entry in the log, starting at 0
+    recorded_at   INTEGER NOT NULL, -- Time when the entry was recorded
+    actor         TEXT    NOT NULL, -- Identifier of the identity which performed the operation
+    action        TEXT    NOT NULL, -- Name of the operation
+    target        TEXT,             -- Identifier of the member affected by the operation, if any
+    attributes    BLOB,             -- CBOR serialized map of the operation parameters
+    previous_hash BLOB    NOT NULL, -- Hash of the previous entry
+    hash          BLOB    NOT NULL, -- Hash of this entry
+    signature     BLOB    NOT NULL  -- CBOR serialized signature of the hash and purpose key attestation used to sign it
+);
+
+CREATE UNIQUE INDEX authority_audit_log_index ON authority_audit_log (authority_id, entry_index);
+
+CREATE TRIGGER authority_audit_log_no_update
+    BEFORE UPDATE ON authority_audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'the authority audit log is append-only');
+END;
+
+CREATE TRIGGER authority_audit_log_no_delete
+    BEFORE DELETE ON authority_audit_log
+BEGIN
+    SELECT RAISE(ABORT, 'the authority audit log is append-only');
+END;
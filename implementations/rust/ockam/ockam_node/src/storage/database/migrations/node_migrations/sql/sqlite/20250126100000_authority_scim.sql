@@ -0,0 +1,35 @@
+-- This table stores the users provisioned on an authority with SCIM.
+-- The user name of a SCIM user is the identifier of a project member
+CREATE TABLE authority_scim_user
+(
+    authority_id TEXT    NOT NULL, -- Identifier of the authority
+    identifier   TEXT    NOT NULL, -- Identifier of the member
+    resource     TEXT    NOT NULL, -- JSON representation of the SCIM user, as provided by the SCIM client
+    created_at   INTEGER NOT NULL, -- Time when the user was provisioned
+    updated_at   INTEGER NOT NULL  -- Time when the user was last modified
+);
+
+CREATE UNIQUE INDEX authority_scim_user_index ON authority_scim_user (authority_id, identifier);
+
+-- This table stores the groups provisioned on an authority with SCIM
+CREATE TABLE authority_scim_group
+(
+    authority_id TEXT    NOT NULL, -- Identifier of the authority
+    group_id     TEXT    NOT NULL, -- Identifier of the group, generated by the authority
+    display_name TEXT    NOT NULL, -- Name of the group
+    external_id  TEXT,             -- Identifier of the group for the SCIM client
+    created_at   INTEGER NOT NULL, -- Time when the group was provisioned
+    updated_at   INTEGER NOT NULL  -- Time when the group was last modified
+);
+
+CREATE UNIQUE INDEX authority_scim_group_index ON authority_scim_group (authority_id, group_id);
+
+-- This table stores the members of the SCIM groups
+CREATE TABLE authority_scim_group_member
+(
+    authority_id TEXT NOT NULL, -- Identifier of the authority
+    group_id     TEXT NOT NULL, -- Identifier of the group
+    identifier   TEXT NOT NULL  -- Identifier of the member
+);
+
+CREATE UNIQUE INDEX authority_scim_group_member_index ON authority_scim_group_member (authority_id, group_id, identifier);
@@ -0,0 +1,12 @@
+-- This table stores the identifiers of the JWTs already used to enroll members with the
+-- OIDC authenticator, so that a token can't be replayed before it expires
+CREATE TABLE authority_used_jwt
+(
+    authority_id TEXT    NOT NULL, -- Identifier of the authority
+    issuer       TEXT    NOT NULL, -- Issuer of the token
+    jwt_id       TEXT    NOT NULL, -- Value of the `jti` claim of the token, or hash of the token if it has no `jti` claim
+    expires_at   INTEGER NOT NULL  -- Time after which the token can't be used anymore
+);
+
+CREATE UNIQUE INDEX authority_used_jwt_index ON authority_used_jwt (authority_id, issuer, jwt_id);
+CREATE INDEX authority_used_jwt_expires_at_index ON authority_used_jwt (expires_at);
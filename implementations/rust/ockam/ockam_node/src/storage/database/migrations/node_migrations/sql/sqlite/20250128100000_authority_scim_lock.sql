@@ -0,0 +1,10 @@
+-- This table stores the lock serializing the SCIM modifications of an authority.
+-- Several authority nodes can share the same database, so the lock can't be held in memory
+CREATE TABLE authority_scim_lock
+(
+    authority_id TEXT    NOT NULL, -- Identifier of the authority
+    holder       TEXT    NOT NULL, -- Random value identifying the holder of the lock
+    expires_at   INTEGER NOT NULL  -- Time after which the lock is released, if the holder stopped
+);
+
+CREATE UNIQUE INDEX authority_scim_lock_index ON authority_scim_lock (authority_id);
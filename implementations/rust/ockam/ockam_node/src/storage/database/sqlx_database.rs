@@ -628,6 +628,25 @@ pub mod tests {
         Ok(())
     }
 
+    /// This test checks that several nodes can start at the same time with the same
+    /// Postgres database, and migrate it only once
+    #[tokio::test]
+    async fn test_migrate_postgres_database_concurrently() -> Result<()> {
+        if let Some(configuration) = DatabaseConfiguration::postgres()? {
+            let db = SqlxDatabase::create_no_migration(&configuration).await?;
+            db.drop_all_postgres_tables().await?;
+
+            let (db1, db2) = tokio::join!(
+                SqlxDatabase::create(&configuration),
+                SqlxDatabase::create(&configuration)
+            );
+            db1?;
+            let inserted = insert_identity(&db2?).await.unwrap();
+            assert_eq!(inserted.rows_affected(), 1);
+        }
+        Ok(())
+    }
+
     /// This test checks that we can run a query and return an entity
     #[tokio::test]
     async fn test_query() -> Result<()> {
@@ -0,0 +1,213 @@
+use crate::QuicTransportError;
+use cfg_if::cfg_if;
+use core::fmt;
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use quinn::crypto::rustls::{QuicClientConfig, QuicServerConfig};
+use quinn::TransportConfig;
+use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+use rustls::crypto::{
+    verify_tls12_signature, verify_tls13_signature, CryptoProvider, WebPkiSupportedAlgorithms,
+};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer, ServerName, UnixTime};
+use rustls::{CertificateError, DigitallySignedStruct, SignatureScheme};
+
+/// Name of the TLS server. Certificates are never verified against a name
+pub(crate) const SERVER_NAME: &str = "ockam";
+
+/// ALPN of the connections carrying Ockam Routing messages
+pub(crate) const ROUTING_ALPN: &[u8] = b"ockam/1";
+
+/// ALPN of the connections between a QUIC portal inlet and outlet
+pub(crate) const PORTAL_ALPN: &[u8] = b"ockam-portal/1";
+
+/// QUIC closes connections without traffic after 30 seconds by default, while the Ockam
+/// connections must stay open as long as they are not explicitly stopped
+const KEEP_ALIVE_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Self-signed certificate with its private key, used to set up the QUIC encryption
+pub(crate) struct QuicCertificate {
+    certificate: CertificateDer<'static>,
+    private_key: Vec<u8>,
+}
+
+impl QuicCertificate {
+    /// Generate a new self-signed certificate
+    pub(crate) fn generate() -> Result<Self> {
+        let certified_key = rcgen::generate_simple_self_signed(vec![SERVER_NAME.to_string()])
+            .map_err(|e| QuicTransportError::Certificate(e.to_string()))?;
+
+        Ok(Self {
+            certificate: certified_key.cert.der().clone(),
+            private_key: certified_key.key_pair.serialize_der(),
+        })
+    }
+
+    /// DER-encoded certificate
+    pub(crate) fn certificate(&self) -> &[u8] {
+        &self.certificate
+    }
+
+    fn private_key_der(&self) -> PrivateKeyDer<'static> {
+        PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(self.private_key.clone()))
+    }
+}
+
+/// Create the configuration of a QUIC client.
+///
+/// Only a server presenting the pinned certificate is accepted if one is given. Otherwise any
+/// server certificate is accepted: the peers are then authenticated by the secure channels
+/// established on top of the connection
+pub(crate) fn client_config(
+    alpn: &[u8],
+    pinned_certificate: Option<CertificateDer<'static>>,
+) -> Result<quinn::ClientConfig> {
+    let provider = crypto_provider();
+    let verifier = Arc::new(ServerCertificate::new(&provider, pinned_certificate));
+
+    let mut config = rustls::ClientConfig::builder_with_provider(provider)
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(tls_error)?
+        .dangerous()
+        .with_custom_certificate_verifier(verifier)
+        .with_no_client_auth();
+    config.alpn_protocols = vec![alpn.to_vec()];
+
+    let config = QuicClientConfig::try_from(config).map_err(tls_error)?;
+    let mut config = quinn::ClientConfig::new(Arc::new(config));
+    config.transport_config(transport_config());
+    Ok(config)
+}
+
+/// Create the configuration of a QUIC server, accepting clients without certificate
+pub(crate) fn server_config(
+    alpn: &[u8],
+    certificate: &QuicCertificate,
+) -> Result<quinn::ServerConfig> {
+    let mut config = rustls::ServerConfig::builder_with_provider(crypto_provider())
+        .with_protocol_versions(&[&rustls::version::TLS13])
+        .map_err(tls_error)?
+        .with_no_client_auth()
+        .with_single_cert(
+            vec![certificate.certificate.clone()],
+            certificate.private_key_der(),
+        )
+        .map_err(tls_error)?;
+    config.alpn_protocols = vec![alpn.to_vec()];
+
+    let config = QuicServerConfig::try_from(config).map_err(tls_error)?;
+    let mut config = quinn::ServerConfig::with_crypto(Arc::new(config));
+    config.transport_config(transport_config());
+    Ok(config)
+}
+
+fn transport_config() -> Arc<TransportConfig> {
+    let mut config = TransportConfig::default();
+    config.keep_alive_interval(Some(KEEP_ALIVE_INTERVAL));
+    Arc::new(config)
+}
+
+fn crypto_provider() -> Arc<CryptoProvider> {
+    cfg_if! {
+        if #[cfg(feature = "aws-lc")] {
+            Arc::new(rustls::crypto::aws_lc_rs::default_provider())
+        } else {
+            Arc::new(rustls::crypto::ring::default_provider())
+        }
+    }
+}
+
+fn tls_error(err: impl fmt::Display) -> QuicTransportError {
+    QuicTransportError::Tls(err.to_string())
+}
+
+/// Verifier accepting the pinned server certificate, or any server certificate if none is
+/// pinned. The handshake signatures are always verified, to make sure that the server owns
+/// the private key of its certificate
+#[derive(Debug)]
+struct ServerCertificate {
+    pinned: Option<CertificateDer<'static>>,
+    algorithms: WebPkiSupportedAlgorithms,
+}
+
+impl ServerCertificate {
+    fn new(provider: &CryptoProvider, pinned: Option<CertificateDer<'static>>) -> Self {
+        Self {
+            pinned,
+            algorithms: provider.signature_verification_algorithms,
+        }
+    }
+
+    fn verify(&self, end_entity: &CertificateDer<'_>) -> Result<(), rustls::Error> {
+        match &self.pinned {
+            Some(pinned) if pinned != end_entity => Err(rustls::Error::InvalidCertificate(
+                CertificateError::ApplicationVerificationFailure,
+            )),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl ServerCertVerifier for ServerCertificate {
+    fn verify_server_cert(
+        &self,
+        end_entity: &CertificateDer<'_>,
+        _intermediates: &[CertificateDer<'_>],
+        _server_name: &ServerName<'_>,
+        _ocsp_response: &[u8],
+        _now: UnixTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        self.verify(end_entity)?;
+        Ok(ServerCertVerified::assertion())
+    }
+
+    fn verify_tls12_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls12_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn verify_tls13_signature(
+        &self,
+        message: &[u8],
+        cert: &CertificateDer<'_>,
+        dss: &DigitallySignedStruct,
+    ) -> Result<HandshakeSignatureValid, rustls::Error> {
+        verify_tls13_signature(message, cert, dss, &self.algorithms)
+    }
+
+    fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+        self.algorithms.supported_schemes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn generated_certificate_can_be_used_by_a_server() -> Result<()> {
+        let certificate = QuicCertificate::generate()?;
+        server_config(ROUTING_ALPN, &certificate)?;
+        client_config(ROUTING_ALPN, None)?;
+        Ok(())
+    }
+
+    #[test]
+    fn only_the_pinned_certificate_is_accepted() -> Result<()> {
+        let pinned = QuicCertificate::generate()?;
+        let other = QuicCertificate::generate()?;
+        let verifier = ServerCertificate::new(&crypto_provider(), Some(pinned.certificate.clone()));
+
+        assert!(verifier.verify(&pinned.certificate).is_ok());
+        assert!(verifier.verify(&other.certificate).is_err());
+
+        let any = ServerCertificate::new(&crypto_provider(), None);
+        assert!(any.verify(&other.certificate).is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,52 @@
+#![allow(missing_docs)]
+
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::Error;
+
+/// QUIC Transport error type
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum QuicTransportError {
+    /// The TLS configuration could not be created
+    Tls(String),
+    /// A certificate could not be generated or parsed
+    Certificate(String),
+    /// A QUIC endpoint could not be created
+    Endpoint(String),
+    /// A QUIC connection could not be established
+    Connect { peer: String, reason: String },
+    /// A QUIC stream could not be opened or accepted
+    Stream(String),
+    /// The multiaddr doesn't describe a QUIC address
+    InvalidMultiAddr(String),
+}
+
+impl ockam_core::compat::error::Error for QuicTransportError {}
+impl core::fmt::Display for QuicTransportError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Tls(reason) => write!(f, "Invalid TLS configuration: {reason}"),
+            Self::Certificate(reason) => write!(f, "Invalid certificate: {reason}"),
+            Self::Endpoint(reason) => write!(f, "Cannot create a QUIC endpoint: {reason}"),
+            Self::Connect { peer, reason } => {
+                write!(f, "Cannot connect to {peer} using QUIC: {reason}")
+            }
+            Self::Stream(reason) => write!(f, "Cannot open a QUIC stream: {reason}"),
+            Self::InvalidMultiAddr(ma) => write!(f, "Invalid QUIC multiaddr: {ma}"),
+        }
+    }
+}
+
+impl From<QuicTransportError> for Error {
+    #[track_caller]
+    fn from(err: QuicTransportError) -> Error {
+        let kind = match err {
+            QuicTransportError::Tls(_)
+            | QuicTransportError::Certificate(_)
+            | QuicTransportError::InvalidMultiAddr(_) => Kind::Invalid,
+            QuicTransportError::Endpoint(_)
+            | QuicTransportError::Connect { .. }
+            | QuicTransportError::Stream(_) => Kind::Io,
+        };
+        Error::new(Origin::Transport, kind, err)
+    }
+}
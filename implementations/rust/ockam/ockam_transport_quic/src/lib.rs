@@ -0,0 +1,48 @@
+//! This crate provides a QUIC Transport for Ockam's Routing Protocol.
+//!
+//! Each QUIC connection carries Ockam Routing messages on a single bidirectional stream.
+//! Authentication is left to the secure channels established on top of the transport, as for
+//! the TCP transport.
+//!
+//! The crate also provides QUIC portals, where each TCP connection accepted by an inlet is
+//! mapped to its own stream of a QUIC connection to the outlet. An inlet only connects once the
+//! outlet worker, reached over a secure channel and protected by its access control, granted it
+//! a token and the certificate of the outlet. The token must be presented on every stream.
+//!
+//! This crate requires the rust standard library `"std"`
+#![deny(unsafe_code)]
+#![warn(
+    missing_docs,
+    dead_code,
+    trivial_casts,
+    trivial_numeric_casts,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+mod certificate;
+mod error;
+mod multiaddr;
+mod options;
+mod portal;
+mod protocol_version;
+mod registry;
+mod transport;
+mod transport_message;
+mod workers;
+
+pub(crate) use workers::*;
+
+pub use error::*;
+pub use multiaddr::*;
+pub use options::{QuicConnectionOptions, QuicListenerOptions};
+pub use portal::{QuicInletOptions, QuicOutletOptions};
+pub use protocol_version::*;
+pub use registry::*;
+pub use transport::*;
+
+/// Transport type for QUIC addresses
+pub const QUIC: ockam_core::TransportType = ockam_core::TransportType::new(6);
+
+/// 16 MB
+pub const MAX_MESSAGE_SIZE: usize = 16 * 1024 * 1024;
@@ -0,0 +1,123 @@
+use crate::{QuicTransportError, QUIC};
+use ockam_core::{Address, Result, Route, LOCAL};
+use ockam_multiaddr::proto::{DnsAddr, Ip4, Ip6, Quic, Secure, Service, Udp, Worker};
+use ockam_multiaddr::{MultiAddr, ProtoIter, Protocol};
+use std::net::{SocketAddr, SocketAddrV4, SocketAddrV6};
+
+/// Return the `host:port` QUIC address of a multiaddr starting with a QUIC transport hop,
+/// for example `/ip4/127.0.0.1/udp/4000/quic` or `/dnsaddr/localhost/udp/4000/quic/service/api`
+pub fn multiaddr_to_socket_address(ma: &MultiAddr) -> Result<String> {
+    parse_transport_hop(ma, &mut ma.iter())
+}
+
+/// Convert a multiaddr starting with a QUIC transport hop into a route.
+///
+/// The QUIC hop is converted to a [`QUIC`] address, which is replaced by the address of a QUIC
+/// sender worker when the route is resolved by the node. The following hops must be local.
+pub fn multiaddr_to_route(ma: &MultiAddr) -> Result<Route> {
+    let mut it = ma.iter();
+    let socket_address = parse_transport_hop(ma, &mut it)?;
+    let mut route = Route::new().append(Address::new_with_string(QUIC, socket_address));
+
+    for p in it {
+        let local = match p.code() {
+            Worker::CODE => p.cast::<Worker>().map(|w| w.to_string()),
+            Service::CODE => p.cast::<Service>().map(|s| s.to_string()),
+            Secure::CODE => p.cast::<Secure>().map(|s| s.to_string()),
+            _ => None,
+        }
+        .ok_or_else(|| invalid_multiaddr(ma))?;
+        route = route.append(Address::new_with_string(LOCAL, local));
+    }
+
+    Ok(route.into())
+}
+
+/// Return the multiaddr of a QUIC socket address, for example `/ip4/127.0.0.1/udp/4000/quic`
+pub fn socket_address_to_multiaddr(socket_address: &SocketAddr) -> Result<MultiAddr> {
+    let mut ma = MultiAddr::default();
+    match socket_address {
+        SocketAddr::V4(socket_address) => ma.push_back(Ip4::new(*socket_address.ip())),
+        SocketAddr::V6(socket_address) => ma.push_back(Ip6::new(*socket_address.ip())),
+    }
+    .and_then(|_| ma.push_back(Udp::new(socket_address.port())))
+    .and_then(|_| ma.push_back(Quic))
+    .map_err(|e| QuicTransportError::InvalidMultiAddr(e.to_string()))?;
+    Ok(ma)
+}
+
+fn parse_transport_hop(ma: &MultiAddr, it: &mut ProtoIter) -> Result<String> {
+    let host = it.next().ok_or_else(|| invalid_multiaddr(ma))?;
+    let port = it
+        .next()
+        .and_then(|p| p.cast::<Udp>())
+        .ok_or_else(|| invalid_multiaddr(ma))?;
+    it.next()
+        .filter(|p| p.code() == Quic::CODE)
+        .ok_or_else(|| invalid_multiaddr(ma))?;
+
+    match host.code() {
+        Ip4::CODE => {
+            let ip4 = host.cast::<Ip4>().ok_or_else(|| invalid_multiaddr(ma))?;
+            Ok(SocketAddrV4::new(*ip4, *port).to_string())
+        }
+        Ip6::CODE => {
+            let ip6 = host.cast::<Ip6>().ok_or_else(|| invalid_multiaddr(ma))?;
+            Ok(SocketAddrV6::new(*ip6, *port, 0, 0).to_string())
+        }
+        DnsAddr::CODE => {
+            let hostname = host
+                .cast::<DnsAddr>()
+                .ok_or_else(|| invalid_multiaddr(ma))?;
+            Ok(format!("{}:{}", &*hostname, *port))
+        }
+        _ => Err(invalid_multiaddr(ma)),
+    }
+}
+
+fn invalid_multiaddr(ma: &MultiAddr) -> ockam_core::Error {
+    QuicTransportError::InvalidMultiAddr(ma.to_string()).into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::str::FromStr;
+    use ockam_core::route;
+
+    #[test]
+    fn quic_multiaddr() -> Result<()> {
+        let ma = MultiAddr::from_str("/ip4/127.0.0.1/udp/4000/quic").unwrap();
+        assert_eq!(multiaddr_to_socket_address(&ma)?, "127.0.0.1:4000");
+
+        let ma = MultiAddr::from_str("/ip6/::1/udp/4000/quic").unwrap();
+        assert_eq!(multiaddr_to_socket_address(&ma)?, "[::1]:4000");
+
+        let ma = MultiAddr::from_str("/dnsaddr/localhost/udp/4000/quic/service/api").unwrap();
+        assert_eq!(multiaddr_to_socket_address(&ma)?, "localhost:4000");
+        assert_eq!(
+            multiaddr_to_route(&ma)?,
+            route![(QUIC, "localhost:4000"), "api"]
+        );
+
+        let socket_address = SocketAddr::from_str("127.0.0.1:4000").unwrap();
+        assert_eq!(
+            socket_address_to_multiaddr(&socket_address)?.to_string(),
+            "/ip4/127.0.0.1/udp/4000/quic"
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn invalid_quic_multiaddr() {
+        for ma in [
+            "/ip4/127.0.0.1/udp/4000",
+            "/ip4/127.0.0.1/tcp/4000/quic",
+            "/service/api",
+            "/ip4/127.0.0.1/udp/4000/quic/ip4/127.0.0.1/udp/5000/quic",
+        ] {
+            let ma = MultiAddr::from_str(ma).unwrap();
+            assert!(multiaddr_to_route(&ma).is_err(), "{ma}");
+        }
+    }
+}
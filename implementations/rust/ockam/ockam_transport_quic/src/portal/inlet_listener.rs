@@ -0,0 +1,222 @@
+use crate::certificate::{client_config, PORTAL_ALPN};
+use crate::portal::{forward_tcp_stream, PortalToken, QuicPortalGrant, QuicPortalRequest};
+use crate::transport::connect;
+use crate::{QuicInletOptions, QuicProtocolVersion, QuicRegistry, QuicTransportError};
+use core::time::Duration;
+use ockam_core::compat::sync::Arc;
+use ockam_core::{async_trait, compat::net::SocketAddr};
+use ockam_core::{Address, AllowOnwardAddress, Mailbox, Mailboxes, Processor, Result, Route};
+use ockam_node::{Context, MessageReceiveOptions, ProcessorBuilder};
+use ockam_transport_core::{HostnamePort, TransportError};
+use quinn::{Connection, RecvStream, SendStream, VarInt};
+use rustls::pki_types::CertificateDer;
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::Mutex;
+use tokio::task::JoinSet;
+use tracing::{debug, instrument, warn};
+
+/// Max duration to receive the grant of the outlet worker
+const GRANT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A QUIC Portal Inlet listen processor
+///
+/// QUIC Portal Inlet listen processors are created by `QuicTransport`
+/// after a call is made to
+/// [`QuicTransport::create_inlet`](crate::QuicTransport::create_inlet).
+///
+/// All the TCP connections accepted by the inlet share the same QUIC connection to the outlet,
+/// each TCP connection being forwarded on its own QUIC stream. The QUIC connection is only
+/// established after the outlet worker granted it, over the route to the outlet worker.
+pub(crate) struct QuicInletListenProcessor {
+    inner: TcpListener,
+    outlet: Arc<OutletConnection>,
+    streams: JoinSet<()>,
+}
+
+impl QuicInletListenProcessor {
+    #[instrument(skip_all, name = "QuicInletListenProcessor::start")]
+    pub(crate) async fn start(
+        ctx: Arc<Context>,
+        registry: QuicRegistry,
+        addr: SocketAddr,
+        outlet: HostnamePort,
+        outlet_route: Route,
+        options: QuicInletOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        debug!("Binding QUIC inlet to {}", addr);
+        let inner = TcpListener::bind(addr)
+            .await
+            .map_err(TransportError::from)?;
+        let saddr = inner.local_addr().map_err(TransportError::from)?;
+
+        let address = Address::random_tagged("QuicInletListenProcessor");
+        let processor = Self {
+            inner,
+            outlet: Arc::new(OutletConnection {
+                ctx: ctx.clone(),
+                registry,
+                listener_address: address.clone(),
+                outlet,
+                outlet_route,
+                options,
+                connection: Mutex::new(None),
+            }),
+            streams: JoinSet::new(),
+        };
+
+        ProcessorBuilder::new(processor)
+            .with_address(address.clone())
+            .start(&ctx)?;
+
+        Ok((saddr, address))
+    }
+}
+
+#[async_trait]
+impl Processor for QuicInletListenProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "QuicInletListenProcessor::initialize")]
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        self.outlet
+            .registry
+            .add_inlet_listener_processor(ctx.primary_address());
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicInletListenProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.outlet
+            .registry
+            .remove_inlet_listener_processor(ctx.primary_address());
+
+        self.streams.abort_all();
+        if let Some((connection, _)) = self.outlet.connection.lock().await.take() {
+            connection.close(VarInt::from_u32(0), b"closed");
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicInletListenProcessor::process")]
+    async fn process(&mut self, _ctx: &mut Self::Context) -> Result<bool> {
+        let (tcp_stream, peer) = self.inner.accept().await.map_err(TransportError::from)?;
+        tcp_stream.set_nodelay(true).map_err(TransportError::from)?;
+        debug!("QUIC inlet accepted a TCP connection from {}", peer);
+
+        // Forget the streams which are already closed
+        while self.streams.try_join_next().is_some() {}
+
+        self.streams
+            .spawn(self.outlet.clone().forward(tcp_stream, peer));
+
+        Ok(true)
+    }
+}
+
+/// QUIC connection of an inlet to its outlet, shared by the TCP connections of the inlet
+struct OutletConnection {
+    ctx: Arc<Context>,
+    registry: QuicRegistry,
+    listener_address: Address,
+    outlet: HostnamePort,
+    outlet_route: Route,
+    options: QuicInletOptions,
+    connection: Mutex<Option<(Connection, PortalToken)>>,
+}
+
+impl OutletConnection {
+    /// Forward a TCP connection on a new stream to the outlet
+    async fn forward(self: Arc<Self>, tcp_stream: TcpStream, peer: SocketAddr) {
+        match self.open_stream().await {
+            Ok((connection, send_stream, recv_stream)) => {
+                forward_tcp_stream(
+                    self.registry.clone(),
+                    self.listener_address.clone(),
+                    connection.stable_id(),
+                    tcp_stream,
+                    send_stream,
+                    recv_stream,
+                )
+                .await
+            }
+            Err(err) => {
+                warn!(
+                    "Closing the TCP connection from {}, the QUIC outlet {} can't be reached: {}",
+                    peer, self.outlet, err
+                );
+            }
+        }
+    }
+
+    /// Open a new stream to the outlet, connecting to the outlet first if the connection
+    /// is not established yet or was closed
+    async fn open_stream(&self) -> Result<(Connection, SendStream, RecvStream)> {
+        let (connection, token) = {
+            let mut current = self.connection.lock().await;
+            match current.as_ref() {
+                Some((connection, token)) if connection.close_reason().is_none() => {
+                    (connection.clone(), *token)
+                }
+                _ => {
+                    let (connection, token) = self.connect().await?;
+                    *current = Some((connection.clone(), token));
+                    (connection, token)
+                }
+            }
+        };
+
+        let (mut send_stream, recv_stream) = connection
+            .open_bi()
+            .await
+            .map_err(|e| QuicTransportError::Stream(e.to_string()))?;
+
+        // The outlet only accepts the stream when receiving data, which might not be sent
+        // by the TCP client before the TCP server answers
+        let mut header = vec![QuicProtocolVersion::V1.into()];
+        header.extend_from_slice(&token);
+        send_stream
+            .write_all(&header)
+            .await
+            .map_err(|e| QuicTransportError::Stream(e.to_string()))?;
+
+        Ok((connection, send_stream, recv_stream))
+    }
+
+    /// Request a grant from the outlet worker, then connect to the outlet with the granted
+    /// certificate
+    async fn connect(&self) -> Result<(Connection, PortalToken)> {
+        let grant = self.request_grant().await?;
+        let config = client_config(PORTAL_ALPN, Some(CertificateDer::from(grant.certificate)))?;
+        let connection = connect(&self.outlet, config).await?;
+
+        Ok((connection, grant.token))
+    }
+
+    /// Send a request to the outlet worker and wait for its grant, which must pass the
+    /// incoming access control of the inlet
+    async fn request_grant(&self) -> Result<QuicPortalGrant> {
+        let next = self.outlet_route.next()?.clone();
+        let address = Address::random_tagged("QuicInlet.request_grant");
+        let mailboxes = Mailboxes::new(
+            Mailbox::new(
+                address.clone(),
+                None,
+                self.options.incoming_access_control.clone(),
+                Arc::new(AllowOnwardAddress(next.clone())),
+            ),
+            vec![],
+        );
+        QuicInletOptions::setup_flow_control_for_address(self.ctx.flow_controls(), &address, &next);
+
+        let mut ctx = self.ctx.new_detached_with_mailboxes(mailboxes)?;
+        ctx.send(self.outlet_route.clone(), QuicPortalRequest)
+            .await?;
+        ctx.receive_extended::<QuicPortalGrant>(
+            MessageReceiveOptions::new().with_timeout(GRANT_TIMEOUT),
+        )
+        .await?
+        .into_body()
+    }
+}
@@ -0,0 +1,18 @@
+use crate::portal::PortalToken;
+use ockam_core::Message;
+use serde::{Deserialize, Serialize};
+
+/// Request sent by a QUIC portal inlet to the outlet worker, over a secure channel, to be
+/// allowed to connect to the outlet
+#[derive(Serialize, Deserialize, Message, Debug)]
+pub(crate) struct QuicPortalRequest;
+
+/// Response of the outlet worker to an authorized [`QuicPortalRequest`]
+#[derive(Serialize, Deserialize, Message, Debug)]
+pub(crate) struct QuicPortalGrant {
+    /// Token authorizing one QUIC connection to the outlet. It must be sent first on each
+    /// stream of the connection
+    pub(crate) token: PortalToken,
+    /// DER-encoded certificate of the outlet, which the inlet pins when connecting
+    pub(crate) certificate: Vec<u8>,
+}
@@ -0,0 +1,76 @@
+mod inlet_listener;
+mod messages;
+mod options;
+mod outlet_listener;
+mod outlet_worker;
+mod tokens;
+
+pub(crate) use inlet_listener::*;
+pub(crate) use messages::*;
+pub use options::*;
+pub(crate) use outlet_listener::*;
+pub(crate) use outlet_worker::*;
+pub(crate) use tokens::*;
+
+use crate::{QuicPortalStreamInfo, QuicRegistry};
+use ockam_core::Address;
+use quinn::{RecvStream, SendStream, VarInt};
+use tokio::io::copy_bidirectional;
+use tokio::net::TcpStream;
+use tracing::{debug, trace};
+
+/// Copy the data of a TCP connection to its QUIC stream, in both directions, until both sides
+/// are closed. The stream is listed in the registry while it is open
+pub(crate) async fn forward_tcp_stream(
+    registry: QuicRegistry,
+    listener_address: Address,
+    connection_id: usize,
+    mut tcp_stream: TcpStream,
+    send_stream: SendStream,
+    recv_stream: RecvStream,
+) {
+    let stream_id = VarInt::from(send_stream.id()).into_inner();
+    let _registered = RegisteredStream::new(registry, listener_address, connection_id, stream_id);
+
+    let mut quic_stream = tokio::io::join(recv_stream, send_stream);
+    match copy_bidirectional(&mut tcp_stream, &mut quic_stream).await {
+        Ok((sent, received)) => {
+            trace!("QUIC portal stream closed. Sent {sent} bytes, received {received} bytes")
+        }
+        Err(err) => debug!("QUIC portal stream failed: {err}"),
+    }
+}
+
+/// Remove a stream from the registry when its forwarding task completes or is aborted
+struct RegisteredStream {
+    registry: QuicRegistry,
+    connection_id: usize,
+    stream_id: u64,
+}
+
+impl RegisteredStream {
+    fn new(
+        registry: QuicRegistry,
+        listener_address: Address,
+        connection_id: usize,
+        stream_id: u64,
+    ) -> Self {
+        registry.add_portal_stream(QuicPortalStreamInfo::new(
+            listener_address,
+            connection_id,
+            stream_id,
+        ));
+        Self {
+            registry,
+            connection_id,
+            stream_id,
+        }
+    }
+}
+
+impl Drop for RegisteredStream {
+    fn drop(&mut self) {
+        self.registry
+            .remove_portal_stream(self.connection_id, self.stream_id);
+    }
+}
@@ -0,0 +1,115 @@
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::{FlowControlId, FlowControls};
+use ockam_core::{Address, AllowAll, IncomingAccessControl};
+
+/// Options of a QUIC portal inlet
+#[derive(Clone, Debug)]
+pub struct QuicInletOptions {
+    pub(crate) incoming_access_control: Arc<dyn IncomingAccessControl>,
+}
+
+impl QuicInletOptions {
+    /// Default constructor without Incoming Access Control
+    pub fn new() -> Self {
+        Self {
+            incoming_access_control: Arc::new(AllowAll),
+        }
+    }
+
+    /// Set the Incoming Access Control checked on the grants sent by the outlet worker,
+    /// for example to only accept the grants of the outlet identity
+    pub fn with_incoming_access_control_impl(
+        mut self,
+        access_control: impl IncomingAccessControl,
+    ) -> Self {
+        self.incoming_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set the Incoming Access Control checked on the grants sent by the outlet worker,
+    /// for example to only accept the grants of the outlet identity
+    pub fn with_incoming_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.incoming_access_control = access_control;
+        self
+    }
+
+    pub(crate) fn setup_flow_control_for_address(
+        flow_controls: &FlowControls,
+        address: &Address,
+        next: &Address,
+    ) {
+        if let Some(flow_control_id) = flow_controls
+            .find_flow_control_with_producer_address(next)
+            .map(|x| x.flow_control_id().clone())
+        {
+            // Allow a sender with corresponding flow_control_id send messages to this address
+            flow_controls.add_consumer(address, &flow_control_id);
+        }
+    }
+}
+
+impl Default for QuicInletOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Options of a QUIC portal outlet
+#[derive(Clone, Debug)]
+pub struct QuicOutletOptions {
+    pub(crate) consumer: Vec<FlowControlId>,
+    pub(crate) incoming_access_control: Arc<dyn IncomingAccessControl>,
+}
+
+impl QuicOutletOptions {
+    /// Default constructor without Incoming Access Control
+    pub fn new() -> Self {
+        Self {
+            consumer: vec![],
+            incoming_access_control: Arc::new(AllowAll),
+        }
+    }
+
+    /// Set the Incoming Access Control of the outlet worker, which grants the inlets the right
+    /// to open QUIC streams to the outlet
+    pub fn with_incoming_access_control_impl(
+        mut self,
+        access_control: impl IncomingAccessControl,
+    ) -> Self {
+        self.incoming_access_control = Arc::new(access_control);
+        self
+    }
+
+    /// Set the Incoming Access Control of the outlet worker, which grants the inlets the right
+    /// to open QUIC streams to the outlet
+    pub fn with_incoming_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.incoming_access_control = access_control;
+        self
+    }
+
+    /// Mark that the outlet worker is a Consumer for to the given [`FlowControlId`],
+    /// usually the one of a secure channel listener
+    pub fn as_consumer(mut self, id: &FlowControlId) -> Self {
+        self.consumer.push(id.clone());
+
+        self
+    }
+
+    pub(crate) fn setup_flow_control(&self, flow_controls: &FlowControls, address: &Address) {
+        for id in &self.consumer {
+            flow_controls.add_consumer(address, id);
+        }
+    }
+}
+
+impl Default for QuicOutletOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
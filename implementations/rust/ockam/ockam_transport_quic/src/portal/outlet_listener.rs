@@ -0,0 +1,230 @@
+use crate::portal::{forward_tcp_stream, PortalToken, PortalTokens};
+use crate::{QuicProtocolVersion, QuicRegistry};
+use core::time::Duration;
+use ockam_core::{async_trait, Address, Processor, Result};
+use ockam_node::{Context, ProcessorBuilder};
+use ockam_transport_core::HostnamePort;
+use quinn::{Connection, Endpoint, Incoming, RecvStream, SendStream, VarInt};
+use tokio::io::AsyncReadExt;
+use tokio::net::TcpStream;
+use tokio::task::JoinSet;
+use tokio::time::timeout;
+use tracing::{debug, instrument};
+
+/// Max duration to receive the header of a stream, so that a peer can't keep streams or
+/// unauthorized connections open
+const HEADER_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Error code sent when closing a connection which doesn't present a granted token
+const UNAUTHORIZED: u32 = 1;
+
+/// A QUIC Portal Outlet listen processor
+///
+/// QUIC Portal Outlet listen processors are created by `QuicTransport`
+/// after a call is made to
+/// [`QuicTransport::create_outlet`](crate::QuicTransport::create_outlet).
+///
+/// A QUIC connection is only accepted if its first stream presents a token granted by the
+/// outlet worker, and all its streams must present the same token. Each stream is then
+/// forwarded to a new TCP connection to the peer.
+pub(crate) struct QuicOutletListenProcessor {
+    registry: QuicRegistry,
+    endpoint: Endpoint,
+    peer: HostnamePort,
+    tokens: PortalTokens,
+    connections: JoinSet<()>,
+}
+
+impl QuicOutletListenProcessor {
+    #[instrument(skip_all, name = "QuicOutletListenProcessor::start")]
+    pub(crate) fn start(
+        ctx: &Context,
+        registry: QuicRegistry,
+        endpoint: Endpoint,
+        peer: HostnamePort,
+        tokens: PortalTokens,
+    ) -> Result<Address> {
+        let processor = Self {
+            registry,
+            endpoint,
+            peer,
+            tokens,
+            connections: JoinSet::new(),
+        };
+
+        let address = Address::random_tagged("QuicOutletListenProcessor");
+        ProcessorBuilder::new(processor)
+            .with_address(address.clone())
+            .start(ctx)?;
+
+        Ok(address)
+    }
+}
+
+#[async_trait]
+impl Processor for QuicOutletListenProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "QuicOutletListenProcessor::initialize")]
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        self.registry
+            .add_outlet_listener_processor(ctx.primary_address());
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicOutletListenProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry
+            .remove_outlet_listener_processor(ctx.primary_address());
+
+        self.connections.abort_all();
+        self.endpoint.close(VarInt::from_u32(0), b"closed");
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicOutletListenProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        let incoming = match self.endpoint.accept().await {
+            Some(incoming) => incoming,
+            None => return Ok(false),
+        };
+
+        // Forget the connections which are already closed
+        while self.connections.try_join_next().is_some() {}
+
+        self.connections.spawn(handle_connection(
+            OutletConnection {
+                registry: self.registry.clone(),
+                listener_address: ctx.primary_address().clone(),
+                peer: self.peer.clone(),
+            },
+            incoming,
+            self.tokens.clone(),
+        ));
+
+        Ok(true)
+    }
+}
+
+/// Data shared by the streams of an outlet connection
+#[derive(Clone)]
+struct OutletConnection {
+    registry: QuicRegistry,
+    listener_address: Address,
+    peer: HostnamePort,
+}
+
+/// Authorize an inlet connection with the token of its first stream, then accept its streams
+/// until the connection is closed
+async fn handle_connection(outlet: OutletConnection, incoming: Incoming, tokens: PortalTokens) {
+    let connection = match incoming.await {
+        Ok(connection) => connection,
+        Err(err) => {
+            debug!("Failed to accept a QUIC inlet connection: {err}");
+            return;
+        }
+    };
+    let remote_address = connection.remote_address();
+
+    let first_stream = timeout(HEADER_TIMEOUT, async {
+        let (send_stream, mut recv_stream) = connection.accept_bi().await.ok()?;
+        let token = read_header(&mut recv_stream).await?;
+        Some((send_stream, recv_stream, token))
+    })
+    .await;
+    let (send_stream, recv_stream, token) = match first_stream {
+        Ok(Some(first_stream)) => first_stream,
+        _ => {
+            debug!("QUIC inlet connection from {remote_address} sent no valid stream header");
+            connection.close(VarInt::from_u32(UNAUTHORIZED), b"unauthorized");
+            return;
+        }
+    };
+    if !tokens.consume(&token) {
+        debug!("QUIC inlet connection from {remote_address} presented an invalid token");
+        connection.close(VarInt::from_u32(UNAUTHORIZED), b"unauthorized");
+        return;
+    }
+    debug!("QUIC outlet accepted a connection from {remote_address}");
+
+    // The streams are aborted when the connection task is aborted
+    let mut streams = JoinSet::new();
+    streams.spawn(connect_peer(
+        outlet.clone(),
+        connection.clone(),
+        send_stream,
+        recv_stream,
+    ));
+
+    loop {
+        let (send_stream, mut recv_stream) = match connection.accept_bi().await {
+            Ok(streams) => streams,
+            Err(err) => {
+                debug!("QUIC inlet connection closed: {err}");
+                return;
+            }
+        };
+
+        while streams.try_join_next().is_some() {}
+
+        let outlet = outlet.clone();
+        let connection = connection.clone();
+        streams.spawn(async move {
+            match timeout(HEADER_TIMEOUT, read_header(&mut recv_stream)).await {
+                Ok(Some(stream_token)) if stream_token == token => {
+                    connect_peer(outlet, connection, send_stream, recv_stream).await
+                }
+                _ => {
+                    debug!("QUIC inlet stream presented an invalid token");
+                    connection.close(VarInt::from_u32(UNAUTHORIZED), b"unauthorized");
+                }
+            }
+        });
+    }
+}
+
+/// Read the header sent first by the inlet on each stream: the protocol version and the token
+/// granted by the outlet worker
+async fn read_header(recv_stream: &mut RecvStream) -> Option<PortalToken> {
+    let version = recv_stream.read_u8().await.ok()?;
+    if let Err(err) = QuicProtocolVersion::try_from(version) {
+        debug!("Received an unsupported protocol version {version}: {err}");
+        return None;
+    }
+
+    let mut token = PortalToken::default();
+    recv_stream.read_exact(&mut token).await.ok()?;
+    Some(token)
+}
+
+/// Forward an authorized stream to a new TCP connection to the peer
+async fn connect_peer(
+    outlet: OutletConnection,
+    connection: Connection,
+    send_stream: SendStream,
+    recv_stream: RecvStream,
+) {
+    let peer = outlet.peer;
+    let tcp_stream = match TcpStream::connect(peer.to_string()).await {
+        Ok(tcp_stream) => tcp_stream,
+        Err(err) => {
+            debug!("QUIC outlet failed to connect to {peer}: {err}");
+            return;
+        }
+    };
+    if let Err(err) = tcp_stream.set_nodelay(true) {
+        debug!("Failed to disable Nagle's algorithm for {peer}: {err}");
+    }
+
+    forward_tcp_stream(
+        outlet.registry,
+        outlet.listener_address,
+        connection.stable_id(),
+        tcp_stream,
+        send_stream,
+        recv_stream,
+    )
+    .await
+}
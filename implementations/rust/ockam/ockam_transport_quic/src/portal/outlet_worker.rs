@@ -0,0 +1,79 @@
+use crate::portal::{PortalTokens, QuicPortalGrant, QuicPortalRequest};
+use crate::QuicOutletOptions;
+use ockam_core::{async_trait, Address, AllowAll, Result, Routed, Worker};
+use ockam_node::{Context, WorkerBuilder};
+use quinn::{Endpoint, VarInt};
+use tracing::{debug, instrument};
+
+/// A QUIC Portal Outlet worker
+///
+/// The worker is reached by the inlets over a secure channel. It grants each inlet passing
+/// its access control a token, which authorizes one QUIC connection to the outlet endpoint,
+/// along with the certificate of the endpoint.
+///
+/// Stopping the worker closes the outlet endpoint.
+pub(crate) struct QuicOutletWorker {
+    endpoint: Endpoint,
+    certificate: Vec<u8>,
+    tokens: PortalTokens,
+}
+
+impl QuicOutletWorker {
+    #[instrument(skip_all, name = "QuicOutletWorker::start")]
+    pub(crate) fn start(
+        ctx: &Context,
+        address: Address,
+        endpoint: Endpoint,
+        certificate: Vec<u8>,
+        tokens: PortalTokens,
+        options: QuicOutletOptions,
+    ) -> Result<()> {
+        options.setup_flow_control(ctx.flow_controls(), &address);
+
+        let worker = Self {
+            endpoint,
+            certificate,
+            tokens,
+        };
+        WorkerBuilder::new(worker)
+            .with_address(address)
+            .with_incoming_access_control_arc(options.incoming_access_control)
+            .with_outgoing_access_control(AllowAll)
+            .start(ctx)?;
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Worker for QuicOutletWorker {
+    type Context = Context;
+    type Message = QuicPortalRequest;
+
+    #[instrument(skip_all, name = "QuicOutletWorker::shutdown")]
+    async fn shutdown(&mut self, _ctx: &mut Self::Context) -> Result<()> {
+        // The outlet listener processor stops when the endpoint is closed
+        self.endpoint.close(VarInt::from_u32(0), b"closed");
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicOutletWorker::handle_message")]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Self::Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        debug!(
+            "QUIC outlet {} grants a connection to {}",
+            ctx.primary_address(),
+            msg.return_route()
+        );
+
+        let grant = QuicPortalGrant {
+            token: self.tokens.grant(),
+            certificate: self.certificate.clone(),
+        };
+        ctx.send(msg.return_route().clone(), grant).await
+    }
+}
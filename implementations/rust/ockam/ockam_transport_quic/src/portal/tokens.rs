@@ -0,0 +1,55 @@
+use core::time::Duration;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::rand::random;
+use ockam_core::compat::sync::{Arc, Mutex};
+use std::time::Instant;
+
+/// Random secret authorizing a QUIC connection to a portal outlet
+pub(crate) type PortalToken = [u8; 32];
+
+/// Time given to an inlet to connect to the outlet after being granted a token
+const TOKEN_VALIDITY: Duration = Duration::from_secs(30);
+
+/// Tokens granted by a portal outlet worker which are not used yet.
+///
+/// A token is consumed by the first QUIC connection presenting it, and expires if no
+/// connection presents it in time
+#[derive(Clone, Default)]
+pub(crate) struct PortalTokens {
+    tokens: Arc<Mutex<HashMap<PortalToken, Instant>>>,
+}
+
+impl PortalTokens {
+    /// Grant a new token
+    pub(crate) fn grant(&self) -> PortalToken {
+        let token = random();
+        let now = Instant::now();
+
+        let mut tokens = self.tokens.lock().unwrap();
+        tokens.retain(|_, expires_at| *expires_at > now);
+        tokens.insert(token, now + TOKEN_VALIDITY);
+
+        token
+    }
+
+    /// Consume a token, return true if it was granted and has not expired
+    pub(crate) fn consume(&self, token: &PortalToken) -> bool {
+        let expires_at = self.tokens.lock().unwrap().remove(token);
+        expires_at.is_some_and(|expires_at| expires_at > Instant::now())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn a_token_can_only_be_consumed_once() {
+        let tokens = PortalTokens::default();
+        let token = tokens.grant();
+
+        assert!(!tokens.consume(&random()));
+        assert!(tokens.consume(&token));
+        assert!(!tokens.consume(&token));
+    }
+}
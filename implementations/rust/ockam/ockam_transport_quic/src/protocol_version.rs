@@ -0,0 +1,26 @@
+use ockam_transport_core::TransportError;
+
+/// QUIC Protocol version, sent first on every QUIC stream
+#[repr(u8)]
+#[derive(Debug)]
+pub enum QuicProtocolVersion {
+    /// Version 1
+    V1 = 1,
+}
+
+impl From<QuicProtocolVersion> for u8 {
+    fn from(value: QuicProtocolVersion) -> Self {
+        value as u8
+    }
+}
+
+impl TryFrom<u8> for QuicProtocolVersion {
+    type Error = ockam_core::Error;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(QuicProtocolVersion::V1),
+            _ => Err(TransportError::InvalidProtocolVersion)?,
+        }
+    }
+}
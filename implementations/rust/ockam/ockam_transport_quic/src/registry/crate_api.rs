@@ -0,0 +1,67 @@
+use crate::{
+    QuicListenerInfo, QuicPortalStreamInfo, QuicReceiverInfo, QuicRegistry, QuicSenderInfo,
+};
+use ockam_core::Address;
+
+impl QuicRegistry {
+    pub(crate) fn add_inlet_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_inlet_listener_processor(addr);
+        }
+    }
+    pub(crate) fn remove_inlet_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_inlet_listener_processor(addr);
+        }
+    }
+    pub(crate) fn add_outlet_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_outlet_listener_processor(addr);
+        }
+    }
+    pub(crate) fn remove_outlet_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_outlet_listener_processor(addr);
+        }
+    }
+    pub(crate) fn add_portal_stream(&self, info: QuicPortalStreamInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_portal_stream(info);
+        }
+    }
+    pub(crate) fn remove_portal_stream(&self, connection_id: usize, stream_id: u64) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_portal_stream(connection_id, stream_id);
+        }
+    }
+    pub(crate) fn add_listener_processor(&self, info: QuicListenerInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_listener_processor(info);
+        }
+    }
+    pub(crate) fn remove_listener_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_listener_processor(addr);
+        }
+    }
+    pub(crate) fn add_sender_worker(&self, info: QuicSenderInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_sender_worker(info);
+        }
+    }
+    pub(crate) fn remove_sender_worker(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_sender_worker(addr);
+        }
+    }
+    pub(crate) fn add_receiver_processor(&self, info: QuicReceiverInfo) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.add_receiver_processor(info);
+        }
+    }
+    pub(crate) fn remove_receiver_processor(&self, addr: &Address) {
+        if let Ok(mut lock) = self.registry.write() {
+            lock.remove_receiver_processor(addr);
+        }
+    }
+}
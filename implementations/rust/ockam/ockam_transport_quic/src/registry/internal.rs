@@ -0,0 +1,52 @@
+use crate::{QuicListenerInfo, QuicPortalStreamInfo, QuicReceiverInfo, QuicSenderInfo};
+use ockam_core::Address;
+
+#[derive(Default, Debug)]
+pub(super) struct InternalRegistry {
+    pub(super) inlet_listener_processors: Vec<Address>,
+    pub(super) outlet_listener_processors: Vec<Address>,
+    pub(super) portal_streams: Vec<QuicPortalStreamInfo>,
+    pub(super) listener_processors: Vec<QuicListenerInfo>,
+    pub(super) sender_workers: Vec<QuicSenderInfo>,
+    pub(super) receiver_processors: Vec<QuicReceiverInfo>,
+}
+
+impl InternalRegistry {
+    pub(super) fn add_inlet_listener_processor(&mut self, addr: &Address) {
+        self.inlet_listener_processors.push(addr.clone())
+    }
+    pub(super) fn remove_inlet_listener_processor(&mut self, addr: &Address) {
+        self.inlet_listener_processors.retain(|x| x != addr);
+    }
+    pub(super) fn add_outlet_listener_processor(&mut self, addr: &Address) {
+        self.outlet_listener_processors.push(addr.clone())
+    }
+    pub(super) fn remove_outlet_listener_processor(&mut self, addr: &Address) {
+        self.outlet_listener_processors.retain(|x| x != addr);
+    }
+    pub(super) fn add_portal_stream(&mut self, info: QuicPortalStreamInfo) {
+        self.portal_streams.push(info)
+    }
+    pub(super) fn remove_portal_stream(&mut self, connection_id: usize, stream_id: u64) {
+        self.portal_streams
+            .retain(|x| x.connection_id() != connection_id || x.stream_id() != stream_id);
+    }
+    pub(super) fn add_listener_processor(&mut self, info: QuicListenerInfo) {
+        self.listener_processors.push(info)
+    }
+    pub(super) fn remove_listener_processor(&mut self, addr: &Address) {
+        self.listener_processors.retain(|x| x.address() != addr);
+    }
+    pub(super) fn add_sender_worker(&mut self, info: QuicSenderInfo) {
+        self.sender_workers.push(info)
+    }
+    pub(super) fn remove_sender_worker(&mut self, addr: &Address) {
+        self.sender_workers.retain(|x| x.address() != addr);
+    }
+    pub(super) fn add_receiver_processor(&mut self, info: QuicReceiverInfo) {
+        self.receiver_processors.push(info)
+    }
+    pub(super) fn remove_receiver_processor(&mut self, addr: &Address) {
+        self.receiver_processors.retain(|x| x.address() != addr);
+    }
+}
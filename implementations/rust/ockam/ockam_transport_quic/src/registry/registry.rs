@@ -0,0 +1,31 @@
+use crate::registry::internal::InternalRegistry;
+use crate::{QuicListenerInfo, QuicPortalStreamInfo, QuicReceiverInfo, QuicSenderInfo};
+use ockam_core::compat::sync::{Arc, RwLock};
+
+/// Registry of all active workers and processors in QUIC Transport to ease their lifecycle management
+#[derive(Default, Clone, Debug)]
+pub struct QuicRegistry {
+    pub(super) registry: Arc<RwLock<InternalRegistry>>,
+}
+
+impl QuicRegistry {
+    /// Return [`Address`]es of all active sender workers
+    pub fn get_all_sender_workers(&self) -> Vec<QuicSenderInfo> {
+        self.registry.read().unwrap().sender_workers.clone()
+    }
+
+    /// Return [`Address`]es of all active receiver processors
+    pub fn get_all_receiver_processors(&self) -> Vec<QuicReceiverInfo> {
+        self.registry.read().unwrap().receiver_processors.clone()
+    }
+
+    /// Return [`Address`]es of all active sender workers
+    pub fn get_all_listeners(&self) -> Vec<QuicListenerInfo> {
+        self.registry.read().unwrap().listener_processors.clone()
+    }
+
+    /// Return the streams of the QUIC portals, one per forwarded TCP connection
+    pub fn get_all_portal_streams(&self) -> Vec<QuicPortalStreamInfo> {
+        self.registry.read().unwrap().portal_streams.clone()
+    }
+}
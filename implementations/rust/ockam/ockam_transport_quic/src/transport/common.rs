@@ -0,0 +1,43 @@
+use crate::certificate::SERVER_NAME;
+use crate::QuicTransportError;
+use ockam_core::Result;
+use ockam_transport_core::HostnamePort;
+use quinn::{ClientConfig, Connection, Endpoint};
+use std::net::{Ipv4Addr, Ipv6Addr, SocketAddr};
+use tracing::{debug, instrument};
+
+/// Establish a QUIC connection to a peer.
+///
+/// Each outgoing connection has its own client endpoint, which is closed with the connection
+#[instrument(skip_all)]
+pub(crate) async fn connect(to: &HostnamePort, config: ClientConfig) -> Result<Connection> {
+    debug!(addr = %to, "Connecting");
+    let connect_error = |reason: String| QuicTransportError::Connect {
+        peer: to.to_string(),
+        reason,
+    };
+
+    let socket_address = tokio::net::lookup_host(to.to_string())
+        .await
+        .map_err(|e| connect_error(e.to_string()))?
+        .next()
+        .ok_or_else(|| connect_error("the hostname can't be resolved".to_string()))?;
+
+    let bind_address: SocketAddr = if socket_address.is_ipv4() {
+        (Ipv4Addr::UNSPECIFIED, 0).into()
+    } else {
+        (Ipv6Addr::UNSPECIFIED, 0).into()
+    };
+    let mut endpoint =
+        Endpoint::client(bind_address).map_err(|e| QuicTransportError::Endpoint(e.to_string()))?;
+    endpoint.set_default_client_config(config);
+
+    let connection = endpoint
+        .connect(socket_address, SERVER_NAME)
+        .map_err(|e| connect_error(e.to_string()))?
+        .await
+        .map_err(|e| connect_error(e.to_string()))?;
+    debug!(addr = %to, "Connected");
+
+    Ok(connection)
+}
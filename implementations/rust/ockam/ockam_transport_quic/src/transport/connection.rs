@@ -0,0 +1,165 @@
+use crate::certificate::{client_config, ROUTING_ALPN};
+use crate::transport::connect;
+use crate::workers::{Addresses, QuicRecvProcessor, QuicSendWorker};
+use crate::{QuicConnectionMode, QuicConnectionOptions, QuicTransport, QuicTransportError};
+use core::fmt;
+use core::fmt::Formatter;
+use core::str::FromStr;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Address, Result};
+use ockam_node::Context;
+use ockam_transport_core::HostnamePort;
+use std::net::SocketAddr;
+use tracing::debug;
+
+/// Result of [`QuicTransport::connect`] call.
+#[derive(Clone, Debug)]
+pub struct QuicConnection {
+    sender_address: Address,
+    receiver_address: Address,
+    socket_address: SocketAddr,
+    mode: QuicConnectionMode,
+    flow_control_id: FlowControlId,
+}
+
+impl fmt::Display for QuicConnection {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}, Worker: {}, Processor: {}, FlowId: {}",
+            self.socket_address, self.sender_address, self.receiver_address, self.flow_control_id
+        )
+    }
+}
+
+impl From<QuicConnection> for Address {
+    fn from(value: QuicConnection) -> Self {
+        value.sender_address
+    }
+}
+
+impl AsRef<Address> for QuicConnection {
+    fn as_ref(&self) -> &Address {
+        self.sender_address()
+    }
+}
+
+impl QuicConnection {
+    /// Constructor
+    pub fn new(
+        sender_address: Address,
+        receiver_address: Address,
+        socket_address: SocketAddr,
+        mode: QuicConnectionMode,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            sender_address,
+            receiver_address,
+            socket_address,
+            mode,
+            flow_control_id,
+        }
+    }
+    /// Stops the [`QuicConnection`], this method must be called to avoid
+    /// leakage of the connection.
+    /// Simply dropping this object won't close the connection
+    pub fn stop(&self, context: &Context) -> Result<()> {
+        context.stop_address(&self.sender_address)
+    }
+    /// Corresponding [`QuicSendWorker`](super::workers::QuicSendWorker) [`Address`] that can be used
+    /// in a route to send messages to the other side of the QUIC connection
+    pub fn sender_address(&self) -> &Address {
+        &self.sender_address
+    }
+    /// Corresponding [`QuicReceiveProcessor`](super::workers::QuicRecvProcessor) [`Address`]
+    pub fn receiver_address(&self) -> &Address {
+        &self.receiver_address
+    }
+    /// Corresponding [`SocketAddr`]
+    pub fn socket_address(&self) -> &SocketAddr {
+        &self.socket_address
+    }
+    /// Generated fresh random [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+    /// Corresponding [`QuicConnectionMode`]
+    pub fn mode(&self) -> QuicConnectionMode {
+        self.mode
+    }
+}
+
+impl QuicTransport {
+    /// Establish an outgoing QUIC connection.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::{QuicConnectionOptions, QuicListenerOptions, QuicTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx)?;
+    /// quic.listen("127.0.0.1:8000", QuicListenerOptions::new()).await?; // Listen on port 8000
+    /// let connection = quic.connect("127.0.0.1:5000", QuicConnectionOptions::new()).await?; // and connect to port 5000
+    /// # Ok(()) }
+    /// ```
+    pub async fn connect(
+        &self,
+        peer: impl Into<String>,
+        options: QuicConnectionOptions,
+    ) -> Result<QuicConnection> {
+        let peer = HostnamePort::from_str(&peer.into())?;
+        debug!("Connecting to {}", peer.clone());
+
+        // Connections are authenticated by the secure channels running on top of them,
+        // so any server certificate is accepted
+        let connection = connect(&peer, client_config(ROUTING_ALPN, None)?).await?;
+        let socket = connection.remote_address();
+        let (send_stream, recv_stream) = connection
+            .open_bi()
+            .await
+            .map_err(|e| QuicTransportError::Stream(e.to_string()))?;
+
+        let mode = QuicConnectionMode::Outgoing;
+        let addresses = Addresses::generate(mode);
+
+        options.setup_flow_control(self.ctx.flow_controls(), &addresses);
+        let flow_control_id = options.flow_control_id.clone();
+        let receiver_outgoing_access_control =
+            options.create_receiver_outgoing_access_control(self.ctx.flow_controls());
+
+        QuicSendWorker::start(
+            &self.ctx,
+            self.registry.clone(),
+            connection,
+            send_stream,
+            &addresses,
+            mode,
+            &flow_control_id,
+        )?;
+
+        QuicRecvProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            recv_stream,
+            &addresses,
+            socket,
+            mode,
+            &flow_control_id,
+            receiver_outgoing_access_control,
+        )?;
+
+        Ok(QuicConnection::new(
+            addresses.sender_address().clone(),
+            addresses.receiver_address().clone(),
+            socket,
+            mode,
+            flow_control_id,
+        ))
+    }
+
+    /// Interrupt an active QUIC connection given its Sender `Address`
+    pub fn disconnect(&self, address: impl AsRef<Address>) -> Result<()> {
+        self.ctx.stop_address(address.as_ref())
+    }
+}
@@ -0,0 +1,100 @@
+use crate::workers::QuicListenProcessor;
+use crate::{socket_address_to_multiaddr, QuicListenerOptions, QuicTransport};
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{Address, Result};
+use ockam_multiaddr::MultiAddr;
+use ockam_transport_core::parse_socket_addr;
+use std::net::SocketAddr;
+
+/// Result of [`QuicTransport::listen`] call.
+#[derive(Clone, Debug)]
+pub struct QuicListener {
+    processor_address: Address,
+    socket_address: SocketAddr,
+    flow_control_id: FlowControlId,
+}
+
+impl fmt::Display for QuicListener {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}, Processor: {}, FlowId: {}",
+            self.socket_address, self.processor_address, self.flow_control_id
+        )
+    }
+}
+
+impl QuicListener {
+    /// Constructor
+    pub fn new(
+        processor_address: Address,
+        socket_address: SocketAddr,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            processor_address,
+            socket_address,
+            flow_control_id,
+        }
+    }
+    /// Corresponding Worker [`Address`] that can be used to stop the Listener
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+    /// Corresponding [`SocketAddr`]
+    pub fn socket_address(&self) -> &SocketAddr {
+        &self.socket_address
+    }
+    /// Corresponding [`SocketAddr`] in String format
+    pub fn socket_string(&self) -> String {
+        self.socket_address.to_string()
+    }
+    /// Corresponding QUIC [`MultiAddr`], for example `/ip4/127.0.0.1/udp/4000/quic`
+    pub fn multiaddr(&self) -> Result<MultiAddr> {
+        socket_address_to_multiaddr(&self.socket_address)
+    }
+    /// Generated fresh random [`FlowControlId`]
+    pub fn flow_control_id(&self) -> &FlowControlId {
+        &self.flow_control_id
+    }
+}
+
+impl QuicTransport {
+    /// Start listening to incoming connections on an existing transport
+    ///
+    /// Returns the local address that this transport is bound to.
+    ///
+    /// This can be useful, for example, when binding to port 0 to figure out
+    /// which port was actually bound.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::{QuicListenerOptions, QuicTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # async fn test(ctx: Context) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx)?;
+    /// quic.listen("127.0.0.1:8000", QuicListenerOptions::new()).await?;
+    /// # Ok(()) }
+    /// ```
+    pub async fn listen(
+        &self,
+        bind_addr: impl AsRef<str>,
+        options: QuicListenerOptions,
+    ) -> Result<QuicListener> {
+        let flow_control_id = options.flow_control_id.clone();
+        let bind_addr = parse_socket_addr(bind_addr.as_ref())?;
+        // Could be different from the bind_addr, e.g., if binding to port 0
+        let (socket_addr, address) =
+            QuicListenProcessor::start(&self.ctx, self.registry.clone(), bind_addr, options)
+                .await?;
+
+        Ok(QuicListener::new(address, socket_addr, flow_control_id))
+    }
+
+    /// Interrupt an active QUIC listener given its `Address`
+    pub fn stop_listener(&self, address: &Address) -> Result<()> {
+        self.ctx.stop_address(address)
+    }
+}
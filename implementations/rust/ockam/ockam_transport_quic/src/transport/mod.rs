@@ -0,0 +1,65 @@
+pub(crate) mod common;
+mod connection;
+mod lifecycle;
+mod listener;
+mod portals;
+
+pub(crate) use common::*;
+
+pub use connection::*;
+pub use listener::*;
+pub use portals::*;
+
+use crate::QuicRegistry;
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_node::{Context, HasContext};
+
+/// High level management interface for QUIC transports
+///
+/// Be aware that only one `QuicTransport` can exist per node, as it
+/// registers itself as a router for the `QUIC` address type.
+///
+/// To listen for incoming connections use
+/// [`quic.listen()`](crate::QuicTransport::listen).
+///
+/// To establish connections use [`quic.connect()`](crate::QuicTransport::connect).
+/// This step is optional when the route is resolved with
+/// [`Context::resolve_transport_route`], which connects the QUIC addresses of the route.
+///
+/// ```rust
+/// use ockam_transport_quic::{QuicConnectionOptions, QuicListenerOptions, QuicTransport};
+/// # use ockam_node::Context;
+/// # use ockam_core::Result;
+/// # async fn test(ctx: Context) -> Result<()> {
+/// let quic = QuicTransport::create(&ctx)?;
+/// quic.listen("127.0.0.1:8000", QuicListenerOptions::new()).await?; // Listen on UDP port 8000
+/// quic.connect("127.0.0.1:5000", QuicConnectionOptions::new()).await?; // And connect to UDP port 5000
+/// # Ok(()) }
+/// ```
+#[derive(Clone, Debug)]
+pub struct QuicTransport {
+    ctx: Arc<Context>,
+    registry: QuicRegistry,
+}
+
+impl QuicTransport {
+    /// Constructor.
+    pub fn new(ctx: Context) -> Self {
+        Self {
+            ctx: Arc::new(ctx),
+            registry: QuicRegistry::default(),
+        }
+    }
+}
+
+/// This trait adds a `create_quic_transport` method to any struct returning a Context.
+/// This is the case for an ockam::Node, so you can write `node.create_quic_transport()`
+pub trait QuicTransportExtension: HasContext {
+    /// Create a QUIC transport
+    fn create_quic_transport(&self) -> Result<QuicTransport> {
+        QuicTransport::create(self.get_context())
+    }
+}
+
+impl<A: HasContext> QuicTransportExtension for A {}
@@ -0,0 +1,232 @@
+use crate::certificate::{server_config, QuicCertificate, PORTAL_ALPN};
+use crate::portal::{
+    PortalTokens, QuicInletListenProcessor, QuicOutletListenProcessor, QuicOutletWorker,
+};
+use crate::{
+    socket_address_to_multiaddr, QuicInletOptions, QuicOutletOptions, QuicTransport,
+    QuicTransportError,
+};
+use core::fmt;
+use core::fmt::{Debug, Formatter};
+use core::str::FromStr;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::{Address, Result, Route};
+use ockam_multiaddr::MultiAddr;
+use ockam_transport_core::{parse_socket_addr, HostnamePort};
+use quinn::Endpoint;
+use tracing::{debug, instrument};
+
+impl QuicTransport {
+    /// Create a QUIC Inlet that listens for TCP connections on bind_addr and forwards each of
+    /// them on its own stream of a QUIC connection to the outlet listening on `outlet`.
+    /// Pair of corresponding Inlet and Outlet is called Portal.
+    ///
+    /// Before connecting, the inlet must be granted the connection by the outlet worker,
+    /// reached with `outlet_route`. This route usually goes through a secure channel, so that
+    /// the outlet worker can check the identity of the inlet.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::{QuicInletOptions, QuicTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::{route, Address, Result};
+    /// # async fn test(ctx: Context, secure_channel: Address) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx)?;
+    /// let inlet = quic.create_inlet(
+    ///     "127.0.0.1:5000",
+    ///     "127.0.0.1:4000",
+    ///     route![secure_channel, "outlet"],
+    ///     QuicInletOptions::new(),
+    /// ).await?;
+    /// # quic.stop_inlet(inlet.processor_address())?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self, outlet_route, options), fields(address = ? bind_addr.clone().into(), outlet = ? outlet.clone().into()))]
+    pub async fn create_inlet(
+        &self,
+        bind_addr: impl Into<String> + Clone + Debug,
+        outlet: impl Into<String> + Clone + Debug,
+        outlet_route: impl Into<Route>,
+        options: QuicInletOptions,
+    ) -> Result<QuicInlet> {
+        let socket_address = parse_socket_addr(&bind_addr.into())?;
+        let outlet = HostnamePort::from_str(&outlet.into())?;
+        let (socket_address, processor_address) = QuicInletListenProcessor::start(
+            self.ctx.clone(),
+            self.registry.clone(),
+            socket_address,
+            outlet,
+            outlet_route.into(),
+            options,
+        )
+        .await?;
+
+        Ok(QuicInlet::new(processor_address, socket_address))
+    }
+
+    /// Stop the inlet with the given processor address
+    #[instrument(skip(self), fields(address = % address))]
+    pub fn stop_inlet(&self, address: &Address) -> Result<()> {
+        self.ctx.stop_address(address)
+    }
+
+    /// Create a QUIC Outlet that accepts the QUIC connections of inlets on bind_addr and
+    /// forwards each of their streams to a new TCP connection to the peer.
+    /// Pair of corresponding Inlet and Outlet is called Portal.
+    ///
+    /// The inlets are granted their connections by the outlet worker started at `address`,
+    /// which only receives the messages allowed by the flow controls and the incoming access
+    /// control of the options.
+    ///
+    /// ```rust
+    /// use ockam_transport_quic::{QuicOutletOptions, QuicTransport};
+    /// # use ockam_node::Context;
+    /// # use ockam_core::Result;
+    /// # use ockam_core::flow_control::FlowControlId;
+    /// # use ockam_transport_core::HostnamePort;
+    /// # async fn test(ctx: Context, secure_channel_listener: FlowControlId) -> Result<()> {
+    /// let quic = QuicTransport::create(&ctx)?;
+    /// let options = QuicOutletOptions::new().as_consumer(&secure_channel_listener);
+    /// let outlet = quic.create_outlet(
+    ///     "outlet",
+    ///     "127.0.0.1:4000",
+    ///     HostnamePort::new("127.0.0.1", 9000)?,
+    ///     options,
+    /// )?;
+    /// # quic.stop_outlet(outlet.worker_address())?;
+    /// # Ok(()) }
+    /// ```
+    #[instrument(skip(self, options), fields(address = ? bind_addr.clone().into(), peer = peer.to_string()))]
+    pub fn create_outlet(
+        &self,
+        address: impl Into<Address>,
+        bind_addr: impl Into<String> + Clone + Debug,
+        peer: HostnamePort,
+        options: QuicOutletOptions,
+    ) -> Result<QuicOutlet> {
+        let socket_address = parse_socket_addr(&bind_addr.into())?;
+        let certificate = QuicCertificate::generate()?;
+
+        debug!("Binding QUIC outlet to {}", socket_address);
+        let endpoint = Endpoint::server(server_config(PORTAL_ALPN, &certificate)?, socket_address)
+            .map_err(|e| QuicTransportError::Endpoint(e.to_string()))?;
+        let socket_address = endpoint
+            .local_addr()
+            .map_err(|e| QuicTransportError::Endpoint(e.to_string()))?;
+
+        let tokens = PortalTokens::default();
+        let processor_address = QuicOutletListenProcessor::start(
+            &self.ctx,
+            self.registry.clone(),
+            endpoint.clone(),
+            peer,
+            tokens.clone(),
+        )?;
+
+        let worker_address = address.into();
+        QuicOutletWorker::start(
+            &self.ctx,
+            worker_address.clone(),
+            endpoint,
+            certificate.certificate().to_vec(),
+            tokens,
+            options,
+        )?;
+
+        Ok(QuicOutlet::new(
+            worker_address,
+            processor_address,
+            socket_address,
+        ))
+    }
+
+    /// Stop the outlet with the given worker address
+    #[instrument(skip(self), fields(address = % address))]
+    pub fn stop_outlet(&self, address: &Address) -> Result<()> {
+        self.ctx.stop_address(address)
+    }
+}
+
+/// Result of [`QuicTransport::create_inlet`] call.
+#[derive(Clone, Debug)]
+pub struct QuicInlet {
+    processor_address: Address,
+    socket_address: SocketAddr,
+}
+
+impl fmt::Display for QuicInlet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}. Processor address: {}",
+            self.socket_address, self.processor_address
+        )
+    }
+}
+
+impl QuicInlet {
+    /// Constructor
+    pub fn new(processor_address: Address, socket_address: SocketAddr) -> Self {
+        Self {
+            processor_address,
+            socket_address,
+        }
+    }
+    /// Address of the processor, that can be used to stop the Inlet
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+    /// TCP [`SocketAddr`] the Inlet is bound to
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+}
+
+/// Result of [`QuicTransport::create_outlet`] call.
+#[derive(Clone, Debug)]
+pub struct QuicOutlet {
+    worker_address: Address,
+    processor_address: Address,
+    socket_address: SocketAddr,
+}
+
+impl fmt::Display for QuicOutlet {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Socket: {}. Worker address: {}. Processor address: {}",
+            self.socket_address, self.worker_address, self.processor_address
+        )
+    }
+}
+
+impl QuicOutlet {
+    /// Constructor
+    pub fn new(
+        worker_address: Address,
+        processor_address: Address,
+        socket_address: SocketAddr,
+    ) -> Self {
+        Self {
+            worker_address,
+            processor_address,
+            socket_address,
+        }
+    }
+    /// Address of the worker granting the connections of the inlets, that can be used
+    /// to stop the Outlet
+    pub fn worker_address(&self) -> &Address {
+        &self.worker_address
+    }
+    /// Address of the processor accepting the QUIC connections
+    pub fn processor_address(&self) -> &Address {
+        &self.processor_address
+    }
+    /// UDP [`SocketAddr`] the Outlet is bound to
+    pub fn socket_address(&self) -> SocketAddr {
+        self.socket_address
+    }
+    /// Corresponding QUIC [`MultiAddr`], for example `/ip4/127.0.0.1/udp/4000/quic`
+    pub fn multiaddr(&self) -> Result<MultiAddr> {
+        socket_address_to_multiaddr(&self.socket_address)
+    }
+}
@@ -0,0 +1,63 @@
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::{CowBytes, LocalMessage, OpenTelemetryContext, Route};
+
+/// QUIC transport message type.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct QuicTransportMessage<'a> {
+    #[n(0)] pub onward_route: Route,
+    #[n(1)] pub return_route: Route,
+    #[b(2)] pub payload: CowBytes<'a>,
+    #[n(3)] pub tracing_context: Option<String>,
+}
+
+impl<'a> QuicTransportMessage<'a> {
+    /// Constructor.
+    pub fn new(
+        onward_route: Route,
+        return_route: Route,
+        payload: CowBytes<'a>,
+        tracing_context: Option<String>,
+    ) -> Self {
+        Self {
+            onward_route,
+            return_route,
+            payload,
+            tracing_context,
+        }
+    }
+
+    /// Return the tracing context
+    pub fn tracing_context(&self) -> OpenTelemetryContext {
+        match self.tracing_context.as_ref() {
+            Some(tracing_context) => OpenTelemetryContext::from_remote_context(tracing_context),
+            None => OpenTelemetryContext::current(),
+        }
+    }
+}
+
+impl From<QuicTransportMessage<'_>> for LocalMessage {
+    fn from(value: QuicTransportMessage) -> Self {
+        LocalMessage::new()
+            .with_tracing_context(value.tracing_context())
+            .with_onward_route(value.onward_route)
+            .with_return_route(value.return_route)
+            .with_payload(value.payload.into_owned())
+    }
+}
+
+impl From<LocalMessage> for QuicTransportMessage<'_> {
+    fn from(value: LocalMessage) -> Self {
+        // make sure to pass the latest tracing context
+        let tracing_context = LocalMessage::start_new_tracing_context(
+            value.tracing_context.update(),
+            "QuicTransportMessage",
+        );
+        Self::new(
+            value.onward_route,
+            value.return_route,
+            CowBytes::from(value.payload),
+            Some(tracing_context),
+        )
+    }
+}
@@ -0,0 +1,161 @@
+use crate::certificate::{server_config, QuicCertificate, ROUTING_ALPN};
+use crate::workers::{Addresses, QuicRecvProcessor};
+use crate::{
+    QuicConnectionMode, QuicListenerInfo, QuicListenerOptions, QuicRegistry, QuicSendWorker,
+    QuicTransportError,
+};
+use core::time::Duration;
+use ockam_core::{async_trait, compat::net::SocketAddr};
+use ockam_core::{Address, Processor, Result};
+use ockam_node::{Context, ProcessorBuilder, WorkerShutdownPriority};
+use quinn::{ConnectionError, Endpoint};
+use tokio::time::timeout;
+use tracing::{debug, instrument};
+
+/// Max duration of the QUIC handshake of an incoming connection, including the opening of its
+/// stream, so that a peer can't block the listener
+const ACCEPT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A QUIC Listen processor
+///
+/// QUIC listen processors are created by `QuicTransport`
+/// after a call is made to
+/// [`QuicTransport::listen`](crate::QuicTransport::listen).
+pub(crate) struct QuicListenProcessor {
+    registry: QuicRegistry,
+    endpoint: Endpoint,
+    socket_address: SocketAddr,
+    options: QuicListenerOptions,
+}
+
+impl QuicListenProcessor {
+    #[instrument(skip_all, name = "QuicListenProcessor::start")]
+    pub(crate) async fn start(
+        ctx: &Context,
+        registry: QuicRegistry,
+        addr: SocketAddr,
+        options: QuicListenerOptions,
+    ) -> Result<(SocketAddr, Address)> {
+        debug!("Binding QUIC endpoint to {}", addr);
+        // Connections are authenticated by the secure channels running on top of them,
+        // the certificate is only used to set up the QUIC encryption
+        let certificate = QuicCertificate::generate()?;
+        let endpoint = Endpoint::server(server_config(ROUTING_ALPN, &certificate)?, addr)
+            .map_err(|e| QuicTransportError::Endpoint(e.to_string()))?;
+        let saddr = endpoint
+            .local_addr()
+            .map_err(|e| QuicTransportError::Endpoint(e.to_string()))?;
+
+        let address = Address::random_tagged("QuicListenProcessor");
+        options.setup_flow_control_for_listener(ctx.flow_controls(), &address);
+
+        let processor = Self {
+            registry,
+            endpoint,
+            socket_address: saddr,
+            options,
+        };
+
+        ProcessorBuilder::new(processor)
+            .with_address(address.clone())
+            .with_shutdown_priority(WorkerShutdownPriority::Priority5)
+            .start(ctx)?;
+
+        Ok((saddr, address))
+    }
+}
+
+#[async_trait]
+impl Processor for QuicListenProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "QuicListenProcessor::initialize")]
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        self.registry.add_listener_processor(QuicListenerInfo::new(
+            ctx.primary_address().clone(),
+            self.socket_address,
+            self.options.flow_control_id.clone(),
+        ));
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicListenProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry
+            .remove_listener_processor(ctx.primary_address());
+
+        // Refuse new connections. The accepted connections share the endpoint and stay open
+        // until their workers are stopped
+        self.endpoint.set_server_config(None);
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicListenProcessor::process")]
+    async fn process(&mut self, ctx: &mut Self::Context) -> Result<bool> {
+        debug!("Waiting for incoming QUIC connection...");
+
+        let incoming = match self.endpoint.accept().await {
+            Some(incoming) => incoming,
+            None => return Ok(false),
+        };
+
+        let accepted = timeout(ACCEPT_TIMEOUT, async {
+            let connection = incoming.await?;
+            let (send_stream, recv_stream) = connection.accept_bi().await?;
+            Ok::<_, ConnectionError>((connection, send_stream, recv_stream))
+        })
+        .await;
+
+        let (connection, send_stream, recv_stream) = match accepted {
+            Ok(Ok(accepted)) => accepted,
+            Ok(Err(err)) => {
+                debug!("Failed to accept a QUIC connection: {}", err);
+                return Ok(true);
+            }
+            Err(_) => {
+                debug!("Timed out while accepting a QUIC connection");
+                return Ok(true);
+            }
+        };
+        debug!("QUIC connection accepted");
+
+        let mode = QuicConnectionMode::Incoming;
+        let addresses = Addresses::generate(mode);
+
+        let receiver_flow_control_id = self
+            .options
+            .setup_flow_control_for_connection(ctx.flow_controls(), &addresses);
+        let receiver_outgoing_access_control =
+            self.options.create_receiver_outgoing_access_control(
+                ctx.flow_controls(),
+                receiver_flow_control_id.clone(),
+            );
+
+        // Worker to receive messages from the Node and send them over the wire
+        QuicSendWorker::start(
+            ctx,
+            self.registry.clone(),
+            connection.clone(),
+            send_stream,
+            &addresses,
+            mode,
+            &receiver_flow_control_id,
+        )?;
+
+        // Processor to receive messages over the wire and forward them to the node
+        QuicRecvProcessor::start(
+            ctx,
+            self.registry.clone(),
+            recv_stream,
+            &addresses,
+            connection.remote_address(),
+            mode,
+            &receiver_flow_control_id,
+            receiver_outgoing_access_control,
+        )?;
+
+        Ok(true)
+    }
+}
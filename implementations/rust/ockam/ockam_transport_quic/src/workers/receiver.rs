@@ -0,0 +1,257 @@
+use crate::transport_message::QuicTransportMessage;
+use crate::workers::Addresses;
+use crate::{
+    QuicConnectionMode, QuicProtocolVersion, QuicReceiverInfo, QuicRegistry, QuicSendWorkerMsg,
+    MAX_MESSAGE_SIZE, QUIC,
+};
+use core::fmt::Display;
+use ockam_core::compat::net::SocketAddr;
+use ockam_core::compat::sync::Arc;
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{
+    async_trait, AllowOnwardAddress, DenyAll, LocalMessage, Mailbox, Mailboxes,
+    OutgoingAccessControl, SourceAddressLocalInfo,
+};
+use ockam_core::{Processor, Result};
+use ockam_node::{Context, ProcessorBuilder, WorkerShutdownPriority};
+use ockam_transport_core::TransportError;
+use quinn::RecvStream;
+use tokio::io::AsyncReadExt;
+use tracing::{debug, instrument, trace};
+
+/// A QUIC receiving message processor
+///
+/// This half of the worker pair is created for each QUIC connection, and reads the messages
+/// from the QUIC stream of the connection, to relay them into the node message system.
+pub(crate) struct QuicRecvProcessor {
+    registry: QuicRegistry,
+    incoming_buffer: Vec<u8>,
+    recv_stream: RecvStream,
+    socket_address: SocketAddr,
+    addresses: Addresses,
+    mode: QuicConnectionMode,
+    flow_control_id: FlowControlId,
+}
+
+impl QuicRecvProcessor {
+    /// Create a new `QuicRecvProcessor`
+    fn new(
+        registry: QuicRegistry,
+        recv_stream: RecvStream,
+        socket_address: SocketAddr,
+        addresses: Addresses,
+        mode: QuicConnectionMode,
+        flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            registry,
+            incoming_buffer: Vec::new(),
+            recv_stream,
+            socket_address,
+            addresses,
+            mode,
+            flow_control_id,
+        }
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    #[instrument(skip_all, name = "QuicRecvProcessor::start")]
+    pub fn start(
+        ctx: &Context,
+        registry: QuicRegistry,
+        recv_stream: RecvStream,
+        addresses: &Addresses,
+        socket_address: SocketAddr,
+        mode: QuicConnectionMode,
+        flow_control_id: &FlowControlId,
+        receiver_outgoing_access_control: Arc<dyn OutgoingAccessControl>,
+    ) -> Result<()> {
+        let receiver = QuicRecvProcessor::new(
+            registry,
+            recv_stream,
+            socket_address,
+            addresses.clone(),
+            mode,
+            flow_control_id.clone(),
+        );
+
+        let mailbox = Mailbox::new(
+            addresses.receiver_address().clone(),
+            None,
+            Arc::new(DenyAll),
+            receiver_outgoing_access_control,
+        );
+        let internal = Mailbox::new(
+            addresses.receiver_internal_address().clone(),
+            None,
+            Arc::new(DenyAll),
+            Arc::new(AllowOnwardAddress(
+                addresses.sender_internal_address().clone(),
+            )),
+        );
+        ProcessorBuilder::new(receiver)
+            .with_mailboxes(Mailboxes::new(mailbox, vec![internal]))
+            .with_shutdown_priority(WorkerShutdownPriority::Priority1)
+            .start(ctx)?;
+
+        Ok(())
+    }
+
+    async fn notify_sender_stream_dropped(&self, ctx: &Context, msg: impl Display) {
+        debug!(
+            "Connection to peer '{}' was closed; dropping stream. {}",
+            self.socket_address, msg
+        );
+
+        // The sender is already stopped if it closed the connection itself
+        if let Err(err) = ctx
+            .send_from_address(
+                self.addresses.sender_internal_address().clone(),
+                QuicSendWorkerMsg::ConnectionClosed,
+                self.addresses.receiver_internal_address().clone(),
+            )
+            .await
+        {
+            debug!("Failed to notify the sender of the closed connection: {err}");
+        }
+    }
+}
+
+#[async_trait]
+impl Processor for QuicRecvProcessor {
+    type Context = Context;
+
+    #[instrument(skip_all, name = "QuicRecvProcessor::initialize")]
+    async fn initialize(&mut self, ctx: &mut Context) -> Result<()> {
+        self.registry.add_receiver_processor(QuicReceiverInfo::new(
+            ctx.primary_address().clone(),
+            self.addresses.sender_address().clone(),
+            self.socket_address,
+            self.mode,
+            self.flow_control_id.clone(),
+        ));
+
+        let protocol_version = match self.recv_stream.read_u8().await {
+            Ok(p) => p,
+            Err(e) => {
+                self.notify_sender_stream_dropped(ctx, e).await;
+                return Err(TransportError::GenericIo)?;
+            }
+        };
+
+        let _protocol_version = match QuicProtocolVersion::try_from(protocol_version) {
+            Ok(v) => v,
+            Err(err) => {
+                self.notify_sender_stream_dropped(
+                    ctx,
+                    format!(
+                        "Received protocol message is unsupported: {}",
+                        protocol_version
+                    ),
+                )
+                .await;
+
+                return Err(err)?;
+            }
+        };
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicRecvProcessor::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry
+            .remove_receiver_processor(ctx.primary_address());
+
+        Ok(())
+    }
+
+    /// Get the next message from the connection if there are any
+    /// available and forward it to the next hop in the route.
+    #[instrument(skip_all, name = "QuicRecvProcessor::process", fields(worker = %ctx.primary_address()))]
+    async fn process(&mut self, ctx: &mut Context) -> Result<bool> {
+        // Read the message length
+        let len = match self.recv_stream.read_u32().await {
+            Ok(l) => l,
+            Err(e) => {
+                self.notify_sender_stream_dropped(ctx, e).await;
+                return Ok(false);
+            }
+        };
+
+        let len_usize = match usize::try_from(len) {
+            Ok(l) => l,
+            Err(_) => {
+                self.notify_sender_stream_dropped(
+                    ctx,
+                    format!("Received message len doesn't fit usize: {}", len),
+                )
+                .await;
+                return Ok(false);
+            }
+        };
+
+        if len_usize > MAX_MESSAGE_SIZE {
+            self.notify_sender_stream_dropped(
+                ctx,
+                format!(
+                    "Received message is larger than allow: {} > {}",
+                    len_usize, MAX_MESSAGE_SIZE
+                ),
+            )
+            .await;
+            return Ok(false);
+        }
+
+        trace!("Received message header for {} bytes", len);
+
+        // Allocate a buffer of that size
+        self.incoming_buffer.clear();
+        self.incoming_buffer.reserve(len_usize);
+        self.incoming_buffer.resize(len_usize, 0);
+
+        // Then read into the buffer
+        match self.recv_stream.read_exact(&mut self.incoming_buffer).await {
+            Ok(_) => {}
+            Err(e) => {
+                self.notify_sender_stream_dropped(ctx, e).await;
+                return Ok(false);
+            }
+        }
+
+        // Deserialize the message now
+        let transport_message: QuicTransportMessage = match minicbor::decode(&self.incoming_buffer)
+        {
+            Ok(msg) => msg,
+            Err(e) => {
+                self.notify_sender_stream_dropped(ctx, e).await;
+                return Ok(false);
+            }
+        };
+
+        let local_message = LocalMessage::from(transport_message);
+        if !local_message.has_next_on_onward_route() {
+            trace!("Got heartbeat message from: {}", self.socket_address);
+            return Ok(true);
+        }
+
+        // Insert the peer address into the return route so that
+        // reply routing can be properly resolved
+        let mut local_message =
+            local_message.push_front_return_route(self.addresses.sender_address().clone());
+
+        // Record the socket address of the peer, so that the message can be authorized
+        // with its source, even after it is decrypted by a secure channel
+        let source = SourceAddressLocalInfo::new(QUIC, self.socket_address).to_local_info()?;
+        local_message.local_info_mut().push(source);
+
+        trace!("Message onward route: {}", local_message.onward_route());
+        trace!("Message return route: {}", local_message.return_route());
+
+        // Forward the message to the next hop in the route
+        ctx.forward_from_address(local_message, self.addresses.receiver_address().clone())
+            .await?;
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,251 @@
+use crate::transport_message::QuicTransportMessage;
+use crate::workers::Addresses;
+use crate::{
+    QuicConnectionMode, QuicProtocolVersion, QuicRegistry, QuicSenderInfo, MAX_MESSAGE_SIZE,
+};
+use ockam_core::flow_control::FlowControlId;
+use ockam_core::{
+    async_trait,
+    compat::{net::SocketAddr, sync::Arc},
+    AddressMetadata, AllowAll, AllowSourceAddress, DenyAll, LocalMessage,
+};
+use ockam_core::{Any, Decodable, Mailbox, Mailboxes, Message, Result, Routed, Worker};
+use ockam_node::{Context, WorkerBuilder, WorkerShutdownPriority};
+use ockam_transport_core::TransportError;
+use quinn::{Connection, SendStream, VarInt};
+use serde::{Deserialize, Serialize};
+use tracing::{debug, instrument, trace, warn};
+
+#[derive(Serialize, Deserialize, Message, Clone)]
+pub(crate) enum QuicSendWorkerMsg {
+    ConnectionClosed,
+}
+
+/// A QUIC sending message worker
+///
+/// This half of the worker pair is created for each QUIC connection, and listens for messages
+/// from the node message system to write them on the QUIC stream of the connection.
+/// Stopping this worker closes the QUIC connection.
+pub(crate) struct QuicSendWorker {
+    buffer: Vec<u8>,
+    registry: QuicRegistry,
+    connection: Connection,
+    send_stream: SendStream,
+    socket_address: SocketAddr,
+    addresses: Addresses,
+    mode: QuicConnectionMode,
+    receiver_flow_control_id: FlowControlId,
+    rx_should_be_stopped: bool,
+}
+
+impl QuicSendWorker {
+    /// Create a new `QuicSendWorker`
+    fn new(
+        registry: QuicRegistry,
+        connection: Connection,
+        send_stream: SendStream,
+        addresses: Addresses,
+        mode: QuicConnectionMode,
+        receiver_flow_control_id: FlowControlId,
+    ) -> Self {
+        Self {
+            buffer: vec![],
+            registry,
+            socket_address: connection.remote_address(),
+            connection,
+            send_stream,
+            addresses,
+            receiver_flow_control_id,
+            mode,
+            rx_should_be_stopped: true,
+        }
+    }
+}
+
+impl QuicSendWorker {
+    /// Start the sender half of the worker pair managing a QUIC connection
+    #[instrument(skip_all, name = "QuicSendWorker::start")]
+    pub(crate) fn start(
+        ctx: &Context,
+        registry: QuicRegistry,
+        connection: Connection,
+        send_stream: SendStream,
+        addresses: &Addresses,
+        mode: QuicConnectionMode,
+        receiver_flow_control_id: &FlowControlId,
+    ) -> Result<()> {
+        trace!("Creating new QUIC worker pair");
+        let sender_worker = Self::new(
+            registry,
+            connection,
+            send_stream,
+            addresses.clone(),
+            mode,
+            receiver_flow_control_id.clone(),
+        );
+
+        let main_mailbox = Mailbox::new(
+            addresses.sender_address().clone(),
+            Some(AddressMetadata {
+                is_terminal: true,
+                attributes: vec![],
+            }),
+            Arc::new(AllowAll),
+            Arc::new(DenyAll),
+        );
+
+        let internal_mailbox = Mailbox::new(
+            addresses.sender_internal_address().clone(),
+            None,
+            Arc::new(AllowSourceAddress(
+                addresses.receiver_internal_address().clone(),
+            )),
+            Arc::new(DenyAll),
+        );
+
+        WorkerBuilder::new(sender_worker)
+            .with_mailboxes(Mailboxes::new(main_mailbox, vec![internal_mailbox]))
+            .with_shutdown_priority(WorkerShutdownPriority::Priority1)
+            .start(ctx)?;
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicSendWorker::stop")]
+    fn stop(&self, ctx: &Context) -> Result<()> {
+        ctx.stop_address(self.addresses.sender_address())
+    }
+
+    fn serialize_message(&mut self, local_message: LocalMessage) -> Result<()> {
+        // Create a message buffer with prepended length
+        let transport_message = QuicTransportMessage::from(local_message);
+
+        let expected_payload_len = minicbor::len(&transport_message);
+
+        const LENGTH_VALUE_SIZE: usize = 4; // u32
+
+        // This buffer starts from 0 length, and grows when we receive a bigger message.
+        self.buffer.clear();
+        self.buffer
+            .reserve(LENGTH_VALUE_SIZE + expected_payload_len);
+
+        // Let's write zeros instead of actual length, since we don't know the exact size yet.
+        self.buffer.extend_from_slice(&[0u8; LENGTH_VALUE_SIZE]);
+
+        // Append encoded payload
+        minicbor::encode(&transport_message, &mut self.buffer)
+            .map_err(|_| TransportError::Encoding)?;
+
+        let payload_len = self.buffer.len() - LENGTH_VALUE_SIZE;
+
+        if payload_len > MAX_MESSAGE_SIZE {
+            return Err(TransportError::MessageLengthExceeded)?;
+        }
+
+        let payload_len_u32 =
+            u32::try_from(payload_len).map_err(|_| TransportError::MessageLengthExceeded)?;
+
+        // Replace zeros with actual length
+        self.buffer[..LENGTH_VALUE_SIZE].copy_from_slice(&payload_len_u32.to_be_bytes());
+
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Worker for QuicSendWorker {
+    type Context = Context;
+    type Message = Any;
+
+    #[instrument(skip_all, name = "QuicSendWorker::initialize")]
+    async fn initialize(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry.add_sender_worker(QuicSenderInfo::new(
+            self.addresses.sender_address().clone(),
+            self.addresses.receiver_address().clone(),
+            self.socket_address,
+            self.mode,
+            self.receiver_flow_control_id.clone(),
+        ));
+
+        // First thing send our protocol version.
+        // This also makes the stream visible to the peer, which accepts it when receiving data
+        if self
+            .send_stream
+            .write_all(&[QuicProtocolVersion::V1.into()])
+            .await
+            .is_err()
+        {
+            warn!(
+                "Failed to send protocol version to peer {}",
+                self.socket_address
+            );
+            self.stop(ctx)?;
+
+            return Ok(());
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicSendWorker::shutdown")]
+    async fn shutdown(&mut self, ctx: &mut Self::Context) -> Result<()> {
+        self.registry
+            .remove_sender_worker(self.addresses.sender_address());
+
+        self.connection.close(VarInt::from_u32(0), b"closed");
+
+        if self.rx_should_be_stopped {
+            let _ = ctx.stop_address(self.addresses.receiver_address());
+        }
+
+        Ok(())
+    }
+
+    #[instrument(skip_all, name = "QuicSendWorker::handle_message", fields(worker = %ctx.primary_address()))]
+    async fn handle_message(
+        &mut self,
+        ctx: &mut Context,
+        msg: Routed<Self::Message>,
+    ) -> Result<()> {
+        let recipient = msg.msg_addr();
+        if &recipient == self.addresses.sender_internal_address() {
+            let msg = QuicSendWorkerMsg::decode(msg.payload())?;
+
+            match msg {
+                QuicSendWorkerMsg::ConnectionClosed => {
+                    debug!(
+                        "Stopping sender due to closed connection {}",
+                        self.socket_address
+                    );
+                    // No need to stop Receiver as it notified us about connection drop and will
+                    // stop itself
+                    self.rx_should_be_stopped = false;
+                    self.stop(ctx)?;
+
+                    return Ok(());
+                }
+            }
+        } else {
+            let mut local_message = msg.into_local_message();
+            // Remove our own address from the route so the other end
+            // knows what to do with the incoming message
+            local_message = local_message.pop_front_onward_route()?;
+
+            if let Err(err) = self.serialize_message(local_message) {
+                // Close the stream
+                self.stop(ctx)?;
+
+                return Err(err);
+            };
+
+            if self.send_stream.write_all(&self.buffer).await.is_err() {
+                warn!("Failed to send message to peer {}", self.socket_address);
+                self.stop(ctx)?;
+
+                return Ok(());
+            }
+        }
+
+        Ok(())
+    }
+}
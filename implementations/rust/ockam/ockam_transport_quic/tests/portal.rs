@@ -0,0 +1,281 @@
+use ockam_core::compat::sync::Arc;
+use ockam_core::{route, Address, Result};
+use ockam_identity::secure_channels::secure_channels;
+use ockam_identity::{
+    Identifier, IdentityIdAccessControl, SecureChannelListenerOptions, SecureChannelOptions,
+    SecureChannels,
+};
+use ockam_node::Context;
+use ockam_transport_core::HostnamePort;
+use ockam_transport_quic::{
+    QuicConnectionOptions, QuicInlet, QuicInletOptions, QuicListenerOptions, QuicOutletOptions,
+    QuicTransport,
+};
+use std::time::Duration;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::time::timeout;
+
+/// Start a TCP server echoing everything it receives, on each of its connections
+async fn start_echo_server() -> HostnamePort {
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let port = listener.local_addr().unwrap().port();
+
+    tokio::spawn(async move {
+        loop {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            tokio::spawn(async move {
+                let (mut read, mut write) = stream.split();
+                let _ = tokio::io::copy(&mut read, &mut write).await;
+            });
+        }
+    });
+
+    HostnamePort::new("127.0.0.1", port).unwrap()
+}
+
+async fn echo_on(stream: &mut TcpStream, message: &[u8]) -> std::io::Result<Vec<u8>> {
+    stream.write_all(message).await?;
+
+    let mut reply = vec![0u8; message.len()];
+    stream.read_exact(&mut reply).await?;
+
+    Ok(reply)
+}
+
+async fn echo(inlet: &str, message: &[u8]) -> std::io::Result<Vec<u8>> {
+    let mut stream = TcpStream::connect(inlet).await?;
+    echo_on(&mut stream, message).await
+}
+
+/// Start a QUIC listener, a secure channel listener on top of it and a QUIC outlet whose worker
+/// only accepts the messages sent by the `trusted` identity over that secure channel.
+///
+/// Return the socket addresses of the QUIC listener and of the QUIC outlet
+async fn start_outlet(
+    ctx: &Context,
+    quic: &QuicTransport,
+    secure_channels: &Arc<SecureChannels>,
+    trusted: &Identifier,
+) -> Result<(String, String)> {
+    let peer = start_echo_server().await;
+
+    let outlet_identity = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+
+    let listener_options = QuicListenerOptions::new();
+    let secure_channel_options = SecureChannelListenerOptions::new()
+        .as_consumer(&listener_options.spawner_flow_control_id());
+    let secure_channel_flow_control_id = secure_channel_options.spawner_flow_control_id();
+    secure_channels.create_secure_channel_listener(
+        ctx,
+        &outlet_identity,
+        "secure_channel_listener",
+        secure_channel_options,
+    )?;
+
+    let outlet = quic.create_outlet(
+        "outlet",
+        "127.0.0.1:0",
+        peer,
+        QuicOutletOptions::new()
+            .as_consumer(&secure_channel_flow_control_id)
+            .with_incoming_access_control_impl(IdentityIdAccessControl::new(vec![trusted.clone()])),
+    )?;
+
+    let listener = quic.listen("127.0.0.1:0", listener_options).await?;
+    Ok((
+        listener.socket_string(),
+        outlet.socket_address().to_string(),
+    ))
+}
+
+/// Connect to the QUIC listener and return the address of the connection
+async fn connect(quic: &QuicTransport, listener: String) -> Result<Address> {
+    Ok(quic
+        .connect(listener, QuicConnectionOptions::new())
+        .await?
+        .sender_address()
+        .clone())
+}
+
+/// Create an inlet whose connection to the outlet is granted over a secure channel
+/// established with the given identity
+async fn create_inlet(
+    ctx: &Context,
+    quic: &QuicTransport,
+    secure_channels: &Arc<SecureChannels>,
+    identity: &Identifier,
+    listener: String,
+    outlet: String,
+) -> Result<QuicInlet> {
+    let connection = connect(quic, listener).await?;
+    let channel = secure_channels
+        .create_secure_channel(
+            ctx,
+            identity,
+            route![connection, "secure_channel_listener"],
+            SecureChannelOptions::new(),
+        )
+        .await?;
+
+    quic.create_inlet(
+        "127.0.0.1:0",
+        outlet,
+        route![channel, "outlet"],
+        QuicInletOptions::new(),
+    )
+    .await
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn portal__two_concurrent_connections__should_use_separate_streams(
+    ctx: &mut Context,
+) -> Result<()> {
+    let quic = QuicTransport::create(ctx)?;
+    let secure_channels = secure_channels().await?;
+    let inlet_identity = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let (listener, outlet) = start_outlet(ctx, &quic, &secure_channels, &inlet_identity).await?;
+    let inlet = create_inlet(
+        ctx,
+        &quic,
+        &secure_channels,
+        &inlet_identity,
+        listener,
+        outlet,
+    )
+    .await?;
+    let inlet_address = inlet.socket_address().to_string();
+
+    // Both TCP connections stay open while the streams are checked
+    let mut first = TcpStream::connect(&inlet_address).await.unwrap();
+    let mut second = TcpStream::connect(&inlet_address).await.unwrap();
+    let (first_reply, second_reply) = timeout(Duration::from_secs(10), async {
+        tokio::join!(
+            echo_on(&mut first, b"hello from the first connection"),
+            echo_on(&mut second, b"hello from the second connection")
+        )
+    })
+    .await
+    .unwrap();
+    assert_eq!(first_reply.unwrap(), b"hello from the first connection");
+    assert_eq!(second_reply.unwrap(), b"hello from the second connection");
+
+    let inlet_streams: Vec<_> = quic
+        .registry()
+        .get_all_portal_streams()
+        .into_iter()
+        .filter(|s| s.listener_address() == inlet.processor_address())
+        .collect();
+    assert_eq!(inlet_streams.len(), 2);
+    assert_eq!(
+        inlet_streams[0].connection_id(),
+        inlet_streams[1].connection_id()
+    );
+    assert_ne!(inlet_streams[0].stream_id(), inlet_streams[1].stream_id());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn portal__concurrent_connections__should_be_forwarded(ctx: &mut Context) -> Result<()> {
+    let quic = QuicTransport::create(ctx)?;
+    let secure_channels = secure_channels().await?;
+    let inlet_identity = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let (listener, outlet) = start_outlet(ctx, &quic, &secure_channels, &inlet_identity).await?;
+    let inlet = create_inlet(
+        ctx,
+        &quic,
+        &secure_channels,
+        &inlet_identity,
+        listener,
+        outlet,
+    )
+    .await?;
+    let inlet_address = inlet.socket_address().to_string();
+
+    let tasks = (0..5).map(|i| {
+        let inlet_address = inlet_address.clone();
+        tokio::spawn(async move {
+            let message = format!("hello from connection {i}").repeat(100);
+            let reply = echo(&inlet_address, message.as_bytes()).await.unwrap();
+            assert_eq!(reply, message.as_bytes());
+        })
+    });
+    for task in tasks {
+        timeout(Duration::from_secs(10), task)
+            .await
+            .unwrap()
+            .unwrap();
+    }
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn portal__untrusted_identity__should_be_rejected(ctx: &mut Context) -> Result<()> {
+    let quic = QuicTransport::create(ctx)?;
+    let secure_channels = secure_channels().await?;
+    let identities_creation = secure_channels.identities().identities_creation();
+    let trusted = identities_creation.create_identity().await?;
+    let untrusted = identities_creation.create_identity().await?;
+    let (listener, outlet) = start_outlet(ctx, &quic, &secure_channels, &trusted).await?;
+    let inlet = create_inlet(ctx, &quic, &secure_channels, &untrusted, listener, outlet).await?;
+
+    let result = timeout(
+        Duration::from_secs(2),
+        echo(&inlet.socket_address().to_string(), b"hello"),
+    )
+    .await;
+    assert!(!matches!(result, Ok(Ok(_))));
+    assert!(quic.registry().get_all_portal_streams().is_empty());
+
+    Ok(())
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test]
+async fn portal__without_secure_channel__should_be_rejected(ctx: &mut Context) -> Result<()> {
+    let quic = QuicTransport::create(ctx)?;
+    let secure_channels = secure_channels().await?;
+    let inlet_identity = secure_channels
+        .identities()
+        .identities_creation()
+        .create_identity()
+        .await?;
+    let (listener, outlet) = start_outlet(ctx, &quic, &secure_channels, &inlet_identity).await?;
+
+    let connection = connect(&quic, listener).await?;
+    let inlet = quic
+        .create_inlet(
+            "127.0.0.1:0",
+            outlet,
+            route![connection, "outlet"],
+            QuicInletOptions::new(),
+        )
+        .await?;
+
+    let result = timeout(
+        Duration::from_secs(2),
+        echo(&inlet.socket_address().to_string(), b"hello"),
+    )
+    .await;
+    assert!(!matches!(result, Ok(Ok(_))));
+    assert!(quic.registry().get_all_portal_streams().is_empty());
+
+    Ok(())
+}
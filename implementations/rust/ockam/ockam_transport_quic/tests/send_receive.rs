@@ -0,0 +1,66 @@
+use ockam_core::compat::rand::{self, Rng};
+use ockam_core::{route, Result};
+use ockam_node::workers::Echoer;
+use ockam_node::Context;
+use ockam_transport_quic::{
+    multiaddr_to_route, QuicConnectionOptions, QuicListenerOptions, QuicTransport,
+};
+
+fn random_message() -> String {
+    rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(256)
+        .map(char::from)
+        .collect()
+}
+
+#[ockam_macros::test]
+async fn send_receive(ctx: &mut Context) -> Result<()> {
+    let options = QuicListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer(&"echoer".into(), &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer)?;
+
+    let transport = QuicTransport::create(ctx)?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let addr = transport
+        .connect(listener.socket_string(), QuicConnectionOptions::new())
+        .await?
+        .sender_address()
+        .clone();
+
+    for _ in 0..3 {
+        let msg = random_message();
+        let reply = ctx
+            .send_and_receive::<String>(route![addr.clone(), "echoer"], msg.clone())
+            .await?;
+
+        assert_eq!(reply, msg, "Should receive the same message");
+    }
+
+    Ok(())
+}
+
+#[ockam_macros::test]
+async fn send_receive_with_multiaddr(ctx: &mut Context) -> Result<()> {
+    let options = QuicListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer(&"echoer".into(), &options.spawner_flow_control_id());
+    ctx.start_worker("echoer", Echoer)?;
+
+    let transport = QuicTransport::create(ctx)?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let multiaddr = format!("{}/service/echoer", listener.multiaddr()?).parse()?;
+    // The QUIC address of the route is replaced with a new connection
+    let route = ctx
+        .resolve_transport_route(multiaddr_to_route(&multiaddr)?)
+        .await?;
+
+    let msg = random_message();
+    let reply = ctx.send_and_receive::<String>(route, msg.clone()).await?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    Ok(())
+}
@@ -117,6 +117,7 @@ impl Default for TcpInletOptions {
 #[derive(Clone, Debug)]
 pub struct TcpOutletOptions {
     pub(crate) consumer: Vec<FlowControlId>,
+    pub(crate) connection_access_control: Option<Arc<dyn IncomingAccessControl>>,
     pub(crate) incoming_access_control: Arc<dyn IncomingAccessControl>,
     pub(crate) outgoing_access_control: Arc<dyn OutgoingAccessControl>,
     pub(crate) tls: bool,
@@ -128,6 +129,7 @@ impl TcpOutletOptions {
     pub fn new() -> Self {
         Self {
             consumer: vec![],
+            connection_access_control: None,
             incoming_access_control: Arc::new(AllowAll),
             outgoing_access_control: Arc::new(AllowAll),
             tls: false,
@@ -153,6 +155,16 @@ impl TcpOutletOptions {
         self
     }
 
+    /// Set the Incoming Access Control of the Outlet listener, which accepts the requests
+    /// to open new connections. The Incoming Access Control is used if it is not set
+    pub fn with_connection_access_control(
+        mut self,
+        access_control: Arc<dyn IncomingAccessControl>,
+    ) -> Self {
+        self.connection_access_control = Some(access_control);
+        self
+    }
+
     /// Set TLS
     pub fn with_tls(mut self, tls: bool) -> Self {
         self.tls = tls;
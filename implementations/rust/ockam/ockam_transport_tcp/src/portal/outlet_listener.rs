@@ -36,7 +36,10 @@ impl TcpOutletListenWorker {
         hostname_port: HostnamePort,
         options: TcpOutletOptions,
     ) -> Result<()> {
-        let access_control = options.incoming_access_control.clone();
+        let access_control = options
+            .connection_access_control
+            .clone()
+            .unwrap_or_else(|| options.incoming_access_control.clone());
 
         options.setup_flow_control_for_outlet_listener(ctx.flow_controls(), &address);
 
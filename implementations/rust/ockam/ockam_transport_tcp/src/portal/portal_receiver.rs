@@ -2,7 +2,7 @@ use crate::portal::addresses::Addresses;
 use crate::{PortalInternalMessage, PortalMessage, TcpRegistry};
 use ockam_core::compat::vec::Vec;
 use ockam_core::{
-    async_trait, Encodable, LocalMessage, OpenTelemetryContext, Route, OCKAM_TRACER_NAME,
+    async_trait, Encodable, LocalInfo, LocalMessage, OpenTelemetryContext, Route, OCKAM_TRACER_NAME,
 };
 use ockam_core::{route, Processor, Result};
 use ockam_node::Context;
@@ -23,6 +23,7 @@ pub(crate) struct TcpPortalRecvProcessor<R> {
     read_half: R,
     addresses: Addresses,
     onward_route: Route,
+    local_info: Vec<LocalInfo>,
     payload_packet_counter: u16,
     portal_payload_length: usize,
 }
@@ -34,6 +35,7 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
         read_half: R,
         addresses: Addresses,
         onward_route: Route,
+        local_info: Vec<LocalInfo>,
         portal_payload_length: usize,
     ) -> Self {
         Self {
@@ -42,6 +44,7 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> TcpPortalRecvProcessor<R> {
             read_half,
             addresses,
             onward_route,
+            local_info,
             payload_packet_counter: 0,
             portal_payload_length,
         }
@@ -107,7 +110,8 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpPortalRecvPr
                     .with_tracing_context(tracing_context.clone())
                     .with_onward_route(self.onward_route.clone())
                     .with_return_route(route![self.addresses.sender_remote.clone()])
-                    .with_payload(PortalMessage::Disconnect.encode()?),
+                    .with_payload(PortalMessage::Disconnect.encode()?)
+                    .with_local_info(self.local_info.clone()),
                 self.addresses.receiver_remote.clone(),
             )
             .await?;
@@ -123,7 +127,8 @@ impl<R: AsyncRead + Unpin + Send + Sync + 'static> Processor for TcpPortalRecvPr
                 .with_return_route(route![self.addresses.sender_remote.clone()])
                 .with_payload(
                     PortalMessage::Payload(chunk, Some(self.payload_packet_counter)).encode()?,
-                );
+                )
+                .with_local_info(self.local_info.clone());
 
             self.payload_packet_counter += 1;
             ctx.forward_from_address(msg, self.addresses.receiver_remote.clone())
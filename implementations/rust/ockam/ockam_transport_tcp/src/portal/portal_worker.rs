@@ -2,11 +2,14 @@ use crate::portal::addresses::{Addresses, PortalType};
 use crate::portal::portal_worker::ReadHalfMaybeTls::{ReadHalfNoTls, ReadHalfWithTls};
 use crate::portal::portal_worker::WriteHalfMaybeTls::{WriteHalfNoTls, WriteHalfWithTls};
 use crate::transport::{connect, connect_tls};
-use crate::{portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry};
-use ockam_core::compat::{boxed::Box, sync::Arc};
+use crate::{
+    portal::TcpPortalRecvProcessor, PortalInternalMessage, PortalMessage, TcpRegistry, TCP,
+};
+use ockam_core::compat::{boxed::Box, sync::Arc, vec::Vec};
 use ockam_core::{
     async_trait, AllowOnwardAddress, AllowSourceAddress, Decodable, DenyAll, IncomingAccessControl,
-    LocalInfoIdentifier, Mailbox, Mailboxes, OutgoingAccessControl, SecureChannelLocalInfo,
+    LocalInfo, LocalInfoIdentifier, Mailbox, Mailboxes, OutgoingAccessControl,
+    SecureChannelLocalInfo, SourceAddressLocalInfo,
 };
 use ockam_core::{Any, Result, Route, Routed, Worker};
 use ockam_node::{Context, ProcessorBuilder, WorkerBuilder};
@@ -235,6 +238,7 @@ impl TcpPortalWorker {
             rx,
             self.addresses.clone(),
             onward_route,
+            self.source_local_info()?,
             self.portal_payload_length,
         );
 
@@ -259,6 +263,17 @@ impl TcpPortalWorker {
         Ok(())
     }
 
+    /// For an inlet, the address of the TCP client, which is attached to the messages
+    /// sent to the outlet, so that they can be authorized with the address of the client
+    fn source_local_info(&self) -> Result<Vec<LocalInfo>> {
+        match self.portal_type {
+            PortalType::Inlet => {
+                SourceAddressLocalInfo::new(TCP, &self.hostname_port).mark(Vec::new())
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
     #[instrument(skip_all)]
     async fn notify_remote_about_disconnection(&mut self, ctx: &Context) -> Result<()> {
         // Notify the other end
@@ -350,10 +365,11 @@ impl TcpPortalWorker {
     #[instrument(skip_all)]
     async fn handle_send_ping(&self, ctx: &Context, ping_route: Route) -> Result<State> {
         // Force creation of Outlet on the other side
-        ctx.send_from_address(
+        ctx.send_from_address_with_local_info(
             ping_route,
             PortalMessage::Ping.to_neutral_message()?,
             self.addresses.sender_remote.clone(),
+            self.source_local_info()?,
         )
         .await?;
 
@@ -2,7 +2,7 @@ use crate::transport_message::TcpTransportMessage;
 use crate::workers::Addresses;
 use crate::{
     TcpConnectionMode, TcpProtocolVersion, TcpReceiverInfo, TcpRegistry, TcpSendWorkerMsg,
-    MAX_MESSAGE_SIZE,
+    MAX_MESSAGE_SIZE, TCP,
 };
 use core::fmt::Display;
 use ockam_core::compat::net::SocketAddr;
@@ -10,7 +10,7 @@ use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{
     async_trait, AllowOnwardAddress, DenyAll, LocalMessage, Mailbox, Mailboxes,
-    OutgoingAccessControl,
+    OutgoingAccessControl, SourceAddressLocalInfo,
 };
 use ockam_core::{Processor, Result};
 use ockam_node::{Context, ProcessorBuilder, WorkerShutdownPriority};
@@ -243,9 +243,14 @@ impl Processor for TcpRecvProcessor {
 
         // Insert the peer address into the return route so that
         // reply routing can be properly resolved
-        let local_message =
+        let mut local_message =
             local_message.push_front_return_route(self.addresses.sender_address().clone());
 
+        // Record the socket address of the peer, so that the message can be authorized
+        // with its source, even after it is decrypted by a secure channel
+        let source = SourceAddressLocalInfo::new(TCP, self.socket_address).to_local_info()?;
+        local_message.local_info_mut().push(source);
+
         trace!("Message onward route: {}", local_message.onward_route());
         trace!("Message return route: {}", local_message.return_route());
 
@@ -1,10 +1,13 @@
+use std::sync::{Arc, Mutex};
 use std::time::Duration;
 
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::net::{TcpListener, TcpStream};
 
 use ockam_core::compat::rand::random;
-use ockam_core::{route, Result};
+use ockam_core::{
+    async_trait, route, OutgoingAccessControl, RelayMessage, Result, SourceAddressLocalInfo,
+};
 use ockam_node::Context;
 use ockam_transport_tcp::{
     TcpConnectionOptions, TcpInletOptions, TcpListenerOptions, TcpOutletOptions, TcpTransport,
@@ -307,3 +310,70 @@ async fn portal__update_route__should_succeed(ctx: &mut Context) -> Result<()> {
 
     Ok(())
 }
+
+/// Outgoing access control recording the source addresses of the messages sent by an inlet
+#[derive(Debug, Default)]
+struct SourceAddressRecorder {
+    sources: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl OutgoingAccessControl for SourceAddressRecorder {
+    async fn is_authorized(&self, relay_msg: &RelayMessage) -> Result<bool> {
+        if let Ok(source) = SourceAddressLocalInfo::find_info(relay_msg.local_message()) {
+            self.sources
+                .lock()
+                .unwrap()
+                .push(source.address().address().to_string());
+        }
+        Ok(true)
+    }
+}
+
+#[allow(non_snake_case)]
+#[ockam_macros::test(timeout = 5000)]
+async fn portal__inlet_messages__should_have_the_address_of_the_client(
+    ctx: &mut Context,
+) -> Result<()> {
+    let payload = generate_binary();
+
+    let tcp = TcpTransport::create(ctx)?;
+
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let bind_address = listener.local_addr().unwrap().to_string();
+    tcp.create_outlet(
+        "outlet",
+        bind_address.try_into().unwrap(),
+        TcpOutletOptions::new(),
+    )?;
+
+    let recorder = SourceAddressRecorder::default();
+    let sources = recorder.sources.clone();
+    let inlet = tcp
+        .create_inlet(
+            "127.0.0.1:0",
+            route!["outlet"],
+            TcpInletOptions::new().with_outgoing_access_control_impl(recorder),
+        )
+        .await?;
+
+    let handle = tokio::spawn(async move {
+        let (mut stream, _) = listener.accept().await.unwrap();
+        read_assert_binary(&mut stream, payload).await;
+        stream
+    });
+
+    let mut stream = TcpStream::connect(inlet.socket_address()).await.unwrap();
+    let client_address = stream.local_addr().unwrap().to_string();
+    write_binary(&mut stream, payload).await;
+
+    let res = handle.await;
+    assert!(res.is_ok());
+
+    // Both the ping and the payload have the address of the client
+    let sources = sources.lock().unwrap().clone();
+    assert!(sources.len() >= 2);
+    assert!(sources.iter().all(|source| source == &client_address));
+
+    Ok(())
+}
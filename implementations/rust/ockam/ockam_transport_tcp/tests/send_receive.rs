@@ -1,5 +1,8 @@
+use core::str::FromStr;
+use std::net::SocketAddr;
+
 use ockam_core::compat::rand::{self, Rng};
-use ockam_core::{route, Result};
+use ockam_core::{route, Result, Routed, SourceAddressLocalInfo, Worker};
 use ockam_node::workers::Echoer;
 use ockam_node::Context;
 use ockam_transport_tcp::{TcpConnectionOptions, TcpListenerOptions, TcpTransport};
@@ -36,3 +39,47 @@ async fn send_receive(ctx: &mut Context) -> Result<()> {
     };
     Ok(())
 }
+
+struct SourceAddressEchoer;
+
+#[ockam_core::worker]
+impl Worker for SourceAddressEchoer {
+    type Message = String;
+    type Context = Context;
+
+    async fn handle_message(&mut self, ctx: &mut Context, msg: Routed<String>) -> Result<()> {
+        let source = SourceAddressLocalInfo::find_info(msg.local_message())?;
+        ctx.send(
+            msg.return_route().clone(),
+            source.address().address().to_string(),
+        )
+        .await
+    }
+}
+
+#[ockam_macros::test]
+async fn received_messages_have_the_address_of_the_peer(ctx: &mut Context) -> Result<()> {
+    let options = TcpListenerOptions::new();
+    ctx.flow_controls()
+        .add_consumer(&"source_echoer".into(), &options.spawner_flow_control_id());
+    ctx.start_worker("source_echoer", SourceAddressEchoer)?;
+
+    let transport = TcpTransport::create(ctx)?;
+    let listener = transport.listen("127.0.0.1:0", options).await?;
+
+    let addr = transport
+        .connect(listener.socket_string(), TcpConnectionOptions::new())
+        .await?
+        .sender_address()
+        .clone();
+
+    let source: String = ctx
+        .send_and_receive(route![addr, "source_echoer"], "hello".to_string())
+        .await?;
+
+    // the peer is the client side of the connection, on an ephemeral port
+    let source = SocketAddr::from_str(&source).unwrap();
+    assert_eq!(source.ip().to_string(), "127.0.0.1");
+    assert_ne!(&source, listener.socket_address());
+    Ok(())
+}
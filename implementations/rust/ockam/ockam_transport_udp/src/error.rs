@@ -33,6 +33,10 @@ pub enum UdpTransportError {
         routing_number: RoutingNumber,
         data_offset_end: usize,
     },
+    MaxRetransmissionsExceeded {
+        sequence_number: u64,
+        retransmissions: u32,
+    },
 }
 
 impl ockam_core::compat::error::Error for UdpTransportError {}
@@ -100,6 +104,15 @@ impl core::fmt::Display for UdpTransportError {
                     "Message exceeded maximum limit. Routing number: {routing_number}, Data offset end: {data_offset_end}",
                 )
             }
+            Self::MaxRetransmissionsExceeded {
+                sequence_number,
+                retransmissions,
+            } => {
+                write!(
+                    f,
+                    "Packet was not acknowledged after {retransmissions} retransmissions. Sequence number: {sequence_number}",
+                )
+            }
         }
     }
 }
@@ -20,6 +20,7 @@ mod error;
 mod messages;
 mod options;
 mod puncture;
+mod reliable_delivery_options;
 mod size_options;
 mod transport;
 mod workers;
@@ -27,6 +28,7 @@ mod workers;
 pub use error::*;
 pub use options::UdpBindOptions;
 pub use puncture::*;
+pub use reliable_delivery_options::*;
 pub use size_options::*;
 pub use transport::{UdpBind, UdpBindArguments, UdpTransport, UdpTransportExtension};
 
@@ -1,7 +1,9 @@
+mod reliable_message;
 mod routing_message;
 mod routing_number;
 mod transport_message;
 
+pub use reliable_message::*;
 pub use routing_message::*;
 pub use routing_number::*;
 pub use transport_message::*;
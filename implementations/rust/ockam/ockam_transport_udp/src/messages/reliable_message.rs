@@ -0,0 +1,134 @@
+use crate::messages::Version;
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::compat::vec::Vec;
+use ockam_core::{CowBytes, Result};
+
+/// Protocol version of the packets sent in the reliable delivery mode.
+pub const RELIABLE_VERSION: Version = Version(2);
+
+/// Max encoding overhead of a [`UdpReliableMessage`] carrying a [`UdpTransportMessage`].
+/// The payload of the transport messages sent in the reliable delivery mode must be reduced
+/// by this amount to fit in one UDP datagram
+pub const RELIABLE_MESSAGE_OVERHEAD: usize = 24;
+
+/// Max number of packets received out of order which are acknowledged in an [`UdpReliableMessageKind::Ack`]
+pub const MAX_SELECTIVE_ACKS: usize = 32;
+
+/// UDP packet sent in the reliable delivery mode.
+///
+/// Packets are numbered per stream, a stream being all the packets sent by a bind to a peer.
+/// The stream id lets the receiver detect that the sender restarted its numbering.
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub struct UdpReliableMessage<'a> {
+    #[n(0)] pub version: Version,
+    #[n(1)] pub stream_id: u32,
+    #[b(2)] pub kind: UdpReliableMessageKind<'a>,
+}
+
+/// Content of a [`UdpReliableMessage`]
+#[derive(Debug, Clone, Eq, PartialEq, Encode, Decode, CborLen)]
+#[rustfmt::skip]
+pub enum UdpReliableMessageKind<'a> {
+    /// Encoded [`UdpTransportMessage`] with its sequence number in the stream
+    #[n(0)] Data {
+        #[n(0)] sequence_number: u64,
+        #[b(1)] transport_message: CowBytes<'a>,
+    },
+    /// All the packets before `next_expected` were received, as well as the packets with the
+    /// selectively acknowledged sequence numbers
+    #[n(1)] Ack {
+        #[n(0)] next_expected: u64,
+        #[n(1)] selective_acks: Vec<u64>,
+    },
+}
+
+impl<'a> UdpReliableMessage<'a> {
+    /// Constructor.
+    pub fn new(stream_id: u32, kind: UdpReliableMessageKind<'a>) -> Self {
+        Self {
+            version: RELIABLE_VERSION,
+            stream_id,
+            kind,
+        }
+    }
+}
+
+/// Return the protocol version of an encoded [`UdpTransportMessage`] or [`UdpReliableMessage`].
+/// Both messages start with their version
+pub fn decode_version(packet: &[u8]) -> Result<Version> {
+    let mut decoder = minicbor::Decoder::new(packet);
+    decoder.array()?;
+    Ok(Version(decoder.u8()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::messages::{
+        decode_version, RoutingNumber, UdpReliableMessage, UdpReliableMessageKind,
+        UdpTransportMessage, Version, CURRENT_VERSION, MAX_SELECTIVE_ACKS,
+        RELIABLE_MESSAGE_OVERHEAD, RELIABLE_VERSION,
+    };
+    use crate::UdpSizeOptions;
+    use ockam_core::Result;
+
+    #[test]
+    fn test_max_size_data_message() -> Result<()> {
+        let size_options = UdpSizeOptions::default();
+
+        let transport_message = UdpTransportMessage::new(
+            Version(u8::MAX),
+            RoutingNumber(u16::MAX),
+            u16::MAX,
+            u16::MAX,
+            vec![0u8; size_options.max_payload_size_per_packet - RELIABLE_MESSAGE_OVERHEAD],
+        );
+        let msg = UdpReliableMessage::new(
+            u32::MAX,
+            UdpReliableMessageKind::Data {
+                sequence_number: u64::MAX,
+                transport_message: ockam_core::cbor_encode_preallocate(transport_message)?.into(),
+            },
+        );
+
+        let len = ockam_core::cbor_encode_preallocate(msg)?.len();
+
+        assert!(len <= size_options.max_on_the_wire_packet_size);
+        Ok(())
+    }
+
+    #[test]
+    fn test_max_size_ack_message() -> Result<()> {
+        let msg = UdpReliableMessage::new(
+            u32::MAX,
+            UdpReliableMessageKind::Ack {
+                next_expected: u64::MAX,
+                selective_acks: vec![u64::MAX; MAX_SELECTIVE_ACKS],
+            },
+        );
+
+        let len = ockam_core::cbor_encode_preallocate(msg)?.len();
+
+        assert!(len <= UdpSizeOptions::default().max_on_the_wire_packet_size);
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_version() -> Result<()> {
+        let transport_message =
+            UdpTransportMessage::new(CURRENT_VERSION, RoutingNumber(1), 0, 1, vec![1]);
+        let encoded = ockam_core::cbor_encode_preallocate(transport_message)?;
+        assert_eq!(decode_version(&encoded)?, CURRENT_VERSION);
+
+        let reliable_message = UdpReliableMessage::new(
+            1,
+            UdpReliableMessageKind::Ack {
+                next_expected: 0,
+                selective_acks: vec![],
+            },
+        );
+        let encoded = ockam_core::cbor_encode_preallocate(reliable_message)?;
+        assert_eq!(decode_version(&encoded)?, RELIABLE_VERSION);
+        Ok(())
+    }
+}
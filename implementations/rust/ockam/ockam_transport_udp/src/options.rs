@@ -1,5 +1,5 @@
 use crate::workers::Addresses;
-use crate::UdpSizeOptions;
+use crate::{UdpReliableDeliveryOptions, UdpSizeOptions};
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
 use ockam_core::OutgoingAccessControl;
@@ -10,6 +10,7 @@ pub struct UdpBindOptions {
     pub(super) consumer: Vec<FlowControlId>,
     pub(crate) flow_control_id: FlowControlId,
     pub(crate) size_options: UdpSizeOptions,
+    pub(crate) reliable_delivery: Option<UdpReliableDeliveryOptions>,
 }
 
 impl UdpBindOptions {
@@ -20,6 +21,7 @@ impl UdpBindOptions {
             consumer: vec![],
             flow_control_id: FlowControls::generate_flow_control_id(),
             size_options: UdpSizeOptions::read_from_env(),
+            reliable_delivery: None,
         }
     }
 
@@ -30,6 +32,14 @@ impl UdpBindOptions {
         self
     }
 
+    /// Acknowledge and retransmit the packets sent by this bind, and deliver the messages
+    /// in the order they were sent. The peers must support the reliable delivery mode
+    pub fn with_reliable_delivery(mut self, options: UdpReliableDeliveryOptions) -> Self {
+        self.reliable_delivery = Some(options);
+
+        self
+    }
+
     /// Getter for freshly generated [`FlowControlId`]
     pub fn flow_control_id(&self) -> FlowControlId {
         self.flow_control_id.clone()
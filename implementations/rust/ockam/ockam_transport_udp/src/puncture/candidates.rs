@@ -0,0 +1,486 @@
+use crate::puncture::rendezvous_service::RendezvousClient;
+use crate::{PunctureError, UdpBind, UDP};
+use minicbor::{CborLen, Decode, Encode};
+use ockam_core::{Result, Route};
+use ockam_node::Context;
+use std::cmp::Reverse;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr};
+use tokio::net::UdpSocket;
+use tracing::{debug, info, warn};
+
+/// Type of a candidate address of a UDP puncture, see
+/// [RFC 8445](https://datatracker.ietf.org/doc/html/rfc8445#section-5.1.1)
+#[derive(Encode, Decode, CborLen, Clone, Copy, Debug, PartialEq, Eq)]
+#[rustfmt::skip]
+pub enum UdpPunctureCandidateType {
+    /// Address of a local network interface
+    #[n(0)] Host,
+    /// Public address of the NAT in front of the node, as seen by a Rendezvous service
+    #[n(1)] ServerReflexive,
+}
+
+impl UdpPunctureCandidateType {
+    /// Preference of the candidate type, the recommended values of RFC 8445
+    fn preference(&self) -> u32 {
+        match self {
+            UdpPunctureCandidateType::Host => 126,
+            UdpPunctureCandidateType::ServerReflexive => 100,
+        }
+    }
+}
+
+/// Address which may be used by the other side of a UDP puncture to reach us
+#[derive(Encode, Decode, CborLen, Clone, Debug, PartialEq, Eq)]
+#[rustfmt::skip]
+pub struct UdpPunctureCandidate {
+    #[n(0)] candidate_type: UdpPunctureCandidateType,
+    #[n(1)] address: String,
+    #[n(2)] priority: u32,
+}
+
+impl UdpPunctureCandidate {
+    /// Constructor. The priority is computed as recommended by RFC 8445, the local preference
+    /// orders the candidates of the same type
+    pub fn new(
+        candidate_type: UdpPunctureCandidateType,
+        address: impl Into<String>,
+        local_preference: u16,
+    ) -> Self {
+        // There is a single component per puncture
+        let priority =
+            (candidate_type.preference() << 24) + ((local_preference as u32) << 8) + (256 - 1);
+
+        Self {
+            candidate_type,
+            address: address.into(),
+            priority,
+        }
+    }
+
+    /// Type of the candidate
+    pub fn candidate_type(&self) -> UdpPunctureCandidateType {
+        self.candidate_type
+    }
+
+    /// UDP address of the candidate
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    /// Priority of the candidate, the higher the better
+    pub fn priority(&self) -> u32 {
+        self.priority
+    }
+}
+
+/// NAT mapping behaviour, see [RFC 4787](https://datatracker.ietf.org/doc/html/rfc4787#section-4.1)
+#[derive(Encode, Decode, CborLen, Clone, Copy, Debug, PartialEq, Eq)]
+#[rustfmt::skip]
+pub enum UdpNatMapping {
+    /// Less than two Rendezvous services answered, the behaviour can't be determined
+    #[n(0)] Unknown,
+    /// The node is reachable on the address of its network interface
+    #[n(1)] NoNat,
+    /// The NAT reuses the same public address for all the destinations, which allows
+    /// hole punching
+    #[n(2)] EndpointIndependent,
+    /// The NAT allocates a different public address for each destination ("symmetric NAT"),
+    /// hole punching will most likely fail when both sides behave this way
+    #[n(3)] AddressDependent,
+}
+
+/// Candidate addresses of a UDP bind, and the behaviour of the NAT in front of it
+#[derive(Clone, Debug)]
+pub struct UdpPunctureCandidates {
+    candidates: Vec<UdpPunctureCandidate>,
+    public_address: String,
+    nat_mapping: UdpNatMapping,
+}
+
+impl UdpPunctureCandidates {
+    /// Gather the candidates of a UDP bind:
+    ///  - the address of the local network interfaces used to reach the Rendezvous services
+    ///  - the public addresses reported by each Rendezvous service
+    ///
+    /// The NAT behaviour is determined by comparing the public addresses reported by
+    /// Rendezvous services with different UDP addresses.
+    pub async fn gather(
+        ctx: &Context,
+        udp_bind: &UdpBind,
+        rendezvous_routes: &[Route],
+    ) -> Result<Self> {
+        let host_addresses = Self::host_addresses(udp_bind, rendezvous_routes).await;
+
+        // Public address as seen by each distinct Rendezvous service
+        let mut reflexive_addresses: Vec<(String, String)> = vec![];
+        let mut last_error = None;
+        for rendezvous_route in rendezvous_routes {
+            let rendezvous_address = Self::rendezvous_udp_address(rendezvous_route);
+            let client = RendezvousClient::new(udp_bind, rendezvous_route.clone());
+            match client.get_my_address(ctx).await {
+                Ok(address) => {
+                    debug!("Rendezvous service {rendezvous_route} reports the address {address}");
+                    reflexive_addresses.push((rendezvous_address.unwrap_or_default(), address));
+                }
+                Err(err) => {
+                    warn!("Error getting UDP public address from {rendezvous_route}: {err}");
+                    last_error = Some(err);
+                }
+            }
+        }
+
+        let Some((_, public_address)) = reflexive_addresses.first().cloned() else {
+            return Err(last_error.unwrap_or(PunctureError::RendezvousServiceNotFound.into()));
+        };
+
+        let nat_mapping = Self::classify_nat_mapping(&host_addresses, &reflexive_addresses);
+        info!("Detected NAT mapping behaviour: {nat_mapping:?}");
+
+        let mut candidates = vec![];
+        for (index, address) in host_addresses.iter().enumerate() {
+            candidates.push(UdpPunctureCandidate::new(
+                UdpPunctureCandidateType::Host,
+                address.to_string(),
+                local_preference(index),
+            ));
+        }
+        for (_, address) in &reflexive_addresses {
+            if !candidates.iter().any(|c| &c.address == address) {
+                let index = candidates.len();
+                candidates.push(UdpPunctureCandidate::new(
+                    UdpPunctureCandidateType::ServerReflexive,
+                    address.clone(),
+                    local_preference(index),
+                ));
+            }
+        }
+
+        Ok(Self {
+            candidates,
+            public_address,
+            nat_mapping,
+        })
+    }
+
+    /// Candidates, in the order they were gathered
+    pub fn candidates(&self) -> &[UdpPunctureCandidate] {
+        &self.candidates
+    }
+
+    /// NAT mapping behaviour
+    pub fn nat_mapping(&self) -> UdpNatMapping {
+        self.nat_mapping
+    }
+
+    /// Public address reported by the first Rendezvous service which answered
+    pub fn public_address(&self) -> &str {
+        &self.public_address
+    }
+
+    /// Addresses of the peer to try, by decreasing priority. Peers which don't send their
+    /// candidates are only reachable on their public address.
+    ///
+    /// The candidates are sent by the peer, so the addresses which can't be reached are dropped
+    /// and at most [`MAX_PEER_ADDRESSES`] addresses are returned, to bound the number of pings.
+    pub(crate) fn peer_addresses(
+        &self,
+        peer_public_address: String,
+        peer_candidates: Option<Vec<UdpPunctureCandidate>>,
+    ) -> Vec<String> {
+        let mut addresses = match peer_candidates {
+            Some(peer_candidates) => {
+                let peer_candidates: Vec<UdpPunctureCandidate> = peer_candidates
+                    .into_iter()
+                    .filter(|c| is_routable(c.address()))
+                    .take(MAX_PEER_ADDRESSES)
+                    .collect();
+                prioritized_peer_addresses(&self.candidates, &peer_candidates)
+            }
+            None => vec![],
+        };
+
+        if !addresses.contains(&peer_public_address) {
+            addresses.truncate(MAX_PEER_ADDRESSES - 1);
+            addresses.push(peer_public_address);
+        }
+
+        addresses
+    }
+
+    /// Hole punching between two NATs with address-dependent mappings will most likely fail,
+    /// the relay should be used right away in that case
+    pub(crate) fn puncture_unlikely(&self, peer_nat_mapping: Option<UdpNatMapping>) -> bool {
+        self.nat_mapping == UdpNatMapping::AddressDependent
+            && peer_nat_mapping == Some(UdpNatMapping::AddressDependent)
+    }
+
+    /// Addresses of the local network interfaces. If the bind address is unspecified, the
+    /// interface used to reach each Rendezvous service is found by connecting a UDP socket,
+    /// which doesn't send any packet
+    async fn host_addresses(udp_bind: &UdpBind, rendezvous_routes: &[Route]) -> Vec<SocketAddr> {
+        let bind_address = udp_bind.bind_address();
+        if !bind_address.ip().is_unspecified() {
+            return vec![bind_address];
+        }
+
+        let mut addresses = vec![];
+        for rendezvous_route in rendezvous_routes {
+            let Some(rendezvous_address) = Self::rendezvous_udp_address(rendezvous_route) else {
+                continue;
+            };
+            let Ok(resolved) = tokio::net::lookup_host(&rendezvous_address).await else {
+                continue;
+            };
+            for rendezvous_address in resolved {
+                if let Some(ip) = Self::interface_ip(rendezvous_address).await {
+                    let address = SocketAddr::new(ip, bind_address.port());
+                    if !addresses.contains(&address) {
+                        addresses.push(address);
+                    }
+                }
+            }
+        }
+
+        addresses
+    }
+
+    /// IP address of the network interface used to reach the given address
+    async fn interface_ip(destination: SocketAddr) -> Option<IpAddr> {
+        let unspecified: IpAddr = match destination {
+            SocketAddr::V4(_) => Ipv4Addr::UNSPECIFIED.into(),
+            SocketAddr::V6(_) => Ipv6Addr::UNSPECIFIED.into(),
+        };
+        let socket = UdpSocket::bind(SocketAddr::new(unspecified, 0))
+            .await
+            .ok()?;
+        socket.connect(destination).await.ok()?;
+        let ip = socket.local_addr().ok()?.ip();
+
+        (!ip.is_unspecified()).then_some(ip)
+    }
+
+    /// First UDP address of a route to a Rendezvous service
+    fn rendezvous_udp_address(rendezvous_route: &Route) -> Option<String> {
+        rendezvous_route
+            .iter()
+            .find(|a| a.transport_type() == UDP)
+            .map(|a| a.address().to_string())
+    }
+
+    /// Compare the public addresses reported by Rendezvous services with different addresses
+    fn classify_nat_mapping(
+        host_addresses: &[SocketAddr],
+        reflexive_addresses: &[(String, String)],
+    ) -> UdpNatMapping {
+        if reflexive_addresses.iter().any(|(_, reflexive)| {
+            host_addresses
+                .iter()
+                .any(|host| &host.to_string() == reflexive)
+        }) {
+            return UdpNatMapping::NoNat;
+        }
+
+        let mut answers = reflexive_addresses.to_vec();
+        answers.sort();
+        answers.dedup_by(|(rendezvous1, _), (rendezvous2, _)| rendezvous1 == rendezvous2);
+
+        match answers.as_slice() {
+            [] | [_] => UdpNatMapping::Unknown,
+            [(_, first), rest @ ..] => {
+                if rest.iter().all(|(_, address)| address == first) {
+                    UdpNatMapping::EndpointIndependent
+                } else {
+                    UdpNatMapping::AddressDependent
+                }
+            }
+        }
+    }
+}
+
+/// Maximum number of addresses of a peer which are pinged during a puncture
+pub(crate) const MAX_PEER_ADDRESSES: usize = 16;
+
+/// Return true if a candidate address sent by a peer can be used as the destination of a ping
+pub(crate) fn is_routable(address: &str) -> bool {
+    let Ok(address) = address.parse::<SocketAddr>() else {
+        debug!("Dropping the UDP candidate {address}, it is not a socket address");
+        return false;
+    };
+    let ip = address.ip();
+    let routable = address.port() != 0
+        && !ip.is_unspecified()
+        && !ip.is_multicast()
+        && ip != IpAddr::V4(Ipv4Addr::BROADCAST);
+    if !routable {
+        debug!("Dropping the unroutable UDP candidate {address}");
+    }
+    routable
+}
+
+/// Candidates gathered first are preferred
+fn local_preference(index: usize) -> u16 {
+    u16::MAX.saturating_sub(index as u16)
+}
+
+/// Order the addresses of the remote candidates by the priority of the pairs they form with our
+/// candidates, as recommended by RFC 8445 for the controlling agent. All our candidates share
+/// the same UDP bind, so each remote address only appears once, with its best pair.
+pub(crate) fn prioritized_peer_addresses(
+    local: &[UdpPunctureCandidate],
+    remote: &[UdpPunctureCandidate],
+) -> Vec<String> {
+    let pair_priority = |local: u32, remote: u32| -> u64 {
+        let (min, max) = (local.min(remote) as u64, local.max(remote) as u64);
+        (min << 32) + 2 * max + u64::from(local > remote)
+    };
+
+    let mut pairs: Vec<(u64, &str)> = remote
+        .iter()
+        .map(|remote| {
+            let priority = local
+                .iter()
+                .map(|local| pair_priority(local.priority, remote.priority))
+                .max()
+                .unwrap_or(remote.priority as u64);
+            (priority, remote.address())
+        })
+        .collect();
+    pairs.sort_by_key(|(priority, _)| Reverse(*priority));
+
+    let mut addresses: Vec<String> = vec![];
+    for (_, address) in pairs {
+        if !addresses.iter().any(|a| a == address) {
+            addresses.push(address.to_string());
+        }
+    }
+
+    addresses
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn candidate_priority() {
+        let host = UdpPunctureCandidate::new(UdpPunctureCandidateType::Host, "a", u16::MAX);
+        let reflexive =
+            UdpPunctureCandidate::new(UdpPunctureCandidateType::ServerReflexive, "b", u16::MAX);
+        let second_host =
+            UdpPunctureCandidate::new(UdpPunctureCandidateType::Host, "c", u16::MAX - 1);
+
+        assert!(host.priority() > second_host.priority());
+        assert!(second_host.priority() > reflexive.priority());
+    }
+
+    #[test]
+    fn nat_mapping() {
+        let host: SocketAddr = "192.168.1.10:4000".parse().unwrap();
+        let reflexive =
+            |rendezvous: &str, address: &str| (rendezvous.to_string(), address.to_string());
+
+        assert_eq!(
+            UdpPunctureCandidates::classify_nat_mapping(&[host], &[]),
+            UdpNatMapping::Unknown
+        );
+        assert_eq!(
+            UdpPunctureCandidates::classify_nat_mapping(
+                &[host],
+                &[reflexive("r1:1", "192.168.1.10:4000")]
+            ),
+            UdpNatMapping::NoNat
+        );
+        // The same Rendezvous service can't tell the mapping behaviour
+        assert_eq!(
+            UdpPunctureCandidates::classify_nat_mapping(
+                &[host],
+                &[
+                    reflexive("r1:1", "1.1.1.1:5000"),
+                    reflexive("r1:1", "1.1.1.1:5001")
+                ]
+            ),
+            UdpNatMapping::Unknown
+        );
+        assert_eq!(
+            UdpPunctureCandidates::classify_nat_mapping(
+                &[host],
+                &[
+                    reflexive("r1:1", "1.1.1.1:5000"),
+                    reflexive("r2:1", "1.1.1.1:5000")
+                ]
+            ),
+            UdpNatMapping::EndpointIndependent
+        );
+        assert_eq!(
+            UdpPunctureCandidates::classify_nat_mapping(
+                &[host],
+                &[
+                    reflexive("r1:1", "1.1.1.1:5000"),
+                    reflexive("r2:1", "1.1.1.1:5001")
+                ]
+            ),
+            UdpNatMapping::AddressDependent
+        );
+    }
+
+    #[test]
+    fn peer_addresses_are_filtered_and_bounded() {
+        let local = UdpPunctureCandidates {
+            candidates: vec![UdpPunctureCandidate::new(
+                UdpPunctureCandidateType::Host,
+                "10.0.0.1:1",
+                u16::MAX,
+            )],
+            public_address: "1.1.1.1:1".to_string(),
+            nat_mapping: UdpNatMapping::Unknown,
+        };
+        let host = |address: String| {
+            UdpPunctureCandidate::new(UdpPunctureCandidateType::Host, address, u16::MAX)
+        };
+
+        let unroutable = ["not an address", "0.0.0.0:1", "10.0.0.2:0", "224.0.0.1:1"];
+        let mut remote: Vec<UdpPunctureCandidate> =
+            unroutable.iter().map(|a| host(a.to_string())).collect();
+        remote.push(host("10.0.0.2:2".to_string()));
+        remote.push(host("10.0.0.2:2".to_string()));
+        assert_eq!(
+            local.peer_addresses("2.2.2.2:2".to_string(), Some(remote)),
+            vec!["10.0.0.2:2".to_string(), "2.2.2.2:2".to_string()]
+        );
+
+        let remote = (0..100).map(|i| host(format!("10.0.1.{i}:2"))).collect();
+        let addresses = local.peer_addresses("2.2.2.2:2".to_string(), Some(remote));
+        assert_eq!(addresses.len(), MAX_PEER_ADDRESSES);
+        assert_eq!(addresses.last(), Some(&"2.2.2.2:2".to_string()));
+    }
+
+    #[test]
+    fn peer_addresses_order() {
+        let local = vec![
+            UdpPunctureCandidate::new(UdpPunctureCandidateType::Host, "10.0.0.1:1", u16::MAX),
+            UdpPunctureCandidate::new(
+                UdpPunctureCandidateType::ServerReflexive,
+                "1.1.1.1:1",
+                u16::MAX - 1,
+            ),
+        ];
+        let remote = vec![
+            UdpPunctureCandidate::new(
+                UdpPunctureCandidateType::ServerReflexive,
+                "2.2.2.2:2",
+                u16::MAX - 1,
+            ),
+            UdpPunctureCandidate::new(UdpPunctureCandidateType::Host, "10.0.0.2:2", u16::MAX),
+            UdpPunctureCandidate::new(
+                UdpPunctureCandidateType::ServerReflexive,
+                "2.2.2.2:2",
+                u16::MAX - 2,
+            ),
+        ];
+
+        assert_eq!(
+            prioritized_peer_addresses(&local, &remote),
+            vec!["10.0.0.2:2".to_string(), "2.2.2.2:2".to_string()]
+        );
+    }
+}
@@ -1,9 +1,11 @@
+mod candidates;
 mod error;
 mod negotiation;
 #[allow(clippy::module_inception)]
 mod puncture;
 mod rendezvous_service;
 
+pub use candidates::*;
 pub use error::*;
 pub use negotiation::*;
 pub use puncture::*;
@@ -2,8 +2,11 @@ use crate::puncture::negotiation::message::{
     UdpPunctureNegotiationMessageAcknowledge, UdpPunctureNegotiationMessageInitiate,
 };
 use crate::puncture::negotiation::options::UdpPunctureNegotiationListenerOptions;
-use crate::puncture::rendezvous_service::RendezvousClient;
-use crate::{UdpBindArguments, UdpBindOptions, UdpPuncture, UdpPunctureOptions, UdpTransport};
+use crate::{
+    PunctureError, UdpBindArguments, UdpBindOptions, UdpPuncture, UdpPunctureCandidates,
+    UdpPunctureOptions, UdpTransport,
+};
+use core::time::Duration;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{async_trait, Address, AllowAll, DenyAll, Result, Route, Routed, Worker};
 use ockam_node::{Context, WorkerBuilder};
@@ -12,28 +15,34 @@ use tracing::{error, info};
 /// UDP puncture listener
 pub struct UdpPunctureNegotiationListener {
     udp: UdpTransport,
-    rendezvous_route: Route,
+    rendezvous_routes: Vec<Route>,
     flow_control_id: FlowControlId,
 }
 
 impl UdpPunctureNegotiationListener {
-    /// Create and start a new listener on given address
+    /// Create and start a new listener on given address. The candidate addresses of each
+    /// puncture are gathered using all the given Rendezvous services, the first one is also used
+    /// to relay the messages if the puncture can't be opened.
     pub fn create(
         ctx: &Context,
         address: impl Into<Address>,
         udp: &UdpTransport,
-        rendezvous_route: Route,
+        rendezvous_routes: Vec<Route>,
         options: UdpPunctureNegotiationListenerOptions,
     ) -> Result<()> {
         let address = address.into();
 
+        if rendezvous_routes.is_empty() {
+            return Err(PunctureError::RendezvousServiceNotFound)?;
+        }
+
         let access_control = options.incoming_access_control.clone();
 
         options.setup_flow_control_for_listener(ctx.flow_controls(), &address);
 
         let worker = Self {
             udp: udp.clone(),
-            rendezvous_route,
+            rendezvous_routes,
             flow_control_id: options.flow_control_id,
         };
 
@@ -49,7 +58,7 @@ impl UdpPunctureNegotiationListener {
     async fn start_puncture(
         ctx: Context,
         udp: UdpTransport,
-        rendezvous_route: Route,
+        rendezvous_routes: Vec<Route>,
         flow_control_id: FlowControlId,
         msg: UdpPunctureNegotiationMessageInitiate,
         return_route: Route,
@@ -64,22 +73,30 @@ impl UdpPunctureNegotiationListener {
             )
             .await?;
 
-        let client = RendezvousClient::new(&udp_bind, rendezvous_route);
-        let my_udp_public_address = match client.get_my_address(&ctx).await {
-            Ok(my_udp_public_address) => my_udp_public_address,
-            Err(err) => {
-                error!(
-                    "Error getting UDP public address for the responder: {}",
-                    err
-                );
-                udp.unbind(udp_bind.sender_address())?;
-                return Err(err);
-            }
-        };
+        let my_candidates =
+            match UdpPunctureCandidates::gather(&ctx, &udp_bind, &rendezvous_routes).await {
+                Ok(my_candidates) => my_candidates,
+                Err(err) => {
+                    error!("Error gathering UDP candidates for the responder: {}", err);
+                    udp.unbind(udp_bind.sender_address())?;
+                    return Err(err);
+                }
+            };
 
         let initiator_remote_address = Address::from(msg.initiator_remote_address);
 
-        let options = UdpPunctureOptions::new_with_spawner(flow_control_id);
+        let mut options = UdpPunctureOptions::new_with_spawner(flow_control_id);
+        if let Some(relay_allocation_id) = msg.relay_allocation_id {
+            options =
+                options.with_relay_fallback(rendezvous_routes[0].clone(), relay_allocation_id);
+            if my_candidates.puncture_unlikely(msg.initiator_nat_mapping) {
+                info!("Both peers are behind NATs with address-dependent mappings, using the relay right away");
+                options = options.with_relay_fallback_timeout(Duration::ZERO);
+            }
+        }
+
+        let peer_udp_addresses = my_candidates
+            .peer_addresses(msg.initiator_udp_public_address, msg.initiator_candidates);
 
         // Let's start puncture as we received the initiates
         let my_remote_address =
@@ -87,7 +104,7 @@ impl UdpPunctureNegotiationListener {
         UdpPuncture::create(
             &ctx,
             udp_bind,
-            msg.initiator_udp_public_address,
+            peer_udp_addresses,
             my_remote_address.clone(),
             initiator_remote_address,
             options,
@@ -106,8 +123,10 @@ impl UdpPunctureNegotiationListener {
         ctx.send(
             return_route,
             UdpPunctureNegotiationMessageAcknowledge {
-                responder_udp_public_address: my_udp_public_address,
+                responder_udp_public_address: my_candidates.public_address().to_string(),
                 responder_remote_address: my_remote_address.to_vec(),
+                responder_candidates: Some(my_candidates.candidates().to_vec()),
+                responder_nat_mapping: Some(my_candidates.nat_mapping()),
             },
         )
         .await?;
@@ -137,14 +156,14 @@ impl Worker for UdpPunctureNegotiationListener {
             AllowAll,
         )?;
 
-        let rendezvous_route = self.rendezvous_route.clone();
+        let rendezvous_routes = self.rendezvous_routes.clone();
         let udp = self.udp.clone();
         let flow_control_id = self.flow_control_id.clone();
         tokio::spawn(async move {
             Self::start_puncture(
                 child_ctx,
                 udp,
-                rendezvous_route,
+                rendezvous_routes,
                 flow_control_id,
                 msg,
                 return_route,
@@ -1,3 +1,4 @@
+use crate::{UdpNatMapping, UdpPunctureCandidate};
 use minicbor::{CborLen, Decode, Encode};
 use ockam_core::{Decodable, Encodable, Message, Result};
 
@@ -8,6 +9,11 @@ use ockam_core::{Decodable, Encodable, Message, Result};
 pub struct UdpPunctureNegotiationMessageInitiate {
     #[n(0)] pub initiator_udp_public_address: String,
     #[n(1)] pub initiator_remote_address: Vec<u8>,
+    /// Allocation on the Rendezvous service relaying the messages if the puncture can't be opened
+    #[n(2)] pub relay_allocation_id: Option<String>,
+    /// All the addresses the initiator may be reachable at
+    #[n(3)] pub initiator_candidates: Option<Vec<UdpPunctureCandidate>>,
+    #[n(4)] pub initiator_nat_mapping: Option<UdpNatMapping>,
 }
 
 /// UDP Puncture negotiation starts with initiator sending this message
@@ -18,6 +24,9 @@ pub struct UdpPunctureNegotiationMessageInitiate {
 pub struct UdpPunctureNegotiationMessageAcknowledge {
     #[n(0)] pub responder_udp_public_address: String,
     #[n(1)] pub responder_remote_address: Vec<u8>,
+    /// All the addresses the responder may be reachable at
+    #[n(2)] pub responder_candidates: Option<Vec<UdpPunctureCandidate>>,
+    #[n(3)] pub responder_nat_mapping: Option<UdpNatMapping>,
 }
 
 impl Encodable for UdpPunctureNegotiationMessageInitiate {
@@ -1,11 +1,13 @@
 use crate::puncture::negotiation::message::{
     UdpPunctureNegotiationMessageAcknowledge, UdpPunctureNegotiationMessageInitiate,
 };
-use crate::puncture::rendezvous_service::RendezvousClient;
-use crate::{UdpBindArguments, UdpBindOptions, UdpPuncture, UdpPunctureOptions, UdpTransport};
+use crate::{
+    PunctureError, UdpBindArguments, UdpBindOptions, UdpPuncture, UdpPunctureCandidates,
+    UdpPunctureOptions, UdpTransport,
+};
+use core::time::Duration;
 use ockam_core::{Address, AllowAll, Result, Route};
 use ockam_node::{Context, MessageReceiveOptions};
-use std::time::Duration;
 use tracing::{debug, error, info};
 
 /// Allows to negotiate a UDP puncture to the other node by communicating
@@ -15,14 +17,21 @@ pub struct UdpPunctureNegotiation {}
 
 impl UdpPunctureNegotiation {
     /// Start a UDP puncture negotiation and notify whenever puncture is open.
+    ///
+    /// The candidate addresses are gathered using all the given Rendezvous services, the first
+    /// one is also used to relay the messages if the puncture can't be opened.
     pub async fn start_negotiation(
         ctx: &Context,
         onward_route: Route, // Route to the UdpPunctureNegotiationListener
         udp: &UdpTransport,
-        rendezvous_route: Route,
+        rendezvous_routes: Vec<Route>,
         acknowledgment_timeout: Duration,
     ) -> Result<UdpPuncture> {
         let next = onward_route.next()?.clone();
+        let rendezvous_route = rendezvous_routes
+            .first()
+            .cloned()
+            .ok_or(PunctureError::RendezvousServiceNotFound)?;
 
         let address = Address::random_tagged("UdpPunctureNegotiator.initiator");
         let mut child_ctx = ctx.new_detached(address, AllowAll, AllowAll)?;
@@ -51,35 +60,37 @@ impl UdpPunctureNegotiation {
             "Initializing UdpPunctureNegotiation Initiator at {}",
             child_ctx.primary_address()
         );
-        let client = RendezvousClient::new(&udp_bind, rendezvous_route);
-        let my_udp_public_address = match client.get_my_address(ctx).await {
-            Ok(my_udp_public_address) => my_udp_public_address,
-            Err(err) => {
-                error!(
-                    "Error getting UDP public address for the initiator: {}",
-                    err
-                );
-                udp.unbind(udp_bind.sender_address())?;
-                return Err(err);
-            }
-        };
+        let my_candidates =
+            match UdpPunctureCandidates::gather(ctx, &udp_bind, &rendezvous_routes).await {
+                Ok(my_candidates) => my_candidates,
+                Err(err) => {
+                    error!("Error gathering UDP candidates for the initiator: {}", err);
+                    udp.unbind(udp_bind.sender_address())?;
+                    return Err(err);
+                }
+            };
 
         info!(
             "UdpPunctureNegotiation Initiator {} got its public address: {}",
             child_ctx.primary_address(),
-            my_udp_public_address
+            my_candidates.public_address()
         );
 
         // Send Initiate message to the responder, but don't start actual UDP puncture yet,
         // until we receive Acknowledge from them
         let my_remote_address =
             Address::random_tagged("UdpPunctureNegotiationWorker.remote.initiator");
+        // Only the two peers know the allocation id, which authorizes them to use the relay
+        let relay_allocation_id = format!("{:032x}", rand::random::<u128>());
         child_ctx
             .send(
                 onward_route,
                 UdpPunctureNegotiationMessageInitiate {
-                    initiator_udp_public_address: my_udp_public_address,
+                    initiator_udp_public_address: my_candidates.public_address().to_string(),
                     initiator_remote_address: my_remote_address.to_vec(),
+                    relay_allocation_id: Some(relay_allocation_id.clone()),
+                    initiator_candidates: Some(my_candidates.candidates().to_vec()),
+                    initiator_nat_mapping: Some(my_candidates.nat_mapping()),
                 },
             )
             .await?;
@@ -118,12 +129,24 @@ impl UdpPunctureNegotiation {
         };
 
         let options = UdpPunctureOptions::new();
+        // Responders which don't support the relay ignore the allocation id, and never
+        // register to the allocation, so the relay is never open in that case
+        let mut options = options.with_relay_fallback(rendezvous_route, relay_allocation_id);
+        if my_candidates.puncture_unlikely(response.responder_nat_mapping) {
+            info!("Both peers are behind NATs with address-dependent mappings, using the relay right away");
+            options = options.with_relay_fallback_timeout(Duration::ZERO);
+        }
+
+        let peer_udp_addresses = my_candidates.peer_addresses(
+            response.responder_udp_public_address,
+            response.responder_candidates,
+        );
 
         // Start puncture
         let puncture = UdpPuncture::create(
             ctx,
             udp_bind,
-            response.responder_udp_public_address,
+            peer_udp_addresses,
             my_remote_address.clone(),
             Address::from(response.responder_remote_address),
             options,
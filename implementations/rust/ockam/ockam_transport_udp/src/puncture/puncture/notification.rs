@@ -5,17 +5,29 @@ use ockam_core::{Error, Result};
 use tokio::sync::broadcast;
 use tokio::sync::broadcast::error::RecvError;
 
+/// Route to the `UdpPunctureReceiverWorker` of the peer
+#[derive(Clone, Debug)]
+pub enum PeerRoute {
+    /// Messages are sent directly through the puncture
+    Direct(Route),
+    /// Messages are relayed by the Rendezvous service
+    Relayed {
+        rendezvous_route: Route,
+        allocation_id: String,
+    },
+}
+
 /// Type that [`UdpPuncture`] broadcasts
 #[derive(Clone, Debug)]
 pub enum UdpPunctureNotification {
-    Open(Route),
+    Open(PeerRoute),
     Closed,
 }
 
 pub async fn wait_for_puncture(
     receiver: &mut broadcast::Receiver<UdpPunctureNotification>,
     timeout: Duration,
-) -> Result<Route> {
+) -> Result<PeerRoute> {
     tokio::time::timeout(timeout, async move {
         loop {
             match receiver.recv().await {
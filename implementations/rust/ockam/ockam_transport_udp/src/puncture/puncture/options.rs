@@ -1,15 +1,31 @@
 use ockam_core::compat::sync::Arc;
 use ockam_core::flow_control::{FlowControlId, FlowControlOutgoingAccessControl, FlowControls};
-use ockam_core::{Address, OutgoingAccessControl, Result};
+use ockam_core::{Address, OutgoingAccessControl, Result, Route};
 
 use crate::puncture::puncture::Addresses;
+use crate::UdpReliableDeliveryOptions;
 use core::fmt;
 use core::fmt::Formatter;
+use core::time::Duration;
+
+/// How long to wait for the puncture to open before using the relay, by default
+pub(crate) const RELAY_FALLBACK_TIMEOUT: Duration = Duration::from_secs(5);
 
 /// Options for a UDP puncture
 pub struct UdpPunctureOptions {
     pub(crate) flow_control_id: FlowControlId,
     pub(crate) spawner_flow_control_id: Option<FlowControlId>,
+    pub(crate) reliable_delivery: Option<UdpReliableDeliveryOptions>,
+    pub(crate) relay: Option<RelayOptions>,
+}
+
+/// Relayed allocation used when the puncture can't be opened
+#[derive(Clone, Debug)]
+pub(crate) struct RelayOptions {
+    /// Route to the Rendezvous service, relative to the UDP bind of the puncture
+    pub(crate) rendezvous_route: Route,
+    pub(crate) allocation_id: String,
+    pub(crate) fallback_timeout: Duration,
 }
 
 impl fmt::Debug for UdpPunctureOptions {
@@ -25,6 +41,8 @@ impl UdpPunctureOptions {
         Self {
             flow_control_id: FlowControls::generate_flow_control_id(),
             spawner_flow_control_id: None,
+            reliable_delivery: None,
+            relay: None,
         }
     }
 
@@ -35,7 +53,44 @@ impl UdpPunctureOptions {
         Self {
             flow_control_id: FlowControls::generate_flow_control_id(),
             spawner_flow_control_id: Some(spawner_flow_control_id),
+            reliable_delivery: None,
+            relay: None,
+        }
+    }
+
+    /// Acknowledge and retransmit the messages sent to the peer through the puncture, and
+    /// deliver them in the order they were sent
+    pub fn with_reliable_delivery(mut self, options: UdpReliableDeliveryOptions) -> Self {
+        self.reliable_delivery = Some(options);
+        self
+    }
+
+    /// Relay the messages through the Rendezvous service when the puncture can't be opened,
+    /// or when it closes. The direct path is still probed while relaying, and is used again
+    /// as soon as it opens.
+    ///
+    /// Both peers must use the same `allocation_id`, which authorizes them to use the relay and
+    /// should be kept secret.
+    pub fn with_relay_fallback(
+        mut self,
+        rendezvous_route: Route,
+        allocation_id: impl Into<String>,
+    ) -> Self {
+        self.relay = Some(RelayOptions {
+            rendezvous_route,
+            allocation_id: allocation_id.into(),
+            fallback_timeout: RELAY_FALLBACK_TIMEOUT,
+        });
+        self
+    }
+
+    /// How long to wait for the puncture to open before using the relay, 5 seconds by default.
+    /// Has no effect if the relay fallback is not set.
+    pub fn with_relay_fallback_timeout(mut self, timeout: Duration) -> Self {
+        if let Some(relay) = self.relay.as_mut() {
+            relay.fallback_timeout = timeout;
         }
+        self
     }
 
     /// Freshly generated [`FlowControlId`]
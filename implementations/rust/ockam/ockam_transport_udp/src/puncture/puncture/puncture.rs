@@ -6,6 +6,7 @@ use ockam_core::compat::time::Duration;
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, Result};
 use ockam_node::Context;
+use ockam_transport_core::parse_socket_addr;
 use tokio::sync::broadcast;
 
 /// Individual puncture with a specified peer.
@@ -27,7 +28,8 @@ impl UdpPuncture {
     pub(crate) fn create(
         ctx: &Context,
         bind: UdpBind,
-        peer_udp_address: String,
+        // Candidate addresses of the peer, by decreasing priority
+        peer_udp_addresses: Vec<String>,
         my_remote_address: Address,
         their_remote_address: Address,
         options: UdpPunctureOptions,
@@ -39,12 +41,17 @@ impl UdpPuncture {
     ) -> Result<UdpPuncture> {
         let flow_control_id = options.producer_flow_control_id();
 
+        // Fail early, the reliable delivery is enabled once a candidate is selected
+        for peer_udp_address in &peer_udp_addresses {
+            parse_socket_addr(peer_udp_address)?;
+        }
+
         let addresses = Addresses::generate(my_remote_address);
         let (notify_puncture_open_sender, notify_puncture_open_receiver) = broadcast::channel(1);
         UdpPunctureReceiverWorker::create(
             ctx,
             bind,
-            peer_udp_address,
+            peer_udp_addresses,
             their_remote_address,
             addresses.clone(),
             notify_puncture_open_sender,
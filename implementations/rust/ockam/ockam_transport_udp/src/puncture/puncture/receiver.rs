@@ -1,14 +1,17 @@
 use crate::puncture::puncture::message::PunctureMessage;
-use crate::puncture::puncture::notification::UdpPunctureNotification;
+use crate::puncture::puncture::notification::{PeerRoute, UdpPunctureNotification};
 use crate::puncture::puncture::sender::UdpPunctureSenderWorker;
 use crate::puncture::puncture::{Addresses, UdpPunctureOptions};
-use crate::{PunctureError, UdpBind, UDP};
+use crate::puncture::rendezvous_service::RendezvousRequest;
+use crate::puncture::{is_routable, MAX_PEER_ADDRESSES};
+use crate::{PunctureError, UdpBind, UdpReliableDeliveryOptions, UDP};
 use ockam_core::compat::sync::Arc;
 use ockam_core::{
-    route, Address, AllowAll, AllowSourceAddress, Any, Decodable, DenyAll, LocalMessage, Mailbox,
-    Mailboxes, Result, Route, Routed, Worker,
+    route, Address, AllowAll, AllowSourceAddress, Any, Decodable, DenyAll, Encodable, LocalInfo,
+    LocalMessage, Mailbox, Mailboxes, Result, Route, Routed, SourceAddressLocalInfo, Worker,
 };
 use ockam_node::{Context, DelayedEvent, WorkerBuilder};
+use ockam_transport_core::parse_socket_addr;
 use std::time::{Duration, Instant};
 use tokio::sync::broadcast::Sender;
 use tracing::log::warn;
@@ -17,15 +20,46 @@ use tracing::{info, trace};
 const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(1);
 const PUNCTURE_OPEN_TIMEOUT: Duration = Duration::from_secs(10);
 
-// TODO: PUNCTURE Possible future improvement, explicitly send list of possible
-//  reachable addresses (usually local IPs) to Rendezvous service, to allow
-//  opening punctures to local nodes
+/// State of the path relayed by the Rendezvous service
+struct Relay {
+    /// Route to the Rendezvous service
+    rendezvous_route: Route,
+    /// Allocation shared with the peer
+    allocation_id: String,
+    /// How long to wait for the puncture to open before using the relay
+    fallback_timeout: Duration,
+    /// Is the relay used to reach the peer?
+    active: bool,
+    /// Timestamp of most recent pong relayed from peer
+    peer_received_at: Option<Instant>,
+}
+
+impl Relay {
+    fn is_open(&self) -> bool {
+        self.peer_received_at
+            .is_some_and(|received_at| received_at.elapsed() < PUNCTURE_OPEN_TIMEOUT)
+    }
+
+    fn peer_route(&self) -> PeerRoute {
+        PeerRoute::Relayed {
+            rendezvous_route: self.rendezvous_route.clone(),
+            allocation_id: self.allocation_id.clone(),
+        }
+    }
+}
 
 /// [`Worker`] for UDP Puncture
 ///
 /// Constantly sends messages to the other side to keep the "connection" in the
 /// routing tables (heartbeat). Also, responsible for sending payload from the remote
 /// to addresses inside our node.
+///
+/// If a relay is configured and the puncture doesn't open in time, or closes, the peer is
+/// reached through the Rendezvous service instead. The puncture keeps being probed and is
+/// used again as soon as it opens.
+///
+/// Until the puncture opens, all the candidate addresses of the peer are pinged. The first one
+/// to answer is used, and is only replaced by a candidate with a higher priority.
 pub(crate) struct UdpPunctureReceiverWorker {
     /// UDP Bind (Owned, we're responsible for unbinding it eventually)
     bind: UdpBind,
@@ -37,8 +71,12 @@ pub(crate) struct UdpPunctureReceiverWorker {
     puncture_open: bool,
     /// Notify that puncture is open those who wait for it
     notify_puncture_open_sender: Sender<UdpPunctureNotification>,
-    /// Peer's UDP address
+    /// Candidate UDP addresses of the peer, by decreasing priority
+    peer_udp_addresses: Vec<String>,
+    /// Peer's UDP address used to reach it, one of the candidates
     peer_udp_address: String,
+    /// Reliable delivery to enable for the selected candidate
+    reliable_delivery: Option<UdpReliableDeliveryOptions>,
     /// Timestamp of most recent message received from peer
     peer_received_at: Instant,
     /// If we have received the first ping
@@ -50,6 +88,10 @@ pub(crate) struct UdpPunctureReceiverWorker {
     // that `UdpPunctureReceiverWorker` was started on the other side
     // See comments at the point of usage
     redirect_first_message_to_transport: bool,
+    /// Fallback path through the Rendezvous service
+    relay: Option<Relay>,
+    /// When the puncture was created, to decide when to fall back to the relay
+    created_at: Instant,
 }
 
 impl UdpPunctureReceiverWorker {
@@ -57,13 +99,18 @@ impl UdpPunctureReceiverWorker {
     pub(crate) fn create(
         ctx: &Context,
         bind: UdpBind,
-        peer_udp_address: String,
+        peer_udp_addresses: Vec<String>,
         recipient_address: Address,
         addresses: Addresses,
         notify_puncture_open_sender: Sender<UdpPunctureNotification>,
         options: UdpPunctureOptions,
         redirect_first_message_to_transport: bool,
     ) -> Result<()> {
+        let peer_udp_address = peer_udp_addresses
+            .first()
+            .cloned()
+            .ok_or(PunctureError::Internal)?;
+
         let heartbeat = DelayedEvent::create(ctx, addresses.heartbeat_address().clone(), ())?;
 
         let remote_mailbox = Mailbox::new(
@@ -89,6 +136,14 @@ impl UdpPunctureReceiverWorker {
             Arc::new(DenyAll),
         );
 
+        let relay = options.relay.clone().map(|relay| Relay {
+            rendezvous_route: bind.sender_address().clone() + relay.rendezvous_route,
+            allocation_id: relay.allocation_id,
+            fallback_timeout: relay.fallback_timeout,
+            active: false,
+            peer_received_at: None,
+        });
+
         let sender_worker = UdpPunctureSenderWorker::new(notify_puncture_open_sender.subscribe());
 
         WorkerBuilder::new(sender_worker)
@@ -104,11 +159,15 @@ impl UdpPunctureReceiverWorker {
             heartbeat,
             puncture_open: false,
             notify_puncture_open_sender,
+            peer_udp_addresses,
             peer_udp_address,
+            reliable_delivery: options.reliable_delivery,
             peer_received_at: Instant::now(),
             first_ping_received: false,
             recipient_address,
             redirect_first_message_to_transport,
+            relay,
+            created_at: Instant::now(),
         };
 
         WorkerBuilder::new(receiver_worker)
@@ -129,24 +188,154 @@ impl UdpPunctureReceiverWorker {
             info!("Puncture succeeded. Peer address={}", self.peer_udp_address);
         }
 
+        if let Some(relay) = self.relay.as_mut().filter(|relay| relay.active) {
+            info!("Switching from the relay to the puncture");
+            relay.active = false;
+        }
+
         // Even if puncture was already open - let's notify everyone that it's still open
         let _ = self
             .notify_puncture_open_sender
-            .send(UdpPunctureNotification::Open(route![
+            .send(UdpPunctureNotification::Open(PeerRoute::Direct(route![
                 self.bind.sender_address().clone(),
                 Address::new_with_string(UDP, self.peer_udp_address.clone()),
                 self.recipient_address.clone()
-            ]));
+            ])));
 
         Ok(())
     }
 
+    /// Use the given candidate to reach the peer, if the puncture is not open yet or if the
+    /// candidate has a higher priority than the one currently used
+    fn select_peer_address(&mut self, candidate: &str) -> Result<()> {
+        let rank = |address: &str| self.peer_udp_addresses.iter().position(|a| a == address);
+
+        if self.puncture_open && rank(candidate) >= rank(&self.peer_udp_address) {
+            return Ok(());
+        }
+
+        if self.peer_udp_address != candidate {
+            info!(
+                "Using the candidate {} to reach the peer instead of {}",
+                candidate, self.peer_udp_address
+            );
+            // The messages which are not acknowledged by the previous address are dropped,
+            // as for any message lost while the puncture is not open
+            if self.reliable_delivery.is_some() {
+                self.bind
+                    .disable_reliable_delivery(&parse_socket_addr(&self.peer_udp_address)?);
+            }
+            self.peer_udp_address = candidate.to_string();
+        }
+
+        if let Some(reliable_delivery) = self.reliable_delivery {
+            self.bind
+                .enable_reliable_delivery(parse_socket_addr(candidate)?, reliable_delivery);
+        }
+
+        Ok(())
+    }
+
+    /// Update state to show the relay to peer is now open
+    fn set_relay_open(&mut self) {
+        // The puncture is preferred to the relay
+        if self.puncture_open {
+            return;
+        }
+
+        let Some(relay) = self.relay.as_mut() else {
+            return;
+        };
+
+        if !relay.is_open() {
+            info!("Relay succeeded. Peer address={}", self.peer_udp_address);
+        }
+        relay.peer_received_at = Some(Instant::now());
+
+        let _ = self
+            .notify_puncture_open_sender
+            .send(UdpPunctureNotification::Open(relay.peer_route()));
+    }
+
+    /// Is the message relayed by the Rendezvous service? Relayed messages are sent by the
+    /// Rendezvous service worker, while the peer sends its messages from its puncture worker
+    fn is_relayed(&self, return_route: &Route) -> bool {
+        self.relay.as_ref().is_some_and(|relay| {
+            relay.rendezvous_route.recipient().ok() == return_route.recipient().ok()
+        })
+    }
+
+    /// Candidate of the peer the message was received from.
+    ///
+    /// A source which is not a candidate yet is a peer-reflexive address, for example the
+    /// mapping of an address-dependent NAT, which differs from the one observed by the
+    /// Rendezvous service. It is added to the candidates with the lowest priority
+    fn peer_candidate(&mut self, return_route: &Route) -> Option<String> {
+        let source = return_route
+            .iter()
+            .find(|address| address.transport_type() == UDP)?
+            .address()
+            .to_string();
+
+        if !self.peer_udp_addresses.contains(&source) {
+            if self.peer_udp_addresses.len() >= MAX_PEER_ADDRESSES || !is_routable(&source) {
+                return None;
+            }
+            info!("Adding the peer-reflexive candidate {}", source);
+            self.peer_udp_addresses.push(source.clone());
+        }
+
+        Some(source)
+    }
+
+    /// Register to the relayed allocation, or refresh the registration
+    async fn send_allocate(&self, ctx: &Context) -> Result<()> {
+        let Some(relay) = &self.relay else {
+            return Ok(());
+        };
+
+        ctx.send_from_address(
+            relay.rendezvous_route.clone(),
+            RendezvousRequest::Allocate(relay.allocation_id.clone()),
+            self.addresses.remote_address().clone(),
+        )
+        .await
+    }
+
+    /// Send a message to the peer through the relay
+    async fn send_relayed(&self, ctx: &Context, msg: PunctureMessage) -> Result<()> {
+        let Some(relay) = &self.relay else {
+            return Ok(());
+        };
+
+        ctx.send_from_address(
+            relay.rendezvous_route.clone(),
+            RendezvousRequest::Relay {
+                allocation_id: relay.allocation_id.clone(),
+                payload: msg.encode()?,
+            },
+            self.addresses.remote_address().clone(),
+        )
+        .await
+    }
+
+    /// Notify that the puncture is closed and shut everything down
+    fn close(&mut self, ctx: &Context) -> Result<()> {
+        _ = self
+            .notify_puncture_open_sender
+            .send(UdpPunctureNotification::Closed);
+
+        // Shut down itself
+        ctx.stop_address(self.addresses.remote_address())
+    }
+
     /// Handle messages from peer
     async fn handle_peer(
         &mut self,
         ctx: &mut Context,
         payload: Vec<u8>,
         return_route: &Route,
+        local_info: &[LocalInfo],
     ) -> Result<()> {
         let msg = PunctureMessage::decode(&payload)?;
         trace!("Puncture remote message: {:?}", msg);
@@ -155,10 +344,22 @@ impl UdpPunctureReceiverWorker {
         // Ping message doesn't guarantee that the other side is reachable
         let now = Instant::now();
 
+        let relayed = self.is_relayed(return_route);
+
         // Handle message
         match msg {
+            PunctureMessage::Ping if relayed => {
+                self.first_ping_received = true;
+                trace!("Received relayed Ping from peer. Will Pong through the relay.");
+                // The peer may fall back to the relay before us, make sure it can reach us
+                self.send_allocate(ctx).await?;
+                self.send_relayed(ctx, PunctureMessage::Pong).await?;
+            }
             PunctureMessage::Ping => {
                 self.first_ping_received = true;
+                // The peer may only be reachable at the address it pings from, which is then
+                // pinged with the other candidates
+                _ = self.peer_candidate(return_route);
                 trace!("Received Ping from peer. Will Pong.");
                 ctx.send_from_address(
                     return_route.clone(),
@@ -167,8 +368,21 @@ impl UdpPunctureReceiverWorker {
                 )
                 .await?;
             }
+            PunctureMessage::Pong if relayed => {
+                trace!("Received relayed Pong from peer. Setting as relay is open");
+                self.set_relay_open();
+            }
             PunctureMessage::Pong => {
+                let Some(candidate) = self.peer_candidate(return_route) else {
+                    trace!("Received Pong from an unusable address. Ignoring it");
+                    return Ok(());
+                };
                 trace!("Received Pong from peer. Setting as puncture is open");
+                self.select_peer_address(&candidate)?;
+                // Late pongs of the candidates which are not used don't keep the puncture open
+                if candidate != self.peer_udp_address {
+                    return Ok(());
+                }
                 self.peer_received_at = now;
                 self.set_puncture_open().await?;
             }
@@ -182,11 +396,16 @@ impl UdpPunctureReceiverWorker {
                 let return_route = self.addresses.sender_address().clone() + return_route;
 
                 // Update routing & payload
-                let local_message = LocalMessage::new()
+                let mut local_message = LocalMessage::new()
                     .with_onward_route(onward_route)
                     .with_return_route(return_route)
                     .with_payload(payload);
 
+                // Keep the UDP address the payload was received from
+                if let Ok(source) = SourceAddressLocalInfo::find_info_from_list(local_info) {
+                    local_message = local_message.with_local_info(source.mark(vec![])?);
+                }
+
                 // Forward
                 ctx.forward_from_address(local_message, self.addresses.receiver_address().clone())
                     .await?;
@@ -206,43 +425,82 @@ impl UdpPunctureReceiverWorker {
 
         // If we have not heard from peer for a while, consider puncture as closed
         if self.puncture_open && self.peer_received_at.elapsed() >= PUNCTURE_OPEN_TIMEOUT {
-            warn!("Haven't received pongs from the peer for more than {:?}. Shutting down the puncture.", PUNCTURE_OPEN_TIMEOUT);
+            self.puncture_open = false;
+
+            match self.relay.as_mut() {
+                Some(relay) => {
+                    warn!("Haven't received pongs from the peer for more than {:?}. Falling back to the relay.", PUNCTURE_OPEN_TIMEOUT);
+                    relay.active = true;
+                    relay.peer_received_at = None;
+                }
+                None => {
+                    warn!("Haven't received pongs from the peer for more than {:?}. Shutting down the puncture.", PUNCTURE_OPEN_TIMEOUT);
+                    return self.close(ctx);
+                }
+            }
+        }
 
-            _ = self
-                .notify_puncture_open_sender
-                .send(UdpPunctureNotification::Closed);
+        if let Some(relay) = self.relay.as_mut() {
+            // The relay was used, but we have not heard from peer for a while either
+            if relay.active && relay.peer_received_at.is_some() && !relay.is_open() {
+                warn!("Haven't received pongs from the peer through the relay for more than {:?}. Shutting down the puncture.", PUNCTURE_OPEN_TIMEOUT);
+                return self.close(ctx);
+            }
 
-            // Shut down itself
-            ctx.stop_address(self.addresses.remote_address())?;
+            if !self.puncture_open
+                && !relay.active
+                && self.created_at.elapsed() >= relay.fallback_timeout
+            {
+                info!(
+                    "Puncture to {:?} didn't open in {:?}. Falling back to the relay.",
+                    self.peer_udp_addresses, relay.fallback_timeout
+                );
+                relay.active = true;
+            }
+        }
 
-            return Ok(());
+        if self.relay.as_ref().is_some_and(|relay| relay.active) {
+            trace!("Pinging peer through the relay");
+            // Also keeps the allocation alive
+            self.send_allocate(ctx).await?;
+            self.send_relayed(ctx, PunctureMessage::Ping).await?;
         }
 
-        // Do keepalive pings to try and keep the puncture open
+        // Do keepalive pings to try and keep the puncture open. While relaying, this probes
+        // for the puncture to open
         trace!("Pinging peer for keepalive");
 
-        // Will send messages to the UDP transport worker instead of the `UdpPunctureReceiverWorker`
-        // on the other side, until we receive the first ping, which guarantees
-        // that `UdpPunctureReceiverWorker` was started on the other side
-        let route = if !self.first_ping_received && self.redirect_first_message_to_transport {
-            route![
-                self.bind.sender_address().clone(),
-                Address::new_with_string(UDP, self.peer_udp_address.clone())
-            ]
+        // Until the puncture is open, we don't know which candidate can reach the peer
+        let peer_udp_addresses = if self.puncture_open {
+            vec![self.peer_udp_address.clone()]
         } else {
-            route![
-                self.bind.sender_address().clone(),
-                Address::new_with_string(UDP, self.peer_udp_address.clone()),
-                self.recipient_address.clone()
-            ]
+            self.peer_udp_addresses.clone()
         };
 
-        ctx.send_from_address(
-            route,
-            PunctureMessage::Ping,
-            self.addresses.remote_address().clone(),
-        )
-        .await?;
+        for peer_udp_address in peer_udp_addresses {
+            // Will send messages to the UDP transport worker instead of the `UdpPunctureReceiverWorker`
+            // on the other side, until we receive the first ping, which guarantees
+            // that `UdpPunctureReceiverWorker` was started on the other side
+            let route = if !self.first_ping_received && self.redirect_first_message_to_transport {
+                route![
+                    self.bind.sender_address().clone(),
+                    Address::new_with_string(UDP, peer_udp_address)
+                ]
+            } else {
+                route![
+                    self.bind.sender_address().clone(),
+                    Address::new_with_string(UDP, peer_udp_address),
+                    self.recipient_address.clone()
+                ]
+            };
+
+            ctx.send_from_address(
+                route,
+                PunctureMessage::Ping,
+                self.addresses.remote_address().clone(),
+            )
+            .await?;
+        }
 
         Ok(())
     }
@@ -286,7 +544,8 @@ impl Worker for UdpPunctureReceiverWorker {
         if &addr == self.addresses.remote_address() {
             let msg = msg.into_local_message();
             let return_route = msg.return_route;
-            self.handle_peer(ctx, msg.payload, &return_route).await?;
+            self.handle_peer(ctx, msg.payload, &return_route, &msg.local_info)
+                .await?;
         } else if &addr == self.addresses.heartbeat_address() {
             self.handle_heartbeat(ctx).await?;
         } else {
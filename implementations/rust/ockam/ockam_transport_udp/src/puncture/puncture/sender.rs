@@ -1,16 +1,23 @@
 use crate::puncture::puncture::message::PunctureMessage;
-use crate::puncture::puncture::notification::{wait_for_puncture, UdpPunctureNotification};
+use crate::puncture::puncture::notification::{
+    wait_for_puncture, PeerRoute, UdpPunctureNotification,
+};
+use crate::puncture::rendezvous_service::RendezvousRequest;
 use crate::PunctureError;
-use ockam_core::{Any, Encodable, LocalMessage, Result, Route, Routed, Worker};
+use ockam_core::{Any, Encodable, LocalMessage, Result, Routed, Worker};
 use ockam_node::Context;
 use std::time::Duration;
+use tokio::sync::broadcast::error::TryRecvError;
 use tokio::sync::broadcast::Receiver;
-use tracing::trace;
+use tracing::{debug, trace};
 
 /// Worker that forwards messages from our node to the other side of the puncture.
+///
+/// Messages are sent directly when the puncture is open, or relayed by the Rendezvous service
+/// otherwise, as notified by the `UdpPunctureReceiverWorker`.
 pub(crate) struct UdpPunctureSenderWorker {
     notify_puncture_open_receiver: Receiver<UdpPunctureNotification>,
-    peer_route: Option<Route>,
+    peer_route: Option<PeerRoute>,
 }
 
 impl UdpPunctureSenderWorker {
@@ -21,9 +28,31 @@ impl UdpPunctureSenderWorker {
         }
     }
 
+    /// Switch to the latest path notified by the receiver
+    fn update_peer_route(&mut self) {
+        loop {
+            match self.notify_puncture_open_receiver.try_recv() {
+                Ok(UdpPunctureNotification::Open(peer_route)) => {
+                    if let (Some(PeerRoute::Direct(_)), PeerRoute::Relayed { .. })
+                    | (Some(PeerRoute::Relayed { .. }), PeerRoute::Direct(_)) =
+                        (&self.peer_route, &peer_route)
+                    {
+                        debug!("UDP puncture switched to {:?}", peer_route);
+                    }
+                    self.peer_route = Some(peer_route);
+                }
+                // The receiver stops this worker when the puncture is closed
+                Ok(UdpPunctureNotification::Closed) | Err(TryRecvError::Lagged(_)) => continue,
+                Err(TryRecvError::Empty) | Err(TryRecvError::Closed) => break,
+            }
+        }
+    }
+
     async fn handle_local(&mut self, ctx: &mut Context, msg: Routed<Any>) -> Result<()> {
         trace!("UDP puncture forward: Local => Remote: {:?}", msg);
 
+        self.update_peer_route();
+
         let peer_route = self
             .peer_route
             .clone()
@@ -41,9 +70,23 @@ impl UdpPunctureSenderWorker {
             payload: msg.payload,
         };
 
-        let msg = LocalMessage::new()
-            .with_onward_route(peer_route)
-            .with_payload(wrapped_payload.encode()?);
+        let msg = match peer_route {
+            PeerRoute::Direct(peer_route) => LocalMessage::new()
+                .with_onward_route(peer_route)
+                .with_payload(wrapped_payload.encode()?),
+            PeerRoute::Relayed {
+                rendezvous_route,
+                allocation_id,
+            } => LocalMessage::new()
+                .with_onward_route(rendezvous_route)
+                .with_payload(
+                    RendezvousRequest::Relay {
+                        allocation_id,
+                        payload: wrapped_payload.encode()?,
+                    }
+                    .encode()?,
+                ),
+        };
 
         // Forward
         ctx.forward(msg).await
@@ -9,6 +9,15 @@ pub enum RendezvousRequest {
     #[n(0)] Ping,
     /// Get my public IP and port
     #[n(1)] GetMyAddress,
+    /// Register to the relayed allocation with the given id, or refresh the registration.
+    /// The first two peers registering to an allocation can relay datagrams to each other
+    /// through the service. No response is sent.
+    #[n(2)] Allocate(#[n(0)] String),
+    /// Relay the payload to the other peer of an allocation. No response is sent.
+    #[n(3)] Relay {
+        #[n(0)] allocation_id: String,
+        #[n(1)] payload: Vec<u8>,
+    },
 }
 
 impl Encodable for RendezvousRequest {
@@ -2,14 +2,26 @@ use crate::{
     puncture::rendezvous_service::{RendezvousRequest, RendezvousResponse},
     UDP,
 };
-use ockam_core::{async_trait, Address, Result, Route, Routed, Worker};
+use ockam_core::{async_trait, route, Address, LocalMessage, Result, Route, Routed, Worker};
 use ockam_node::Context;
-use tracing::{debug, info, warn};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{debug, info, trace, warn};
+
+/// Allocations which are not refreshed by their peers for this long are dropped
+const ALLOCATION_LIFETIME: Duration = Duration::from_secs(30);
+/// Max number of relayed allocations handled at the same time
+const MAX_ALLOCATIONS: usize = 1024;
 
 /// Rendezvous Service allows other nodes to discover their public IP address and port via UDP.
 ///
 /// The Rendezvous service is a part of UDP NAT Hole Punching (see [Wikipedia](https://en.wikipedia.org/wiki/UDP_hole_punching)).
 ///
+/// When a puncture can't be opened, e.g. when both peers are behind symmetric NATs, the peers
+/// can fall back to a relayed allocation: the service forwards the datagrams between the two
+/// peers which registered to the same allocation id. The allocation id is exchanged by the
+/// peers during the puncture negotiation and is only known to them.
+///
 /// # Example
 ///
 /// ```rust
@@ -34,12 +46,24 @@ impl RendezvousService {
     }
 }
 
+/// Peers of a relayed allocation
+struct Allocation {
+    /// UDP address and route to the puncture worker of each peer, at most 2
+    peers: Vec<(String, Route)>,
+    /// Last time a peer registered to the allocation
+    refreshed_at: Instant,
+}
+
 /// Worker for the UDP Puncture Rendezvous service
-struct RendezvousServiceWorker {}
+struct RendezvousServiceWorker {
+    allocations: HashMap<String, Allocation>,
+}
 
 impl RendezvousServiceWorker {
     fn new() -> Self {
-        Self {}
+        Self {
+            allocations: HashMap::new(),
+        }
     }
 
     /// Extract from `return route` everything just before we received the
@@ -69,6 +93,71 @@ impl RendezvousServiceWorker {
     fn handle_get_my_address(&mut self, return_route: &Route) -> Option<String> {
         Self::get_udp_address(return_route).map(|a| a.address().to_string())
     }
+
+    /// Register the sender as a peer of the allocation, or refresh its registration
+    fn handle_allocate(&mut self, allocation_id: String, return_route: Route) {
+        let Some(udp_address) = Self::get_udp_address(&return_route) else {
+            warn!("Return route has no UDP part, will not allocate a relay: {return_route:?}");
+            return;
+        };
+        let udp_address = udp_address.address().to_string();
+
+        self.allocations
+            .retain(|_, allocation| allocation.refreshed_at.elapsed() < ALLOCATION_LIFETIME);
+
+        if !self.allocations.contains_key(&allocation_id)
+            && self.allocations.len() >= MAX_ALLOCATIONS
+        {
+            warn!("Too many relayed allocations, rejecting the allocation for {udp_address}");
+            return;
+        }
+
+        let allocation = self
+            .allocations
+            .entry(allocation_id)
+            .or_insert_with(|| Allocation {
+                peers: vec![],
+                refreshed_at: Instant::now(),
+            });
+
+        if let Some(peer) = allocation
+            .peers
+            .iter_mut()
+            .find(|(address, _)| address == &udp_address)
+        {
+            peer.1 = return_route;
+        } else if allocation.peers.len() < 2 {
+            info!("{udp_address} joined a relayed allocation");
+            allocation.peers.push((udp_address, return_route));
+        } else {
+            warn!("The relayed allocation already has 2 peers, rejecting {udp_address}");
+            return;
+        }
+
+        allocation.refreshed_at = Instant::now();
+    }
+
+    /// Return the route to the other peer of the allocation, if the sender is one of its peers
+    fn relay_route(&self, allocation_id: &str, return_route: &Route) -> Option<Route> {
+        let udp_address = Self::get_udp_address(return_route)?;
+        let udp_address = udp_address.address();
+
+        let allocation = self.allocations.get(allocation_id)?;
+        if allocation.refreshed_at.elapsed() >= ALLOCATION_LIFETIME
+            || !allocation
+                .peers
+                .iter()
+                .any(|(address, _)| address == udp_address)
+        {
+            return None;
+        }
+
+        allocation
+            .peers
+            .iter()
+            .find(|(address, _)| address != udp_address)
+            .map(|(_, route)| route.clone())
+    }
 }
 
 #[async_trait]
@@ -109,6 +198,25 @@ impl Worker for RendezvousServiceWorker {
                     }
                 }
             }
+            RendezvousRequest::Allocate(allocation_id) => {
+                self.handle_allocate(allocation_id, return_route);
+            }
+            RendezvousRequest::Relay {
+                allocation_id,
+                payload,
+            } => match self.relay_route(&allocation_id, &return_route) {
+                Some(peer_route) => {
+                    // The payload is delivered as is to the peer, as if it was sent directly
+                    let msg = LocalMessage::new()
+                        .with_onward_route(peer_route)
+                        .with_return_route(route![ctx.primary_address().clone()])
+                        .with_payload(payload);
+                    ctx.forward(msg).await?;
+                }
+                None => {
+                    trace!("Dropping a datagram for an unknown relayed allocation");
+                }
+            },
         }
 
         Ok(())
@@ -120,8 +228,9 @@ mod tests {
     use super::RendezvousServiceWorker;
     use crate::puncture::rendezvous_service::{RendezvousRequest, RendezvousResponse};
     use crate::{RendezvousService, UdpBind, UdpBindArguments, UdpBindOptions, UdpTransport, UDP};
-    use ockam_core::{route, Result, Route, TransportType};
-    use ockam_node::Context;
+    use core::time::Duration;
+    use ockam_core::{route, AllowAll, Encodable, Result, Route, TransportType};
+    use ockam_node::{Context, MessageReceiveOptions};
 
     #[test]
     fn parse_route() {
@@ -178,6 +287,89 @@ mod tests {
         Ok(())
     }
 
+    #[ockam_macros::test]
+    async fn relay(ctx: &mut Context) -> Result<()> {
+        let (_, udp_bind) = test_setup(ctx).await?;
+        let transport = UdpTransport::create(ctx)?;
+
+        // Each peer of the allocation sends from its own UDP bind
+        let (mut peer1, peer1_route) = peer_setup(ctx, &transport, &udp_bind, "peer1").await?;
+        let (mut peer2, peer2_route) = peer_setup(ctx, &transport, &udp_bind, "peer2").await?;
+        let (intruder, intruder_route) = peer_setup(ctx, &transport, &udp_bind, "intruder").await?;
+
+        // The first two peers registering to the allocation are its peers
+        for (peer, route) in [
+            (&peer1, &peer1_route),
+            (&peer2, &peer2_route),
+            (&intruder, &intruder_route),
+        ] {
+            peer.send(
+                route.clone(),
+                RendezvousRequest::Allocate("allocation".to_string()),
+            )
+            .await?;
+            // Wait for the allocation to be processed
+            ctx.sleep(Duration::from_millis(100)).await;
+        }
+
+        let relay = |payload: &str| -> Result<RendezvousRequest> {
+            Ok(RendezvousRequest::Relay {
+                allocation_id: "allocation".to_string(),
+                payload: payload.to_string().encode()?,
+            })
+        };
+
+        peer1.send(peer1_route.clone(), relay("to peer2")?).await?;
+        let msg = peer2.receive::<String>().await?;
+        assert_eq!(msg.into_body()?, "to peer2");
+
+        peer2.send(peer2_route.clone(), relay("to peer1")?).await?;
+        let msg = peer1.receive::<String>().await?;
+        assert_eq!(msg.into_body()?, "to peer1");
+
+        // The allocation is full, the third peer can't use it
+        intruder
+            .send(intruder_route.clone(), relay("from intruder")?)
+            .await?;
+        let res = peer1
+            .receive_extended::<String>(
+                MessageReceiveOptions::new().with_timeout(Duration::from_millis(500)),
+            )
+            .await;
+        assert!(res.is_err());
+        let res = peer2
+            .receive_extended::<String>(
+                MessageReceiveOptions::new().with_timeout(Duration::from_millis(500)),
+            )
+            .await;
+        assert!(res.is_err());
+
+        Ok(())
+    }
+
+    /// Helper, start a detached context sending to the Rendezvous service from its own UDP bind
+    async fn peer_setup(
+        ctx: &Context,
+        transport: &UdpTransport,
+        rendezvous_bind: &UdpBind,
+        name: &str,
+    ) -> Result<(Context, Route)> {
+        let bind = transport
+            .bind(UdpBindArguments::new(), UdpBindOptions::new())
+            .await?;
+        let peer = ctx.new_detached(name, AllowAll, AllowAll)?;
+        ctx.flow_controls()
+            .add_consumer(peer.primary_address(), bind.flow_control_id());
+
+        let rendezvous_route = route![
+            bind.sender_address().clone(),
+            (UDP, rendezvous_bind.bind_address().to_string()),
+            "rendezvous"
+        ];
+
+        Ok((peer, rendezvous_route))
+    }
+
     /// Helper
     async fn test_setup(ctx: &mut Context) -> Result<(Route, UdpBind)> {
         // Create transport, start rendezvous service, start echo service and listen
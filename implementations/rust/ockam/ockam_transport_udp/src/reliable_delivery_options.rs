@@ -0,0 +1,40 @@
+use core::time::Duration;
+
+/// Options for the reliable delivery mode of the UDP transport.
+///
+/// In that mode the packets sent to a peer are numbered, acknowledged by the peer and
+/// retransmitted when they are lost, and the messages are delivered in the order they were sent.
+/// The number of unacknowledged packets is limited by a congestion window, which grows while
+/// packets are acknowledged and shrinks when packets are lost.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UdpReliableDeliveryOptions {
+    /// Retransmission timeout used until a round-trip time to the peer is measured
+    pub initial_retransmission_timeout: Duration,
+    /// Lower bound of the retransmission timeout
+    pub min_retransmission_timeout: Duration,
+    /// Upper bound of the retransmission timeout, when it is backed off after a timeout
+    pub max_retransmission_timeout: Duration,
+    /// Number of retransmissions of a packet before the peer is considered unreachable
+    pub max_retransmissions: u32,
+    /// Number of packets which can be sent before the first acknowledgement is received
+    pub initial_congestion_window: u32,
+    /// Max number of unacknowledged packets. It shouldn't exceed the receive window of the peer
+    pub max_congestion_window: u32,
+    /// Max number of packets received out of order which are buffered until the missing
+    /// packets are retransmitted
+    pub receive_window: u32,
+}
+
+impl Default for UdpReliableDeliveryOptions {
+    fn default() -> Self {
+        Self {
+            initial_retransmission_timeout: Duration::from_millis(500),
+            min_retransmission_timeout: Duration::from_millis(200),
+            max_retransmission_timeout: Duration::from_secs(10),
+            max_retransmissions: 8,
+            initial_congestion_window: 10,
+            max_congestion_window: 256,
+            receive_window: 512,
+        }
+    }
+}
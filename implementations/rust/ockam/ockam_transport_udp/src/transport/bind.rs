@@ -1,8 +1,11 @@
-use crate::workers::{split_socket, Addresses, UdpReceiverProcessor, UdpSenderWorker};
-use crate::{UdpBindOptions, UdpTransport};
+use crate::workers::{
+    split_socket, Addresses, ReliableDelivery, UdpReceiverProcessor, UdpSenderWorker,
+};
+use crate::{UdpBindOptions, UdpReliableDeliveryOptions, UdpTransport};
 use core::fmt;
 use core::fmt::Formatter;
 use core::str::FromStr;
+use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::flow_control::FlowControlId;
 use ockam_core::{Address, AllowAll, DenyAll, Error, Result};
@@ -115,11 +118,14 @@ impl UdpTransport {
         let receiver_outgoing_access_control =
             options.create_receiver_outgoing_access_control(self.ctx.flow_controls());
 
+        let reliable_delivery = Arc::new(ReliableDelivery::new(options.reliable_delivery));
+
         let sender = UdpSenderWorker::new(
             addresses.clone(),
-            socket_write,
+            socket_write.clone(),
             arguments.peer_address,
             options.size_options.max_payload_size_per_packet,
+            reliable_delivery.clone(),
         );
         WorkerBuilder::new(sender)
             .with_address(addresses.sender_address().clone())
@@ -130,9 +136,11 @@ impl UdpTransport {
         let receiver = UdpReceiverProcessor::new(
             addresses.clone(),
             socket_read,
+            socket_write,
             arguments.peer_address,
             options.size_options.pending_messages_per_peer,
             options.size_options.max_on_the_wire_packet_size,
+            reliable_delivery.clone(),
         );
         ProcessorBuilder::new(receiver)
             .with_address(addresses.receiver_address().clone())
@@ -146,6 +154,7 @@ impl UdpTransport {
             arguments.peer_address,
             local_addr,
             flow_control_id,
+            reliable_delivery,
         );
 
         Ok(bind)
@@ -164,6 +173,7 @@ pub struct UdpBind {
     peer: Option<SocketAddr>,
     bind_address: SocketAddr,
     flow_control_id: FlowControlId,
+    reliable_delivery: Arc<ReliableDelivery>,
 }
 
 impl fmt::Display for UdpBind {
@@ -187,12 +197,14 @@ impl UdpBind {
         peer: Option<SocketAddr>,
         bind_address: SocketAddr,
         flow_control_id: FlowControlId,
+        reliable_delivery: Arc<ReliableDelivery>,
     ) -> Self {
         Self {
             addresses,
             peer,
             bind_address,
             flow_control_id,
+            reliable_delivery,
         }
     }
 
@@ -220,6 +232,21 @@ impl UdpBind {
     pub fn flow_control_id(&self) -> &FlowControlId {
         &self.flow_control_id
     }
+
+    /// Enable the reliable delivery for the messages sent to that peer
+    pub(crate) fn enable_reliable_delivery(
+        &self,
+        peer: SocketAddr,
+        options: UdpReliableDeliveryOptions,
+    ) {
+        self.reliable_delivery.enable_for_peer(peer, options)
+    }
+
+    /// Disable the reliable delivery for the messages sent to that peer, and drop the messages
+    /// which are not acknowledged yet
+    pub(crate) fn disable_reliable_delivery(&self, peer: &SocketAddr) {
+        self.reliable_delivery.disable_for_peer(peer)
+    }
 }
 
 impl From<UdpBind> for Address {
@@ -15,7 +15,7 @@ impl UdpTransport {
         UdpPuncture::create(
             &self.ctx,
             bind,
-            peer_udp_address,
+            vec![peer_udp_address],
             my_remote_address,
             their_remote_address,
             options,
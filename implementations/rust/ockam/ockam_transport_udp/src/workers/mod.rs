@@ -9,3 +9,6 @@ pub(crate) use sender::*;
 pub(crate) use socket_split::*;
 
 mod pending_messages;
+mod reliable_delivery;
+
+pub(crate) use reliable_delivery::*;
@@ -1,11 +1,19 @@
-use super::{Addresses, UdpSocketRead};
-use crate::messages::UdpTransportMessage;
+use super::{Addresses, ReliableDelivery, UdpSocketRead, UdpSocketWrite};
+use crate::messages::{
+    decode_version, UdpReliableMessage, UdpRoutingMessage, UdpTransportMessage, RELIABLE_VERSION,
+};
 use crate::workers::pending_messages::PendingRoutingMessageStorage;
 use crate::UDP;
+use core::future::pending;
+use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
-use ockam_core::{async_trait, Address, Error, LocalMessage, Processor, Result, RouteBuilder};
+use ockam_core::{
+    async_trait, Address, Error, LocalMessage, Processor, Result, RouteBuilder,
+    SourceAddressLocalInfo,
+};
 use ockam_node::Context;
 use std::net::SocketAddr;
+use std::time::Instant;
 use tracing::{trace, warn};
 
 /// A listener for the UDP transport
@@ -20,33 +28,131 @@ pub(crate) struct UdpReceiverProcessor {
     addresses: Addresses,
     /// The read half of the underlying UDP socket.
     socket_read: UdpSocketRead,
+    /// The write half of the underlying UDP socket, to send acknowledgements and retransmissions
+    socket_write: UdpSocketWrite,
     buffer: Vec<u8>,
     /// Will be Some if we communicate with one specific peer.
     peer: Option<SocketAddr>,
     /// Pending routing messages that we haven't yet assembled fully
     pending_routing_messages: PendingRoutingMessageStorage,
     max_on_the_wire_packet_size: usize,
+    /// State of the reliable delivery, shared with the sender
+    reliable_delivery: Arc<ReliableDelivery>,
 }
 
 impl UdpReceiverProcessor {
     pub fn new(
         addresses: Addresses,
         socket_read: UdpSocketRead,
+        socket_write: UdpSocketWrite,
         peer: Option<SocketAddr>,
         max_pending_messages_per_peer: u16,
         max_on_the_wire_packet_size: usize,
+        reliable_delivery: Arc<ReliableDelivery>,
     ) -> Self {
         Self {
             addresses,
             socket_read,
+            socket_write,
             buffer: vec![0; max_on_the_wire_packet_size],
             peer,
             pending_routing_messages: PendingRoutingMessageStorage::new(
                 max_pending_messages_per_peer,
             ),
             max_on_the_wire_packet_size,
+            reliable_delivery,
         }
     }
+
+    async fn send_packet(&self, packet: &[u8], peer: SocketAddr) -> Result<()> {
+        self.socket_write
+            .send_to(packet, peer)
+            .await
+            .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?;
+        Ok(())
+    }
+
+    /// Retransmit the reliable packets which were not acknowledged in time
+    async fn handle_timers(&self) -> Result<()> {
+        for (peer, packet) in self.reliable_delivery.handle_timers(Instant::now()) {
+            self.send_packet(&packet, peer).await?;
+        }
+        Ok(())
+    }
+
+    /// Acknowledge a reliable packet and forward the routing messages which can be assembled
+    /// from the transport messages delivered in order
+    async fn handle_reliable_message(
+        &mut self,
+        ctx: &Context,
+        addr: SocketAddr,
+        reliable_message: UdpReliableMessage<'_>,
+    ) -> Result<()> {
+        let output =
+            self.reliable_delivery
+                .handle_packet(addr, reliable_message, Instant::now())?;
+        for packet in output.packets {
+            self.send_packet(&packet, addr).await?;
+        }
+
+        for transport_message in output.delivered {
+            let transport_message: UdpTransportMessage = minicbor::decode(&transport_message)?;
+            if let Some(routing_message) = self
+                .pending_routing_messages
+                .add_transport_message_and_try_assemble(addr, transport_message)?
+            {
+                self.forward(ctx, addr, routing_message).await?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Forward a fully assembled routing message to its destination
+    async fn forward(
+        &self,
+        ctx: &Context,
+        addr: SocketAddr,
+        routing_message: UdpRoutingMessage<'static>,
+    ) -> Result<()> {
+        if routing_message.onward_route.is_empty() {
+            return Ok(());
+        }
+
+        let return_route = RouteBuilder::default().append(self.addresses.sender_address().clone());
+
+        let return_route = if self.peer.is_some() {
+            // If the peer address is defined, we don't need to specify it in the return route
+            return_route
+        } else {
+            // Add the peer address so that sender knows where to send the message
+            return_route.append(Address::new_with_string(UDP, addr.to_string()))
+        };
+
+        let mut local_message = LocalMessage::from(routing_message);
+
+        let return_route = return_route.append_route(local_message.return_route.clone());
+
+        local_message = local_message.set_return_route(return_route.into());
+
+        // Record the socket address of the peer, so that the message can be authorized
+        // with its source, even after it is decrypted by a secure channel
+        let source = SourceAddressLocalInfo::new(UDP, addr).to_local_info()?;
+        local_message.local_info_mut().push(source);
+
+        trace!(onward_route = %local_message.onward_route(),
+            return_route = %local_message.return_route(),
+            "Forwarding UDP message");
+
+        ctx.forward(local_message).await
+    }
+}
+
+/// Wait until the next retransmission timeout, if there is one
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => pending().await,
+    }
 }
 
 #[async_trait]
@@ -59,11 +165,21 @@ impl Processor for UdpReceiverProcessor {
         self.buffer.clear();
         self.buffer.resize(self.max_on_the_wire_packet_size, 0);
 
-        let (len, addr) = self
-            .socket_read
-            .recv_from(&mut self.buffer)
-            .await
-            .map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?;
+        // Wake up to retransmit the reliable packets which are not acknowledged in time
+        let deadline = self.reliable_delivery.next_deadline();
+        let received = tokio::select! {
+            received = self.socket_read.recv_from(&mut self.buffer) => Some(received),
+            _ = sleep_until(deadline) => None,
+            _ = self.reliable_delivery.notified() => None,
+        };
+
+        let (len, addr) = match received {
+            Some(received) => received.map_err(|e| Error::new(Origin::Transport, Kind::Io, e))?,
+            None => {
+                self.handle_timers().await?;
+                return Ok(true);
+            }
+        };
 
         if let Some(peer) = &self.peer {
             if peer != &addr {
@@ -76,6 +192,14 @@ impl Processor for UdpReceiverProcessor {
             }
         }
 
+        if decode_version(&self.buffer[..len])? == RELIABLE_VERSION {
+            let packet = self.buffer[..len].to_vec();
+            let reliable_message: UdpReliableMessage = minicbor::decode(&packet)?;
+            self.handle_reliable_message(ctx, addr, reliable_message)
+                .await?;
+            return Ok(true);
+        }
+
         let transport_message: UdpTransportMessage = minicbor::decode(&self.buffer[..len])?;
 
         // Let's save newly received message and see if we can assemble a Routing Message
@@ -90,31 +214,7 @@ impl Processor for UdpReceiverProcessor {
             }
         };
 
-        if routing_message.onward_route.is_empty() {
-            return Ok(true);
-        }
-
-        let return_route = RouteBuilder::default().append(self.addresses.sender_address().clone());
-
-        let return_route = if self.peer.is_some() {
-            // If the peer address is defined, we don't need to specify it in the return route
-            return_route
-        } else {
-            // Add the peer address so that sender knows where to send the message
-            return_route.append(Address::new_with_string(UDP, addr.to_string()))
-        };
-
-        let mut local_message = LocalMessage::from(routing_message);
-
-        let return_route = return_route.append_route(local_message.return_route.clone());
-
-        local_message = local_message.set_return_route(return_route.into());
-
-        trace!(onward_route = %local_message.onward_route(),
-            return_route = %local_message.return_route(),
-            "Forwarding UDP message");
-
-        ctx.forward(local_message).await?;
+        self.forward(ctx, addr, routing_message).await?;
 
         Ok(true)
     }
@@ -0,0 +1,187 @@
+use crate::UdpReliableDeliveryOptions;
+use core::cmp::{max, min};
+use core::time::Duration;
+
+/// Congestion window counted in packets, growing with slow start and then with congestion
+/// avoidance, as in TCP Reno
+pub(crate) struct CongestionWindow {
+    window: u32,
+    slow_start_threshold: u32,
+    max_window: u32,
+    // Packets acknowledged since the window was last increased in congestion avoidance
+    acknowledged: u32,
+}
+
+impl CongestionWindow {
+    pub(crate) fn new(options: &UdpReliableDeliveryOptions) -> Self {
+        let max_window = max(options.max_congestion_window, 1);
+        Self {
+            window: options.initial_congestion_window.clamp(1, max_window),
+            slow_start_threshold: max_window,
+            max_window,
+            acknowledged: 0,
+        }
+    }
+
+    /// Max number of unacknowledged packets
+    pub(crate) fn window(&self) -> u32 {
+        self.window
+    }
+
+    /// A packet was acknowledged
+    pub(crate) fn on_ack(&mut self) {
+        if self.window < self.slow_start_threshold {
+            self.window += 1;
+        } else {
+            self.acknowledged += 1;
+            if self.acknowledged >= self.window {
+                self.acknowledged = 0;
+                self.window += 1;
+            }
+        }
+        self.window = min(self.window, self.max_window);
+    }
+
+    /// A packet was detected as lost by the selective acknowledgements
+    pub(crate) fn on_loss(&mut self) {
+        self.slow_start_threshold = max(self.window / 2, 2);
+        self.window = min(self.slow_start_threshold, self.max_window);
+        self.acknowledged = 0;
+    }
+
+    /// A packet was not acknowledged before the retransmission timeout
+    pub(crate) fn on_timeout(&mut self) {
+        self.slow_start_threshold = max(self.window / 2, 2);
+        self.window = 1;
+        self.acknowledged = 0;
+    }
+}
+
+/// Retransmission timeout computed from the measured round-trip times, see RFC 6298
+pub(crate) struct RttEstimator {
+    smoothed_rtt: Option<Duration>,
+    rtt_variation: Duration,
+    timeout: Duration,
+    min_timeout: Duration,
+    max_timeout: Duration,
+    // Number of times the timeout was doubled since packets were last acknowledged
+    backoff: u32,
+}
+
+impl RttEstimator {
+    pub(crate) fn new(options: &UdpReliableDeliveryOptions) -> Self {
+        Self {
+            smoothed_rtt: None,
+            rtt_variation: Duration::ZERO,
+            timeout: options.initial_retransmission_timeout,
+            min_timeout: options.min_retransmission_timeout,
+            max_timeout: options.max_retransmission_timeout,
+            backoff: 0,
+        }
+    }
+
+    pub(crate) fn timeout(&self) -> Duration {
+        min(
+            self.timeout.saturating_mul(1 << min(self.backoff, 16)),
+            self.max_timeout,
+        )
+    }
+
+    /// Update the timeout with the round-trip time of a packet which was not retransmitted
+    pub(crate) fn on_sample(&mut self, rtt: Duration) {
+        let smoothed_rtt = match self.smoothed_rtt {
+            None => {
+                self.rtt_variation = rtt / 2;
+                rtt
+            }
+            Some(smoothed_rtt) => {
+                let difference = if smoothed_rtt > rtt {
+                    smoothed_rtt - rtt
+                } else {
+                    rtt - smoothed_rtt
+                };
+                self.rtt_variation = self.rtt_variation * 3 / 4 + difference / 4;
+                smoothed_rtt * 7 / 8 + rtt / 8
+            }
+        };
+        self.smoothed_rtt = Some(smoothed_rtt);
+        self.timeout = (smoothed_rtt + max(self.rtt_variation * 4, Duration::from_millis(1)))
+            .clamp(self.min_timeout, self.max_timeout);
+    }
+
+    /// Double the timeout after a retransmission timeout
+    pub(crate) fn backoff(&mut self) {
+        self.backoff = self.backoff.saturating_add(1);
+    }
+
+    /// Packets were acknowledged: the peer is reachable and the timeout doesn't need to be
+    /// backed off anymore, even if there is no new round-trip time sample
+    pub(crate) fn reset_backoff(&mut self) {
+        self.backoff = 0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn congestion_window() {
+        let options = UdpReliableDeliveryOptions {
+            initial_congestion_window: 2,
+            max_congestion_window: 8,
+            ..Default::default()
+        };
+        let mut window = CongestionWindow::new(&options);
+
+        // slow start
+        window.on_ack();
+        window.on_ack();
+        assert_eq!(window.window(), 4);
+
+        window.on_loss();
+        assert_eq!(window.window(), 2);
+
+        // congestion avoidance: one more packet per window of acknowledged packets
+        window.on_ack();
+        assert_eq!(window.window(), 2);
+        window.on_ack();
+        assert_eq!(window.window(), 3);
+
+        window.on_timeout();
+        assert_eq!(window.window(), 1);
+
+        for _ in 0..100 {
+            window.on_ack();
+        }
+        assert_eq!(window.window(), 8);
+    }
+
+    #[test]
+    fn retransmission_timeout() {
+        let options = UdpReliableDeliveryOptions::default();
+        let mut rtt = RttEstimator::new(&options);
+        assert_eq!(rtt.timeout(), options.initial_retransmission_timeout);
+
+        rtt.on_sample(Duration::from_millis(100));
+        assert_eq!(rtt.timeout(), Duration::from_millis(300));
+
+        // the timeout can't be lower than the minimum
+        for _ in 0..100 {
+            rtt.on_sample(Duration::from_millis(1));
+        }
+        assert_eq!(rtt.timeout(), options.min_retransmission_timeout);
+
+        rtt.backoff();
+        assert_eq!(rtt.timeout(), options.min_retransmission_timeout * 2);
+
+        // the timeout can't exceed the maximum
+        for _ in 0..100 {
+            rtt.backoff();
+        }
+        assert_eq!(rtt.timeout(), options.max_retransmission_timeout);
+
+        rtt.reset_backoff();
+        assert_eq!(rtt.timeout(), options.min_retransmission_timeout);
+    }
+}
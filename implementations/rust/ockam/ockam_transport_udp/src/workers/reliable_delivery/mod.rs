@@ -0,0 +1,342 @@
+mod congestion;
+mod reliable_receiver;
+mod reliable_sender;
+
+use crate::messages::{UdpReliableMessage, UdpReliableMessageKind};
+use crate::UdpReliableDeliveryOptions;
+use core::fmt;
+use core::fmt::Formatter;
+use ockam_core::compat::collections::HashMap;
+use ockam_core::compat::sync::Mutex;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use reliable_receiver::ReliableReceiver;
+use reliable_sender::ReliableSender;
+use std::net::SocketAddr;
+use std::time::Instant;
+use tokio::sync::futures::Notified;
+use tokio::sync::Notify;
+use tracing::{trace, warn};
+
+/// Result of handling a packet received in the reliable delivery mode
+pub(crate) struct ReliableDeliveryOutput {
+    /// Packets to send back to the peer: acknowledgements, retransmissions or new packets
+    pub(crate) packets: Vec<Vec<u8>>,
+    /// Encoded [`UdpTransportMessage`]s which can be delivered, in order
+    pub(crate) delivered: Vec<Vec<u8>>,
+}
+
+/// State of the reliable delivery mode, shared by the sender and the receiver of a bind.
+///
+/// The sender worker numbers and queues the packets sent to a peer. The receiver processor
+/// handles the acknowledgements and the retransmission timers, and buffers the packets received
+/// out of order. Packets sent reliably by a peer are always acknowledged, even if the reliable
+/// delivery is not enabled for our own packets.
+pub(crate) struct ReliableDelivery {
+    options: Option<UdpReliableDeliveryOptions>,
+    state: Mutex<ReliableDeliveryState>,
+    /// Wakes up the receiver when new packets are in flight, to arm the retransmission timer
+    notify: Notify,
+}
+
+#[derive(Default)]
+struct ReliableDeliveryState {
+    peers_options: HashMap<SocketAddr, UdpReliableDeliveryOptions>,
+    senders: HashMap<SocketAddr, ReliableSender>,
+    receivers: HashMap<SocketAddr, ReliableReceiver>,
+}
+
+impl fmt::Debug for ReliableDelivery {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "ReliableDelivery: {:?}", self.options)
+    }
+}
+
+impl ReliableDelivery {
+    /// Create the state for a bind, with the options used for all its peers if the reliable
+    /// delivery is enabled
+    pub(crate) fn new(options: Option<UdpReliableDeliveryOptions>) -> Self {
+        Self {
+            options,
+            state: Default::default(),
+            notify: Notify::new(),
+        }
+    }
+
+    /// Enable the reliable delivery for the packets sent to a specific peer
+    pub(crate) fn enable_for_peer(&self, peer: SocketAddr, options: UdpReliableDeliveryOptions) {
+        self.state
+            .lock()
+            .unwrap()
+            .peers_options
+            .insert(peer, options);
+    }
+
+    /// Disable the reliable delivery for the packets sent to a specific peer, and discard
+    /// the packets which are still queued or in flight for it
+    pub(crate) fn disable_for_peer(&self, peer: &SocketAddr) {
+        let mut state = self.state.lock().unwrap();
+        state.peers_options.remove(peer);
+        state.senders.remove(peer);
+    }
+
+    /// Options of the reliable delivery to that peer, if it is enabled
+    pub(crate) fn peer_options(&self, peer: &SocketAddr) -> Option<UdpReliableDeliveryOptions> {
+        self.state
+            .lock()
+            .unwrap()
+            .peers_options
+            .get(peer)
+            .copied()
+            .or(self.options)
+    }
+
+    /// Queue encoded [`UdpTransportMessage`]s for a peer and return the packets which can be
+    /// sent right away
+    pub(crate) fn send(
+        &self,
+        peer: SocketAddr,
+        options: &UdpReliableDeliveryOptions,
+        transport_messages: Vec<Vec<u8>>,
+        now: Instant,
+    ) -> Result<Vec<Vec<u8>>> {
+        let mut state = self.state.lock().unwrap();
+        let sender = state
+            .senders
+            .entry(peer)
+            .or_insert_with(|| ReliableSender::new(options));
+        for transport_message in transport_messages {
+            sender.push(&transport_message)?;
+        }
+        let packets = sender.transmit(now);
+        self.notify.notify_one();
+        Ok(packets)
+    }
+
+    /// Handle a packet received from a peer in the reliable delivery mode
+    pub(crate) fn handle_packet(
+        &self,
+        peer: SocketAddr,
+        message: UdpReliableMessage<'_>,
+        now: Instant,
+    ) -> Result<ReliableDeliveryOutput> {
+        let mut state = self.state.lock().unwrap();
+        match message.kind {
+            UdpReliableMessageKind::Data {
+                sequence_number,
+                transport_message,
+            } => {
+                let receive_window = state
+                    .peers_options
+                    .get(&peer)
+                    .copied()
+                    .or(self.options)
+                    .unwrap_or_default()
+                    .receive_window;
+                let receiver = state
+                    .receivers
+                    .entry(peer)
+                    .or_insert_with(|| ReliableReceiver::new(message.stream_id, receive_window));
+
+                // The peer restarted its numbering
+                if receiver.stream_id() != message.stream_id {
+                    trace!("New reliable stream {} from {}", message.stream_id, peer);
+                    *receiver = ReliableReceiver::new(message.stream_id, receive_window);
+                }
+
+                let delivered = receiver.handle_data(sequence_number, &transport_message);
+                Ok(ReliableDeliveryOutput {
+                    packets: vec![receiver.ack()?],
+                    delivered,
+                })
+            }
+            UdpReliableMessageKind::Ack {
+                next_expected,
+                selective_acks,
+            } => {
+                let packets = match state.senders.get_mut(&peer) {
+                    Some(sender) if sender.stream_id() == message.stream_id => {
+                        sender.handle_ack(next_expected, &selective_acks, now)
+                    }
+                    _ => {
+                        trace!("Dropping an acknowledgement for an unknown stream from {peer}");
+                        vec![]
+                    }
+                };
+                Ok(ReliableDeliveryOutput {
+                    packets,
+                    delivered: vec![],
+                })
+            }
+        }
+    }
+
+    /// Return the packets to retransmit because their retransmission timeout expired.
+    /// The packets for a peer which doesn't acknowledge them anymore are discarded
+    pub(crate) fn handle_timers(&self, now: Instant) -> Vec<(SocketAddr, Vec<u8>)> {
+        let mut state = self.state.lock().unwrap();
+        let mut packets = vec![];
+        state
+            .senders
+            .retain(|peer, sender| match sender.handle_timers(now) {
+                Ok(retransmissions) => {
+                    packets.extend(retransmissions.into_iter().map(|packet| (*peer, packet)));
+                    true
+                }
+                Err(err) => {
+                    warn!("Discarding the packets for {peer}: {err}");
+                    false
+                }
+            });
+        packets
+    }
+
+    /// Time when the next retransmission timeout expires
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        self.state
+            .lock()
+            .unwrap()
+            .senders
+            .values()
+            .filter_map(|sender| sender.next_deadline())
+            .min()
+    }
+
+    /// Resolves when new packets were sent
+    pub(crate) fn notified(&self) -> Notified<'_> {
+        self.notify.notified()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use core::time::Duration;
+    use rand::rngs::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    /// Simulated network between two peers, dropping and reordering packets
+    struct LossyNetwork {
+        rng: StdRng,
+        loss: f64,
+        // Packets in transit: delivery time, true if sent to the receiver, packet
+        in_transit: Vec<(Instant, bool, Vec<u8>)>,
+    }
+
+    impl LossyNetwork {
+        fn new(loss: f64) -> Self {
+            Self {
+                rng: StdRng::seed_from_u64(42),
+                loss,
+                in_transit: vec![],
+            }
+        }
+
+        fn send(&mut self, now: Instant, to_receiver: bool, packets: Vec<Vec<u8>>) {
+            for packet in packets {
+                if self.rng.gen_bool(self.loss) {
+                    continue;
+                }
+                let latency = Duration::from_millis(self.rng.gen_range(10..30));
+                self.in_transit.push((now + latency, to_receiver, packet));
+            }
+        }
+
+        fn next_delivery(&self) -> Option<Instant> {
+            self.in_transit.iter().map(|(at, _, _)| *at).min()
+        }
+
+        fn deliver(&mut self, now: Instant) -> Vec<(bool, Vec<u8>)> {
+            let (delivered, in_transit) =
+                self.in_transit.drain(..).partition(|(at, _, _)| *at <= now);
+            self.in_transit = in_transit;
+            delivered
+                .into_iter()
+                .map(|(_, to_receiver, packet)| (to_receiver, packet))
+                .collect()
+        }
+    }
+
+    #[test]
+    fn messages_are_delivered_in_order_despite_losses() -> Result<()> {
+        let sender_address: SocketAddr = "127.0.0.1:1000".parse().unwrap();
+        let receiver_address: SocketAddr = "127.0.0.1:2000".parse().unwrap();
+        let options = UdpReliableDeliveryOptions::default();
+        let sender = ReliableDelivery::new(Some(options));
+        let receiver = ReliableDelivery::new(None);
+        let mut network = LossyNetwork::new(0.3);
+
+        let messages: Vec<Vec<u8>> = (0..500u32).map(|i| i.to_be_bytes().to_vec()).collect();
+        let mut now = Instant::now();
+        let packets = sender.send(receiver_address, &options, messages.clone(), now)?;
+        network.send(now, true, packets);
+
+        let mut delivered = vec![];
+        while delivered.len() < messages.len() {
+            now = [network.next_delivery(), sender.next_deadline()]
+                .into_iter()
+                .flatten()
+                .min()
+                .expect("the sender gave up");
+
+            for (to_receiver, packet) in network.deliver(now) {
+                let message: UdpReliableMessage = minicbor::decode(&packet)?;
+                if to_receiver {
+                    let output = receiver.handle_packet(sender_address, message, now)?;
+                    delivered.extend(output.delivered);
+                    network.send(now, false, output.packets);
+                } else {
+                    let output = sender.handle_packet(receiver_address, message, now)?;
+                    network.send(now, true, output.packets);
+                }
+            }
+
+            let retransmissions = sender
+                .handle_timers(now)
+                .into_iter()
+                .map(|(_, packet)| packet)
+                .collect();
+            network.send(now, true, retransmissions);
+        }
+
+        assert_eq!(delivered, messages);
+        Ok(())
+    }
+
+    #[test]
+    fn disabled_peer_is_discarded() -> Result<()> {
+        let peer: SocketAddr = "127.0.0.1:2000".parse().unwrap();
+        let options = UdpReliableDeliveryOptions::default();
+        let sender = ReliableDelivery::new(None);
+        sender.enable_for_peer(peer, options);
+
+        let now = Instant::now();
+        sender.send(peer, &options, vec![vec![1]], now)?;
+        assert!(sender.next_deadline().is_some());
+
+        sender.disable_for_peer(&peer);
+        assert!(sender.peer_options(&peer).is_none());
+        assert!(sender.next_deadline().is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn unreachable_peer_is_discarded() -> Result<()> {
+        let receiver_address: SocketAddr = "127.0.0.1:2000".parse().unwrap();
+        let options = UdpReliableDeliveryOptions::default();
+        let sender = ReliableDelivery::new(Some(options));
+
+        let mut now = Instant::now();
+        let packets = sender.send(receiver_address, &options, vec![vec![1]], now)?;
+        assert_eq!(packets.len(), 1);
+
+        let mut retransmissions = 0;
+        while let Some(deadline) = sender.next_deadline() {
+            now = deadline;
+            retransmissions += sender.handle_timers(now).len();
+        }
+
+        assert_eq!(retransmissions, options.max_retransmissions as usize);
+        Ok(())
+    }
+}
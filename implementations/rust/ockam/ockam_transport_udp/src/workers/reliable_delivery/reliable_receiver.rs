@@ -0,0 +1,81 @@
+use crate::messages::{UdpReliableMessage, UdpReliableMessageKind, MAX_SELECTIVE_ACKS};
+use ockam_core::compat::collections::BTreeMap;
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use tracing::trace;
+
+/// Packets received reliably from a peer.
+/// Packets received out of order are buffered until the missing packets are retransmitted, so
+/// that the transport messages are delivered in the order they were sent
+pub(crate) struct ReliableReceiver {
+    stream_id: u32,
+    next_expected: u64,
+    out_of_order: BTreeMap<u64, Vec<u8>>,
+    receive_window: u64,
+}
+
+impl ReliableReceiver {
+    pub(crate) fn new(stream_id: u32, receive_window: u32) -> Self {
+        Self {
+            stream_id,
+            next_expected: 0,
+            out_of_order: Default::default(),
+            receive_window: receive_window as u64,
+        }
+    }
+
+    pub(crate) fn stream_id(&self) -> u32 {
+        self.stream_id
+    }
+
+    /// Store a received packet and return the encoded transport messages which can now be
+    /// delivered in order
+    pub(crate) fn handle_data(
+        &mut self,
+        sequence_number: u64,
+        transport_message: &[u8],
+    ) -> Vec<Vec<u8>> {
+        if sequence_number < self.next_expected {
+            trace!("Dropping duplicate packet {}", sequence_number);
+            return vec![];
+        }
+
+        // Packets too far ahead will be retransmitted by the sender
+        if sequence_number >= self.next_expected + self.receive_window {
+            trace!(
+                "Dropping packet {} outside of the receive window",
+                sequence_number
+            );
+            return vec![];
+        }
+
+        self.out_of_order
+            .entry(sequence_number)
+            .or_insert_with(|| transport_message.to_vec());
+
+        let mut delivered = vec![];
+        while let Some(transport_message) = self.out_of_order.remove(&self.next_expected) {
+            delivered.push(transport_message);
+            self.next_expected += 1;
+        }
+        delivered
+    }
+
+    /// Encoded acknowledgement of the received packets
+    pub(crate) fn ack(&self) -> Result<Vec<u8>> {
+        let selective_acks = self
+            .out_of_order
+            .keys()
+            .take(MAX_SELECTIVE_ACKS)
+            .copied()
+            .collect();
+
+        ockam_core::cbor_encode_preallocate(UdpReliableMessage::new(
+            self.stream_id,
+            UdpReliableMessageKind::Ack {
+                next_expected: self.next_expected,
+                selective_acks,
+            },
+        ))
+    }
+}
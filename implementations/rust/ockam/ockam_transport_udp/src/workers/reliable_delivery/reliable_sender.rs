@@ -0,0 +1,205 @@
+use crate::messages::{UdpReliableMessage, UdpReliableMessageKind};
+use crate::workers::reliable_delivery::congestion::{CongestionWindow, RttEstimator};
+use crate::{UdpReliableDeliveryOptions, UdpTransportError};
+use ockam_core::compat::collections::{BTreeMap, VecDeque};
+use ockam_core::compat::vec::Vec;
+use ockam_core::Result;
+use rand::random;
+use std::time::Instant;
+use tracing::trace;
+
+/// Number of packets acknowledged after a missing packet before it is considered lost,
+/// without waiting for the retransmission timeout
+const LOSS_DETECTION_THRESHOLD: usize = 3;
+
+struct InFlightPacket {
+    packet: Vec<u8>,
+    sent_at: Instant,
+    transmissions: u32,
+    fast_retransmitted: bool,
+}
+
+/// Packets sent reliably to a peer.
+/// Packets are queued until the congestion window allows to send them, then kept until they
+/// are acknowledged by the peer, and retransmitted if they are lost
+pub(crate) struct ReliableSender {
+    stream_id: u32,
+    max_retransmissions: u32,
+    next_sequence_number: u64,
+    // Packets waiting for the congestion window, with their sequence number
+    queued: VecDeque<(u64, Vec<u8>)>,
+    in_flight: BTreeMap<u64, InFlightPacket>,
+    congestion_window: CongestionWindow,
+    rtt: RttEstimator,
+    // The window is reduced once per loss event: losses of packets sent before this
+    // sequence number belong to the same event
+    recovery_sequence_number: u64,
+}
+
+impl ReliableSender {
+    pub(crate) fn new(options: &UdpReliableDeliveryOptions) -> Self {
+        Self {
+            stream_id: random(),
+            max_retransmissions: options.max_retransmissions,
+            next_sequence_number: 0,
+            queued: Default::default(),
+            in_flight: Default::default(),
+            congestion_window: CongestionWindow::new(options),
+            rtt: RttEstimator::new(options),
+            recovery_sequence_number: 0,
+        }
+    }
+
+    pub(crate) fn stream_id(&self) -> u32 {
+        self.stream_id
+    }
+
+    /// Queue an encoded [`UdpTransportMessage`]
+    pub(crate) fn push(&mut self, transport_message: &[u8]) -> Result<()> {
+        let sequence_number = self.next_sequence_number;
+        self.next_sequence_number += 1;
+
+        let packet = ockam_core::cbor_encode_preallocate(UdpReliableMessage::new(
+            self.stream_id,
+            UdpReliableMessageKind::Data {
+                sequence_number,
+                transport_message: transport_message.into(),
+            },
+        ))?;
+        self.queued.push_back((sequence_number, packet));
+        Ok(())
+    }
+
+    /// Return the queued packets which can be sent within the congestion window
+    pub(crate) fn transmit(&mut self, now: Instant) -> Vec<Vec<u8>> {
+        let mut packets = vec![];
+        while self.in_flight.len() < self.congestion_window.window() as usize {
+            let (sequence_number, packet) = match self.queued.pop_front() {
+                Some(queued) => queued,
+                None => break,
+            };
+            packets.push(packet.clone());
+            self.in_flight.insert(
+                sequence_number,
+                InFlightPacket {
+                    packet,
+                    sent_at: now,
+                    transmissions: 1,
+                    fast_retransmitted: false,
+                },
+            );
+        }
+        packets
+    }
+
+    /// Remove the acknowledged packets, and return the packets to retransmit and the queued
+    /// packets which can now be sent
+    pub(crate) fn handle_ack(
+        &mut self,
+        next_expected: u64,
+        selective_acks: &[u64],
+        now: Instant,
+    ) -> Vec<Vec<u8>> {
+        let mut acknowledged: Vec<u64> = self
+            .in_flight
+            .range(..next_expected)
+            .map(|(sequence_number, _)| *sequence_number)
+            .collect();
+        acknowledged.extend(
+            selective_acks
+                .iter()
+                .filter(|sequence_number| self.in_flight.contains_key(sequence_number)),
+        );
+
+        for sequence_number in acknowledged {
+            if let Some(packet) = self.in_flight.remove(&sequence_number) {
+                self.rtt.reset_backoff();
+                // Karn's algorithm: the round-trip time of a retransmitted packet is ambiguous
+                if packet.transmissions == 1 {
+                    self.rtt
+                        .on_sample(now.saturating_duration_since(packet.sent_at));
+                }
+                self.congestion_window.on_ack();
+            }
+        }
+
+        let mut selective_acks = selective_acks.to_vec();
+        selective_acks.sort_unstable();
+
+        let mut packets = vec![];
+        for (sequence_number, packet) in self.in_flight.iter_mut() {
+            let acknowledged_after = selective_acks.len()
+                - selective_acks.partition_point(|acked| acked <= sequence_number);
+            if acknowledged_after < LOSS_DETECTION_THRESHOLD || packet.fast_retransmitted {
+                continue;
+            }
+
+            trace!("Fast retransmission of packet {}", sequence_number);
+            if *sequence_number >= self.recovery_sequence_number {
+                self.congestion_window.on_loss();
+                self.recovery_sequence_number = self.next_sequence_number;
+            }
+            packet.fast_retransmitted = true;
+            packet.transmissions += 1;
+            packet.sent_at = now;
+            packets.push(packet.packet.clone());
+        }
+
+        packets.extend(self.transmit(now));
+        packets
+    }
+
+    /// Retransmit the packets which were not acknowledged before the retransmission timeout.
+    /// Fail if a packet was already retransmitted too many times
+    pub(crate) fn handle_timers(&mut self, now: Instant) -> Result<Vec<Vec<u8>>> {
+        let timeout = self.rtt.timeout();
+        let expired: Vec<u64> = self
+            .in_flight
+            .iter()
+            .filter(|(_, packet)| packet.sent_at + timeout <= now)
+            .map(|(sequence_number, _)| *sequence_number)
+            .collect();
+
+        if expired.is_empty() {
+            return Ok(vec![]);
+        }
+
+        self.congestion_window.on_timeout();
+        self.rtt.backoff();
+        self.recovery_sequence_number = self.next_sequence_number;
+
+        // All the expired packets are considered lost. The reduced congestion window only
+        // applies to the new packets
+        let mut packets = vec![];
+        for sequence_number in expired {
+            let packet = match self.in_flight.get_mut(&sequence_number) {
+                Some(packet) => packet,
+                None => continue,
+            };
+            if packet.transmissions > self.max_retransmissions {
+                return Err(UdpTransportError::MaxRetransmissionsExceeded {
+                    sequence_number,
+                    retransmissions: self.max_retransmissions,
+                })?;
+            }
+            trace!(
+                "Retransmission of packet {} after a timeout",
+                sequence_number
+            );
+            packet.sent_at = now;
+            packet.transmissions += 1;
+            packet.fast_retransmitted = false;
+            packets.push(packet.packet.clone());
+        }
+        Ok(packets)
+    }
+
+    /// Time when the next unacknowledged packet must be retransmitted
+    pub(crate) fn next_deadline(&self) -> Option<Instant> {
+        let timeout = self.rtt.timeout();
+        self.in_flight
+            .values()
+            .map(|packet| packet.sent_at + timeout)
+            .min()
+    }
+}
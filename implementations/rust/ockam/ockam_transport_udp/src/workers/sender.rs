@@ -1,14 +1,16 @@
-use super::{Addresses, UdpSocketWrite};
-use crate::messages::{RoutingNumber, UdpRoutingMessage};
+use super::{Addresses, ReliableDelivery, UdpSocketWrite};
+use crate::messages::{RoutingNumber, UdpRoutingMessage, RELIABLE_MESSAGE_OVERHEAD};
 use crate::workers::pending_messages::TransportMessagesIterator;
 use crate::UDP;
 use core::str::FromStr;
+use ockam_core::compat::sync::Arc;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::{async_trait, Any, Error, Result, Routed, Worker};
 use ockam_node::compat::asynchronous::resolve_peer;
 use ockam_node::Context;
 use ockam_transport_core::{HostnamePort, TransportError};
 use std::net::SocketAddr;
+use std::time::Instant;
 use tracing::{error, trace, warn};
 
 /// A sender for the UDP transport
@@ -24,6 +26,8 @@ pub(crate) struct UdpSenderWorker {
     /// Current number of the packet
     current_routing_number: RoutingNumber,
     max_payload_size_per_packet: usize,
+    /// State of the reliable delivery, shared with the receiver
+    reliable_delivery: Arc<ReliableDelivery>,
 }
 
 impl UdpSenderWorker {
@@ -33,6 +37,7 @@ impl UdpSenderWorker {
         socket_write: UdpSocketWrite,
         peer: Option<SocketAddr>,
         max_payload_size_per_packet: usize,
+        reliable_delivery: Arc<ReliableDelivery>,
     ) -> Self {
         Self {
             addresses,
@@ -40,6 +45,20 @@ impl UdpSenderWorker {
             peer,
             current_routing_number: RoutingNumber::default(),
             max_payload_size_per_packet,
+            reliable_delivery,
+        }
+    }
+
+    async fn send_packet(&self, packet: &[u8], peer: SocketAddr) -> Result<()> {
+        match self.socket_write.send_to(packet, peer).await {
+            Ok(_) => {
+                trace!("Successful send to {}", peer);
+                Ok(())
+            }
+            Err(e) => {
+                error!("Failed send to {}: {:?}", peer, e);
+                Err(Error::new(Origin::Transport, Kind::Io, e))?
+            }
         }
     }
 }
@@ -88,10 +107,32 @@ impl Worker for UdpSenderWorker {
             return Err(TransportError::InvalidAddress(peer.to_string()))?;
         }
 
+        let routing_message = UdpRoutingMessage::from(msg);
+
+        // In the reliable delivery mode, the packets are sent when the congestion window allows it
+        if let Some(options) = self.reliable_delivery.peer_options(&peer) {
+            let messages = TransportMessagesIterator::new(
+                self.current_routing_number,
+                &routing_message,
+                self.max_payload_size_per_packet - RELIABLE_MESSAGE_OVERHEAD,
+            )?
+            .collect::<Result<Vec<_>>>()?;
+
+            self.current_routing_number.increment();
+
+            let packets = self
+                .reliable_delivery
+                .send(peer, &options, messages, Instant::now())?;
+            for packet in packets {
+                self.send_packet(&packet, peer).await?;
+            }
+            return Ok(());
+        }
+
         // Serialize a [`LocalMessage`] into a vector of smaller messages suitable for 1 UDP datagram
         let messages = TransportMessagesIterator::new(
             self.current_routing_number,
-            &UdpRoutingMessage::from(msg),
+            &routing_message,
             self.max_payload_size_per_packet,
         )?;
 
@@ -99,15 +140,7 @@ impl Worker for UdpSenderWorker {
 
         for message in messages {
             let message = message?;
-            match self.socket_write.send_to(&message, peer).await {
-                Ok(_) => {
-                    trace!("Successful send to {}", peer);
-                }
-                Err(e) => {
-                    error!("Failed send to {}: {:?}", peer, e);
-                    return Err(Error::new(Origin::Transport, Kind::Io, e))?;
-                }
-            }
+            self.send_packet(&message, peer).await?;
         }
 
         Ok(())
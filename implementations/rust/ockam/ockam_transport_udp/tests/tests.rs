@@ -1,8 +1,12 @@
 use ockam_core::compat::rand::{self, Rng};
 use ockam_core::{route, Result, Routed, Worker};
-use ockam_node::{Context, MessageSendReceiveOptions};
+use ockam_node::{Context, MessageReceiveOptions, MessageSendReceiveOptions};
 use ockam_transport_core::MAXIMUM_MESSAGE_LENGTH;
-use ockam_transport_udp::{UdpBindArguments, UdpBindOptions, UdpTransport, UDP};
+use ockam_transport_udp::{
+    RendezvousService, UdpBindArguments, UdpBindOptions, UdpNatMapping, UdpPunctureCandidates,
+    UdpPunctureNegotiation, UdpPunctureNegotiationListener, UdpPunctureNegotiationListenerOptions,
+    UdpPunctureOptions, UdpReliableDeliveryOptions, UdpTransport, UDP,
+};
 use std::net::SocketAddr;
 use std::time::Duration;
 use tracing::{debug, error, trace};
@@ -10,6 +14,7 @@ use tracing::{debug, error, trace};
 mod utils;
 
 const TIMEOUT: Duration = Duration::from_secs(5);
+const LOSSY_LINK_TIMEOUT: Duration = Duration::from_secs(20);
 
 /// When acting as a server, the transport should reply using the same
 /// UDP port that we sent to.
@@ -425,6 +430,268 @@ async fn send_receive_large_message(ctx: &mut Context) -> Result<()> {
     Ok(())
 }
 
+/// With the reliable delivery, messages go through a lossy link and are received in order
+#[ockam_macros::test(timeout = 60_000)]
+async fn send_receive_reliably_over_lossy_link(ctx: &mut Context) -> Result<()> {
+    // Find available ports
+    let bind_addrs = utils::available_local_ports(2).await?;
+    let (proxy1, proxy2) = utils::start_lossy_proxy(bind_addrs[0], bind_addrs[1], 0.2).await?;
+
+    // Don't back off the retransmissions for too long when the last packets are lost
+    let options = UdpReliableDeliveryOptions {
+        max_retransmission_timeout: Duration::from_secs(1),
+        ..Default::default()
+    };
+
+    // Transport
+    let transport = UdpTransport::create(ctx)?;
+
+    ctx.start_worker("echoer", Echoer::new(false))?;
+    let bind1 = transport
+        .bind(
+            UdpBindArguments::new()
+                .with_bind_socket_address(bind_addrs[0])
+                .with_peer_socket_address(proxy1),
+            UdpBindOptions::new().with_reliable_delivery(options),
+        )
+        .await?;
+    let bind2 = transport
+        .bind(
+            UdpBindArguments::new()
+                .with_bind_socket_address(bind_addrs[1])
+                .with_peer_socket_address(proxy2),
+            UdpBindOptions::new().with_reliable_delivery(options),
+        )
+        .await?;
+
+    ctx.flow_controls()
+        .add_consumer(&"echoer".into(), bind2.flow_control_id());
+    ctx.flow_controls()
+        .add_consumer(ctx.primary_address(), bind1.flow_control_id());
+
+    // A message split into many packets
+    let msg: String = rand::thread_rng()
+        .sample_iter(&rand::distributions::Alphanumeric)
+        .take(MAXIMUM_MESSAGE_LENGTH)
+        .map(char::from)
+        .collect();
+    let reply = ctx
+        .send_and_receive_extended::<String>(
+            route![bind1.sender_address().clone(), "echoer"],
+            msg.clone(),
+            MessageSendReceiveOptions::new().with_timeout(LOSSY_LINK_TIMEOUT),
+        )
+        .await?
+        .into_body()?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    // Many messages sent without waiting for the replies
+    let messages: Vec<String> = (0..100).map(|i| format!("message {i}")).collect();
+    for msg in &messages {
+        ctx.send(
+            route![bind1.sender_address().clone(), "echoer"],
+            msg.clone(),
+        )
+        .await?;
+    }
+    for msg in &messages {
+        let reply = ctx
+            .receive_extended::<String>(
+                MessageReceiveOptions::new().with_timeout(LOSSY_LINK_TIMEOUT),
+            )
+            .await?
+            .into_body()?;
+        assert_eq!(&reply, msg, "Should receive the messages in order");
+    }
+
+    Ok(())
+}
+
+/// When the puncture can't be opened, messages are relayed by the Rendezvous service
+#[ockam_macros::test(timeout = 60_000)]
+async fn puncture_falls_back_to_relay(ctx: &mut Context) -> Result<()> {
+    // Find available ports, and drop all the packets sent directly between the peers
+    let bind_addrs = utils::available_local_ports(2).await?;
+    let (proxy1, proxy2) = utils::start_lossy_proxy(bind_addrs[0], bind_addrs[1], 1.0).await?;
+
+    // Transport
+    let transport = UdpTransport::create(ctx)?;
+
+    RendezvousService::start(ctx, "rendezvous")?;
+    let rendezvous_bind = transport
+        .bind(UdpBindArguments::new(), UdpBindOptions::new())
+        .await?;
+    ctx.flow_controls()
+        .add_consumer(&"rendezvous".into(), rendezvous_bind.flow_control_id());
+    let rendezvous_route = route![
+        (UDP, rendezvous_bind.bind_address().to_string()),
+        "rendezvous"
+    ];
+
+    let bind1 = transport
+        .bind(
+            UdpBindArguments::new().with_bind_socket_address(bind_addrs[0]),
+            UdpBindOptions::new(),
+        )
+        .await?;
+    let bind2 = transport
+        .bind(
+            UdpBindArguments::new().with_bind_socket_address(bind_addrs[1]),
+            UdpBindOptions::new(),
+        )
+        .await?;
+
+    let mut puncture1 = transport.puncture(
+        bind1,
+        proxy1.to_string(),
+        "remote1".into(),
+        "remote2".into(),
+        UdpPunctureOptions::new().with_relay_fallback(rendezvous_route.clone(), "allocation"),
+        false,
+    )?;
+    let mut puncture2 = transport.puncture(
+        bind2,
+        proxy2.to_string(),
+        "remote2".into(),
+        "remote1".into(),
+        UdpPunctureOptions::new().with_relay_fallback(rendezvous_route, "allocation"),
+        false,
+    )?;
+
+    puncture1.wait_for_puncture(LOSSY_LINK_TIMEOUT).await?;
+    puncture2.wait_for_puncture(LOSSY_LINK_TIMEOUT).await?;
+
+    ctx.start_worker("echoer", Echoer::new(false))?;
+    ctx.flow_controls()
+        .add_consumer(&"echoer".into(), puncture2.flow_control_id());
+    ctx.flow_controls()
+        .add_consumer(ctx.primary_address(), puncture1.flow_control_id());
+
+    let msg = "hello through the relay".to_string();
+    let reply = ctx
+        .send_and_receive_extended::<String>(
+            route![puncture1.sender_address(), "echoer"],
+            msg.clone(),
+            MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+        )
+        .await?
+        .into_body()?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    Ok(())
+}
+
+/// A peer behind an address-dependent NAT pings from an address which is not one of its
+/// candidates. Its pings must still be answered directly
+#[ockam_macros::test(timeout = 60_000)]
+async fn puncture_answers_pings_from_a_peer_reflexive_address(ctx: &mut Context) -> Result<()> {
+    // The third port stays unused: it is the mapping of the first peer known by the second
+    // peer, which doesn't forward anything
+    let bind_addrs = utils::available_local_ports(3).await?;
+
+    // Transport
+    let transport = UdpTransport::create(ctx)?;
+
+    let bind1 = transport
+        .bind(
+            UdpBindArguments::new().with_bind_socket_address(bind_addrs[0]),
+            UdpBindOptions::new(),
+        )
+        .await?;
+    let bind2 = transport
+        .bind(
+            UdpBindArguments::new().with_bind_socket_address(bind_addrs[1]),
+            UdpBindOptions::new(),
+        )
+        .await?;
+
+    let mut puncture1 = transport.puncture(
+        bind1,
+        bind_addrs[1].to_string(),
+        "remote1".into(),
+        "remote2".into(),
+        UdpPunctureOptions::new(),
+        false,
+    )?;
+    let _puncture2 = transport.puncture(
+        bind2,
+        bind_addrs[2].to_string(),
+        "remote2".into(),
+        "remote1".into(),
+        UdpPunctureOptions::new(),
+        false,
+    )?;
+
+    puncture1.wait_for_puncture(TIMEOUT).await?;
+
+    Ok(())
+}
+
+/// The address a peer pings from is used to reach it when none of its candidates can be
+/// reached, and the reliable delivery follows the selected address
+#[ockam_macros::test(timeout = 60_000)]
+async fn puncture_opens_on_the_peer_reflexive_address_of_a_ping(ctx: &mut Context) -> Result<()> {
+    // The third port stays unused: it is the mapping of the first peer known by the second
+    // peer, which doesn't forward anything
+    let bind_addrs = utils::available_local_ports(3).await?;
+
+    // Transport
+    let transport = UdpTransport::create(ctx)?;
+
+    let bind1 = transport
+        .bind(
+            UdpBindArguments::new().with_bind_socket_address(bind_addrs[0]),
+            UdpBindOptions::new(),
+        )
+        .await?;
+    let bind2 = transport
+        .bind(
+            UdpBindArguments::new().with_bind_socket_address(bind_addrs[1]),
+            UdpBindOptions::new(),
+        )
+        .await?;
+
+    let reliable_delivery = UdpReliableDeliveryOptions::default();
+    let mut puncture1 = transport.puncture(
+        bind1,
+        bind_addrs[1].to_string(),
+        "remote1".into(),
+        "remote2".into(),
+        UdpPunctureOptions::new().with_reliable_delivery(reliable_delivery),
+        false,
+    )?;
+    let mut puncture2 = transport.puncture(
+        bind2,
+        bind_addrs[2].to_string(),
+        "remote2".into(),
+        "remote1".into(),
+        UdpPunctureOptions::new().with_reliable_delivery(reliable_delivery),
+        false,
+    )?;
+
+    puncture1.wait_for_puncture(TIMEOUT).await?;
+    puncture2.wait_for_puncture(TIMEOUT).await?;
+
+    ctx.start_worker("echoer", Echoer::new(false))?;
+    ctx.flow_controls()
+        .add_consumer(&"echoer".into(), puncture1.flow_control_id());
+    ctx.flow_controls()
+        .add_consumer(ctx.primary_address(), puncture2.flow_control_id());
+
+    let msg = "hello from the second peer".to_string();
+    let reply = ctx
+        .send_and_receive_extended::<String>(
+            route![puncture2.sender_address(), "echoer"],
+            msg.clone(),
+            MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+        )
+        .await?
+        .into_body()?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    Ok(())
+}
+
 pub struct Echoer {
     check_sender_is_the_same: bool,
     prev_src_addr: Option<String>,
@@ -482,3 +749,80 @@ impl Worker for Echoer {
         ctx.send(msg.return_route().clone(), msg.into_body()?).await
     }
 }
+
+/// The negotiation gathers the candidates of both peers using all the Rendezvous services,
+/// and opens the puncture on the best candidate pair
+#[ockam_macros::test(timeout = 60_000)]
+async fn negotiation_with_multiple_rendezvous_services(ctx: &mut Context) -> Result<()> {
+    // Transport
+    let transport = UdpTransport::create(ctx)?;
+
+    // The same service, reachable on two different UDP addresses
+    RendezvousService::start(ctx, "rendezvous")?;
+    let mut rendezvous_routes = vec![];
+    for _ in 0..2 {
+        let rendezvous_bind = transport
+            .bind(UdpBindArguments::new(), UdpBindOptions::new())
+            .await?;
+        ctx.flow_controls()
+            .add_consumer(&"rendezvous".into(), rendezvous_bind.flow_control_id());
+        rendezvous_routes.push(route![
+            (UDP, rendezvous_bind.bind_address().to_string()),
+            "rendezvous"
+        ]);
+    }
+
+    let bind = transport
+        .bind(
+            UdpBindArguments::new().with_bind_address("127.0.0.1:0")?,
+            UdpBindOptions::new(),
+        )
+        .await?;
+    let candidates = UdpPunctureCandidates::gather(ctx, &bind, &rendezvous_routes).await?;
+    assert_eq!(candidates.nat_mapping(), UdpNatMapping::NoNat);
+    assert_eq!(
+        candidates.public_address(),
+        bind.bind_address().to_string(),
+        "Without NAT, the public address is the bind address"
+    );
+    transport.unbind(bind.sender_address())?;
+
+    let options = UdpPunctureNegotiationListenerOptions::new();
+    let listener_flow_control_id = options.flow_control_id();
+    UdpPunctureNegotiationListener::create(
+        ctx,
+        "negotiation_listener",
+        &transport,
+        rendezvous_routes.clone(),
+        options,
+    )?;
+
+    let mut puncture = UdpPunctureNegotiation::start_negotiation(
+        ctx,
+        route!["negotiation_listener"],
+        &transport,
+        rendezvous_routes,
+        TIMEOUT,
+    )
+    .await?;
+    puncture.wait_for_puncture(TIMEOUT).await?;
+
+    ctx.start_worker("echoer", Echoer::new(false))?;
+    ctx.flow_controls()
+        .add_consumer(&"echoer".into(), &listener_flow_control_id);
+    ctx.flow_controls()
+        .add_consumer(ctx.primary_address(), puncture.flow_control_id());
+
+    let msg = "hello through the puncture".to_string();
+    let reply = ctx
+        .send_and_receive_extended::<String>(
+            route![puncture.sender_address(), "echoer"],
+            msg.clone(),
+            MessageSendReceiveOptions::new().with_timeout(TIMEOUT),
+        )
+        .await?
+        .into_body()?;
+    assert_eq!(reply, msg, "Should receive the same message");
+
+    Ok(())
+}
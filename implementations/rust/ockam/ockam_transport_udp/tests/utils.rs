@@ -1,3 +1,5 @@
+use ockam_core::compat::rand::{self, Rng};
+use ockam_core::compat::sync::Arc;
 use ockam_core::{errcode::Origin, Error, Result};
 use std::net::SocketAddr;
 use tokio::net::UdpSocket;
@@ -25,3 +27,46 @@ pub async fn available_local_ports(count: usize) -> Result<Vec<SocketAddr>> {
 
     Ok(addrs)
 }
+
+/// Forward UDP packets between two peers, dropping a fraction of them.
+///
+/// The first peer must send its packets to the first returned address, and the second peer to
+/// the second returned address. Each peer receives the packets of the other peer from the
+/// address it sends its own packets to.
+pub async fn start_lossy_proxy(
+    first_peer: SocketAddr,
+    second_peer: SocketAddr,
+    loss: f64,
+) -> Result<(SocketAddr, SocketAddr)> {
+    let bind = || async {
+        let socket = UdpSocket::bind(AVAILABLE_LOCAL_PORTS_ADDR)
+            .await
+            .map_err(|e| Error::new_unknown(Origin::Unknown, e))?;
+        let address = socket
+            .local_addr()
+            .map_err(|e| Error::new_unknown(Origin::Unknown, e))?;
+        Ok::<_, Error>((Arc::new(socket), address))
+    };
+    let (first_socket, first_address) = bind().await?;
+    let (second_socket, second_address) = bind().await?;
+
+    tokio::spawn(forward_lossy(
+        first_socket.clone(),
+        second_socket.clone(),
+        second_peer,
+        loss,
+    ));
+    tokio::spawn(forward_lossy(second_socket, first_socket, first_peer, loss));
+
+    Ok((first_address, second_address))
+}
+
+async fn forward_lossy(from: Arc<UdpSocket>, to: Arc<UdpSocket>, peer: SocketAddr, loss: f64) {
+    let mut buffer = vec![0; 65536];
+    while let Ok((len, _)) = from.recv_from(&mut buffer).await {
+        if rand::thread_rng().gen_bool(loss) {
+            continue;
+        }
+        let _ = to.send_to(&buffer[..len], peer).await;
+    }
+}
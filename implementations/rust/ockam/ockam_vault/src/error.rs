@@ -39,6 +39,8 @@ pub enum VaultError {
     InsufficientEncryptBuffer,
     /// Buffer is too short during decryption
     InsufficientDecryptBuffer,
+    /// ML-KEM encapsulation or decapsulation failed
+    MlKemError,
 }
 
 impl ockam_core::compat::error::Error for VaultError {}
@@ -63,6 +65,7 @@ impl core::fmt::Display for VaultError {
             Self::AeadSecretNotFound => write!(f, "aead secret was not found in the storage"),
             Self::InsufficientEncryptBuffer => write!(f, "insufficient encrypt buffer"),
             Self::InsufficientDecryptBuffer => write!(f, "insufficient decrypt buffer"),
+            Self::MlKemError => write!(f, "ml-kem operation failed"),
         }
     }
 }
@@ -51,8 +51,10 @@ impl StoredSecret {
     }
 }
 
-impl From<SigningSecret> for StoredSecret {
-    fn from(value: SigningSecret) -> Self {
+impl TryFrom<SigningSecret> for StoredSecret {
+    type Error = Error;
+
+    fn try_from(value: SigningSecret) -> Result<Self, Self::Error> {
         let (secret, attributes) = match value {
             SigningSecret::EdDSACurve25519(value) => {
                 (value.key().to_vec(), SecretAttributes::Ed25519)
@@ -60,11 +62,13 @@ impl From<SigningSecret> for StoredSecret {
             SigningSecret::ECDSASHA256CurveP256(value) => {
                 (value.key().to_vec(), SecretAttributes::NistP256)
             }
+            // The legacy format doesn't support P-384 keys
+            SigningSecret::ECDSASHA384CurveP384(_) => Err(VaultError::InvalidKeyType)?,
         };
 
         let secret = Secret::new(secret);
 
-        Self::new(secret, attributes)
+        Ok(Self::new(secret, attributes))
     }
 }
 
@@ -11,10 +11,10 @@ compile_error! {"One feature must be enabled: \"aws-lc\" or \"rust-crypto\""}
 cfg_if! {
     if #[cfg(feature = "aws-lc")] {
         mod aes_aws_lc;
-        use aes_aws_lc::make_aes;
+        pub(crate) use aes_aws_lc::make_aes;
     } else {
         mod aes_rs;
-        use aes_rs::make_aes;
+        pub(crate) use aes_rs::make_aes;
     }
 }
 
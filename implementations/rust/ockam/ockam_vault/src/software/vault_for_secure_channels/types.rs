@@ -16,11 +16,27 @@ impl X25519SecretKey {
         Self(key)
     }
 
-    pub(crate) fn key(&self) -> &[u8; X25519_SECRET_KEY_LENGTH] {
+    /// Return the secret key
+    pub fn key(&self) -> &[u8; X25519_SECRET_KEY_LENGTH] {
         &self.0
     }
 }
 
+/// ML-KEM-768 decapsulation key, in its encoded form.
+#[derive(Eq, PartialEq, Clone, Zeroize, ZeroizeOnDrop)]
+pub struct MlKemSecretKey(Vec<u8>);
+
+impl MlKemSecretKey {
+    /// Constructor.
+    pub fn new(key: Vec<u8>) -> Self {
+        Self(key)
+    }
+
+    pub(crate) fn key(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
 /// Buffer with sensitive data, like HKDF output.
 #[derive(Eq, PartialEq, Clone, Zeroize, ZeroizeOnDrop)]
 pub struct BufferSecret(Vec<u8>);
@@ -1,3 +1,5 @@
+use ml_kem::kem::{Decapsulate, DecapsulationKey, Encapsulate, EncapsulationKey};
+use ml_kem::{EncodedSizeUser, KemCore, MlKem768, MlKem768Params};
 use sha2::{Digest, Sha256};
 use tracing::instrument;
 
@@ -15,9 +17,9 @@ use crate::storage::SecretsSqlxDatabase;
 
 use crate::{
     AeadSecret, AeadSecretKeyHandle, BufferSecret, HKDFNumberOfOutputs, HandleToSecret, HashOutput,
-    HkdfOutput, SecretBufferHandle, SoftwareVaultForVerifyingSignatures, VaultError,
-    VaultForSecureChannels, X25519PublicKey, X25519SecretKey, X25519SecretKeyHandle,
-    AEAD_SECRET_LENGTH,
+    HkdfOutput, MlKemCiphertext, MlKemPublicKey, MlKemSecretKey, MlKemSecretKeyHandle,
+    SecretBufferHandle, SoftwareVaultForVerifyingSignatures, VaultError, VaultForSecureChannels,
+    X25519PublicKey, X25519SecretKey, X25519SecretKeyHandle, AEAD_SECRET_LENGTH,
 };
 
 use super::make_aes;
@@ -27,6 +29,7 @@ pub struct SoftwareVaultForSecureChannels {
     ephemeral_buffer_secrets: Arc<RwLock<BTreeMap<SecretBufferHandle, BufferSecret>>>,
     ephemeral_aead_secrets: Arc<RwLock<BTreeMap<AeadSecretKeyHandle, AeadSecret>>>,
     ephemeral_x25519_secrets: Arc<RwLock<BTreeMap<X25519SecretKeyHandle, X25519SecretKey>>>,
+    ephemeral_ml_kem_secrets: Arc<RwLock<BTreeMap<MlKemSecretKeyHandle, MlKemSecretKey>>>,
     secrets_repository: Arc<dyn SecretsRepository>,
 }
 
@@ -37,6 +40,7 @@ impl SoftwareVaultForSecureChannels {
             ephemeral_buffer_secrets: Default::default(),
             ephemeral_aead_secrets: Default::default(),
             ephemeral_x25519_secrets: Default::default(),
+            ephemeral_ml_kem_secrets: Default::default(),
             secrets_repository,
         }
     }
@@ -102,6 +106,11 @@ impl SoftwareVaultForSecureChannels {
         self.ephemeral_x25519_secrets.read().unwrap().len()
     }
 
+    /// Return the total number of ephemeral ML-KEM secrets present in the Vault
+    pub fn number_of_ephemeral_ml_kem_secrets(&self) -> usize {
+        self.ephemeral_ml_kem_secrets.read().unwrap().len()
+    }
+
     /// Return the total number of ephemeral buffer secrets present in the Vault
     pub fn number_of_ephemeral_buffer_secrets(&self) -> usize {
         self.ephemeral_buffer_secrets.read().unwrap().len()
@@ -171,6 +180,23 @@ impl SoftwareVaultForSecureChannels {
         X25519SecretKey::new(secret.to_bytes())
     }
 
+    fn import_ml_kem_secret_key(
+        secret: &MlKemSecretKey,
+    ) -> Result<DecapsulationKey<MlKem768Params>> {
+        let encoded = secret
+            .key()
+            .try_into()
+            .map_err(|_| VaultError::InvalidSecretLength)?;
+        Ok(DecapsulationKey::from_bytes(encoded))
+    }
+
+    fn get_ml_kem_secret(&self, handle: &MlKemSecretKeyHandle) -> Result<MlKemSecretKey> {
+        match self.ephemeral_ml_kem_secrets.read().unwrap().get(handle) {
+            Some(secret) => Ok(secret.clone()),
+            None => Err(VaultError::KeyNotFound)?,
+        }
+    }
+
     fn import_buffer_secret_impl(&self, secret: BufferSecret) -> SecretBufferHandle {
         let handle = Self::generate_buffer_handle();
 
@@ -382,6 +408,70 @@ impl VaultForSecureChannels for SoftwareVaultForSecureChannels {
         Ok(Self::compute_handle_for_public_key(public_key))
     }
 
+    async fn generate_ephemeral_ml_kem_secret_key(&self) -> Result<MlKemSecretKeyHandle> {
+        let (decapsulation_key, _) = MlKem768::generate(&mut thread_rng());
+        let secret = MlKemSecretKey::new(decapsulation_key.as_bytes().to_vec());
+        let handle = MlKemSecretKeyHandle(Self::generate_random_handle());
+
+        self.ephemeral_ml_kem_secrets
+            .write()
+            .unwrap()
+            .insert(handle.clone(), secret);
+
+        Ok(handle)
+    }
+
+    async fn delete_ephemeral_ml_kem_secret_key(
+        &self,
+        secret_key_handle: MlKemSecretKeyHandle,
+    ) -> Result<bool> {
+        Ok(self
+            .ephemeral_ml_kem_secrets
+            .write()
+            .unwrap()
+            .remove(&secret_key_handle)
+            .is_some())
+    }
+
+    async fn get_ml_kem_public_key(
+        &self,
+        secret_key_handle: &MlKemSecretKeyHandle,
+    ) -> Result<MlKemPublicKey> {
+        let secret = self.get_ml_kem_secret(secret_key_handle)?;
+        let decapsulation_key = Self::import_ml_kem_secret_key(&secret)?;
+        let public_key = decapsulation_key.encapsulation_key().as_bytes();
+
+        Ok(MlKemPublicKey(public_key.into()))
+    }
+
+    async fn ml_kem_encapsulate(
+        &self,
+        peer_public_key: &MlKemPublicKey,
+    ) -> Result<(MlKemCiphertext, SecretBufferHandle)> {
+        let encapsulation_key =
+            EncapsulationKey::<MlKem768Params>::from_bytes(&peer_public_key.0.into());
+        let (ciphertext, shared_secret) = encapsulation_key
+            .encapsulate(&mut thread_rng())
+            .map_err(|_| VaultError::MlKemError)?;
+        let handle = self.import_buffer_secret_impl(BufferSecret::new(shared_secret.to_vec()));
+
+        Ok((MlKemCiphertext(ciphertext.into()), handle))
+    }
+
+    async fn ml_kem_decapsulate(
+        &self,
+        secret_key_handle: &MlKemSecretKeyHandle,
+        ciphertext: &MlKemCiphertext,
+    ) -> Result<SecretBufferHandle> {
+        let secret = self.get_ml_kem_secret(secret_key_handle)?;
+        let decapsulation_key = Self::import_ml_kem_secret_key(&secret)?;
+        let shared_secret = decapsulation_key
+            .decapsulate(&ciphertext.0.into())
+            .map_err(|_| VaultError::MlKemError)?;
+
+        Ok(self.import_buffer_secret_impl(BufferSecret::new(shared_secret.to_vec())))
+    }
+
     async fn import_secret_buffer(&self, buffer: Vec<u8>) -> Result<SecretBufferHandle> {
         Ok(self.import_buffer_secret_impl(BufferSecret::new(buffer)))
     }
@@ -8,6 +8,9 @@ pub const EDDSA_CURVE25519_SECRET_KEY_LENGTH: usize = 32;
 /// NIST P256 private key length.
 pub const ECDSA_SHA256_CURVEP256_SECRET_KEY_LENGTH: usize = 32;
 
+/// NIST P384 private key length.
+pub const ECDSA_SHA384_CURVEP384_SECRET_KEY_LENGTH: usize = 48;
+
 /// EdDSACurve25519 Secret Key.
 #[derive(Eq, PartialEq, Clone, Zeroize, ZeroizeOnDrop)]
 pub struct EdDSACurve25519SecretKey([u8; EDDSA_CURVE25519_SECRET_KEY_LENGTH]);
@@ -38,6 +41,21 @@ impl ECDSASHA256CurveP256SecretKey {
     }
 }
 
+/// ECDSASHA384CurveP384 Secret Key.
+#[derive(Eq, PartialEq, Clone, Zeroize, ZeroizeOnDrop)]
+pub struct ECDSASHA384CurveP384SecretKey([u8; ECDSA_SHA384_CURVEP384_SECRET_KEY_LENGTH]);
+
+impl ECDSASHA384CurveP384SecretKey {
+    /// Constructor.
+    pub fn new(key: [u8; ECDSA_SHA384_CURVEP384_SECRET_KEY_LENGTH]) -> Self {
+        Self(key)
+    }
+
+    pub(crate) fn key(&self) -> &[u8; ECDSA_SHA384_CURVEP384_SECRET_KEY_LENGTH] {
+        &self.0
+    }
+}
+
 /// Signing secret binary
 #[derive(Eq, PartialEq, Clone, Zeroize)]
 pub enum SigningSecret {
@@ -45,14 +63,17 @@ pub enum SigningSecret {
     EdDSACurve25519(EdDSACurve25519SecretKey),
     /// Curve P-256 key that is only used for ECDSA SHA256 signatures.
     ECDSASHA256CurveP256(ECDSASHA256CurveP256SecretKey),
+    /// Curve P-384 key that is only used for ECDSA SHA384 signatures.
+    ECDSASHA384CurveP384(ECDSASHA384CurveP384SecretKey),
 }
 
 impl SigningSecret {
     /// Return the secret key
-    pub fn key(&self) -> &[u8; 32] {
+    pub fn key(&self) -> &[u8] {
         match self {
             SigningSecret::EdDSACurve25519(k) => k.key(),
             SigningSecret::ECDSASHA256CurveP256(k) => k.key(),
+            SigningSecret::ECDSASHA384CurveP384(k) => k.key(),
         }
     }
 }
@@ -1,12 +1,14 @@
 use crate::{
     ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256SecretKey, ECDSASHA256CurveP256Signature,
+    ECDSASHA384CurveP384PublicKey, ECDSASHA384CurveP384SecretKey, ECDSASHA384CurveP384Signature,
     EdDSACurve25519PublicKey, EdDSACurve25519SecretKey, EdDSACurve25519Signature, HandleToSecret,
     Signature, SigningKeyType, SigningSecret, SigningSecretKeyHandle, VaultError, VaultForSigning,
     VerifyingPublicKey,
 };
 use crate::{
     ECDSA_SHA256_CURVEP256_PUBLIC_KEY_LENGTH, ECDSA_SHA256_CURVEP256_SECRET_KEY_LENGTH,
-    EDDSA_CURVE25519_SECRET_KEY_LENGTH,
+    ECDSA_SHA384_CURVEP384_PUBLIC_KEY_LENGTH, ECDSA_SHA384_CURVEP384_SECRET_KEY_LENGTH,
+    ECDSA_SHA384_CURVEP384_SIGNATURE_LENGTH, EDDSA_CURVE25519_SECRET_KEY_LENGTH,
 };
 
 use crate::storage::SecretsRepository;
@@ -84,6 +86,18 @@ impl VaultForSigning for SoftwareVaultForSigning {
                 let signature = ECDSASHA256CurveP256Signature(signature.into());
                 let signature = Signature::ECDSASHA256CurveP256(signature);
 
+                Ok(signature)
+            }
+            SigningSecret::ECDSASHA384CurveP384(secret) => {
+                use p384::ecdsa::signature::Signer;
+                let key = Self::import_p384_key(secret.key())?;
+                let signature: p384::ecdsa::Signature = key.sign(data);
+                let signature = signature.to_bytes();
+                let signature = *array_ref![signature, 0, ECDSA_SHA384_CURVEP384_SIGNATURE_LENGTH];
+
+                let signature = ECDSASHA384CurveP384Signature(signature);
+                let signature = Signature::ECDSASHA384CurveP384(signature);
+
                 Ok(signature)
             }
         }
@@ -110,6 +124,14 @@ impl VaultForSigning for SoftwareVaultForSigning {
 
                 SigningSecret::ECDSASHA256CurveP256(signing_key)
             }
+            SigningKeyType::ECDSASHA384CurveP384 => {
+                // Somewhat special random 48 bytes
+                let signing_key = p384::ecdsa::SigningKey::random(&mut thread_rng());
+                let signing_key = signing_key.to_bytes();
+                let signing_key = ECDSASHA384CurveP384SecretKey::new(signing_key.into());
+
+                SigningSecret::ECDSASHA384CurveP384(signing_key)
+            }
         };
 
         let handle = self.import_key(key).await?;
@@ -155,7 +177,13 @@ impl SoftwareVaultForSigning {
     fn import_p256_key(
         key: &[u8; ECDSA_SHA256_CURVEP256_SECRET_KEY_LENGTH],
     ) -> Result<p256::ecdsa::SigningKey> {
-        p256::ecdsa::SigningKey::from_bytes(key.as_ref().into()).map_err(Self::from_bytes)
+        p256::ecdsa::SigningKey::from_bytes(key.into()).map_err(Self::from_bytes)
+    }
+
+    fn import_p384_key(
+        key: &[u8; ECDSA_SHA384_CURVEP384_SECRET_KEY_LENGTH],
+    ) -> Result<p384::ecdsa::SigningKey> {
+        p384::ecdsa::SigningKey::from_bytes(key.into()).map_err(Self::from_bytes)
     }
 
     fn import_ed25519_key(
@@ -190,6 +218,22 @@ impl SoftwareVaultForSigning {
                 let verifying_key = ECDSASHA256CurveP256PublicKey(verifying_key);
                 let verifying_key = VerifyingPublicKey::ECDSASHA256CurveP256(verifying_key);
 
+                Ok(verifying_key)
+            }
+            SigningSecret::ECDSASHA384CurveP384(key) => {
+                let signing_key = Self::import_p384_key(key.key())?;
+                let verifying_key = signing_key.verifying_key();
+                let verifying_key = verifying_key.to_sec1_bytes().to_vec();
+
+                if verifying_key.len() != ECDSA_SHA384_CURVEP384_PUBLIC_KEY_LENGTH {
+                    return Err(VaultError::InvalidPublicLength)?;
+                }
+
+                let verifying_key =
+                    *array_ref![verifying_key, 0, ECDSA_SHA384_CURVEP384_PUBLIC_KEY_LENGTH];
+                let verifying_key = ECDSASHA384CurveP384PublicKey(verifying_key);
+                let verifying_key = VerifyingPublicKey::ECDSASHA384CurveP384(verifying_key);
+
                 Ok(verifying_key)
             }
         }
@@ -209,6 +253,11 @@ impl SoftwareVaultForSigning {
                 let handle = HandleToSecret::new(digest.to_vec());
                 SigningSecretKeyHandle::ECDSASHA256CurveP256(handle)
             }
+            VerifyingPublicKey::ECDSASHA384CurveP384(public_key) => {
+                let digest = Sha256::digest(public_key.0);
+                let handle = HandleToSecret::new(digest.to_vec());
+                SigningSecretKeyHandle::ECDSASHA384CurveP384(handle)
+            }
         };
 
         Ok(handle)
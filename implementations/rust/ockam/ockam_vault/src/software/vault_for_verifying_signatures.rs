@@ -1,6 +1,6 @@
 use crate::{
-    ECDSASHA256CurveP256PublicKey, EdDSACurve25519PublicKey, Sha256Output, Signature, VaultError,
-    VaultForVerifyingSignatures, VerifyingPublicKey,
+    ECDSASHA256CurveP256PublicKey, ECDSASHA384CurveP384PublicKey, EdDSACurve25519PublicKey,
+    Sha256Output, Signature, VaultError, VaultForVerifyingSignatures, VerifyingPublicKey,
 };
 
 use ockam_core::compat::sync::Arc;
@@ -53,6 +53,12 @@ impl SoftwareVaultForVerifyingSignatures {
         Error::new(Origin::Vault, Kind::Unknown, e)
     }
 
+    fn import_p384_key(
+        public_key: &ECDSASHA384CurveP384PublicKey,
+    ) -> Result<p384::ecdsa::VerifyingKey> {
+        p384::ecdsa::VerifyingKey::from_sec1_bytes(&public_key.0).map_err(Self::from_pkcs8)
+    }
+
     fn import_p256_key(
         public_key: &ECDSASHA256CurveP256PublicKey,
     ) -> Result<p256::ecdsa::VerifyingKey> {
@@ -107,6 +113,18 @@ impl SoftwareVaultForVerifyingSignatures {
                 use p256::ecdsa::signature::Verifier;
                 Ok(verifying_public_key.verify(data, &signature).is_ok())
             }
+            (
+                VerifyingPublicKey::ECDSASHA384CurveP384(verifying_public_key),
+                Signature::ECDSASHA384CurveP384(signature),
+            ) => {
+                let verifying_public_key = Self::import_p384_key(verifying_public_key)?;
+
+                let signature =
+                    p384::ecdsa::Signature::from_slice(&signature.0).map_err(Self::from_ecdsa)?;
+
+                use p384::ecdsa::signature::Verifier;
+                Ok(verifying_public_key.verify(data, &signature).is_ok())
+            }
             _ => Err(VaultError::SignatureAndPublicKeyTypesDontMatch)?,
         }
     }
@@ -0,0 +1,321 @@
+use argon2::Argon2;
+use ockam_core::compat::boxed::Box;
+use ockam_core::compat::rand::{thread_rng, RngCore};
+use ockam_core::compat::string::String;
+use ockam_core::compat::vec::Vec;
+use ockam_core::errcode::{Kind, Origin};
+use ockam_core::{async_trait, Result};
+use sha2::{Digest, Sha256};
+use zeroize::Zeroize;
+
+use crate::software::make_aes;
+use crate::{AeadSecret, VaultError, AEAD_SECRET_LENGTH, AES_NONCE_LENGTH};
+
+/// Environment variable containing a hex-encoded key-encryption key
+pub const OCKAM_VAULT_KEK: &str = "OCKAM_VAULT_KEK";
+
+/// Environment variable containing a passphrase used to derive a key-encryption key
+pub const OCKAM_VAULT_PASSPHRASE: &str = "OCKAM_VAULT_PASSPHRASE";
+
+/// Length of the salt used to derive a key-encryption key from a passphrase
+pub(crate) const PASSPHRASE_SALT_LENGTH: usize = 16;
+
+/// Length of the authentication tag appended to encrypted data
+const TAG_LENGTH: usize = 16;
+
+/// Additional data used when encrypting a data key
+const WRAPPED_KEY_AAD: &[u8] = b"ockam-vault-data-key";
+
+/// A key-encryption key (KEK) protects the data keys which are used to encrypt secrets at rest.
+///
+/// This trait can be implemented to keep the key-encryption key outside of the node,
+/// for example in a KMS, similarly to what is done for signing keys with [`crate::VaultForSigning`].
+#[async_trait]
+pub trait KeyEncryptionKeyProvider: Send + Sync + 'static {
+    /// Return an identifier for the key-encryption key. It is stored alongside each
+    /// encrypted data key in order to detect when a different key is configured.
+    fn key_id(&self) -> String;
+
+    /// Encrypt a data key
+    async fn wrap_key(&self, data_key: &[u8]) -> Result<Vec<u8>>;
+
+    /// Decrypt a data key
+    async fn unwrap_key(&self, wrapped_key: &[u8]) -> Result<Vec<u8>>;
+}
+
+/// Key-encryption key held in memory
+pub struct KeyEncryptionKey {
+    secret: AeadSecret,
+    key_id: String,
+}
+
+impl KeyEncryptionKey {
+    /// Create a key-encryption key from a secret
+    pub fn new(secret: AeadSecret) -> Self {
+        let digest = Sha256::new()
+            .chain_update(b"ockam-vault-kek")
+            .chain_update(secret.0)
+            .finalize();
+        let key_id = hex::encode(&digest[..8]);
+        Self { secret, key_id }
+    }
+
+    /// Create a key-encryption key from a hex-encoded secret
+    pub fn from_hex(key: &str) -> Result<Self> {
+        let mut bytes = hex::decode(key.trim()).map_err(|_| VaultError::InvalidSecretLength)?;
+        let secret: Result<[u8; AEAD_SECRET_LENGTH]> = bytes
+            .as_slice()
+            .try_into()
+            .map_err(|_| VaultError::InvalidSecretLength.into());
+        bytes.zeroize();
+        Ok(Self::new(AeadSecret(secret?)))
+    }
+
+    /// Derive a key-encryption key from a passphrase with Argon2
+    pub fn from_passphrase(passphrase: &str, salt: &[u8]) -> Result<Self> {
+        let mut secret = [0u8; AEAD_SECRET_LENGTH];
+        Argon2::default()
+            .hash_password_into(passphrase.as_bytes(), salt, &mut secret)
+            .map_err(|e| {
+                ockam_core::Error::new(
+                    Origin::Vault,
+                    Kind::Invalid,
+                    format!("cannot derive a key-encryption key from a passphrase: {e}"),
+                )
+            })?;
+        Ok(Self::new(AeadSecret(secret)))
+    }
+
+    /// Encrypt some data which is not a data key, for example an exported vault.
+    ///
+    /// The additional data identifies the kind of data which is encrypted, so that it can't be
+    /// decrypted as a data key, and must be different from the additional data used for data keys.
+    pub fn encrypt(&self, data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        Self::check_aad(aad)?;
+        encrypt(&self.secret, data, aad)
+    }
+
+    /// Decrypt some data encrypted with [`KeyEncryptionKey::encrypt`]
+    pub fn decrypt(&self, data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        Self::check_aad(aad)?;
+        decrypt(&self.secret, data, aad)
+    }
+
+    /// The additional data used to encrypt data keys is reserved
+    fn check_aad(aad: &[u8]) -> Result<()> {
+        if aad == WRAPPED_KEY_AAD {
+            return Err(ockam_core::Error::new(
+                Origin::Vault,
+                Kind::Invalid,
+                "the additional data used to encrypt data keys can't be used for other data",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl KeyEncryptionKeyProvider for KeyEncryptionKey {
+    fn key_id(&self) -> String {
+        self.key_id.clone()
+    }
+
+    async fn wrap_key(&self, data_key: &[u8]) -> Result<Vec<u8>> {
+        encrypt(&self.secret, data_key, WRAPPED_KEY_AAD)
+    }
+
+    async fn unwrap_key(&self, wrapped_key: &[u8]) -> Result<Vec<u8>> {
+        decrypt(&self.secret, wrapped_key, WRAPPED_KEY_AAD)
+    }
+}
+
+/// Secret encrypted with a data key
+pub(crate) struct EncryptedSecret {
+    pub(crate) ciphertext: Vec<u8>,
+    pub(crate) wrapped_key: Vec<u8>,
+    pub(crate) kek_id: String,
+}
+
+/// Encrypt a secret with a new data key, which is itself encrypted with the key-encryption key.
+/// The secret handle is authenticated so that an encrypted secret can not be moved to another row.
+pub(crate) async fn encrypt_secret(
+    kek: &dyn KeyEncryptionKeyProvider,
+    handle: &[u8],
+    secret: &[u8],
+) -> Result<EncryptedSecret> {
+    let mut data_key = [0u8; AEAD_SECRET_LENGTH];
+    thread_rng().fill_bytes(&mut data_key);
+    let data_key = AeadSecret(data_key);
+
+    Ok(EncryptedSecret {
+        ciphertext: encrypt(&data_key, secret, handle)?,
+        wrapped_key: kek.wrap_key(&data_key.0).await?,
+        kek_id: kek.key_id(),
+    })
+}
+
+/// Decrypt a secret encrypted with [`encrypt_secret`]
+pub(crate) async fn decrypt_secret(
+    kek: &dyn KeyEncryptionKeyProvider,
+    handle: &[u8],
+    ciphertext: &[u8],
+    wrapped_key: &[u8],
+    kek_id: &str,
+) -> Result<Vec<u8>> {
+    check_key_id(kek, kek_id)?;
+    let mut data_key = kek.unwrap_key(wrapped_key).await?;
+    let data_key_secret: Result<[u8; AEAD_SECRET_LENGTH]> = data_key
+        .as_slice()
+        .try_into()
+        .map_err(|_| VaultError::InvalidSecretLength.into());
+    data_key.zeroize();
+    decrypt(&AeadSecret(data_key_secret?), ciphertext, handle)
+}
+
+/// Decrypt a data key with the current key-encryption key and encrypt it with a new one
+pub(crate) async fn rewrap_data_key(
+    current: &dyn KeyEncryptionKeyProvider,
+    new: &dyn KeyEncryptionKeyProvider,
+    wrapped_key: &[u8],
+    kek_id: &str,
+) -> Result<Vec<u8>> {
+    check_key_id(current, kek_id)?;
+    let mut data_key = current.unwrap_key(wrapped_key).await?;
+    let result = new.wrap_key(&data_key).await;
+    data_key.zeroize();
+    result
+}
+
+/// Return an error when encrypted secrets are read without a key-encryption key
+pub(crate) fn missing_key_encryption_key() -> ockam_core::Error {
+    ockam_core::Error::new(
+        Origin::Vault,
+        Kind::NotFound,
+        format!("the secrets are encrypted but no key-encryption key is configured. Please set the {OCKAM_VAULT_KEK} or the {OCKAM_VAULT_PASSPHRASE} environment variable"),
+    )
+}
+
+/// Check that a secret was encrypted with the configured key-encryption key
+fn check_key_id(kek: &dyn KeyEncryptionKeyProvider, kek_id: &str) -> Result<()> {
+    if kek.key_id() != kek_id {
+        return Err(ockam_core::Error::new(
+            Origin::Vault,
+            Kind::Invalid,
+            format!(
+                "the secret was encrypted with the key-encryption key {kek_id} but the configured key is {}",
+                kek.key_id()
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Create a random salt for the derivation of a key-encryption key
+pub(crate) fn make_salt() -> Vec<u8> {
+    let mut salt = vec![0u8; PASSPHRASE_SALT_LENGTH];
+    thread_rng().fill_bytes(&mut salt);
+    salt
+}
+
+/// Encrypt some data and return: nonce || ciphertext || tag
+fn encrypt(key: &AeadSecret, data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    let mut nonce = [0u8; AES_NONCE_LENGTH];
+    thread_rng().fill_bytes(&mut nonce);
+
+    let mut message = Vec::with_capacity(data.len() + TAG_LENGTH);
+    message.extend_from_slice(data);
+    message.extend_from_slice(&[0u8; TAG_LENGTH]);
+    make_aes(key).encrypt_message(&mut message, &nonce, aad)?;
+
+    let mut result = nonce.to_vec();
+    result.append(&mut message);
+    Ok(result)
+}
+
+/// Decrypt some data encrypted with [`encrypt`]
+fn decrypt(key: &AeadSecret, data: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    if data.len() < AES_NONCE_LENGTH + TAG_LENGTH {
+        return Err(VaultError::InsufficientDecryptBuffer.into());
+    }
+    let (nonce, message) = data.split_at(AES_NONCE_LENGTH);
+    let mut message = message.to_vec();
+    let result = make_aes(key)
+        .decrypt_message(&mut message, nonce, aad)
+        .map(|plain_text| plain_text.to_vec());
+    message.zeroize();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_secret() -> Result<()> {
+        let kek = KeyEncryptionKey::from_passphrase("passphrase", &make_salt())?;
+        let encrypted = encrypt_secret(&kek, b"handle", &[1; 32]).await?;
+        assert_ne!(encrypted.ciphertext, vec![1; 32]);
+
+        let decrypted = decrypt_secret(
+            &kek,
+            b"handle",
+            &encrypted.ciphertext,
+            &encrypted.wrapped_key,
+            &encrypted.kek_id,
+        )
+        .await?;
+        assert_eq!(decrypted, vec![1; 32]);
+
+        // the secret is bound to its handle
+        let result = decrypt_secret(
+            &kek,
+            b"other handle",
+            &encrypted.ciphertext,
+            &encrypted.wrapped_key,
+            &encrypted.kek_id,
+        )
+        .await;
+        assert!(result.is_err());
+
+        // another key-encryption key can not decrypt the secret
+        let other_kek = KeyEncryptionKey::new(AeadSecret([2; AEAD_SECRET_LENGTH]));
+        let result = decrypt_secret(
+            &other_kek,
+            b"handle",
+            &encrypted.ciphertext,
+            &encrypted.wrapped_key,
+            &encrypted.kek_id,
+        )
+        .await;
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_key_encryption_key_from_hex() -> Result<()> {
+        let kek = KeyEncryptionKey::from_hex(&hex::encode([3; AEAD_SECRET_LENGTH]))?;
+        assert_eq!(
+            kek.key_id(),
+            KeyEncryptionKey::new(AeadSecret([3; AEAD_SECRET_LENGTH])).key_id()
+        );
+        assert!(KeyEncryptionKey::from_hex("0102").is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_encrypt_decrypt_data() -> Result<()> {
+        let kek = KeyEncryptionKey::new(AeadSecret([3; AEAD_SECRET_LENGTH]));
+        let encrypted = kek.encrypt(b"data", b"ockam-test-data")?;
+        assert_eq!(kek.decrypt(&encrypted, b"ockam-test-data")?, b"data");
+
+        // the data is bound to its additional data and is not a data key
+        assert!(kek.decrypt(&encrypted, b"ockam-other-data").is_err());
+        assert!(kek.unwrap_key(&encrypted).await.is_err());
+
+        // the additional data of the data keys is reserved
+        assert!(kek.encrypt(b"data", WRAPPED_KEY_AAD).is_err());
+        let wrapped_key = kek.wrap_key(&[1; 32]).await?;
+        assert!(kek.decrypt(&wrapped_key, WRAPPED_KEY_AAD).is_err());
+        Ok(())
+    }
+}
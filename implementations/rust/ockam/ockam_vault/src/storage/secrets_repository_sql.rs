@@ -6,31 +6,56 @@ use std::sync::Arc;
 use tracing::debug;
 use zeroize::{Zeroize, ZeroizeOnDrop};
 
+use crate::storage::secrets_encryption::{
+    decrypt_secret, encrypt_secret, make_salt, missing_key_encryption_key, rewrap_data_key,
+    EncryptedSecret, KeyEncryptionKeyProvider,
+};
 use crate::storage::secrets_repository::SecretsRepository;
 use ockam_core::async_trait;
 use ockam_core::compat::vec::Vec;
 use ockam_core::errcode::{Kind, Origin};
 use ockam_core::Result;
 use ockam_node::database::AutoRetry;
-use ockam_node::database::{FromSqlxError, SqlxDatabase, ToVoid};
+use ockam_node::database::{FromSqlxError, Nullable, SqlxDatabase, ToVoid};
+
+#[cfg(feature = "std")]
+use crate::storage::secrets_encryption::{
+    KeyEncryptionKey, OCKAM_VAULT_KEK, OCKAM_VAULT_PASSPHRASE,
+};
 
 use crate::{
-    AeadSecret, AeadSecretKeyHandle, ECDSASHA256CurveP256SecretKey, EdDSACurve25519SecretKey,
-    HandleToSecret, SigningSecret, SigningSecretKeyHandle, X25519SecretKey, X25519SecretKeyHandle,
-    AEAD_TYPE,
+    AeadSecret, AeadSecretKeyHandle, ECDSASHA256CurveP256SecretKey, ECDSASHA384CurveP384SecretKey,
+    EdDSACurve25519SecretKey, HandleToSecret, SigningSecret, SigningSecretKeyHandle,
+    X25519SecretKey, X25519SecretKeyHandle, AEAD_TYPE,
 };
 
-/// Implementation of a secrets repository using a SQL database
+/// Implementation of a secrets repository using a SQL database.
+///
+/// When a key-encryption key is configured, secrets are encrypted with a random data key
+/// before being stored, and the data key is encrypted with the key-encryption key.
 #[derive(Clone)]
 pub struct SecretsSqlxDatabase {
     database: SqlxDatabase,
+    key_encryption_key: Option<Arc<dyn KeyEncryptionKeyProvider>>,
 }
 
 impl SecretsSqlxDatabase {
     /// Create a new database for secrets
     pub fn new(database: SqlxDatabase) -> Self {
         debug!("create a repository for secrets");
-        Self { database }
+        Self {
+            database,
+            key_encryption_key: None,
+        }
+    }
+
+    /// Encrypt the secrets with a key-encryption key
+    pub fn with_key_encryption_key(
+        mut self,
+        key_encryption_key: Arc<dyn KeyEncryptionKeyProvider>,
+    ) -> Self {
+        self.key_encryption_key = Some(key_encryption_key);
+        self
     }
 
     /// Create a repository
@@ -42,14 +67,236 @@ impl SecretsSqlxDatabase {
         }
     }
 
+    /// Create a repository encrypting its secrets with a key-encryption key.
+    /// The secrets which were previously stored in plain text get encrypted.
+    pub async fn make_encrypted_repository(
+        database: SqlxDatabase,
+        key_encryption_key: Arc<dyn KeyEncryptionKeyProvider>,
+    ) -> Result<Arc<dyn SecretsRepository>> {
+        let repository = Self::new(database.clone()).with_key_encryption_key(key_encryption_key);
+        let count = repository.encrypt_existing_secrets().await?;
+        if count > 0 {
+            debug!("encrypted {count} secrets stored in plain text");
+        }
+
+        if database.needs_retry() {
+            Ok(Arc::new(AutoRetry::new(repository)))
+        } else {
+            Ok(Arc::new(repository))
+        }
+    }
+
+    /// Create a repository encrypting its secrets with the key-encryption key configured
+    /// with the `OCKAM_VAULT_KEK` or `OCKAM_VAULT_PASSPHRASE` environment variables.
+    /// The secrets are stored in plain text if no key-encryption key is configured.
+    #[cfg(feature = "std")]
+    pub async fn make_repository_from_env(
+        database: SqlxDatabase,
+    ) -> Result<Arc<dyn SecretsRepository>> {
+        match Self::new(database.clone())
+            .key_encryption_key_from_env()
+            .await?
+        {
+            Some(key_encryption_key) => {
+                Self::make_encrypted_repository(database, Arc::new(key_encryption_key)).await
+            }
+            None => Ok(Self::make_repository(database)),
+        }
+    }
+
     /// Create a new in-memory database for secrets
     pub async fn create() -> Result<Self> {
         Ok(Self::new(SqlxDatabase::in_memory("secrets").await?))
     }
+
+    /// Return the key-encryption key configured with the `OCKAM_VAULT_KEK`
+    /// or `OCKAM_VAULT_PASSPHRASE` environment variables
+    #[cfg(feature = "std")]
+    pub async fn key_encryption_key_from_env(&self) -> Result<Option<KeyEncryptionKey>> {
+        self.key_encryption_key_from_env_variables(OCKAM_VAULT_KEK, OCKAM_VAULT_PASSPHRASE)
+            .await
+    }
+
+    /// Return a key-encryption key configured with either a hex-encoded key
+    /// or a passphrase, given the names of their environment variables
+    #[cfg(feature = "std")]
+    pub async fn key_encryption_key_from_env_variables(
+        &self,
+        key_variable: &str,
+        passphrase_variable: &str,
+    ) -> Result<Option<KeyEncryptionKey>> {
+        use ockam_core::env::get_env;
+
+        if let Some(key) = get_env::<String>(key_variable)? {
+            return Ok(Some(KeyEncryptionKey::from_hex(&key)?));
+        };
+        if let Some(passphrase) = get_env::<String>(passphrase_variable)? {
+            let salt = self.get_or_create_passphrase_salt().await?;
+            return Ok(Some(KeyEncryptionKey::from_passphrase(&passphrase, &salt)?));
+        };
+        Ok(None)
+    }
+
+    /// Return the salt used to derive a key-encryption key from a passphrase.
+    /// The salt is created the first time that this function is called.
+    pub async fn get_or_create_passphrase_salt(&self) -> Result<Vec<u8>> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        let query1 = query_scalar("SELECT salt FROM secrets_encryption_salt");
+        let salt: Option<Vec<u8>> = query1.fetch_optional(&mut *transaction).await.into_core()?;
+
+        let salt = match salt {
+            Some(salt) => salt,
+            None => {
+                let salt = make_salt();
+                let query2 =
+                    query("INSERT INTO secrets_encryption_salt (salt) VALUES ($1)").bind(&salt);
+                query2.execute(&mut *transaction).await.void()?;
+                salt
+            }
+        };
+        transaction.commit().await.void()?;
+        Ok(salt)
+    }
+
+    /// Encrypt all the secrets which are stored in plain text with the configured
+    /// key-encryption key. Return the number of encrypted secrets.
+    pub async fn encrypt_existing_secrets(&self) -> Result<usize> {
+        let key_encryption_key = self
+            .key_encryption_key
+            .clone()
+            .ok_or_else(missing_key_encryption_key)?;
+        self.encrypt_secrets(key_encryption_key.as_ref(), true)
+            .await
+    }
+
+    /// Encrypt all the data keys with a new key-encryption key, which is then used by this repository.
+    /// The secrets which are stored in plain text are encrypted as well.
+    /// Return the number of updated secrets.
+    pub async fn rotate_key_encryption_key(
+        &mut self,
+        key_encryption_key: Arc<dyn KeyEncryptionKeyProvider>,
+    ) -> Result<usize> {
+        let count = self
+            .encrypt_secrets(key_encryption_key.as_ref(), false)
+            .await?;
+        self.key_encryption_key = Some(key_encryption_key);
+        Ok(count)
+    }
+
+    /// Encrypt secrets with a key-encryption key.
+    /// If `plain_text_only` is false, the data keys of the already encrypted secrets
+    /// are decrypted with the current key-encryption key and encrypted with the new one.
+    async fn encrypt_secrets(
+        &self,
+        key_encryption_key: &dyn KeyEncryptionKeyProvider,
+        plain_text_only: bool,
+    ) -> Result<usize> {
+        let mut transaction = self.database.begin().await.into_core()?;
+        let mut count = 0;
+        for table in SECRETS_TABLES {
+            let select = if plain_text_only {
+                format!("SELECT handle, secret, wrapped_key, kek_id FROM {table} WHERE wrapped_key IS NULL")
+            } else {
+                format!("SELECT handle, secret, wrapped_key, kek_id FROM {table}")
+            };
+            let rows: Vec<StoredSecretRow> = query_as(&select)
+                .fetch_all(&mut *transaction)
+                .await
+                .into_core()?;
+
+            let update = format!(
+                "UPDATE {table} SET secret = $1, wrapped_key = $2, kek_id = $3 WHERE handle = $4"
+            );
+            for row in rows {
+                let encrypted = match (row.wrapped_key.to_option(), row.kek_id.to_option()) {
+                    (Some(wrapped_key), Some(kek_id)) => {
+                        let current = self
+                            .key_encryption_key
+                            .as_ref()
+                            .ok_or_else(missing_key_encryption_key)?;
+                        EncryptedSecret {
+                            ciphertext: row.secret.clone(),
+                            wrapped_key: rewrap_data_key(
+                                current.as_ref(),
+                                key_encryption_key,
+                                &wrapped_key,
+                                &kek_id,
+                            )
+                            .await?,
+                            kek_id: key_encryption_key.key_id(),
+                        }
+                    }
+                    _ => encrypt_secret(key_encryption_key, &row.handle, &row.secret).await?,
+                };
+                query(&update)
+                    .bind(encrypted.ciphertext)
+                    .bind(encrypted.wrapped_key)
+                    .bind(encrypted.kek_id)
+                    .bind(row.handle.clone())
+                    .execute(&mut *transaction)
+                    .await
+                    .void()?;
+                count += 1;
+            }
+        }
+        transaction.commit().await.void()?;
+        Ok(count)
+    }
+
+    /// Encrypt a secret if a key-encryption key is configured
+    async fn encrypt(&self, handle: &[u8], secret: Vec<u8>) -> Result<StoredSecret> {
+        match &self.key_encryption_key {
+            Some(key_encryption_key) => {
+                let encrypted =
+                    encrypt_secret(key_encryption_key.as_ref(), handle, &secret).await?;
+                Ok(StoredSecret {
+                    secret: encrypted.ciphertext,
+                    wrapped_key: Some(encrypted.wrapped_key),
+                    kek_id: Some(encrypted.kek_id),
+                })
+            }
+            None => Ok(StoredSecret {
+                secret,
+                wrapped_key: None,
+                kek_id: None,
+            }),
+        }
+    }
+
+    /// Decrypt a secret if it was stored encrypted
+    async fn decrypt(
+        &self,
+        handle: &[u8],
+        secret: &[u8],
+        wrapped_key: &Nullable<Vec<u8>>,
+        kek_id: &Nullable<String>,
+    ) -> Result<Vec<u8>> {
+        match (wrapped_key.to_option(), kek_id.to_option()) {
+            (Some(wrapped_key), Some(kek_id)) => {
+                let key_encryption_key = self
+                    .key_encryption_key
+                    .as_ref()
+                    .ok_or_else(missing_key_encryption_key)?;
+                decrypt_secret(
+                    key_encryption_key.as_ref(),
+                    handle,
+                    secret,
+                    &wrapped_key,
+                    &kek_id,
+                )
+                .await
+            }
+            _ => Ok(secret.to_vec()),
+        }
+    }
 }
 
 const ED_DSA_CURVE_25519: &str = "EdDSACurve25519";
 const EC_DSA_SHA256_CURVE_P256: &str = "ECDSASHA256CurveP256";
+const EC_DSA_SHA384_CURVE_P384: &str = "ECDSASHA384CurveP384";
+
+/// Tables containing secrets which can be encrypted
+const SECRETS_TABLES: [&str; 3] = ["signing_secret", "x25519_secret", "aead_secret"];
 
 #[async_trait]
 impl SecretsRepository for SecretsSqlxDatabase {
@@ -61,18 +308,24 @@ impl SecretsRepository for SecretsSqlxDatabase {
         let secret_type: String = match handle {
             SigningSecretKeyHandle::EdDSACurve25519(_) => ED_DSA_CURVE_25519.into(),
             SigningSecretKeyHandle::ECDSASHA256CurveP256(_) => EC_DSA_SHA256_CURVE_P256.into(),
+            SigningSecretKeyHandle::ECDSASHA384CurveP384(_) => EC_DSA_SHA384_CURVE_P384.into(),
         };
+        let stored = self
+            .encrypt(handle.handle().value(), secret.key().to_vec())
+            .await?;
 
         let query = query(
             r#"
-            INSERT INTO signing_secret (handle, secret_type, secret)
-            VALUES ($1, $2, $3)
+            INSERT INTO signing_secret (handle, secret_type, secret, wrapped_key, kek_id)
+            VALUES ($1, $2, $3, $4, $5)
             ON CONFLICT (handle)
-            DO UPDATE SET secret_type = $2, secret = $3"#,
+            DO UPDATE SET secret_type = $2, secret = $3, wrapped_key = $4, kek_id = $5"#,
         )
         .bind(handle)
         .bind(secret_type)
-        .bind(secret);
+        .bind(&stored.secret)
+        .bind(&stored.wrapped_key)
+        .bind(&stored.kek_id);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -87,18 +340,28 @@ impl SecretsRepository for SecretsSqlxDatabase {
         &self,
         handle: &SigningSecretKeyHandle,
     ) -> Result<Option<SigningSecret>> {
-        let query =
-            query_as("SELECT handle, secret_type, secret FROM signing_secret WHERE handle = $1")
-                .bind(handle);
+        let query = query_as(
+            "SELECT handle, secret_type, secret, wrapped_key, kek_id FROM signing_secret WHERE handle = $1",
+        )
+        .bind(handle);
         let row: Option<SigningSecretRow> = query
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.signing_secret()).transpose()?)
+        match row {
+            Some(mut row) => {
+                row.secret = self
+                    .decrypt(&row.handle, &row.secret, &row.wrapped_key, &row.kek_id)
+                    .await?;
+                Ok(Some(row.signing_secret()?))
+            }
+            None => Ok(None),
+        }
     }
 
     async fn get_signing_secret_handles(&self) -> Result<Vec<SigningSecretKeyHandle>> {
-        let query = query_as("SELECT handle, secret_type, secret FROM signing_secret");
+        let query =
+            query_as("SELECT handle, secret_type, secret, wrapped_key, kek_id FROM signing_secret");
         let rows: Vec<SigningSecretRow> =
             query.fetch_all(&*self.database.pool).await.into_core()?;
         Ok(rows
@@ -112,15 +375,21 @@ impl SecretsRepository for SecretsSqlxDatabase {
         handle: &X25519SecretKeyHandle,
         secret: X25519SecretKey,
     ) -> Result<()> {
+        let stored = self
+            .encrypt(handle.0.value(), secret.key().to_vec())
+            .await?;
+
         let query = query(
             r#"
-        INSERT INTO x25519_secret (handle, secret)
-        VALUES ($1, $2)
+        INSERT INTO x25519_secret (handle, secret, wrapped_key, kek_id)
+        VALUES ($1, $2, $3, $4)
         ON CONFLICT (handle)
-        DO UPDATE SET secret = $2"#,
+        DO UPDATE SET secret = $2, wrapped_key = $3, kek_id = $4"#,
         )
         .bind(handle)
-        .bind(secret);
+        .bind(&stored.secret)
+        .bind(&stored.wrapped_key)
+        .bind(&stored.kek_id);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -135,17 +404,27 @@ impl SecretsRepository for SecretsSqlxDatabase {
         &self,
         handle: &X25519SecretKeyHandle,
     ) -> Result<Option<X25519SecretKey>> {
-        let query =
-            query_as("SELECT handle, secret FROM x25519_secret WHERE handle = $1").bind(handle);
+        let query = query_as(
+            "SELECT handle, secret, wrapped_key, kek_id FROM x25519_secret WHERE handle = $1",
+        )
+        .bind(handle);
         let row: Option<X25519SecretRow> = query
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.x25519_secret()).transpose()?)
+        match row {
+            Some(mut row) => {
+                row.secret = self
+                    .decrypt(&row.handle, &row.secret, &row.wrapped_key, &row.kek_id)
+                    .await?;
+                Ok(Some(row.x25519_secret()?))
+            }
+            None => Ok(None),
+        }
     }
 
     async fn get_x25519_secret_handles(&self) -> Result<Vec<X25519SecretKeyHandle>> {
-        let query = query_as("SELECT handle, secret FROM x25519_secret");
+        let query = query_as("SELECT handle, secret, wrapped_key, kek_id FROM x25519_secret");
         let rows: Vec<X25519SecretRow> = query.fetch_all(&*self.database.pool).await.into_core()?;
         Ok(rows
             .iter()
@@ -158,16 +437,20 @@ impl SecretsRepository for SecretsSqlxDatabase {
         handle: &AeadSecretKeyHandle,
         secret: AeadSecret,
     ) -> Result<()> {
+        let stored = self.encrypt(handle.0 .0.value(), secret.0.to_vec()).await?;
+
         let query = query(
             r#"
-                INSERT INTO aead_secret (handle, type, secret)
-                VALUES ($1, $2, $3)
+                INSERT INTO aead_secret (handle, type, secret, wrapped_key, kek_id)
+                VALUES ($1, $2, $3, $4, $5)
                 ON CONFLICT (handle)
-                DO UPDATE SET type = $2, secret = $3"#,
+                DO UPDATE SET type = $2, secret = $3, wrapped_key = $4, kek_id = $5"#,
         )
         .bind(handle)
         .bind(AEAD_TYPE)
-        .bind(secret);
+        .bind(&stored.secret)
+        .bind(&stored.wrapped_key)
+        .bind(&stored.kek_id);
         query.execute(&*self.database.pool).await.void()
     }
 
@@ -179,14 +462,24 @@ impl SecretsRepository for SecretsSqlxDatabase {
     }
 
     async fn get_aead_secret(&self, handle: &AeadSecretKeyHandle) -> Result<Option<AeadSecret>> {
-        let query = query_as("SELECT secret FROM aead_secret WHERE handle = $1 AND type = $2")
-            .bind(handle)
-            .bind(AEAD_TYPE);
+        let query = query_as(
+            "SELECT handle, secret, wrapped_key, kek_id FROM aead_secret WHERE handle = $1 AND type = $2",
+        )
+        .bind(handle)
+        .bind(AEAD_TYPE);
         let row: Option<AeadSecretRow> = query
             .fetch_optional(&*self.database.pool)
             .await
             .into_core()?;
-        Ok(row.map(|r| r.aead_secret()).transpose()?)
+        match row {
+            Some(mut row) => {
+                row.secret = self
+                    .decrypt(&row.handle, &row.secret, &row.wrapped_key, &row.kek_id)
+                    .await?;
+                Ok(Some(row.aead_secret()?))
+            }
+            None => Ok(None),
+        }
     }
 
     async fn delete_all(&self) -> Result<()> {
@@ -293,23 +586,23 @@ struct SigningSecretRow {
     handle: Vec<u8>,
     secret_type: String,
     secret: Vec<u8>,
+    #[zeroize(skip)]
+    wrapped_key: Nullable<Vec<u8>>,
+    #[zeroize(skip)]
+    kek_id: Nullable<String>,
 }
 
 impl SigningSecretRow {
     fn signing_secret(&self) -> Result<SigningSecret> {
-        let secret = self.secret.clone().try_into().map_err(|_| {
-            ockam_core::Error::new(
-                Origin::Api,
-                Kind::Serialization,
-                "cannot convert a signing secret to [u8; 32]",
-            )
-        })?;
         match self.secret_type.as_str() {
-            "EdDSACurve25519" => Ok(SigningSecret::EdDSACurve25519(
-                EdDSACurve25519SecretKey::new(secret),
+            ED_DSA_CURVE_25519 => Ok(SigningSecret::EdDSACurve25519(
+                EdDSACurve25519SecretKey::new(self.secret_bytes()?),
+            )),
+            EC_DSA_SHA256_CURVE_P256 => Ok(SigningSecret::ECDSASHA256CurveP256(
+                ECDSASHA256CurveP256SecretKey::new(self.secret_bytes()?),
             )),
-            "ECDSASHA256CurveP256" => Ok(SigningSecret::ECDSASHA256CurveP256(
-                ECDSASHA256CurveP256SecretKey::new(secret),
+            EC_DSA_SHA384_CURVE_P384 => Ok(SigningSecret::ECDSASHA384CurveP384(
+                ECDSASHA384CurveP384SecretKey::new(self.secret_bytes()?),
             )),
             _ => Err(ockam_core::Error::new(
                 Origin::Api,
@@ -319,12 +612,25 @@ impl SigningSecretRow {
         }
     }
 
+    fn secret_bytes<const N: usize>(&self) -> Result<[u8; N]> {
+        self.secret.clone().try_into().map_err(|_| {
+            ockam_core::Error::new(
+                Origin::Api,
+                Kind::Serialization,
+                format!("cannot convert a signing secret to [u8; {N}]"),
+            )
+        })
+    }
+
     fn handle(&self) -> Result<SigningSecretKeyHandle> {
         match self.secret_type.as_str() {
-            "EdDSACurve25519" => Ok(SigningSecretKeyHandle::EdDSACurve25519(
+            ED_DSA_CURVE_25519 => Ok(SigningSecretKeyHandle::EdDSACurve25519(
+                HandleToSecret::new(self.handle.clone()),
+            )),
+            EC_DSA_SHA256_CURVE_P256 => Ok(SigningSecretKeyHandle::ECDSASHA256CurveP256(
                 HandleToSecret::new(self.handle.clone()),
             )),
-            "ECDSASHA256CurveP256" => Ok(SigningSecretKeyHandle::ECDSASHA256CurveP256(
+            EC_DSA_SHA384_CURVE_P384 => Ok(SigningSecretKeyHandle::ECDSASHA384CurveP384(
                 HandleToSecret::new(self.handle.clone()),
             )),
             _ => Err(ockam_core::Error::new(
@@ -340,6 +646,10 @@ impl SigningSecretRow {
 struct X25519SecretRow {
     handle: Vec<u8>,
     secret: Vec<u8>,
+    #[zeroize(skip)]
+    wrapped_key: Nullable<Vec<u8>>,
+    #[zeroize(skip)]
+    kek_id: Nullable<String>,
 }
 
 impl X25519SecretRow {
@@ -363,7 +673,12 @@ impl X25519SecretRow {
 
 #[derive(FromRow, Zeroize, ZeroizeOnDrop)]
 struct AeadSecretRow {
+    handle: Vec<u8>,
     secret: Vec<u8>,
+    #[zeroize(skip)]
+    wrapped_key: Nullable<Vec<u8>>,
+    #[zeroize(skip)]
+    kek_id: Nullable<String>,
 }
 
 impl AeadSecretRow {
@@ -380,6 +695,25 @@ impl AeadSecretRow {
     }
 }
 
+/// Columns shared by all the tables storing secrets
+#[derive(FromRow, Zeroize, ZeroizeOnDrop)]
+struct StoredSecretRow {
+    handle: Vec<u8>,
+    secret: Vec<u8>,
+    #[zeroize(skip)]
+    wrapped_key: Nullable<Vec<u8>>,
+    #[zeroize(skip)]
+    kek_id: Nullable<String>,
+}
+
+/// Secret as stored in the database, possibly encrypted
+#[derive(Zeroize, ZeroizeOnDrop)]
+struct StoredSecret {
+    secret: Vec<u8>,
+    wrapped_key: Option<Vec<u8>>,
+    kek_id: Option<String>,
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -398,18 +732,29 @@ mod test {
         let handle2 = SigningSecretKeyHandle::EdDSACurve25519(HandleToSecret::new(vec![4, 5, 6]));
         let secret2 = SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new([1; 32]));
 
+        let handle3 =
+            SigningSecretKeyHandle::ECDSASHA384CurveP384(HandleToSecret::new(vec![7, 8, 9]));
+        let secret3 =
+            SigningSecret::ECDSASHA384CurveP384(ECDSASHA384CurveP384SecretKey::new([1; 48]));
+
         repository
             .store_signing_secret(&handle1, secret1.clone())
             .await?;
         repository
             .store_signing_secret(&handle2, secret2.clone())
             .await?;
+        repository
+            .store_signing_secret(&handle3, secret3.clone())
+            .await?;
 
         let result = repository.get_signing_secret(&handle1).await?;
         assert!(result == Some(secret1));
 
+        let result = repository.get_signing_secret(&handle3).await?;
+        assert!(result == Some(secret3));
+
         let result = repository.get_signing_secret_handles().await?;
-        assert_eq!(result, vec![handle1.clone(), handle2]);
+        assert_eq!(result, vec![handle1.clone(), handle2, handle3]);
 
         repository.delete_signing_secret(&handle1).await?;
 
@@ -481,6 +826,72 @@ mod test {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_encrypted_secrets_repository() -> Result<()> {
+        let database = SqlxDatabase::in_memory("secrets").await?;
+
+        // store a secret in plain text
+        let plain_text_repository = SecretsSqlxDatabase::new(database.clone());
+        let handle1 = X25519SecretKeyHandle(HandleToSecret::new(vec![1, 2, 3]));
+        let secret1 = X25519SecretKey::new([1; 32]);
+        plain_text_repository
+            .store_x25519_secret(&handle1, secret1.clone())
+            .await?;
+
+        // the existing secret gets encrypted when a key-encryption key is configured
+        let salt = plain_text_repository
+            .get_or_create_passphrase_salt()
+            .await?;
+        assert_eq!(
+            plain_text_repository
+                .get_or_create_passphrase_salt()
+                .await?,
+            salt
+        );
+        let kek = Arc::new(KeyEncryptionKey::from_passphrase("passphrase", &salt)?);
+        let repository =
+            SecretsSqlxDatabase::make_encrypted_repository(database.clone(), kek.clone()).await?;
+        assert!(plain_text_repository
+            .get_x25519_secret(&handle1)
+            .await
+            .is_err());
+
+        // new secrets are encrypted
+        let handle2 = SigningSecretKeyHandle::EdDSACurve25519(HandleToSecret::new(vec![4, 5, 6]));
+        let secret2 = SigningSecret::EdDSACurve25519(EdDSACurve25519SecretKey::new([2; 32]));
+        repository
+            .store_signing_secret(&handle2, secret2.clone())
+            .await?;
+        assert!(plain_text_repository
+            .get_signing_secret(&handle2)
+            .await
+            .is_err());
+
+        let result = repository.get_x25519_secret(&handle1).await?;
+        assert!(result == Some(secret1.clone()));
+        let result = repository.get_signing_secret(&handle2).await?;
+        assert!(result == Some(secret2.clone()));
+
+        // the key-encryption key can be rotated
+        let mut rotated_repository =
+            SecretsSqlxDatabase::new(database.clone()).with_key_encryption_key(kek);
+        let new_kek = Arc::new(KeyEncryptionKey::new(AeadSecret([3; 32])));
+        assert_eq!(
+            rotated_repository
+                .rotate_key_encryption_key(new_kek.clone())
+                .await?,
+            2
+        );
+        assert!(repository.get_x25519_secret(&handle1).await.is_err());
+
+        let result = rotated_repository.get_x25519_secret(&handle1).await?;
+        assert!(result == Some(secret1));
+        let result = rotated_repository.get_signing_secret(&handle2).await?;
+        assert!(result == Some(secret2));
+
+        Ok(())
+    }
+
     /// HELPERS
     async fn create_repository() -> Result<Arc<dyn SecretsRepository>> {
         Ok(Arc::new(SecretsSqlxDatabase::create().await?))
@@ -9,6 +9,15 @@ pub const EDDSA_CURVE25519_PUBLIC_KEY_LENGTH: usize = 32;
 /// NIST P256 public key length.
 pub const ECDSA_SHA256_CURVEP256_PUBLIC_KEY_LENGTH: usize = 65;
 
+/// NIST P384 public key length.
+pub const ECDSA_SHA384_CURVEP384_PUBLIC_KEY_LENGTH: usize = 97;
+
+/// ML-KEM-768 encapsulation key length.
+pub const ML_KEM_768_PUBLIC_KEY_LENGTH: usize = 1184;
+
+/// ML-KEM-768 ciphertext length.
+pub const ML_KEM_768_CIPHERTEXT_LENGTH: usize = 1088;
+
 /// A public key for verifying signatures.
 #[derive(Encode, Decode, CborLen, Debug, Clone, PartialEq, Eq)]
 #[rustfmt::skip]
@@ -17,6 +26,8 @@ pub enum VerifyingPublicKey {
     #[n(0)] EdDSACurve25519(#[n(0)] EdDSACurve25519PublicKey),
     /// Curve P-256 Public Key for verifying ECDSA SHA256 signatures.
     #[n(1)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256PublicKey),
+    /// Curve P-384 Public Key for verifying ECDSA SHA384 signatures.
+    #[n(2)] ECDSASHA384CurveP384(#[n(0)] ECDSASHA384CurveP384PublicKey),
 }
 
 /// A Curve25519 Public Key that is only used for EdDSA signatures.
@@ -53,6 +64,26 @@ pub struct ECDSASHA256CurveP256PublicKey(
     #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ECDSA_SHA256_CURVEP256_PUBLIC_KEY_LENGTH],
 );
 
+/// A Curve P-384 Public Key that is only used for ECDSA SHA384 signatures.
+///
+/// This type only supports the uncompressed form which is 97 bytes and has
+/// the first byte - 0x04. The uncompressed form is defined [here][1] in
+/// section 2.3.3.
+///
+/// - ECDSA Signature as defined [here][2].
+/// - SHA384 as defined [here][3].
+/// - Curve P-384 as defined [here][4].
+///
+/// [1]: https://www.secg.org/SEC1-Ver-1.0.pdf
+/// [2]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf
+/// [3]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+/// [4]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-186.pdf
+#[derive(Encode, Decode, CborLen, Clone, Debug, PartialEq, Eq)]
+#[cbor(transparent)]
+pub struct ECDSASHA384CurveP384PublicKey(
+    #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ECDSA_SHA384_CURVEP384_PUBLIC_KEY_LENGTH],
+);
+
 /// X25519 Public Key is used for ECDH.
 ///
 /// - X25519 as defined [here][1].
@@ -65,3 +96,27 @@ pub struct ECDSASHA256CurveP256PublicKey(
 pub struct X25519PublicKey(
     #[cbor(n(0), with = "minicbor::bytes")] pub [u8; X25519_PUBLIC_KEY_LENGTH],
 );
+
+/// ML-KEM-768 encapsulation (public) key, used to establish a shared secret which is resistant
+/// to attacks using quantum computers.
+///
+/// - ML-KEM as defined [here][1].
+///
+/// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+#[derive(Encode, Decode, CborLen, Clone, Debug, PartialEq, Eq)]
+#[cbor(transparent)]
+pub struct MlKemPublicKey(
+    #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ML_KEM_768_PUBLIC_KEY_LENGTH],
+);
+
+/// ML-KEM-768 ciphertext, which encapsulates a shared secret for the holder of the
+/// corresponding decapsulation key.
+///
+/// - ML-KEM as defined [here][1].
+///
+/// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.203.pdf
+#[derive(Encode, Decode, CborLen, Clone, Debug, PartialEq, Eq)]
+#[cbor(transparent)]
+pub struct MlKemCiphertext(
+    #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ML_KEM_768_CIPHERTEXT_LENGTH],
+);
@@ -29,6 +29,8 @@ pub enum SigningSecretKeyHandle {
     EdDSACurve25519(HandleToSecret),
     /// Curve P-256 key that is only used for ECDSA SHA256 signatures.
     ECDSASHA256CurveP256(HandleToSecret),
+    /// Curve P-384 key that is only used for ECDSA SHA384 signatures.
+    ECDSASHA384CurveP384(HandleToSecret),
 }
 
 impl SigningSecretKeyHandle {
@@ -37,6 +39,7 @@ impl SigningSecretKeyHandle {
         match self {
             SigningSecretKeyHandle::EdDSACurve25519(handle) => handle,
             SigningSecretKeyHandle::ECDSASHA256CurveP256(handle) => handle,
+            SigningSecretKeyHandle::ECDSASHA384CurveP384(handle) => handle,
         }
     }
 }
@@ -48,12 +51,18 @@ pub enum SigningKeyType {
     EdDSACurve25519,
     /// See [`super::signatures::ECDSASHA256CurveP256Signature`]
     ECDSASHA256CurveP256,
+    /// See [`super::signatures::ECDSASHA384CurveP384Signature`]
+    ECDSASHA384CurveP384,
 }
 
 /// A handle to a X25519 Secret Key.
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct X25519SecretKeyHandle(pub HandleToSecret);
 
+/// A handle to a ML-KEM-768 decapsulation (secret) Key.
+#[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
+pub struct MlKemSecretKeyHandle(pub HandleToSecret);
+
 /// A handle to a secret Buffer (like an HKDF output).
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq)]
 pub struct SecretBufferHandle(pub HandleToSecret);
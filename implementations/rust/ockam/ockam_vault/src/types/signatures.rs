@@ -4,6 +4,8 @@ use minicbor::{CborLen, Decode, Encode};
 pub const EDDSA_CURVE25519_SIGNATURE_LENGTH: usize = 64;
 /// ECDSASHA256CurveP256 signature length.
 pub const ECDSA_SHA256_CURVEP256_SIGNATURE_LENGTH: usize = 64;
+/// ECDSASHA384CurveP384 signature length.
+pub const ECDSA_SHA384_CURVEP384_SIGNATURE_LENGTH: usize = 96;
 
 /// A cryptographic signature.
 #[derive(Encode, Decode, CborLen)]
@@ -13,6 +15,8 @@ pub enum Signature {
     #[n(0)] EdDSACurve25519(#[n(0)] EdDSACurve25519Signature),
     /// An ECDSA signature using SHA-256 and Curve P-256.
     #[n(1)] ECDSASHA256CurveP256(#[n(0)] ECDSASHA256CurveP256Signature),
+    /// An ECDSA signature using SHA-384 and Curve P-384.
+    #[n(2)] ECDSASHA384CurveP384(#[n(0)] ECDSASHA384CurveP384Signature),
 }
 
 /// An EdDSA Signature using Curve25519.
@@ -42,3 +46,18 @@ pub struct EdDSACurve25519Signature(
 pub struct ECDSASHA256CurveP256Signature(
     #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ECDSA_SHA256_CURVEP256_SIGNATURE_LENGTH],
 );
+
+/// An ECDSA Signature using SHA384 and Curve P-384.
+///
+/// - ECDSA Signature as defined [here][1].
+/// - SHA384 as defined [here][2].
+/// - Curve P-384 as defined [here][3].
+///
+/// [1]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.186-5.pdf
+/// [2]: https://nvlpubs.nist.gov/nistpubs/FIPS/NIST.FIPS.180-4.pdf
+/// [3]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-186.pdf
+#[derive(Encode, Decode, CborLen, PartialEq, Eq, Clone, Debug)]
+#[cbor(transparent)]
+pub struct ECDSASHA384CurveP384Signature(
+    #[cbor(n(0), with = "minicbor::bytes")] pub [u8; ECDSA_SHA384_CURVEP384_SIGNATURE_LENGTH],
+);
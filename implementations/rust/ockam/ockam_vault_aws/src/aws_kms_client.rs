@@ -77,7 +77,8 @@ impl AwsKmsClient {
 
     fn cast_handle_to_kid(handle: &SigningSecretKeyHandle) -> Result<String> {
         let handle = match handle {
-            SigningSecretKeyHandle::EdDSACurve25519(_) => return Err(Error::InvalidHandle)?,
+            SigningSecretKeyHandle::EdDSACurve25519(_)
+            | SigningSecretKeyHandle::ECDSASHA384CurveP384(_) => return Err(Error::InvalidHandle)?,
             SigningSecretKeyHandle::ECDSASHA256CurveP256(handle) => handle.value().clone(),
         };
 
@@ -0,0 +1,48 @@
+use ockam_core::errcode::{Kind, Origin};
+use thiserror::Error;
+
+#[allow(missing_docs)]
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error("the PKCS#11 module path is missing, please set {0}")]
+    MissingModule(&'static str),
+    #[error("the PKCS#11 PIN is missing, please set {0}")]
+    MissingPin(&'static str),
+    #[error("PKCS#11 error loading the module: {0}")]
+    Initialize(String),
+    #[error("no PKCS#11 token was found with the label {0}")]
+    TokenNotFound(String),
+    #[error("no PKCS#11 token was found")]
+    NoToken,
+    #[error("PKCS#11 error opening a session: {0}")]
+    Session(String),
+    #[error("PKCS#11 error creating new key: {0}")]
+    Create(String),
+    #[error("PKCS#11 error signing message: {0}")]
+    Sign(String),
+    #[error("PKCS#11 error exporting public key: {0}")]
+    Export(String),
+    #[error("PKCS#11 error deleting key: {0}")]
+    Delete(String),
+    #[error("PKCS#11 error deriving a shared secret: {0}")]
+    Derive(String),
+    #[error("PKCS#11 error looking up a key: {0}")]
+    Find(String),
+    #[error("the token doesn't support the mechanism {0}")]
+    UnsupportedMechanism(String),
+    #[error("key type is not supported")]
+    UnsupportedKeyType,
+    #[error("public key encoding is incorrect")]
+    InvalidPublicKey,
+    #[error("signature encoding is incorrect")]
+    InvalidSignature,
+    #[error("key was not found")]
+    KeyNotFound,
+}
+
+impl From<Error> for ockam_core::Error {
+    #[track_caller]
+    fn from(e: Error) -> Self {
+        ockam_core::Error::new(Origin::Vault, Kind::Io, e)
+    }
+}
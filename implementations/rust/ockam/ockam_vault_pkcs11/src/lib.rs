@@ -0,0 +1,22 @@
+//! PKCS#11 implementation of the ockam_vault traits
+//!
+//! Signing keys, and optionally the static X25519 keys of secure channels, are generated and
+//! used inside a PKCS#11 token (an HSM, a YubiHSM, SoftHSM,...) and never leave it.
+#![deny(unsafe_code)]
+#![warn(
+    missing_docs,
+    trivial_casts,
+    trivial_numeric_casts,
+    unused_import_braces,
+    unused_qualifications
+)]
+
+mod error;
+mod pkcs11_client;
+mod pkcs11_secure_channel_vault;
+mod pkcs11_signing_vault;
+
+pub use error::*;
+pub use pkcs11_client::*;
+pub use pkcs11_secure_channel_vault::*;
+pub use pkcs11_signing_vault::*;
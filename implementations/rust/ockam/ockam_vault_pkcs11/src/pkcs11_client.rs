@@ -0,0 +1,515 @@
+use crate::error::Error;
+use cryptoki::context::{CInitializeArgs, Pkcs11};
+use cryptoki::error::{Error as CryptokiError, RvError};
+use cryptoki::mechanism::elliptic_curve::{EcKdf, Ecdh1DeriveParams};
+use cryptoki::mechanism::{Mechanism, MechanismType};
+use cryptoki::object::{Attribute, AttributeType, KeyType, ObjectClass, ObjectHandle};
+use cryptoki::session::{Session, UserType};
+use cryptoki::slot::Slot;
+use cryptoki::types::AuthPin;
+use ockam_core::compat::sync::{Arc, Mutex, Weak};
+use ockam_core::env::get_env;
+use ockam_core::Result;
+use sha2::{Digest, Sha256, Sha384};
+use std::fmt::{Debug, Formatter};
+use std::path::PathBuf;
+use tracing as log;
+
+/// Environment variable containing the path of the PKCS#11 module
+pub const OCKAM_PKCS11_MODULE: &str = "OCKAM_PKCS11_MODULE";
+
+/// Environment variable containing the label of the token to use
+pub const OCKAM_PKCS11_TOKEN_LABEL: &str = "OCKAM_PKCS11_TOKEN_LABEL";
+
+/// Environment variable containing the user PIN of the token
+pub const OCKAM_PKCS11_PIN: &str = "OCKAM_PKCS11_PIN";
+
+/// Label set on the keys created by this vault
+const KEY_LABEL: &str = "ockam";
+
+/// Length of the CKA_ID attribute of the keys created by this vault
+const KEY_ID_LENGTH: u32 = 20;
+
+/// Client in use for a given module and token label
+type CachedClient = (PathBuf, Option<String>, Weak<Pkcs11Client>);
+
+/// Clients which are currently in use, since a PKCS#11 module must only be initialized
+/// once per process and is finalized when its context is dropped
+static CLIENTS: Mutex<Vec<CachedClient>> = Mutex::new(Vec::new());
+
+/// PKCS#11 configuration.
+#[derive(Clone)]
+pub struct Pkcs11Config {
+    module: PathBuf,
+    token_label: Option<String>,
+    pin: String,
+}
+
+impl Debug for Pkcs11Config {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Config")
+            .field("module", &self.module)
+            .field("token_label", &self.token_label)
+            .finish()
+    }
+}
+
+impl Pkcs11Config {
+    /// Create a new configuration for a PKCS#11 module.
+    /// The first available token is used unless a token label is specified.
+    pub fn new(module: impl Into<PathBuf>, pin: impl Into<String>) -> Pkcs11Config {
+        Pkcs11Config {
+            module: module.into(),
+            token_label: None,
+            pin: pin.into(),
+        }
+    }
+
+    /// Create a configuration from the OCKAM_PKCS11_MODULE, OCKAM_PKCS11_TOKEN_LABEL
+    /// and OCKAM_PKCS11_PIN environment variables
+    pub fn from_env() -> Result<Pkcs11Config> {
+        let module: PathBuf =
+            get_env(OCKAM_PKCS11_MODULE)?.ok_or(Error::MissingModule(OCKAM_PKCS11_MODULE))?;
+        let pin: String = get_env(OCKAM_PKCS11_PIN)?.ok_or(Error::MissingPin(OCKAM_PKCS11_PIN))?;
+        let config = Self::new(module, pin);
+        Ok(match get_env::<String>(OCKAM_PKCS11_TOKEN_LABEL)? {
+            Some(token_label) => config.with_token_label(token_label),
+            None => config,
+        })
+    }
+
+    /// Use the token with a specific label
+    pub fn with_token_label(self, token_label: impl Into<String>) -> Self {
+        Self {
+            token_label: Some(token_label.into()),
+            ..self
+        }
+    }
+}
+
+/// Elliptic curves of the keys which can be stored in a token
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Pkcs11Curve {
+    /// NIST P-256, used for ECDSA signatures
+    P256,
+    /// NIST P-384, used for ECDSA signatures
+    P384,
+    /// Edwards 25519, used for EdDSA signatures
+    Ed25519,
+    /// Montgomery 25519, used for ECDH
+    X25519,
+}
+
+impl Pkcs11Curve {
+    /// DER encoding of the curve OID, used as the CKA_EC_PARAMS attribute
+    fn ec_params(&self) -> &'static [u8] {
+        match self {
+            Pkcs11Curve::P256 => &[0x06, 0x08, 0x2a, 0x86, 0x48, 0xce, 0x3d, 0x03, 0x01, 0x07],
+            Pkcs11Curve::P384 => &[0x06, 0x05, 0x2b, 0x81, 0x04, 0x00, 0x22],
+            Pkcs11Curve::Ed25519 => &[0x06, 0x03, 0x2b, 0x65, 0x70],
+            Pkcs11Curve::X25519 => &[0x06, 0x03, 0x2b, 0x65, 0x6e],
+        }
+    }
+
+    fn key_type(&self) -> KeyType {
+        match self {
+            Pkcs11Curve::P256 | Pkcs11Curve::P384 => KeyType::EC,
+            Pkcs11Curve::Ed25519 => KeyType::EC_EDWARDS,
+            Pkcs11Curve::X25519 => KeyType::EC_MONTGOMERY,
+        }
+    }
+
+    fn key_generation_mechanism(&self) -> Mechanism<'static> {
+        match self {
+            Pkcs11Curve::P256 | Pkcs11Curve::P384 => Mechanism::EccKeyPairGen,
+            Pkcs11Curve::Ed25519 => Mechanism::EccEdwardsKeyPairGen,
+            Pkcs11Curve::X25519 => Mechanism::EccMontgomeryKeyPairGen,
+        }
+    }
+
+    /// Length of a public key encoded as a raw point
+    fn public_key_length(&self) -> usize {
+        match self {
+            Pkcs11Curve::P256 => 65,
+            Pkcs11Curve::P384 => 97,
+            Pkcs11Curve::Ed25519 | Pkcs11Curve::X25519 => 32,
+        }
+    }
+
+    /// Mechanisms that the token must support in order to use keys on that curve
+    pub fn required_mechanisms(&self) -> Vec<MechanismType> {
+        match self {
+            Pkcs11Curve::P256 | Pkcs11Curve::P384 => {
+                vec![MechanismType::ECC_KEY_PAIR_GEN, MechanismType::ECDSA]
+            }
+            Pkcs11Curve::Ed25519 => {
+                vec![
+                    MechanismType::ECC_EDWARDS_KEY_PAIR_GEN,
+                    MechanismType::EDDSA,
+                ]
+            }
+            Pkcs11Curve::X25519 => vec![
+                MechanismType::ECC_MONTGOMERY_KEY_PAIR_GEN,
+                MechanismType::ECDH1_DERIVE,
+            ],
+        }
+    }
+
+    /// Return the curve of a key given its type and CKA_EC_PARAMS attribute
+    fn from_attributes(key_type: KeyType, ec_params: &[u8]) -> Option<Pkcs11Curve> {
+        if key_type == KeyType::EC_EDWARDS {
+            // the curve can also be described with the "edwards25519" printable string
+            Some(Pkcs11Curve::Ed25519)
+        } else if key_type == KeyType::EC_MONTGOMERY {
+            Some(Pkcs11Curve::X25519)
+        } else if key_type == KeyType::EC && ec_params == Pkcs11Curve::P256.ec_params() {
+            Some(Pkcs11Curve::P256)
+        } else if key_type == KeyType::EC && ec_params == Pkcs11Curve::P384.ec_params() {
+            Some(Pkcs11Curve::P384)
+        } else {
+            None
+        }
+    }
+}
+
+/// PKCS#11 client.
+///
+/// A single session is opened on the token and shared by all the operations.
+pub struct Pkcs11Client {
+    session: Mutex<Session>,
+    mechanisms: Vec<MechanismType>,
+}
+
+impl Debug for Pkcs11Client {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Pkcs11Client")
+            .field("mechanisms", &self.mechanisms)
+            .finish()
+    }
+}
+
+impl Pkcs11Client {
+    /// Return a client for the configured token.
+    /// The client is shared with the other users of the same token in this process.
+    pub fn create(config: Pkcs11Config) -> Result<Arc<Pkcs11Client>> {
+        let mut clients = CLIENTS.lock().unwrap();
+        clients.retain(|(_, _, client)| client.strong_count() > 0);
+        let existing = clients
+            .iter()
+            .find(|(module, token_label, _)| {
+                module == &config.module && token_label == &config.token_label
+            })
+            .and_then(|(_, _, client)| client.upgrade());
+        if let Some(client) = existing {
+            return Ok(client);
+        }
+
+        let client = Arc::new(Self::open(&config)?);
+        clients.push((
+            config.module.clone(),
+            config.token_label.clone(),
+            Arc::downgrade(&client),
+        ));
+        Ok(client)
+    }
+
+    fn open(config: &Pkcs11Config) -> Result<Pkcs11Client> {
+        log::trace!(module = ?config.module, "load the PKCS#11 module");
+        let pkcs11 = Pkcs11::new(&config.module).map_err(|e| Error::Initialize(e.to_string()))?;
+        match pkcs11.initialize(CInitializeArgs::OsThreads) {
+            Ok(()) | Err(CryptokiError::Pkcs11(RvError::CryptokiAlreadyInitialized, _)) => (),
+            Err(e) => return Err(Error::Initialize(e.to_string()))?,
+        }
+
+        let slot = Self::find_slot(&pkcs11, config)?;
+        let mechanisms = pkcs11
+            .get_mechanism_list(slot)
+            .map_err(|e| Error::Session(e.to_string()))?;
+
+        let session = pkcs11
+            .open_rw_session(slot)
+            .map_err(|e| Error::Session(e.to_string()))?;
+        match session.login(UserType::User, Some(&AuthPin::new(config.pin.clone()))) {
+            Ok(()) | Err(CryptokiError::Pkcs11(RvError::UserAlreadyLoggedIn, _)) => (),
+            Err(e) => return Err(Error::Session(e.to_string()))?,
+        }
+        log::debug!(slot = slot.id(), "opened a PKCS#11 session");
+
+        Ok(Pkcs11Client {
+            session: Mutex::new(session),
+            mechanisms,
+        })
+    }
+
+    fn find_slot(pkcs11: &Pkcs11, config: &Pkcs11Config) -> Result<Slot> {
+        let slots = pkcs11
+            .get_slots_with_initialized_token()
+            .map_err(|e| Error::Session(e.to_string()))?;
+        let Some(token_label) = &config.token_label else {
+            return Ok(slots.first().cloned().ok_or(Error::NoToken)?);
+        };
+
+        for slot in slots {
+            let token_info = pkcs11
+                .get_token_info(slot)
+                .map_err(|e| Error::Session(e.to_string()))?;
+            if token_info.label().trim_end() == token_label {
+                return Ok(slot);
+            }
+        }
+        Err(Error::TokenNotFound(token_label.clone()))?
+    }
+
+    /// Return true if the token supports keys on that curve
+    pub fn supports(&self, curve: Pkcs11Curve) -> bool {
+        curve
+            .required_mechanisms()
+            .iter()
+            .all(|m| self.mechanisms.contains(m))
+    }
+
+    /// Generate a key pair in the token and return its id
+    pub fn generate_key(&self, curve: Pkcs11Curve) -> Result<Vec<u8>> {
+        if !self.supports(curve) {
+            return Err(Error::UnsupportedMechanism(format!("{curve:?}")))?;
+        }
+
+        let session = self.session.lock().unwrap();
+        let id = session
+            .generate_random_vec(KEY_ID_LENGTH)
+            .map_err(|e| Error::Create(e.to_string()))?;
+        let signing = curve != Pkcs11Curve::X25519;
+
+        let public_key_template = vec![
+            Attribute::Token(true),
+            Attribute::Private(false),
+            Attribute::KeyType(curve.key_type()),
+            Attribute::EcParams(curve.ec_params().to_vec()),
+            Attribute::Verify(signing),
+            Attribute::Id(id.clone()),
+            Attribute::Label(KEY_LABEL.as_bytes().to_vec()),
+        ];
+        let private_key_template = vec![
+            Attribute::Token(true),
+            Attribute::Private(true),
+            Attribute::Sensitive(true),
+            Attribute::Extractable(false),
+            Attribute::Sign(signing),
+            Attribute::Derive(!signing),
+            Attribute::Id(id.clone()),
+            Attribute::Label(KEY_LABEL.as_bytes().to_vec()),
+        ];
+
+        session
+            .generate_key_pair(
+                &curve.key_generation_mechanism(),
+                &public_key_template,
+                &private_key_template,
+            )
+            .map_err(|e| Error::Create(e.to_string()))?;
+        log::debug!(id = hex::encode(&id), ?curve, "created new key");
+
+        Ok(id)
+    }
+
+    /// Return the curve and the raw public key of a key pair
+    pub fn public_key(&self, id: &[u8]) -> Result<(Pkcs11Curve, Vec<u8>)> {
+        let session = self.session.lock().unwrap();
+        let object =
+            Self::find_object(&session, ObjectClass::PUBLIC_KEY, id)?.ok_or(Error::KeyNotFound)?;
+        Self::read_public_key(&session, object)
+    }
+
+    /// Return the id of the key pair having a given public key
+    pub fn find_key_id(&self, curve: Pkcs11Curve, public_key: &[u8]) -> Result<Option<Vec<u8>>> {
+        let session = self.session.lock().unwrap();
+        let objects = session
+            .find_objects(&[
+                Attribute::Class(ObjectClass::PUBLIC_KEY),
+                Attribute::KeyType(curve.key_type()),
+            ])
+            .map_err(|e| Error::Find(e.to_string()))?;
+
+        for object in objects {
+            let (object_curve, object_public_key) = match Self::read_public_key(&session, object) {
+                Ok(public_key) => public_key,
+                // skip keys that this vault can't use
+                Err(_) => continue,
+            };
+            if object_curve == curve && object_public_key == public_key {
+                let attributes = session
+                    .get_attributes(object, &[AttributeType::Id])
+                    .map_err(|e| Error::Find(e.to_string()))?;
+                for attribute in attributes {
+                    if let Attribute::Id(id) = attribute {
+                        return Ok(Some(id));
+                    }
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Return true if the token contains a private key with that id
+    pub fn has_private_key(&self, id: &[u8]) -> Result<bool> {
+        let session = self.session.lock().unwrap();
+        Ok(Self::find_object(&session, ObjectClass::PRIVATE_KEY, id)?.is_some())
+    }
+
+    /// Sign a message. The message is hashed first for ECDSA signatures.
+    /// The signature is returned as the concatenation of its r and s values for ECDSA
+    pub fn sign(&self, id: &[u8], curve: Pkcs11Curve, message: &[u8]) -> Result<Vec<u8>> {
+        let (mechanism, data) = match curve {
+            Pkcs11Curve::P256 => (Mechanism::Ecdsa, Sha256::digest(message).to_vec()),
+            Pkcs11Curve::P384 => (Mechanism::Ecdsa, Sha384::digest(message).to_vec()),
+            Pkcs11Curve::Ed25519 => (Mechanism::Eddsa, message.to_vec()),
+            Pkcs11Curve::X25519 => return Err(Error::UnsupportedKeyType)?,
+        };
+
+        let session = self.session.lock().unwrap();
+        let object =
+            Self::find_object(&session, ObjectClass::PRIVATE_KEY, id)?.ok_or(Error::KeyNotFound)?;
+        let signature = session
+            .sign(&mechanism, object, &data)
+            .map_err(|e| Error::Sign(e.to_string()))?;
+        log::debug!(id = hex::encode(id), "signed message");
+
+        Ok(signature)
+    }
+
+    /// Perform an ECDH between a private key of the token and a peer public key.
+    /// The shared secret is returned to the caller
+    pub fn ecdh(&self, id: &[u8], peer_public_key: &[u8]) -> Result<Vec<u8>> {
+        let session = self.session.lock().unwrap();
+        let object =
+            Self::find_object(&session, ObjectClass::PRIVATE_KEY, id)?.ok_or(Error::KeyNotFound)?;
+
+        let params = Ecdh1DeriveParams::new(EcKdf::null(), peer_public_key);
+        let template = vec![
+            Attribute::Class(ObjectClass::SECRET_KEY),
+            Attribute::KeyType(KeyType::GENERIC_SECRET),
+            Attribute::ValueLen(32.into()),
+            Attribute::Token(false),
+            Attribute::Sensitive(false),
+            Attribute::Extractable(true),
+        ];
+        let shared_secret = session
+            .derive_key(&Mechanism::Ecdh1Derive(params), object, &template)
+            .map_err(|e| Error::Derive(e.to_string()))?;
+
+        let value = session.get_attributes(shared_secret, &[AttributeType::Value]);
+        // the shared secret is exported right away, it doesn't need to stay in the token
+        let _ = session.destroy_object(shared_secret);
+
+        for attribute in value.map_err(|e| Error::Derive(e.to_string()))? {
+            if let Attribute::Value(value) = attribute {
+                return Ok(value);
+            }
+        }
+        Err(Error::Derive(
+            "the shared secret can't be exported".to_string(),
+        ))?
+    }
+
+    /// Delete both keys of a key pair. Return false if the key pair doesn't exist
+    pub fn delete_key(&self, id: &[u8]) -> Result<bool> {
+        let session = self.session.lock().unwrap();
+        let mut deleted = false;
+        for class in [ObjectClass::PRIVATE_KEY, ObjectClass::PUBLIC_KEY] {
+            if let Some(object) = Self::find_object(&session, class, id)? {
+                session
+                    .destroy_object(object)
+                    .map_err(|e| Error::Delete(e.to_string()))?;
+                deleted = true;
+            }
+        }
+        log::debug!(id = hex::encode(id), deleted, "delete key");
+        Ok(deleted)
+    }
+
+    fn find_object(
+        session: &Session,
+        class: ObjectClass,
+        id: &[u8],
+    ) -> Result<Option<ObjectHandle>> {
+        let objects = session
+            .find_objects(&[Attribute::Class(class), Attribute::Id(id.to_vec())])
+            .map_err(|e| Error::Find(e.to_string()))?;
+        Ok(objects.first().cloned())
+    }
+
+    fn read_public_key(session: &Session, object: ObjectHandle) -> Result<(Pkcs11Curve, Vec<u8>)> {
+        let attributes = session
+            .get_attributes(
+                object,
+                &[
+                    AttributeType::KeyType,
+                    AttributeType::EcParams,
+                    AttributeType::EcPoint,
+                ],
+            )
+            .map_err(|e| Error::Export(e.to_string()))?;
+
+        let (mut key_type, mut ec_params, mut ec_point) = (None, None, None);
+        for attribute in attributes {
+            match attribute {
+                Attribute::KeyType(value) => key_type = Some(value),
+                Attribute::EcParams(value) => ec_params = Some(value),
+                Attribute::EcPoint(value) => ec_point = Some(value),
+                _ => (),
+            }
+        }
+
+        let (Some(key_type), Some(ec_params), Some(ec_point)) = (key_type, ec_params, ec_point)
+        else {
+            return Err(Error::InvalidPublicKey)?;
+        };
+        let curve =
+            Pkcs11Curve::from_attributes(key_type, &ec_params).ok_or(Error::UnsupportedKeyType)?;
+        let public_key = decode_ec_point(&ec_point, curve.public_key_length())?;
+
+        Ok((curve, public_key))
+    }
+}
+
+/// Decode the CKA_EC_POINT attribute of a public key.
+/// Most tokens return a DER-encoded octet string but some of them return the raw point
+pub(crate) fn decode_ec_point(ec_point: &[u8], length: usize) -> Result<Vec<u8>> {
+    let point = match ec_point {
+        point if point.len() == length => point,
+        [0x04, len, point @ ..] if *len as usize == point.len() => point,
+        [0x04, 0x81, len, point @ ..] if *len as usize == point.len() => point,
+        _ => return Err(Error::InvalidPublicKey)?,
+    };
+    if point.len() != length {
+        Err(Error::InvalidPublicKey)?
+    } else {
+        Ok(point.to_vec())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_ec_point() {
+        let point = [0x04; 65];
+        assert_eq!(decode_ec_point(&point, 65).unwrap(), point.to_vec());
+
+        let mut encoded = vec![0x04, 65];
+        encoded.extend_from_slice(&point);
+        assert_eq!(decode_ec_point(&encoded, 65).unwrap(), point.to_vec());
+
+        let point = [0x01; 32];
+        let mut encoded = vec![0x04, 32];
+        encoded.extend_from_slice(&point);
+        assert_eq!(decode_ec_point(&encoded, 32).unwrap(), point.to_vec());
+        assert!(decode_ec_point(&encoded, 65).is_err());
+
+        let point = [0x04; 97];
+        let mut encoded = vec![0x04, 0x81, 97];
+        encoded.extend_from_slice(&point);
+        assert_eq!(decode_ec_point(&encoded, 97).unwrap(), point.to_vec());
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::Error;
+use crate::pkcs11_client::{Pkcs11Client, Pkcs11Curve};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{async_trait, Result};
+use ockam_vault::{
+    AeadSecretKeyHandle, HKDFNumberOfOutputs, HandleToSecret, HashOutput, HkdfOutput,
+    MlKemCiphertext, MlKemPublicKey, MlKemSecretKeyHandle, SecretBufferHandle,
+    VaultForSecureChannels, X25519PublicKey, X25519SecretKeyHandle,
+};
+
+/// Secure channel vault keeping the static X25519 keys in a PKCS#11 token.
+///
+/// The ECDH with a static key is performed by the token and its result is imported in the
+/// wrapped vault, which performs all the other operations: ephemeral keys, hashing, key
+/// derivation and encryption.
+pub struct Pkcs11SecureChannelVault {
+    client: Arc<Pkcs11Client>,
+    vault: Arc<dyn VaultForSecureChannels>,
+}
+
+impl Pkcs11SecureChannelVault {
+    /// Create a new vault. Static keys which are not found in the token are delegated to
+    /// the wrapped vault, so that keys created before can still be used
+    pub fn new(client: Arc<Pkcs11Client>, vault: Arc<dyn VaultForSecureChannels>) -> Self {
+        Self { client, vault }
+    }
+
+    /// Return true if the token supports X25519 keys and ECDH
+    pub fn is_supported(client: &Pkcs11Client) -> bool {
+        client.supports(Pkcs11Curve::X25519)
+    }
+
+    fn is_in_token(&self, secret_key_handle: &X25519SecretKeyHandle) -> Result<bool> {
+        self.client.has_private_key(secret_key_handle.0.value())
+    }
+}
+
+#[async_trait]
+impl VaultForSecureChannels for Pkcs11SecureChannelVault {
+    async fn x25519_ecdh(
+        &self,
+        secret_key_handle: &X25519SecretKeyHandle,
+        peer_public_key: &X25519PublicKey,
+    ) -> Result<SecretBufferHandle> {
+        if !self.is_in_token(secret_key_handle)? {
+            return self
+                .vault
+                .x25519_ecdh(secret_key_handle, peer_public_key)
+                .await;
+        }
+
+        let shared_secret = self
+            .client
+            .ecdh(secret_key_handle.0.value(), &peer_public_key.0)?;
+        self.vault.import_secret_buffer(shared_secret).await
+    }
+
+    async fn hash(&self, data: &[u8]) -> Result<HashOutput> {
+        self.vault.hash(data).await
+    }
+
+    async fn hkdf(
+        &self,
+        salt: &SecretBufferHandle,
+        input_key_material: Option<&SecretBufferHandle>,
+        number_of_outputs: HKDFNumberOfOutputs,
+    ) -> Result<HkdfOutput> {
+        self.vault
+            .hkdf(salt, input_key_material, number_of_outputs)
+            .await
+    }
+
+    async fn aead_encrypt(
+        &self,
+        secret_key_handle: &AeadSecretKeyHandle,
+        plain_text: &mut [u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<()> {
+        self.vault
+            .aead_encrypt(secret_key_handle, plain_text, nonce, aad)
+            .await
+    }
+
+    async fn aead_decrypt<'a>(
+        &self,
+        secret_key_handle: &AeadSecretKeyHandle,
+        cipher_text: &'a mut [u8],
+        nonce: &[u8],
+        aad: &[u8],
+    ) -> Result<&'a mut [u8]> {
+        self.vault
+            .aead_decrypt(secret_key_handle, cipher_text, nonce, aad)
+            .await
+    }
+
+    async fn persist_aead_key(&self, secret_key_handle: &AeadSecretKeyHandle) -> Result<()> {
+        self.vault.persist_aead_key(secret_key_handle).await
+    }
+
+    async fn load_aead_key(&self, secret_key_handle: &AeadSecretKeyHandle) -> Result<()> {
+        self.vault.load_aead_key(secret_key_handle).await
+    }
+
+    async fn generate_static_x25519_secret_key(&self) -> Result<X25519SecretKeyHandle> {
+        let id = self.client.generate_key(Pkcs11Curve::X25519)?;
+        Ok(X25519SecretKeyHandle(HandleToSecret::new(id)))
+    }
+
+    async fn delete_static_x25519_secret_key(
+        &self,
+        secret_key_handle: X25519SecretKeyHandle,
+    ) -> Result<bool> {
+        if self.client.delete_key(secret_key_handle.0.value())? {
+            Ok(true)
+        } else {
+            self.vault
+                .delete_static_x25519_secret_key(secret_key_handle)
+                .await
+        }
+    }
+
+    async fn generate_ephemeral_x25519_secret_key(&self) -> Result<X25519SecretKeyHandle> {
+        self.vault.generate_ephemeral_x25519_secret_key().await
+    }
+
+    async fn delete_ephemeral_x25519_secret_key(
+        &self,
+        secret_key_handle: X25519SecretKeyHandle,
+    ) -> Result<bool> {
+        self.vault
+            .delete_ephemeral_x25519_secret_key(secret_key_handle)
+            .await
+    }
+
+    async fn get_x25519_public_key(
+        &self,
+        secret_key_handle: &X25519SecretKeyHandle,
+    ) -> Result<X25519PublicKey> {
+        if !self.is_in_token(secret_key_handle)? {
+            return self.vault.get_x25519_public_key(secret_key_handle).await;
+        }
+
+        let (curve, public_key) = self.client.public_key(secret_key_handle.0.value())?;
+        if curve != Pkcs11Curve::X25519 {
+            return Err(Error::UnsupportedKeyType)?;
+        }
+        Ok(X25519PublicKey(
+            public_key.try_into().map_err(|_| Error::InvalidPublicKey)?,
+        ))
+    }
+
+    async fn get_x25519_secret_key_handle(
+        &self,
+        public_key: &X25519PublicKey,
+    ) -> Result<X25519SecretKeyHandle> {
+        match self
+            .client
+            .find_key_id(Pkcs11Curve::X25519, &public_key.0)?
+        {
+            Some(id) => Ok(X25519SecretKeyHandle(HandleToSecret::new(id))),
+            None => self.vault.get_x25519_secret_key_handle(public_key).await,
+        }
+    }
+
+    async fn generate_ephemeral_ml_kem_secret_key(&self) -> Result<MlKemSecretKeyHandle> {
+        self.vault.generate_ephemeral_ml_kem_secret_key().await
+    }
+
+    async fn delete_ephemeral_ml_kem_secret_key(
+        &self,
+        secret_key_handle: MlKemSecretKeyHandle,
+    ) -> Result<bool> {
+        self.vault
+            .delete_ephemeral_ml_kem_secret_key(secret_key_handle)
+            .await
+    }
+
+    async fn get_ml_kem_public_key(
+        &self,
+        secret_key_handle: &MlKemSecretKeyHandle,
+    ) -> Result<MlKemPublicKey> {
+        self.vault.get_ml_kem_public_key(secret_key_handle).await
+    }
+
+    async fn ml_kem_encapsulate(
+        &self,
+        peer_public_key: &MlKemPublicKey,
+    ) -> Result<(MlKemCiphertext, SecretBufferHandle)> {
+        self.vault.ml_kem_encapsulate(peer_public_key).await
+    }
+
+    async fn ml_kem_decapsulate(
+        &self,
+        secret_key_handle: &MlKemSecretKeyHandle,
+        ciphertext: &MlKemCiphertext,
+    ) -> Result<SecretBufferHandle> {
+        self.vault
+            .ml_kem_decapsulate(secret_key_handle, ciphertext)
+            .await
+    }
+
+    async fn import_secret_buffer(&self, buffer: Vec<u8>) -> Result<SecretBufferHandle> {
+        self.vault.import_secret_buffer(buffer).await
+    }
+
+    async fn delete_secret_buffer(&self, secret_buffer_handle: SecretBufferHandle) -> Result<bool> {
+        self.vault.delete_secret_buffer(secret_buffer_handle).await
+    }
+
+    async fn convert_secret_buffer_to_aead_key(
+        &self,
+        secret_buffer_handle: SecretBufferHandle,
+    ) -> Result<AeadSecretKeyHandle> {
+        self.vault
+            .convert_secret_buffer_to_aead_key(secret_buffer_handle)
+            .await
+    }
+
+    async fn delete_aead_secret_key(&self, secret_key_handle: AeadSecretKeyHandle) -> Result<bool> {
+        self.vault.delete_aead_secret_key(secret_key_handle).await
+    }
+}
@@ -0,0 +1,159 @@
+use crate::error::Error;
+use crate::pkcs11_client::{Pkcs11Client, Pkcs11Config, Pkcs11Curve};
+use ockam_core::compat::sync::Arc;
+use ockam_core::{async_trait, Result};
+use ockam_vault::{
+    ECDSASHA256CurveP256PublicKey, ECDSASHA256CurveP256Signature, ECDSASHA384CurveP384PublicKey,
+    ECDSASHA384CurveP384Signature, EdDSACurve25519PublicKey, EdDSACurve25519Signature,
+    HandleToSecret, Signature, SigningKeyType, SigningSecretKeyHandle, VaultError, VaultForSigning,
+    VerifyingPublicKey,
+};
+
+/// Signing vault implementation using a PKCS#11 token.
+///
+/// The handle of a key is the CKA_ID attribute of its key pair in the token.
+pub struct Pkcs11SigningVault {
+    client: Arc<Pkcs11Client>,
+}
+
+impl Pkcs11SigningVault {
+    /// Create a vault configured with environment variables, see [`Pkcs11Config::from_env`]
+    pub async fn create() -> Result<Self> {
+        Self::create_with_config(Pkcs11Config::from_env()?).await
+    }
+
+    /// Create a new PKCS#11 vault
+    pub async fn create_with_config(config: Pkcs11Config) -> Result<Self> {
+        Ok(Self::new(Pkcs11Client::create(config)?))
+    }
+
+    /// Create a new PKCS#11 vault using an existing client
+    pub fn new(client: Arc<Pkcs11Client>) -> Self {
+        Self { client }
+    }
+
+    /// Return the client used to access the token
+    pub fn client(&self) -> Arc<Pkcs11Client> {
+        self.client.clone()
+    }
+
+    fn curve(key_type: &SigningKeyType) -> Pkcs11Curve {
+        match key_type {
+            SigningKeyType::EdDSACurve25519 => Pkcs11Curve::Ed25519,
+            SigningKeyType::ECDSASHA256CurveP256 => Pkcs11Curve::P256,
+            SigningKeyType::ECDSASHA384CurveP384 => Pkcs11Curve::P384,
+        }
+    }
+
+    fn curve_and_id(handle: &SigningSecretKeyHandle) -> (Pkcs11Curve, &[u8]) {
+        match handle {
+            SigningSecretKeyHandle::EdDSACurve25519(h) => (Pkcs11Curve::Ed25519, h.value()),
+            SigningSecretKeyHandle::ECDSASHA256CurveP256(h) => (Pkcs11Curve::P256, h.value()),
+            SigningSecretKeyHandle::ECDSASHA384CurveP384(h) => (Pkcs11Curve::P384, h.value()),
+        }
+    }
+
+    fn make_handle(curve: Pkcs11Curve, id: Vec<u8>) -> Result<SigningSecretKeyHandle> {
+        let handle = HandleToSecret::new(id);
+        match curve {
+            Pkcs11Curve::Ed25519 => Ok(SigningSecretKeyHandle::EdDSACurve25519(handle)),
+            Pkcs11Curve::P256 => Ok(SigningSecretKeyHandle::ECDSASHA256CurveP256(handle)),
+            Pkcs11Curve::P384 => Ok(SigningSecretKeyHandle::ECDSASHA384CurveP384(handle)),
+            Pkcs11Curve::X25519 => Err(Error::UnsupportedKeyType)?,
+        }
+    }
+
+    fn make_public_key(curve: Pkcs11Curve, public_key: Vec<u8>) -> Result<VerifyingPublicKey> {
+        let invalid = |_| Error::InvalidPublicKey;
+        match curve {
+            Pkcs11Curve::Ed25519 => Ok(VerifyingPublicKey::EdDSACurve25519(
+                EdDSACurve25519PublicKey(public_key.try_into().map_err(invalid)?),
+            )),
+            Pkcs11Curve::P256 => Ok(VerifyingPublicKey::ECDSASHA256CurveP256(
+                ECDSASHA256CurveP256PublicKey(public_key.try_into().map_err(invalid)?),
+            )),
+            Pkcs11Curve::P384 => Ok(VerifyingPublicKey::ECDSASHA384CurveP384(
+                ECDSASHA384CurveP384PublicKey(public_key.try_into().map_err(invalid)?),
+            )),
+            Pkcs11Curve::X25519 => Err(Error::UnsupportedKeyType)?,
+        }
+    }
+
+    fn make_signature(curve: Pkcs11Curve, signature: Vec<u8>) -> Result<Signature> {
+        let invalid = |_| Error::InvalidSignature;
+        match curve {
+            Pkcs11Curve::Ed25519 => Ok(Signature::EdDSACurve25519(EdDSACurve25519Signature(
+                signature.try_into().map_err(invalid)?,
+            ))),
+            Pkcs11Curve::P256 => Ok(Signature::ECDSASHA256CurveP256(
+                ECDSASHA256CurveP256Signature(signature.try_into().map_err(invalid)?),
+            )),
+            Pkcs11Curve::P384 => Ok(Signature::ECDSASHA384CurveP384(
+                ECDSASHA384CurveP384Signature(signature.try_into().map_err(invalid)?),
+            )),
+            Pkcs11Curve::X25519 => Err(Error::UnsupportedKeyType)?,
+        }
+    }
+}
+
+#[async_trait]
+impl VaultForSigning for Pkcs11SigningVault {
+    async fn sign(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+        data: &[u8],
+    ) -> Result<Signature> {
+        let (curve, id) = Self::curve_and_id(signing_secret_key_handle);
+        let signature = self.client.sign(id, curve, data)?;
+        Self::make_signature(curve, signature)
+    }
+
+    async fn generate_signing_secret_key(
+        &self,
+        signing_key_type: SigningKeyType,
+    ) -> Result<SigningSecretKeyHandle> {
+        let curve = Self::curve(&signing_key_type);
+        if !self.client.supports(curve) {
+            return Err(VaultError::InvalidKeyType)?;
+        }
+
+        let id = self.client.generate_key(curve)?;
+        Self::make_handle(curve, id)
+    }
+
+    async fn get_verifying_public_key(
+        &self,
+        signing_secret_key_handle: &SigningSecretKeyHandle,
+    ) -> Result<VerifyingPublicKey> {
+        let (curve, id) = Self::curve_and_id(signing_secret_key_handle);
+        let (key_curve, public_key) = self.client.public_key(id)?;
+        if key_curve != curve {
+            return Err(VaultError::InvalidKeyType)?;
+        }
+        Self::make_public_key(curve, public_key)
+    }
+
+    async fn get_secret_key_handle(
+        &self,
+        verifying_public_key: &VerifyingPublicKey,
+    ) -> Result<SigningSecretKeyHandle> {
+        let (curve, public_key) = match verifying_public_key {
+            VerifyingPublicKey::EdDSACurve25519(k) => (Pkcs11Curve::Ed25519, k.0.as_slice()),
+            VerifyingPublicKey::ECDSASHA256CurveP256(k) => (Pkcs11Curve::P256, k.0.as_slice()),
+            VerifyingPublicKey::ECDSASHA384CurveP384(k) => (Pkcs11Curve::P384, k.0.as_slice()),
+        };
+        let id = self
+            .client
+            .find_key_id(curve, public_key)?
+            .ok_or(Error::KeyNotFound)?;
+        Self::make_handle(curve, id)
+    }
+
+    async fn delete_signing_secret_key(
+        &self,
+        signing_secret_key_handle: SigningSecretKeyHandle,
+    ) -> Result<bool> {
+        let (_, id) = Self::curve_and_id(&signing_secret_key_handle);
+        self.client.delete_key(id)
+    }
+}
@@ -0,0 +1,103 @@
+use ockam_core::compat::sync::Arc;
+use ockam_core::Result;
+use ockam_vault::{
+    SigningKeyType, SoftwareVaultForSecureChannels, SoftwareVaultForVerifyingSignatures,
+    VaultForSecureChannels, VaultForSigning, VaultForVerifyingSignatures,
+};
+use ockam_vault_pkcs11::{Pkcs11SecureChannelVault, Pkcs11SigningVault};
+
+/// These tests need to be executed against a PKCS#11 token, for example SoftHSM:
+///
+/// softhsm2-util --init-token --free --label ockam --pin 1234 --so-pin 1234
+///
+/// with the following environment variables
+/// OCKAM_PKCS11_MODULE=/usr/lib/softhsm/libsofthsm2.so
+/// OCKAM_PKCS11_TOKEN_LABEL=ockam
+/// OCKAM_PKCS11_PIN=1234
+
+#[tokio::test]
+#[ignore]
+async fn test_sign_verify() -> Result<()> {
+    let signing_vault = Pkcs11SigningVault::create().await?;
+    let verifier = SoftwareVaultForVerifyingSignatures::new();
+
+    for key_type in [
+        SigningKeyType::EdDSACurve25519,
+        SigningKeyType::ECDSASHA256CurveP256,
+        SigningKeyType::ECDSASHA384CurveP384,
+    ] {
+        let handle = signing_vault.generate_signing_secret_key(key_type).await?;
+        let message = b"hello world";
+        let signature = signing_vault.sign(&handle, message.as_slice()).await?;
+        let public_key = signing_vault.get_verifying_public_key(&handle).await?;
+
+        assert!(
+            verifier
+                .verify_signature(&public_key, message, &signature)
+                .await?
+        );
+
+        // the handle can be found again from the public key
+        let found = signing_vault.get_secret_key_handle(&public_key).await?;
+        assert_eq!(found, handle);
+
+        assert!(
+            signing_vault
+                .delete_signing_secret_key(handle.clone())
+                .await?
+        );
+        assert!(!signing_vault.delete_signing_secret_key(handle).await?);
+    }
+
+    Ok(())
+}
+
+#[tokio::test]
+#[ignore]
+async fn test_x25519_ecdh() -> Result<()> {
+    let signing_vault = Pkcs11SigningVault::create().await?;
+    let client = signing_vault.client();
+    assert!(Pkcs11SecureChannelVault::is_supported(&client));
+
+    let software_vault: Arc<dyn VaultForSecureChannels> =
+        SoftwareVaultForSecureChannels::create().await?;
+    let vault = Pkcs11SecureChannelVault::new(client, software_vault);
+    let peer_vault = SoftwareVaultForSecureChannels::create().await?;
+
+    let static_key = vault.generate_static_x25519_secret_key().await?;
+    let static_public_key = vault.get_x25519_public_key(&static_key).await?;
+    assert_eq!(
+        vault
+            .get_x25519_secret_key_handle(&static_public_key)
+            .await?,
+        static_key
+    );
+
+    let peer_key = peer_vault.generate_ephemeral_x25519_secret_key().await?;
+    let peer_public_key = peer_vault.get_x25519_public_key(&peer_key).await?;
+
+    // both sides must obtain the same shared secret, check it by deriving a key from it
+    let dh = vault.x25519_ecdh(&static_key, &peer_public_key).await?;
+    let peer_dh = peer_vault
+        .x25519_ecdh(&peer_key, &static_public_key)
+        .await?;
+    let key = vault.convert_secret_buffer_to_aead_key(dh).await?;
+    let peer_key = peer_vault
+        .convert_secret_buffer_to_aead_key(peer_dh)
+        .await?;
+
+    let nonce = [0u8; 12];
+    let mut message = b"hello".to_vec();
+    message.extend_from_slice(&[0u8; 16]);
+    vault
+        .aead_encrypt(&key, message.as_mut_slice(), &nonce, &[])
+        .await?;
+    let decrypted = peer_vault
+        .aead_decrypt(&peer_key, message.as_mut_slice(), &nonce, &[])
+        .await?;
+    assert_eq!(decrypted, b"hello");
+
+    assert!(vault.delete_static_x25519_secret_key(static_key).await?);
+
+    Ok(())
+}